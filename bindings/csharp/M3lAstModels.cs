@@ -21,6 +21,14 @@ public class SourceLocation
 
     [JsonPropertyName("col")]
     public int Col { get; set; }
+
+    /// <summary>Last line of the node's text (0 when not recorded).</summary>
+    [JsonPropertyName("endLine")]
+    public int EndLine { get; set; }
+
+    /// <summary>Column just past the node's last character (0 when not recorded).</summary>
+    [JsonPropertyName("endCol")]
+    public int EndCol { get; set; }
 }
 
 // ---------------------------------------------------------------------------
@@ -69,7 +77,7 @@ public enum ModelType
 }
 
 /// <summary>
-/// Default value type: literal or expression.
+/// Default value type: literal, expression or JSON.
 /// </summary>
 [JsonConverter(typeof(JsonStringEnumConverter))]
 public enum DefaultValueType
@@ -79,6 +87,9 @@ public enum DefaultValueType
 
     [JsonPropertyName("expression")]
     Expression,
+
+    [JsonPropertyName("json")]
+    Json,
 }
 
 /// <summary>
@@ -110,6 +121,44 @@ public class ProjectInfo
     public string? Version { get; set; }
 }
 
+/// <summary>
+/// Metadata from a file's leading <c>---</c> YAML frontmatter block.
+/// </summary>
+public class FileMetadata
+{
+    [JsonPropertyName("source")]
+    public string Source { get; set; } = "";
+
+    [JsonPropertyName("namespace")]
+    public string? Namespace { get; set; }
+
+    [JsonPropertyName("owner")]
+    public string? Owner { get; set; }
+
+    [JsonPropertyName("version")]
+    public string? Version { get; set; }
+
+    [JsonPropertyName("tags")]
+    public List<string> Tags { get; set; } = [];
+
+    /// <summary>Any other keys.</summary>
+    [JsonPropertyName("metadata")]
+    public Dictionary<string, JsonElement> Metadata { get; set; } = [];
+}
+
+/// <summary>
+/// A file's <c>&lt;!-- m3l: 1.1 --&gt;</c> or <c># M3L-Version: 1.1</c> pragma.
+/// </summary>
+public class M3lVersion
+{
+    [JsonPropertyName("source")]
+    public string Source { get; set; } = "";
+
+    /// <summary>The version as written.</summary>
+    [JsonPropertyName("version")]
+    public string Version { get; set; } = "";
+}
+
 // ---------------------------------------------------------------------------
 // Field-related types
 // ---------------------------------------------------------------------------
@@ -133,6 +182,9 @@ public class FieldAttribute
 
     [JsonPropertyName("isRegistered")]
     public bool? IsRegistered { get; set; }
+
+    [JsonPropertyName("loc")]
+    public SourceLocation? Loc { get; set; }
 }
 
 /// <summary>
@@ -162,6 +214,59 @@ public class CustomAttribute
     public CustomAttributeParsed? Parsed { get; set; }
 }
 
+/// <summary>
+/// The resolved target of an <c>@reference</c>/<c>@fk</c> field.
+/// </summary>
+public class ReferenceDef
+{
+    [JsonPropertyName("model")]
+    public string Model { get; set; } = "";
+
+    /// <summary>The target model's namespace, if it has one.</summary>
+    [JsonPropertyName("namespace")]
+    public string? Namespace { get; set; }
+
+    /// <summary>As written, or else the target's primary key.</summary>
+    [JsonPropertyName("field")]
+    public string? Field { get; set; }
+
+    /// <summary>From the <c>!</c>/<c>!!</c>/<c>?</c> symbol, <c>@on_delete</c>, or the field's nullability.</summary>
+    [JsonPropertyName("on_delete")]
+    public ReferentialAction OnDelete { get; set; }
+
+    /// <summary>Null unless <c>@on_update</c> or an <c>on_update:</c> item names an action.</summary>
+    [JsonPropertyName("on_update")]
+    public ReferentialAction? OnUpdate { get; set; }
+}
+
+[JsonConverter(typeof(JsonStringEnumConverter))]
+public enum ReferentialAction
+{
+    [JsonPropertyName("cascade")]
+    Cascade,
+
+    [JsonPropertyName("set_null")]
+    SetNull,
+
+    [JsonPropertyName("no_action")]
+    NoAction,
+
+    [JsonPropertyName("restrict")]
+    Restrict,
+}
+
+/// <summary>
+/// The source field a view field selects.
+/// </summary>
+public class FromDef
+{
+    [JsonPropertyName("model")]
+    public string Model { get; set; } = "";
+
+    [JsonPropertyName("field")]
+    public string Field { get; set; } = "";
+}
+
 /// <summary>
 /// Lookup definition for a lookup field.
 /// </summary>
@@ -220,6 +325,66 @@ public class EnumValue
 
     [JsonPropertyName("value")]
     public JsonElement? Value { get; set; }
+
+    [JsonPropertyName("loc")]
+    public SourceLocation? Loc { get; set; }
+}
+
+/// <summary>
+/// A union member or tuple component: <c>string(50)</c> in <c>string(50) | integer</c>.
+/// </summary>
+public class TypeMember
+{
+    [JsonPropertyName("type")]
+    public string Type { get; set; } = "";
+
+    [JsonPropertyName("params")]
+    public List<JsonElement>? Params { get; set; }
+
+    [JsonPropertyName("generic_params")]
+    public List<string>? GenericParams { get; set; }
+
+    /// <summary>Only tuple components are nullable on their own.</summary>
+    [JsonPropertyName("nullable")]
+    public bool Nullable { get; set; }
+}
+
+/// <summary>
+/// <c>@since</c>, <c>@deprecated</c>, <c>@example</c> and <c>@see</c> lines of a description.
+/// </summary>
+public class DocTags
+{
+    [JsonPropertyName("since")]
+    public string? Since { get; set; }
+
+    [JsonPropertyName("deprecated")]
+    public bool Deprecated { get; set; }
+
+    /// <summary>Text after <c>@deprecated</c>.</summary>
+    [JsonPropertyName("deprecation")]
+    public string? Deprecation { get; set; }
+
+    [JsonPropertyName("examples")]
+    public List<string> Examples { get; set; } = [];
+
+    [JsonPropertyName("see")]
+    public List<string> See { get; set; } = [];
+}
+
+/// <summary>
+/// From <c>@deprecated("message", since: "2.0", replacement: X)</c> or an <c>@deprecated</c> doc tag.
+/// </summary>
+public class Deprecation
+{
+    [JsonPropertyName("message")]
+    public string? Message { get; set; }
+
+    [JsonPropertyName("since")]
+    public string? Since { get; set; }
+
+    /// <summary><c>replacement:</c>, or <c>X</c> in a message of the form <c>use X</c>.</summary>
+    [JsonPropertyName("replacement")]
+    public string? Replacement { get; set; }
 }
 
 /// <summary>
@@ -233,6 +398,10 @@ public class FieldNode
     [JsonPropertyName("label")]
     public string? Label { get; set; }
 
+    /// <summary>Localized labels by locale; <see cref="Label"/> is the first one written.</summary>
+    [JsonPropertyName("labels")]
+    public Dictionary<string, string>? Labels { get; set; }
+
     [JsonPropertyName("type")]
     public string? Type { get; set; }
 
@@ -260,15 +429,32 @@ public class FieldNode
     [JsonPropertyName("default_value_type")]
     public DefaultValueType? DefaultValueType { get; set; }
 
+    [JsonPropertyName("default_json")]
+    public JsonElement? DefaultJson { get; set; }
+
     [JsonPropertyName("description")]
     public string? Description { get; set; }
 
+    [JsonPropertyName("doc")]
+    public DocTags? Doc { get; set; }
+
+    [JsonPropertyName("deprecated")]
+    public Deprecation? Deprecated { get; set; }
+
     [JsonPropertyName("attributes")]
     public List<FieldAttribute> Attributes { get; set; } = [];
 
     [JsonPropertyName("framework_attrs")]
     public List<CustomAttribute>? FrameworkAttrs { get; set; }
 
+    /// <summary>Set when an <c>@reference</c>/<c>@fk</c> target resolves.</summary>
+    [JsonPropertyName("reference")]
+    public ReferenceDef? Reference { get; set; }
+
+    /// <summary>For a view field, the source field it selects; an untyped field takes its type.</summary>
+    [JsonPropertyName("from")]
+    public FromDef? From { get; set; }
+
     [JsonPropertyName("lookup")]
     public LookupDef? Lookup { get; set; }
 
@@ -281,6 +467,18 @@ public class FieldNode
     [JsonPropertyName("enum_values")]
     public List<EnumValue>? EnumValues { get; set; }
 
+    /// <summary>Member types when <c>type</c> is <c>union</c>.</summary>
+    [JsonPropertyName("union")]
+    public List<TypeMember>? Union { get; set; }
+
+    /// <summary>Component types, in order, when <c>type</c> is <c>tuple</c>.</summary>
+    [JsonPropertyName("tuple")]
+    public List<TypeMember>? Tuple { get; set; }
+
+    /// <summary>The project type alias the field was written with; <c>type</c> holds its expansion.</summary>
+    [JsonPropertyName("type_alias")]
+    public string? TypeAlias { get; set; }
+
     [JsonPropertyName("fields")]
     public List<FieldNode>? Fields { get; set; }
 
@@ -292,6 +490,28 @@ public class FieldNode
 // View-related types
 // ---------------------------------------------------------------------------
 
+/// <summary>
+/// Kind of a view join: <c>left</c> in <c>left join Order o on ...</c>.
+/// </summary>
+[JsonConverter(typeof(JsonStringEnumConverter))]
+public enum JoinKind
+{
+    [JsonPropertyName("inner")]
+    Inner,
+
+    [JsonPropertyName("left")]
+    Left,
+
+    [JsonPropertyName("right")]
+    Right,
+
+    [JsonPropertyName("full")]
+    Full,
+
+    [JsonPropertyName("cross")]
+    Cross,
+}
+
 /// <summary>
 /// Join definition in a view source.
 /// </summary>
@@ -302,6 +522,12 @@ public class JoinDef
 
     [JsonPropertyName("on")]
     public string On { get; set; } = "";
+
+    [JsonPropertyName("kind")]
+    public JoinKind? Kind { get; set; }
+
+    [JsonPropertyName("alias")]
+    public string? Alias { get; set; }
 }
 
 /// <summary>
@@ -339,8 +565,28 @@ public class RefreshDef
     [JsonPropertyName("strategy")]
     public string Strategy { get; set; } = "";
 
+    /// <summary>The interval as written: <c>5m</c>, <c>6 hours</c>, <c>0 3 * * *</c>.</summary>
     [JsonPropertyName("interval")]
     public string? Interval { get; set; }
+
+    /// <summary><c>Interval</c>, parsed; null when it is malformed (<c>M3L-E022</c>).</summary>
+    [JsonPropertyName("schedule")]
+    public RefreshSchedule? Schedule { get; set; }
+}
+
+/// <summary>
+/// Parsed refresh interval: <c>interval</c> with <c>Seconds</c>, or <c>cron</c> with <c>Expression</c>.
+/// </summary>
+public class RefreshSchedule
+{
+    [JsonPropertyName("kind")]
+    public string Kind { get; set; } = "";
+
+    [JsonPropertyName("seconds")]
+    public long? Seconds { get; set; }
+
+    [JsonPropertyName("expression")]
+    public string? Expression { get; set; }
 }
 
 // ---------------------------------------------------------------------------
@@ -348,19 +594,269 @@ public class RefreshDef
 // ---------------------------------------------------------------------------
 
 /// <summary>
-/// Model sections (indexes, relations, behaviors, metadata, plus custom sections).
+/// Index from an <c>### Indexes</c> entry or an <c>@index(...)</c>/<c>@unique(...)</c> directive,
+/// which keeps its line in <see cref="Raw"/>.
+/// </summary>
+public class IndexDef
+{
+    [JsonPropertyName("name")]
+    public string? Name { get; set; }
+
+    [JsonPropertyName("label")]
+    public string? Label { get; set; }
+
+    [JsonPropertyName("fields")]
+    public List<string> Fields { get; set; } = [];
+
+    [JsonPropertyName("unique")]
+    public bool Unique { get; set; }
+
+    /// <summary>Partial index condition.</summary>
+    [JsonPropertyName("where")]
+    public string? Where { get; set; }
+
+    /// <summary>Index method: <c>btree</c>, <c>hash</c>, <c>gin</c>, ...</summary>
+    [JsonPropertyName("method")]
+    public string? Method { get; set; }
+
+    [JsonPropertyName("options")]
+    public Dictionary<string, JsonElement> Options { get; set; } = [];
+
+    [JsonPropertyName("description")]
+    public string? Description { get; set; }
+
+    [JsonPropertyName("raw")]
+    public string? Raw { get; set; }
+
+    [JsonPropertyName("loc")]
+    public SourceLocation Loc { get; set; } = new();
+}
+
+/// <summary>
+/// Side of a relation holding the foreign key: <c>&gt;</c> (<c>to</c>), <c>&lt;</c> (<c>from</c>) or <c>&lt;&gt;</c> (<c>both</c>).
+/// </summary>
+[JsonConverter(typeof(JsonStringEnumConverter))]
+public enum RelationDirection
+{
+    [JsonPropertyName("to")]
+    To,
+
+    [JsonPropertyName("from")]
+    From,
+
+    [JsonPropertyName("both")]
+    Both,
+}
+
+[JsonConverter(typeof(JsonStringEnumConverter))]
+public enum Cardinality
+{
+    [JsonPropertyName("one-to-one")]
+    OneToOne,
+
+    [JsonPropertyName("one-to-many")]
+    OneToMany,
+
+    [JsonPropertyName("many-to-one")]
+    ManyToOne,
+
+    [JsonPropertyName("many-to-many")]
+    ManyToMany,
+}
+
+/// <summary>
+/// Relation from a <c>### Relations</c> entry or an <c>@relation(...)</c> line, as written in <see cref="Raw"/>.
+/// </summary>
+public class RelationDef
+{
+    [JsonPropertyName("name")]
+    public string Name { get; set; } = "";
+
+    [JsonPropertyName("direction")]
+    public RelationDirection? Direction { get; set; }
+
+    /// <summary><c>Person</c>, or <c>Comment.post_id</c>.</summary>
+    [JsonPropertyName("target")]
+    public string? Target { get; set; }
+
+    /// <summary>The foreign key field.</summary>
+    [JsonPropertyName("from")]
+    public string? From { get; set; }
+
+    /// <summary>As written, or else following the direction.</summary>
+    [JsonPropertyName("cardinality")]
+    public Cardinality? Cardinality { get; set; }
+
+    [JsonPropertyName("optional")]
+    public bool Optional { get; set; }
+
+    [JsonPropertyName("on_delete")]
+    public string? OnDelete { get; set; }
+
+    [JsonPropertyName("options")]
+    public Dictionary<string, JsonElement> Options { get; set; } = [];
+
+    [JsonPropertyName("description")]
+    public string? Description { get; set; }
+
+    [JsonPropertyName("raw")]
+    public string Raw { get; set; } = "";
+
+    [JsonPropertyName("loc")]
+    public SourceLocation Loc { get; set; } = new();
+}
+
+[JsonConverter(typeof(JsonStringEnumConverter))]
+public enum BehaviorTiming
+{
+    [JsonPropertyName("before")]
+    Before,
+
+    [JsonPropertyName("after")]
+    After,
+
+    [JsonPropertyName("on")]
+    On,
+}
+
+/// <summary>
+/// Behavior from a <c>### Behaviors</c> entry or an <c>@behavior(...)</c> line, as written in <see cref="Raw"/>.
+/// </summary>
+public class BehaviorDef
+{
+    [JsonPropertyName("name")]
+    public string Name { get; set; } = "";
+
+    /// <summary>Null when the name has no <c>before_</c>/<c>after_</c>/<c>on_</c> prefix.</summary>
+    [JsonPropertyName("timing")]
+    public BehaviorTiming? Timing { get; set; }
+
+    /// <summary><c>create</c>, <c>update</c>, <c>delete</c>, <c>save</c> or a custom event.</summary>
+    [JsonPropertyName("event")]
+    public string Event { get; set; } = "";
+
+    [JsonPropertyName("action")]
+    public string? Action { get; set; }
+
+    [JsonPropertyName("condition")]
+    public string? Condition { get; set; }
+
+    [JsonPropertyName("options")]
+    public Dictionary<string, JsonElement> Options { get; set; } = [];
+
+    [JsonPropertyName("description")]
+    public string? Description { get; set; }
+
+    [JsonPropertyName("raw")]
+    public string Raw { get; set; } = "";
+
+    [JsonPropertyName("loc")]
+    public SourceLocation Loc { get; set; } = new();
+}
+
+/// <summary>
+/// Natural key: fields that must be unique together (emitted as a UNIQUE constraint).
+/// </summary>
+public class KeyDef
+{
+    [JsonPropertyName("name")]
+    public string? Name { get; set; }
+
+    [JsonPropertyName("fields")]
+    public List<string> Fields { get; set; } = [];
+
+    [JsonPropertyName("description")]
+    public string? Description { get; set; }
+
+    [JsonPropertyName("loc")]
+    public SourceLocation Loc { get; set; } = new();
+}
+
+/// <summary>
+/// Check constraint from <c>@check(...)</c>; <see cref="Field"/> is set when declared on a field.
+/// </summary>
+public class CheckDef
+{
+    [JsonPropertyName("expression")]
+    public string Expression { get; set; } = "";
+
+    [JsonPropertyName("field")]
+    public string? Field { get; set; }
+
+    [JsonPropertyName("description")]
+    public string? Description { get; set; }
+
+    [JsonPropertyName("loc")]
+    public SourceLocation Loc { get; set; } = new();
+}
+
+/// <summary>
+/// REST operation from an <c>### Endpoints</c> (or <c>### API</c>) section.
+/// </summary>
+public class EndpointDef
+{
+    /// <summary>Upper-case HTTP method.</summary>
+    [JsonPropertyName("method")]
+    public string Method { get; set; } = "";
+
+    [JsonPropertyName("path")]
+    public string Path { get; set; } = "";
+
+    /// <summary><c>{name}</c> path segments, in order.</summary>
+    [JsonPropertyName("params")]
+    public List<string> Params { get; set; } = [];
+
+    [JsonPropertyName("description")]
+    public string? Description { get; set; }
+
+    [JsonPropertyName("options")]
+    public Dictionary<string, JsonElement> Options { get; set; } = [];
+
+    [JsonPropertyName("loc")]
+    public SourceLocation Loc { get; set; } = new();
+}
+
+/// <summary>
+/// Row of a <c>### Seed</c> section; <see cref="Name"/> is set for list rows.
+/// </summary>
+public class SeedRow
+{
+    [JsonPropertyName("name")]
+    public string? Name { get; set; }
+
+    [JsonPropertyName("values")]
+    public Dictionary<string, JsonElement> Values { get; set; } = [];
+
+    [JsonPropertyName("loc")]
+    public SourceLocation Loc { get; set; } = new();
+}
+
+/// <summary>
+/// Model sections (indexes, keys, checks, seeds, endpoints, relations, behaviors, metadata, plus custom sections).
 /// Custom sections are captured via ExtensionData.
 /// </summary>
 public class Sections
 {
     [JsonPropertyName("indexes")]
-    public List<JsonElement> Indexes { get; set; } = [];
+    public List<IndexDef> Indexes { get; set; } = [];
+
+    [JsonPropertyName("keys")]
+    public List<KeyDef> Keys { get; set; } = [];
+
+    [JsonPropertyName("checks")]
+    public List<CheckDef> Checks { get; set; } = [];
+
+    [JsonPropertyName("seeds")]
+    public List<SeedRow> Seeds { get; set; } = [];
+
+    [JsonPropertyName("endpoints")]
+    public List<EndpointDef> Endpoints { get; set; } = [];
 
     [JsonPropertyName("relations")]
-    public List<JsonElement> Relations { get; set; } = [];
+    public List<RelationDef> Relations { get; set; } = [];
 
     [JsonPropertyName("behaviors")]
-    public List<JsonElement> Behaviors { get; set; } = [];
+    public List<BehaviorDef> Behaviors { get; set; } = [];
 
     [JsonPropertyName("metadata")]
     public Dictionary<string, JsonElement> Metadata { get; set; } = [];
@@ -384,21 +880,38 @@ public class ModelNode
     [JsonPropertyName("label")]
     public string? Label { get; set; }
 
+    /// <summary>Localized labels by locale; <see cref="Label"/> is the first one written.</summary>
+    [JsonPropertyName("labels")]
+    public Dictionary<string, string>? Labels { get; set; }
+
     [JsonPropertyName("type")]
     public ModelType Type { get; set; }
 
     [JsonPropertyName("source")]
     public string Source { get; set; } = "";
 
+    [JsonPropertyName("namespace")]
+    public string? Namespace { get; set; }
+
     [JsonPropertyName("line")]
     public int Line { get; set; }
 
     [JsonPropertyName("inherits")]
     public List<string> Inherits { get; set; } = [];
 
+    /// <summary>Other names the model can be referenced by, from <c>@alias(Account)</c>.</summary>
+    [JsonPropertyName("aliases")]
+    public List<string>? Aliases { get; set; }
+
     [JsonPropertyName("description")]
     public string? Description { get; set; }
 
+    [JsonPropertyName("doc")]
+    public DocTags? Doc { get; set; }
+
+    [JsonPropertyName("deprecated")]
+    public Deprecation? Deprecated { get; set; }
+
     [JsonPropertyName("attributes")]
     public List<FieldAttribute> Attributes { get; set; } = [];
 
@@ -417,6 +930,21 @@ public class ModelNode
     [JsonPropertyName("refresh")]
     public RefreshDef? Refresh { get; set; }
 
+    /// <summary>Type parameters of a generic model: <c>T</c> in <c>## Paged&lt;T&gt;</c>.</summary>
+    [JsonPropertyName("generic_params")]
+    public List<string> GenericParams { get; set; } = [];
+
+    /// <summary>Set on models the resolver instantiated from a generic one.</summary>
+    [JsonPropertyName("instance_of")]
+    public string? InstanceOf { get; set; }
+
+    [JsonPropertyName("generic_args")]
+    public List<string> GenericArgs { get; set; } = [];
+
+    /// <summary>Parents added by the project's <c>[defaults] inherits</c>; also in <c>inherits</c>.</summary>
+    [JsonPropertyName("default_inherits")]
+    public List<string> DefaultInherits { get; set; } = [];
+
     [JsonPropertyName("loc")]
     public SourceLocation Loc { get; set; } = new();
 }
@@ -432,12 +960,19 @@ public class EnumNode
     [JsonPropertyName("label")]
     public string? Label { get; set; }
 
+    /// <summary>Localized labels by locale; <see cref="Label"/> is the first one written.</summary>
+    [JsonPropertyName("labels")]
+    public Dictionary<string, string>? Labels { get; set; }
+
     [JsonPropertyName("type")]
     public ModelType Type { get; set; }
 
     [JsonPropertyName("source")]
     public string Source { get; set; } = "";
 
+    [JsonPropertyName("namespace")]
+    public string? Namespace { get; set; }
+
     [JsonPropertyName("line")]
     public int Line { get; set; }
 
@@ -447,6 +982,12 @@ public class EnumNode
     [JsonPropertyName("description")]
     public string? Description { get; set; }
 
+    [JsonPropertyName("doc")]
+    public DocTags? Doc { get; set; }
+
+    [JsonPropertyName("deprecated")]
+    public Deprecation? Deprecated { get; set; }
+
     [JsonPropertyName("values")]
     public List<EnumValue> Values { get; set; } = [];
 
@@ -478,6 +1019,14 @@ public class AttributeRegistryEntry
     [JsonPropertyName("range")]
     public List<double>? Range { get; set; }
 
+    /// <summary>Allowed arguments of a <c>type: enum</c> attribute.</summary>
+    [JsonPropertyName("values")]
+    public List<string>? Values { get; set; }
+
+    /// <summary>Regex that string arguments must match.</summary>
+    [JsonPropertyName("pattern")]
+    public string? Pattern { get; set; }
+
     [JsonPropertyName("required")]
     public bool Required { get; set; }
 
@@ -517,6 +1066,41 @@ public class Diagnostic
 // Top-level AST
 // ---------------------------------------------------------------------------
 
+/// <summary>
+/// A loaded file with its namespace and the files it imports.
+/// </summary>
+public class SourceFile
+{
+    [JsonPropertyName("path")]
+    public string Path { get; set; } = "";
+
+    [JsonPropertyName("namespace")]
+    public string? Namespace { get; set; }
+
+    [JsonPropertyName("imports")]
+    public List<ImportEdge> Imports { get; set; } = [];
+}
+
+/// <summary>
+/// An <c>@import</c>, resolved against the loaded sources.
+/// </summary>
+public class ImportEdge
+{
+    /// <summary>The path as written.</summary>
+    [JsonPropertyName("path")]
+    public string Path { get; set; } = "";
+
+    /// <summary>The loaded source it refers to; null when it was not loaded (M3L-E013).</summary>
+    [JsonPropertyName("target")]
+    public string? Target { get; set; }
+
+    [JsonPropertyName("symbols")]
+    public List<string>? Symbols { get; set; }
+
+    [JsonPropertyName("line")]
+    public int Line { get; set; }
+}
+
 /// <summary>
 /// The complete M3L AST — top-level JSON output from parsing.
 /// </summary>
@@ -534,6 +1118,10 @@ public class M3lAst
     [JsonPropertyName("sources")]
     public List<string> Sources { get; set; } = [];
 
+    /// <summary>Each source's namespace and import edges, in source order.</summary>
+    [JsonPropertyName("files")]
+    public List<SourceFile> Files { get; set; } = [];
+
     [JsonPropertyName("models")]
     public List<ModelNode> Models { get; set; } = [];
 
@@ -555,11 +1143,55 @@ public class M3lAst
     [JsonPropertyName("attributeRegistry")]
     public List<AttributeRegistryEntry> AttributeRegistry { get; set; } = [];
 
+    /// <summary>Frontmatter of the files that have one, in source order.</summary>
+    [JsonPropertyName("fileMetadata")]
+    public List<FileMetadata> FileMetadata { get; set; } = [];
+
+    /// <summary>Version pragmas of the files that have one, in source order.</summary>
+    [JsonPropertyName("m3lVersions")]
+    public List<M3lVersion> M3lVersions { get; set; } = [];
+
+    /// <summary>Concrete models for each use of a generic model, named as used: <c>Paged&lt;Product&gt;</c>.</summary>
+    [JsonPropertyName("genericInstances")]
+    public List<ModelNode> GenericInstances { get; set; } = [];
+
+    /// <summary><c>## extend User</c> blocks as written; their fields, indexes and relations are already in the model.</summary>
+    [JsonPropertyName("extendBlocks")]
+    public List<ModelNode> ExtendBlocks { get; set; } = [];
+
     [JsonPropertyName("errors")]
     public List<Diagnostic> Errors { get; set; } = [];
 
     [JsonPropertyName("warnings")]
     public List<Diagnostic> Warnings { get; set; } = [];
+
+    /// <summary>Elements nested by namespace; only in <c>m3l parse --group-namespaces</c> output, which leaves the flat lists empty.</summary>
+    [JsonPropertyName("namespaces")]
+    public List<NamespaceGroup> Namespaces { get; set; } = [];
+}
+
+/// <summary>
+/// The elements of one namespace; <c>_default</c> holds those outside any namespace.
+/// </summary>
+public class NamespaceGroup
+{
+    [JsonPropertyName("name")]
+    public string Name { get; set; } = "";
+
+    [JsonPropertyName("models")]
+    public List<ModelNode> Models { get; set; } = [];
+
+    [JsonPropertyName("enums")]
+    public List<EnumNode> Enums { get; set; } = [];
+
+    [JsonPropertyName("interfaces")]
+    public List<ModelNode> Interfaces { get; set; } = [];
+
+    [JsonPropertyName("views")]
+    public List<ModelNode> Views { get; set; } = [];
+
+    [JsonPropertyName("flows")]
+    public List<ModelNode> Flows { get; set; } = [];
 }
 
 // ---------------------------------------------------------------------------
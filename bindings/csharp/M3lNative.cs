@@ -21,6 +21,12 @@ public static class M3lNative
     private static extern IntPtr m3l_parse_multi(
         [MarshalAs(UnmanagedType.LPUTF8Str)] string filesJson);
 
+    [DllImport(LibName, CallingConvention = CallingConvention.Cdecl, CharSet = CharSet.Ansi)]
+    private static extern IntPtr m3l_extract(
+        [MarshalAs(UnmanagedType.LPUTF8Str)] string content,
+        [MarshalAs(UnmanagedType.LPUTF8Str)] string filename,
+        [MarshalAs(UnmanagedType.LPUTF8Str)] string path);
+
     [DllImport(LibName, CallingConvention = CallingConvention.Cdecl, CharSet = CharSet.Ansi)]
     private static extern IntPtr m3l_validate(
         [MarshalAs(UnmanagedType.LPUTF8Str)] string content,
@@ -31,6 +37,10 @@ public static class M3lNative
         [MarshalAs(UnmanagedType.LPUTF8Str)] string content,
         [MarshalAs(UnmanagedType.LPUTF8Str)] string configJson);
 
+    [DllImport(LibName, CallingConvention = CallingConvention.Cdecl, CharSet = CharSet.Ansi)]
+    private static extern IntPtr m3l_explain(
+        [MarshalAs(UnmanagedType.LPUTF8Str)] string code);
+
     [DllImport(LibName, CallingConvention = CallingConvention.Cdecl)]
     private static extern void m3l_free_string(IntPtr ptr);
 
@@ -73,6 +83,20 @@ public static class M3lNative
         return CallNative(ptr);
     }
 
+    /// <summary>
+    /// Parse a single M3L file and return only the part of the AST at a JSON Pointer
+    /// (single value) or JSONPath (array of matches).
+    /// </summary>
+    /// <param name="content">M3L markdown text</param>
+    /// <param name="filename">Source filename for error reporting</param>
+    /// <param name="path">JSON Pointer (/models/0) or JSONPath ($.models[?(@.name == 'User')])</param>
+    /// <returns>JSON string with { success, data?, error? }</returns>
+    public static string Extract(string content, string filename, string path)
+    {
+        var ptr = m3l_extract(content, filename, path);
+        return CallNative(ptr);
+    }
+
     /// <summary>
     /// Validate M3L content and return diagnostics as JSON.
     /// </summary>
@@ -151,6 +175,18 @@ public static class M3lNative
         return JsonSerializer.Deserialize<M3lResult<LintResultData>>(json);
     }
 
+    /// <summary>
+    /// Describe a validation code or lint rule: title, description and
+    /// bad/fixed M3L examples.
+    /// </summary>
+    /// <param name="code">M3L-E005, E005 or a lint rule id such as naming-convention</param>
+    /// <returns>JSON string with { success, data?, error? }</returns>
+    public static string Explain(string code)
+    {
+        var ptr = m3l_explain(code);
+        return CallNative(ptr);
+    }
+
     private static readonly JsonSerializerOptions AstJsonOptions = new()
     {
         PropertyNameCaseInsensitive = false,
@@ -207,6 +243,43 @@ public class DiagnosticItem
 
     [JsonPropertyName("message")]
     public string Message { get; set; } = "";
+
+    [JsonPropertyName("fix")]
+    public LintFixItem? Fix { get; set; }
+}
+
+/// <summary>
+/// A machine-applicable fix attached to a lint diagnostic.
+/// </summary>
+public class LintFixItem
+{
+    [JsonPropertyName("title")]
+    public string Title { get; set; } = "";
+
+    [JsonPropertyName("edits")]
+    public List<LintFixEdit> Edits { get; set; } = [];
+
+    [JsonPropertyName("is_rename")]
+    public bool IsRename { get; set; }
+}
+
+/// <summary>
+/// Replaces the first whole-word <c>OldText</c> on a 1-based line; an empty
+/// <c>OldText</c> appends to the end of the line.
+/// </summary>
+public class LintFixEdit
+{
+    [JsonPropertyName("file")]
+    public string File { get; set; } = "";
+
+    [JsonPropertyName("line")]
+    public int Line { get; set; }
+
+    [JsonPropertyName("old_text")]
+    public string OldText { get; set; } = "";
+
+    [JsonPropertyName("new_text")]
+    public string NewText { get; set; } = "";
 }
 
 /// <summary>
@@ -17,6 +17,10 @@ export interface SourceLocation {
   file: string;
   line: number;
   col: number;
+  /** Last line of the node's text (0 in ASTs from older parsers) */
+  endLine: number;
+  /** Column just past the node's last character (0 in ASTs from older parsers) */
+  endCol: number;
 }
 
 // --- Primitive union types ---
@@ -39,7 +43,7 @@ export type ModelType = "model" | "enum" | "interface" | "view";
 export type DiagnosticSeverity = "error" | "warning";
 
 /** Default value type — serialized as lowercase string */
-export type DefaultValueType = "literal" | "expression";
+export type DefaultValueType = "literal" | "expression" | "json";
 
 // --- Attribute types ---
 
@@ -49,6 +53,7 @@ export interface FieldAttribute {
   cascade?: string;
   isStandard?: boolean;
   isRegistered?: boolean;
+  loc?: SourceLocation;
 }
 
 export interface CustomAttributeParsed {
@@ -69,10 +74,41 @@ export interface EnumValue {
   description?: string;
   type?: string;
   value?: unknown;
+  loc?: SourceLocation;
+}
+
+/** A union member or tuple component: `string(50)` in `string(50) | integer`. */
+export interface TypeMember {
+  type: string;
+  params?: ParamValue[];
+  generic_params?: string[];
+  /** Only tuple components are nullable on their own. */
+  nullable?: boolean;
 }
 
 // --- Field definition types ---
 
+/** The resolved target of an `@reference`/`@fk` field. */
+export type ReferentialAction = "cascade" | "set_null" | "no_action" | "restrict";
+
+export interface ReferenceDef {
+  model: string;
+  /** The target model's namespace, if it has one */
+  namespace?: string;
+  /** As written, or else the target's primary key */
+  field?: string;
+  /** From the `!`/`!!`/`?` symbol, `@on_delete`, or the field's nullability */
+  on_delete: ReferentialAction;
+  /** Only when `@on_update` or an `on_update:` item names an action */
+  on_update?: ReferentialAction;
+}
+
+/** The source field a view field selects. */
+export interface FromDef {
+  model: string;
+  field: string;
+}
+
 export interface LookupDef {
   path: string;
 }
@@ -90,11 +126,31 @@ export interface ComputedDef {
   platform?: string;
 }
 
+/** `@since`, `@deprecated`, `@example` and `@see` lines of a description */
+export interface DocTags {
+  since?: string;
+  deprecated?: boolean;
+  /** Text after `@deprecated` */
+  deprecation?: string;
+  examples?: string[];
+  see?: string[];
+}
+
+/** From `@deprecated("message", since: "2.0", replacement: X)` or an `@deprecated` doc tag */
+export interface Deprecation {
+  message?: string;
+  since?: string;
+  /** `replacement:`, or `X` in a message of the form `use X` */
+  replacement?: string;
+}
+
 // --- Field node ---
 
 export interface FieldNode {
   name: string;
   label?: string;
+  /** Localized labels by locale; `label` is the first one written. */
+  labels?: Record<string, string>;
   type?: string;
   params?: ParamValue[];
   generic_params?: string[];
@@ -104,22 +160,40 @@ export interface FieldNode {
   kind: FieldKind;
   default_value?: string;
   default_value_type?: DefaultValueType;
+  default_json?: unknown;
   description?: string;
+  doc?: DocTags;
+  deprecated?: Deprecation;
   attributes: FieldAttribute[];
   framework_attrs?: CustomAttribute[];
+  /** Set when an `@reference`/`@fk` target resolves */
+  reference?: ReferenceDef;
+  /** For a view field, the source field it selects; an untyped field takes its type. */
+  from?: FromDef;
   lookup?: LookupDef;
   rollup?: RollupDef;
   computed?: ComputedDef;
   enum_values?: EnumValue[];
+  /** Member types when `type` is `union`. */
+  union?: TypeMember[];
+  /** Component types, in order, when `type` is `tuple`. */
+  tuple?: TypeMember[];
+  /** The project type alias the field was written with; `type` holds its expansion. */
+  type_alias?: string;
   fields?: FieldNode[];
   loc: SourceLocation;
 }
 
 // --- View types ---
 
+/** Kind of a view join: `left` in `left join Order o on ...` */
+export type JoinKind = "inner" | "left" | "right" | "full" | "cross";
+
 export interface JoinDef {
   model: string;
   on: string;
+  kind?: JoinKind;
+  alias?: string;
 }
 
 export interface ViewSourceDef {
@@ -134,15 +208,120 @@ export interface ViewSourceDef {
 
 export interface RefreshDef {
   strategy: string;
+  /** The interval as written: `5m`, `6 hours`, `0 3 * * *`. */
   interval?: string;
+  /** `interval`, parsed; absent when it is malformed (`M3L-E022`). */
+  schedule?: RefreshSchedule;
 }
 
+export type RefreshSchedule =
+  | { kind: "interval"; seconds: number }
+  | { kind: "cron"; expression: string };
+
 // --- Sections ---
 
+/** An `### Indexes` entry, or an `@index(...)`/`@unique(...)` directive with its line in `raw`. */
+export interface IndexDef {
+  name?: string;
+  label?: string;
+  fields: string[];
+  unique: boolean;
+  /** Partial index condition */
+  where?: string;
+  /** `btree`, `hash`, `gin`, ... */
+  method?: string;
+  options?: Record<string, unknown>;
+  description?: string;
+  raw?: string;
+  loc: SourceLocation;
+}
+
+/** `>` (this model references the target), `<` (the target references this model) or `<>`. */
+export type RelationDirection = "to" | "from" | "both";
+
+export type Cardinality = "one-to-one" | "one-to-many" | "many-to-one" | "many-to-many";
+
+/** A `### Relations` entry or `@relation(...)` line, as written in `raw`. */
+export interface RelationDef {
+  name: string;
+  direction?: RelationDirection;
+  /** `Person`, or `Comment.post_id` */
+  target?: string;
+  /** The foreign key field */
+  from?: string;
+  /** As written, or else following the direction */
+  cardinality?: Cardinality;
+  optional?: boolean;
+  on_delete?: string;
+  options?: Record<string, unknown>;
+  description?: string;
+  raw: string;
+  loc: SourceLocation;
+}
+
+export type BehaviorTiming = "before" | "after" | "on";
+
+/** A `### Behaviors` entry or `@behavior(...)` line, as written in `raw`. */
+export interface BehaviorDef {
+  name: string;
+  /** Omitted when the name has no `before_`/`after_`/`on_` prefix */
+  timing?: BehaviorTiming;
+  /** `create`, `update`, `delete`, `save` or a custom event */
+  event: string;
+  action?: string;
+  condition?: string;
+  options?: Record<string, unknown>;
+  description?: string;
+  raw: string;
+  loc: SourceLocation;
+}
+
+export interface KeyDef {
+  name?: string;
+  fields: string[];
+  description?: string;
+  loc: SourceLocation;
+}
+
+/** `@check(...)` constraint; `field` is set when declared on a field */
+export interface CheckDef {
+  expression: string;
+  field?: string;
+  description?: string;
+  loc: SourceLocation;
+}
+
+/** REST operation from an `### Endpoints` (or `### API`) section */
+export interface EndpointDef {
+  /** Upper-case HTTP method */
+  method: string;
+  path: string;
+  /** `{name}` path segments, in order */
+  params?: string[];
+  description?: string;
+  options?: Record<string, unknown>;
+  loc: SourceLocation;
+}
+
+/** Row of a `### Seed` section; `name` is set for list rows */
+export interface SeedRow {
+  name?: string;
+  values: Record<string, string | number | boolean | null>;
+  loc: SourceLocation;
+}
+
 export interface Sections {
-  indexes: unknown[];
-  relations: unknown[];
-  behaviors: unknown[];
+  indexes: IndexDef[];
+  /** Natural keys; omitted when the model declares none */
+  keys?: KeyDef[];
+  /** Check constraints; omitted when the model declares none */
+  checks?: CheckDef[];
+  /** Seed rows; omitted when the model declares none */
+  seeds?: SeedRow[];
+  /** REST endpoints; omitted when the model declares none */
+  endpoints?: EndpointDef[];
+  relations: RelationDef[];
+  behaviors: BehaviorDef[];
   metadata: Record<string, unknown>;
   /** Additional custom sections (via serde flatten) */
   [key: string]: unknown;
@@ -153,17 +332,31 @@ export interface Sections {
 export interface ModelNode {
   name: string;
   label?: string;
+  /** Localized labels by locale; `label` is the first one written. */
+  labels?: Record<string, string>;
   type: ModelType;
   source: string;
+  namespace?: string;
   line: number;
   inherits: string[];
+  /** Other names the model can be referenced by, from `@alias(Account)`. */
+  aliases?: string[];
   description?: string;
+  doc?: DocTags;
+  deprecated?: Deprecation;
   attributes: FieldAttribute[];
   fields: FieldNode[];
   sections: Sections;
   materialized?: boolean;
   source_def?: ViewSourceDef;
   refresh?: RefreshDef;
+  /** Type parameters of a generic model: `T` in `## Paged<T>`. */
+  generic_params?: string[];
+  /** Set on models the resolver instantiated from a generic one. */
+  instance_of?: string;
+  generic_args?: string[];
+  /** Parents added by the project's `[defaults] inherits`; also in `inherits`. */
+  default_inherits?: string[];
   loc: SourceLocation;
 }
 
@@ -172,11 +365,16 @@ export interface ModelNode {
 export interface EnumNode {
   name: string;
   label?: string;
+  /** Localized labels by locale; `label` is the first one written. */
+  labels?: Record<string, string>;
   type: ModelType;
   source: string;
+  namespace?: string;
   line: number;
   inherits: string[];
   description?: string;
+  doc?: DocTags;
+  deprecated?: Deprecation;
   values: EnumValue[];
   loc: SourceLocation;
 }
@@ -188,6 +386,24 @@ export interface ProjectInfo {
   version?: string;
 }
 
+/** Metadata from a file's leading `---` YAML frontmatter block. */
+export interface FileMetadata {
+  source: string;
+  namespace?: string;
+  owner?: string;
+  version?: string;
+  tags?: string[];
+  /** Any other keys. */
+  metadata?: Record<string, unknown>;
+}
+
+/** A file's `<!-- m3l: 1.1 -->` or `# M3L-Version: 1.1` pragma. */
+export interface M3lVersion {
+  source: string;
+  /** As written */
+  version: string;
+}
+
 // --- Diagnostics ---
 
 export interface Diagnostic {
@@ -207,26 +423,72 @@ export interface AttributeRegistryEntry {
   target: string[];
   type: string;
   range?: [number, number];
+  /** Allowed arguments of a `type: enum` attribute. */
+  values?: string[];
+  /** Regex that string arguments must match. */
+  pattern?: string;
   required: boolean;
   defaultValue?: AttrArgValue;
 }
 
 // --- Top-level AST ---
 
+/** An `@import`, resolved against the loaded sources. */
+export interface ImportEdge {
+  /** The path as written. */
+  path: string;
+  /** The loaded source it refers to; absent when it was not loaded (M3L-E013). */
+  target?: string;
+  symbols?: string[];
+  line: number;
+}
+
+/** A loaded file with its namespace and the files it imports. */
+export interface SourceFile {
+  path: string;
+  namespace?: string;
+  imports?: ImportEdge[];
+}
+
 export interface M3lAst {
   parserVersion: string;
   astVersion: string;
   project: ProjectInfo;
   sources: string[];
+  /** Each source's namespace and import edges, in source order. */
+  files?: SourceFile[];
   models: ModelNode[];
   enums: EnumNode[];
   interfaces: ModelNode[];
   views: ModelNode[];
   attributeRegistry: AttributeRegistryEntry[];
+  /** Frontmatter of the files that have one, in source order. */
+  fileMetadata?: FileMetadata[];
+  /** Version pragmas of the files that have one, in source order. */
+  m3lVersions?: M3lVersion[];
+  /** Concrete models for each use of a generic model, named as used: `Paged<Product>`. */
+  genericInstances?: ModelNode[];
+  /** `## extend User` blocks as written; their fields, indexes and relations are already in the model. */
+  extendBlocks?: ModelNode[];
   errors: Diagnostic[];
   warnings: Diagnostic[];
 }
 
+/** The elements of one namespace; `_default` holds those outside any namespace. */
+export interface NamespaceGroup {
+  name: string;
+  models?: ModelNode[];
+  enums?: EnumNode[];
+  interfaces?: ModelNode[];
+  views?: ModelNode[];
+  flows?: ModelNode[];
+}
+
+/** Output of `m3l parse --group-namespaces`: elements nested by namespace instead of flat lists. */
+export type NamespacedAst = Omit<M3lAst, 'models' | 'enums' | 'interfaces' | 'views'> & {
+  namespaces: NamespaceGroup[];
+};
+
 // --- Validate result ---
 
 export interface ValidateResult {
@@ -249,8 +511,25 @@ export interface LintDiagnostic {
   line: number;
   col: number;
   message: string;
+  fix?: LintFix;
+}
+
+/** Replacement of the first whole-word `old_text` on a 1-based line; empty `old_text` appends */
+export interface FixEdit {
+  file: string;
+  line: number;
+  old_text: string;
+  new_text: string;
 }
 
+export interface LintFix {
+  title: string;
+  edits: FixEdit[];
+  /** The edits rename a declaration; references should be renamed too */
+  is_rename?: boolean;
+}
+
+/** Unknown keys and rule ids are rejected with an error naming the accepted ones. */
 export interface LintConfig {
   rules?: Record<string, RuleLevel>;
 }
@@ -267,11 +546,30 @@ export interface FileInput {
   filename: string;
 }
 
+/** Unknown keys are rejected with an error naming the accepted ones. */
 export interface ValidateOptions {
   strict?: boolean;
   filename?: string;
 }
 
+/** Code kind — serialized as lowercase string */
+export type CodeKind = "error" | "warning" | "lint";
+
+/** Reference entry for a validation code or lint rule */
+export interface CodeDoc {
+  /** `M3L-E005` or a lint rule id */
+  code: string;
+  kind: CodeKind;
+  title: string;
+  description: string;
+  /** M3L that is reported */
+  bad: string;
+  /** The same M3L, fixed */
+  good: string;
+  /** Only reported by strict validation */
+  strict: boolean;
+}
+
 // ---------------------------------------------------------------------------
 // Function declarations
 // ---------------------------------------------------------------------------
@@ -297,6 +595,24 @@ export function parse(content: string, filename: string): string;
  */
 export function parseMulti(filesJson: string): string;
 
+/**
+ * Parse a single M3L file and return only the part of the AST at `path`,
+ * without serializing the whole AST.
+ *
+ * A JSON Pointer (`/models/0/fields`) yields the single value at that
+ * location. A JSONPath (`$.models[?(@.name == 'User')].fields[*].name`)
+ * yields an array of every match; supported are `.name`, `['name']`, `[n]`,
+ * `[*]` and `[?(...)]` filters over relative paths (`@.key`,
+ * `@.attributes[?(@.name == 'pk')]`) with `==`, `!=`, `<`, `<=`, `>`, `>=`,
+ * `!`, `&&`, `||` and parentheses.
+ *
+ * @param content - M3L markdown text
+ * @param filename - Source filename for error reporting
+ * @param path - JSON Pointer or JSONPath
+ * @returns JSON string with `{ success: boolean, data?: unknown, error?: string }`
+ */
+export function extract(content: string, filename: string, path: string): string;
+
 /**
  * Validate M3L content and return diagnostics as JSON.
  *
@@ -318,3 +634,13 @@ export function validate(content: string, optionsJson: string): string;
  * @returns JSON string with `{ success: boolean, data?: LintResult, error?: string }`
  */
 export function lint(content: string, configJson: string): string;
+
+/**
+ * Describe a validation code or lint rule.
+ *
+ * The returned JSON string deserializes to `M3lResult<CodeDoc>`.
+ *
+ * @param code - `M3L-E005`, `E005` or a lint rule id such as `naming-convention`
+ * @returns JSON string with `{ success: boolean, data?: CodeDoc, error?: string }`
+ */
+export function explain(code: string): string;
@@ -5,9 +5,11 @@
  * All parsing is performed by the Rust m3l-core library.
  */
 
-const { parse, parseMulti, validate, lint } = require('@iyulab/m3l-napi');
+const { parse, parseMulti, extract, validate, lint, explain } = require('@iyulab/m3l-napi');
 
 module.exports.parse = parse;
 module.exports.parseMulti = parseMulti;
+module.exports.extract = extract;
 module.exports.validate = validate;
 module.exports.lint = lint;
+module.exports.explain = explain;
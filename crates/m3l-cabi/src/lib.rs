@@ -9,7 +9,9 @@
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
-use m3l_core::{parse_multi_to_json, parse_to_json, validate_to_json};
+use m3l_core::{
+    explain_to_json, extract_to_json, parse_multi_to_json, parse_to_json, validate_to_json,
+};
 use m3l_lint::lint_to_json;
 
 /// Parse a single M3L file and return the AST as JSON.
@@ -54,6 +56,39 @@ pub unsafe extern "C" fn m3l_parse_multi(files_json: *const c_char) -> *mut c_ch
     to_c_string(&result)
 }
 
+/// Parse a single M3L file and return only the part of the AST at `path`
+/// (a JSON Pointer or JSONPath).
+///
+/// # Safety
+/// - `content`, `filename` and `path` must be valid null-terminated UTF-8 strings.
+/// - The returned pointer must be freed with `m3l_free_string`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn m3l_extract(
+    content: *const c_char,
+    filename: *const c_char,
+    path: *const c_char,
+) -> *mut c_char {
+    let content = unsafe { CStr::from_ptr(content) };
+    let filename = unsafe { CStr::from_ptr(filename) };
+    let path = unsafe { CStr::from_ptr(path) };
+
+    let content_str = match content.to_str() {
+        Ok(s) => s,
+        Err(_) => return to_c_string(r#"{"success":false,"error":"Invalid UTF-8 in content"}"#),
+    };
+    let filename_str = match filename.to_str() {
+        Ok(s) => s,
+        Err(_) => return to_c_string(r#"{"success":false,"error":"Invalid UTF-8 in filename"}"#),
+    };
+    let path_str = match path.to_str() {
+        Ok(s) => s,
+        Err(_) => return to_c_string(r#"{"success":false,"error":"Invalid UTF-8 in path"}"#),
+    };
+
+    let result = extract_to_json(content_str, filename_str, path_str);
+    to_c_string(&result)
+}
+
 /// Validate M3L content and return diagnostics as JSON.
 ///
 /// # Safety
@@ -112,7 +147,27 @@ pub unsafe extern "C" fn m3l_lint(
     to_c_string(&result)
 }
 
-/// Free a string previously returned by m3l_parse, m3l_parse_multi, m3l_validate, or m3l_lint.
+/// Describe a validation code (`M3L-E005`) or lint rule (`naming-convention`)
+/// as JSON.
+///
+/// # Safety
+/// - `code` must be a valid null-terminated UTF-8 string.
+/// - The returned pointer must be freed with `m3l_free_string`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn m3l_explain(code: *const c_char) -> *mut c_char {
+    let code = unsafe { CStr::from_ptr(code) };
+
+    let code_str = match code.to_str() {
+        Ok(s) => s,
+        Err(_) => return to_c_string(r#"{"success":false,"error":"Invalid UTF-8 in code"}"#),
+    };
+
+    let result = explain_to_json(code_str);
+    to_c_string(&result)
+}
+
+/// Free a string previously returned by m3l_parse, m3l_parse_multi, m3l_extract, m3l_validate,
+/// m3l_lint or m3l_explain.
 ///
 /// # Safety
 /// - `ptr` must be a pointer previously returned by one of the m3l_* functions,
@@ -1,23 +1,662 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::Path;
 
+use clap::ValueEnum;
+use m3l_core::{Cardinality, RelationDirection};
+use serde::Serialize;
+
 use crate::build_ast;
+use crate::commands::format::{order_models, ModelOrder};
+use crate::commands::stats::inheritance_depth;
+
+/// A dependency between two schema elements.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Edge {
+    source: String,
+    target: String,
+    relation: String,
+    /// Source and target are declared in different namespaces
+    cross_namespace: bool,
+}
+
+/// Kind of schema element a graph node stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NodeKind {
+    #[value(skip)]
+    Model,
+    #[value(name = "interfaces")]
+    Interface,
+    #[value(name = "views")]
+    View,
+    #[value(name = "enums")]
+    Enum,
+}
+
+impl NodeKind {
+    fn name(self) -> &'static str {
+        match self {
+            NodeKind::Model => "model",
+            NodeKind::Interface => "interface",
+            NodeKind::View => "view",
+            NodeKind::Enum => "enum",
+        }
+    }
+}
+
+/// Which part of the dependency graph to draw.
+#[derive(Debug, Clone, Default)]
+pub struct GraphOptions {
+    pub focus: Option<Focus>,
+    /// Element kinds drawn besides models; `None` draws every kind.
+    pub include: Option<Vec<NodeKind>>,
+}
+
+/// Part of the graph to draw: `model` and the elements within `depth`
+/// edges of it, in either direction.
+#[derive(Debug, Clone)]
+pub struct Focus {
+    pub model: String,
+    pub depth: usize,
+}
+
+impl Focus {
+    /// Names within `depth` hops of the focused one over `links`; an error if
+    /// the focused name is not among `names`.
+    fn neighborhood<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a str>,
+        links: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<HashSet<String>, String> {
+        if !names.into_iter().any(|n| n == self.model) {
+            return Err(format!(
+                "Unknown element \"{}\"; --focus takes a model, interface, view or enum name",
+                self.model
+            ));
+        }
+        let mut adjacent: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (a, b) in links {
+            adjacent.entry(a).or_default().push(b);
+            adjacent.entry(b).or_default().push(a);
+        }
+        let mut reached: HashSet<String> = HashSet::from([self.model.clone()]);
+        let mut frontier: Vec<&str> = vec![self.model.as_str()];
+        for _ in 0..self.depth {
+            let mut next = Vec::new();
+            for name in frontier {
+                for &n in adjacent.get(name).into_iter().flatten() {
+                    if reached.insert(n.to_string()) {
+                        next.push(n);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        Ok(reached)
+    }
+}
 
-pub fn run_analyze(input_path: &Path, format: &str) -> Result<String, String> {
+pub fn run_analyze(
+    input_path: &Path,
+    format: &str,
+    options: &GraphOptions,
+) -> Result<String, String> {
+    analyze_ast(&build_ast(input_path)?, format, options)
+}
+
+/// `m3l analyze --check-cycles`: list the cycles of the reference graph
+/// (models whose foreign keys depend on each other, so no table can be
+/// created first). Returns the report and the number of cycles.
+pub fn run_check_cycles(input_path: &Path) -> Result<(String, usize), String> {
+    let cycles = find_cycles(&build_ast(input_path)?);
+    if cycles.is_empty() {
+        return Ok(("No reference cycles found".into(), 0));
+    }
+    let mut lines = vec![format!(
+        "Found {} reference {}:",
+        cycles.len(),
+        if cycles.len() == 1 { "cycle" } else { "cycles" }
+    )];
+    for cycle in &cycles {
+        lines.push(format!("  {}", cycle_path(cycle)));
+    }
+    Ok((lines.join("\n"), cycles.len()))
+}
+
+/// Elementary cycles of the reference graph between models, each as the
+/// `(model, field)` steps from its alphabetically first model. A model that
+/// references itself is not a cycle: its rows, not its table, depend on
+/// each other.
+fn find_cycles(ast: &m3l_core::M3lAst) -> Vec<Vec<(String, String)>> {
+    let models: Vec<&m3l_core::ModelNode> = ast.models.iter().collect();
+    // model → (field, target), first field per target
+    let mut graph: BTreeMap<&str, BTreeMap<&str, &str>> = BTreeMap::new();
+    for model in &models {
+        let targets = graph.entry(model.name.as_str()).or_default();
+        for field in &model.fields {
+            if let Some(target) = reference_target(ast, field) {
+                if target.name != model.name {
+                    targets
+                        .entry(target.name.as_str())
+                        .or_insert(field.name.as_str());
+                }
+            }
+        }
+    }
+
+    let mut cycles = Vec::new();
+    for &start in graph.keys() {
+        let mut path = Vec::new();
+        walk_cycles(start, start, &graph, &mut path, &mut cycles);
+    }
+    cycles
+}
+
+/// Depth-first search for paths back to `start` through models that sort
+/// after it, so each cycle is found once.
+fn walk_cycles<'a>(
+    start: &'a str,
+    node: &'a str,
+    graph: &BTreeMap<&'a str, BTreeMap<&'a str, &'a str>>,
+    path: &mut Vec<(&'a str, &'a str)>,
+    cycles: &mut Vec<Vec<(String, String)>>,
+) {
+    for (&target, &field) in graph.get(node).into_iter().flatten() {
+        path.push((node, field));
+        if target == start {
+            cycles.push(
+                path.iter()
+                    .map(|(m, f)| (m.to_string(), f.to_string()))
+                    .collect(),
+            );
+        } else if target > start && !path.iter().any(|(m, _)| *m == target) {
+            walk_cycles(start, target, graph, path, cycles);
+        }
+        path.pop();
+    }
+}
+
+/// `m3l analyze --orphans`: models, interfaces and enums that nothing
+/// references, inherits from, or uses as a view source or rollup target.
+pub fn run_orphans(input_path: &Path) -> Result<String, String> {
     let ast = build_ast(input_path)?;
+    let (_, edges) = dependency_graph(&ast);
+    let names = NodeNames::new(&ast);
+    let mut used: HashSet<&str> = edges.iter().map(|e| e.target.as_str()).collect();
+    for view in &ast.views {
+        if let Some(ref source) = view.source_def {
+            used.extend(source.from.as_deref());
+            used.extend(source.joins.iter().flatten().map(|j| j.model.as_str()));
+        }
+    }
+    for model in ast.models.iter().chain(ast.views.iter()) {
+        for field in &model.fields {
+            for attr in field.attributes.iter().filter(|a| a.name == "rollup") {
+                if let Some(m3l_core::AttrArgValue::String(target)) =
+                    attr.args.as_ref().and_then(|a| a.first())
+                {
+                    used.insert(target.split('.').next().unwrap_or(target));
+                }
+            }
+        }
+    }
+    for e in &ast.enums {
+        used.extend(e.inherits.iter().map(String::as_str));
+    }
+
+    // Edges name a node, view sources, rollups and enum parents the element
+    let unused = |namespace: &Option<String>, name: &str| {
+        !used.contains(names.of(namespace.as_deref(), name).as_str()) && !used.contains(name)
+    };
+
+    let mut orphans: Vec<(&str, &str, &str, usize)> = Vec::new();
+    for (kind, models) in [("model", &ast.models), ("interface", &ast.interfaces)] {
+        orphans.extend(
+            models
+                .iter()
+                .filter(|m| unused(&m.namespace, &m.name))
+                .map(|m| (kind, m.name.as_str(), m.source.as_str(), m.line)),
+        );
+    }
+    orphans.extend(
+        ast.enums
+            .iter()
+            .filter(|e| unused(&e.namespace, &e.name))
+            .map(|e| ("enum", e.name.as_str(), e.source.as_str(), e.line)),
+    );
+    if orphans.is_empty() {
+        return Ok("No unreferenced models, interfaces or enums".into());
+    }
+    let mut lines = vec![format!(
+        "{} unreferenced {}:",
+        orphans.len(),
+        if orphans.len() == 1 {
+            "element"
+        } else {
+            "elements"
+        }
+    )];
+    for (kind, name, file, line) in orphans {
+        lines.push(format!("  {kind} {name} ({file}:{line})"));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Coupling of one model in the dependency graph.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelMetrics {
+    name: String,
+    /// Elements that depend on this model
+    fan_in: usize,
+    /// Elements this model depends on
+    fan_out: usize,
+    inheritance_depth: usize,
+    /// Size of the strongly connected component the model is in (1 when it
+    /// is on no reference cycle)
+    component_size: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CouplingReport {
+    models: Vec<ModelMetrics>,
+    /// Mean of fan-in plus fan-out over the models
+    average_degree: f64,
+    /// Strongly connected components with more than one model
+    components: Vec<Vec<String>>,
+}
+
+/// `m3l analyze --metrics`: fan-in, fan-out, inheritance depth and
+/// strongly connected components of the models, as a table or JSON.
+pub fn run_metrics(input_path: &Path, format: &str) -> Result<String, String> {
+    let report = coupling(&build_ast(input_path)?);
+    match format {
+        "json" => serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("JSON serialization error: {e}")),
+        "mermaid" | "table" => Ok(format_coupling(&report)),
+        other => Err(format!(
+            "Unknown format \"{other}\" for --metrics; use table (default) or json"
+        )),
+    }
+}
+
+fn coupling(ast: &m3l_core::M3lAst) -> CouplingReport {
+    let (_, edges) = dependency_graph(ast);
+    let mut fan_in: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    let mut fan_out: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for edge in &edges {
+        fan_in.entry(&edge.target).or_default().insert(&edge.source);
+        fan_out
+            .entry(&edge.source)
+            .or_default()
+            .insert(&edge.target);
+    }
+
+    let names = NodeNames::new(ast);
+    let model_names: BTreeSet<String> = ast
+        .models
+        .iter()
+        .map(|m| names.of(m.namespace.as_deref(), &m.name))
+        .collect();
+    let mut graph: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for name in &model_names {
+        graph.insert(name, Vec::new());
+    }
+    for edge in &edges {
+        if model_names.contains(edge.target.as_str()) {
+            if let Some(targets) = graph.get_mut(edge.source.as_str()) {
+                targets.push(&edge.target);
+            }
+        }
+    }
+    let components = strongly_connected(&graph);
+    let component_size: HashMap<&str, usize> = components
+        .iter()
+        .flat_map(|c| c.iter().map(move |m| (*m, c.len())))
+        .collect();
+
+    let by_name: HashMap<&str, &m3l_core::ModelNode> = ast
+        .models
+        .iter()
+        .chain(ast.interfaces.iter())
+        .map(|m| (m.name.as_str(), m))
+        .collect();
+    let count =
+        |map: &HashMap<&str, BTreeSet<&str>>, name: &str| map.get(name).map_or(0, BTreeSet::len);
+    let models: Vec<ModelMetrics> = ast
+        .models
+        .iter()
+        .map(|m| {
+            let node = names.of(m.namespace.as_deref(), &m.name);
+            ModelMetrics {
+                fan_in: count(&fan_in, &node),
+                fan_out: count(&fan_out, &node),
+                inheritance_depth: inheritance_depth(&m.name, &by_name, &mut Vec::new()),
+                component_size: component_size.get(node.as_str()).copied().unwrap_or(1),
+                name: node,
+            }
+        })
+        .collect();
+
+    let average_degree = if models.is_empty() {
+        0.0
+    } else {
+        let total: usize = models.iter().map(|m| m.fan_in + m.fan_out).sum();
+        // Two decimal places keep the report readable
+        (total as f64 / models.len() as f64 * 100.0).round() / 100.0
+    };
+    let mut components: Vec<Vec<String>> = components
+        .into_iter()
+        .filter(|c| c.len() > 1)
+        .map(|c| {
+            let mut names: Vec<String> = c.into_iter().map(str::to_string).collect();
+            names.sort();
+            names
+        })
+        .collect();
+    components.sort();
+
+    CouplingReport {
+        models,
+        average_degree,
+        components,
+    }
+}
+
+fn format_coupling(report: &CouplingReport) -> String {
+    let width = report
+        .models
+        .iter()
+        .map(|m| m.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("Model".len());
+    let mut lines = vec![format!(
+        "{:<width$}  {:>6}  {:>7}  {:>5}  {:>3}",
+        "Model", "Fan-in", "Fan-out", "Depth", "SCC"
+    )];
+    for m in &report.models {
+        lines.push(format!(
+            "{:<width$}  {:>6}  {:>7}  {:>5}  {:>3}",
+            m.name, m.fan_in, m.fan_out, m.inheritance_depth, m.component_size
+        ));
+    }
+    lines.push(String::new());
+    lines.push(format!("Average degree: {}", report.average_degree));
+    if report.components.is_empty() {
+        lines.push("Strongly connected components: none".into());
+    } else {
+        let sizes: Vec<String> = report
+            .components
+            .iter()
+            .map(|c| c.len().to_string())
+            .collect();
+        lines.push(format!(
+            "Strongly connected components: {} (sizes {})",
+            report.components.len(),
+            sizes.join(", ")
+        ));
+        for component in &report.components {
+            lines.push(format!("  {}", component.join(", ")));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Tarjan's algorithm: the strongly connected components of `graph`.
+fn strongly_connected<'a>(graph: &BTreeMap<&'a str, Vec<&'a str>>) -> Vec<Vec<&'a str>> {
+    struct State<'a> {
+        index: HashMap<&'a str, usize>,
+        low: HashMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: HashSet<&'a str>,
+        components: Vec<Vec<&'a str>>,
+    }
+
+    fn connect<'a>(node: &'a str, graph: &BTreeMap<&'a str, Vec<&'a str>>, state: &mut State<'a>) {
+        let index = state.index.len();
+        state.index.insert(node, index);
+        state.low.insert(node, index);
+        state.stack.push(node);
+        state.on_stack.insert(node);
+        for &next in graph.get(node).into_iter().flatten() {
+            if !state.index.contains_key(next) {
+                connect(next, graph, state);
+                let low = state.low[node].min(state.low[next]);
+                state.low.insert(node, low);
+            } else if state.on_stack.contains(next) {
+                let low = state.low[node].min(state.index[next]);
+                state.low.insert(node, low);
+            }
+        }
+        if state.low[node] == state.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(member);
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            state.components.push(component);
+        }
+    }
+
+    let mut state = State {
+        index: HashMap::new(),
+        low: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        components: Vec::new(),
+    };
+    for &node in graph.keys() {
+        if !state.index.contains_key(node) {
+            connect(node, graph, &mut state);
+        }
+    }
+    state.components
+}
+
+/// Render the dependency graph of a resolved AST as Mermaid, a Mermaid
+/// entity-relationship diagram (`er`), DOT, GraphML or D2, limited to the
+/// included element kinds and the focus neighborhood.
+pub fn analyze_ast(
+    ast: &m3l_core::M3lAst,
+    format: &str,
+    options: &GraphOptions,
+) -> Result<String, String> {
+    let focus = options.focus.as_ref();
+    match format {
+        "er" => return render_er(ast, focus),
+        "order" => return render_order(ast, focus),
+        _ => {}
+    }
+
+    let (mut defined_names, mut edges) = dependency_graph(ast);
+    let kinds = node_kinds(ast);
+
+    if let Some(ref include) = options.include {
+        defined_names.retain(|n| {
+            let kind = kind_of(&kinds, n);
+            kind == NodeKind::Model || include.contains(&kind)
+        });
+        edges.retain(|e| defined_names.contains(&e.source) && defined_names.contains(&e.target));
+    }
+
+    if let Some(focus) = focus {
+        let reached = focus.neighborhood(
+            defined_names.iter().map(String::as_str),
+            edges.iter().map(|e| (e.source.as_str(), e.target.as_str())),
+        )?;
+        defined_names.retain(|n| reached.contains(n));
+        edges.retain(|e| reached.contains(&e.source) && reached.contains(&e.target));
+    }
+
+    match format {
+        "dot" => Ok(render_dot(&defined_names, &edges, &kinds)),
+        "graphml" => Ok(render_graphml(&defined_names, &edges, &kinds)),
+        "d2" => Ok(render_d2(&defined_names, &edges, &kinds)),
+        _ => Ok(render_mermaid(&defined_names, &edges, &kinds)),
+    }
+}
+
+/// Kind of every node.
+fn node_kinds(ast: &m3l_core::M3lAst) -> HashMap<String, NodeKind> {
+    let names = NodeNames::new(ast);
+    let mut kinds: HashMap<String, NodeKind> = HashMap::new();
+    for (kind, models) in [
+        (NodeKind::Model, &ast.models),
+        (NodeKind::Interface, &ast.interfaces),
+        (NodeKind::View, &ast.views),
+    ] {
+        kinds.extend(
+            models
+                .iter()
+                .map(|m| (names.of(m.namespace.as_deref(), &m.name), kind)),
+        );
+    }
+    kinds.extend(
+        ast.enums
+            .iter()
+            .map(|e| (names.of(e.namespace.as_deref(), &e.name), NodeKind::Enum)),
+    );
+    kinds
+}
+
+fn kind_of(kinds: &HashMap<String, NodeKind>, name: &str) -> NodeKind {
+    kinds.get(name).copied().unwrap_or(NodeKind::Model)
+}
+
+/// Models in an order they can be created in: parents and referenced
+/// models first. Models on a reference cycle are marked, and the cycles are
+/// listed after the order, since one foreign key per cycle has to be added
+/// after its tables exist.
+fn render_order(ast: &m3l_core::M3lAst, focus: Option<&Focus>) -> Result<String, String> {
+    let mut models = order_models(&ast.models, ModelOrder::Dependency);
+    let mut cycles = find_cycles(ast);
+    if let Some(focus) = focus {
+        let (names, edges) = dependency_graph(ast);
+        let reached = focus.neighborhood(
+            names.iter().map(String::as_str),
+            edges.iter().map(|e| (e.source.as_str(), e.target.as_str())),
+        )?;
+        let names = NodeNames::new(ast);
+        models.retain(|m| reached.contains(&names.of(m.namespace.as_deref(), &m.name)));
+        cycles.retain(|c| c.iter().all(|(m, _)| reached.contains(m)));
+    }
+
+    let in_cycle: HashSet<&str> = cycles.iter().flatten().map(|(m, _)| m.as_str()).collect();
+    let mut lines: Vec<String> = models
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let mark = if in_cycle.contains(m.name.as_str()) {
+                " (cycle)"
+            } else {
+                ""
+            };
+            format!("{}. {}{mark}", i + 1, m.name)
+        })
+        .collect();
+    if !cycles.is_empty() {
+        lines.push(String::new());
+        lines.push(format!(
+            "{} reference {}; create the tables, then add one of each cycle's foreign keys:",
+            cycles.len(),
+            if cycles.len() == 1 { "cycle" } else { "cycles" }
+        ));
+        for cycle in &cycles {
+            lines.push(format!("  {}", cycle_path(cycle)));
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+/// `A.b_id → B.a_id → A`.
+fn cycle_path(cycle: &[(String, String)]) -> String {
+    let mut path: Vec<String> = cycle.iter().map(|(m, f)| format!("{m}.{f}")).collect();
+    path.push(cycle[0].0.clone());
+    path.join(" → ")
+}
 
-    // Collect all defined model/enum/interface/view names
-    let mut defined_names: HashSet<String> = HashSet::new();
+/// Graph node names: an element's name, qualified with its namespace
+/// (`Namespace.Model`) when another namespace defines the same name, so the
+/// two stay separate nodes.
+struct NodeNames {
+    /// Namespaces each element name is defined in
+    namespaces: HashMap<String, Vec<Option<String>>>,
+}
+
+impl NodeNames {
+    fn new(ast: &m3l_core::M3lAst) -> Self {
+        let mut namespaces: HashMap<String, Vec<Option<String>>> = HashMap::new();
+        let elements = ast
+            .models
+            .iter()
+            .chain(ast.interfaces.iter())
+            .chain(ast.views.iter())
+            .map(|m| (&m.name, &m.namespace))
+            .chain(ast.enums.iter().map(|e| (&e.name, &e.namespace)));
+        for (name, namespace) in elements {
+            let entry = namespaces.entry(name.clone()).or_default();
+            if !entry.contains(namespace) {
+                entry.push(namespace.clone());
+            }
+        }
+        NodeNames { namespaces }
+    }
+
+    /// Node name of element `name` declared in `namespace`.
+    fn of(&self, namespace: Option<&str>, name: &str) -> String {
+        match namespace {
+            Some(ns) if self.namespaces.get(name).is_some_and(|n| n.len() > 1) => {
+                format!("{ns}.{name}")
+            }
+            _ => name.to_string(),
+        }
+    }
+
+    /// Node name of the element a type or parent name written in namespace
+    /// `from` stands for: a qualified name picks its namespace, a short one
+    /// prefers `from`. `None` when no element has that name.
+    fn resolve(&self, written: &str, from: Option<&str>) -> Option<String> {
+        let (qualifier, name) = match written.rsplit_once('.') {
+            Some((ns, name)) => (Some(ns), name),
+            None => (None, written),
+        };
+        let defined = self.namespaces.get(name)?;
+        let wanted = qualifier.or(from);
+        let namespace = defined
+            .iter()
+            .find(|ns| ns.as_deref() == wanted)
+            .unwrap_or(&defined[0]);
+        Some(self.of(namespace.as_deref(), name))
+    }
+}
+
+/// Every model, interface, view and enum node name, and the deduplicated
+/// dependency edges between them.
+fn dependency_graph(ast: &m3l_core::M3lAst) -> (BTreeSet<String>, Vec<Edge>) {
+    let names = NodeNames::new(ast);
+    // Collect all defined model/enum/interface/view nodes and their namespaces.
+    // Sorted, so isolated nodes print in a stable order (generated diagrams
+    // are compared byte for byte by `m3l generate --check`)
+    let mut defined_names: BTreeSet<String> = BTreeSet::new();
+    let mut namespaces: HashMap<String, Option<String>> = HashMap::new();
     for m in ast
         .models
         .iter()
         .chain(ast.interfaces.iter())
         .chain(ast.views.iter())
     {
-        defined_names.insert(m.name.clone());
+        let node = names.of(m.namespace.as_deref(), &m.name);
+        defined_names.insert(node.clone());
+        namespaces.insert(node, m.namespace.clone());
     }
     for e in &ast.enums {
-        defined_names.insert(e.name.clone());
+        let node = names.of(e.namespace.as_deref(), &e.name);
+        defined_names.insert(node.clone());
+        namespaces.insert(node, e.namespace.clone());
     }
 
     // edges: (source_model, target_model, relation_type)
@@ -29,63 +668,83 @@ pub fn run_analyze(input_path: &Path, format: &str) -> Result<String, String> {
         .chain(ast.interfaces.iter())
         .chain(ast.views.iter())
     {
+        let node = names.of(m.namespace.as_deref(), &m.name);
         // Inheritance edges
         for parent in &m.inherits {
-            if defined_names.contains(parent.as_str()) {
-                edges.push((m.name.clone(), parent.clone(), "inherits".into()));
+            if let Some(parent) = names.resolve(parent, m.namespace.as_deref()) {
+                edges.push((node.clone(), parent, "inherits".into()));
             }
         }
 
         // Field type references and attribute references
-        collect_field_edges(&m.name, &m.fields, &defined_names, &mut edges);
+        collect_field_edges(
+            &node,
+            m.namespace.as_deref(),
+            &m.fields,
+            &names,
+            ast,
+            &mut edges,
+        );
     }
 
+    let mut edges: Vec<Edge> = edges
+        .into_iter()
+        .map(|(source, target, relation)| {
+            let cross_namespace = match (namespaces.get(&source), namespaces.get(&target)) {
+                (Some(Some(a)), Some(Some(b))) => a != b,
+                _ => false,
+            };
+            Edge {
+                source,
+                target,
+                relation,
+                cross_namespace,
+            }
+        })
+        .collect();
+
     // Deduplicate edges
     edges.sort();
     edges.dedup();
 
-    match format {
-        "dot" => Ok(render_dot(&defined_names, &edges)),
-        _ => Ok(render_mermaid(&defined_names, &edges)),
-    }
+    (defined_names, edges)
 }
 
 fn collect_field_edges(
-    model_name: &str,
+    node: &str,
+    namespace: Option<&str>,
     fields: &[m3l_core::FieldNode],
-    defined_names: &HashSet<String>,
+    names: &NodeNames,
+    ast: &m3l_core::M3lAst,
     edges: &mut Vec<(String, String, String)>,
 ) {
     for field in fields {
         // Field type → model/enum reference
         if let Some(ref ft) = field.field_type {
-            let type_name = resolve_type_name(ft);
-            if defined_names.contains(&type_name) && type_name != model_name {
-                edges.push((model_name.to_string(), type_name, "type_ref".into()));
+            if let Some(target) = names.resolve(ft, namespace) {
+                if target != node {
+                    edges.push((node.to_string(), target, "type_ref".into()));
+                }
             }
         }
 
-        // @reference / @fk attributes → target from first arg
-        for attr in &field.attributes {
-            if attr.name == "reference" || attr.name == "fk" {
-                if let Some(ref args) = attr.args {
-                    if let Some(m3l_core::AttrArgValue::String(target)) = args.first() {
-                        let target_model = target.split('.').next().unwrap_or(target);
-                        if defined_names.contains(target_model) && target_model != model_name {
-                            edges.push((
-                                model_name.to_string(),
-                                target_model.to_string(),
-                                attr.name.clone(),
-                            ));
-                        }
-                    }
+        // @reference / @fk attributes → their resolved target
+        if let Some(target) = ast.reference_target(field) {
+            let attr = field
+                .attributes
+                .iter()
+                .find(|a| a.name == "reference" || a.name == "fk");
+            if let Some(attr) = attr {
+                let target = names.of(target.namespace.as_deref(), &target.name);
+                if target != node {
+                    edges.push((node.to_string(), target, attr.name.clone()));
                 }
             }
         }
 
         // Recurse into nested fields
         if let Some(ref sub_fields) = field.fields {
-            collect_field_edges(model_name, sub_fields, defined_names, edges);
+            collect_field_edges(node, namespace, sub_fields, names, ast, edges);
         }
     }
 }
@@ -99,55 +758,105 @@ fn resolve_type_name(type_str: &str) -> String {
     }
 }
 
-fn render_mermaid(defined_names: &HashSet<String>, edges: &[(String, String, String)]) -> String {
+fn render_mermaid(
+    defined_names: &BTreeSet<String>,
+    edges: &[Edge],
+    kinds: &HashMap<String, NodeKind>,
+) -> String {
     let mut lines = vec!["graph LR".to_string()];
 
     // Nodes — group by whether they have edges
     let mut referenced: HashSet<&str> = HashSet::new();
-    for (src, tgt, _) in edges {
-        referenced.insert(src);
-        referenced.insert(tgt);
+    for edge in edges {
+        referenced.insert(&edge.source);
+        referenced.insert(&edge.target);
     }
 
-    // Add isolated nodes
+    // Isolated models, qualified names (their ids cannot hold the dot), and
+    // every other element with its kind's shape
     for name in defined_names {
-        if !referenced.contains(name.as_str()) {
-            lines.push(format!("    {name}"));
-        }
+        let id = mermaid_id(name);
+        let label = if id == *name {
+            name.clone()
+        } else {
+            format!("\"{name}\"")
+        };
+        let node = match kind_of(kinds, name) {
+            NodeKind::Model if id == *name && referenced.contains(name.as_str()) => continue,
+            NodeKind::Model if id == *name => id,
+            NodeKind::Model => format!("{id}[{label}]"),
+            NodeKind::Interface => format!("{id}([{label}])"),
+            NodeKind::View => format!("{id}[/{label}/]"),
+            NodeKind::Enum => format!("{id}{{{{{label}}}}}"),
+        };
+        lines.push(format!("    {node}"));
     }
 
-    // Edges with labels
-    let edge_labels: HashMap<&str, &str> = HashMap::from([
-        ("inherits", "inherits"),
-        ("type_ref", "has"),
-        ("reference", "ref"),
-        ("fk", "fk"),
-    ]);
+    // Cross-namespace edges are drawn dotted
+    for edge in edges {
+        let label = edge_label(&edge.relation);
+        let arrow = if edge.cross_namespace { "-.->" } else { "-->" };
+        lines.push(format!(
+            "    {} {arrow}|{label}| {}",
+            mermaid_id(&edge.source),
+            mermaid_id(&edge.target)
+        ));
+    }
 
-    for (src, tgt, rel) in edges {
-        let rel_str = rel.as_str();
-        let label = edge_labels.get(rel_str).unwrap_or(&rel_str);
-        lines.push(format!("    {src} -->|{label}| {tgt}"));
+    for (kind, style) in [
+        (NodeKind::Interface, "fill:#e3f0fb,stroke:#3b7dc4"),
+        (NodeKind::View, "fill:#e6f5e6,stroke:#3f9142"),
+        (NodeKind::Enum, "fill:#fdf0dc,stroke:#c98a2b"),
+    ] {
+        let members: Vec<String> = defined_names
+            .iter()
+            .filter(|n| kind_of(kinds, n) == kind)
+            .map(|n| mermaid_id(n))
+            .collect();
+        if !members.is_empty() {
+            lines.push(format!("    classDef {} {style}", kind.name()));
+            lines.push(format!("    class {} {}", members.join(","), kind.name()));
+        }
     }
 
     // Summary comment
     let node_count = defined_names.len();
     let edge_count = edges.len();
     lines.push(format!("%% {node_count} nodes, {edge_count} edges"));
+    let cross_count = edges.iter().filter(|e| e.cross_namespace).count();
+    if cross_count > 0 {
+        lines.push(format!("%% {cross_count} cross-namespace edges"));
+    }
 
     lines.join("\n")
 }
 
-fn render_dot(defined_names: &HashSet<String>, edges: &[(String, String, String)]) -> String {
+/// A Mermaid node id: the node name with the dots of a qualified name
+/// replaced by underscores.
+fn mermaid_id(name: &str) -> String {
+    name.replace('.', "_")
+}
+
+fn render_dot(
+    defined_names: &BTreeSet<String>,
+    edges: &[Edge],
+    kinds: &HashMap<String, NodeKind>,
+) -> String {
     let mut lines = vec![
         "digraph M3L {".to_string(),
         "    rankdir=LR;".to_string(),
         "    node [shape=box, style=filled, fillcolor=lightyellow];".to_string(),
     ];
 
-    // Nodes
+    // Nodes; models use the default style
     for name in defined_names {
-        lines.push(format!("    \"{name}\";"));
+        let style = match kind_of(kinds, name) {
+            NodeKind::Model => "",
+            NodeKind::Interface => " [shape=component, fillcolor=lightblue]",
+            NodeKind::View => " [shape=parallelogram, fillcolor=honeydew]",
+            NodeKind::Enum => " [shape=hexagon, fillcolor=wheat]",
+        };
+        lines.push(format!("    \"{name}\"{style};"));
     }
 
     // Edges
@@ -158,13 +867,305 @@ fn render_dot(defined_names: &HashSet<String>, edges: &[(String, String, String)
         ("fk", "color=green"),
     ]);
 
-    for (src, tgt, rel) in edges {
-        let style = edge_styles.get(rel.as_str()).unwrap_or(&"color=gray");
+    for edge in edges {
+        let style = edge_styles
+            .get(edge.relation.as_str())
+            .unwrap_or(&"color=gray");
+        let cross = if edge.cross_namespace {
+            ", penwidth=2, class=\"cross-namespace\""
+        } else {
+            ""
+        };
         lines.push(format!(
-            "    \"{src}\" -> \"{tgt}\" [label=\"{rel}\", {style}];",
+            "    \"{}\" -> \"{}\" [label=\"{}\", {style}{cross}];",
+            edge.source, edge.target, edge.relation
         ));
     }
 
     lines.push("}".to_string());
     lines.join("\n")
 }
+
+/// A relationship line of an `erDiagram`: `source <markers> target : label`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct ErRelationship {
+    source: String,
+    target: String,
+    markers: &'static str,
+    label: String,
+}
+
+/// Mermaid `erDiagram` of the models (views, interfaces and enums are not
+/// entities). Each entity lists its fields with `PK`, `FK` and `UK` keys.
+/// Relationships come from `@reference` fields, with cardinality from the
+/// field's array, nullable and `@unique` markers, and from `### Relations`
+/// entries that no reference field already covers.
+fn render_er(ast: &m3l_core::M3lAst, focus: Option<&Focus>) -> Result<String, String> {
+    let mut models: Vec<&m3l_core::ModelNode> = ast.models.iter().collect();
+
+    let mut relationships: Vec<ErRelationship> = Vec::new();
+    // (model, field) pairs already drawn from a reference field
+    let mut covered: HashSet<(&str, &str)> = HashSet::new();
+    for model in &models {
+        for field in &model.fields {
+            let Some(target) = reference_target(ast, field) else {
+                continue;
+            };
+            let unique = field.attributes.iter().any(|a| a.name == "unique");
+            let markers = match (field.array, unique, field.nullable) {
+                (true, _, _) => "}o--o{",
+                (false, true, true) => "|o--o|",
+                (false, true, false) => "|o--||",
+                (false, false, true) => "}o--o|",
+                (false, false, false) => "}o--||",
+            };
+            covered.insert((model.name.as_str(), field.name.as_str()));
+            relationships.push(ErRelationship {
+                source: model.name.clone(),
+                target: target.name.clone(),
+                markers,
+                label: field.name.clone(),
+            });
+        }
+    }
+    for model in &models {
+        for entry in &model.sections.relations {
+            if let Some(relationship) = relation_entry(ast, model, entry, &models, &covered) {
+                relationships.push(relationship);
+            }
+        }
+    }
+    relationships.sort();
+    relationships.dedup();
+
+    if let Some(focus) = focus {
+        let reached = focus.neighborhood(
+            models.iter().map(|m| m.name.as_str()),
+            relationships
+                .iter()
+                .map(|r| (r.source.as_str(), r.target.as_str())),
+        )?;
+        models.retain(|m| reached.contains(&m.name));
+        relationships.retain(|r| reached.contains(&r.source) && reached.contains(&r.target));
+    }
+
+    let mut lines = vec!["erDiagram".to_string()];
+    for model in &models {
+        if model.fields.is_empty() {
+            lines.push(format!("    {}", model.name));
+            continue;
+        }
+        lines.push(format!("    {} {{", model.name));
+        for field in &model.fields {
+            lines.push(format!("        {}", er_attribute(field)));
+        }
+        lines.push("    }".to_string());
+    }
+    for r in &relationships {
+        lines.push(format!(
+            "    {} {} {} : \"{}\"",
+            r.source, r.markers, r.target, r.label
+        ));
+    }
+    lines.push(format!(
+        "%% {} entities, {} relationships",
+        models.len(),
+        relationships.len()
+    ));
+    Ok(lines.join("\n"))
+}
+
+/// An entity attribute: `type name KEYS "description"`.
+fn er_attribute(field: &m3l_core::FieldNode) -> String {
+    let mut type_name = field
+        .field_type
+        .as_deref()
+        .map_or_else(|| "any".to_string(), resolve_type_name);
+    if field.array {
+        type_name.push_str("[]");
+    }
+    let mut line = format!("{type_name} {}", field.name);
+    let mut keys = Vec::new();
+    for (attrs, key) in [
+        (&["primary", "pk"][..], "PK"),
+        (&["reference", "fk"][..], "FK"),
+        (&["unique"][..], "UK"),
+    ] {
+        if field
+            .attributes
+            .iter()
+            .any(|a| attrs.contains(&a.name.as_str()))
+        {
+            keys.push(key);
+        }
+    }
+    if !keys.is_empty() {
+        line.push(' ');
+        line.push_str(&keys.join(", "));
+    }
+    if let Some(ref desc) = field.description {
+        line.push_str(&format!(" \"{}\"", desc.replace('"', "'")));
+    }
+    line
+}
+
+/// The model a `@reference`/`@fk` field points at; references to views,
+/// interfaces and flows are not entities.
+fn reference_target<'a>(
+    ast: &'a m3l_core::M3lAst,
+    field: &m3l_core::FieldNode,
+) -> Option<&'a m3l_core::ModelNode> {
+    ast.reference_target(field)
+        .filter(|target| ast.models.iter().any(|m| std::ptr::eq(m, *target)))
+}
+
+/// A `### Relations` entry as a relationship, drawn with its cardinality.
+/// Entries drawn already from a reference field, on either side, are
+/// skipped.
+fn relation_entry(
+    ast: &m3l_core::M3lAst,
+    model: &m3l_core::ModelNode,
+    relation: &m3l_core::RelationDef,
+    models: &[&m3l_core::ModelNode],
+    covered: &HashSet<(&str, &str)>,
+) -> Option<ErRelationship> {
+    let cardinality = relation.cardinality?;
+    let target_text = relation.target.as_deref().unwrap_or(&relation.name);
+    let (target, target_field) = m3l_core::find_reference_target(target_text, models)?;
+    if relation
+        .from_field
+        .as_deref()
+        .is_some_and(|f| covered.contains(&(model.name.as_str(), f)))
+    {
+        return None;
+    }
+    if relation.direction == Some(RelationDirection::From) {
+        // The target's reference field back to this model draws the same line
+        let back = target.fields.iter().any(|f| {
+            target_field.is_none_or(|name| f.name == name)
+                && covered.contains(&(target.name.as_str(), f.name.as_str()))
+                && reference_target(ast, f).is_some_and(|m| m.name == model.name)
+        });
+        if back {
+            return None;
+        }
+    }
+
+    let markers = match (cardinality, relation.optional) {
+        (Cardinality::ManyToMany, _) => "}o--o{",
+        (Cardinality::OneToMany, _) => "||--o{",
+        (Cardinality::OneToOne, true) => "|o--o|",
+        (Cardinality::OneToOne, false) => "|o--||",
+        (Cardinality::ManyToOne, true) => "}o--o|",
+        (Cardinality::ManyToOne, false) => "}o--||",
+    };
+    Some(ErRelationship {
+        source: model.name.clone(),
+        target: target.name.clone(),
+        markers,
+        label: relation.name.clone(),
+    })
+}
+
+/// Short edge label of a relation, as drawn in Mermaid and D2.
+fn edge_label(relation: &str) -> &str {
+    match relation {
+        "type_ref" => "has",
+        "reference" => "ref",
+        other => other,
+    }
+}
+
+/// GraphML for yEd and Gephi: nodes carry their name and element kind,
+/// edges their relation and whether they cross namespaces.
+fn render_graphml(
+    defined_names: &BTreeSet<String>,
+    edges: &[Edge],
+    kinds: &HashMap<String, NodeKind>,
+) -> String {
+    let mut lines = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#.to_string(),
+        r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#.to_string(),
+        r#"  <key id="kind" for="node" attr.name="kind" attr.type="string"/>"#.to_string(),
+        r#"  <key id="relation" for="edge" attr.name="relation" attr.type="string"/>"#.to_string(),
+        r#"  <key id="cross_namespace" for="edge" attr.name="cross_namespace" attr.type="boolean"/>"#
+            .to_string(),
+        r#"  <graph id="M3L" edgedefault="directed">"#.to_string(),
+    ];
+    for name in defined_names {
+        let kind = kind_of(kinds, name).name();
+        let name = xml_escape(name);
+        lines.push(format!(
+            r#"    <node id="{name}"><data key="label">{name}</data><data key="kind">{kind}</data></node>"#
+        ));
+    }
+    for (i, edge) in edges.iter().enumerate() {
+        lines.push(format!(
+            r#"    <edge id="e{i}" source="{}" target="{}"><data key="relation">{}</data><data key="cross_namespace">{}</data></edge>"#,
+            xml_escape(&edge.source),
+            xml_escape(&edge.target),
+            edge.relation,
+            edge.cross_namespace
+        ));
+    }
+    lines.push("  </graph>".to_string());
+    lines.push("</graphml>".to_string());
+    lines.join("\n")
+}
+
+/// D2: inheritance edges are dashed and cross-namespace edges bold, as in
+/// the DOT output.
+fn render_d2(
+    defined_names: &BTreeSet<String>,
+    edges: &[Edge],
+    kinds: &HashMap<String, NodeKind>,
+) -> String {
+    let mut lines = vec!["direction: right".to_string()];
+    for name in defined_names {
+        let style = match kind_of(kinds, name) {
+            NodeKind::Model => "",
+            NodeKind::Interface => r##": {shape: oval; style.fill: "#e3f0fb"}"##,
+            NodeKind::View => r##": {shape: parallelogram; style.fill: "#e6f5e6"}"##,
+            NodeKind::Enum => r##": {shape: hexagon; style.fill: "#fdf0dc"}"##,
+        };
+        lines.push(format!("{}{style}", d2_key(name)));
+    }
+    for edge in edges {
+        let mut styles = Vec::new();
+        if edge.relation == "inherits" {
+            styles.push("style.stroke-dash: 3");
+        }
+        if edge.cross_namespace {
+            styles.push("style.stroke-width: 3");
+        }
+        let style = if styles.is_empty() {
+            String::new()
+        } else {
+            format!(" {{{}}}", styles.join("; "))
+        };
+        lines.push(format!(
+            "{} -> {}: {}{style}",
+            d2_key(&edge.source),
+            d2_key(&edge.target),
+            edge_label(&edge.relation)
+        ));
+    }
+    lines.join("\n")
+}
+
+/// A D2 shape key, quoted unless it is a plain identifier.
+fn d2_key(name: &str) -> String {
+    if name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\\\""))
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+
+use m3l_core::catalogs::STANDARD_ATTRIBUTES;
+use m3l_core::{FieldAttribute, FieldNode, M3lAst};
+use serde::Serialize;
+
+use crate::build_ast;
+
+/// Where an attribute name is defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeStatus {
+    /// Part of the M3L standard attribute catalog.
+    Standard,
+    /// Declared in an `::attribute` registry in the project.
+    Registered,
+    /// Neither standard nor registered.
+    Unknown,
+}
+
+impl AttributeStatus {
+    fn label(self) -> &'static str {
+        match self {
+            AttributeStatus::Standard => "standard",
+            AttributeStatus::Registered => "registered",
+            AttributeStatus::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttributeUsage {
+    pub name: String,
+    pub status: AttributeStatus,
+    pub count: usize,
+    pub files: BTreeSet<String>,
+}
+
+/// Count every attribute on models and fields. Inherited fields are counted
+/// once, where they are declared.
+pub fn collect_usage(ast: &M3lAst) -> Vec<AttributeUsage> {
+    let registered: HashSet<&str> = ast
+        .attribute_registry
+        .iter()
+        .map(|r| r.name.as_str())
+        .collect();
+    let mut usage: BTreeMap<String, AttributeUsage> = BTreeMap::new();
+    let mut record = |attrs: &[FieldAttribute], file: &str| {
+        for attr in attrs {
+            let entry = usage.entry(attr.name.clone()).or_insert_with(|| {
+                let status = if STANDARD_ATTRIBUTES.contains(attr.name.as_str()) {
+                    AttributeStatus::Standard
+                } else if registered.contains(attr.name.as_str()) {
+                    AttributeStatus::Registered
+                } else {
+                    AttributeStatus::Unknown
+                };
+                AttributeUsage {
+                    name: attr.name.clone(),
+                    status,
+                    count: 0,
+                    files: BTreeSet::new(),
+                }
+            });
+            entry.count += 1;
+            entry.files.insert(file.to_string());
+        }
+    };
+
+    let mut seen: HashSet<(&str, usize)> = HashSet::new();
+    for model in ast
+        .models
+        .iter()
+        .chain(ast.interfaces.iter())
+        .chain(ast.views.iter())
+    {
+        record(&model.attributes, &model.source);
+        let mut stack: Vec<&FieldNode> = model.fields.iter().collect();
+        while let Some(field) = stack.pop() {
+            if !seen.insert((field.loc.file.as_str(), field.loc.line)) {
+                continue;
+            }
+            record(&field.attributes, &field.loc.file);
+            if let Some(ref nested) = field.fields {
+                stack.extend(nested);
+            }
+        }
+    }
+
+    let mut usage: Vec<AttributeUsage> = usage.into_values().collect();
+    usage.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    usage
+}
+
+pub fn run_attributes(input_path: &Path, format: &str) -> Result<String, String> {
+    let ast = build_ast(input_path)?;
+    let usage = collect_usage(&ast);
+    let count_status =
+        |status: AttributeStatus| usage.iter().filter(|u| u.status == status).count();
+    let total: usize = usage.iter().map(|u| u.count).sum();
+
+    if format == "json" {
+        return serde_json::to_string_pretty(&serde_json::json!({
+            "attributes": usage,
+            "summary": {
+                "attributes": usage.len(),
+                "uses": total,
+                "standard": count_status(AttributeStatus::Standard),
+                "registered": count_status(AttributeStatus::Registered),
+                "unknown": count_status(AttributeStatus::Unknown),
+            }
+        }))
+        .map_err(|e| format!("JSON serialization error: {e}"));
+    }
+
+    if usage.is_empty() {
+        return Ok("No attributes found.".into());
+    }
+
+    let name_width = usage
+        .iter()
+        .map(|u| u.name.len() + 1)
+        .max()
+        .unwrap_or(0)
+        .max("Attribute".len());
+    let mut lines = vec![format!(
+        "{:<name_width$}  {:>5}  {:<10}  Files",
+        "Attribute", "Uses", "Status"
+    )];
+    for u in &usage {
+        lines.push(format!(
+            "{:<name_width$}  {:>5}  {:<10}  {}",
+            format!("@{}", u.name),
+            u.count,
+            u.status.label(),
+            u.files.iter().cloned().collect::<Vec<_>>().join(", ")
+        ));
+    }
+    let attr_word = if usage.len() == 1 {
+        "attribute"
+    } else {
+        "attributes"
+    };
+    let use_word = if total == 1 { "use" } else { "uses" };
+    lines.push(String::new());
+    lines.push(format!(
+        "{} {attr_word}, {total} {use_word} ({} standard, {} registered, {} unknown)",
+        usage.len(),
+        count_status(AttributeStatus::Standard),
+        count_status(AttributeStatus::Registered),
+        count_status(AttributeStatus::Unknown),
+    ));
+    Ok(lines.join("\n"))
+}
@@ -0,0 +1,220 @@
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use m3l_core::parser::parse_tokens;
+use m3l_core::{lex, parse_string, resolve, validate, ParsedFile, ValidateOptions};
+use serde::Serialize;
+
+use crate::reader::read_m3l_files;
+
+/// Mean time per iteration of each pipeline stage, in milliseconds.
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageTimes {
+    pub lex_ms: f64,
+    pub parse_ms: f64,
+    pub resolve_ms: f64,
+    pub validate_ms: f64,
+    pub total_ms: f64,
+}
+
+/// Timings and throughput for one file, or for the whole input.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchRow {
+    pub file: String,
+    pub bytes: usize,
+    pub models: usize,
+    #[serde(flatten)]
+    pub times: StageTimes,
+    pub mb_per_sec: f64,
+    pub models_per_sec: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchReport {
+    pub iterations: usize,
+    pub files: Vec<BenchRow>,
+    pub total: BenchRow,
+}
+
+/// `m3l bench`: run lex, parse, resolve and validate `iterations` times over
+/// the files at `input_path`. Each file is timed on its own (resolved and
+/// validated alone); the total row times the files as one project, so
+/// cross-file resolution is included there.
+pub fn run_bench(input_path: &Path, iterations: usize, format: &str) -> Result<String, String> {
+    if format != "human" && format != "json" {
+        return Err(format!(
+            "Unknown format \"{format}\" (expected one of: human, json)"
+        ));
+    }
+    if iterations == 0 {
+        return Err("--iterations must be at least 1".into());
+    }
+    let files = read_m3l_files(input_path)?;
+    if files.is_empty() {
+        return Err(format!("No M3L files found in {}", input_path.display()));
+    }
+
+    let rows: Vec<BenchRow> = files
+        .iter()
+        .map(|f| {
+            let times = mean_times(iterations, || time_pipeline(&[(&f.content, &f.path)]));
+            let parsed = parse_string(&f.content, &f.path);
+            row(
+                f.path.clone(),
+                f.content.len(),
+                model_count(&[parsed]),
+                times,
+            )
+        })
+        .collect();
+
+    let sources: Vec<(&str, &str)> = files
+        .iter()
+        .map(|f| (f.content.as_str(), f.path.as_str()))
+        .collect();
+    let times = mean_times(iterations, || time_pipeline(&sources));
+    let parsed: Vec<ParsedFile> = files
+        .iter()
+        .map(|f| parse_string(&f.content, &f.path))
+        .collect();
+    let total = row(
+        "Total".into(),
+        files.iter().map(|f| f.content.len()).sum(),
+        model_count(&parsed),
+        times,
+    );
+
+    let report = BenchReport {
+        iterations,
+        files: rows,
+        total,
+    };
+    if format == "json" {
+        return serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("JSON serialization error: {e}"));
+    }
+    Ok(format_report(&report))
+}
+
+/// Time each stage once over `sources` (content, path), in pipeline order.
+fn time_pipeline(sources: &[(&str, &str)]) -> [Duration; 4] {
+    let mut stages = [Duration::ZERO; 4];
+    let mut parsed = Vec::with_capacity(sources.len());
+    for (content, path) in sources {
+        let start = Instant::now();
+        let tokens = black_box(lex(content, path));
+        stages[0] += start.elapsed();
+
+        let start = Instant::now();
+        parsed.push(black_box(parse_tokens(&tokens, path)));
+        stages[1] += start.elapsed();
+    }
+
+    let start = Instant::now();
+    let ast = black_box(resolve(&parsed, None));
+    stages[2] += start.elapsed();
+
+    let start = Instant::now();
+    black_box(validate(&ast, &ValidateOptions { strict: false }));
+    stages[3] += start.elapsed();
+
+    stages
+}
+
+fn mean_times(iterations: usize, mut run: impl FnMut() -> [Duration; 4]) -> StageTimes {
+    let mut sums = [Duration::ZERO; 4];
+    for _ in 0..iterations {
+        for (sum, stage) in sums.iter_mut().zip(run()) {
+            *sum += stage;
+        }
+    }
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0 / iterations as f64;
+    StageTimes {
+        lex_ms: ms(sums[0]),
+        parse_ms: ms(sums[1]),
+        resolve_ms: ms(sums[2]),
+        validate_ms: ms(sums[3]),
+        total_ms: ms(sums.iter().sum()),
+    }
+}
+
+fn model_count(parsed: &[ParsedFile]) -> usize {
+    parsed
+        .iter()
+        .map(|p| p.models.len() + p.interfaces.len() + p.views.len())
+        .sum()
+}
+
+fn row(file: String, bytes: usize, models: usize, times: StageTimes) -> BenchRow {
+    let secs = times.total_ms / 1000.0;
+    let per_sec = |n: f64| if secs > 0.0 { n / secs } else { 0.0 };
+    BenchRow {
+        file,
+        bytes,
+        models,
+        times,
+        mb_per_sec: per_sec(bytes as f64 / 1_000_000.0),
+        models_per_sec: per_sec(models as f64),
+    }
+}
+
+fn format_report(report: &BenchReport) -> String {
+    let width = report
+        .files
+        .iter()
+        .map(|r| r.file.len())
+        .chain(["File".len()])
+        .max()
+        .unwrap_or(0);
+    let header = format!(
+        "{:<width$}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>8}  {:>10}",
+        "File", "Lex", "Parse", "Resolve", "Validate", "Total", "MB/s", "Models/s"
+    );
+    let line = |r: &BenchRow| {
+        let t = &r.times;
+        format!(
+            "{:<width$}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>8.2}  {:>10.0}",
+            r.file,
+            ms(t.lex_ms),
+            ms(t.parse_ms),
+            ms(t.resolve_ms),
+            ms(t.validate_ms),
+            ms(t.total_ms),
+            r.mb_per_sec,
+            r.models_per_sec
+        )
+    };
+
+    let mut lines = vec![
+        format!(
+            "Mean of {} {} over {} {} ({} bytes, {} models)",
+            report.iterations,
+            if report.iterations == 1 {
+                "iteration"
+            } else {
+                "iterations"
+            },
+            report.files.len(),
+            if report.files.len() == 1 {
+                "file"
+            } else {
+                "files"
+            },
+            report.total.bytes,
+            report.total.models
+        ),
+        String::new(),
+        header,
+    ];
+    lines.extend(report.files.iter().map(line));
+    lines.push(line(&report.total));
+    lines.join("\n")
+}
+
+fn ms(value: f64) -> String {
+    format!("{value:.3}ms")
+}
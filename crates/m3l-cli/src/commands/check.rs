@@ -0,0 +1,198 @@
+use std::path::Path;
+use std::time::Instant;
+
+use m3l_core::{validate, DiagnosticSeverity, M3lAst, ValidateOptions};
+use m3l_lint::{LintConfig, LintFix, LintSeverity, Linter};
+use serde::Serialize;
+
+use crate::commands::lint::{lint_config, sarif_log, sarif_run};
+use crate::commands::snippet::{render_snippets, Snippet, Sources};
+use crate::{build_ast, elapsed_ms};
+
+/// A validation diagnostic or lint finding, in one shape.
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    /// `validate` or `lint`.
+    pub source: &'static str,
+    /// Diagnostic code (`M3L-E009`) or lint rule id.
+    pub code: String,
+    /// `error`, `warning` or `info`.
+    pub severity: &'static str,
+    pub file: String,
+    pub line: usize,
+    pub col: usize,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<LintFix>,
+}
+
+/// `m3l check`: validate and lint one AST built from a single read of the
+/// files. Returns the report and the number of error- and warning-severity
+/// findings from either pass; any error fails the check.
+pub fn run_check(
+    input_path: &Path,
+    strict: bool,
+    format: &str,
+    color: bool,
+) -> Result<(String, usize, usize), String> {
+    let ast = build_ast(input_path)?;
+    let config = lint_config(input_path)?;
+    check_ast(&ast, strict, config, format, color, |_| true)
+}
+
+/// Validate and lint `ast`, reporting only the findings in files for which
+/// `report` returns true; other files count as context only.
+pub(crate) fn check_ast(
+    ast: &M3lAst,
+    strict: bool,
+    config: LintConfig,
+    format: &str,
+    color: bool,
+    report: impl Fn(&str) -> bool,
+) -> Result<(String, usize, usize), String> {
+    let start = Instant::now();
+    let result = validate(ast, &ValidateOptions { strict });
+    let linter = Linter::new(config);
+    let mut lint_results = linter.lint(ast);
+    lint_results.retain(|d| report(&d.file));
+    tracing::info!(
+        errors = result.errors.len(),
+        warnings = result.warnings.len(),
+        findings = lint_results.len(),
+        elapsed_ms = elapsed_ms(start),
+        "validated and linted"
+    );
+
+    let mut findings: Vec<Finding> = result
+        .errors
+        .iter()
+        .chain(result.warnings.iter())
+        .map(|d| Finding {
+            source: "validate",
+            code: d.code.clone(),
+            severity: match d.severity {
+                DiagnosticSeverity::Error => "error",
+                DiagnosticSeverity::Warning => "warning",
+            },
+            file: d.file.clone(),
+            line: d.line,
+            col: d.col,
+            message: d.message.clone(),
+            fix: None,
+        })
+        .chain(lint_results.iter().map(|d| Finding {
+            source: "lint",
+            code: d.rule.clone(),
+            severity: match d.severity {
+                LintSeverity::Error => "error",
+                LintSeverity::Warning => "warning",
+                LintSeverity::Info => "info",
+            },
+            file: d.file.clone(),
+            line: d.line,
+            col: d.col,
+            message: d.message.clone(),
+            fix: d.fix.clone(),
+        }))
+        .filter(|f| report(&f.file))
+        .collect();
+    findings.sort_by(|a, b| (&a.file, a.line, a.col).cmp(&(&b.file, b.line, b.col)));
+
+    let count = |severity: &str| findings.iter().filter(|f| f.severity == severity).count();
+    let (errors, warnings, infos) = (count("error"), count("warning"), count("info"));
+    let file_count = ast.sources.iter().filter(|s| report(s)).count();
+
+    let output = match format {
+        "json" => serde_json::to_string_pretty(&serde_json::json!({
+            "diagnostics": findings,
+            "summary": {
+                "errors": errors,
+                "warnings": warnings,
+                "info": infos,
+                "files": file_count,
+            }
+        }))
+        .map_err(|e| format!("JSON serialization error: {e}"))?,
+        "sarif" => {
+            let validate_run = validate_sarif_run(&findings);
+            serde_json::to_string_pretty(&sarif_log(vec![
+                validate_run,
+                sarif_run(&lint_results, &linter),
+            ]))
+            .map_err(|e| format!("SARIF serialization error: {e}"))?
+        }
+        _ => {
+            let snippets: Vec<Snippet> = findings
+                .iter()
+                .map(|f| Snippet {
+                    severity: f.severity,
+                    code: &f.code,
+                    file: &f.file,
+                    line: f.line,
+                    col: f.col,
+                    message: &f.message,
+                })
+                .collect();
+            let mut lines: Vec<String> = Vec::new();
+            if !snippets.is_empty() {
+                lines.push(render_snippets(&snippets, &mut Sources::default(), color));
+                lines.push(String::new());
+            }
+            let plural = |n: usize, word: &str| {
+                if n == 1 {
+                    format!("1 {word}")
+                } else {
+                    format!("{n} {word}s")
+                }
+            };
+            let mut summary = format!(
+                "{}, {}",
+                plural(errors, "error"),
+                plural(warnings, "warning")
+            );
+            if infos > 0 {
+                summary.push_str(&format!(", {infos} info"));
+            }
+            lines.push(format!("{summary} in {}.", plural(file_count, "file")));
+            lines.join("\n")
+        }
+    };
+    Ok((output, errors, warnings))
+}
+
+/// The `m3l-validate` SARIF run, with a rule entry for each code reported.
+fn validate_sarif_run(findings: &[Finding]) -> serde_json::Value {
+    let validation: Vec<&Finding> = findings.iter().filter(|f| f.source == "validate").collect();
+    let mut codes: Vec<&str> = validation.iter().map(|f| f.code.as_str()).collect();
+    codes.sort_unstable();
+    codes.dedup();
+    let results: Vec<serde_json::Value> = validation
+        .iter()
+        .map(|f| {
+            serde_json::json!({
+                "ruleId": f.code,
+                "level": f.severity,
+                "message": { "text": f.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": f.file },
+                        "region": {
+                            "startLine": f.line,
+                            "startColumn": f.col
+                        }
+                    }
+                }]
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "tool": {
+            "driver": {
+                "name": "m3l-validate",
+                "version": env!("CARGO_PKG_VERSION"),
+                "rules": codes.iter().map(|c| serde_json::json!({ "id": c })).collect::<Vec<_>>()
+            }
+        },
+        "results": results
+    })
+}
@@ -0,0 +1,11 @@
+use clap::Command;
+use clap_complete::{generate, Shell};
+
+/// Render the completion script for `shell`, covering every subcommand and
+/// flag of `cmd`.
+pub fn run_completions(shell: Shell, cmd: &mut Command) -> Result<String, String> {
+    let name = cmd.get_name().to_string();
+    let mut script = Vec::new();
+    generate(shell, cmd, name, &mut script);
+    String::from_utf8(script).map_err(|e| format!("Completion script is not UTF-8: {e}"))
+}
@@ -0,0 +1,850 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use m3l_core::{
+    CheckDef, FieldNode, IndexDef, KeyDef, M3lAst, ModelNode, ModelType, RelationDef,
+    SourceLocation,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::commands::format::{cascade_label, join_label, relation_details, type_label};
+use crate::git::{git, read_blobs};
+use crate::reader::{glob_base, is_glob, select_m3l_paths, M3lFile};
+use crate::{build_ast, resolve_files};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+    /// A field removed and a similar one added in the same model, reported
+    /// as one change.
+    Renamed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityKind {
+    Model,
+    View,
+    Interface,
+    Enum,
+    Field,
+}
+
+impl EntityKind {
+    fn name(self) -> &'static str {
+        match self {
+            EntityKind::Model => "model",
+            EntityKind::View => "view",
+            EntityKind::Interface => "interface",
+            EntityKind::Enum => "enum",
+            EntityKind::Field => "field",
+        }
+    }
+}
+
+/// One property of a modified entity, with its old and new value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PropertyChange {
+    pub property: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// One difference between two schemas.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaChange {
+    pub change: ChangeKind,
+    pub entity: EntityKind,
+    /// `Model` for models, views, interfaces and enums; `Model.field` for
+    /// fields.
+    pub path: String,
+    /// Path on the left side, for `renamed` entries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_path: Option<String>,
+    /// How alike the two fields of a `renamed` entry are, from 0 to 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+    /// What changed, for `modified` and `renamed` entries.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<PropertyChange>,
+    /// Where the entity is defined: in the right schema, or in the left one
+    /// for removals.
+    pub location: SourceLocation,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiffSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+    pub renamed: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiffReport {
+    pub changes: Vec<SchemaChange>,
+    pub summary: DiffSummary,
+}
+
+/// Similarity a removed and an added field need by default to be reported
+/// as a rename: same type, modifiers, default and attributes.
+pub const DEFAULT_RENAME_THRESHOLD: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DiffOptions {
+    /// Minimum similarity for rename detection; `None` turns it off.
+    pub rename_threshold: Option<f64>,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        DiffOptions {
+            rename_threshold: Some(DEFAULT_RENAME_THRESHOLD),
+        }
+    }
+}
+
+impl DiffOptions {
+    pub fn check(&self) -> Result<(), String> {
+        match self.rename_threshold {
+            Some(t) if !(0.0..=1.0).contains(&t) => Err(format!(
+                "--rename-threshold must be between 0 and 1, got {t}"
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The two schemas to compare.
+#[derive(Debug, Clone, Copy)]
+pub enum DiffInputs<'a> {
+    /// Two paths on disk, old then new.
+    Paths(&'a Path, &'a Path),
+    /// A path as of a git revision (old) and in the worktree (new).
+    Against(&'a str, &'a Path),
+}
+
+impl DiffInputs<'_> {
+    /// The old and new ASTs.
+    pub fn load(self) -> Result<(M3lAst, M3lAst), String> {
+        match self {
+            DiffInputs::Paths(left, right) => Ok((build_ast(left)?, build_ast(right)?)),
+            DiffInputs::Against(revision, path) => {
+                Ok((ast_at_revision(revision, path)?, build_ast(path)?))
+            }
+        }
+    }
+}
+
+/// The AST of the M3L files `path` selects, read from git object storage
+/// as of `revision`. Paths are kept as in the worktree, so locations of
+/// removed entities name the files they were in.
+fn ast_at_revision(revision: &str, path: &Path) -> Result<M3lAst, String> {
+    let cwd = std::env::current_dir()
+        .and_then(std::fs::canonicalize)
+        .map_err(|e| format!("Failed to resolve the current directory: {e}"))?;
+    let input = std::fs::canonicalize(path).unwrap_or_else(|_| cwd.join(path));
+    // A glob is listed from its base and filtered after
+    let base = if is_glob(path) {
+        glob_base(&input)
+    } else {
+        input.clone()
+    };
+    // The repository is the one holding the input, wherever it is run from
+    let dir = base.ancestors().find(|p| p.is_dir()).unwrap_or(&cwd);
+    let commit = format!("{revision}^{{commit}}");
+    git(Some(dir), &["rev-parse", "--verify", "--quiet", &commit])
+        .map_err(|_| format!("Unknown git revision: {revision}"))?;
+    // git lists and reads paths from the repository root; they are matched
+    // as absolute paths, so a relative and an absolute input select the
+    // same files
+    let toplevel = git(Some(dir), &["rev-parse", "--show-toplevel"])?;
+    let root = std::fs::canonicalize(toplevel.trim())
+        .map_err(|e| format!("Failed to resolve {}: {e}", toplevel.trim()))?;
+    let pathspec = base.strip_prefix(&root).map_err(|_| {
+        format!(
+            "{} is outside the git repository at {}",
+            path.display(),
+            root.display()
+        )
+    })?;
+    let pathspec = match pathspec.to_string_lossy().replace('\\', "/") {
+        spec if spec.is_empty() => ".".to_string(),
+        spec => spec,
+    };
+    let listed = git(
+        Some(&root),
+        &["ls-tree", "-r", "--name-only", revision, "--", &pathspec],
+    )?;
+    let candidates: Vec<String> = listed
+        .lines()
+        .map(|line| root.join(line).to_string_lossy().into_owned())
+        .collect();
+    let selected = select_m3l_paths(&input, &candidates)?;
+    if selected.is_empty() {
+        return Err(format!(
+            "No M3L files (.m3l, .m3l.md, .md) found at {} in {revision}",
+            path.display()
+        ));
+    }
+    let specs: Vec<String> = selected
+        .iter()
+        .map(|p| {
+            Path::new(p)
+                .strip_prefix(&root)
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_else(|_| p.clone())
+        })
+        .collect();
+    let specs: Vec<&str> = specs.iter().map(String::as_str).collect();
+    let contents = read_blobs(&root, revision, &specs)?;
+    // A relative input reads relative paths from the worktree
+    let files: Vec<M3lFile> = selected
+        .into_iter()
+        .map(|p| match Path::new(&p).strip_prefix(&cwd) {
+            Ok(relative) if path.is_relative() => relative.to_string_lossy().into_owned(),
+            _ => p,
+        })
+        .zip(contents)
+        .map(|(path, content)| M3lFile { path, content })
+        .collect();
+    resolve_files(path, &files)
+}
+
+/// `m3l diff`: compare two schemas.
+pub fn run_diff(inputs: DiffInputs, format: &str, options: &DiffOptions) -> Result<String, String> {
+    if !matches!(format, "human" | "json" | "markdown") {
+        return Err(format!(
+            "Unknown format \"{format}\" (expected one of: human, json, markdown)"
+        ));
+    }
+    options.check()?;
+    let (left_ast, right_ast) = inputs.load()?;
+    let changes = diff_asts(&left_ast, &right_ast, options);
+
+    if format == "json" {
+        let count = |kind| changes.iter().filter(|c| c.change == kind).count();
+        let report = DiffReport {
+            summary: DiffSummary {
+                added: count(ChangeKind::Added),
+                removed: count(ChangeKind::Removed),
+                modified: count(ChangeKind::Modified),
+                renamed: count(ChangeKind::Renamed),
+            },
+            changes,
+        };
+        return serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("JSON serialization error: {e}"));
+    }
+    if format == "markdown" {
+        return Ok(format_markdown(&changes));
+    }
+    Ok(format_changes(&changes))
+}
+
+/// Models, views, interfaces, fields and enums added, removed, modified or
+/// renamed from `left` to `right`, sorted by path.
+pub fn diff_asts(left: &M3lAst, right: &M3lAst, options: &DiffOptions) -> Vec<SchemaChange> {
+    let mut changes = Vec::new();
+
+    let left_models = model_map(left);
+    let right_models = model_map(right);
+    for (name, model) in &right_models {
+        if !left_models.contains_key(name) {
+            changes.push(change(
+                ChangeKind::Added,
+                model_kind(model),
+                name,
+                &model.loc,
+            ));
+        }
+    }
+    for (name, model) in &left_models {
+        if !right_models.contains_key(name) {
+            changes.push(change(
+                ChangeKind::Removed,
+                model_kind(model),
+                name,
+                &model.loc,
+            ));
+        }
+    }
+
+    // Sections, view sources and fields of models present on both sides
+    for (name, left_model) in &left_models {
+        let Some(right_model) = right_models.get(name) else {
+            continue;
+        };
+        let properties = model_changes(left_model, right_model);
+        if !properties.is_empty() {
+            let mut modified = change(
+                ChangeKind::Modified,
+                model_kind(right_model),
+                name,
+                &right_model.loc,
+            );
+            modified.properties = properties;
+            changes.push(modified);
+        }
+        diff_fields(name, left_model, right_model, options, &mut changes);
+    }
+
+    // Enum diff
+    let left_enums: HashMap<&str, _> = left.enums.iter().map(|e| (e.name.as_str(), e)).collect();
+    let right_enums: HashMap<&str, _> = right.enums.iter().map(|e| (e.name.as_str(), e)).collect();
+    for (name, e) in &right_enums {
+        if !left_enums.contains_key(name) {
+            changes.push(change(ChangeKind::Added, EntityKind::Enum, name, &e.loc));
+        }
+    }
+    for (name, e) in &left_enums {
+        if !right_enums.contains_key(name) {
+            changes.push(change(ChangeKind::Removed, EntityKind::Enum, name, &e.loc));
+        }
+    }
+
+    changes.sort_by(|a, b| a.path.cmp(&b.path).then(a.change.cmp(&b.change)));
+    changes
+}
+
+fn diff_fields(
+    name: &str,
+    left: &ModelNode,
+    right: &ModelNode,
+    options: &DiffOptions,
+    changes: &mut Vec<SchemaChange>,
+) {
+    let left_fields = field_map(left);
+    let right_fields = field_map(right);
+    let added: Vec<(usize, &FieldNode)> = right
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| !left_fields.contains_key(f.name.as_str()))
+        .collect();
+    let removed: Vec<(usize, &FieldNode)> = left
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| !right_fields.contains_key(f.name.as_str()))
+        .collect();
+
+    // Pair removed and added fields, most similar first and, among equally
+    // similar pairs, those closest in position
+    let mut candidates = Vec::new();
+    if let Some(threshold) = options.rename_threshold {
+        for (r, (old_index, old)) in removed.iter().enumerate() {
+            for (a, (new_index, new)) in added.iter().enumerate() {
+                let score = similarity(old, new);
+                if score >= threshold {
+                    candidates.push((score, old_index.abs_diff(*new_index), r, a));
+                }
+            }
+        }
+    }
+    candidates.sort_by(|x, y| y.0.total_cmp(&x.0).then(x.1.cmp(&y.1)));
+    let mut renamed_from = vec![None; added.len()];
+    let mut renamed_to = vec![false; removed.len()];
+    for (score, _, r, a) in candidates {
+        if !renamed_to[r] && renamed_from[a].is_none() {
+            renamed_to[r] = true;
+            renamed_from[a] = Some((r, score));
+        }
+    }
+
+    for (a, (_, field)) in added.iter().enumerate() {
+        let path = format!("{name}.{}", field.name);
+        match renamed_from[a] {
+            Some((r, score)) => {
+                let old = removed[r].1;
+                let mut renamed = change(ChangeKind::Renamed, EntityKind::Field, &path, &field.loc);
+                renamed.previous_path = Some(format!("{name}.{}", old.name));
+                renamed.similarity = Some(score);
+                renamed.properties = field_changes(old, field);
+                changes.push(renamed);
+            }
+            None => changes.push(change(
+                ChangeKind::Added,
+                EntityKind::Field,
+                &path,
+                &field.loc,
+            )),
+        }
+    }
+    for (r, (_, field)) in removed.iter().enumerate() {
+        if !renamed_to[r] {
+            let path = format!("{name}.{}", field.name);
+            changes.push(change(
+                ChangeKind::Removed,
+                EntityKind::Field,
+                &path,
+                &field.loc,
+            ));
+        }
+    }
+    for field in &left.fields {
+        let Some(right_field) = right_fields.get(field.name.as_str()) else {
+            continue;
+        };
+        let properties = field_changes(field, right_field);
+        if !properties.is_empty() {
+            let path = format!("{name}.{}", field.name);
+            let mut modified = change(
+                ChangeKind::Modified,
+                EntityKind::Field,
+                &path,
+                &right_field.loc,
+            );
+            modified.properties = properties;
+            changes.push(modified);
+        }
+    }
+}
+
+/// Share of the structural properties (type, modifiers, default and each
+/// attribute) two fields have in common. Labels and descriptions are left
+/// out since they usually change along with the name.
+fn similarity(left: &FieldNode, right: &FieldNode) -> f64 {
+    let properties = field_changes(left, right);
+    let differing = properties
+        .iter()
+        .filter(|p| !matches!(p.property.as_str(), "label" | "labels" | "description"))
+        .count();
+    let attributes: HashSet<&str> = left
+        .attributes
+        .iter()
+        .chain(&right.attributes)
+        .map(|a| a.name.as_str())
+        .collect();
+    // type, nullable, array and default, plus the attributes
+    let total = 4 + attributes.len();
+    (total - differing) as f64 / total as f64
+}
+
+fn model_map(ast: &M3lAst) -> HashMap<&str, &ModelNode> {
+    ast.models
+        .iter()
+        .chain(ast.views.iter())
+        .chain(ast.interfaces.iter())
+        .map(|m| (m.name.as_str(), m))
+        .collect()
+}
+
+fn field_map(model: &ModelNode) -> HashMap<&str, &FieldNode> {
+    model.fields.iter().map(|f| (f.name.as_str(), f)).collect()
+}
+
+fn model_kind(model: &ModelNode) -> EntityKind {
+    match model.model_type {
+        ModelType::View => EntityKind::View,
+        ModelType::Interface => EntityKind::Interface,
+        _ => EntityKind::Model,
+    }
+}
+
+/// Changes to what a model declares besides its fields: the source of a
+/// view, `### Indexes`, `### Keys` and `### Relations` entries, and
+/// model-level `@check`s. Entries are keyed `index:<name>`, `key:<name>`,
+/// `relation:<name>` and `check:<expression>`, like attributes by
+/// `@<name>`.
+fn model_changes(left: &ModelNode, right: &ModelNode) -> Vec<PropertyChange> {
+    let mut properties = Vec::new();
+    let mut compare = |property: &str, before: Value, after: Value| {
+        if before != after {
+            properties.push(PropertyChange {
+                property: property.to_string(),
+                before,
+                after,
+            });
+        }
+    };
+
+    let (left_source, right_source) = (view_source(left), view_source(right));
+    for (property, before) in &left_source {
+        let after = right_source
+            .iter()
+            .find(|(p, _)| p == property)
+            .map_or(Value::Null, |(_, v)| v.clone());
+        compare(property, before.clone(), after);
+    }
+    for (property, after) in &right_source {
+        if !left_source.iter().any(|(p, _)| p == property) {
+            compare(property, Value::Null, after.clone());
+        }
+    }
+
+    for (kind, left_entries, right_entries) in [
+        (
+            "index",
+            entries(&left.sections.indexes, index_entry),
+            entries(&right.sections.indexes, index_entry),
+        ),
+        (
+            "key",
+            entries(&left.sections.keys, key_entry),
+            entries(&right.sections.keys, key_entry),
+        ),
+        (
+            "check",
+            entries(&table_checks(left), check_entry),
+            entries(&table_checks(right), check_entry),
+        ),
+        (
+            "relation",
+            entries(&left.sections.relations, relation_entry),
+            entries(&right.sections.relations, relation_entry),
+        ),
+    ] {
+        for (key, before) in &left_entries {
+            compare(
+                &format!("{kind}:{key}"),
+                before.clone().into(),
+                right_entries.get(key).cloned().into(),
+            );
+        }
+        for (key, after) in &right_entries {
+            if !left_entries.contains_key(key) {
+                compare(&format!("{kind}:{key}"), Value::Null, after.clone().into());
+            }
+        }
+    }
+    properties
+}
+
+/// The parts of a view's definition that shape its rows, in source order;
+/// empty for models.
+fn view_source(model: &ModelNode) -> Vec<(&'static str, Value)> {
+    let mut source = Vec::new();
+    if let Some(def) = &model.source_def {
+        let mut push = |property, value: Option<String>| {
+            if let Some(value) = value {
+                source.push((property, Value::String(value)));
+            }
+        };
+        // A SQL block's tables come from the SQL; a change to them shows
+        // up as a raw_sql change
+        let derived = def.raw_sql.is_some();
+        push("from", def.from.clone().filter(|_| !derived));
+        push(
+            "joins",
+            def.joins
+                .as_ref()
+                .filter(|_| !derived)
+                .map(|joins| joins.iter().map(join_label).collect::<Vec<_>>().join("; ")),
+        );
+        push("where", def.where_clause.clone());
+        push("group_by", def.group_by.as_ref().map(|g| g.join(", ")));
+        push("order_by", def.order_by.clone());
+        push("raw_sql", def.raw_sql.clone());
+    }
+    if let Some(materialized) = model.materialized {
+        source.push(("materialized", materialized.into()));
+    }
+    if let Some(refresh) = &model.refresh {
+        let label = match &refresh.interval {
+            Some(interval) => format!("{} every {interval}", refresh.strategy),
+            None => refresh.strategy.clone(),
+        };
+        source.push(("refresh", label.into()));
+    }
+    source
+}
+
+/// Section entries by key, with a label for each.
+fn entries<T>(values: &[T], entry: fn(&T) -> (String, String)) -> BTreeMap<String, String> {
+    values.iter().map(entry).collect()
+}
+
+/// A named index is keyed by its name; a directive such as
+/// `@unique(a, b)` by its text.
+fn index_entry(index: &IndexDef) -> (String, String) {
+    if let Some(ref raw) = index.raw {
+        let label = raw.trim().trim_start_matches("- ").to_string();
+        return (label.clone(), label);
+    }
+    let name = index.name.clone().unwrap_or_default();
+    let mut label = format!("{name}({})", index.fields.join(", "));
+    if index.unique {
+        label.push_str(" unique");
+    }
+    if let Some(ref method) = index.method {
+        label.push_str(&format!(" using {method}"));
+    }
+    if let Some(ref condition) = index.where_clause {
+        label.push_str(&format!(" where {condition}"));
+    }
+    (name, label)
+}
+
+/// A named key is keyed by its name, an `@natural_key(a, b)` directive by
+/// its text.
+fn key_entry(key: &KeyDef) -> (String, String) {
+    let fields = key.fields.join(", ");
+    match key.name {
+        Some(ref name) => (name.clone(), format!("{name}({fields})")),
+        None => {
+            let label = format!("@natural_key({fields})");
+            (label.clone(), label)
+        }
+    }
+}
+
+/// Checks declared on the model itself; field checks are compared with
+/// the field's `@check` attribute.
+fn table_checks(model: &ModelNode) -> Vec<&CheckDef> {
+    model
+        .sections
+        .checks
+        .iter()
+        .filter(|c| c.field.is_none())
+        .collect()
+}
+
+/// A check is keyed by its expression and labelled with its description.
+fn check_entry(check: &&CheckDef) -> (String, String) {
+    let mut label = check.expression.clone();
+    if let Some(ref description) = check.description {
+        label.push_str(&format!(" \"{description}\""));
+    }
+    (check.expression.clone(), label)
+}
+
+/// A relation is keyed by its name and labelled by its line, with the
+/// nested items after it in parentheses.
+fn relation_entry(relation: &RelationDef) -> (String, String) {
+    let mut label = relation.raw.trim().to_string();
+    let details: Vec<String> = relation_details(relation)
+        .into_iter()
+        .map(|(key, value)| format!("{key}: {value}"))
+        .collect();
+    if !details.is_empty() {
+        label.push_str(&format!(" ({})", details.join(", ")));
+    }
+    let key = if relation.name.is_empty() {
+        label.clone()
+    } else {
+        relation.name.clone()
+    };
+    (key, label)
+}
+
+fn change(kind: ChangeKind, entity: EntityKind, path: &str, loc: &SourceLocation) -> SchemaChange {
+    SchemaChange {
+        change: kind,
+        entity,
+        path: path.to_string(),
+        previous_path: None,
+        similarity: None,
+        properties: Vec::new(),
+        location: loc.clone(),
+    }
+}
+
+/// Property changes of a field present on both sides. Attributes are
+/// compared by name, so a changed argument (`@reference(A)` to
+/// `@reference(B)`) is one change keyed `@reference`.
+fn field_changes(left: &FieldNode, right: &FieldNode) -> Vec<PropertyChange> {
+    let mut properties = Vec::new();
+    let mut compare = |property: &str, before: Value, after: Value| {
+        if before != after {
+            properties.push(PropertyChange {
+                property: property.to_string(),
+                before,
+                after,
+            });
+        }
+    };
+    compare("type", base_type(left).into(), base_type(right).into());
+    compare("nullable", left.nullable.into(), right.nullable.into());
+    compare("array", left.array.into(), right.array.into());
+    compare(
+        "default",
+        left.default_value.clone().into(),
+        right.default_value.clone().into(),
+    );
+    compare(
+        "label",
+        left.label.clone().into(),
+        right.label.clone().into(),
+    );
+    compare(
+        "labels",
+        serde_json::json!(left.labels),
+        serde_json::json!(right.labels),
+    );
+    compare(
+        "description",
+        left.description.clone().into(),
+        right.description.clone().into(),
+    );
+
+    let attribute = |field: &FieldNode, name: &str| {
+        field
+            .attributes
+            .iter()
+            .find(|a| a.name == name)
+            .map(cascade_label)
+    };
+    for attr in &left.attributes {
+        compare(
+            &format!("@{}", attr.name),
+            cascade_label(attr).into(),
+            attribute(right, &attr.name).into(),
+        );
+    }
+    for attr in &right.attributes {
+        if attribute(left, &attr.name).is_none() {
+            compare(
+                &format!("@{}", attr.name),
+                Value::Null,
+                cascade_label(attr).into(),
+            );
+        }
+    }
+    properties
+}
+
+/// The type with its parameters (`string(100)`), without the `?` and `[]`
+/// modifiers, which are compared on their own.
+fn base_type(field: &FieldNode) -> Option<String> {
+    field.field_type.as_ref()?;
+    let label = type_label(field);
+    let label = label.strip_suffix("[]").unwrap_or(&label);
+    Some(label.strip_suffix('?').unwrap_or(label).to_string())
+}
+
+/// One property change in words, with values passed through `code`:
+/// `type: phone → string`, `added @unique`.
+fn describe(change: &PropertyChange, code: impl Fn(&str) -> String) -> String {
+    let (before, after) = (value(&change.before), value(&change.after));
+    // Attributes and section entries are added and removed as a whole
+    let kind = match change.property.split_once(':') {
+        _ if change.property.starts_with('@') => "",
+        Some((kind, _)) => kind,
+        None => return format!("{}: {} → {}", change.property, code(&before), code(&after)),
+    };
+    let kind = if kind.is_empty() {
+        String::new()
+    } else {
+        format!("{kind} ")
+    };
+    match (&change.before, &change.after) {
+        (Value::Null, _) => format!("added {kind}{}", code(&after)),
+        (_, Value::Null) => format!("removed {kind}{}", code(&before)),
+        _ => format!("{kind}{} → {}", code(&before), code(&after)),
+    }
+}
+
+/// The `+`/`-`/`~` listing with a summary line.
+fn format_changes(changes: &[SchemaChange]) -> String {
+    if changes.is_empty() {
+        return "No differences found.".into();
+    }
+    let mut lines: Vec<String> = changes.iter().map(format_change).collect();
+    lines.sort();
+    lines.push(format!("\n{}", summary(changes)));
+    lines.join("\n")
+}
+
+/// `2 added, 1 removed, 1 modified`, with renames only when there are any.
+fn summary(changes: &[SchemaChange]) -> String {
+    let count = |kind| changes.iter().filter(|c| c.change == kind).count();
+    let mut summary = format!(
+        "{} added, {} removed, {} modified",
+        count(ChangeKind::Added),
+        count(ChangeKind::Removed),
+        count(ChangeKind::Modified)
+    );
+    let renamed = count(ChangeKind::Renamed);
+    if renamed > 0 {
+        summary.push_str(&format!(", {renamed} renamed"));
+    }
+    summary
+}
+
+fn format_change(change: &SchemaChange) -> String {
+    let sign = match change.change {
+        ChangeKind::Added => "+",
+        ChangeKind::Removed => "-",
+        ChangeKind::Modified | ChangeKind::Renamed => "~",
+    };
+    let path = match &change.previous_path {
+        Some(previous) => format!("{previous} → {} (possible rename)", change.path),
+        None => change.path.clone(),
+    };
+    let entity = match change.entity {
+        EntityKind::Field => String::new(),
+        entity => format!("{} ", entity.name()),
+    };
+    if change.properties.is_empty() {
+        return format!("{sign} {entity}{path}");
+    }
+    let details: Vec<String> = change
+        .properties
+        .iter()
+        .map(|p| describe(p, str::to_string))
+        .collect();
+    format!("{sign} {entity}{path}: {}", details.join(", "))
+}
+
+/// A changelog section with one heading per model, view, interface or enum and a
+/// bullet per change, for release notes and pull requests.
+fn format_markdown(changes: &[SchemaChange]) -> String {
+    let mut lines = vec!["## Schema changes".to_string(), String::new()];
+    if changes.is_empty() {
+        lines.push("No changes.".into());
+        return lines.join("\n");
+    }
+    lines.push(format!("{}.", summary(changes)));
+
+    let mut groups: BTreeMap<&str, Vec<&SchemaChange>> = BTreeMap::new();
+    for change in changes {
+        let owner = change.path.split('.').next().unwrap_or(&change.path);
+        groups.entry(owner).or_default().push(change);
+    }
+    for (owner, changes) in groups {
+        lines.push(String::new());
+        lines.push(format!("### {owner}"));
+        lines.push(String::new());
+        for change in changes {
+            let verb = match change.change {
+                ChangeKind::Added => "Added",
+                ChangeKind::Removed => "Removed",
+                ChangeKind::Modified => "Changed",
+                ChangeKind::Renamed => "Renamed",
+            };
+            let previous = change
+                .previous_path
+                .as_deref()
+                .and_then(|p| p.split_once('.'));
+            match (change.path.split_once('.'), previous) {
+                (Some((_, field)), Some((_, old))) => lines.push(format!(
+                    "- {verb} field `{old}` to `{field}` (possible rename)"
+                )),
+                (Some((_, field)), None) => lines.push(format!("- {verb} field `{field}`")),
+                (None, _) => lines.push(format!("- {verb} {}", change.entity.name())),
+            }
+            lines.extend(
+                change
+                    .properties
+                    .iter()
+                    .map(|p| format!("  - {}", describe(p, |v| format!("`{v}`")))),
+            );
+        }
+    }
+    lines.join("\n")
+}
+
+/// A property value on one line: multi-line SQL is joined with spaces.
+fn value(value: &Value) -> String {
+    match value {
+        Value::Null => "none".into(),
+        Value::String(s) => s.split_whitespace().collect::<Vec<_>>().join(" "),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,782 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use m3l_core::{Deprecation, DocTags, EnumNode, FieldNode, M3lAst, ModelNode, TypeMember};
+
+use crate::build_ast;
+use crate::commands::analyze::{analyze_ast, GraphOptions};
+use crate::commands::format::{attribute_label, seed_columns, type_label, type_member_label};
+
+const STYLE: &str = r#"body { margin: 0; font: 15px/1.5 system-ui, sans-serif; color: #1f2328; }
+nav { padding: 12px 32px; background: #f6f8fa; border-bottom: 1px solid #d0d7de; }
+nav a { font-weight: 600; }
+main { max-width: 1100px; padding: 16px 32px 48px; }
+a { color: #0969da; text-decoration: none; }
+a:hover { text-decoration: underline; }
+h1 .kind { font-size: 14px; font-weight: 500; color: #59636e; border: 1px solid #d0d7de; border-radius: 12px; padding: 2px 10px; vertical-align: middle; }
+h2 { margin-top: 32px; border-bottom: 1px solid #d0d7de; padding-bottom: 4px; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; vertical-align: top; padding: 6px 10px; border-bottom: 1px solid #eaeef2; }
+th { background: #f6f8fa; }
+code, pre { font: 13px ui-monospace, monospace; }
+pre { background: #f6f8fa; padding: 12px; overflow-x: auto; }
+.muted { color: #59636e; }
+.inherited td { background: #fbfcfd; }
+.deprecated { color: #9a6700; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 4px 16px; }
+dt { color: #59636e; }
+dd { margin: 0; }
+"#;
+
+/// Renders `pre.mermaid` blocks; without network access the diagram source
+/// stays readable as text.
+const MERMAID_SCRIPT: &str = r#"<script type="module">
+import mermaid from "https://cdn.jsdelivr.net/npm/mermaid@10/dist/mermaid.esm.min.mjs";
+mermaid.initialize({ startOnLoad: true });
+</script>"#;
+
+/// Write a static HTML site for the schema at `input_path` into `out_dir`:
+/// an index with the relationship diagram, one page per model, interface
+/// and view, and one per enum.
+pub fn run_docs(input_path: &Path, out_dir: &Path) -> Result<String, String> {
+    let ast = build_ast(input_path)?;
+    let site = Site::new(&ast);
+
+    let mut pages = vec![("index.html".to_string(), site.index_page()?)];
+    for model in site.elements() {
+        pages.push((
+            site.pages[model.name.as_str()].clone(),
+            site.model_page(model),
+        ));
+    }
+    for en in &ast.enums {
+        pages.push((site.pages[en.name.as_str()].clone(), site.enum_page(en)));
+    }
+
+    for dir in [
+        out_dir.to_path_buf(),
+        out_dir.join("models"),
+        out_dir.join("enums"),
+    ] {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+    let write = |path: &Path, content: &str| {
+        fs::write(path, content).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    };
+    write(&out_dir.join("style.css"), STYLE)?;
+    for (page, html) in &pages {
+        write(&out_dir.join(page), html)?;
+    }
+    Ok(format!(
+        "Wrote {} pages to {}",
+        pages.len(),
+        out_dir.display()
+    ))
+}
+
+struct Site<'a> {
+    ast: &'a M3lAst,
+    /// Page of each model, interface, view and enum, relative to the root.
+    pages: HashMap<&'a str, String>,
+    /// Which element declares each line, to attribute inherited fields.
+    declared_at: HashMap<&'a str, Vec<(usize, &'a str)>>,
+    /// `(model, field)` pairs that reference each model or use each enum.
+    used_by: HashMap<&'a str, Vec<(&'a str, String)>>,
+}
+
+impl<'a> Site<'a> {
+    fn new(ast: &'a M3lAst) -> Self {
+        let mut site = Site {
+            ast,
+            pages: HashMap::new(),
+            declared_at: HashMap::new(),
+            used_by: HashMap::new(),
+        };
+        for model in site.elements() {
+            site.pages
+                .insert(&model.name, format!("models/{}.html", model.name));
+            site.declared_at
+                .entry(&model.source)
+                .or_default()
+                .push((model.line, &model.name));
+        }
+        for en in &ast.enums {
+            site.pages
+                .insert(&en.name, format!("enums/{}.html", en.name));
+        }
+        for lines in site.declared_at.values_mut() {
+            lines.sort();
+        }
+
+        for model in site.elements() {
+            let mut stack: Vec<(String, &FieldNode)> =
+                model.fields.iter().map(|f| (f.name.clone(), f)).collect();
+            while let Some((path, field)) = stack.pop() {
+                if let Some(en) = field
+                    .field_type
+                    .as_deref()
+                    .and_then(|t| ast.enums.iter().find(|e| e.name == base_type(t)))
+                {
+                    site.used_by
+                        .entry(&en.name)
+                        .or_default()
+                        .push((&model.name, path.clone()));
+                }
+                if let Some(target) = ast.reference_target(field) {
+                    if target.name != model.name {
+                        site.used_by
+                            .entry(&target.name)
+                            .or_default()
+                            .push((&model.name, path.clone()));
+                    }
+                }
+                if let Some(ref nested) = field.fields {
+                    stack.extend(nested.iter().map(|f| (format!("{path}.{}", f.name), f)));
+                }
+            }
+        }
+        for used in site.used_by.values_mut() {
+            used.sort();
+            used.dedup();
+        }
+        site
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &'a ModelNode> {
+        self.ast
+            .models
+            .iter()
+            .chain(self.ast.interfaces.iter())
+            .chain(self.ast.views.iter())
+    }
+
+    fn element(&self, name: &str) -> Option<&'a ModelNode> {
+        self.elements().find(|m| m.name == name)
+    }
+
+    fn title(&self) -> &'a str {
+        self.ast.project.name.as_deref().unwrap_or("Schema")
+    }
+
+    /// `text` linked to the page of `name`, if it has one.
+    fn link(&self, name: &str, text: &str, prefix: &str) -> String {
+        match self.pages.get(name) {
+            Some(page) => format!("<a href=\"{prefix}{page}\">{}</a>", esc(text)),
+            None => esc(text),
+        }
+    }
+
+    fn page(&self, title: &str, prefix: &str, body: &str, scripts: &str) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+             <title>{}</title>\n<link rel=\"stylesheet\" href=\"{prefix}style.css\">\n</head>\n\
+             <body>\n<nav><a href=\"{prefix}index.html\">{}</a></nav>\n<main>\n{body}</main>\n\
+             {scripts}</body>\n</html>\n",
+            esc(title),
+            esc(self.title())
+        )
+    }
+
+    fn index_page(&self) -> Result<String, String> {
+        let ast = self.ast;
+        let mut body = format!("<h1>{}</h1>\n", esc(self.title()));
+        if let Some(ref version) = ast.project.version {
+            let _ = writeln!(body, "<p class=\"muted\">Version {}</p>", esc(version));
+        }
+        let _ = writeln!(
+            body,
+            "<p>{}, {}, {}, {} from {}.</p>",
+            count(ast.models.len(), "model"),
+            count(ast.interfaces.len(), "interface"),
+            count(ast.views.len(), "view"),
+            count(ast.enums.len(), "enum"),
+            count(ast.sources.len(), "file")
+        );
+
+        body.push_str("<h2>Relationships</h2>\n");
+        let _ = writeln!(
+            body,
+            "<pre class=\"mermaid\">\n{}\n</pre>",
+            esc(&analyze_ast(ast, "mermaid", &GraphOptions::default())?)
+        );
+
+        for (heading, elements) in [
+            ("Models", &ast.models),
+            ("Interfaces", &ast.interfaces),
+            ("Views", &ast.views),
+        ] {
+            if elements.is_empty() {
+                continue;
+            }
+            let _ = writeln!(
+                body,
+                "<h2>{heading}</h2>\n<table>\n<tr><th>Name</th><th>Namespace</th><th>Fields</th><th>Description</th></tr>"
+            );
+            for model in elements {
+                let _ = writeln!(
+                    body,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    self.link(&model.name, &model.name, ""),
+                    esc(model.namespace.as_deref().unwrap_or("")),
+                    model.fields.len(),
+                    esc(model.description.as_deref().unwrap_or(""))
+                );
+            }
+            body.push_str("</table>\n");
+        }
+
+        if !ast.enums.is_empty() {
+            body.push_str(
+                "<h2>Enums</h2>\n<table>\n<tr><th>Name</th><th>Namespace</th><th>Values</th><th>Description</th></tr>\n",
+            );
+            for en in &ast.enums {
+                let _ = writeln!(
+                    body,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    self.link(&en.name, &en.name, ""),
+                    esc(en.namespace.as_deref().unwrap_or("")),
+                    en.values.len(),
+                    esc(en.description.as_deref().unwrap_or(""))
+                );
+            }
+            body.push_str("</table>\n");
+        }
+
+        Ok(self.page(self.title(), "", &body, MERMAID_SCRIPT))
+    }
+
+    fn model_page(&self, model: &ModelNode) -> String {
+        let p = "../";
+        let kind = serde_json::to_value(&model.model_type)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let mut body = format!(
+            "<h1>{} <span class=\"kind\">{kind}</span></h1>\n",
+            esc(&model.name)
+        );
+        if let Some(ref desc) = model.description {
+            let _ = writeln!(body, "<p>{}</p>", esc(desc));
+        }
+        body.push_str(&self.deprecation_notice(model.deprecated.as_ref(), p));
+
+        body.push_str("<dl>\n");
+        self.doc_rows(&mut body, model.doc.as_ref(), p);
+        if let Some(ref ns) = model.namespace {
+            let _ = writeln!(body, "<dt>Namespace</dt><dd>{}</dd>", esc(ns));
+        }
+        let _ = writeln!(
+            body,
+            "<dt>Defined in</dt><dd><code>{}:{}</code></dd>",
+            esc(&model.source),
+            model.line
+        );
+        if !model.attributes.is_empty() {
+            let attrs: Vec<String> = model
+                .attributes
+                .iter()
+                .map(|a| format!("<code>{}</code>", esc(&attribute_label(a))))
+                .collect();
+            let _ = writeln!(body, "<dt>Attributes</dt><dd>{}</dd>", attrs.join(" "));
+        }
+        let chains = self.inheritance_chains(model);
+        if !chains.is_empty() {
+            let rendered: Vec<String> = chains
+                .iter()
+                .map(|chain| {
+                    chain[1..]
+                        .iter()
+                        .map(|name| self.link(name, name, p))
+                        .collect::<Vec<_>>()
+                        .join(" → ")
+                })
+                .collect();
+            let _ = writeln!(body, "<dt>Inherits</dt><dd>{}</dd>", rendered.join("<br>"));
+        }
+        let children: Vec<String> = self
+            .elements()
+            .filter(|m| m.inherits.contains(&model.name))
+            .map(|m| self.link(&m.name, &m.name, p))
+            .collect();
+        if !children.is_empty() {
+            let _ = writeln!(body, "<dt>Extended by</dt><dd>{}</dd>", children.join(", "));
+        }
+        body.push_str("</dl>\n");
+        examples(&mut body, model.doc.as_ref());
+
+        if let Some(ref source) = model.source_def {
+            body.push_str("<h2>Source</h2>\n<dl>\n");
+            if let Some(ref from) = source.from {
+                let _ = writeln!(body, "<dt>From</dt><dd>{}</dd>", self.link(from, from, p));
+            }
+            for (label, value) in [
+                ("Where", &source.where_clause),
+                ("Order by", &source.order_by),
+            ] {
+                if let Some(value) = value {
+                    let _ = writeln!(body, "<dt>{label}</dt><dd><code>{}</code></dd>", esc(value));
+                }
+            }
+            if let Some(ref group_by) = source.group_by {
+                let _ = writeln!(
+                    body,
+                    "<dt>Group by</dt><dd><code>{}</code></dd>",
+                    esc(&group_by.join(", "))
+                );
+            }
+            body.push_str("</dl>\n");
+            if let Some(ref sql) = source.raw_sql {
+                let _ = writeln!(body, "<pre>{}</pre>", esc(sql));
+            }
+        }
+
+        if !model.fields.is_empty() {
+            body.push_str(
+                "<h2>Fields</h2>\n<table>\n<tr><th>Field</th><th>Type</th><th>Default</th><th>Attributes</th><th>Description</th></tr>\n",
+            );
+            self.field_rows(&mut body, model, &model.fields, "");
+            body.push_str("</table>\n");
+        }
+
+        if !model.sections.keys.is_empty() {
+            body.push_str("<h2>Keys</h2>\n<ul>\n");
+            for key in &model.sections.keys {
+                let name = key.name.as_deref().unwrap_or("natural key");
+                let _ = writeln!(
+                    body,
+                    "<li>{}: <code>{}</code></li>",
+                    esc(name),
+                    esc(&key.fields.join(", "))
+                );
+            }
+            body.push_str("</ul>\n");
+        }
+
+        // Field checks are listed with the field's attributes
+        let checks: Vec<_> = model
+            .sections
+            .checks
+            .iter()
+            .filter(|c| c.field.is_none())
+            .collect();
+        if !checks.is_empty() {
+            body.push_str("<h2>Checks</h2>\n<ul>\n");
+            for check in checks {
+                let _ = write!(body, "<li><code>{}</code>", esc(&check.expression));
+                if let Some(ref description) = check.description {
+                    let _ = write!(body, ": {}", esc(description));
+                }
+                body.push_str("</li>\n");
+            }
+            body.push_str("</ul>\n");
+        }
+
+        if !model.sections.endpoints.is_empty() {
+            body.push_str("<h2>Endpoints</h2>\n<ul>\n");
+            for endpoint in &model.sections.endpoints {
+                let _ = write!(
+                    body,
+                    "<li><code>{} {}</code>",
+                    esc(&endpoint.method),
+                    esc(&endpoint.path)
+                );
+                if let Some(ref description) = endpoint.description {
+                    let _ = write!(body, ": {}", esc(description));
+                }
+                body.push_str("</li>\n");
+            }
+            body.push_str("</ul>\n");
+        }
+
+        if !model.sections.seeds.is_empty() {
+            let columns = seed_columns(model);
+            body.push_str("<h2>Seed data</h2>\n<table>\n<tr><th></th>");
+            for column in &columns {
+                let _ = write!(body, "<th>{}</th>", esc(column));
+            }
+            body.push_str("</tr>\n");
+            for row in &model.sections.seeds {
+                let _ = write!(
+                    body,
+                    "<tr><td>{}</td>",
+                    esc(row.name.as_deref().unwrap_or_default())
+                );
+                for column in &columns {
+                    let cell = match row.values.get(column) {
+                        Some(serde_json::Value::String(s)) => s.clone(),
+                        Some(value) => value.to_string(),
+                        None => String::new(),
+                    };
+                    let _ = write!(body, "<td>{}</td>", esc(&cell));
+                }
+                body.push_str("</tr>\n");
+            }
+            body.push_str("</table>\n");
+        }
+
+        let relations = self.relation_rows(model);
+        if !relations.is_empty() {
+            body.push_str(
+                "<h2>Relations</h2>\n<table>\n<tr><th>Kind</th><th>Field</th><th>Target</th></tr>\n",
+            );
+            for (kind, field, target) in relations {
+                let _ = writeln!(
+                    body,
+                    "<tr><td>{kind}</td><td><code>{}</code></td><td>{target}</td></tr>",
+                    esc(&field)
+                );
+            }
+            body.push_str("</table>\n");
+        }
+
+        self.used_by_section(&mut body, &model.name, "Referenced by");
+        self.page(&model.name, p, &body, "")
+    }
+
+    fn field_rows(&self, body: &mut String, model: &ModelNode, fields: &[FieldNode], parent: &str) {
+        let p = "../";
+        for field in fields {
+            let path = format!("{parent}{}", field.name);
+            let owner = self
+                .declared_at
+                .get(field.loc.file.as_str())
+                .and_then(|lines| lines.iter().rev().find(|(l, _)| *l <= field.loc.line))
+                .map(|(_, name)| *name)
+                .filter(|owner| *owner != model.name && parent.is_empty());
+
+            // Each union member and tuple component links to its own page
+            let members = |members: &[TypeMember]| -> Vec<String> {
+                members
+                    .iter()
+                    .map(|m| self.link(base_type(&m.member_type), &type_member_label(m), p))
+                    .collect()
+            };
+            let suffix = format!(
+                "{}{}",
+                if field.nullable { "?" } else { "" },
+                if field.array { "[]" } else { "" }
+            );
+            let type_cell = match (&field.union, &field.tuple, field.field_type.as_deref()) {
+                (Some(union), _, _) => format!("{}{suffix}", members(union).join(" | ")),
+                (None, Some(tuple), _) => format!("({}){suffix}", members(tuple).join(", ")),
+                (None, None, Some(t)) => self.link(base_type(t), &type_label(field), p),
+                (None, None, None) => String::new(),
+            };
+            let attrs: Vec<String> = field
+                .attributes
+                .iter()
+                .map(|a| {
+                    let label = attribute_label(a);
+                    let target = (a.name == "reference" || a.name == "fk")
+                        .then(|| self.ast.reference_target(field))
+                        .flatten();
+                    match target {
+                        Some(target) => {
+                            format!("<code>{}</code>", self.link(&target.name, &label, p))
+                        }
+                        None => format!("<code>{}</code>", esc(&label)),
+                    }
+                })
+                .collect();
+            let mut description = esc(field.description.as_deref().unwrap_or(""));
+            if let Some(ref deprecation) = field.deprecated {
+                if !description.is_empty() {
+                    description.push(' ');
+                }
+                let text = self.deprecation_text(deprecation, p);
+                let note = match deprecation.since {
+                    Some(ref since) => format!("Deprecated since {}", esc(since)),
+                    None => "Deprecated".to_string(),
+                };
+                if text.is_empty() {
+                    let _ = write!(description, "<span class=\"deprecated\">{note}.</span>");
+                } else {
+                    let _ = write!(
+                        description,
+                        "<span class=\"deprecated\">{note}: {text}</span>"
+                    );
+                }
+            }
+            if let Some(ref doc) = field.doc {
+                for (class, text) in self.field_doc(doc, p) {
+                    if !description.is_empty() {
+                        description.push(' ');
+                    }
+                    let _ = write!(description, "<span class=\"{class}\">{text}</span>");
+                }
+                for example in &doc.examples {
+                    let _ = write!(description, "<pre>{}</pre>", esc(example));
+                }
+            }
+            if let Some(owner) = owner {
+                if !description.is_empty() {
+                    description.push(' ');
+                }
+                let _ = write!(
+                    description,
+                    "<span class=\"muted\">(inherited from {})</span>",
+                    self.link(owner, owner, p)
+                );
+            }
+            let _ = writeln!(
+                body,
+                "<tr{}><td><code>{}</code></td><td><code>{type_cell}</code></td><td><code>{}</code></td><td>{}</td><td>{description}</td></tr>",
+                if owner.is_some() { " class=\"inherited\"" } else { "" },
+                esc(&path),
+                esc(field.default_value.as_deref().unwrap_or("")),
+                attrs.join(" ")
+            );
+            if let Some(ref nested) = field.fields {
+                self.field_rows(body, model, nested, &format!("{path}."));
+            }
+        }
+    }
+
+    /// Outgoing relations: references, lookups, rollups and Relations entries.
+    /// `Since` and `See also` rows of a definition list.
+    fn doc_rows(&self, body: &mut String, doc: Option<&DocTags>, p: &str) {
+        let Some(doc) = doc else {
+            return;
+        };
+        if let Some(ref since) = doc.since {
+            let _ = writeln!(body, "<dt>Since</dt><dd>{}</dd>", esc(since));
+        }
+        if !doc.see.is_empty() {
+            let _ = writeln!(body, "<dt>See also</dt><dd>{}</dd>", self.see_links(doc, p));
+        }
+    }
+
+    /// `@see` targets, linked to their pages or, for URLs, to themselves.
+    fn see_links(&self, doc: &DocTags, p: &str) -> String {
+        doc.see
+            .iter()
+            .map(|see| {
+                if see.starts_with("http://") || see.starts_with("https://") {
+                    format!("<a href=\"{0}\">{0}</a>", esc(see))
+                } else {
+                    let name = see.split('.').next().unwrap_or(see);
+                    self.link(name, see, p)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The `@since` and `@see` tags of a field as `(class, html)` notes for
+    /// its description cell.
+    fn field_doc(&self, doc: &DocTags, p: &str) -> Vec<(&'static str, String)> {
+        let mut notes = Vec::new();
+        if let Some(ref since) = doc.since {
+            notes.push(("muted", format!("Since {}.", esc(since))));
+        }
+        if !doc.see.is_empty() {
+            notes.push(("muted", format!("See {}.", self.see_links(doc, p))));
+        }
+        notes
+    }
+
+    /// A paragraph saying the element is deprecated, if it is.
+    fn deprecation_notice(&self, deprecation: Option<&Deprecation>, p: &str) -> String {
+        let Some(deprecation) = deprecation else {
+            return String::new();
+        };
+        let since = deprecation
+            .since
+            .as_ref()
+            .map(|since| format!(" since {}", esc(since)))
+            .unwrap_or_default();
+        format!(
+            "<p class=\"deprecated\"><strong>Deprecated{since}.</strong> {}</p>\n",
+            self.deprecation_text(deprecation, p)
+        )
+    }
+
+    /// The deprecation message with its replacement linked, or a pointer to
+    /// the replacement when there is no message.
+    fn deprecation_text(&self, deprecation: &Deprecation, p: &str) -> String {
+        let link = |replacement: &str| {
+            let name = replacement.split('.').next().unwrap_or(replacement);
+            self.link(name, replacement, p)
+        };
+        match (&deprecation.message, &deprecation.replacement) {
+            (Some(message), Some(replacement)) => {
+                esc(message).replacen(&esc(replacement), &link(replacement), 1)
+            }
+            (Some(message), None) => esc(message),
+            (None, Some(replacement)) => format!("Use {}.", link(replacement)),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn relation_rows(&self, model: &ModelNode) -> Vec<(&'static str, String, String)> {
+        let p = "../";
+        let mut rows = Vec::new();
+        for field in &model.fields {
+            if let Some(target) = self.ast.reference_target(field) {
+                rows.push((
+                    "reference",
+                    field.name.clone(),
+                    self.link(&target.name, &target.name, p),
+                ));
+            }
+            if let Some(ref lookup) = field.lookup {
+                rows.push((
+                    "lookup",
+                    field.name.clone(),
+                    format!("<code>{}</code>", esc(&lookup.path)),
+                ));
+            }
+            if let Some(ref rollup) = field.rollup {
+                let target = rollup.target.split('.').next().unwrap_or(&rollup.target);
+                rows.push((
+                    "rollup",
+                    field.name.clone(),
+                    format!(
+                        "{} <code>{}</code>",
+                        self.link(target, target, p),
+                        esc(&rollup.aggregate)
+                    ),
+                ));
+            }
+        }
+        for relation in &model.sections.relations {
+            let cell = match relation.target {
+                Some(ref target) => {
+                    let name = target.split('.').next().unwrap_or(target);
+                    self.link(name, target, p)
+                }
+                None => format!("<code>{}</code>", esc(&relation.raw)),
+            };
+            rows.push(("relation", relation.name.clone(), cell));
+        }
+        rows
+    }
+
+    fn used_by_section(&self, body: &mut String, name: &str, heading: &str) {
+        let Some(used) = self.used_by.get(name) else {
+            return;
+        };
+        let _ = writeln!(body, "<h2>{heading}</h2>\n<ul>");
+        for (model, field) in used {
+            let _ = writeln!(
+                body,
+                "<li>{}<code>.{}</code></li>",
+                self.link(model, model, "../"),
+                esc(field)
+            );
+        }
+        body.push_str("</ul>\n");
+    }
+
+    fn enum_page(&self, en: &EnumNode) -> String {
+        let mut body = format!(
+            "<h1>{} <span class=\"kind\">enum</span></h1>\n",
+            esc(&en.name)
+        );
+        if let Some(ref desc) = en.description {
+            let _ = writeln!(body, "<p>{}</p>", esc(desc));
+        }
+        body.push_str(&self.deprecation_notice(en.deprecated.as_ref(), "../"));
+        body.push_str("<dl>\n");
+        self.doc_rows(&mut body, en.doc.as_ref(), "../");
+        if let Some(ref ns) = en.namespace {
+            let _ = writeln!(body, "<dt>Namespace</dt><dd>{}</dd>", esc(ns));
+        }
+        let _ = writeln!(
+            body,
+            "<dt>Defined in</dt><dd><code>{}:{}</code></dd>\n</dl>",
+            esc(&en.source),
+            en.line
+        );
+        examples(&mut body, en.doc.as_ref());
+
+        body.push_str(
+            "<h2>Values</h2>\n<table>\n<tr><th>Value</th><th>Stored as</th><th>Description</th></tr>\n",
+        );
+        for value in &en.values {
+            let stored = match value.value {
+                Some(serde_json::Value::String(ref s)) => s.clone(),
+                Some(ref v) => v.to_string(),
+                None => String::new(),
+            };
+            let _ = writeln!(
+                body,
+                "<tr><td><code>{}</code></td><td><code>{}</code></td><td>{}</td></tr>",
+                esc(&value.name),
+                esc(&stored),
+                esc(value.description.as_deref().unwrap_or(""))
+            );
+        }
+        body.push_str("</table>\n");
+
+        self.used_by_section(&mut body, &en.name, "Used by");
+        self.page(&en.name, "../", &body, "")
+    }
+
+    /// Every path from `model` up through its ancestors, e.g.
+    /// `[Post, BaseModel, Entity]`, starting with the model itself. Cycles
+    /// stop at the repeated name.
+    fn inheritance_chains(&self, model: &'a ModelNode) -> Vec<Vec<&'a str>> {
+        let mut chains = Vec::new();
+        let mut stack = vec![vec![model.name.as_str()]];
+        while let Some(chain) = stack.pop() {
+            let last = chain[chain.len() - 1];
+            let parents: Vec<&str> = self
+                .element(last)
+                .map(|m| {
+                    m.inherits
+                        .iter()
+                        .map(String::as_str)
+                        .filter(|p| !chain.contains(p))
+                        .collect()
+                })
+                .unwrap_or_default();
+            if parents.is_empty() {
+                if chain.len() > 1 {
+                    chains.push(chain);
+                }
+                continue;
+            }
+            for parent in parents.into_iter().rev() {
+                let mut next = chain.clone();
+                next.push(parent);
+                stack.push(next);
+            }
+        }
+        chains
+    }
+}
+
+/// `1 model`, `3 models`.
+fn examples(body: &mut String, doc: Option<&DocTags>) {
+    let Some(doc) = doc.filter(|d| !d.examples.is_empty()) else {
+        return;
+    };
+    body.push_str("<h2>Examples</h2>\n");
+    for example in &doc.examples {
+        let _ = writeln!(body, "<pre>{}</pre>", esc(example));
+    }
+}
+
+fn count(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{n} {noun}s")
+    }
+}
+
+/// `Auth.User` → `User`.
+fn base_type(type_name: &str) -> &str {
+    type_name.rsplit('.').next().unwrap_or(type_name)
+}
+
+fn esc(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,79 @@
+use m3l_core::{explain, CodeDoc, CodeKind, CODES};
+
+/// `m3l explain`: the reference entry for one validation code or lint rule,
+/// or a list of all of them when `code` is `None`.
+pub fn run_explain(code: Option<&str>, format: &str) -> Result<String, String> {
+    if format != "human" && format != "json" {
+        return Err(format!(
+            "Unknown format \"{format}\" (expected one of: human, json)"
+        ));
+    }
+    let Some(code) = code else {
+        if format == "json" {
+            return serde_json::to_string_pretty(CODES)
+                .map_err(|e| format!("JSON serialization error: {e}"));
+        }
+        let width = CODES.iter().map(|d| d.code.len()).max().unwrap_or(0);
+        let mut lines: Vec<String> = CODES
+            .iter()
+            .map(|d| format!("{:<width$}  {:<7}  {}", d.code, kind_label(d), d.title))
+            .collect();
+        lines.push(String::new());
+        lines.push("Run `m3l explain <code>` for a description and examples.".into());
+        return Ok(lines.join("\n"));
+    };
+
+    let doc = explain(code).ok_or_else(|| {
+        format!("Unknown code \"{code}\"; run `m3l explain` to list every code and lint rule")
+    })?;
+    if format == "json" {
+        return serde_json::to_string_pretty(doc)
+            .map_err(|e| format!("JSON serialization error: {e}"));
+    }
+
+    let mut lines = vec![format!("{}: {} ({})", doc.code, doc.title, kind_label(doc))];
+    if doc.strict {
+        lines.push("Reported by `m3l validate --strict` only.".into());
+    }
+    lines.push(String::new());
+    lines.extend(wrap(doc.description, 80));
+    for (heading, example) in [("Bad:", doc.bad), ("Fixed:", doc.good)] {
+        lines.push(String::new());
+        lines.push(heading.into());
+        lines.extend(example.lines().map(|l| {
+            if l.is_empty() {
+                String::new()
+            } else {
+                format!("    {l}")
+            }
+        }));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn kind_label(doc: &CodeDoc) -> &'static str {
+    match doc.kind {
+        CodeKind::Error => "error",
+        CodeKind::Warning => "warning",
+        CodeKind::Lint => "lint",
+    }
+}
+
+/// Words of `text` in lines of at most `width` characters.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,169 @@
+use m3l_core::{validate, M3lAst, ValidateOptions};
+
+/// Page shell; `__TITLE__` and `__DATA__` are filled in by [`render_explorer`].
+const TEMPLATE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>__TITLE__ — M3L AST</title>
+<style>
+body { margin: 0; font: 14px/1.5 system-ui, sans-serif; color: #1f2328; }
+header { position: sticky; top: 0; padding: 10px 24px; background: #f6f8fa; border-bottom: 1px solid #d0d7de; display: flex; gap: 16px; align-items: center; }
+header h1 { font-size: 16px; margin: 0; }
+header input { margin-left: auto; padding: 4px 8px; width: 260px; }
+main { padding: 8px 24px 48px; }
+h2 { font-size: 15px; margin: 24px 0 8px; }
+details { margin: 2px 0; }
+details > summary { cursor: pointer; }
+.element > summary { padding: 4px 0; }
+.element > div { margin: 4px 0 12px 18px; }
+.muted { color: #59636e; }
+.name { font-weight: 600; }
+code, .json { font: 12px ui-monospace, monospace; }
+table { border-collapse: collapse; }
+th, td { text-align: left; vertical-align: top; padding: 3px 10px 3px 0; border-bottom: 1px solid #eaeef2; }
+.error { color: #cf222e; }
+.warning { color: #9a6700; }
+.json { margin-left: 14px; }
+.key { color: #8250df; }
+.hidden { display: none; }
+</style>
+</head>
+<body>
+<header><h1 id="title"></h1><span id="summary" class="muted"></span><input id="filter" type="search" placeholder="Filter by name"></header>
+<main id="root"></main>
+<script type="application/json" id="m3l-data">__DATA__</script>
+<script>
+const data = JSON.parse(document.getElementById("m3l-data").textContent);
+const ast = data.ast;
+const root = document.getElementById("root");
+
+function el(tag, attrs, ...children) {
+  const node = document.createElement(tag);
+  for (const [k, v] of Object.entries(attrs || {})) node.setAttribute(k, v);
+  for (const child of children) {
+    if (child != null) node.append(child instanceof Node ? child : String(child));
+  }
+  return node;
+}
+
+function jsonTree(value, label) {
+  const head = label == null ? "" : label;
+  if (value === null || typeof value !== "object") {
+    return el("div", { class: "json" }, el("span", { class: "key" }, head ? head + ": " : ""), JSON.stringify(value));
+  }
+  const entries = Array.isArray(value) ? value.map((v, i) => [i, v]) : Object.entries(value);
+  const brackets = Array.isArray(value) ? "[" + entries.length + "]" : "{" + entries.length + "}";
+  const details = el("details", { class: "json" }, el("summary", {}, el("span", { class: "key" }, head), " ", el("span", { class: "muted" }, brackets)));
+  details.addEventListener("toggle", () => {
+    if (details.open && details.children.length === 1) {
+      for (const [k, v] of entries) details.append(jsonTree(v, String(k)));
+    }
+  });
+  return details;
+}
+
+function typeOf(field) {
+  let t = field.type || "";
+  if (field.params) t += "(" + field.params.join(", ") + ")";
+  if (field.nullable) t += "?";
+  if (field.array) t += "[]";
+  return t;
+}
+
+function attrs(list) {
+  return (list || []).map(a => "@" + a.name + (a.args ? "(" + a.args.join(", ") + ")" : "")).join(" ");
+}
+
+function fieldRows(table, fields, prefix) {
+  for (const f of fields || []) {
+    table.append(el("tr", {}, el("td", {}, el("code", {}, prefix + f.name)), el("td", {}, el("code", {}, typeOf(f))),
+      el("td", {}, el("code", {}, attrs(f.attributes))), el("td", {}, f.description || "")));
+    fieldRows(table, f.fields, prefix + f.name + ".");
+  }
+}
+
+function element(node, extra) {
+  const summary = el("summary", {}, el("span", { class: "name" }, node.name));
+  if (node.inherits && node.inherits.length) summary.append(" : " + node.inherits.join(", "));
+  if (node.description) summary.append(" ", el("span", { class: "muted" }, "— " + node.description));
+  const body = el("div", {}, el("div", { class: "muted" }, node.source + ":" + node.line));
+  if (extra) body.append(extra);
+  const fields = node.fields || [];
+  if (fields.length) {
+    const table = el("table", {}, el("tr", {}, el("th", {}, "Field"), el("th", {}, "Type"), el("th", {}, "Attributes"), el("th", {}, "Description")));
+    fieldRows(table, fields, "");
+    body.append(table);
+  }
+  body.append(jsonTree(node, "JSON"));
+  const details = el("details", { class: "element", "data-name": node.name.toLowerCase() }, summary, body);
+  return details;
+}
+
+function section(title, items, render) {
+  if (!items || !items.length) return;
+  root.append(el("h2", {}, title + " (" + items.length + ")"));
+  for (const item of items) root.append(render(item));
+}
+
+const name = (ast.project && ast.project.name) || "M3L schema";
+document.getElementById("title").textContent = name;
+const errors = data.diagnostics.filter(d => d.severity === "error").length;
+document.getElementById("summary").textContent =
+  ast.models.length + " models, " + ast.enums.length + " enums, " + errors + " errors, " +
+  (data.diagnostics.length - errors) + " warnings · parser " + ast.parserVersion;
+
+if (data.diagnostics.length) {
+  const list = el("details", { open: "" }, el("summary", { class: "name" }, "Diagnostics (" + data.diagnostics.length + ")"));
+  for (const d of data.diagnostics) {
+    list.append(el("div", { class: d.severity }, d.file + ":" + d.line + ":" + d.col + " ", el("code", {}, d.code), " " + d.message));
+  }
+  root.append(list);
+}
+section("Models", ast.models, m => element(m));
+section("Interfaces", ast.interfaces, m => element(m));
+section("Views", ast.views, m => element(m));
+section("Enums", ast.enums, e => {
+  const table = el("table", {}, el("tr", {}, el("th", {}, "Value"), el("th", {}, "Description")));
+  for (const v of e.values) table.append(el("tr", {}, el("td", {}, el("code", {}, v.name)), el("td", {}, v.description || "")));
+  return element(Object.assign({}, e, { fields: [] }), table);
+});
+section("Flows", ast.flows, m => element(m));
+root.append(el("h2", {}, "Full AST"), jsonTree(ast, "ast"));
+
+document.getElementById("filter").addEventListener("input", e => {
+  const q = e.target.value.trim().toLowerCase();
+  for (const node of root.querySelectorAll(".element")) {
+    const match = !q || node.dataset.name.includes(q) || node.textContent.toLowerCase().includes(q);
+    node.classList.toggle("hidden", !match);
+  }
+});
+</script>
+</body>
+</html>
+"##;
+
+/// A standalone HTML page embedding the AST and its diagnostics, with a
+/// small script to browse models, fields and diagnostics. It needs no
+/// network access.
+pub fn render_explorer(ast: &M3lAst) -> Result<String, String> {
+    let result = validate(ast, &ValidateOptions { strict: false });
+    let diagnostics: Vec<_> = result.errors.iter().chain(result.warnings.iter()).collect();
+    let data = serde_json::json!({ "ast": ast, "diagnostics": diagnostics });
+    let json =
+        serde_json::to_string(&data).map_err(|e| format!("JSON serialization error: {e}"))?;
+    // `<` only appears inside JSON strings; escaping it keeps `</script>`
+    // in a description from closing the data block
+    let json = json.replace('<', "\\u003c");
+    let title = ast
+        .project
+        .name
+        .as_deref()
+        .unwrap_or("M3L schema")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;");
+    Ok(TEMPLATE
+        .replace("__TITLE__", &title)
+        .replace("__DATA__", &json))
+}
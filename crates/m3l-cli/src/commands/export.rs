@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use m3l_core::{FieldNode, M3lAst};
+use rust_xlsxwriter::{Format, Workbook};
+
+use crate::build_ast;
+use crate::commands::format::{attribute_label, type_label};
+
+const HEADER: [&str; 7] = [
+    "Model",
+    "Field",
+    "Type",
+    "Nullable",
+    "Default",
+    "Description",
+    "Attributes",
+];
+
+/// One row per field of every model and view, inherited fields included so
+/// each table is complete. Nested fields use dotted names (`profile.bio`).
+pub fn dictionary_rows(ast: &M3lAst) -> Vec<[String; 7]> {
+    let mut rows = Vec::new();
+    for model in ast.models.iter().chain(ast.views.iter()) {
+        push_rows(&mut rows, &model.name, &model.fields, "");
+    }
+    rows
+}
+
+fn push_rows(rows: &mut Vec<[String; 7]>, model: &str, fields: &[FieldNode], parent: &str) {
+    for field in fields {
+        let name = format!("{parent}{}", field.name);
+        let mut field_type = type_label(field);
+        if field.nullable {
+            // Shown in its own column; type names and params never contain `?`
+            field_type = field_type.replacen('?', "", 1);
+        }
+        let attributes: Vec<String> = field.attributes.iter().map(attribute_label).collect();
+        rows.push([
+            model.to_string(),
+            name.clone(),
+            field_type,
+            if field.nullable { "yes" } else { "no" }.to_string(),
+            field.default_value.clone().unwrap_or_default(),
+            field.description.clone().unwrap_or_default(),
+            attributes.join(" "),
+        ]);
+        if let Some(ref nested) = field.fields {
+            push_rows(rows, model, nested, &format!("{name}."));
+        }
+    }
+}
+
+/// `m3l export dictionary`: the data dictionary as CSV (stdout or a file) or
+/// an XLSX workbook (a file).
+pub fn run_export_dictionary(
+    input_path: &Path,
+    format: &str,
+    output: Option<&Path>,
+) -> Result<String, String> {
+    let ast = build_ast(input_path)?;
+    let rows = dictionary_rows(&ast);
+
+    match format {
+        "csv" => {
+            let csv = to_csv(&rows)?;
+            match output {
+                Some(path) => {
+                    std::fs::write(path, csv)
+                        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+                    Ok(format!("Written to {}", path.display()))
+                }
+                None => Ok(csv.trim_end().to_string()),
+            }
+        }
+        "xlsx" => {
+            let path = output.ok_or("XLSX output is binary; choose a file with -o <file>")?;
+            write_xlsx(&rows, path)?;
+            Ok(format!("Written to {}", path.display()))
+        }
+        other => Err(format!(
+            "Unknown format \"{other}\" (expected one of: csv, xlsx)"
+        )),
+    }
+}
+
+fn to_csv(rows: &[[String; 7]]) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let error = |e: csv::Error| format!("CSV error: {e}");
+    writer.write_record(HEADER).map_err(error)?;
+    for row in rows {
+        writer.write_record(row).map_err(error)?;
+    }
+    let bytes = writer.into_inner().map_err(|e| format!("CSV error: {e}"))?;
+    String::from_utf8(bytes).map_err(|e| format!("CSV error: {e}"))
+}
+
+fn write_xlsx(rows: &[[String; 7]], path: &Path) -> Result<(), String> {
+    let error = |e: rust_xlsxwriter::XlsxError| format!("XLSX error: {e}");
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Dictionary").map_err(error)?;
+    let bold = Format::new().set_bold();
+    for (col, title) in HEADER.iter().enumerate() {
+        sheet
+            .write_string_with_format(0, col as u16, *title, &bold)
+            .map_err(error)?;
+    }
+    for (i, row) in rows.iter().enumerate() {
+        for (col, value) in row.iter().enumerate() {
+            sheet
+                .write_string(i as u32 + 1, col as u16, value)
+                .map_err(error)?;
+        }
+    }
+    sheet.set_freeze_panes(1, 0).map_err(error)?;
+    sheet
+        .autofilter(0, 0, rows.len() as u32, HEADER.len() as u16 - 1)
+        .map_err(error)?;
+    sheet.autofit();
+    workbook
+        .save(path)
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
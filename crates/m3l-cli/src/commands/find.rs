@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use m3l_core::{fuzzy_score, M3lAst, ModelType};
+use serde::Serialize;
+
+use crate::build_ast;
+
+/// Description matches rank below every name match.
+const DESCRIPTION_SCORE: i64 = -1000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Match {
+    pub kind: &'static str,
+    pub name: String,
+    pub file: String,
+    pub line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Model that declares an inherited field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherited_from: Option<String>,
+    /// Whether the query matched the name or only the description.
+    pub matched: &'static str,
+    #[serde(skip)]
+    score: i64,
+}
+
+fn model_kind(model_type: &ModelType) -> &'static str {
+    match model_type {
+        ModelType::Interface => "interface",
+        ModelType::View => "view",
+        ModelType::Flow => "flow",
+        _ => "model",
+    }
+}
+
+/// Score `name` fuzzily, falling back to a case-insensitive substring match
+/// on the description.
+fn score(query: &str, names: &[&str], description: Option<&str>) -> Option<(i64, &'static str)> {
+    if let Some(best) = names.iter().filter_map(|n| fuzzy_score(query, n)).max() {
+        return Some((best, "name"));
+    }
+    let description = description?.to_lowercase();
+    description
+        .contains(&query.to_lowercase())
+        .then_some((DESCRIPTION_SCORE, "description"))
+}
+
+/// Fuzzy-search element, field, enum and enum value names (and, failing
+/// that, descriptions) across the resolved AST, best matches first.
+pub fn find(ast: &M3lAst, query: &str, kind: Option<&str>) -> Vec<Match> {
+    // Which model declares each line, to attribute inherited fields
+    let mut declared_at: HashMap<&str, Vec<(usize, &str)>> = HashMap::new();
+    for model in ast
+        .models
+        .iter()
+        .chain(ast.interfaces.iter())
+        .chain(ast.views.iter())
+    {
+        declared_at
+            .entry(model.source.as_str())
+            .or_default()
+            .push((model.line, model.name.as_str()));
+    }
+    for models in declared_at.values_mut() {
+        models.sort();
+    }
+    let declaring_model = |file: &str, line: usize| {
+        declared_at
+            .get(file)?
+            .iter()
+            .rev()
+            .find(|(l, _)| *l <= line)
+            .map(|(_, name)| *name)
+    };
+
+    let wants = |k: &str| kind.is_none_or(|kind| kind == k);
+    let mut matches = Vec::new();
+
+    for model in ast
+        .models
+        .iter()
+        .chain(ast.interfaces.iter())
+        .chain(ast.views.iter())
+        .chain(ast.flows.iter())
+    {
+        let kind_label = model_kind(&model.model_type);
+        if wants(kind_label) {
+            if let Some((score, matched)) =
+                score(query, &[&model.name], model.description.as_deref())
+            {
+                matches.push(Match {
+                    kind: kind_label,
+                    name: model.name.clone(),
+                    file: model.source.clone(),
+                    line: model.line,
+                    description: model.description.clone(),
+                    inherited_from: None,
+                    matched,
+                    score,
+                });
+            }
+        }
+
+        if !wants("field") {
+            continue;
+        }
+        for field in &model.fields {
+            let qualified = format!("{}.{}", model.name, field.name);
+            let Some((score, matched)) = score(
+                query,
+                &[&field.name, &qualified],
+                field.description.as_deref(),
+            ) else {
+                continue;
+            };
+            let inherited_from = declaring_model(&field.loc.file, field.loc.line)
+                .filter(|owner| *owner != model.name)
+                .map(str::to_string);
+            matches.push(Match {
+                kind: "field",
+                name: qualified,
+                file: field.loc.file.clone(),
+                line: field.loc.line,
+                description: field.description.clone(),
+                inherited_from,
+                matched,
+                score,
+            });
+        }
+    }
+
+    for en in &ast.enums {
+        if wants("enum") {
+            if let Some((score, matched)) = score(query, &[&en.name], en.description.as_deref()) {
+                matches.push(Match {
+                    kind: "enum",
+                    name: en.name.clone(),
+                    file: en.source.clone(),
+                    line: en.line,
+                    description: en.description.clone(),
+                    inherited_from: None,
+                    matched,
+                    score,
+                });
+            }
+        }
+        if !wants("value") {
+            continue;
+        }
+        for value in &en.values {
+            let qualified = format!("{}.{}", en.name, value.name);
+            let Some((score, matched)) = score(
+                query,
+                &[&value.name, &qualified],
+                value.description.as_deref(),
+            ) else {
+                continue;
+            };
+            let (file, line) = match value.loc {
+                Some(ref loc) => (loc.file.clone(), loc.line),
+                None => (en.source.clone(), en.line),
+            };
+            matches.push(Match {
+                kind: "value",
+                name: qualified,
+                file,
+                line,
+                description: value.description.clone(),
+                inherited_from: None,
+                matched,
+                score,
+            });
+        }
+    }
+
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.file.cmp(&b.file))
+    });
+    matches
+}
+
+pub fn run_find(
+    input_path: &Path,
+    query: &str,
+    kind: Option<&str>,
+    limit: usize,
+    format: &str,
+) -> Result<String, String> {
+    const KINDS: &[&str] = &[
+        "model",
+        "interface",
+        "view",
+        "flow",
+        "field",
+        "enum",
+        "value",
+    ];
+    if let Some(kind) = kind {
+        if !KINDS.contains(&kind) {
+            return Err(format!(
+                "Unknown kind \"{kind}\" (expected one of: {})",
+                KINDS.join(", ")
+            ));
+        }
+    }
+
+    let ast = build_ast(input_path)?;
+    let mut matches = find(&ast, query, kind);
+    let total = matches.len();
+    matches.truncate(limit);
+
+    if format == "json" {
+        return serde_json::to_string_pretty(&serde_json::json!({
+            "matches": matches,
+            "summary": { "shown": matches.len(), "total": total },
+        }))
+        .map_err(|e| format!("JSON serialization error: {e}"));
+    }
+
+    if matches.is_empty() {
+        return Ok(format!("No matches for \"{query}\"."));
+    }
+
+    let locations: Vec<String> = matches
+        .iter()
+        .map(|m| format!("{}:{}", m.file, m.line))
+        .collect();
+    let width = locations.iter().map(String::len).max().unwrap_or(0);
+    let mut lines: Vec<String> = matches
+        .iter()
+        .zip(&locations)
+        .map(|(m, location)| {
+            let mut line = format!("{location:<width$}  {:<9} {}", m.kind, m.name);
+            if let Some(ref parent) = m.inherited_from {
+                line.push_str(&format!(" (inherited from {parent})"));
+            }
+            if m.matched == "description" {
+                if let Some(ref description) = m.description {
+                    line.push_str(&format!(" — {description}"));
+                }
+            }
+            line
+        })
+        .collect();
+    if total > matches.len() {
+        lines.push(format!(
+            "... {} more (use --limit to show more)",
+            total - matches.len()
+        ));
+    }
+    Ok(lines.join("\n"))
+}
@@ -1,44 +1,237 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
 use std::path::Path;
 
-use crate::build_ast;
+use clap::ValueEnum;
+use m3l_core::lexer::parse_attr_args_string;
+use m3l_core::{parse_string, resolve, M3lAst};
+use serde::Deserialize;
+use serde_json::Value;
 
-pub fn run_format(input_path: &Path) -> Result<String, String> {
-    let ast = build_ast(input_path)?;
-    Ok(format_ast(&ast))
+use crate::build_source_ast;
+use crate::reader::{find_project_config, read_m3l_files};
+
+/// Order of fields within a model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FieldOrder {
+    #[default]
+    Declaration,
+    Alphabetical,
+    /// Primary-key fields first, the rest in declaration order.
+    PkFirst,
+}
+
+/// Order of models within each section (models, interfaces, views).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ModelOrder {
+    #[default]
+    Declaration,
+    Name,
+    /// Parents and referenced models before the models that depend on them.
+    Dependency,
+}
+
+/// Formatter style, from the `format` section of the project config and
+/// command-line flags.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FormatOptions {
+    pub sort_fields: FieldOrder,
+    pub sort_models: ModelOrder,
+    /// Align field types and attributes into columns.
+    pub align: bool,
 }
 
-fn format_ast(ast: &m3l_core::M3lAst) -> String {
+/// Format the combined schema at `input_path`, as written: project type
+/// aliases and default parents are left to the config, as with `--write`.
+/// With `verify`, the output is parsed back and must describe the same
+/// schema.
+pub fn run_format(
+    input_path: &Path,
+    overrides: &FormatOverrides,
+    verify: bool,
+) -> Result<String, String> {
+    let ast = build_source_ast(input_path)?;
+    let options = resolve_options(input_path, overrides)?;
+    let formatted = format_ast(&ast, &options);
+    if verify {
+        check_roundtrip(&ast, &formatted, &options)
+            .map_err(|e| format!("Formatting would change the schema at {e}"))?;
+    }
+    Ok(formatted)
+}
+
+/// Format a single file's content with `options`, round-trip checked as
+/// with `--verify`.
+pub fn format_content(
+    content: &str,
+    filename: &str,
+    options: &FormatOptions,
+) -> Result<String, String> {
+    let ast = resolve(&[parse_string(content, filename)], None);
+    let mut formatted = format_ast(&ast, options);
+    formatted.push('\n');
+    check_roundtrip(&ast, &formatted, options)
+        .map_err(|e| format!("Formatting would change the schema at {e}"))?;
+    Ok(formatted)
+}
+
+/// Format each file in place. Every file is round-trip checked before any
+/// is written, so a divergence leaves the whole tree untouched.
+pub fn run_format_write(input_path: &Path, overrides: &FormatOverrides) -> Result<String, String> {
+    let files = read_m3l_files(input_path)?;
+    if files.is_empty() {
+        return Err(format!(
+            "No M3L files (.m3l, .m3l.md, .md) found at: {}",
+            input_path.display()
+        ));
+    }
+    let options = resolve_options(input_path, overrides)?;
+
+    let mut changed: Vec<(&str, String)> = Vec::new();
+    for file in &files {
+        let ast = resolve(&[parse_string(&file.content, &file.path)], None);
+        let mut formatted = format_ast(&ast, &options);
+        formatted.push('\n');
+        check_roundtrip(&ast, &formatted, &options).map_err(|e| {
+            format!(
+                "Formatting {} would change the schema at {e}; no files were written",
+                file.path
+            )
+        })?;
+        if formatted != file.content {
+            changed.push((&file.path, formatted));
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (path, formatted) in &changed {
+        fs::write(path, formatted).map_err(|e| format!("Failed to write {path}: {e}"))?;
+        lines.push(format!("Formatted {path}"));
+    }
+    let unchanged = files.len() - changed.len();
+    lines.push(format!(
+        "{} formatted, {unchanged} unchanged",
+        match changed.len() {
+            1 => "1 file".to_string(),
+            n => format!("{n} files"),
+        }
+    ));
+    Ok(lines.join("\n"))
+}
+
+/// Configured style (from the nearest project config) with flags applied on
+/// top.
+fn resolve_options(
+    input_path: &Path,
+    overrides: &FormatOverrides,
+) -> Result<FormatOptions, String> {
+    let mut options = find_project_config(input_path)?
+        .and_then(|p| p.config.format)
+        .unwrap_or_default();
+    overrides.apply(&mut options);
+    Ok(options)
+}
+
+/// Command-line flags; each one set replaces the configured value.
+#[derive(Debug, Default)]
+pub struct FormatOverrides {
+    pub sort_fields: Option<FieldOrder>,
+    pub sort_models: Option<ModelOrder>,
+    pub align: Option<bool>,
+}
+
+impl FormatOverrides {
+    fn apply(&self, options: &mut FormatOptions) {
+        if let Some(order) = self.sort_fields {
+            options.sort_fields = order;
+        }
+        if let Some(order) = self.sort_models {
+            options.sort_models = order;
+        }
+        if let Some(align) = self.align {
+            options.align = align;
+        }
+    }
+}
+
+fn format_ast(ast: &M3lAst, options: &FormatOptions) -> String {
     let mut lines: Vec<String> = Vec::new();
 
-    // Namespace
-    if let Some(ref name) = ast.project.name {
-        lines.push(format!("# Namespace: {name}"));
+    // Frontmatter, as written
+    let frontmatter = ast.file_metadata.first();
+    if let Some(meta) = frontmatter {
+        lines.push("---".into());
+        lines.extend(meta.raw.lines().map(str::to_string));
+        lines.push("---".into());
         lines.push(String::new());
     }
 
+    // Version pragma, as written
+    if let Some(version) = ast.m3l_versions.first() {
+        lines.push(version.raw.trim().to_string());
+        lines.push(String::new());
+    }
+
+    // A `# Namespace:` header wherever the next element's namespace differs
+    // from the one in effect, which the frontmatter may set
+    let mut current = frontmatter.and_then(|m| m.namespace.clone());
+    let mut namespace = |lines: &mut Vec<String>, namespace: &Option<String>| {
+        if let Some(name) = namespace {
+            if current.as_ref() != Some(name) {
+                lines.push(format!("# Namespace: {name}"));
+                lines.push(String::new());
+                current = Some(name.clone());
+            }
+        }
+    };
+
     // Models
-    for model in &ast.models {
-        format_model(&mut lines, model);
+    for model in order_models(&ast.models, options.sort_models) {
+        namespace(&mut lines, &model.namespace);
+        format_model(&mut lines, model, ast, options);
+        lines.push(String::new());
+    }
+
+    // Additions to models, after the models
+    for block in &ast.extend_blocks {
+        namespace(&mut lines, &block.namespace);
+        lines.push(format!("## extend {}", block.name));
+        push_description(
+            &mut lines,
+            "",
+            block.description.as_deref(),
+            block.doc.as_ref(),
+        );
+        format_fields(&mut lines, &block.fields, 0, options);
+        format_sections(&mut lines, block);
         lines.push(String::new());
     }
 
     // Enums
     for e in &ast.enums {
+        namespace(&mut lines, &e.namespace);
         format_enum(&mut lines, e);
         lines.push(String::new());
     }
 
     // Interfaces
-    for iface in &ast.interfaces {
-        lines.push(format!("## {} ::interface", iface.name));
-        format_model_body(&mut lines, iface);
+    for iface in order_models(&ast.interfaces, options.sort_models) {
+        namespace(&mut lines, &iface.namespace);
+        lines.push(format!(
+            "## {} ::interface",
+            labelled_name(&generic_name(iface), iface.label.as_deref(), &iface.labels)
+        ));
+        format_model_body(&mut lines, iface, options);
         lines.push(String::new());
     }
 
     // Views
-    for view in &ast.views {
-        lines.push(format!("## {} ::view", view.name));
-        format_model_body(&mut lines, view);
+    for view in order_models(&ast.views, options.sort_models) {
+        namespace(&mut lines, &view.namespace);
+        format_view(&mut lines, view, options);
         lines.push(String::new());
     }
 
@@ -50,88 +243,889 @@ fn format_ast(ast: &m3l_core::M3lAst) -> String {
     lines.join("\n")
 }
 
-fn format_model(lines: &mut Vec<String>, model: &m3l_core::ModelNode) {
-    let mut header = format!("## {}", model.name);
+/// Models in the requested order. Dependency order is a stable topological
+/// sort over inheritance and references; models in a cycle keep their
+/// declaration order.
+pub(crate) fn order_models(
+    models: &[m3l_core::ModelNode],
+    order: ModelOrder,
+) -> Vec<&m3l_core::ModelNode> {
+    let mut ordered: Vec<&m3l_core::ModelNode> = models.iter().collect();
+    match order {
+        ModelOrder::Declaration => {}
+        ModelOrder::Name => ordered.sort_by(|a, b| a.name.cmp(&b.name)),
+        ModelOrder::Dependency => {
+            let index: HashMap<&str, usize> = models
+                .iter()
+                .enumerate()
+                .map(|(i, m)| (m.name.as_str(), i))
+                .collect();
+            let mut placed: HashSet<usize> = HashSet::new();
+            let mut visiting: HashSet<usize> = HashSet::new();
+            let mut result = Vec::with_capacity(models.len());
+            for i in 0..models.len() {
+                visit(i, models, &index, &mut placed, &mut visiting, &mut result);
+            }
+            ordered = result;
+        }
+    }
+    ordered
+}
+
+fn visit<'a>(
+    i: usize,
+    models: &'a [m3l_core::ModelNode],
+    index: &HashMap<&str, usize>,
+    placed: &mut HashSet<usize>,
+    visiting: &mut HashSet<usize>,
+    result: &mut Vec<&'a m3l_core::ModelNode>,
+) {
+    if placed.contains(&i) || !visiting.insert(i) {
+        return;
+    }
+    for dep in dependencies(&models[i]) {
+        if let Some(&j) = index.get(dep) {
+            if j != i {
+                visit(j, models, index, placed, visiting, result);
+            }
+        }
+    }
+    visiting.remove(&i);
+    placed.insert(i);
+    result.push(&models[i]);
+}
+
+/// Names a model depends on: parents, reference targets and Relations.
+fn dependencies(model: &m3l_core::ModelNode) -> Vec<&str> {
+    let mut deps: Vec<&str> = model.inherits.iter().map(String::as_str).collect();
+    for field in &model.fields {
+        for attr in &field.attributes {
+            if attr.name != "reference" && attr.name != "fk" {
+                continue;
+            }
+            if let Some(m3l_core::AttrArgValue::String(target)) =
+                attr.args.as_ref().and_then(|a| a.first())
+            {
+                deps.push(target.split('.').next().unwrap_or(target));
+            }
+        }
+    }
+    for relation in &model.sections.relations {
+        if let Some(ref target) = relation.target {
+            deps.push(target.split('.').next().unwrap_or(target));
+        }
+    }
+    deps
+}
+
+pub(crate) fn format_model(
+    lines: &mut Vec<String>,
+    model: &m3l_core::ModelNode,
+    ast: &M3lAst,
+    options: &FormatOptions,
+) {
+    let mut header = format!(
+        "## {}",
+        labelled_name(&generic_name(model), model.label.as_deref(), &model.labels)
+    );
     if !model.inherits.is_empty() {
         header.push_str(&format!(" : {}", model.inherits.join(", ")));
     }
-    if !model.attributes.is_empty() {
-        for attr in &model.attributes {
-            header.push_str(&format!(" @{}", attr.name));
-            if let Some(ref args) = attr.args {
-                let arg_strs: Vec<String> = args.iter().map(format_arg).collect();
-                header.push_str(&format!("({})", arg_strs.join(", ")));
+    for attr in &model.attributes {
+        header.push(' ');
+        header.push_str(&attribute_label(attr));
+    }
+    lines.push(header);
+
+    // The resolver copies parent fields into the model; `: Parent` already
+    // brings them back, so only the model's own fields are written. Fields
+    // from `## extend` blocks are written with the blocks.
+    let mut inherited: HashSet<(&str, usize)> = ast
+        .extend_blocks
+        .iter()
+        .flat_map(|b| &b.fields)
+        .map(|f| (f.loc.file.as_str(), f.loc.line))
+        .collect();
+    for parent in ast
+        .models
+        .iter()
+        .chain(ast.interfaces.iter())
+        .filter(|p| model.inherits.contains(&p.name))
+    {
+        inherited.extend(
+            parent
+                .fields
+                .iter()
+                .map(|f| (f.loc.file.as_str(), f.loc.line)),
+        );
+    }
+    let own: Vec<m3l_core::FieldNode> = model
+        .fields
+        .iter()
+        .filter(|f| !inherited.contains(&(f.loc.file.as_str(), f.loc.line)))
+        .cloned()
+        .collect();
+    push_description(lines, "", model.description.as_deref(), model.doc.as_ref());
+    format_fields(lines, &own, 0, options);
+    if ast.extend_blocks.is_empty() {
+        format_sections(lines, model);
+    } else {
+        let mut own = model.clone();
+        let blocks = &ast.extend_blocks;
+        own.sections
+            .indexes
+            .retain(|i| !blocks.iter().any(|b| b.sections.indexes.contains(i)));
+        own.sections
+            .relations
+            .retain(|r| !blocks.iter().any(|b| b.sections.relations.contains(r)));
+        format_sections(lines, &own);
+    }
+}
+
+/// A view: header, `### Source`, its fields and `### Refresh`.
+pub(crate) fn format_view(
+    lines: &mut Vec<String>,
+    view: &m3l_core::ModelNode,
+    options: &FormatOptions,
+) {
+    let mut header = format!(
+        "## {} ::view",
+        labelled_name(&view.name, view.label.as_deref(), &view.labels)
+    );
+    if view.materialized == Some(true) {
+        header.push_str(" @materialized");
+    }
+    lines.push(header);
+    push_description(lines, "", view.description.as_deref(), view.doc.as_ref());
+    if let Some(ref source) = view.source_def {
+        lines.push(String::new());
+        lines.push("### Source".into());
+        if let Some(ref sql) = source.raw_sql {
+            lines.push(format!(
+                "```{}",
+                source.language_hint.as_deref().unwrap_or_default()
+            ));
+            lines.extend(sql.lines().map(str::to_string));
+            lines.push("```".into());
+        }
+        // A SQL block's tables are read back from the SQL itself
+        let (from, joins) = match source.raw_sql {
+            Some(_) => (None, None),
+            None => (source.from.as_ref(), source.joins.as_ref()),
+        };
+        if let Some(from) = from {
+            lines.push(format!("- from: {from}"));
+        }
+        // `on` conditions and group_by lists are quoted so they read back
+        // as one value
+        for join in joins.into_iter().flatten() {
+            let label = join_label(join);
+            if label.contains(' ') {
+                lines.push(format!("- join: \"{label}\""));
+            } else {
+                lines.push(format!("- join: {label}"));
             }
         }
+        if let Some(ref where_clause) = source.where_clause {
+            lines.push(format!("- where: \"{where_clause}\""));
+        }
+        if let Some(ref group_by) = source.group_by {
+            lines.push(format!("- group_by: \"[{}]\"", group_by.join(", ")));
+        }
+        if let Some(ref order_by) = source.order_by {
+            lines.push(format!("- order_by: \"{order_by}\""));
+        }
+        lines.push(String::new());
+    }
+    format_fields(lines, &view.fields, 0, options);
+    if let Some(ref refresh) = view.refresh {
+        lines.push(String::new());
+        lines.push("### Refresh".into());
+        lines.push(format!("- strategy: {}", refresh.strategy));
+        if let Some(ref interval) = refresh.interval {
+            lines.push(format!("- interval: \"{interval}\""));
+        }
     }
-    lines.push(header);
-    format_model_body(lines, model);
 }
 
-fn format_model_body(lines: &mut Vec<String>, model: &m3l_core::ModelNode) {
-    if let Some(ref desc) = model.description {
-        lines.push(format!("> {desc}"));
+/// `### Indexes`, `### Relations`, `### Behaviors`, `### Endpoints`,
+/// `### Seed` and `### Metadata`. Named indexes keep their nested properties; `@index`,
+/// `@unique`, `@relation` and `@behavior` directives are written as
+/// declared.
+fn format_sections(lines: &mut Vec<String>, model: &m3l_core::ModelNode) {
+    let sections = &model.sections;
+    // Directive lines belong to the model body, before any section;
+    // checks declared on a field are written with the field
+    for key in sections.keys.iter().filter(|k| k.name.is_none()) {
+        lines.push(with_description(
+            format!("- @natural_key({})", key.fields.join(", ")),
+            key.description.as_deref(),
+        ));
     }
-    for field in &model.fields {
-        format_field(lines, field, 0);
+    for check in sections.checks.iter().filter(|c| c.field.is_none()) {
+        lines.push(with_description(
+            format!("- @check(`{}`)", check.expression),
+            check.description.as_deref(),
+        ));
+    }
+    if !sections.indexes.is_empty() {
+        lines.push(String::new());
+        lines.push("### Indexes".into());
+        for index in &sections.indexes {
+            if let Some(ref raw) = index.raw {
+                lines.push(raw.clone());
+                continue;
+            }
+            let mut line = format!("- {}", index.name.as_deref().unwrap_or_default());
+            if let Some(ref label) = index.label {
+                line.push_str(&format!("({label})"));
+            }
+            if let Some(ref description) = index.description {
+                line.push_str(&format!(" \"{description}\""));
+            }
+            lines.push(line);
+            if !index.fields.is_empty() {
+                lines.push(format!("  - fields: [{}]", index.fields.join(", ")));
+            }
+            if index.unique {
+                lines.push("  - unique: true".into());
+            }
+            if let Some(ref condition) = index.where_clause {
+                lines.push(format!("  - where: \"{condition}\""));
+            }
+            if let Some(ref method) = index.method {
+                lines.push(format!("  - method: {method}"));
+            }
+            format_entry_properties(lines, &Value::Object(index.options.clone()), &[]);
+        }
+    }
+    if sections.keys.iter().any(|k| k.name.is_some()) {
+        lines.push(String::new());
+        lines.push("### Keys".into());
+        for key in &sections.keys {
+            let Some(ref name) = key.name else {
+                continue;
+            };
+            lines.push(with_description(
+                format!("- {name}"),
+                key.description.as_deref(),
+            ));
+            lines.push(format!("  - fields: [{}]", key.fields.join(", ")));
+        }
+    }
+    if !sections.relations.is_empty() {
+        lines.push(String::new());
+        lines.push("### Relations".into());
+        for relation in &sections.relations {
+            lines.push(format!("- {}", relation.raw));
+            for (key, value) in relation_details(relation) {
+                lines.push(format!("  - {key}: {value}"));
+            }
+        }
+    }
+    if !sections.behaviors.is_empty() {
+        lines.push(String::new());
+        lines.push("### Behaviors".into());
+        for behavior in &sections.behaviors {
+            if behavior.raw.starts_with('@') {
+                lines.push(format!("- {}", behavior.raw));
+                continue;
+            }
+            match behavior.action {
+                Some(ref action) => lines.push(format!("- {}: {action}", behavior.name)),
+                None => lines.push(format!("- {}", behavior.name)),
+            }
+            if let Some(ref condition) = behavior.condition {
+                let condition = if condition.contains(char::is_whitespace) {
+                    format!("\"{condition}\"")
+                } else {
+                    condition.clone()
+                };
+                lines.push(format!("  - condition: {condition}"));
+            }
+            format_entry_properties(lines, &Value::Object(behavior.options.clone()), &[]);
+        }
+    }
+    if !sections.endpoints.is_empty() {
+        lines.push(String::new());
+        lines.push("### Endpoints".into());
+        for endpoint in &sections.endpoints {
+            let mut line = format!("- {} {}", endpoint.method, endpoint.path);
+            if let Some(ref description) = endpoint.description {
+                line.push_str(&format!(" \"{description}\""));
+            }
+            lines.push(line);
+            format_entry_properties(lines, &Value::Object(endpoint.options.clone()), &[]);
+        }
+    }
+    if !sections.seeds.is_empty() {
+        lines.push(String::new());
+        lines.push("### Seed".into());
+        format_seeds(lines, model);
+    }
+    if !sections.metadata.is_empty() {
+        lines.push(String::new());
+        lines.push("### Metadata".into());
+        let mut metadata: Vec<_> = sections.metadata.iter().collect();
+        metadata.sort_by_key(|(key, _)| *key);
+        for (key, value) in metadata {
+            format_metadata_item(lines, 0, Some(key), value);
+        }
     }
 }
 
-fn format_field(lines: &mut Vec<String>, field: &m3l_core::FieldNode, indent: usize) {
-    let prefix = "  ".repeat(indent);
-    let mut line = format!("{prefix}- {}", field.name);
+/// `line` followed by its quoted description, if it has one.
+fn with_description(line: String, description: Option<&str>) -> String {
+    match description {
+        Some(description) => format!("{line} \"{description}\""),
+        None => line,
+    }
+}
+
+/// A `### Metadata` item: objects and lists as nested items under
+/// `- key:`, with each object in a list written as its own `- key: value`
+/// items. Strings are quoted when they would read back as another value.
+fn format_metadata_item(lines: &mut Vec<String>, indent: usize, key: Option<&str>, value: &Value) {
+    let pad = " ".repeat(indent);
+    let label = key.map(|k| format!("{k}:")).unwrap_or_default();
+    match (key, value) {
+        (Some(_), Value::Object(map)) if !map.is_empty() => {
+            lines.push(format!("{pad}- {label}"));
+            for (key, value) in map {
+                format_metadata_item(lines, indent + 2, Some(key), value);
+            }
+        }
+        (Some(_), Value::Array(items)) if !items.is_empty() => {
+            lines.push(format!("{pad}- {label}"));
+            for item in items {
+                match item.as_object() {
+                    Some(map) => {
+                        for (key, value) in map {
+                            format_metadata_item(lines, indent + 2, Some(key), value);
+                        }
+                    }
+                    None => format_metadata_item(lines, indent + 2, None, item),
+                }
+            }
+        }
+        (_, Value::Array(items)) if items.is_empty() => lines.push(format!("{pad}- {label} []")),
+        _ => {
+            let text = match value {
+                Value::String(s) if s.starts_with('[') || s.contains(" # ") => format!("\"{s}\""),
+                other => seed_cell(other),
+            };
+            let line = match key {
+                Some(_) => format!("{pad}- {label} {text}"),
+                None => format!("{pad}- {text}"),
+            };
+            lines.push(line);
+        }
+    }
+}
 
-    if let Some(ref ft) = field.field_type {
-        line.push_str(&format!(": {ft}"));
-        if let Some(ref params) = field.params {
-            let param_strs: Vec<String> = params
+/// Unnamed seed rows as a table, with columns in field order, then named
+/// rows as list items.
+fn format_seeds(lines: &mut Vec<String>, model: &m3l_core::ModelNode) {
+    let (named, unnamed): (Vec<_>, Vec<_>) =
+        model.sections.seeds.iter().partition(|r| r.name.is_some());
+    if !unnamed.is_empty() {
+        let columns = seed_columns(model);
+        let row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+        lines.push(row(columns.clone()));
+        lines.push(row(columns
+            .iter()
+            .map(|c| "-".repeat(c.len().max(3)))
+            .collect()));
+        for seed in unnamed {
+            lines.push(row(columns
                 .iter()
-                .map(|p| match p {
-                    m3l_core::ParamValue::String(s) => s.clone(),
-                    m3l_core::ParamValue::Number(n) => n.to_string(),
-                })
+                .map(|c| seed.values.get(c).map(seed_cell).unwrap_or_default())
+                .collect()));
+        }
+    }
+    for seed in named {
+        lines.push(format!("- {}", seed.name.as_deref().unwrap_or_default()));
+        for (field, value) in ordered_values(model, seed) {
+            let value = match value {
+                Value::String(s) => format!("\"{s}\""),
+                other => other.to_string(),
+            };
+            lines.push(format!("  - {field}: {value}"));
+        }
+    }
+}
+
+/// A table cell; strings are quoted when they would read back as another
+/// kind of value or contain the cell separator.
+fn seed_cell(value: &Value) -> String {
+    match value {
+        Value::String(s) => {
+            let plain = !s.is_empty()
+                && !s.contains('|')
+                && !s.starts_with(['"', '\''])
+                && s.parse::<f64>().is_err()
+                && !matches!(s.as_str(), "true" | "false" | "null");
+            if plain {
+                s.clone()
+            } else {
+                format!("\"{s}\"")
+            }
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Fields set by any seed row: declared fields in field order, then any
+/// others by name.
+pub(crate) fn seed_columns(model: &m3l_core::ModelNode) -> Vec<String> {
+    let mut columns: Vec<String> = model
+        .fields
+        .iter()
+        .filter(|f| {
+            model
+                .sections
+                .seeds
+                .iter()
+                .any(|r| r.values.contains_key(&f.name))
+        })
+        .map(|f| f.name.clone())
+        .collect();
+    for row in &model.sections.seeds {
+        for key in row.values.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+    columns
+}
+
+fn ordered_values<'a>(
+    model: &'a m3l_core::ModelNode,
+    seed: &'a m3l_core::SeedRow,
+) -> Vec<(&'a String, &'a Value)> {
+    let mut values: Vec<(&String, &Value)> = seed.values.iter().collect();
+    values.sort_by_key(|(field, _)| {
+        model
+            .fields
+            .iter()
+            .position(|f| &f.name == *field)
+            .unwrap_or(usize::MAX)
+    });
+    values
+}
+
+/// The properties of a relation that its line does not already say: the
+/// nested items it was written with, in a fixed order.
+pub(crate) fn relation_details(relation: &m3l_core::RelationDef) -> Vec<(String, String)> {
+    let inline = m3l_core::parse_relation(&relation.raw, relation.loc.clone());
+    let mut details = Vec::new();
+    let mut detail = |key: &str, value: Option<String>, written: Option<String>| {
+        if let Some(value) = value.filter(|v| Some(v) != written.as_ref()) {
+            details.push((key.to_string(), value));
+        }
+    };
+    detail("target", relation.target.clone(), inline.target);
+    detail("from", relation.from_field.clone(), inline.from_field);
+    detail(
+        "cardinality",
+        relation.cardinality.map(|c| c.as_str().to_string()),
+        inline.cardinality.map(|c| c.as_str().to_string()),
+    );
+    detail("on_delete", relation.on_delete.clone(), inline.on_delete);
+    if relation.optional && !inline.optional {
+        details.push(("optional".into(), "true".into()));
+    }
+    for (key, value) in &relation.options {
+        if inline.options.get(key) != Some(value) {
+            let value = value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_string);
+            details.push((key.clone(), value));
+        }
+    }
+    details
+}
+
+/// Nested `  - key: value` lines of a section entry, skipping `skip`.
+fn format_entry_properties(lines: &mut Vec<String>, entry: &Value, skip: &[&str]) {
+    let Some(entry) = entry.as_object() else {
+        return;
+    };
+    for (key, value) in entry.iter().filter(|(k, _)| !skip.contains(&k.as_str())) {
+        let value = match value {
+            Value::String(s) => s.clone(),
+            Value::Array(items) => {
+                let items: Vec<String> = items
+                    .iter()
+                    .map(|i| i.as_str().map_or_else(|| i.to_string(), str::to_string))
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
+            other => other.to_string(),
+        };
+        lines.push(format!("  - {key}: {value}"));
+    }
+}
+
+pub(crate) fn format_model_body(
+    lines: &mut Vec<String>,
+    model: &m3l_core::ModelNode,
+    options: &FormatOptions,
+) {
+    push_description(lines, "", model.description.as_deref(), model.doc.as_ref());
+    format_fields(lines, &model.fields, 0, options);
+}
+
+fn is_primary_key(field: &m3l_core::FieldNode) -> bool {
+    field
+        .attributes
+        .iter()
+        .any(|a| a.name == "primary" || a.name == "pk")
+}
+
+/// A field line split into columns: `- name:`, type, and the rest
+/// (default, attributes, description).
+struct FieldColumns {
+    name: String,
+    field_type: String,
+    rest: String,
+}
+
+/// A field's type as written: `decimal(10, 2)?`, `string[]`.
+pub(crate) fn type_label(field: &m3l_core::FieldNode) -> String {
+    let members = |members: &[m3l_core::TypeMember]| -> Vec<String> {
+        members.iter().map(type_member_label).collect()
+    };
+    let mut field_type = match (&field.union, &field.tuple, &field.field_type) {
+        _ if literal_enum(field) => {
+            let values: Vec<String> = field
+                .enum_values
+                .iter()
+                .flatten()
+                .map(|v| format!("\"{}\"", v.name))
                 .collect();
-            line.push_str(&format!("({})", param_strs.join(", ")));
+            values.join(" | ")
+        }
+        (Some(union), _, _) => members(union).join(" | "),
+        (None, Some(tuple), _) => format!("({})", members(tuple).join(", ")),
+        (None, None, Some(ft)) => type_name_label(ft, &field.generic_params, &field.params),
+        (None, None, None) => String::new(),
+    };
+    // `string?[]` holds nullable items, `string[]?` may itself be null
+    if field.array {
+        if field.array_item_nullable {
+            field_type.push('?');
         }
+        field_type.push_str("[]");
     }
     if field.nullable {
-        line.push('?');
+        field_type.push('?');
     }
-    if field.array {
-        line.push_str("[]");
+    field_type
+}
+
+/// A type name with its type arguments and parameters: `map<string, integer>`,
+/// `decimal(10, 2)`.
+fn type_name_label(
+    name: &str,
+    generic_params: &Option<Vec<String>>,
+    params: &Option<Vec<m3l_core::ParamValue>>,
+) -> String {
+    let mut label = name.to_string();
+    if let Some(generic_params) = generic_params {
+        label.push_str(&format!("<{}>", generic_params.join(", ")));
+    }
+    if let Some(params) = params {
+        let param_strs: Vec<String> = params
+            .iter()
+            .map(|p| match p {
+                m3l_core::ParamValue::String(s) => s.clone(),
+                m3l_core::ParamValue::Number(n) => n.to_string(),
+            })
+            .collect();
+        label.push_str(&format!("({})", param_strs.join(", ")));
     }
+    label
+}
 
-    if let Some(ref dv) = field.default_value {
-        line.push_str(&format!(" = {dv}"));
+/// Whether an inline enum is written as literals, `"draft" | "published"`:
+/// its values are bare names, with no description or value.
+pub(crate) fn literal_enum(field: &m3l_core::FieldNode) -> bool {
+    field.field_type.as_deref() == Some("enum")
+        && field.enum_values.as_ref().is_some_and(|values| {
+            !values.is_empty()
+                && values.iter().all(|v| {
+                    v.description.is_none()
+                        && v.value.is_none()
+                        && v.value_type.is_none()
+                        && !v.name.contains('"')
+                })
+        })
+}
+
+/// A union member or tuple component as written: `string(50)`, `float?`.
+pub(crate) fn type_member_label(member: &m3l_core::TypeMember) -> String {
+    let mut label = type_name_label(&member.member_type, &member.generic_params, &member.params);
+    if member.nullable {
+        label.push('?');
     }
+    label
+}
 
-    for attr in &field.attributes {
-        line.push_str(&format!(" @{}", attr.name));
-        if let Some(ref args) = attr.args {
-            let arg_strs: Vec<String> = args.iter().map(format_arg).collect();
-            line.push_str(&format!("({})", arg_strs.join(", ")));
+/// An attribute as written: `@reference(Customer)`.
+pub(crate) fn attribute_label(attr: &m3l_core::FieldAttribute) -> String {
+    match attr.args {
+        Some(ref args) => {
+            let args: Vec<String> = args.iter().map(format_arg).collect();
+            format!("@{}({})", attr.name, args.join(", "))
         }
+        None => format!("@{}", attr.name),
     }
+}
 
-    if let Some(ref desc) = field.description {
-        line.push_str(&format!(" # {desc}"));
+/// A default value as written after `=`. An unquoted value ends at the
+/// first space, so expressions such as `price * 0.9` keep their backticks
+/// and strings such as `"Hello world"` their quotes; `now()` and `draft`
+/// stay bare.
+fn default_label(value: &str, kind: Option<&m3l_core::DefaultValueType>) -> String {
+    use m3l_core::DefaultValueType;
+    let bare = !value.is_empty() && !value.contains([' ', '@', '"', '`']);
+    match kind {
+        Some(DefaultValueType::Json) => value.to_string(),
+        Some(DefaultValueType::Expression) if !(bare && value.contains('(')) => {
+            format!("`{value}`")
+        }
+        Some(DefaultValueType::Literal)
+            if !bare || value.contains('(') || value.starts_with(['[', '{']) =>
+        {
+            format!("\"{value}\"")
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// A field attribute as written, with its cascade symbol:
+/// `@reference(Customer)!`.
+pub(crate) fn cascade_label(attr: &m3l_core::FieldAttribute) -> String {
+    format!(
+        "{}{}",
+        attribute_label(attr),
+        attr.cascade.as_deref().unwrap_or("")
+    )
+}
+
+/// A view join as written after `join:`: `left join Order o on o.id = c.id`.
+pub(crate) fn join_label(join: &m3l_core::JoinDef) -> String {
+    let mut label = match join.kind {
+        Some(kind) => format!("{} join {}", kind.as_str(), join.model),
+        None => join.model.clone(),
+    };
+    if let Some(ref alias) = join.alias {
+        label.push(' ');
+        label.push_str(alias);
+    }
+    if !join.on.is_empty() {
+        label.push_str(" on ");
+        label.push_str(&join.on);
+    }
+    label
+}
+
+/// A model's name with its type parameters: `Paged<T>`.
+fn generic_name(model: &m3l_core::ModelNode) -> String {
+    if model.generic_params.is_empty() {
+        return model.name.clone();
+    }
+    format!("{}<{}>", model.name, model.generic_params.join(", "))
+}
+
+/// `name` followed by its label, or its localized labels with the one that
+/// is also `label` first so it stays the default: `name(en: "Name", ko: "이름")`.
+pub(crate) fn labelled_name(
+    name: &str,
+    label: Option<&str>,
+    labels: &BTreeMap<String, String>,
+) -> String {
+    if labels.is_empty() {
+        return match label {
+            Some(label) => format!("{name}({label})"),
+            None => name.to_string(),
+        };
+    }
+    let mut entries: Vec<(&String, &String)> = labels.iter().collect();
+    if let Some(i) = entries
+        .iter()
+        .position(|(_, text)| Some(text.as_str()) == label)
+    {
+        let first = entries.remove(i);
+        entries.insert(0, first);
+    }
+    let entries: Vec<String> = entries
+        .iter()
+        .map(|(locale, text)| format!("{locale}: \"{text}\""))
+        .collect();
+    format!("{name}({})", entries.join(", "))
+}
+
+/// Whether a field's description goes in `> ` lines under it rather than
+/// in a trailing `# comment`, which holds one line and no doc tags.
+fn quotes_description(field: &m3l_core::FieldNode) -> bool {
+    field.doc.is_some() || field.description.as_ref().is_some_and(|d| d.contains('\n'))
+}
+
+/// A description and its doc tags as `> ` lines, each after `prefix`.
+fn push_description(
+    lines: &mut Vec<String>,
+    prefix: &str,
+    description: Option<&str>,
+    doc: Option<&m3l_core::DocTags>,
+) {
+    let tags = doc.map(doc_tag_lines).unwrap_or_default();
+    for line in description
+        .into_iter()
+        .flat_map(str::lines)
+        .chain(tags.iter().map(String::as_str))
+    {
+        lines.push(format!("{prefix}> {line}"));
     }
+}
 
-    lines.push(line);
+/// `@since`, `@deprecated`, `@example` and `@see` lines for `doc`.
+pub(crate) fn doc_tag_lines(doc: &m3l_core::DocTags) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(ref since) = doc.since {
+        lines.push(format!("@since {since}"));
+    }
+    if doc.deprecated {
+        lines.push(match doc.deprecation {
+            Some(ref reason) => format!("@deprecated {reason}"),
+            None => "@deprecated".to_string(),
+        });
+    }
+    for example in &doc.examples {
+        let mut example_lines = example.lines();
+        lines.push(format!("@example {}", example_lines.next().unwrap_or("")));
+        lines.extend(example_lines.map(String::from));
+    }
+    for see in &doc.see {
+        lines.push(format!("@see {see}"));
+    }
+    lines
+}
 
-    // Nested fields
-    if let Some(ref sub_fields) = field.fields {
-        for sf in sub_fields {
-            format_field(lines, sf, indent + 1);
+fn field_columns(field: &m3l_core::FieldNode) -> FieldColumns {
+    let field_type = type_label(field);
+    let mut rest = String::new();
+    if let Some(ref dv) = field.default_value {
+        rest.push_str(&format!(
+            " = {}",
+            default_label(dv, field.default_value_type.as_ref())
+        ));
+    }
+    for attr in &field.attributes {
+        rest.push(' ');
+        rest.push_str(&cascade_label(attr));
+    }
+    if let Some(ref desc) = field.description {
+        if !quotes_description(field) {
+            rest.push_str(&format!(" # {desc}"));
         }
     }
+
+    let mut name = labelled_name(&field.name, field.label.as_deref(), &field.labels);
+    if field.field_type.is_some() {
+        name.push(':');
+    }
+    FieldColumns {
+        name,
+        field_type,
+        rest,
+    }
 }
 
-fn format_enum(lines: &mut Vec<String>, e: &m3l_core::EnumNode) {
-    lines.push(format!("## {} ::enum", e.name));
-    if let Some(ref desc) = e.description {
-        lines.push(format!("> {desc}"));
+/// Format sibling fields, sorted and aligned as configured.
+fn format_fields(
+    lines: &mut Vec<String>,
+    fields: &[m3l_core::FieldNode],
+    indent: usize,
+    options: &FormatOptions,
+) {
+    let mut ordered: Vec<&m3l_core::FieldNode> = fields.iter().collect();
+    match options.sort_fields {
+        FieldOrder::Declaration => {}
+        FieldOrder::Alphabetical => ordered.sort_by(|a, b| a.name.cmp(&b.name)),
+        FieldOrder::PkFirst => ordered.sort_by_key(|f| !is_primary_key(f)),
     }
+
+    let columns: Vec<FieldColumns> = ordered.iter().map(|f| field_columns(f)).collect();
+    let (name_width, type_width) = if options.align {
+        (
+            columns.iter().map(|c| c.name.len()).max().unwrap_or(0),
+            columns
+                .iter()
+                .map(|c| c.field_type.len())
+                .max()
+                .unwrap_or(0),
+        )
+    } else {
+        (0, 0)
+    };
+
+    let prefix = "  ".repeat(indent);
+    for (field, c) in ordered.iter().zip(&columns) {
+        let line = if c.field_type.is_empty() {
+            format!("{prefix}- {:<name_width$}{}", c.name, c.rest)
+        } else {
+            format!(
+                "{prefix}- {:<name_width$} {:<type_width$}{}",
+                c.name, c.field_type, c.rest
+            )
+        };
+        lines.push(line.trim_end().to_string());
+        if quotes_description(field) {
+            push_description(
+                lines,
+                &"  ".repeat(indent + 1),
+                field.description.as_deref(),
+                field.doc.as_ref(),
+            );
+        }
+
+        // Inline enum values the type does not already list
+        if let Some(ref values) = field.enum_values {
+            if !literal_enum(field) {
+                let prefix = "  ".repeat(indent + 1);
+                lines.push(format!("{prefix}- values:"));
+                for value in values {
+                    let text = match (&value.description, &value.value) {
+                        (Some(desc), _) => format!("\"{desc}\""),
+                        (None, Some(serde_json::Value::String(v))) => v.clone(),
+                        (None, Some(v)) => v.to_string(),
+                        (None, None) => String::new(),
+                    };
+                    lines.push(
+                        format!("{prefix}  - {}: {text}", value.name)
+                            .trim_end()
+                            .to_string(),
+                    );
+                }
+            }
+        }
+
+        // Nested fields
+        if let Some(ref sub_fields) = field.fields {
+            format_fields(lines, sub_fields, indent + 1, options);
+        }
+    }
+}
+
+pub(crate) fn format_enum(lines: &mut Vec<String>, e: &m3l_core::EnumNode) {
+    lines.push(format!(
+        "## {} ::enum",
+        labelled_name(&e.name, e.label.as_deref(), &e.labels)
+    ));
+    push_description(lines, "", e.description.as_deref(), e.doc.as_ref());
     for val in &e.values {
         let mut line = format!("- {}", val.name);
         if let Some(ref desc) = val.description {
@@ -143,8 +1137,175 @@ fn format_enum(lines: &mut Vec<String>, e: &m3l_core::EnumNode) {
 
 fn format_arg(arg: &m3l_core::AttrArgValue) -> String {
     match arg {
-        m3l_core::AttrArgValue::String(s) => s.clone(),
+        // Bare unless that reads back as something else: `'/users/' + name`
+        // splits at its quotes, `a, b` at the comma, `42` is a number
+        m3l_core::AttrArgValue::String(s) => {
+            let reads_back = |text: &str| parse_attr_args_string(text) == [arg.clone()];
+            let quoted = format!("\"{s}\"");
+            if !reads_back(s) && reads_back(&quoted) {
+                quoted
+            } else {
+                s.clone()
+            }
+        }
         m3l_core::AttrArgValue::Number(n) => n.to_string(),
         m3l_core::AttrArgValue::Bool(b) => b.to_string(),
     }
 }
+
+/// Parse `formatted` back and compare it with the AST it was produced from.
+/// Source positions are ignored, as is the order of models and fields when
+/// the options reorder them. On a mismatch, returns the path of the first
+/// difference and what changed there. Blocks the parser skipped for the
+/// file's declared M3L version (M3L-P005) are not in the AST, so their
+/// files are never formatted.
+pub fn check_roundtrip(
+    original: &M3lAst,
+    formatted: &str,
+    options: &FormatOptions,
+) -> Result<(), String> {
+    if let Some(skipped) = original.errors.iter().find(|d| d.code == "M3L-P005") {
+        return Err(format!("line {}: {}", skipped.line, skipped.message));
+    }
+    let file = original.sources.first().map_or("formatted.m3l.md", |s| s);
+    let reparsed = resolve(&[parse_string(formatted, file)], None);
+    let before = semantic_value(original, options)?;
+    let after = semantic_value(&reparsed, options)?;
+    match first_difference("", &before, &after) {
+        Some(difference) => Err(difference),
+        None => Ok(()),
+    }
+}
+
+/// Where two ASTs first describe different schemas, ignoring source
+/// positions and the order of elements (files may be read in any order).
+pub(crate) fn schema_difference(before: &M3lAst, after: &M3lAst) -> Option<String> {
+    let options = FormatOptions {
+        sort_models: ModelOrder::Name,
+        ..FormatOptions::default()
+    };
+    let before = semantic_value(before, &options).ok()?;
+    let after = semantic_value(after, &options).ok()?;
+    first_difference("", &before, &after)
+}
+
+/// Keys that record where something was written, not what it means.
+const POSITION_KEYS: &[&str] = &["loc", "source", "line", "col"];
+
+/// Everything in the AST that formatting must preserve.
+fn semantic_value(ast: &M3lAst, options: &FormatOptions) -> Result<Value, String> {
+    let mut value =
+        serde_json::to_value(ast).map_err(|e| format!("JSON serialization error: {e}"))?;
+    if let Value::Object(ref mut map) = value {
+        for key in [
+            "parserVersion",
+            "astVersion",
+            "project",
+            "sources",
+            "files",
+            "errors",
+            "warnings",
+        ] {
+            map.remove(key);
+        }
+    }
+    strip_positions(&mut value, options.sort_fields != FieldOrder::Declaration);
+    if options.sort_models != ModelOrder::Declaration {
+        for key in ["models", "enums", "interfaces", "views"] {
+            if let Some(Value::Array(items)) = value.get_mut(key) {
+                sort_by_name(items);
+            }
+        }
+    }
+    Ok(value)
+}
+
+fn strip_positions(value: &mut Value, sort_fields: bool) {
+    match value {
+        Value::Object(map) => {
+            for key in POSITION_KEYS {
+                map.remove(*key);
+            }
+            if sort_fields {
+                if let Some(Value::Array(fields)) = map.get_mut("fields") {
+                    sort_by_name(fields);
+                }
+            }
+            for child in map.values_mut() {
+                strip_positions(child, sort_fields);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                strip_positions(item, sort_fields);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn sort_by_name(items: &mut [Value]) {
+    items.sort_by(|a, b| {
+        let name = |v: &Value| {
+            v.get("name")
+                .and_then(Value::as_str)
+                .unwrap_or("")
+                .to_string()
+        };
+        name(a).cmp(&name(b))
+    });
+}
+
+/// `path: what changed` for the first place `before` and `after` disagree.
+/// Array elements are labelled by name where they have one.
+fn first_difference(path: &str, before: &Value, after: &Value) -> Option<String> {
+    let join = |segment: &str| {
+        if path.is_empty() {
+            segment.to_string()
+        } else {
+            format!("{path}.{segment}")
+        }
+    };
+    match (before, after) {
+        (Value::Object(b), Value::Object(a)) => {
+            let mut keys: Vec<&String> = b.keys().chain(a.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            keys.into_iter()
+                .find_map(|key| match (b.get(key), a.get(key)) {
+                    (Some(b), Some(a)) => first_difference(&join(key), b, a),
+                    (Some(b), None) => Some(format!("{}: dropped (was {})", join(key), brief(b))),
+                    (None, Some(a)) => Some(format!("{}: added ({})", join(key), brief(a))),
+                    (None, None) => None,
+                })
+        }
+        (Value::Array(b), Value::Array(a)) => {
+            let label = |i: usize, v: &Value| match v.get("name").and_then(Value::as_str) {
+                Some(name) => format!("{path}[{name}]"),
+                None => format!("{path}[{i}]"),
+            };
+            (0..b.len().max(a.len())).find_map(|i| match (b.get(i), a.get(i)) {
+                (Some(b), Some(a)) if b.get("name") != a.get("name") => Some(format!(
+                    "{path}[{i}]: {} became {}",
+                    b.get("name").map_or_else(|| brief(b), brief),
+                    a.get("name").map_or_else(|| brief(a), brief)
+                )),
+                (Some(b), Some(a)) => first_difference(&label(i, b), b, a),
+                (Some(b), None) => Some(format!("{}: dropped", label(i, b))),
+                (None, Some(a)) => Some(format!("{}: added", label(i, a))),
+                (None, None) => None,
+            })
+        }
+        _ if before == after => None,
+        _ => Some(format!("{path}: {} became {}", brief(before), brief(after))),
+    }
+}
+
+/// Compact JSON, cut short for error messages.
+fn brief(value: &Value) -> String {
+    let json = value.to_string();
+    match json.char_indices().nth(60) {
+        Some((end, _)) => format!("{}…", &json[..end]),
+        None => json,
+    }
+}
@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::Path;
+
+use m3l_core::{parse_string, resolve, M3lAst, ProjectInfo};
+use serde::Deserialize;
+
+use crate::commands::analyze::{analyze_ast, GraphOptions};
+use crate::reader::{find_project_config, read_m3l_files};
+
+/// Artifact kinds `m3l generate` can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TargetKind {
+    /// The resolved AST as pretty-printed JSON, as `m3l parse` prints it.
+    Ast,
+    /// Dependency graph as a Mermaid diagram.
+    Mermaid,
+    /// Dependency graph in Graphviz DOT.
+    Dot,
+}
+
+impl TargetKind {
+    fn label(self) -> &'static str {
+        match self {
+            TargetKind::Ast => "ast",
+            TargetKind::Mermaid => "mermaid",
+            TargetKind::Dot => "dot",
+        }
+    }
+}
+
+/// One entry of the `generate` list in the project config.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GenerateTarget {
+    pub target: TargetKind,
+    /// Output file, relative to the project directory.
+    pub output: String,
+}
+
+/// Render every configured target for the project `path` belongs to: the
+/// directory of the nearest project config at or above it. With `check`,
+/// nothing is written; the outputs are compared with the files on disk and
+/// any missing or stale file is an error.
+pub fn run_generate(path: &Path, check: bool) -> Result<String, String> {
+    let project = find_project_config(path)?.ok_or_else(|| {
+        format!(
+            "No m3l.toml or m3l.config.yaml found in {} or its parents; m3l generate reads its targets from the project config",
+            path.display()
+        )
+    })?;
+    let config = project.config;
+    let targets = config.generate.unwrap_or_default();
+    if targets.is_empty() {
+        return Err(format!(
+            "No targets configured under `generate` in {}",
+            project.file.display()
+        ));
+    }
+
+    let dir = project.dir.as_path();
+    let files = read_m3l_files(dir)?;
+    // Paths relative to the project keep the AST identical wherever the
+    // command is run from
+    let parsed: Vec<_> = files
+        .iter()
+        .map(|f| parse_string(&f.content, &project_relative(dir, &f.path)))
+        .collect();
+    let ast = resolve(
+        &parsed,
+        Some(ProjectInfo {
+            name: config.name,
+            version: config.version,
+        }),
+    );
+
+    let mut lines = Vec::new();
+    let mut stale = Vec::new();
+    for target in &targets {
+        let content = render(&ast, target.target)?;
+        let path = dir.join(&target.output);
+        if check {
+            let on_disk = fs::read_to_string(&path)
+                .ok()
+                .map(|c| c.replace("\r\n", "\n"));
+            match on_disk {
+                None => stale.push(format!("  {} (missing)", target.output)),
+                Some(existing) if existing != content => {
+                    stale.push(format!("  {} (out of date)", target.output))
+                }
+                Some(_) => lines.push(format!("Up to date {}", target.output)),
+            }
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        fs::write(&path, &content)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        lines.push(format!(
+            "Generated {} ({})",
+            target.output,
+            target.target.label()
+        ));
+    }
+
+    if !stale.is_empty() {
+        let noun = if stale.len() == 1 {
+            "file is"
+        } else {
+            "files are"
+        };
+        return Err(format!(
+            "{} generated {noun} not in sync with the M3L sources:\n{}\nRun `m3l generate` to update them.",
+            stale.len(),
+            stale.join("\n")
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn render(ast: &M3lAst, kind: TargetKind) -> Result<String, String> {
+    let mut content = match kind {
+        TargetKind::Ast => serde_json::to_string_pretty(ast)
+            .map_err(|e| format!("JSON serialization error: {e}"))?,
+        TargetKind::Mermaid => analyze_ast(ast, "mermaid", &GraphOptions::default())?,
+        TargetKind::Dot => analyze_ast(ast, "dot", &GraphOptions::default())?,
+    };
+    content.push('\n');
+    Ok(content)
+}
+
+fn project_relative(dir: &Path, path: &str) -> String {
+    Path::new(path)
+        .strip_prefix(dir)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| path.to_string())
+}
@@ -0,0 +1,127 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+use m3l_core::{parse_string, resolve};
+
+use crate::commands::check::check_ast;
+use crate::commands::lint::lint_config;
+use crate::git::{git, read_blobs};
+use crate::validate_settings;
+
+/// Marks hook scripts written by `m3l hook install`, so reinstalling may
+/// replace them without `--force`.
+const MARKER: &str = "# Installed by `m3l hook install`";
+
+/// Git hook that runs the check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    /// Checks the files staged for the commit, as they are in the index.
+    PreCommit,
+    /// Checks the files changed by the commits being pushed, as of `HEAD`.
+    PrePush,
+}
+
+impl HookKind {
+    fn name(self) -> &'static str {
+        match self {
+            HookKind::PreCommit => "pre-commit",
+            HookKind::PrePush => "pre-push",
+        }
+    }
+}
+
+/// `m3l hook install`: write a hook script into the repository's hooks
+/// directory (honoring `core.hooksPath`). An existing hook that was not
+/// written by m3l is only replaced with `force`.
+pub fn run_install(kind: HookKind, force: bool) -> Result<String, String> {
+    let hooks_dir = PathBuf::from(git(None, &["rev-parse", "--git-path", "hooks"])?.trim());
+    let path = hooks_dir.join(kind.name());
+    if let Ok(existing) = fs::read_to_string(&path) {
+        if !force && !existing.contains(MARKER) {
+            return Err(format!(
+                "{} already exists and was not installed by m3l (use --force to replace it)",
+                path.display()
+            ));
+        }
+    }
+    fs::create_dir_all(&hooks_dir)
+        .map_err(|e| format!("Failed to create {}: {e}", hooks_dir.display()))?;
+    let script = format!(
+        "#!/bin/sh\n{MARKER}; runs `m3l check` on the changed M3L files.\nexec m3l hook run --{}\n",
+        kind.name()
+    );
+    fs::write(&path, script).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {e}", path.display()))?;
+    }
+    Ok(format!(
+        "Installed {} hook at {}",
+        kind.name(),
+        path.display()
+    ))
+}
+
+/// `m3l hook run`: check the M3L files the hook is about, with every other
+/// M3L file of the repository (at the same revision) as context so
+/// cross-file references resolve. Only findings in the changed files are
+/// reported. Returns the report and the error count.
+pub fn run_hook(kind: HookKind, color: bool) -> Result<(String, usize), String> {
+    let root = PathBuf::from(git(None, &["rev-parse", "--show-toplevel"])?.trim());
+    let (changed, all, revision) = match kind {
+        HookKind::PreCommit => (
+            git(
+                Some(&root),
+                &["diff", "--cached", "--name-only", "--diff-filter=ACMR"],
+            )?,
+            git(Some(&root), &["ls-files"])?,
+            "",
+        ),
+        HookKind::PrePush => {
+            let all = git(Some(&root), &["ls-tree", "-r", "--name-only", "HEAD"])?;
+            // Without an upstream every commit is new, so check everything
+            let changed = git(
+                Some(&root),
+                &[
+                    "diff",
+                    "--name-only",
+                    "--diff-filter=ACMR",
+                    "@{upstream}...HEAD",
+                ],
+            )
+            .unwrap_or_else(|_| all.clone());
+            (changed, all, "HEAD")
+        }
+    };
+    let changed: HashSet<&str> = changed.lines().filter(|p| is_hook_path(p)).collect();
+    if changed.is_empty() {
+        return Ok((
+            format!("No changed M3L files to check ({})", kind.name()),
+            0,
+        ));
+    }
+
+    let paths: Vec<&str> = all.lines().filter(|p| is_hook_path(p)).collect();
+    let contents = read_blobs(&root, revision, &paths)?;
+    let parsed: Vec<_> = paths
+        .iter()
+        .zip(&contents)
+        .map(|(path, content)| parse_string(content, path))
+        .collect();
+    let ast = resolve(&parsed, None);
+
+    let (strict, _) = validate_settings(&root, false, None)?;
+    let config = lint_config(&root)?;
+    let (output, errors, _) = check_ast(&ast, strict, config, "human", color, |file| {
+        changed.contains(file)
+    })?;
+    Ok((output, errors))
+}
+
+/// Files the hook looks at: `.m3l.md` and `.m3l`, not every Markdown file.
+fn is_hook_path(path: &str) -> bool {
+    path.ends_with(".m3l.md") || path.ends_with(".m3l")
+}
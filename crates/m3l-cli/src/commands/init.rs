@@ -0,0 +1,282 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use m3l_lint::{LintConfig, LintSeverity, Linter};
+
+use crate::reader::CONFIG_FILES;
+
+/// Starter schema written by `m3l init`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Template {
+    /// One interface, enum and model.
+    #[default]
+    Minimal,
+    /// Customers, products and orders.
+    Ecommerce,
+    /// Authors, posts and comments.
+    Blog,
+}
+
+impl Template {
+    fn namespace(self) -> &'static str {
+        match self {
+            Template::Minimal => "app",
+            Template::Ecommerce => "shop",
+            Template::Blog => "blog",
+        }
+    }
+
+    fn schema(self) -> &'static str {
+        match self {
+            Template::Minimal => MINIMAL,
+            Template::Ecommerce => ECOMMERCE,
+            Template::Blog => BLOG,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InitOptions {
+    pub template: Template,
+    /// Project name; defaults to the directory name.
+    pub name: Option<String>,
+    /// Write a `[lint.rules]` section listing every rule.
+    pub lint: bool,
+    /// Write a `[format]` section with the formatter defaults.
+    pub format: bool,
+    /// Overwrite files that already exist.
+    pub force: bool,
+}
+
+pub fn run_init(dir: &Path, options: &InitOptions) -> Result<String, String> {
+    let config_path = dir.join("m3l.toml");
+    let schema_path = dir
+        .join("models")
+        .join(format!("{}.m3l.md", options.template.namespace()));
+
+    if !options.force {
+        // Another config in the directory would shadow or be shadowed by
+        // the new one
+        let existing: Vec<String> = CONFIG_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .chain([schema_path.clone()])
+            .filter(|p| p.exists())
+            .map(|p| p.display().to_string())
+            .collect();
+        if !existing.is_empty() {
+            return Err(format!(
+                "{} already exist{} (use --force to overwrite)",
+                existing.join(", "),
+                if existing.len() == 1 { "s" } else { "" }
+            ));
+        }
+    }
+
+    let name = match options.name {
+        Some(ref name) => name.clone(),
+        None => project_name(dir),
+    };
+    write(&config_path, &config_toml(&name, options))?;
+    write(&schema_path, options.template.schema())?;
+
+    let shown = if dir == Path::new(".") {
+        String::from(".")
+    } else {
+        dir.display().to_string()
+    };
+    Ok([
+        format!("Created {}", config_path.display()),
+        format!("Created {}", schema_path.display()),
+        String::new(),
+        format!("Next: m3l validate {shown}"),
+    ]
+    .join("\n"))
+}
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+fn project_name(dir: &Path) -> String {
+    fs::canonicalize(dir)
+        .unwrap_or_else(|_| PathBuf::from(dir))
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "m3l-project".to_string())
+}
+
+fn config_toml(name: &str, options: &InitOptions) -> String {
+    // A JSON string is a valid TOML basic string
+    let quoted = serde_json::to_string(name).unwrap_or_else(|_| "\"m3l-project\"".into());
+    let mut lines = vec![
+        format!("name = {quoted}"),
+        "version = \"0.1.0\"".to_string(),
+        "sources = [\"models/**/*.m3l.md\"]".to_string(),
+    ];
+
+    if options.format {
+        lines.push(String::new());
+        lines.push("[format]".to_string());
+        lines.push(
+            "sort_fields = \"declaration\"  # declaration | alphabetical | pk-first".to_string(),
+        );
+        lines.push("sort_models = \"declaration\"  # declaration | name | dependency".to_string());
+        lines.push("align = false".to_string());
+    }
+
+    if options.lint {
+        lines.push(String::new());
+        lines.push("# Rule levels: off | warn | error".to_string());
+        lines.push("[lint.rules]".to_string());
+        let linter = Linter::new(LintConfig::default());
+        for rule in linter.rules() {
+            match rule.default_severity() {
+                LintSeverity::Error => lines.push(format!("{} = \"error\"", rule.id())),
+                LintSeverity::Warning => lines.push(format!("{} = \"warn\"", rule.id())),
+                // Reported as info by default; listing it would change that
+                LintSeverity::Info => lines.push(format!("# {} = \"warn\"", rule.id())),
+            }
+        }
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+const MINIMAL: &str = r#"# Namespace: app
+
+> Starter schema created by `m3l init`.
+> Run `m3l validate` after each change.
+
+## Timestampable ::interface
+
+- created_at: timestamp = now() "When the record was created"
+- updated_at: timestamp = now() "When the record last changed"
+
+## UserRole ::enum
+
+- admin: "Administrator"
+- member: "Member"
+
+## User : Timestampable
+
+> A person who can sign in.
+
+- id: identifier @primary @generated
+- email: email @unique "Address used to sign in"
+- display_name: string(100) "Name shown to other users"
+- role: UserRole = "member" "Permissions granted to the user"
+"#;
+
+const ECOMMERCE: &str = r#"# Namespace: shop
+
+> Starter e-commerce schema created by `m3l init`:
+> customers place orders for products.
+
+## Timestampable ::interface
+
+- created_at: timestamp = now() "When the record was created"
+- updated_at: timestamp = now() "When the record last changed"
+
+## OrderStatus ::enum
+
+- pending: "Pending"
+- paid: "Paid"
+- shipped: "Shipped"
+- cancelled: "Cancelled"
+
+## Customer : Timestampable
+
+> Someone who buys from the store.
+
+- id: identifier @primary @generated
+- email: email @unique "Contact and sign-in address"
+- full_name: string(200) "Name used on invoices"
+
+## Product : Timestampable
+
+> An item for sale.
+
+- id: identifier @primary @generated
+- sku: string(40) @unique "Stock keeping unit"
+- title: string(200) @searchable "Name shown in the catalog"
+- price: decimal(10,2) @min(0) "Unit price"
+- stock: integer = 0 @min(0) "Units available"
+
+## Order : Timestampable
+
+> A customer's purchase.
+
+- id: identifier @primary @generated
+- customer_id: identifier @reference(Customer) "Customer placing the order"
+- status: OrderStatus = "pending" "Fulfilment stage"
+
+### Rollup
+- item_count: integer @rollup(OrderItem.order_id, count) "Number of line items"
+
+## OrderItem
+
+> One product line within an order.
+
+- id: identifier @primary @generated
+- order_id: identifier @reference(Order) "Order this line belongs to"
+- product_id: identifier @reference(Product) "Product being bought"
+- quantity: integer = 1 @min(1) "Units ordered"
+- unit_price: decimal(10,2) "Price at the time of purchase"
+"#;
+
+const BLOG: &str = r#"# Namespace: blog
+
+> Starter blog schema created by `m3l init`:
+> authors write posts, readers leave comments.
+
+## Timestampable ::interface
+
+- created_at: timestamp = now() "When the record was created"
+- updated_at: timestamp = now() "When the record last changed"
+
+## PostStatus ::enum
+
+- draft: "Draft"
+- published: "Published"
+- archived: "Archived"
+
+## Author : Timestampable
+
+> A person who writes posts.
+
+- id: identifier @primary @generated
+- email: email @unique "Contact and sign-in address"
+- display_name: string(100) "Byline shown on posts"
+- bio: text? "Short biography"
+
+## Post : Timestampable
+
+> An article on the blog.
+
+- id: identifier @primary @generated
+- author_id: identifier @reference(Author) "Author of the post"
+- title: string(200) @searchable "Headline shown above the post"
+- slug: string(200) @unique "URL path segment"
+- body: text "Article content in Markdown"
+- status: PostStatus = "draft" "Publication stage"
+- published_at: timestamp? "When the post went live"
+
+### Lookup
+- byline: string @lookup(author_id.display_name) "Display name of the author"
+
+## Comment : Timestampable
+
+> A reader's response to a post.
+
+- id: identifier @primary @generated
+- post_id: identifier @reference(Post) "Post being discussed"
+- author_email: email "Commenter's address, never shown"
+- body: text "Comment content"
+"#;
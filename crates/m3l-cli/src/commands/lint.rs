@@ -1,17 +1,29 @@
 use std::path::Path;
+use std::time::Instant;
 
 use m3l_lint::{LintConfig, Linter};
 
-use crate::build_ast;
+use crate::commands::snippet::{render_snippets, Snippet, Sources};
+use crate::reader::find_project_config;
+use crate::{build_ast, elapsed_ms};
 
-pub fn run_lint(input_path: &Path, format: &str) -> Result<String, String> {
+/// `m3l lint`: the report and the number of warning-severity findings.
+pub fn run_lint(input_path: &Path, format: &str, color: bool) -> Result<(String, usize), String> {
     let ast = build_ast(input_path)?;
-
-    let config = LintConfig::default();
-    let linter = Linter::new(config);
+    let linter = Linter::new(lint_config(input_path)?);
+    let start = Instant::now();
     let results = linter.lint(&ast);
+    tracing::info!(
+        findings = results.len(),
+        elapsed_ms = elapsed_ms(start),
+        "linted"
+    );
+    let warning_count = results
+        .iter()
+        .filter(|d| d.severity == m3l_lint::LintSeverity::Warning)
+        .count();
 
-    match format {
+    let output = match format {
         "json" => {
             let json = serde_json::to_string_pretty(&serde_json::json!({
                 "diagnostics": results,
@@ -21,27 +33,34 @@ pub fn run_lint(input_path: &Path, format: &str) -> Result<String, String> {
                 }
             }))
             .map_err(|e| format!("JSON serialization error: {e}"))?;
-            Ok(json)
+            json
         }
         "sarif" => {
             let sarif = build_sarif(&results, &linter);
             serde_json::to_string_pretty(&sarif)
-                .map_err(|e| format!("SARIF serialization error: {e}"))
+                .map_err(|e| format!("SARIF serialization error: {e}"))?
         }
         _ => {
             // Human-readable format
+            let snippets: Vec<Snippet> = results
+                .iter()
+                .map(|d| Snippet {
+                    severity: match d.severity {
+                        m3l_lint::LintSeverity::Error => "error",
+                        m3l_lint::LintSeverity::Warning => "warning",
+                        m3l_lint::LintSeverity::Info => "info",
+                    },
+                    code: &d.rule,
+                    file: &d.file,
+                    line: d.line,
+                    col: d.col,
+                    message: &d.message,
+                })
+                .collect();
             let mut lines: Vec<String> = Vec::new();
-
-            for d in &results {
-                let severity = match d.severity {
-                    m3l_lint::LintSeverity::Error => "error",
-                    m3l_lint::LintSeverity::Warning => "warning",
-                    m3l_lint::LintSeverity::Info => "info",
-                };
-                lines.push(format!(
-                    "{}:{}:{} {}[{}]: {}",
-                    d.file, d.line, d.col, severity, d.rule, d.message
-                ));
+            if !snippets.is_empty() {
+                lines.push(render_snippets(&snippets, &mut Sources::default(), color));
+                lines.push(String::new());
             }
 
             let count = results.len();
@@ -52,12 +71,37 @@ pub fn run_lint(input_path: &Path, format: &str) -> Result<String, String> {
                 "{count} lint {issue_word} in {file_count} {file_word}."
             ));
 
-            Ok(lines.join("\n"))
+            lines.join("\n")
         }
-    }
+    };
+    Ok((output, warning_count))
+}
+
+/// The `lint` section of the nearest project config.
+pub(crate) fn lint_config(input_path: &Path) -> Result<LintConfig, String> {
+    Ok(find_project_config(input_path)?
+        .and_then(|p| p.config.lint)
+        .unwrap_or_default())
 }
 
 fn build_sarif(results: &[m3l_lint::LintDiagnostic], linter: &Linter) -> serde_json::Value {
+    sarif_log(vec![sarif_run(results, linter)])
+}
+
+/// A SARIF 2.1.0 log holding the given runs.
+pub(crate) fn sarif_log(runs: Vec<serde_json::Value>) -> serde_json::Value {
+    serde_json::json!({
+        "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/main/sarif-2.1/schema/sarif-schema-2.1.0.json",
+        "version": "2.1.0",
+        "runs": runs
+    })
+}
+
+/// The `m3l-lint` run: every rule of `linter` and the findings.
+pub(crate) fn sarif_run(
+    results: &[m3l_lint::LintDiagnostic],
+    linter: &Linter,
+) -> serde_json::Value {
     let rule_descriptors: Vec<serde_json::Value> = linter
         .rules()
         .iter()
@@ -102,17 +146,13 @@ fn build_sarif(results: &[m3l_lint::LintDiagnostic], linter: &Linter) -> serde_j
         .collect();
 
     serde_json::json!({
-        "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/main/sarif-2.1/schema/sarif-schema-2.1.0.json",
-        "version": "2.1.0",
-        "runs": [{
-            "tool": {
-                "driver": {
-                    "name": "m3l-lint",
-                    "version": env!("CARGO_PKG_VERSION"),
-                    "rules": rule_descriptors
-                }
-            },
-            "results": sarif_results
-        }]
+        "tool": {
+            "driver": {
+                "name": "m3l-lint",
+                "version": env!("CARGO_PKG_VERSION"),
+                "rules": rule_descriptors
+            }
+        },
+        "results": sarif_results
     })
 }
@@ -0,0 +1,228 @@
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use m3l_core::{parse_string, resolve, validate, M3lAst, ValidateOptions};
+use m3l_lint::{LintConfig, Linter};
+use serde_json::{json, Value};
+
+use crate::commands::diff::{run_diff, DiffInputs, DiffOptions};
+use crate::commands::explain::run_explain;
+use crate::commands::lint::lint_config;
+use crate::{build_ast, validate_settings};
+
+/// Protocol revisions this server speaks, newest first. The tool surface is
+/// the same in each.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const DEFAULT_FILENAME: &str = "input.m3l.md";
+
+/// `m3l mcp`: a Model Context Protocol server on stdin/stdout, one JSON-RPC
+/// message per line. Returns when stdin is closed.
+pub fn run_mcp() -> Result<(), String> {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|e| format!("Failed to read stdin: {e}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some(reply) = handle_line(&line) else {
+            continue;
+        };
+        writeln!(stdout, "{reply}")
+            .and_then(|()| stdout.flush())
+            .map_err(|e| format!("Failed to write stdout: {e}"))?;
+    }
+    Ok(())
+}
+
+/// The reply to one line of input; `None` for notifications.
+fn handle_line(line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(m) => m,
+        Err(e) => {
+            return Some(error_reply(
+                Value::Null,
+                -32700,
+                &format!("Parse error: {e}"),
+            ))
+        }
+    };
+    match message {
+        // Batches were dropped in 2025-06-18 but older clients may send them
+        Value::Array(batch) => {
+            let replies: Vec<Value> = batch.into_iter().filter_map(handle_message).collect();
+            (!replies.is_empty()).then_some(Value::Array(replies))
+        }
+        message => handle_message(message),
+    }
+}
+
+fn handle_message(message: Value) -> Option<Value> {
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        // A response to us (we send no requests) or not JSON-RPC at all
+        return id.map(|id| error_reply(id, -32600, "Invalid request: missing method"));
+    };
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    tracing::debug!(method, "mcp request");
+
+    let result = match method {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => call_tool(&params),
+        _ if id.is_none() => return None,
+        _ => Err((-32601, format!("Method not found: {method}"))),
+    };
+    // Notifications (no id) never get a reply
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_reply(id, code, &message),
+    })
+}
+
+fn error_reply(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params["protocolVersion"].as_str().unwrap_or_default();
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| **v == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "m3l", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Tools for M3L (.m3l.md) data model schemas. Pass `path` (a file, directory or glob relative to the server's working directory) or inline `content`.",
+    })
+}
+
+fn tools() -> Value {
+    let source = json!({
+        "path": {
+            "type": "string",
+            "description": "File, directory or glob pattern of M3L files"
+        },
+        "content": {
+            "type": "string",
+            "description": "M3L source text, instead of path"
+        }
+    });
+    let with = |extra: Value| {
+        let mut properties = source.clone();
+        if let (Some(map), Value::Object(extra)) = (properties.as_object_mut(), extra) {
+            map.extend(extra);
+        }
+        json!({ "type": "object", "properties": properties })
+    };
+    json!([
+        {
+            "name": "parse_schema",
+            "description": "Parse and resolve M3L files into the JSON AST (models, enums, views, fields and their attributes).",
+            "inputSchema": with(json!({})),
+        },
+        {
+            "name": "validate_schema",
+            "description": "Validate M3L files and return errors and warnings with codes (see explain_code) and locations. With a path, the project config's validate settings apply.",
+            "inputSchema": with(json!({
+                "strict": { "type": "boolean", "description": "Also report style warnings" }
+            })),
+        },
+        {
+            "name": "lint_schema",
+            "description": "Run the style and quality lint rules over M3L files. With a path, the project config's rule levels apply.",
+            "inputSchema": with(json!({})),
+        },
+        {
+            "name": "diff_schemas",
+            "description": "Compare two M3L schemas (files or directories) and list added, removed and changed models and fields.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "left": { "type": "string", "description": "Path of the old schema" },
+                    "right": { "type": "string", "description": "Path of the new schema" }
+                },
+                "required": ["left", "right"]
+            },
+        },
+        {
+            "name": "explain_code",
+            "description": "Explain a validation code (M3L-E005, W001) or lint rule id, with a bad and a fixed example. Omit code to list them all.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "code": { "type": "string", "description": "Code or lint rule id" }
+                }
+            },
+        }
+    ])
+}
+
+/// `tools/call`: unknown tools are protocol errors; a tool that fails
+/// returns its message with `isError` so the model can see it.
+fn call_tool(params: &Value) -> Result<Value, (i64, String)> {
+    let name = params["name"]
+        .as_str()
+        .ok_or((-32602, "Missing tool name".to_string()))?;
+    let args = &params["arguments"];
+    let output = match name {
+        "parse_schema" => load_ast(args).and_then(|ast| to_json(&ast)),
+        "validate_schema" => validate_tool(args),
+        "lint_schema" => lint_tool(args),
+        "diff_schemas" => match (args["left"].as_str(), args["right"].as_str()) {
+            (Some(left), Some(right)) => run_diff(
+                DiffInputs::Paths(Path::new(left), Path::new(right)),
+                "human",
+                &DiffOptions::default(),
+            ),
+            _ => Err("diff_schemas needs both \"left\" and \"right\"".into()),
+        },
+        "explain_code" => run_explain(args["code"].as_str(), "human"),
+        _ => return Err((-32602, format!("Unknown tool: {name}"))),
+    };
+    tracing::info!(tool = name, ok = output.is_ok(), "mcp tool call");
+    let (text, is_error) = match output {
+        Ok(text) => (text, false),
+        Err(e) => (e, true),
+    };
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    }))
+}
+
+fn load_ast(args: &Value) -> Result<M3lAst, String> {
+    match (args["path"].as_str(), args["content"].as_str()) {
+        (Some(path), None) => build_ast(Path::new(path)),
+        (None, Some(content)) => Ok(resolve(&[parse_string(content, DEFAULT_FILENAME)], None)),
+        (Some(_), Some(_)) => Err("Pass either \"path\" or \"content\", not both".into()),
+        (None, None) => Err("Pass \"path\" or \"content\"".into()),
+    }
+}
+
+fn validate_tool(args: &Value) -> Result<String, String> {
+    let strict = args["strict"].as_bool().unwrap_or(false);
+    let strict = match args["path"].as_str() {
+        Some(path) => validate_settings(Path::new(path), strict, None)?.0,
+        None => strict,
+    };
+    let ast = load_ast(args)?;
+    to_json(&validate(&ast, &ValidateOptions { strict }))
+}
+
+fn lint_tool(args: &Value) -> Result<String, String> {
+    let config = match args["path"].as_str() {
+        Some(path) => lint_config(Path::new(path))?,
+        None => LintConfig::default(),
+    };
+    let ast = load_ast(args)?;
+    to_json(&json!({ "diagnostics": Linter::new(config).lint(&ast) }))
+}
+
+fn to_json(value: &impl serde::Serialize) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| format!("JSON serialization error: {e}"))
+}
@@ -0,0 +1,156 @@
+use std::path::Path;
+
+use m3l_core::{EnumNode, M3lAst, ModelNode};
+
+use crate::build_ast;
+use crate::commands::format::{
+    format_enum, format_model, format_model_body, format_view, labelled_name, order_models,
+    FormatOptions, ModelOrder,
+};
+use crate::reader::find_project_config;
+
+/// Elements sharing one namespace, in the order they are written.
+struct Group<'a> {
+    namespace: Option<String>,
+    interfaces: Vec<&'a ModelNode>,
+    enums: Vec<&'a EnumNode>,
+    models: Vec<&'a ModelNode>,
+    views: Vec<&'a ModelNode>,
+}
+
+/// `m3l merge`: the project at `input_path` as one M3L document, written to
+/// `output` or returned for stdout.
+pub fn run_merge(input_path: &Path, output: Option<&Path>) -> Result<String, String> {
+    let ast = build_ast(input_path)?;
+    let mut options: FormatOptions = find_project_config(input_path)?
+        .and_then(|p| p.config.format)
+        .unwrap_or_default();
+    // Parents and referenced models first, so the document reads top-down
+    options.sort_models = ModelOrder::Dependency;
+    let merged = merge_ast(&ast, &options);
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, format!("{merged}\n"))
+                .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+            Ok(format!(
+                "Merged {} into {} ({}, {})",
+                count(ast.sources.len(), "file"),
+                path.display(),
+                count(
+                    ast.models.len() + ast.interfaces.len() + ast.views.len(),
+                    "model"
+                ),
+                count(ast.enums.len(), "enum"),
+            ))
+        }
+        None => Ok(merged),
+    }
+}
+
+/// One document with a section per namespace. Elements without a namespace
+/// come first, since a `# Namespace:` header applies until the next one;
+/// the other namespaces follow the dependency order of their elements.
+/// `@import` lines are dropped: everything they pulled in is now inline.
+pub fn merge_ast(ast: &M3lAst, options: &FormatOptions) -> String {
+    let mut groups: Vec<Group> = Vec::new();
+    let mut group_of = |namespace: Option<&str>| -> usize {
+        if let Some(i) = groups
+            .iter()
+            .position(|g| g.namespace.as_deref() == namespace)
+        {
+            return i;
+        }
+        groups.push(Group {
+            namespace: namespace.map(str::to_string),
+            interfaces: Vec::new(),
+            enums: Vec::new(),
+            models: Vec::new(),
+            views: Vec::new(),
+        });
+        groups.len() - 1
+    };
+
+    let parents_first: Vec<ModelNode> = ast
+        .interfaces
+        .iter()
+        .chain(ast.models.iter())
+        .cloned()
+        .collect();
+    let placement: Vec<(usize, &ModelNode)> = order_models(&parents_first, options.sort_models)
+        .into_iter()
+        .map(|m| (group_of(m.namespace.as_deref()), m))
+        .collect();
+    let views: Vec<(usize, &ModelNode)> = order_models(&ast.views, options.sort_models)
+        .into_iter()
+        .map(|v| (group_of(v.namespace.as_deref()), v))
+        .collect();
+    let enums: Vec<(usize, &EnumNode)> = ast
+        .enums
+        .iter()
+        .map(|e| (group_of(e.namespace.as_deref()), e))
+        .collect();
+    for (i, element) in placement {
+        let group = &mut groups[i];
+        if ast
+            .interfaces
+            .iter()
+            .any(|iface| iface.name == element.name)
+        {
+            group.interfaces.push(element);
+        } else {
+            group.models.push(element);
+        }
+    }
+    for (i, view) in views {
+        groups[i].views.push(view);
+    }
+    for (i, e) in enums {
+        groups[i].enums.push(e);
+    }
+    groups.sort_by_key(|g| g.namespace.is_some());
+
+    let mut lines: Vec<String> = Vec::new();
+    if let Some(ref name) = ast.project.name {
+        lines.push(format!("# {name}"));
+        lines.push(String::new());
+    }
+    for group in &groups {
+        if let Some(ref namespace) = group.namespace {
+            lines.push(format!("# Namespace: {namespace}"));
+            lines.push(String::new());
+        }
+        for iface in &group.interfaces {
+            lines.push(format!(
+                "## {} ::interface",
+                labelled_name(&iface.name, iface.label.as_deref(), &iface.labels)
+            ));
+            format_model_body(&mut lines, iface, options);
+            lines.push(String::new());
+        }
+        for e in &group.enums {
+            format_enum(&mut lines, e);
+            lines.push(String::new());
+        }
+        for model in &group.models {
+            format_model(&mut lines, model, ast, options);
+            lines.push(String::new());
+        }
+        for view in &group.views {
+            format_view(&mut lines, view, options);
+            lines.push(String::new());
+        }
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+fn count(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{n} {noun}s")
+    }
+}
@@ -0,0 +1,736 @@
+use clap::ValueEnum;
+use m3l_core::{
+    CheckDef, DefaultValueType, FieldKind, FieldNode, KeyDef, M3lAst, ModelNode, ModelType,
+    ParamValue,
+};
+
+use crate::commands::diff::{
+    diff_asts, ChangeKind, DiffInputs, DiffOptions, EntityKind, SchemaChange,
+};
+
+/// Kind of migration `m3l diff --migration` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MigrationKind {
+    /// DDL statements for a relational database.
+    Sql,
+}
+
+/// SQL dialect of the migration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Dialect {
+    #[default]
+    Postgres,
+}
+
+impl Dialect {
+    /// Column type for an M3L type; `None` for names that are not built-in
+    /// types (models and enums).
+    fn type_name(self, m3l_type: &str, params: &[String]) -> Option<String> {
+        let sized = |name: &str, default: &str| {
+            if params.is_empty() {
+                default.to_string()
+            } else {
+                format!("{name}({})", params.join(", "))
+            }
+        };
+        let name = match self {
+            Dialect::Postgres => match m3l_type {
+                "string" => sized("VARCHAR", "TEXT"),
+                "text" => "TEXT".into(),
+                "integer" => "INTEGER".into(),
+                "long" => "BIGINT".into(),
+                "decimal" => sized("NUMERIC", "NUMERIC"),
+                "float" => "DOUBLE PRECISION".into(),
+                "boolean" => "BOOLEAN".into(),
+                "date" => "DATE".into(),
+                "time" => "TIME".into(),
+                "timestamp" | "datetime" => "TIMESTAMPTZ".into(),
+                "identifier" => "UUID".into(),
+                "binary" => "BYTEA".into(),
+                "email" => "VARCHAR(320)".into(),
+                "phone" => "VARCHAR(20)".into(),
+                "url" => "VARCHAR(2048)".into(),
+                "money" => "NUMERIC(19, 4)".into(),
+                "percentage" => "NUMERIC(5, 2)".into(),
+                // No column type holds one of several types
+                "object" | "json" | "map" | "union" | "tuple" => "JSONB".into(),
+                _ => return None,
+            },
+        };
+        Some(name)
+    }
+}
+
+/// `m3l diff --migration sql`: the statements that take a database from the
+/// old schema to the new one, in one transaction. Detected
+/// field renames become `RENAME COLUMN`; `@reference`/`@fk` fields get
+/// foreign key constraints, added and dropped as they change. Drops and
+/// column type changes may lose data; without `allow_destructive` they are
+/// written as comments only.
+pub fn run_migration(
+    inputs: DiffInputs,
+    dialect: Dialect,
+    allow_destructive: bool,
+    options: &DiffOptions,
+) -> Result<String, String> {
+    options.check()?;
+    let (left_ast, right_ast) = inputs.load()?;
+    let changes = diff_asts(&left_ast, &right_ast, options);
+    let migration = Migration {
+        left: &left_ast,
+        right: &right_ast,
+        dialect,
+    };
+    Ok(migration.render(&changes, allow_destructive))
+}
+
+struct Statement {
+    sql: String,
+    destructive: bool,
+}
+
+struct Migration<'a> {
+    left: &'a M3lAst,
+    right: &'a M3lAst,
+    dialect: Dialect,
+}
+
+impl Migration<'_> {
+    fn render(&self, changes: &[SchemaChange], allow_destructive: bool) -> String {
+        let statements = self.statements(changes);
+        if statements.is_empty() {
+            return "-- No schema changes.".into();
+        }
+        let skipped = statements
+            .iter()
+            .filter(|s| s.destructive && !allow_destructive)
+            .count();
+
+        let mut lines = Vec::new();
+        if skipped > 0 {
+            lines.push(format!(
+                "-- {skipped} destructive {} commented out; pass --allow-destructive to include {}.",
+                if skipped == 1 {
+                    "statement"
+                } else {
+                    "statements"
+                },
+                if skipped == 1 { "it" } else { "them" }
+            ));
+        }
+        lines.push("BEGIN;".into());
+        for statement in &statements {
+            if statement.destructive && !allow_destructive {
+                lines.extend(statement.sql.lines().map(|l| format!("-- {l}")));
+            } else {
+                lines.push(statement.sql.clone());
+            }
+        }
+        lines.push("COMMIT;".into());
+        lines.join("\n")
+    }
+
+    /// Statements in a safe order: new tables, dropped constraints, new
+    /// columns, changed columns, new constraints, dropped columns, then
+    /// dropped tables. Foreign keys come after every table they may point
+    /// at exists, and a table is dropped before the tables it references.
+    fn statements(&self, changes: &[SchemaChange]) -> Vec<Statement> {
+        let mut creates = Vec::new();
+        let mut drop_constraints = Vec::new();
+        let mut adds = Vec::new();
+        let mut alters = Vec::new();
+        let mut add_constraints = Vec::new();
+        let mut drops = Vec::new();
+        let mut removed = Vec::new();
+        for change in changes {
+            match (change.change, change.entity) {
+                (ChangeKind::Added, EntityKind::Model) => {
+                    if let Some(model) = table(self.right, &change.path) {
+                        creates.push(safe(self.create_table(model)));
+                        add_constraints.extend(
+                            model
+                                .fields
+                                .iter()
+                                .filter(|f| is_column(f))
+                                .filter_map(|f| self.add_foreign_key(&model.name, f))
+                                .map(safe),
+                        );
+                    }
+                }
+                (ChangeKind::Removed, EntityKind::Model) => removed.push(change.path.as_str()),
+                (ChangeKind::Modified, EntityKind::Model) => self.alter_table_constraints(
+                    &change.path,
+                    &mut drop_constraints,
+                    &mut add_constraints,
+                ),
+                (_, EntityKind::Field) => {
+                    let Some((model, field)) = change.path.split_once('.') else {
+                        continue;
+                    };
+                    let previous = match &change.previous_path {
+                        Some(path) => path.split_once('.').map_or(field, |(_, f)| f),
+                        None => field,
+                    };
+                    let left = table(self.left, model).and_then(|m| column(m, previous));
+                    let right = table(self.right, model).and_then(|m| column(m, field));
+                    let prefix = format!("ALTER TABLE {}", quote(model));
+                    match (left, right) {
+                        (None, Some(right)) => {
+                            let definition = self.column_definition(
+                                model,
+                                right,
+                                column_checks(self.right, model, field),
+                            );
+                            let mut sql = format!("{prefix} ADD COLUMN {definition};");
+                            // Existing rows get the default, and there is none
+                            if !right.nullable && default_value(right).is_none() {
+                                sql = format!(
+                                    "-- {} is NOT NULL with no default; this fails on a table with rows.\n{sql}",
+                                    quote(field)
+                                );
+                            }
+                            adds.push(safe(sql));
+                            add_constraints.extend(self.add_foreign_key(model, right).map(safe));
+                        }
+                        (Some(_), None) => drops.push(Statement {
+                            sql: format!("{prefix} DROP COLUMN {};", quote(previous)),
+                            destructive: true,
+                        }),
+                        (Some(left), Some(right)) => {
+                            if previous != field {
+                                alters.push(safe(format!(
+                                    "{prefix} RENAME COLUMN {} TO {};",
+                                    quote(previous),
+                                    quote(field)
+                                )));
+                            }
+                            alters.extend(self.alter_column(&prefix, left, right));
+                            self.alter_constraints(
+                                model,
+                                (left, right),
+                                &mut drop_constraints,
+                                &mut alters,
+                                &mut add_constraints,
+                            );
+                        }
+                        (None, None) => {}
+                    }
+                }
+                // Views, enums (stored as text) and interfaces have no tables
+                _ => {}
+            }
+        }
+        creates
+            .into_iter()
+            .chain(drop_constraints)
+            .chain(adds)
+            .chain(alters)
+            .chain(add_constraints)
+            .chain(drops)
+            .chain(self.drop_tables(&removed))
+            .collect()
+    }
+
+    /// `DROP TABLE` for the removed models, each before the removed tables
+    /// it references. A foreign key closing a reference cycle between them
+    /// is dropped first.
+    fn drop_tables(&self, removed: &[&str]) -> Vec<Statement> {
+        let mut order = DropOrder {
+            visiting: Vec::new(),
+            done: Vec::new(),
+            broken: Vec::new(),
+        };
+        for name in removed {
+            self.visit_dropped(name, removed, &mut order);
+        }
+        let tables = order.done.iter().rev().map(|name| Statement {
+            sql: format!("DROP TABLE {};", quote(name)),
+            destructive: true,
+        });
+        order.broken.into_iter().chain(tables).collect()
+    }
+
+    /// Depth-first over the foreign keys of the removed model `name`,
+    /// finishing the tables it references before it.
+    fn visit_dropped<'n>(&self, name: &'n str, removed: &[&'n str], order: &mut DropOrder<'n>) {
+        if order.done.contains(&name) || order.visiting.contains(&name) {
+            return;
+        }
+        order.visiting.push(name);
+        for field in table(self.left, name)
+            .into_iter()
+            .flat_map(|m| m.fields.iter())
+            .filter(|f| is_column(f) && references_clause(self.left, f).is_some())
+        {
+            let Some(target) = self
+                .left
+                .reference_target(field)
+                .and_then(|t| removed.iter().find(|r| **r == t.name))
+            else {
+                continue;
+            };
+            if *target == name {
+                continue;
+            }
+            if order.visiting.contains(target) {
+                order.broken.push(Statement {
+                    sql: format!(
+                        "ALTER TABLE {} DROP CONSTRAINT {};",
+                        quote(name),
+                        quote(&constraint_name(name, &field.name, "fkey"))
+                    ),
+                    destructive: true,
+                });
+            } else {
+                self.visit_dropped(target, removed, order);
+            }
+        }
+        order.visiting.retain(|n| *n != name);
+        order.done.push(name);
+    }
+
+    /// The table with its columns, each with its own checks, then the
+    /// model's natural keys as UNIQUE constraints and its table-level
+    /// checks. Foreign keys are added once every table exists.
+    fn create_table(&self, model: &ModelNode) -> String {
+        let columns = model.fields.iter().filter(|f| is_column(f)).map(|f| {
+            let checks = model
+                .sections
+                .checks
+                .iter()
+                .filter(|c| c.field.as_deref() == Some(f.name.as_str()));
+            format!("  {}", self.column_definition(&model.name, f, checks))
+        });
+        let keys = model
+            .sections
+            .keys
+            .iter()
+            .map(|k| format!("  {}", key_constraint(&model.name, k)));
+        let table_checks = table_checks(model).enumerate().map(|(i, c)| {
+            format!(
+                "  CONSTRAINT {} CHECK ({})",
+                quote(&check_name(&model.name, None, i)),
+                c.expression
+            )
+        });
+        let lines: Vec<String> = columns.chain(keys).chain(table_checks).collect();
+        format!(
+            "CREATE TABLE {} (\n{}\n);",
+            quote(&model.name),
+            lines.join(",\n")
+        )
+    }
+
+    fn column_definition<'c>(
+        &self,
+        table: &str,
+        field: &FieldNode,
+        checks: impl Iterator<Item = &'c CheckDef>,
+    ) -> String {
+        let mut definition = format!("{} {}", quote(&field.name), self.sql_type(field));
+        if is_primary_key(field) {
+            definition.push_str(" PRIMARY KEY");
+        } else {
+            if !field.nullable {
+                definition.push_str(" NOT NULL");
+            }
+            if is_unique(field) {
+                definition.push_str(" UNIQUE");
+            }
+        }
+        if let Some(default) = default_value(field) {
+            definition.push_str(&format!(" DEFAULT {default}"));
+        }
+        for (i, check) in checks.enumerate() {
+            definition.push_str(&format!(
+                " CONSTRAINT {} CHECK ({})",
+                quote(&check_name(table, Some(&field.name), i)),
+                check.expression
+            ));
+        }
+        definition
+    }
+
+    fn alter_column(&self, prefix: &str, left: &FieldNode, right: &FieldNode) -> Vec<Statement> {
+        let name = quote(&right.name);
+        let mut statements = Vec::new();
+        let (before, after) = (self.sql_type(left), self.sql_type(right));
+        if before != after {
+            statements.push(Statement {
+                sql: format!("{prefix} ALTER COLUMN {name} TYPE {after} USING {name}::{after};"),
+                destructive: true,
+            });
+        }
+        if left.nullable != right.nullable && !is_primary_key(right) {
+            let action = if right.nullable {
+                "DROP NOT NULL"
+            } else {
+                "SET NOT NULL"
+            };
+            statements.push(safe(format!("{prefix} ALTER COLUMN {name} {action};")));
+        }
+        let default = default_value(right);
+        if default_value(left) != default {
+            let action = match default {
+                Some(default) => format!("SET DEFAULT {default}"),
+                None => "DROP DEFAULT".into(),
+            };
+            statements.push(safe(format!("{prefix} ALTER COLUMN {name} {action};")));
+        }
+        statements
+    }
+
+    /// Constraint changes of a column present on both sides: a changed
+    /// `@reference` drops the old foreign key and adds the new one,
+    /// `@unique` adds or drops the UNIQUE constraint, changed `@check`s
+    /// replace the column's CHECK constraints, and a renamed column keeps
+    /// its constraints' names in step.
+    fn alter_constraints(
+        &self,
+        model: &str,
+        (left, right): (&FieldNode, &FieldNode),
+        drops: &mut Vec<Statement>,
+        renames: &mut Vec<Statement>,
+        adds: &mut Vec<Statement>,
+    ) {
+        let prefix = format!("ALTER TABLE {}", quote(model));
+        let rename = |suffix: &str| {
+            safe(format!(
+                "{prefix} RENAME CONSTRAINT {} TO {};",
+                quote(&constraint_name(model, &left.name, suffix)),
+                quote(&constraint_name(model, &right.name, suffix))
+            ))
+        };
+        let drop = |suffix: &str| {
+            safe(format!(
+                "{prefix} DROP CONSTRAINT {};",
+                quote(&constraint_name(model, &left.name, suffix))
+            ))
+        };
+
+        let before = references_clause(self.left, left);
+        let after = references_clause(self.right, right);
+        if before != after {
+            if before.is_some() {
+                drops.push(drop("fkey"));
+            }
+            adds.extend(self.add_foreign_key(model, right).map(safe));
+        } else if before.is_some() && left.name != right.name {
+            renames.push(rename("fkey"));
+        }
+
+        match (is_unique(left), is_unique(right)) {
+            (true, false) => drops.push(drop("key")),
+            (false, true) => adds.push(safe(format!(
+                "{prefix} ADD CONSTRAINT {} UNIQUE ({});",
+                quote(&constraint_name(model, &right.name, "key")),
+                quote(&right.name)
+            ))),
+            (true, true) if left.name != right.name => renames.push(rename("key")),
+            _ => {}
+        }
+
+        let before: Vec<&str> = column_checks(self.left, model, &left.name)
+            .map(|c| c.expression.as_str())
+            .collect();
+        let after: Vec<&str> = column_checks(self.right, model, &right.name)
+            .map(|c| c.expression.as_str())
+            .collect();
+        if before != after {
+            drops.extend(before.iter().enumerate().map(|(i, _)| {
+                safe(format!(
+                    "{prefix} DROP CONSTRAINT {};",
+                    quote(&check_name(model, Some(&left.name), i))
+                ))
+            }));
+            adds.extend(after.iter().enumerate().map(|(i, expression)| {
+                safe(format!(
+                    "{prefix} ADD CONSTRAINT {} CHECK ({expression});",
+                    quote(&check_name(model, Some(&right.name), i))
+                ))
+            }));
+        } else if left.name != right.name {
+            renames.extend(before.iter().enumerate().map(|(i, _)| {
+                safe(format!(
+                    "{prefix} RENAME CONSTRAINT {} TO {};",
+                    quote(&check_name(model, Some(&left.name), i)),
+                    quote(&check_name(model, Some(&right.name), i))
+                ))
+            }));
+        }
+    }
+
+    /// Table-level constraint changes of a model present on both sides:
+    /// natural keys are added and dropped as they change, and changed model
+    /// `@check`s replace the table's CHECK constraints.
+    fn alter_table_constraints(
+        &self,
+        model: &str,
+        drops: &mut Vec<Statement>,
+        adds: &mut Vec<Statement>,
+    ) {
+        let (Some(left), Some(right)) = (table(self.left, model), table(self.right, model)) else {
+            return;
+        };
+        let prefix = format!("ALTER TABLE {}", quote(model));
+        let has_key =
+            |m: &ModelNode, key: &KeyDef| m.sections.keys.iter().any(|k| k.fields == key.fields);
+        drops.extend(
+            left.sections
+                .keys
+                .iter()
+                .filter(|k| !has_key(right, k))
+                .map(|k| {
+                    safe(format!(
+                        "{prefix} DROP CONSTRAINT {};",
+                        quote(&key_name(model, k))
+                    ))
+                }),
+        );
+        adds.extend(
+            right
+                .sections
+                .keys
+                .iter()
+                .filter(|k| !has_key(left, k))
+                .map(|k| safe(format!("{prefix} ADD {};", key_constraint(model, k)))),
+        );
+
+        let before: Vec<&str> = table_checks(left).map(|c| c.expression.as_str()).collect();
+        let after: Vec<&str> = table_checks(right).map(|c| c.expression.as_str()).collect();
+        if before != after {
+            drops.extend(before.iter().enumerate().map(|(i, _)| {
+                safe(format!(
+                    "{prefix} DROP CONSTRAINT {};",
+                    quote(&check_name(model, None, i))
+                ))
+            }));
+            adds.extend(after.iter().enumerate().map(|(i, expression)| {
+                safe(format!(
+                    "{prefix} ADD CONSTRAINT {} CHECK ({expression});",
+                    quote(&check_name(model, None, i))
+                ))
+            }));
+        }
+    }
+
+    /// `ADD CONSTRAINT ... FOREIGN KEY` for a `@reference`/`@fk` column of
+    /// the table `model` in the new schema.
+    fn add_foreign_key(&self, model: &str, field: &FieldNode) -> Option<String> {
+        let references = references_clause(self.right, field)?;
+        Some(format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) {references};",
+            quote(model),
+            quote(&constraint_name(model, &field.name, "fkey")),
+            quote(&field.name)
+        ))
+    }
+
+    /// Column type of `field`. A field typed with a model name stores that
+    /// model's key, so it takes the key's column type.
+    fn sql_type(&self, field: &FieldNode) -> String {
+        let params: Vec<String> = field.params.iter().flatten().map(param_text).collect();
+        let name = field.field_type.as_deref().unwrap_or("text");
+        let base = self
+            .tuple_array_type(field)
+            .or_else(|| self.dialect.type_name(name, &params))
+            .or_else(|| self.key_of(name).map(|key| self.sql_type(key)))
+            .unwrap_or_else(|| self.dialect.type_name("text", &[]).unwrap_or_default());
+        if field.array {
+            format!("{base}[]")
+        } else {
+            base
+        }
+    }
+
+    /// Array column type for a tuple whose components share one built-in
+    /// type, `(float, float)`; other tuples are stored as JSON.
+    fn tuple_array_type(&self, field: &FieldNode) -> Option<String> {
+        let types: Vec<Option<String>> = field
+            .tuple
+            .iter()
+            .flatten()
+            .map(|m| {
+                let params: Vec<String> = m.params.iter().flatten().map(param_text).collect();
+                self.dialect.type_name(&m.member_type, &params)
+            })
+            .collect();
+        let first = types.first()?.clone()?;
+        types
+            .iter()
+            .all(|t| t.as_ref() == Some(&first))
+            .then(|| format!("{first}[]"))
+    }
+
+    /// Primary key field of the model named `name`, looked up in the new
+    /// schema first.
+    fn key_of(&self, name: &str) -> Option<&FieldNode> {
+        table(self.right, name)
+            .or_else(|| table(self.left, name))
+            .and_then(|m| m.fields.iter().find(|f| is_primary_key(f)))
+    }
+}
+
+/// Traversal state of [`Migration::drop_tables`].
+struct DropOrder<'n> {
+    visiting: Vec<&'n str>,
+    /// Finished tables, each after the tables it references.
+    done: Vec<&'n str>,
+    /// Foreign keys dropped to break reference cycles.
+    broken: Vec<Statement>,
+}
+
+fn param_text(param: &ParamValue) -> String {
+    match param {
+        ParamValue::String(s) => s.clone(),
+        ParamValue::Number(n) => n.to_string(),
+    }
+}
+
+fn safe(sql: String) -> Statement {
+    Statement {
+        sql,
+        destructive: false,
+    }
+}
+
+fn table<'a>(ast: &'a M3lAst, name: &str) -> Option<&'a ModelNode> {
+    ast.find_model(name)
+}
+
+/// Checks declared on the column `field` of the model `model`.
+fn column_checks<'a>(
+    ast: &'a M3lAst,
+    model: &'a str,
+    field: &'a str,
+) -> impl Iterator<Item = &'a CheckDef> {
+    table(ast, model)
+        .into_iter()
+        .flat_map(|m| m.sections.checks.iter())
+        .filter(move |c| c.field.as_deref() == Some(field))
+}
+
+/// `CONSTRAINT "<table>_<a>_<b>_key" UNIQUE ("a", "b")` for a natural key.
+fn key_constraint(table: &str, key: &KeyDef) -> String {
+    let fields: Vec<String> = key.fields.iter().map(|f| quote(f)).collect();
+    format!(
+        "CONSTRAINT {} UNIQUE ({})",
+        quote(&key_name(table, key)),
+        fields.join(", ")
+    )
+}
+
+/// Constraint name of a natural key, after its columns as PostgreSQL names
+/// an unnamed UNIQUE constraint: `<table>_<a>_<b>_key`.
+fn key_name(table: &str, key: &KeyDef) -> String {
+    constraint_name(table, &key.fields.join("_"), "key")
+}
+
+/// Checks declared on the model `model` itself rather than a column.
+fn table_checks(model: &ModelNode) -> impl Iterator<Item = &CheckDef> {
+    model.sections.checks.iter().filter(|c| c.field.is_none())
+}
+
+fn column<'a>(model: &'a ModelNode, name: &str) -> Option<&'a FieldNode> {
+    model
+        .fields
+        .iter()
+        .find(|f| f.name == name)
+        .filter(|f| is_column(f))
+}
+
+/// Lookup, rollup and computed fields are derived, not stored.
+fn is_column(field: &FieldNode) -> bool {
+    field.kind == FieldKind::Stored
+}
+
+/// `@unique` on a column that is not the primary key, which is unique
+/// already.
+fn is_unique(field: &FieldNode) -> bool {
+    !is_primary_key(field) && field.attributes.iter().any(|a| a.name == "unique")
+}
+
+/// `REFERENCES "Customer" ("id") ON DELETE ...` for a field whose
+/// `@reference`/`@fk` resolved to a table column. A target with a
+/// composite key has no single column to reference.
+fn references_clause(ast: &M3lAst, field: &FieldNode) -> Option<String> {
+    let reference = field.reference.as_ref()?;
+    let column = reference.field.as_deref()?;
+    let target = ast
+        .reference_target(field)
+        .filter(|m| m.model_type == ModelType::Model)?;
+    let mut clause = format!(
+        "REFERENCES {} ({}) ON DELETE {}",
+        quote(&target.name),
+        quote(column),
+        reference.on_delete.sql()
+    );
+    if let Some(on_update) = reference.on_update {
+        clause.push_str(&format!(" ON UPDATE {}", on_update.sql()));
+    }
+    Some(clause)
+}
+
+/// Constraint name as PostgreSQL picks it for a column constraint:
+/// `<table>_<column>_fkey`, `<table>_<column>_key`.
+fn constraint_name(table: &str, column: &str, suffix: &str) -> String {
+    format!("{table}_{column}_{suffix}")
+}
+
+/// Name of the `index`th CHECK constraint of a column, or of the table
+/// without one, numbered as PostgreSQL numbers clashing names:
+/// `<table>_<column>_check`, `<table>_<column>_check1`, `<table>_check`.
+fn check_name(table: &str, column: Option<&str>, index: usize) -> String {
+    let base = match column {
+        Some(column) => constraint_name(table, column, "check"),
+        None => format!("{table}_check"),
+    };
+    if index == 0 {
+        base
+    } else {
+        format!("{base}{index}")
+    }
+}
+
+fn is_primary_key(field: &FieldNode) -> bool {
+    field
+        .attributes
+        .iter()
+        .any(|a| a.name == "primary" || a.name == "pk")
+}
+
+fn default_value(field: &FieldNode) -> Option<String> {
+    let value = field.default_value.as_deref()?;
+    Some(match field.default_value_type {
+        Some(DefaultValueType::Expression) => value.to_string(),
+        // Array fields are array columns; anything else JSON is JSONB
+        Some(DefaultValueType::Json) => match field.default_json {
+            Some(serde_json::Value::Array(ref items)) if field.array && items.is_empty() => {
+                "'{}'".to_string()
+            }
+            Some(serde_json::Value::Array(ref items)) if field.array => {
+                let items: Vec<String> = items.iter().map(sql_literal).collect();
+                format!("ARRAY[{}]", items.join(", "))
+            }
+            _ => format!("'{}'::jsonb", value.replace('\'', "''")),
+        },
+        _ if value.parse::<f64>().is_ok() || value == "true" || value == "false" => {
+            value.to_string()
+        }
+        _ => format!("'{}'", value.replace('\'', "''")),
+    })
+}
+
+/// A JSON value as an SQL literal.
+fn sql_literal(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "NULL".to_string(),
+        serde_json::Value::Bool(_) | serde_json::Value::Number(_) => value.to_string(),
+        serde_json::Value::String(s) => format!("'{}'", s.replace('\'', "''")),
+        _ => format!("'{}'::jsonb", value.to_string().replace('\'', "''")),
+    }
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
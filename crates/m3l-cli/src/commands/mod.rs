@@ -1,3 +1,26 @@
 pub mod analyze;
+pub mod attributes;
+pub mod bench;
+pub mod check;
+pub mod completions;
+pub mod diff;
+pub mod docs;
+pub mod explain;
+pub mod explorer;
+pub mod export;
+pub mod find;
 pub mod format;
+pub mod generate;
+pub mod hook;
+pub mod init;
 pub mod lint;
+pub mod mcp;
+pub mod merge;
+pub mod migration;
+pub mod query;
+pub mod sample;
+pub mod serve;
+pub mod snippet;
+pub mod split;
+pub mod stats;
+pub mod watch;
@@ -0,0 +1,30 @@
+use std::path::Path;
+
+use m3l_core::extract;
+use serde_json::Value;
+
+use crate::build_ast;
+
+/// `m3l query`: evaluate a JSONPath or JSON Pointer against the resolved AST
+/// of `input_path` (see `m3l_core::extract` for the syntax). With `raw`,
+/// matches print one per line and strings without quotes, for shell loops.
+pub fn run_query(input_path: &Path, expression: &str, raw: bool) -> Result<String, String> {
+    let ast = build_ast(input_path)?;
+    let result = extract(&ast, expression)?;
+
+    if raw {
+        let items = match result {
+            Value::Array(items) => items,
+            other => vec![other],
+        };
+        return Ok(items
+            .iter()
+            .map(|item| match item {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"));
+    }
+    serde_json::to_string_pretty(&result).map_err(|e| format!("JSON serialization error: {e}"))
+}
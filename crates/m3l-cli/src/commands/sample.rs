@@ -0,0 +1,55 @@
+use std::fs;
+use std::path::Path;
+
+use clap::ValueEnum;
+
+/// Reference schema bundled with the CLI; copies of the conformance inputs
+/// of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Sample {
+    /// Products, orders and customers: references, lookups, rollups and indexes.
+    Ecommerce,
+    /// Posts and authors: inheritance, views, framework attributes and behaviors.
+    Blog,
+    /// Every data type, modifier and validation rule.
+    Types,
+}
+
+impl Sample {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Sample::Ecommerce => "01-ecommerce.m3l.md",
+            Sample::Blog => "02-blog-cms.m3l.md",
+            Sample::Types => "03-types-showcase.m3l.md",
+        }
+    }
+
+    pub fn content(self) -> &'static str {
+        match self {
+            Sample::Ecommerce => include_str!("../../samples/01-ecommerce.m3l.md"),
+            Sample::Blog => include_str!("../../samples/02-blog-cms.m3l.md"),
+            Sample::Types => include_str!("../../samples/03-types-showcase.m3l.md"),
+        }
+    }
+}
+
+/// `m3l sample`: write a bundled sample schema into `dir`, created if
+/// missing. An existing file is only replaced with `force`.
+pub fn run_sample(sample: Sample, dir: &Path, force: bool) -> Result<String, String> {
+    let path = dir.join(sample.file_name());
+    if path.exists() && !force {
+        return Err(format!(
+            "{} already exists (use --force to overwrite)",
+            path.display()
+        ));
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    fs::write(&path, sample.content())
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok([
+        format!("Created {}", path.display()),
+        String::new(),
+        format!("Next: m3l validate {}", path.display()),
+    ]
+    .join("\n"))
+}
@@ -0,0 +1,181 @@
+use std::io::{Read, Write};
+use std::time::Instant;
+
+use m3l_core::ffi::FfiResult;
+use m3l_core::{parse_multi_to_json, parse_to_json, validate_to_json};
+use m3l_lint::lint_to_json;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::commands::format::{format_content, FormatOptions};
+use crate::elapsed_ms;
+
+/// Largest request body accepted, in bytes.
+const MAX_BODY: u64 = 16 * 1024 * 1024;
+
+const DEFAULT_FILENAME: &str = "input.m3l.md";
+
+/// Body of every endpoint. `content` is the M3L source; `/parse` also
+/// accepts `files` (as `parse_multi`) instead. `options` is passed on as the
+/// options (or lint config) object of the matching FFI function.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ApiRequest {
+    content: Option<String>,
+    filename: Option<String>,
+    files: Option<Value>,
+    options: Option<Value>,
+}
+
+/// `m3l serve`: answer `POST /parse`, `/validate`, `/lint` and `/format`
+/// on `host:port` until the process is stopped. Responses use the FFI
+/// envelope (`{ success, data?, error? }`), with status 400 when `success`
+/// is false.
+pub fn run_serve(host: &str, port: u16) -> Result<(), String> {
+    let server = Server::http((host, port))
+        .map_err(|e| format!("Failed to listen on {host}:{port}: {e}"))?;
+    let address = server
+        .server_addr()
+        .to_ip()
+        .map_or_else(|| format!("{host}:{port}"), |a| a.to_string());
+    println!("Listening on http://{address} (POST /parse, /validate, /lint, /format)");
+    std::io::stdout().flush().ok();
+
+    for request in server.incoming_requests() {
+        handle(request);
+    }
+    Ok(())
+}
+
+fn handle(mut request: Request) {
+    let start = Instant::now();
+    let method = request.method().clone();
+    let path = request.url().split('?').next().unwrap_or("").to_string();
+
+    let (status, body) = match (&method, path.as_str()) {
+        // CORS preflight from browser playgrounds
+        (Method::Options, _) => (204, String::new()),
+        (Method::Post, "/parse" | "/validate" | "/lint" | "/format") => {
+            match read_body(&mut request) {
+                Ok(body) => respond(&path, &body),
+                Err((status, message)) => (status, error_json(&message)),
+            }
+        }
+        (_, "/parse" | "/validate" | "/lint" | "/format") => {
+            (405, error_json(&format!("{path} only accepts POST")))
+        }
+        _ => (
+            404,
+            error_json(&format!(
+                "No endpoint at {path}; use /parse, /validate, /lint or /format"
+            )),
+        ),
+    };
+
+    tracing::info!(
+        method = %method,
+        path = %path,
+        status,
+        elapsed_ms = elapsed_ms(start),
+        "handled request"
+    );
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+        .with_header(header("Access-Control-Allow-Origin", "*"))
+        .with_header(header("Access-Control-Allow-Methods", "POST, OPTIONS"))
+        .with_header(header("Access-Control-Allow-Headers", "Content-Type"));
+    if let Err(e) = request.respond(response) {
+        tracing::warn!(error = %e, "failed to send response");
+    }
+}
+
+fn read_body(request: &mut Request) -> Result<String, (u16, String)> {
+    if request
+        .body_length()
+        .is_some_and(|len| len as u64 > MAX_BODY)
+    {
+        return Err((413, format!("Request body exceeds {MAX_BODY} bytes")));
+    }
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY + 1)
+        .read_to_string(&mut body)
+        .map_err(|e| (400, format!("Failed to read request body: {e}")))?;
+    if body.len() as u64 > MAX_BODY {
+        return Err((413, format!("Request body exceeds {MAX_BODY} bytes")));
+    }
+    Ok(body)
+}
+
+/// Status and JSON body for a request to one of the endpoints.
+fn respond(path: &str, body: &str) -> (u16, String) {
+    let request: ApiRequest = match serde_json::from_str(body) {
+        Ok(r) => r,
+        Err(e) => return (400, error_json(&format!("Invalid request JSON: {e}"))),
+    };
+    let filename = request.filename.as_deref().unwrap_or(DEFAULT_FILENAME);
+    let options = request.options.unwrap_or_else(|| json!({}));
+
+    let result = match (path, request.content, request.files) {
+        ("/parse", None, Some(files)) => parse_multi_to_json(&files.to_string()),
+        ("/parse", Some(_), Some(_)) => {
+            return (
+                400,
+                error_json("Send either \"content\" or \"files\", not both"),
+            )
+        }
+        (_, _, Some(_)) => return (400, error_json("\"files\" is only accepted by /parse")),
+        (_, None, None) => return (400, error_json("Missing \"content\"")),
+        ("/parse", Some(content), None) => parse_to_json(&content, filename),
+        ("/validate", Some(content), None) => {
+            let mut options = options;
+            if let (Some(map), Some(name)) = (options.as_object_mut(), &request.filename) {
+                map.entry("filename").or_insert_with(|| json!(name));
+            }
+            validate_to_json(&content, &options.to_string())
+        }
+        ("/lint", Some(content), None) => lint_to_json(&content, &options.to_string()),
+        (_, Some(content), None) => format_json(&content, filename, options),
+    };
+
+    let success = serde_json::from_str::<Value>(&result)
+        .ok()
+        .and_then(|v| v["success"].as_bool())
+        .unwrap_or(false);
+    (if success { 200 } else { 400 }, result)
+}
+
+fn format_json(content: &str, filename: &str, options: Value) -> String {
+    let formatted = serde_json::from_value::<FormatOptions>(options)
+        .map_err(|e| format!("Invalid options JSON: {e}"))
+        .and_then(|options| format_content(content, filename, &options));
+    match formatted {
+        Ok(text) => envelope(FfiResult {
+            success: true,
+            data: Some(text),
+            error: None,
+        }),
+        Err(e) => error_json(&e),
+    }
+}
+
+fn error_json(message: &str) -> String {
+    envelope(FfiResult::<()> {
+        success: false,
+        data: None,
+        error: Some(message.to_string()),
+    })
+}
+
+fn envelope<T: serde::Serialize>(result: FfiResult<T>) -> String {
+    serde_json::to_string(&result).unwrap_or_else(|e| {
+        format!("{{\"success\":false,\"error\":\"JSON serialization error: {e}\"}}")
+    })
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("static header is valid")
+}
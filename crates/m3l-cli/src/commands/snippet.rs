@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use clap::ColorChoice;
+
+/// Whether output to a stream gets ANSI colors: `--color always|never`, or
+/// with `auto` a terminal and no `NO_COLOR` in the environment.
+pub fn use_color(choice: ColorChoice, terminal: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()) && terminal,
+    }
+}
+
+/// One diagnostic or lint finding to render.
+pub struct Snippet<'a> {
+    /// `error`, `warning` or `info`.
+    pub severity: &'a str,
+    /// Diagnostic code or lint rule id.
+    pub code: &'a str,
+    pub file: &'a str,
+    pub line: usize,
+    pub col: usize,
+    pub message: &'a str,
+}
+
+/// Source lines of reported files, read once each. A file that cannot be
+/// read is rendered without its source line.
+#[derive(Default)]
+pub struct Sources {
+    files: HashMap<String, Option<Vec<String>>>,
+}
+
+impl Sources {
+    fn line(&mut self, file: &str, line: usize) -> Option<&str> {
+        let lines = self.files.entry(file.to_string()).or_insert_with(|| {
+            std::fs::read_to_string(file)
+                .ok()
+                .map(|text| text.lines().map(str::to_string).collect())
+        });
+        lines
+            .as_ref()?
+            .get(line.checked_sub(1)?)
+            .map(String::as_str)
+    }
+}
+
+/// The findings, each as a header, its location and the source line with
+/// carets under the span it is about, separated by blank lines.
+pub fn render_snippets(snippets: &[Snippet], sources: &mut Sources, color: bool) -> String {
+    snippets
+        .iter()
+        .map(|s| render_snippet(s, sources, color))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn render_snippet(snippet: &Snippet, sources: &mut Sources, color: bool) -> String {
+    let paint = |code: &str, text: &str| {
+        if color {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    };
+    let severity_color = match snippet.severity {
+        "error" => "1;31",
+        "warning" => "1;33",
+        _ => "1;36",
+    };
+    let gutter_color = "1;34";
+
+    let mut lines = vec![format!(
+        "{}{}",
+        paint(
+            severity_color,
+            &format!("{}[{}]", snippet.severity, snippet.code)
+        ),
+        paint("1", &format!(": {}", snippet.message))
+    )];
+    let number = snippet.line.to_string();
+    let pad = " ".repeat(number.len());
+    lines.push(format!(
+        "{pad}{} {}:{}:{}",
+        paint(gutter_color, "-->"),
+        snippet.file,
+        snippet.line,
+        snippet.col
+    ));
+    let Some(text) = sources.line(snippet.file, snippet.line) else {
+        return lines.join("\n");
+    };
+    let (start, end) = span(text, snippet);
+    // Tabs are kept so the carets line up however the terminal expands them
+    let indent: String = text
+        .chars()
+        .take(start)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let bar = paint(gutter_color, "|");
+    lines.push(format!("{pad} {bar}"));
+    lines.push(format!("{} {bar} {text}", paint(gutter_color, &number)));
+    lines.push(format!(
+        "{pad} {bar} {indent}{}",
+        paint(severity_color, &"^".repeat(end - start))
+    ));
+    lines.join("\n")
+}
+
+/// Character range to underline: the first name quoted in the message that
+/// appears on the line as a whole word, otherwise the line from the
+/// reported column on, without surrounding whitespace.
+fn span(text: &str, snippet: &Snippet) -> (usize, usize) {
+    let chars: Vec<char> = text.chars().collect();
+    for name in snippet.message.split('"').skip(1).step_by(2) {
+        if let Some(start) = find_word(&chars, name) {
+            return (start, start + name.chars().count());
+        }
+    }
+    let from = snippet.col.saturating_sub(1).min(chars.len());
+    let start = (from..chars.len())
+        .find(|&i| !chars[i].is_whitespace())
+        .unwrap_or(from);
+    let end = (start..chars.len())
+        .rfind(|&i| !chars[i].is_whitespace())
+        .map_or(start, |i| i + 1);
+    (start, end.max(start + 1))
+}
+
+/// Start of `word` in `chars`, not touching other identifier characters.
+fn find_word(chars: &[char], word: &str) -> Option<usize> {
+    let word: Vec<char> = word.chars().collect();
+    if word.is_empty() || word.len() > chars.len() {
+        return None;
+    }
+    let ident = |c: &char| c.is_alphanumeric() || *c == '_';
+    (0..=chars.len() - word.len()).find(|&i| {
+        chars[i..i + word.len()] == word[..]
+            && (i == 0 || !ident(&chars[i - 1]))
+            && chars.get(i + word.len()).is_none_or(|c| !ident(c))
+    })
+}
@@ -0,0 +1,341 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+use clap::ValueEnum;
+use m3l_core::{lex, parse_string, resolve, AttrArgValue, FieldNode, ModelNode, TokenType};
+
+use crate::commands::format::schema_difference;
+
+/// How `m3l split` groups elements into files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SplitBy {
+    /// One file per namespace; elements without one keep the input's name.
+    Namespace,
+    /// One file per model, interface, view and enum; attribute
+    /// registrations share `attributes.m3l.md`.
+    Model,
+}
+
+/// One output file.
+struct Unit {
+    file: String,
+    namespace: Option<String>,
+    /// Text following the `# Namespace:` header (by namespace only).
+    intro: Option<String>,
+    blocks: Vec<String>,
+    imports: BTreeSet<String>,
+}
+
+/// `m3l split`: write the elements of one M3L file into several files under
+/// `out_dir`, copying each element's text unchanged and adding `@import`
+/// lines for what it uses from other files. The split project is parsed back
+/// and must resolve to the same schema before anything is written.
+pub fn run_split(input: &Path, by: SplitBy, out_dir: &Path, force: bool) -> Result<String, String> {
+    if !input.is_file() {
+        return Err(format!(
+            "{} is not a file; m3l split breaks apart a single M3L file",
+            input.display()
+        ));
+    }
+    let content = fs::read_to_string(input)
+        .map_err(|e| format!("Failed to read {}: {e}", input.display()))?;
+    let source = input.to_string_lossy().replace('\\', "/");
+    let stem = file_stem(input);
+
+    let lines: Vec<&str> = content.lines().collect();
+    let headers: Vec<_> = lex(&content, &source)
+        .into_iter()
+        .filter(|t| {
+            matches!(
+                t.token_type,
+                TokenType::Namespace
+                    | TokenType::Model
+                    | TokenType::Enum
+                    | TokenType::Interface
+                    | TokenType::View
+                    | TokenType::Flow
+                    | TokenType::Extension(_)
+                    | TokenType::AttributeDef
+            )
+        })
+        .collect();
+    if headers.is_empty() {
+        return Err(format!("No elements found in {}", input.display()));
+    }
+
+    let mut units: Vec<Unit> = Vec::new();
+    // Element name to the file it is written to
+    let mut file_of: HashMap<String, String> = HashMap::new();
+    let mut namespace: Option<String> = None;
+    for (i, token) in headers.iter().enumerate() {
+        let end = headers.get(i + 1).map_or(lines.len(), |next| next.line - 1);
+        let name = token.data.name.clone().unwrap_or_default();
+
+        let (file, unit_namespace) = match (by, &token.token_type) {
+            (_, TokenType::Namespace) => {
+                namespace = Some(name);
+                if by == SplitBy::Namespace {
+                    let index =
+                        unit_index(&mut units, namespace_file(&namespace, &stem), &namespace);
+                    let intro = block_text(&lines[token.line..end]);
+                    if units[index].intro.is_none() && !intro.is_empty() {
+                        units[index].intro = Some(intro);
+                    }
+                }
+                continue;
+            }
+            (SplitBy::Namespace, _) => (namespace_file(&namespace, &stem), namespace.clone()),
+            (SplitBy::Model, TokenType::AttributeDef) => ("attributes.m3l.md".to_string(), None),
+            (SplitBy::Model, _) => (format!("{name}.m3l.md"), namespace.clone()),
+        };
+        if token.token_type != TokenType::AttributeDef {
+            file_of.insert(name, file.clone());
+        }
+        let index = unit_index(&mut units, file, &unit_namespace);
+        units[index]
+            .blocks
+            .push(block_text(&lines[token.line - 1..end]));
+    }
+    // A namespace header with no elements after it gets no file
+    units.retain(|u| !u.blocks.is_empty());
+
+    // Imports come from each element's own declarations, not the fields it
+    // inherits, so a child imports its parent rather than the parent's types
+    let parsed = parse_string(&content, &source);
+    let elements: Vec<&ModelNode> = parsed
+        .models
+        .iter()
+        .chain(parsed.interfaces.iter())
+        .chain(parsed.views.iter())
+        .chain(parsed.flows.iter())
+        .chain(parsed.extensions.values().flatten())
+        .collect();
+    // An import that would close a cycle (M3L-E003) is skipped. Adding the
+    // strongest dependencies of every element first means the skipped ones
+    // are rollup and Relations targets rather than parents or references.
+    let mut edges: Vec<(Dependency, &str, &str)> = elements
+        .iter()
+        .flat_map(|e| {
+            dependencies(e)
+                .into_iter()
+                .map(|(kind, target)| (kind, e.name.as_str(), target))
+        })
+        .collect();
+    edges.sort_by_key(|&(kind, _, _)| kind);
+    let mut skipped = Vec::new();
+    for (_, name, dependency) in edges {
+        let Some(own) = file_of.get(name) else {
+            continue;
+        };
+        // `auth.User.id` names `User`; try each part
+        let Some(target) = dependency.split('.').find_map(|part| file_of.get(part)) else {
+            continue;
+        };
+        let Some(unit) = units.iter().position(|u| &u.file == own) else {
+            continue;
+        };
+        if target == own || units[unit].imports.contains(target) {
+            continue;
+        }
+        if imports_reach(&units, target, own) {
+            let note = format!(
+                "Note: {own} uses {target} without importing it; the import would close a cycle"
+            );
+            if !skipped.contains(&note) {
+                skipped.push(note);
+            }
+            continue;
+        }
+        units[unit].imports.insert(target.clone());
+    }
+
+    let preamble = block_text(&lines[..headers[0].line - 1]);
+    let rendered: Vec<(String, String)> = units
+        .iter()
+        .enumerate()
+        .map(|(i, unit)| {
+            let mut parts = Vec::new();
+            if i == 0 && !preamble.is_empty() {
+                parts.push(preamble.clone());
+            }
+            if let Some(ref namespace) = unit.namespace {
+                let mut header = format!("# Namespace: {namespace}");
+                if let Some(ref intro) = unit.intro {
+                    header.push_str(&format!("\n\n{intro}"));
+                }
+                parts.push(header);
+            }
+            if !unit.imports.is_empty() {
+                let imports: Vec<String> = unit
+                    .imports
+                    .iter()
+                    .map(|f| format!("@import \"./{f}\""))
+                    .collect();
+                parts.push(imports.join("\n"));
+            }
+            parts.extend(unit.blocks.iter().cloned());
+            (unit.file.clone(), format!("{}\n", parts.join("\n\n")))
+        })
+        .collect();
+
+    let original = resolve(&[parsed], None);
+    let split_files: Vec<_> = rendered
+        .iter()
+        .map(|(file, text)| parse_string(text, &format!("{}/{file}", out_dir.display())))
+        .collect();
+    if let Some(difference) = schema_difference(&original, &resolve(&split_files, None)) {
+        return Err(format!(
+            "Splitting would change the schema at {difference}; no files were written"
+        ));
+    }
+
+    if !force {
+        if let Some((file, _)) = rendered
+            .iter()
+            .find(|(file, _)| out_dir.join(file).exists())
+        {
+            return Err(format!(
+                "{} already exists (use --force to overwrite)",
+                out_dir.join(file).display()
+            ));
+        }
+    }
+    fs::create_dir_all(out_dir)
+        .map_err(|e| format!("Failed to create {}: {e}", out_dir.display()))?;
+    let mut output = Vec::new();
+    for ((file, text), unit) in rendered.iter().zip(&units) {
+        let path = out_dir.join(file);
+        fs::write(&path, text).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        output.push(format!(
+            "Wrote {} ({} element{})",
+            path.display(),
+            unit.blocks.len(),
+            if unit.blocks.len() == 1 { "" } else { "s" }
+        ));
+    }
+    output.extend(skipped);
+    output.push(format!(
+        "Split {} into {} files",
+        input.display(),
+        rendered.len()
+    ));
+    Ok(output.join("\n"))
+}
+
+fn unit_index(units: &mut Vec<Unit>, file: String, namespace: &Option<String>) -> usize {
+    if let Some(i) = units.iter().position(|u| u.file == file) {
+        return i;
+    }
+    units.push(Unit {
+        file,
+        namespace: namespace.clone(),
+        intro: None,
+        blocks: Vec::new(),
+        imports: BTreeSet::new(),
+    });
+    units.len() - 1
+}
+
+/// Whether `from` imports `to`, directly or through other files.
+fn imports_reach(units: &[Unit], from: &str, to: &str) -> bool {
+    let mut stack = vec![from];
+    let mut seen = BTreeSet::new();
+    while let Some(file) = stack.pop() {
+        if file == to {
+            return true;
+        }
+        if !seen.insert(file) {
+            continue;
+        }
+        if let Some(unit) = units.iter().find(|u| u.file == file) {
+            stack.extend(unit.imports.iter().map(String::as_str));
+        }
+    }
+    false
+}
+
+fn namespace_file(namespace: &Option<String>, stem: &str) -> String {
+    format!("{}.m3l.md", namespace.as_deref().unwrap_or(stem))
+}
+
+/// `schema` for `schema.m3l.md`, `schema.m3l` or `schema.md`.
+fn file_stem(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    [".m3l.md", ".m3l", ".md"]
+        .iter()
+        .find_map(|ext| name.strip_suffix(ext))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+/// Lines joined, without the blank lines and `---` rules around them.
+fn block_text(lines: &[&str]) -> String {
+    let separator = |l: &&str| l.trim().is_empty() || l.trim() == "---";
+    let start = lines
+        .iter()
+        .position(|l| !separator(l))
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|l| !separator(l))
+        .map_or(start, |i| i + 1);
+    lines[start..end].join("\n")
+}
+
+/// How strongly an element depends on another. When an import has to be
+/// left out to avoid a cycle, the weakest kind goes first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Dependency {
+    Parent,
+    /// Field types, references and view sources.
+    Uses,
+    /// Rollup and Relations targets, which point back at the models
+    /// referencing this one.
+    Reverse,
+}
+
+/// What `element` refers to, as written (`User.id`).
+fn dependencies(element: &ModelNode) -> Vec<(Dependency, &str)> {
+    let mut found: Vec<(Dependency, &str)> = element
+        .inherits
+        .iter()
+        .map(|p| (Dependency::Parent, p.as_str()))
+        .collect();
+    field_dependencies(&element.fields, &mut found);
+    if let Some(ref source) = element.source_def {
+        found.extend(source.from.as_deref().map(|f| (Dependency::Uses, f)));
+        for join in source.joins.iter().flatten() {
+            found.push((Dependency::Uses, &join.model));
+        }
+    }
+    for relation in &element.sections.relations {
+        if let Some(ref target) = relation.target {
+            found.push((Dependency::Reverse, target));
+        }
+    }
+    found
+}
+
+fn field_dependencies<'a>(fields: &'a [FieldNode], found: &mut Vec<(Dependency, &'a str)>) {
+    for field in fields {
+        found.extend(field.field_type.as_deref().map(|t| (Dependency::Uses, t)));
+        for attr in &field.attributes {
+            if attr.name != "reference" && attr.name != "fk" {
+                continue;
+            }
+            if let Some(AttrArgValue::String(target)) = attr.args.as_ref().and_then(|a| a.first()) {
+                found.push((Dependency::Uses, target));
+            }
+        }
+        if let Some(ref rollup) = field.rollup {
+            found.push((Dependency::Reverse, &rollup.target));
+        }
+        if let Some(ref nested) = field.fields {
+            field_dependencies(nested, found);
+        }
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+use std::time::SystemTime;
+
+use m3l_core::{FieldKind, M3lAst, ModelNode};
+use serde::{Deserialize, Serialize};
+
+use crate::build_ast;
+use crate::commands::attributes::collect_usage;
+
+/// Default history file for `--append-history` and `--trend`.
+pub const DEFAULT_HISTORY_FILE: &str = ".m3l-history.jsonl";
+
+/// Schema metrics for one run. Every field defaults so history records
+/// written by older versions still load.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchemaStats {
+    pub files: usize,
+    pub models: usize,
+    pub interfaces: usize,
+    pub views: usize,
+    pub enums: usize,
+    pub flows: usize,
+    pub fields: usize,
+    /// Top-level fields per model, inherited ones included.
+    pub fields_per_model: FieldSpread,
+    /// `@reference`/`@fk` fields plus Relations entries.
+    pub references: usize,
+    pub lookups: usize,
+    pub rollups: usize,
+    pub max_inheritance_depth: usize,
+    /// Uses of each attribute, counting inherited fields once.
+    pub attributes: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldSpread {
+    pub min: usize,
+    pub avg: f64,
+    pub max: usize,
+}
+
+/// Attributes listed in the human-readable summary; JSON has all of them.
+const TOP_ATTRIBUTES: usize = 10;
+
+/// One line of the history file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub stats: SchemaStats,
+}
+
+/// Metrics shown in the trend table, as (header, accessor).
+type Metric = (&'static str, fn(&SchemaStats) -> usize);
+
+const TREND_METRICS: &[Metric] = &[
+    ("models", |s| s.models),
+    ("views", |s| s.views),
+    ("enums", |s| s.enums),
+    ("fields", |s| s.fields),
+    ("refs", |s| s.references),
+    ("depth", |s| s.max_inheritance_depth),
+];
+
+pub fn collect_stats(ast: &M3lAst) -> SchemaStats {
+    let by_name: HashMap<&str, &ModelNode> = ast
+        .models
+        .iter()
+        .chain(ast.interfaces.iter())
+        .chain(ast.views.iter())
+        .map(|m| (m.name.as_str(), m))
+        .collect();
+
+    // Inherited fields are copied into every child; count each declaration once
+    let mut seen: HashSet<(&str, usize)> = HashSet::new();
+    let mut fields = 0;
+    let mut references = 0;
+    let mut lookups = 0;
+    let mut rollups = 0;
+    for model in ast
+        .models
+        .iter()
+        .chain(ast.interfaces.iter())
+        .chain(ast.views.iter())
+    {
+        for field in &model.fields {
+            if !seen.insert((field.loc.file.as_str(), field.loc.line)) {
+                continue;
+            }
+            fields += 1;
+            match field.kind {
+                FieldKind::Lookup => lookups += 1,
+                FieldKind::Rollup => rollups += 1,
+                _ => {}
+            }
+            if field
+                .attributes
+                .iter()
+                .any(|a| a.name == "reference" || a.name == "fk")
+            {
+                references += 1;
+            }
+        }
+        references += model.sections.relations.len();
+    }
+
+    let max_inheritance_depth = ast
+        .models
+        .iter()
+        .chain(ast.views.iter())
+        .map(|m| inheritance_depth(&m.name, &by_name, &mut Vec::new()))
+        .max()
+        .unwrap_or(0);
+
+    let counts: Vec<usize> = ast.models.iter().map(|m| m.fields.len()).collect();
+    let fields_per_model = FieldSpread {
+        min: counts.iter().copied().min().unwrap_or(0),
+        avg: if counts.is_empty() {
+            0.0
+        } else {
+            // One decimal place keeps history records stable and readable
+            let avg = counts.iter().sum::<usize>() as f64 / counts.len() as f64;
+            (avg * 10.0).round() / 10.0
+        },
+        max: counts.iter().copied().max().unwrap_or(0),
+    };
+
+    SchemaStats {
+        files: ast.sources.len(),
+        models: ast.models.len(),
+        interfaces: ast.interfaces.len(),
+        views: ast.views.len(),
+        enums: ast.enums.len(),
+        flows: ast.flows.len(),
+        fields,
+        fields_per_model,
+        references,
+        lookups,
+        rollups,
+        max_inheritance_depth,
+        attributes: collect_usage(ast)
+            .into_iter()
+            .map(|u| (u.name, u.count))
+            .collect(),
+    }
+}
+
+/// Length of the longest chain of parents above `name`.
+pub(crate) fn inheritance_depth<'a>(
+    name: &'a str,
+    by_name: &HashMap<&str, &'a ModelNode>,
+    visiting: &mut Vec<&'a str>,
+) -> usize {
+    let Some(model) = by_name.get(name) else {
+        return 0;
+    };
+    visiting.push(name);
+    let mut depth = 0;
+    for parent in &model.inherits {
+        if !visiting.contains(&parent.as_str()) {
+            depth = depth.max(1 + inheritance_depth(parent, by_name, visiting));
+        }
+    }
+    visiting.pop();
+    depth
+}
+
+fn format_stats(stats: &SchemaStats, format: &str) -> Result<String, String> {
+    match format {
+        "json" => serde_json::to_string_pretty(stats)
+            .map_err(|e| format!("JSON serialization error: {e}")),
+        _ => {
+            let spread = &stats.fields_per_model;
+            let rows = [
+                ("Files", stats.files.to_string()),
+                ("Models", stats.models.to_string()),
+                ("Interfaces", stats.interfaces.to_string()),
+                ("Views", stats.views.to_string()),
+                ("Enums", stats.enums.to_string()),
+                ("Flows", stats.flows.to_string()),
+                ("Fields", stats.fields.to_string()),
+                (
+                    "Fields per model",
+                    format!("min {}, avg {}, max {}", spread.min, spread.avg, spread.max),
+                ),
+                ("References", stats.references.to_string()),
+                ("Lookups", stats.lookups.to_string()),
+                ("Rollups", stats.rollups.to_string()),
+                (
+                    "Max inheritance depth",
+                    stats.max_inheritance_depth.to_string(),
+                ),
+            ];
+            let mut lines: Vec<String> = rows
+                .iter()
+                .map(|(label, value)| format!("{:<22} {value}", format!("{label}:")))
+                .collect();
+
+            if !stats.attributes.is_empty() {
+                let mut usage: Vec<(&String, &usize)> = stats.attributes.iter().collect();
+                usage.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+                lines.push(String::new());
+                lines.push("Attribute usage:".to_string());
+                for (name, count) in usage.iter().take(TOP_ATTRIBUTES) {
+                    lines.push(format!("  {:<20} {count}", format!("@{name}")));
+                }
+                if usage.len() > TOP_ATTRIBUTES {
+                    lines.push(format!(
+                        "  ... {} more (see m3l attributes)",
+                        usage.len() - TOP_ATTRIBUTES
+                    ));
+                }
+            }
+            Ok(lines.join("\n"))
+        }
+    }
+}
+
+fn append_history(path: &Path, stats: &SchemaStats) -> Result<(), String> {
+    let record = HistoryRecord {
+        timestamp: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+        stats: stats.clone(),
+    };
+    let line =
+        serde_json::to_string(&record).map_err(|e| format!("JSON serialization error: {e}"))?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    writeln!(file, "{line}").map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+pub fn read_history(path: &Path) -> Result<Vec<HistoryRecord>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            serde_json::from_str(l)
+                .map_err(|e| format!("{}:{}: invalid history record: {e}", path.display(), i + 1))
+        })
+        .collect()
+}
+
+fn signed(delta: i64) -> String {
+    if delta > 0 {
+        format!("+{delta}")
+    } else {
+        delta.to_string()
+    }
+}
+
+fn format_trend(records: &[HistoryRecord], format: &str) -> Result<String, String> {
+    let (Some(first), Some(last)) = (records.first(), records.last()) else {
+        return Ok("No history records found.".into());
+    };
+    let change: Vec<(&str, i64)> = TREND_METRICS
+        .iter()
+        .map(|(name, get)| (*name, get(&last.stats) as i64 - get(&first.stats) as i64))
+        .collect();
+
+    if format == "json" {
+        let change: serde_json::Map<String, serde_json::Value> = change
+            .iter()
+            .map(|(name, d)| (name.to_string(), serde_json::json!(d)))
+            .collect();
+        return serde_json::to_string_pretty(&serde_json::json!({
+            "records": records,
+            "change": change,
+        }))
+        .map_err(|e| format!("JSON serialization error: {e}"));
+    }
+
+    let mut lines = Vec::new();
+    let mut header = format!("{:<22}", "timestamp");
+    for (name, _) in TREND_METRICS {
+        header.push_str(&format!("{name:>8}"));
+    }
+    lines.push(header);
+    for record in records {
+        let mut row = format!("{:<22}", record.timestamp);
+        for (_, get) in TREND_METRICS {
+            row.push_str(&format!("{:>8}", get(&record.stats)));
+        }
+        lines.push(row);
+    }
+
+    let moved: Vec<String> = change
+        .iter()
+        .filter(|(_, d)| *d != 0)
+        .map(|(name, d)| format!("{name} {}", signed(*d)))
+        .collect();
+    let run_word = if records.len() == 1 { "run" } else { "runs" };
+    lines.push(String::new());
+    if moved.is_empty() {
+        lines.push(format!("No change over {} {run_word}.", records.len()));
+    } else {
+        lines.push(format!(
+            "Change over {} {run_word}: {}",
+            records.len(),
+            moved.join(", ")
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+pub fn run_stats(
+    input_path: &Path,
+    format: &str,
+    append_history_path: Option<&Path>,
+    trend_path: Option<&Path>,
+) -> Result<String, String> {
+    if let Some(history) = append_history_path {
+        let stats = collect_stats(&build_ast(input_path)?);
+        append_history(history, &stats)?;
+        if trend_path.is_none() {
+            return format_stats(&stats, format);
+        }
+    }
+
+    match trend_path {
+        Some(history) => format_trend(&read_history(history)?, format),
+        None => format_stats(&collect_stats(&build_ast(input_path)?), format),
+    }
+}
@@ -0,0 +1,137 @@
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::{Duration, Instant, SystemTime};
+
+use m3l_core::is_m3l_path;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use crate::reader::{find_project_config, glob_base, is_glob, CONFIG_FILES};
+
+/// Quiet period after the first change event, so an editor's
+/// write-rename-chmod burst triggers a single re-run.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Run `run` once, then again every time an M3L file under `input_path` (a
+/// file, directory or glob pattern) or its project config changes, even a
+/// config in a directory above. Only returns if the watcher fails.
+pub fn watch(input_path: &Path, mut run: impl FnMut() -> String) -> Result<(), String> {
+    let (root, mode) = if input_path.is_dir() {
+        (input_path.to_path_buf(), RecursiveMode::Recursive)
+    } else if is_glob(input_path) {
+        (glob_base(input_path), RecursiveMode::Recursive)
+    } else {
+        let parent = input_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        (parent.to_path_buf(), RecursiveMode::NonRecursive)
+    };
+
+    let (tx, rx) = channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| format!("Failed to create watcher: {e}"))?;
+    watcher
+        .watch(&root, mode)
+        .map_err(|e| format!("Failed to watch {}: {e}", root.display()))?;
+
+    // A config above the root is watched through its directory, since
+    // editors replace files rather than write them in place
+    let root_dir = std::path::absolute(&root).unwrap_or_else(|_| root.clone());
+    let config = find_project_config(input_path)
+        .ok()
+        .flatten()
+        .filter(|project| project.dir != root_dir)
+        .map(|project| project.file);
+    if let Some(dir) = config.as_deref().and_then(Path::parent) {
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {e}", dir.display()))?;
+    }
+
+    let mut changed: Vec<PathBuf> = Vec::new();
+    loop {
+        render(input_path, &changed, &mut run);
+        changed = next_change(&rx, config.as_deref())?;
+    }
+}
+
+fn render(input_path: &Path, changed: &[PathBuf], run: &mut impl FnMut() -> String) {
+    let mut stdout = std::io::stdout();
+    if stdout.is_terminal() {
+        // Clear screen and move the cursor home
+        print!("\x1b[2J\x1b[H");
+    }
+    if !changed.is_empty() {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let names: Vec<String> = changed
+            .iter()
+            .map(|p| p.strip_prefix(&cwd).unwrap_or(p).display().to_string())
+            .collect();
+        println!("Changed: {}\n", names.join(", "));
+    }
+
+    let start = Instant::now();
+    let output = run();
+    let elapsed = start.elapsed();
+
+    println!("{output}");
+    println!(
+        "\n[{}] Finished in {:.1}ms. Watching {} for changes (Ctrl+C to exit)...",
+        humantime::format_rfc3339_seconds(SystemTime::now()),
+        elapsed.as_secs_f64() * 1000.0,
+        input_path.display()
+    );
+    stdout.flush().ok();
+}
+
+/// Block until a relevant file changes, then collect everything else that
+/// arrives within the debounce window. `config` is a project config outside
+/// the watched root.
+fn next_change(
+    rx: &Receiver<notify::Result<Event>>,
+    config: Option<&Path>,
+) -> Result<Vec<PathBuf>, String> {
+    let mut changed = Vec::new();
+    while changed.is_empty() {
+        let event = rx.recv().map_err(|_| "File watcher stopped".to_string())?;
+        collect_paths(event, config, &mut changed);
+    }
+    while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+        collect_paths(event, config, &mut changed);
+    }
+    tracing::debug!(files = ?changed, "change detected");
+    Ok(changed)
+}
+
+fn collect_paths(event: notify::Result<Event>, config: Option<&Path>, changed: &mut Vec<PathBuf>) {
+    let event = match event {
+        Ok(event) => event,
+        Err(e) => {
+            tracing::warn!(error = %e, "file watcher error");
+            return;
+        }
+    };
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+    for path in event.paths {
+        if is_watched(&path, config) && !changed.contains(&path) {
+            changed.push(path);
+        }
+    }
+}
+
+fn is_watched(path: &Path, config: Option<&Path>) -> bool {
+    // In the config's directory, only the config itself counts
+    if let Some(config) = config {
+        if path.parent() == config.parent() {
+            return path == config;
+        }
+    }
+    is_m3l_path(path)
+        || path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| CONFIG_FILES.contains(&n))
+}
@@ -0,0 +1,70 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Contents of `paths` at `revision` (empty for the index), read through a
+/// single `git cat-file --batch`.
+pub fn read_blobs(root: &Path, revision: &str, paths: &[&str]) -> Result<Vec<String>, String> {
+    let mut child = Command::new("git")
+        .args(["cat-file", "--batch"])
+        .current_dir(root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let requests: String = paths
+        .iter()
+        .map(|path| format!("{revision}:{path}\n"))
+        .collect();
+    // Written from another thread so a large reply cannot block the request
+    let writer = std::thread::spawn(move || stdin.write_all(requests.as_bytes()));
+
+    let mut stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+    let mut contents = Vec::with_capacity(paths.len());
+    for path in paths {
+        let mut header = String::new();
+        stdout
+            .read_line(&mut header)
+            .map_err(|e| format!("Failed to read git output: {e}"))?;
+        let size: usize = match header.split_whitespace().collect::<Vec<_>>()[..] {
+            [_, "blob", size] => size.parse().unwrap_or(0),
+            _ => return Err(format!("git could not read {revision}:{path}")),
+        };
+        let mut content = vec![0; size + 1];
+        stdout
+            .read_exact(&mut content)
+            .map_err(|e| format!("Failed to read git output: {e}"))?;
+        content.pop();
+        contents.push(String::from_utf8_lossy(&content).into_owned());
+    }
+    writer
+        .join()
+        .map_err(|_| "git writer thread panicked".to_string())?
+        .map_err(|e| format!("Failed to write to git: {e}"))?;
+    child
+        .wait()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    Ok(contents)
+}
+
+/// Run git in `dir` (or the current directory) and return its stdout.
+pub fn git(dir: Option<&Path>, args: &[&str]) -> Result<String, String> {
+    let mut command = Command::new("git");
+    // Report non-ASCII paths as they are, not as quoted escapes
+    command.args(["-c", "core.quotePath=false"]).args(args);
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
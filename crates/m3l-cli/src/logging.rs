@@ -0,0 +1,32 @@
+use clap::ValueEnum;
+use tracing::level_filters::LevelFilter;
+
+/// How log events are written to stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum LogFormat {
+    /// One readable line per event.
+    #[default]
+    Text,
+    /// One JSON object per line, for log collectors.
+    Json,
+}
+
+/// Install the stderr subscriber. Warnings are shown by default; each `-v`
+/// adds a level (info, debug, trace) and `-q` silences logging entirely.
+/// Command output on stdout is unaffected either way.
+pub fn init(quiet: bool, verbose: u8, format: LogFormat, ansi: bool) {
+    let level = match (quiet, verbose) {
+        (true, _) => LevelFilter::OFF,
+        (false, 0) => LevelFilter::WARN,
+        (false, 1) => LevelFilter::INFO,
+        (false, 2) => LevelFilter::DEBUG,
+        (false, _) => LevelFilter::TRACE,
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr);
+    match format {
+        LogFormat::Text => builder.with_ansi(ansi).init(),
+        LogFormat::Json => builder.json().with_current_span(false).init(),
+    }
+}
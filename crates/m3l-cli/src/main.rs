@@ -1,13 +1,20 @@
 mod commands;
+mod git;
+mod logging;
 mod reader;
 
+use std::io::{IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::process;
+use std::time::Instant;
 
-use clap::{Parser, Subcommand};
+use clap::{ColorChoice, CommandFactory, Parser, Subcommand};
 
-use m3l_core::{parse_string, resolve, validate, ProjectInfo, ValidateOptions};
-use reader::{read_m3l_files, read_project_config};
+use commands::snippet::{render_snippets, use_color, Snippet, Sources};
+use m3l_core::{
+    parse_string, resolve_with_options, validate, ProjectInfo, ResolveOptions, ValidateOptions,
+};
+use reader::{find_project_config, read_imports, read_m3l_files};
 
 #[derive(Parser)]
 #[command(
@@ -18,16 +25,48 @@ use reader::{read_m3l_files, read_project_config};
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Color in human output: auto (a terminal, unless NO_COLOR is set),
+    /// always or never
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Log nothing to stderr (command output is unaffected)
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Log more to stderr: -v info, -vv debug (parse timings), -vvv trace
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Log format on stderr: text or json (one object per line)
+    #[arg(long, global = true, value_enum, default_value_t)]
+    log_format: logging::LogFormat,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Parse M3L files and output JSON AST
     Parse {
-        /// Input path (file or directory, defaults to current directory)
+        /// Input path (file, directory or glob pattern, defaults to current directory)
         #[arg(default_value = ".")]
         path: PathBuf,
 
+        /// Output format: json (default), yaml, msgpack (binary
+        /// MessagePack) or html-explorer (a standalone page for browsing
+        /// models, fields and diagnostics)
+        #[arg(long, default_value = "json")]
+        format: String,
+
+        /// Write JSON on a single line instead of pretty-printed
+        #[arg(long)]
+        compact: bool,
+
+        /// Nest models, enums, interfaces, views and flows under
+        /// `namespaces: [{name, models, ...}]` instead of flat lists
+        #[arg(long)]
+        group_namespaces: bool,
+
         /// Write output to file instead of stdout
         #[arg(short, long)]
         output: Option<PathBuf>,
@@ -35,45 +74,385 @@ enum Commands {
 
     /// Analyze model dependencies and output a graph
     Analyze {
-        /// Input path (file or directory, defaults to current directory)
+        /// Input path (file, directory or glob pattern, defaults to current directory)
         #[arg(default_value = ".")]
         path: PathBuf,
 
-        /// Output format: mermaid (default) or dot
+        /// Output format: mermaid (default), er (Mermaid erDiagram), dot,
+        /// graphml, d2 or order (creation order of the models)
         #[arg(long, default_value = "mermaid")]
         format: String,
+
+        /// List reference cycles between models instead of the graph; exits
+        /// with 1 if there are any
+        #[arg(long, conflicts_with_all = ["format", "focus"])]
+        check_cycles: bool,
+
+        /// List models, interfaces and enums that nothing references
+        /// instead of the graph
+        #[arg(long, conflicts_with_all = ["format", "focus", "check_cycles"])]
+        orphans: bool,
+
+        /// Report fan-in, fan-out, inheritance depth and strongly connected
+        /// components per model instead of the graph (--format json for JSON)
+        #[arg(long, conflicts_with_all = ["focus", "check_cycles", "orphans", "include"])]
+        metrics: bool,
+
+        /// Only draw this model (or interface, view, enum) and its neighbors
+        #[arg(long, value_name = "NAME")]
+        focus: Option<String>,
+
+        /// With --focus: how many edges away neighbors may be
+        #[arg(long, default_value_t = 1, requires = "focus")]
+        depth: usize,
+
+        /// Element kinds to draw besides models, comma-separated (default:
+        /// all); each kind has its own node shape and color
+        #[arg(long, value_delimiter = ',', value_name = "KINDS")]
+        include: Option<Vec<commands::analyze::NodeKind>>,
+    },
+
+    /// Report attribute usage counts, status (standard/registered/unknown) and files
+    Attributes {
+        /// Input path (file, directory or glob pattern, defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output format: human (default) or json
+        #[arg(long, default_value = "human")]
+        format: String,
+    },
+
+    /// Time lexing, parsing, resolving and validating, per file and in total
+    Bench {
+        /// Input path (file, directory or glob pattern, defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Number of runs to average over
+        #[arg(short = 'n', long, default_value_t = 10)]
+        iterations: usize,
+
+        /// Output format: human (default) or json
+        #[arg(long, default_value = "human")]
+        format: String,
+    },
+
+    /// Validate and lint in one pass, with a single exit code for CI
+    Check {
+        /// Input path (file, directory or glob pattern, defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Enable strict style guidelines
+        #[arg(long)]
+        strict: bool,
+
+        /// Output format: human (default), json or sarif
+        #[arg(long, default_value = "human")]
+        format: String,
+
+        /// Fail when there are more than N warnings, even without errors
+        #[arg(long, value_name = "N")]
+        max_warnings: Option<usize>,
+    },
+
+    /// Print a shell completion script
+    ///
+    /// Install, for example:
+    ///   bash:       m3l completions bash > /etc/bash_completion.d/m3l
+    ///   zsh:        m3l completions zsh > "${fpath[1]}/_m3l"
+    ///   fish:       m3l completions fish > ~/.config/fish/completions/m3l.fish
+    ///   PowerShell: m3l completions powershell | Out-String | Invoke-Expression
+    #[command(verbatim_doc_comment)]
+    Completions {
+        /// Target shell
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
     },
 
     /// Compare two M3L files and show differences
     Diff {
-        /// First input file/directory
+        /// First input file/directory (with --against, the path to compare)
         left: PathBuf,
 
         /// Second input file/directory
-        right: PathBuf,
+        #[arg(required_unless_present = "against", conflicts_with = "against")]
+        right: Option<PathBuf>,
+
+        /// Compare the first path as of this git revision (commit, branch or
+        /// tag) with the worktree
+        #[arg(long, value_name = "REV")]
+        against: Option<String>,
+
+        /// Output format: human (default), json or markdown (a changelog
+        /// grouped by model)
+        #[arg(long, default_value = "human")]
+        format: String,
+
+        /// Write a migration from the first schema to the second instead
+        /// of listing the changes
+        #[arg(long, value_enum, conflicts_with = "format")]
+        migration: Option<commands::migration::MigrationKind>,
+
+        /// SQL dialect of the migration
+        #[arg(long, value_enum, default_value_t, requires = "migration")]
+        dialect: commands::migration::Dialect,
+
+        /// Include drops and column type changes in the migration instead
+        /// of commenting them out
+        #[arg(long, requires = "migration")]
+        allow_destructive: bool,
+
+        /// How alike (0 to 1) a removed and an added field of a model must
+        /// be to be reported as a rename; 1 requires the same type,
+        /// modifiers, default and attributes
+        #[arg(long, default_value_t = commands::diff::DEFAULT_RENAME_THRESHOLD)]
+        rename_threshold: f64,
+
+        /// Report renamed fields as a removal and an addition
+        #[arg(long, conflicts_with = "rename_threshold")]
+        no_renames: bool,
+    },
+
+    /// Render the schema as a static HTML documentation site
+    Docs {
+        /// Input path (file, directory or glob pattern, defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output directory for the site
+        #[arg(long, default_value = "site")]
+        out: PathBuf,
+    },
+
+    /// Describe a validation code or lint rule, with bad and fixed examples
+    Explain {
+        /// Code (M3L-E005, E005) or lint rule id (naming-convention); lists
+        /// every code when omitted
+        code: Option<String>,
+
+        /// Output format: human (default) or json
+        #[arg(long, default_value = "human")]
+        format: String,
+    },
+
+    /// Export schema artifacts for people outside the M3L toolchain
+    Export {
+        #[command(subcommand)]
+        artifact: ExportArtifact,
+    },
+
+    /// Fuzzy-search model, field and enum names and descriptions
+    Find {
+        /// Search query
+        query: String,
+
+        /// Input path (file, directory or glob pattern, defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Only show one kind: model, interface, view, flow, field, enum or value
+        #[arg(long)]
+        kind: Option<String>,
+
+        /// Maximum number of matches to show
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+
+        /// Output format: human (default) or json
+        #[arg(long, default_value = "human")]
+        format: String,
     },
 
     /// Format M3L files into standardized output
     Format {
-        /// Input path (file or directory, defaults to current directory)
+        /// Input path (file, directory or glob pattern, defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Field order within each model (overrides `format.sort_fields` in config)
+        #[arg(long, value_enum)]
+        sort_fields: Option<commands::format::FieldOrder>,
+
+        /// Model order within each section (overrides `format.sort_models` in config)
+        #[arg(long, value_enum)]
+        sort_models: Option<commands::format::ModelOrder>,
+
+        /// Align field types and attributes into columns
+        #[arg(long, conflicts_with = "no_align")]
+        align: bool,
+
+        /// Do not align columns, even if the config enables it
+        #[arg(long)]
+        no_align: bool,
+
+        /// Parse the output back and fail if it describes a different schema
+        #[arg(long)]
+        verify: bool,
+
+        /// Rewrite each file in place (always verified; nothing is written if any file fails)
+        #[arg(short, long)]
+        write: bool,
+    },
+
+    /// Write the artifacts listed under `generate` in the project config
+    Generate {
+        /// Project directory or any path inside it (defaults to current directory)
         #[arg(default_value = ".")]
         path: PathBuf,
+
+        /// Write nothing; fail if any generated file is missing or out of date
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Install or run a git hook that checks changed M3L files
+    Hook {
+        #[command(subcommand)]
+        action: HookAction,
+    },
+
+    /// Create a project config and a starter schema
+    Init {
+        /// Project directory (created if missing, defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Starter schema to write
+        #[arg(long, value_enum, default_value_t)]
+        template: commands::init::Template,
+
+        /// Project name (defaults to the directory name)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Add a `lint:` section listing every rule
+        #[arg(long)]
+        with_lint: bool,
+
+        /// Add a `format:` section with the formatter defaults
+        #[arg(long)]
+        with_format: bool,
+
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
     },
 
     /// Lint M3L files for style and quality issues
     Lint {
-        /// Input path (file or directory, defaults to current directory)
+        /// Input path (file, directory or glob pattern, defaults to current directory)
         #[arg(default_value = ".")]
         path: PathBuf,
 
         /// Output format: human (default) or json
         #[arg(long, default_value = "human")]
         format: String,
+
+        /// Re-run whenever M3L files or the project config change
+        #[arg(long)]
+        watch: bool,
+
+        /// Fail when there are more than N warnings, even without errors
+        #[arg(long, value_name = "N")]
+        max_warnings: Option<usize>,
+    },
+
+    /// Run a Model Context Protocol server on stdio for AI assistants
+    Mcp,
+
+    /// Combine a multi-file project into a single M3L document
+    Merge {
+        /// Input path (file, directory or glob pattern, defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Query the resolved AST with a JSONPath or JSON Pointer expression
+    Query {
+        /// Expression, e.g. "$.models[*].fields[?(@.type == 'decimal')].name"
+        expression: String,
+
+        /// Input path (file, directory or glob pattern, defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Print matches one per line, strings without quotes
+        #[arg(long, short)]
+        raw: bool,
+    },
+
+    /// Write a bundled reference schema (ecommerce, blog or types) to disk
+    Sample {
+        /// Which sample to write
+        #[arg(value_enum)]
+        name: commands::sample::Sample,
+
+        /// Directory to write into (created if missing, defaults to current directory)
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+
+        /// Overwrite the file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Serve parse, validate, lint and format as a local HTTP JSON API
+    Serve {
+        /// Port to listen on (0 picks a free one)
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+
+        /// Address to bind; the default only accepts local connections
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+    },
+
+    /// Break one M3L file into a file per namespace or per model
+    Split {
+        /// M3L file to split
+        path: PathBuf,
+
+        /// Grouping: namespace or model
+        #[arg(long, value_enum, default_value = "namespace")]
+        by: commands::split::SplitBy,
+
+        /// Directory to write the files to
+        #[arg(long)]
+        out: PathBuf,
+
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Show schema metrics, optionally recording them to a history file
+    Stats {
+        /// Input path (file, directory or glob pattern, defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output format: human (default) or json
+        #[arg(long, default_value = "human")]
+        format: String,
+
+        /// Append a timestamped metrics record to a JSON Lines history file
+        #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = commands::stats::DEFAULT_HISTORY_FILE)]
+        append_history: Option<PathBuf>,
+
+        /// Show how metrics changed across the records of a history file
+        #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = commands::stats::DEFAULT_HISTORY_FILE)]
+        trend: Option<PathBuf>,
     },
 
     /// Validate M3L files and report diagnostics
     Validate {
-        /// Input path (file or directory, defaults to current directory)
+        /// Input path (file, directory or glob pattern, defaults to current directory)
         #[arg(default_value = ".")]
         path: PathBuf,
 
@@ -84,17 +463,109 @@ enum Commands {
         /// Output format: human (default) or json
         #[arg(long, default_value = "human")]
         format: String,
+
+        /// Re-run whenever M3L files or the project config change
+        #[arg(long)]
+        watch: bool,
+
+        /// Fail when there are more than N warnings, even without errors
+        #[arg(long, value_name = "N")]
+        max_warnings: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExportArtifact {
+    /// Data dictionary: one row per field with type, nullability, default,
+    /// description and attributes
+    Dictionary {
+        /// Input path (file, directory or glob pattern, defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output format: csv (default) or xlsx
+        #[arg(long, default_value = "csv")]
+        format: String,
+
+        /// Output file (required for xlsx; csv prints to stdout without it)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum HookAction {
+    /// Write a hook script running `m3l hook run` into the repository
+    Install {
+        /// Install as pre-commit hook, checking staged files (default)
+        #[arg(long, conflicts_with = "pre_push")]
+        pre_commit: bool,
+
+        /// Install as pre-push hook, checking files changed by the pushed commits
+        #[arg(long)]
+        pre_push: bool,
+
+        /// Replace an existing hook not installed by m3l
+        #[arg(long)]
+        force: bool,
+    },
+    /// Check the staged (or, with --pre-push, unpushed) .m3l.md and .m3l files
+    Run {
+        /// Check the files staged for commit (default)
+        #[arg(long, conflicts_with = "pre_push")]
+        pre_commit: bool,
+
+        /// Check the files changed between the upstream branch and HEAD
+        #[arg(long)]
+        pre_push: bool,
     },
 }
 
 fn main() {
     let cli = Cli::parse();
+    let color = use_color(cli.color, std::io::stdout().is_terminal());
+    logging::init(
+        cli.quiet,
+        cli.verbose,
+        cli.log_format,
+        use_color(cli.color, std::io::stderr().is_terminal()),
+    );
 
     match cli.command {
-        Commands::Parse { path, output } => match run_parse(&path, output.as_deref()) {
-            Ok(json) => {
-                if output.is_none() {
-                    println!("{json}");
+        Commands::Parse {
+            path,
+            format,
+            compact,
+            group_namespaces,
+            output,
+        } => match run_parse(&path, &format, compact, group_namespaces, output.as_deref()) {
+            Ok(Some(bytes)) => {
+                let mut stdout = std::io::stdout().lock();
+                match stdout.write_all(&bytes).and_then(|()| stdout.flush()) {
+                    // The reader (e.g. `head`) stopped early; nothing to report
+                    Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {}
+                    Err(e) => {
+                        eprintln!("Error: Failed to write output: {e}");
+                        process::exit(1);
+                    }
+                    Ok(()) => {}
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Analyze {
+            path,
+            check_cycles: true,
+            ..
+        } => match commands::analyze::run_check_cycles(&path) {
+            Ok((output, cycle_count)) => {
+                println!("{output}");
+                if cycle_count > 0 {
+                    process::exit(1);
                 }
             }
             Err(e) => {
@@ -102,8 +573,57 @@ fn main() {
                 process::exit(1);
             }
         },
-        Commands::Analyze { path, format } => {
-            match commands::analyze::run_analyze(&path, &format) {
+        Commands::Analyze {
+            path,
+            format,
+            metrics: true,
+            ..
+        } => match commands::analyze::run_metrics(&path, &format) {
+            Ok(output) => {
+                println!("{output}");
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Analyze {
+            path,
+            orphans: true,
+            ..
+        } => match commands::analyze::run_orphans(&path) {
+            Ok(output) => {
+                println!("{output}");
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Analyze {
+            path,
+            format,
+            focus,
+            depth,
+            include,
+            ..
+        } => {
+            let options = commands::analyze::GraphOptions {
+                focus: focus.map(|model| commands::analyze::Focus { model, depth }),
+                include,
+            };
+            match commands::analyze::run_analyze(&path, &format, &options) {
+                Ok(output) => {
+                    println!("{output}");
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Attributes { path, format } => {
+            match commands::attributes::run_attributes(&path, &format) {
                 Ok(output) => {
                     println!("{output}");
                 }
@@ -113,7 +633,11 @@ fn main() {
                 }
             }
         }
-        Commands::Diff { left, right } => match run_diff(&left, &right) {
+        Commands::Bench {
+            path,
+            iterations,
+            format,
+        } => match commands::bench::run_bench(&path, iterations, &format) {
             Ok(output) => {
                 println!("{output}");
             }
@@ -122,7 +646,281 @@ fn main() {
                 process::exit(1);
             }
         },
-        Commands::Format { path } => match commands::format::run_format(&path) {
+        Commands::Check {
+            path,
+            strict,
+            format,
+            max_warnings,
+        } => match validate_settings(&path, strict, max_warnings).and_then(
+            |(strict, max_warnings)| {
+                commands::check::run_check(&path, strict, &format, color)
+                    .map(|(output, errors, warnings)| (output, errors, warnings, max_warnings))
+            },
+        ) {
+            Ok((output, error_count, warning_count, max_warnings)) => {
+                println!("{output}");
+                if error_count > 0 {
+                    process::exit(1);
+                }
+                exit_over_budget(warning_count, max_warnings);
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Completions { shell } => {
+            match commands::completions::run_completions(shell, &mut Cli::command()) {
+                Ok(script) => {
+                    print!("{script}");
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Diff {
+            left,
+            right,
+            against,
+            format,
+            migration,
+            dialect,
+            allow_destructive,
+            rename_threshold,
+            no_renames,
+        } => {
+            let options = commands::diff::DiffOptions {
+                rename_threshold: (!no_renames).then_some(rename_threshold),
+            };
+            let inputs = match (&against, &right) {
+                (Some(revision), _) => commands::diff::DiffInputs::Against(revision, &left),
+                (None, Some(right)) => commands::diff::DiffInputs::Paths(&left, right),
+                (None, None) => unreachable!("clap requires the second path without --against"),
+            };
+            let result = match migration {
+                Some(commands::migration::MigrationKind::Sql) => {
+                    commands::migration::run_migration(inputs, dialect, allow_destructive, &options)
+                }
+                None => commands::diff::run_diff(inputs, &format, &options),
+            };
+            match result {
+                Ok(output) => {
+                    println!("{output}");
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Docs { path, out } => match commands::docs::run_docs(&path, &out) {
+            Ok(output) => {
+                println!("{output}");
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Explain { code, format } => {
+            match commands::explain::run_explain(code.as_deref(), &format) {
+                Ok(output) => {
+                    println!("{output}");
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Export {
+            artifact:
+                ExportArtifact::Dictionary {
+                    path,
+                    format,
+                    output,
+                },
+        } => match commands::export::run_export_dictionary(&path, &format, output.as_deref()) {
+            Ok(output) => {
+                println!("{output}");
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Find {
+            query,
+            path,
+            kind,
+            limit,
+            format,
+        } => match commands::find::run_find(&path, &query, kind.as_deref(), limit, &format) {
+            Ok(output) => {
+                println!("{output}");
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Format {
+            path,
+            sort_fields,
+            sort_models,
+            align,
+            no_align,
+            verify,
+            write,
+        } => {
+            let overrides = commands::format::FormatOverrides {
+                sort_fields,
+                sort_models,
+                align: (align || no_align).then_some(align),
+            };
+            let result = if write {
+                commands::format::run_format_write(&path, &overrides)
+            } else {
+                commands::format::run_format(&path, &overrides, verify)
+            };
+            match result {
+                Ok(output) => {
+                    println!("{output}");
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Generate { path, check } => {
+            match commands::generate::run_generate(&path, check) {
+                Ok(output) => {
+                    println!("{output}");
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Hook {
+            action: HookAction::Install {
+                pre_push, force, ..
+            },
+        } => match commands::hook::run_install(hook_kind(pre_push), force) {
+            Ok(output) => {
+                println!("{output}");
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Hook {
+            action: HookAction::Run { pre_push, .. },
+        } => match commands::hook::run_hook(hook_kind(pre_push), color) {
+            Ok((output, error_count)) => {
+                println!("{output}");
+                if error_count > 0 {
+                    process::exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Init {
+            path,
+            template,
+            name,
+            with_lint,
+            with_format,
+            force,
+        } => {
+            let options = commands::init::InitOptions {
+                template,
+                name,
+                lint: with_lint,
+                format: with_format,
+                force,
+            };
+            match commands::init::run_init(&path, &options) {
+                Ok(output) => {
+                    println!("{output}");
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Lint {
+            path,
+            format,
+            watch: true,
+            max_warnings,
+        } => watch_or_exit(&path, || {
+            match commands::lint::run_lint(&path, &format, color) {
+                Ok((output, warning_count)) => {
+                    with_budget_note(output, warning_count, max_warnings)
+                }
+                Err(e) => format!("Error: {e}"),
+            }
+        }),
+        Commands::Lint {
+            path,
+            format,
+            max_warnings,
+            ..
+        } => match commands::lint::run_lint(&path, &format, color) {
+            Ok((output, warning_count)) => {
+                println!("{output}");
+                exit_over_budget(warning_count, max_warnings);
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Mcp => {
+            if let Err(e) = commands::mcp::run_mcp() {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        }
+        Commands::Merge { path, output } => {
+            match commands::merge::run_merge(&path, output.as_deref()) {
+                Ok(output) => {
+                    println!("{output}");
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Query {
+            expression,
+            path,
+            raw,
+        } => match commands::query::run_query(&path, &expression, raw) {
+            Ok(output) => {
+                println!("{output}");
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Sample {
+            name,
+            output,
+            force,
+        } => match commands::sample::run_sample(name, &output, force) {
             Ok(output) => {
                 println!("{output}");
             }
@@ -131,7 +929,37 @@ fn main() {
                 process::exit(1);
             }
         },
-        Commands::Lint { path, format } => match commands::lint::run_lint(&path, &format) {
+        Commands::Serve { port, host } => {
+            if let Err(e) = commands::serve::run_serve(&host, port) {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        }
+        Commands::Split {
+            path,
+            by,
+            out,
+            force,
+        } => match commands::split::run_split(&path, by, &out, force) {
+            Ok(output) => {
+                println!("{output}");
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        },
+        Commands::Stats {
+            path,
+            format,
+            append_history,
+            trend,
+        } => match commands::stats::run_stats(
+            &path,
+            &format,
+            append_history.as_deref(),
+            trend.as_deref(),
+        ) {
             Ok(output) => {
                 println!("{output}");
             }
@@ -144,12 +972,40 @@ fn main() {
             path,
             strict,
             format,
-        } => match run_validate(&path, strict, &format) {
-            Ok((output, error_count)) => {
+            watch: true,
+            max_warnings,
+        } => watch_or_exit(&path, || {
+            // Re-read each run, so edits to the config apply while watching
+            match validate_settings(&path, strict, max_warnings).and_then(
+                |(strict, max_warnings)| {
+                    run_validate(&path, strict, &format, color)
+                        .map(|(output, _, warnings)| (output, warnings, max_warnings))
+                },
+            ) {
+                Ok((output, warning_count, max_warnings)) => {
+                    with_budget_note(output, warning_count, max_warnings)
+                }
+                Err(e) => format!("Error: {e}"),
+            }
+        }),
+        Commands::Validate {
+            path,
+            strict,
+            format,
+            max_warnings,
+            ..
+        } => match validate_settings(&path, strict, max_warnings).and_then(
+            |(strict, max_warnings)| {
+                run_validate(&path, strict, &format, color)
+                    .map(|(output, errors, warnings)| (output, errors, warnings, max_warnings))
+            },
+        ) {
+            Ok((output, error_count, warning_count, max_warnings)) => {
                 println!("{output}");
                 if error_count > 0 {
                     process::exit(1);
                 }
+                exit_over_budget(warning_count, max_warnings);
             }
             Err(e) => {
                 eprintln!("Error: {e}");
@@ -159,197 +1015,259 @@ fn main() {
     }
 }
 
-pub fn build_ast(input_path: &Path) -> Result<m3l_core::M3lAst, String> {
-    let files = read_m3l_files(input_path)?;
-
-    if files.is_empty() {
-        return Err(format!(
-            "No M3L files (.m3l, .m3l.md, .md) found at: {}",
-            input_path.display()
-        ));
-    }
-
-    let parsed_files: Vec<_> = files
-        .iter()
-        .map(|f| parse_string(&f.content, &f.path))
-        .collect();
+/// `--strict` and `--max-warnings`, falling back to the `validate` section
+/// of the project config when a flag is not given.
+fn validate_settings(
+    path: &Path,
+    strict: bool,
+    max_warnings: Option<usize>,
+) -> Result<(bool, Option<usize>), String> {
+    let config = find_project_config(path)?
+        .and_then(|p| p.config.validate)
+        .unwrap_or_default();
+    Ok((
+        strict || config.strict,
+        max_warnings.or(config.max_warnings),
+    ))
+}
 
-    // Read project config if input is a directory
-    let project_info = if input_path.is_dir() {
-        read_project_config(input_path).map(|c| ProjectInfo {
-            name: c.name,
-            version: c.version,
-        })
+fn hook_kind(pre_push: bool) -> commands::hook::HookKind {
+    if pre_push {
+        commands::hook::HookKind::PrePush
     } else {
-        None
-    };
-
-    let ast = resolve(&parsed_files, project_info);
+        commands::hook::HookKind::PreCommit
+    }
+}
 
-    Ok(ast)
+/// Why `--max-warnings` fails the run, if the count is over the budget.
+fn over_budget(warning_count: usize, max_warnings: Option<usize>) -> Option<String> {
+    let max = max_warnings?;
+    (warning_count > max).then(|| {
+        let (noun, verb) = if warning_count == 1 {
+            ("warning", "exceeds")
+        } else {
+            ("warnings", "exceed")
+        };
+        format!("{warning_count} {noun} {verb} the --max-warnings limit of {max}")
+    })
 }
 
-fn run_parse(input_path: &Path, output_file: Option<&Path>) -> Result<String, String> {
-    let ast = build_ast(input_path)?;
-    let json =
-        serde_json::to_string_pretty(&ast).map_err(|e| format!("JSON serialization error: {e}"))?;
+fn exit_over_budget(warning_count: usize, max_warnings: Option<usize>) {
+    if let Some(message) = over_budget(warning_count, max_warnings) {
+        eprintln!("Error: {message}");
+        process::exit(1);
+    }
+}
 
-    if let Some(out_path) = output_file {
-        std::fs::write(out_path, &json)
-            .map_err(|e| format!("Failed to write {}: {e}", out_path.display()))?;
-        return Ok(format!("Written to {}", out_path.display()));
+/// Watch mode keeps running, so the budget is reported under the output.
+fn with_budget_note(output: String, warning_count: usize, max_warnings: Option<usize>) -> String {
+    match over_budget(warning_count, max_warnings) {
+        Some(message) => format!("{output}\nError: {message}"),
+        None => output,
     }
+}
 
-    Ok(json)
+fn watch_or_exit(path: &Path, run: impl FnMut() -> String) {
+    if let Err(e) = commands::watch::watch(path, run) {
+        eprintln!("Error: {e}");
+        process::exit(1);
+    }
 }
 
-fn run_diff(left_path: &Path, right_path: &Path) -> Result<String, String> {
-    let left_ast = build_ast(left_path)?;
-    let right_ast = build_ast(right_path)?;
+pub fn build_ast(input_path: &Path) -> Result<m3l_core::M3lAst, String> {
+    let (parsed_files, project) = parse_input(input_path)?;
+    Ok(resolve_parsed(&parsed_files, project))
+}
 
-    let mut lines: Vec<String> = Vec::new();
+/// The schema at `input_path` as written, for writing it back: like
+/// [`build_ast`], but without the project's type aliases expanded or its
+/// default parents added.
+pub fn build_source_ast(input_path: &Path) -> Result<m3l_core::M3lAst, String> {
+    let (parsed_files, mut project) = parse_input(input_path)?;
+    if let Some(ref mut project) = project {
+        project.resolve.type_aliases.clear();
+        project.resolve.default_inherits.clear();
+    }
+    Ok(resolve_parsed(&parsed_files, project))
+}
 
-    // Build name maps
-    let left_models: std::collections::HashMap<&str, &m3l_core::ModelNode> = left_ast
-        .models
-        .iter()
-        .chain(left_ast.views.iter())
-        .map(|m| (m.name.as_str(), m))
-        .collect();
-    let right_models: std::collections::HashMap<&str, &m3l_core::ModelNode> = right_ast
-        .models
-        .iter()
-        .chain(right_ast.views.iter())
-        .map(|m| (m.name.as_str(), m))
-        .collect();
+/// The files at `input_path` and the files they import, parsed, with the
+/// project config found for them.
+fn parse_input(
+    input_path: &Path,
+) -> Result<(Vec<m3l_core::ParsedFile>, Option<reader::ProjectConfig>), String> {
+    let files = read_m3l_files(input_path)?;
 
-    // Added models
-    for name in right_models.keys() {
-        if !left_models.contains_key(name) {
-            lines.push(format!("+ model {name}"));
-        }
+    if files.is_empty() {
+        return Err(format!(
+            "No M3L files (.m3l, .m3l.md, .md) found at: {}",
+            input_path.display()
+        ));
     }
 
-    // Removed models
-    for name in left_models.keys() {
-        if !right_models.contains_key(name) {
-            lines.push(format!("- model {name}"));
-        }
+    let project = find_project_config(input_path)?;
+    let root = project.as_ref().map(|p| p.dir.as_path());
+    let mut parsed_files = parse_files(&files);
+    // Pull in the files the input imports, and the files those import
+    let mut followed = 0;
+    while followed < parsed_files.len() {
+        let imported = read_imports(&parsed_files[followed..], &parsed_files, root)?;
+        followed = parsed_files.len();
+        parsed_files.extend(parse_files(&imported));
     }
+    Ok((parsed_files, project))
+}
 
-    // Changed models (field-level diff)
-    for (name, left_model) in &left_models {
-        if let Some(right_model) = right_models.get(name) {
-            let left_fields: std::collections::HashMap<&str, &m3l_core::FieldNode> = left_model
-                .fields
-                .iter()
-                .map(|f| (f.name.as_str(), f))
-                .collect();
-            let right_fields: std::collections::HashMap<&str, &m3l_core::FieldNode> = right_model
-                .fields
-                .iter()
-                .map(|f| (f.name.as_str(), f))
-                .collect();
-
-            for fname in right_fields.keys() {
-                if !left_fields.contains_key(fname) {
-                    lines.push(format!("+ {name}.{fname}"));
-                }
-            }
-            for fname in left_fields.keys() {
-                if !right_fields.contains_key(fname) {
-                    lines.push(format!("- {name}.{fname}"));
-                }
-            }
-            for (fname, lf) in &left_fields {
-                if let Some(rf) = right_fields.get(fname) {
-                    let mut changes = Vec::new();
-                    if lf.field_type != rf.field_type {
-                        changes.push(format!(
-                            "type: {} → {}",
-                            lf.field_type.as_deref().unwrap_or("none"),
-                            rf.field_type.as_deref().unwrap_or("none")
-                        ));
-                    }
-                    if lf.nullable != rf.nullable {
-                        changes.push(format!("nullable: {} → {}", lf.nullable, rf.nullable));
-                    }
-                    if lf.array != rf.array {
-                        changes.push(format!("array: {} → {}", lf.array, rf.array));
-                    }
-                    if !changes.is_empty() {
-                        lines.push(format!("~ {name}.{fname}: {}", changes.join(", ")));
-                    }
-                }
-            }
-        }
-    }
+/// Parse and resolve `files`, read from `input_path`, with the project info
+/// of its config. Imports are not followed, as the files may come from
+/// another revision than the working tree.
+fn resolve_files(input_path: &Path, files: &[reader::M3lFile]) -> Result<m3l_core::M3lAst, String> {
+    let project = find_project_config(input_path)?;
+    Ok(resolve_parsed(&parse_files(files), project))
+}
 
-    // Enum diff
-    let left_enums: std::collections::HashMap<&str, &m3l_core::EnumNode> = left_ast
-        .enums
-        .iter()
-        .map(|e| (e.name.as_str(), e))
-        .collect();
-    let right_enums: std::collections::HashMap<&str, &m3l_core::EnumNode> = right_ast
-        .enums
+fn parse_files(files: &[reader::M3lFile]) -> Vec<m3l_core::ParsedFile> {
+    files
         .iter()
-        .map(|e| (e.name.as_str(), e))
-        .collect();
-
-    for name in right_enums.keys() {
-        if !left_enums.contains_key(name) {
-            lines.push(format!("+ enum {name}"));
-        }
-    }
-    for name in left_enums.keys() {
-        if !right_enums.contains_key(name) {
-            lines.push(format!("- enum {name}"));
-        }
-    }
+        .map(|f| {
+            let start = Instant::now();
+            let parsed = parse_string(&f.content, &f.path);
+            tracing::debug!(
+                file = %f.path,
+                bytes = f.content.len(),
+                elapsed_ms = elapsed_ms(start),
+                "parsed"
+            );
+            parsed
+        })
+        .collect()
+}
 
-    if lines.is_empty() {
-        lines.push("No differences found.".into());
-    } else {
-        lines.sort();
-        let add_count = lines.iter().filter(|l| l.starts_with('+')).count();
-        let rem_count = lines.iter().filter(|l| l.starts_with('-')).count();
-        let mod_count = lines.iter().filter(|l| l.starts_with('~')).count();
-        lines.push(format!(
-            "\n{} added, {} removed, {} modified",
-            add_count, rem_count, mod_count
-        ));
-    }
+fn resolve_parsed(
+    parsed_files: &[m3l_core::ParsedFile],
+    project: Option<reader::ProjectConfig>,
+) -> m3l_core::M3lAst {
+    let (project_info, options) = match project {
+        Some(p) => (
+            Some(ProjectInfo {
+                name: p.config.name,
+                version: p.config.version,
+            }),
+            p.resolve,
+        ),
+        None => (None, ResolveOptions::default()),
+    };
 
-    Ok(lines.join("\n"))
+    let start = Instant::now();
+    let ast = resolve_with_options(parsed_files, project_info, &options);
+    tracing::info!(
+        files = ast.sources.len(),
+        models = ast.models.len(),
+        elapsed_ms = elapsed_ms(start),
+        "resolved"
+    );
+    ast
 }
 
-fn run_validate(input_path: &Path, strict: bool, format: &str) -> Result<(String, usize), String> {
-    let files = read_m3l_files(input_path)?;
+/// Milliseconds since `start`, for log fields.
+pub fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
 
-    if files.is_empty() {
+/// The AST of `input_path` in `format`, written to `output_file` (returning
+/// `None`) or returned for stdout. Text formats end with a newline on
+/// stdout; MessagePack is refused on a terminal.
+fn run_parse(
+    input_path: &Path,
+    format: &str,
+    compact: bool,
+    group_namespaces: bool,
+    output_file: Option<&Path>,
+) -> Result<Option<Vec<u8>>, String> {
+    if compact && format != "json" {
         return Err(format!(
-            "No M3L files (.m3l, .m3l.md, .md) found at: {}",
-            input_path.display()
+            "--compact only applies to --format json, not \"{format}\""
         ));
     }
+    if group_namespaces && format == "html-explorer" {
+        return Err("--group-namespaces does not apply to --format html-explorer".into());
+    }
+    if format == "msgpack" && output_file.is_none() && std::io::stdout().is_terminal() {
+        return Err(
+            "MessagePack output is binary; write it with --output or redirect stdout".into(),
+        );
+    }
+    let ast = build_ast(input_path)?;
+    if group_namespaces {
+        let value = ast.to_namespaced_value()?;
+        return write_parse_output(serialize_ast(&value, format, compact)?, format, output_file);
+    }
+    let output: Vec<u8> = match format {
+        "html-explorer" => commands::explorer::render_explorer(&ast)?.into_bytes(),
+        _ => serialize_ast(&ast, format, compact)?,
+    };
+    write_parse_output(output, format, output_file)
+}
 
-    let parsed_files: Vec<_> = files
-        .iter()
-        .map(|f| parse_string(&f.content, &f.path))
-        .collect();
+/// The AST (or its namespace-grouped value) in one of the data formats.
+fn serialize_ast<T: serde::Serialize>(
+    ast: &T,
+    format: &str,
+    compact: bool,
+) -> Result<Vec<u8>, String> {
+    Ok(match format {
+        "json" if compact => serde_json::to_string(ast)
+            .map_err(|e| format!("JSON serialization error: {e}"))?
+            .into_bytes(),
+        "json" => serde_json::to_string_pretty(ast)
+            .map_err(|e| format!("JSON serialization error: {e}"))?
+            .into_bytes(),
+        "yaml" => serde_yaml::to_string(ast)
+            .map_err(|e| format!("YAML serialization error: {e}"))?
+            .into_bytes(),
+        "msgpack" => rmp_serde::to_vec_named(ast)
+            .map_err(|e| format!("MessagePack serialization error: {e}"))?,
+        other => {
+            return Err(format!(
+                "Unknown format \"{other}\" (expected one of: json, yaml, msgpack, html-explorer)"
+            ))
+        }
+    })
+}
 
-    let project_info = if input_path.is_dir() {
-        read_project_config(input_path).map(|c| ProjectInfo {
-            name: c.name,
-            version: c.version,
-        })
-    } else {
-        None
-    };
+fn write_parse_output(
+    output: Vec<u8>,
+    format: &str,
+    output_file: Option<&Path>,
+) -> Result<Option<Vec<u8>>, String> {
+    if let Some(out_path) = output_file {
+        std::fs::write(out_path, &output)
+            .map_err(|e| format!("Failed to write {}: {e}", out_path.display()))?;
+        return Ok(None);
+    }
+
+    let mut output = output;
+    if format != "msgpack" && !output.ends_with(b"\n") {
+        output.push(b'\n');
+    }
+    Ok(Some(output))
+}
 
-    let ast = resolve(&parsed_files, project_info);
+fn run_validate(
+    input_path: &Path,
+    strict: bool,
+    format: &str,
+    color: bool,
+) -> Result<(String, usize, usize), String> {
+    let ast = build_ast(input_path)?;
+    let start = Instant::now();
     let result = validate(&ast, &ValidateOptions { strict });
+    tracing::info!(
+        errors = result.errors.len(),
+        warnings = result.warnings.len(),
+        elapsed_ms = elapsed_ms(start),
+        "validated"
+    );
 
     // ValidateResult already includes resolver diagnostics (cloned from AST)
     let error_count = result.errors.len();
@@ -369,21 +1287,30 @@ fn run_validate(input_path: &Path, strict: bool, format: &str) -> Result<(String
         });
         let json = serde_json::to_string_pretty(&output)
             .map_err(|e| format!("JSON serialization error: {e}"))?;
-        return Ok((json, error_count));
+        return Ok((json, error_count, warning_count));
     }
 
     // Human-readable format
+    let snippets: Vec<Snippet> = result
+        .errors
+        .iter()
+        .chain(result.warnings.iter())
+        .map(|d| Snippet {
+            severity: match d.severity {
+                m3l_core::DiagnosticSeverity::Error => "error",
+                m3l_core::DiagnosticSeverity::Warning => "warning",
+            },
+            code: &d.code,
+            file: &d.file,
+            line: d.line,
+            col: d.col,
+            message: &d.message,
+        })
+        .collect();
     let mut lines: Vec<String> = Vec::new();
-
-    for d in result.errors.iter().chain(result.warnings.iter()) {
-        let severity = match d.severity {
-            m3l_core::DiagnosticSeverity::Error => "error",
-            m3l_core::DiagnosticSeverity::Warning => "warning",
-        };
-        lines.push(format!(
-            "{}:{}:{} {}[{}]: {}",
-            d.file, d.line, d.col, severity, d.code, d.message
-        ));
+    if !snippets.is_empty() {
+        lines.push(render_snippets(&snippets, &mut Sources::default(), color));
+        lines.push(String::new());
     }
 
     let error_word = if error_count == 1 { "error" } else { "errors" };
@@ -397,5 +1324,5 @@ fn run_validate(input_path: &Path, strict: bool, format: &str) -> Result<(String
         "{error_count} {error_word}, {warning_count} {warning_word} in {file_count} {file_word}."
     ));
 
-    Ok((lines.join("\n"), error_count))
+    Ok((lines.join("\n"), error_count, warning_count))
 }
@@ -1,24 +1,118 @@
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
+use m3l_core::{import_target, ParsedFile, ResolveOptions, TypeAlias};
+use m3l_lint::LintConfig;
 use serde::Deserialize;
 
+use crate::commands::format::FormatOptions;
+use crate::commands::generate::GenerateTarget;
+use crate::elapsed_ms;
+
 /// A file with its path and content.
 pub struct M3lFile {
     pub path: String,
     pub content: String,
 }
 
-/// Project configuration from m3l.config.yaml.
-#[derive(Debug, Deserialize)]
+/// Config file names, in order of precedence when a directory has both.
+pub const CONFIG_FILES: &[&str] = &["m3l.toml", "m3l.config.yaml"];
+
+/// Project configuration from `m3l.toml` or `m3l.config.yaml`.
+#[derive(Debug, Default, Deserialize)]
 pub struct M3lConfig {
     pub name: Option<String>,
     pub version: Option<String>,
     pub sources: Option<Vec<String>>,
+    /// Glob patterns for project files, relative to the config; combined
+    /// with `sources`. Without either, the directory is scanned.
+    pub include: Option<Vec<String>>,
+    /// Glob patterns for files to leave out, relative to the config.
+    pub exclude: Option<Vec<String>>,
+    /// Formatter style for `m3l format`.
+    pub format: Option<FormatOptions>,
+    /// Rule levels for `m3l lint`.
+    pub lint: Option<LintConfig>,
+    /// Artifacts written by `m3l generate`.
+    pub generate: Option<Vec<GenerateTarget>>,
+    /// Defaults for `m3l validate` and `m3l check`.
+    pub validate: Option<ValidateConfig>,
+    /// Element order in the merged AST: `source`, `alphabetical` or
+    /// `dependency`.
+    pub order: Option<m3l_core::MergeOrder>,
+    /// Type aliases, alias name → type: `email = "string(320)"`.
+    pub types: Option<BTreeMap<String, String>>,
+    /// Settings applied to every model.
+    pub defaults: Option<DefaultsConfig>,
+}
+
+/// The `defaults` section.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DefaultsConfig {
+    /// Parents every model inherits, after the ones it names.
+    pub inherits: Vec<String>,
+}
+
+/// The `validate` section; command-line flags take precedence.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ValidateConfig {
+    /// Validate as if `--strict` were given.
+    pub strict: bool,
+    /// Fail when there are more warnings than this (`--max-warnings`).
+    pub max_warnings: Option<usize>,
+}
+
+/// A config file and the project directory it belongs to.
+#[derive(Debug)]
+pub struct ProjectConfig {
+    /// Directory holding the config file; relative paths in it start here.
+    pub dir: PathBuf,
+    pub file: PathBuf,
+    pub config: M3lConfig,
+    /// Order, type aliases and default parents from the config.
+    pub resolve: ResolveOptions,
+}
+
+/// Whether a path argument is a glob pattern rather than a literal path.
+pub fn is_glob(input_path: &Path) -> bool {
+    !input_path.exists()
+        && input_path
+            .to_string_lossy()
+            .contains(['*', '?', '['].as_slice())
 }
 
-/// Read M3L files from a path (file or directory).
+/// The directory a glob pattern is rooted at: its components up to the
+/// first one containing a wildcard.
+pub fn glob_base(pattern: &Path) -> PathBuf {
+    let base: PathBuf = pattern
+        .components()
+        .take_while(|c| {
+            !c.as_os_str()
+                .to_string_lossy()
+                .contains(['*', '?', '['].as_slice())
+        })
+        .collect();
+    if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    }
+}
+
+/// Read M3L files from a path (file, directory or glob pattern).
 pub fn read_m3l_files(input_path: &Path) -> Result<Vec<M3lFile>, String> {
+    if is_glob(input_path) {
+        let paths = expand_globs(Path::new(""), &[input_path.to_string_lossy().to_string()])?;
+        if paths.is_empty() {
+            return Err(format!("No files match pattern: {}", input_path.display()));
+        }
+        return read_paths(paths);
+    }
+
     if !input_path.exists() {
         return Err(format!("Path does not exist: {}", input_path.display()));
     }
@@ -33,14 +127,24 @@ pub fn read_m3l_files(input_path: &Path) -> Result<Vec<M3lFile>, String> {
     }
 
     if input_path.is_dir() {
-        // Check for m3l.config.yaml
-        let config_path = input_path.join("m3l.config.yaml");
-        if config_path.exists() {
-            return read_from_config(&config_path, input_path);
+        // The project config, in the directory or above it, selects the
+        // files; under a subdirectory, only the selected files in it
+        if let Some(project) = find_project_config(input_path)? {
+            tracing::debug!(config = %project.file.display(), "using project config");
+            let input = std::path::absolute(input_path)
+                .map_err(|e| format!("Failed to resolve {}: {e}", input_path.display()))?;
+            if project.dir == input {
+                return read_paths(config_paths(&project.config, input_path)?);
+            }
+            let paths = config_paths(&project.config, &project.dir)?
+                .into_iter()
+                .filter_map(|path| Some(input_path.join(path.strip_prefix(&input).ok()?)))
+                .collect();
+            return read_paths(paths);
         }
 
         // Default: scan for *.m3l.md and *.m3l files
-        return scan_directory(input_path);
+        return read_paths(scan_directory(input_path)?);
     }
 
     Err(format!(
@@ -49,18 +153,218 @@ pub fn read_m3l_files(input_path: &Path) -> Result<Vec<M3lFile>, String> {
     ))
 }
 
-/// Read project config from m3l.config.yaml if it exists.
-pub fn read_project_config(dir_path: &Path) -> Option<M3lConfig> {
-    let config_path = dir_path.join("m3l.config.yaml");
-    if !config_path.exists() {
-        return None;
+/// Files named by the `@import` directives of `new` that are not among
+/// `loaded` (compared by canonical path), each read once. Relative imports
+/// (`./`, `../`) start at the importing file's directory; package paths at
+/// `root`, the project directory, or without one also at the importing
+/// file. Imports of files that do not exist are left to the resolver, which
+/// reports them as `M3L-E013`.
+pub fn read_imports(
+    new: &[ParsedFile],
+    loaded: &[ParsedFile],
+    root: Option<&Path>,
+) -> Result<Vec<M3lFile>, String> {
+    let mut seen: HashSet<PathBuf> = loaded
+        .iter()
+        .filter_map(|f| fs::canonicalize(&f.source).ok())
+        .collect();
+    let mut files = Vec::new();
+    for file in new {
+        for import in &file.imports {
+            let import = &import.path;
+            let relative = import.starts_with("./") || import.starts_with("../");
+            let path = match root {
+                Some(root) if !relative => normalize(&root.join(import)),
+                _ => PathBuf::from(import_target(&file.source, import)),
+            };
+            let Ok(canonical) = fs::canonicalize(&path) else {
+                continue;
+            };
+            if !seen.insert(canonical) {
+                continue;
+            }
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+            tracing::debug!(file = %path.display(), importer = %file.source, "following import");
+            files.push(M3lFile {
+                path: path.to_string_lossy().to_string(),
+                content,
+            });
+        }
+    }
+    Ok(files)
+}
+
+/// The paths among `candidates` (relative to the current directory, or
+/// absolute when `input_path` is) that `read_m3l_files(input_path)` would
+/// read if they were on disk: those under the input directory with an M3L
+/// extension, narrowed by the directory's project config; those matching a
+/// glob; or the input file itself.
+pub fn select_m3l_paths(input_path: &Path, candidates: &[String]) -> Result<Vec<String>, String> {
+    let input = normalize(input_path);
+    if is_glob(input_path) {
+        let pattern = glob::Pattern::new(&input.to_string_lossy())
+            .map_err(|e| format!("Invalid glob pattern: {e}"))?;
+        return Ok(candidates
+            .iter()
+            .filter(|c| pattern.matches_path_with(&normalize(Path::new(c)), SEPARATOR_AWARE))
+            .cloned()
+            .collect());
+    }
+    if !input_path.is_dir() {
+        return Ok(candidates
+            .iter()
+            .filter(|c| normalize(Path::new(c)) == input)
+            .cloned()
+            .collect());
     }
 
-    let content = fs::read_to_string(&config_path).ok()?;
-    serde_yaml::from_str(&content).ok()
+    let patterns = |patterns: &Option<Vec<String>>| {
+        patterns
+            .iter()
+            .flatten()
+            .map(|p| glob::Pattern::new(p).map_err(|e| format!("Invalid glob pattern '{p}': {e}")))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    // Config patterns are relative to the config's directory, which may be
+    // above the input directory
+    let (include, exclude, prefix) = match find_project_config(input_path)? {
+        Some(project) => {
+            let config = &project.config;
+            let mut include = patterns(&config.sources)?;
+            include.extend(patterns(&config.include)?);
+            let input = std::path::absolute(input_path)
+                .map_err(|e| format!("Failed to resolve {}: {e}", input_path.display()))?;
+            let prefix = input
+                .strip_prefix(&project.dir)
+                .map(Path::to_path_buf)
+                .unwrap_or_default();
+            (include, patterns(&config.exclude)?, prefix)
+        }
+        None => (Vec::new(), Vec::new(), PathBuf::new()),
+    };
+    Ok(candidates
+        .iter()
+        .filter(|c| {
+            let Ok(relative) = normalize(Path::new(c))
+                .strip_prefix(&input)
+                .map(|p| prefix.join(p))
+            else {
+                return false;
+            };
+            let included = if include.is_empty() {
+                m3l_core::is_m3l_path(&relative)
+            } else {
+                include
+                    .iter()
+                    .any(|p| p.matches_path_with(&relative, SEPARATOR_AWARE))
+            };
+            included && !exclude.iter().any(|p| p.matches_path(&relative))
+        })
+        .cloned()
+        .collect())
 }
 
-fn scan_directory(dir_path: &Path) -> Result<Vec<M3lFile>, String> {
+/// Glob matching where `*` stops at `/`, as when globbing the filesystem.
+const SEPARATOR_AWARE: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// `path` without `.` components, so `./models/a.m3l.md` and
+/// `models/a.m3l.md` compare equal.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, std::path::Component::CurDir))
+        .collect()
+}
+
+/// The project config for `input_path`: the nearest `m3l.toml` or
+/// `m3l.config.yaml` in the input directory (the file's directory, or the
+/// base of a glob) or any directory above it. A config that cannot be read
+/// or parsed is an error rather than being ignored.
+pub fn find_project_config(input_path: &Path) -> Result<Option<ProjectConfig>, String> {
+    let start = if is_glob(input_path) {
+        glob_base(input_path)
+    } else if input_path.is_file() {
+        input_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf()
+    } else {
+        input_path.to_path_buf()
+    };
+    let start = std::path::absolute(&start)
+        .map_err(|e| format!("Failed to resolve {}: {e}", start.display()))?;
+    for dir in start.ancestors() {
+        if let Some(file) = config_file_in(dir) {
+            tracing::debug!(config = %file.display(), "found project config");
+            let config = parse_config(&file)?;
+            let resolve = resolve_options(&config)
+                .map_err(|e| format!("Invalid config {}: {e}", file.display()))?;
+            return Ok(Some(ProjectConfig {
+                dir: dir.to_path_buf(),
+                file,
+                config,
+                resolve,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// The `order`, `types` and `defaults` settings as resolve options.
+fn resolve_options(config: &M3lConfig) -> Result<ResolveOptions, String> {
+    let type_aliases = config
+        .types
+        .iter()
+        .flatten()
+        .map(|(name, expansion)| TypeAlias::new(name, expansion))
+        .collect::<Result<_, _>>()?;
+    Ok(ResolveOptions {
+        order: config.order.unwrap_or_default(),
+        type_aliases,
+        default_inherits: config
+            .defaults
+            .as_ref()
+            .map(|d| d.inherits.clone())
+            .unwrap_or_default(),
+    })
+}
+
+/// The config file directly in `dir`, `m3l.toml` first.
+fn config_file_in(dir: &Path) -> Option<PathBuf> {
+    let mut found = CONFIG_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|path| path.is_file());
+    let first = found.next()?;
+    if let Some(ignored) = found.next() {
+        tracing::warn!(
+            config = %first.display(),
+            ignored = %ignored.display(),
+            "two project configs in one directory; using the first"
+        );
+    }
+    Some(first)
+}
+
+/// Parse a config file as TOML or YAML, by its extension.
+fn parse_config(path: &Path) -> Result<M3lConfig, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str(&content).map_err(|e| format!("Invalid config {}: {e}", path.display()))
+    } else {
+        serde_yaml::from_str(&content)
+            .map_err(|e| format!("Invalid config {}: {e}", path.display()))
+    }
+}
+
+fn scan_directory(dir_path: &Path) -> Result<Vec<PathBuf>, String> {
+    let start = Instant::now();
     // Scan *.m3l.md, *.m3l, and *.md — all three extensions are valid M3L files.
     let patterns = [
         dir_path.join("**/*.m3l.md"),
@@ -91,36 +395,22 @@ fn scan_directory(dir_path: &Path) -> Result<Vec<M3lFile>, String> {
     }
 
     paths.sort();
-
-    let mut files = Vec::new();
-    for path in paths {
-        let content = fs::read_to_string(&path)
-            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
-        files.push(M3lFile {
-            path: path.to_string_lossy().to_string(),
-            content,
-        });
-    }
-
-    Ok(files)
+    tracing::debug!(
+        dir = %dir_path.display(),
+        files = paths.len(),
+        elapsed_ms = elapsed_ms(start),
+        "scanned directory"
+    );
+    Ok(paths)
 }
 
-fn read_from_config(config_path: &Path, base_dir: &Path) -> Result<Vec<M3lFile>, String> {
-    let yaml_content =
-        fs::read_to_string(config_path).map_err(|e| format!("Failed to read config: {}", e))?;
-
-    let config: M3lConfig =
-        serde_yaml::from_str(&yaml_content).map_err(|e| format!("Invalid YAML config: {}", e))?;
-
-    let source_patterns = match config.sources {
-        Some(ref s) if !s.is_empty() => s.clone(),
-        _ => return scan_directory(base_dir),
-    };
-
-    let mut files: Vec<M3lFile> = Vec::new();
-    let mut seen: std::collections::HashSet<PathBuf> = std::collections::HashSet::new();
+/// Expand glob patterns relative to `base_dir`, keeping pattern order and
+/// sorting the matches of each pattern. Directories are skipped.
+fn expand_globs(base_dir: &Path, patterns: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    let mut seen: HashSet<PathBuf> = HashSet::new();
 
-    for pattern in &source_patterns {
+    for pattern in patterns {
         let full_pattern = base_dir.join(pattern);
         let pattern_str = full_pattern.to_string_lossy().replace('\\', "/");
         let entries = glob::glob(&pattern_str)
@@ -130,8 +420,7 @@ fn read_from_config(config_path: &Path, base_dir: &Path) -> Result<Vec<M3lFile>,
         for entry in entries {
             match entry {
                 Ok(path) => {
-                    if !seen.contains(&path) {
-                        seen.insert(path.clone());
+                    if path.is_file() && seen.insert(path.clone()) {
                         matched.push(path);
                     }
                 }
@@ -139,16 +428,65 @@ fn read_from_config(config_path: &Path, base_dir: &Path) -> Result<Vec<M3lFile>,
             }
         }
         matched.sort();
+        tracing::debug!(pattern = %pattern_str, files = matched.len(), "expanded glob");
+        paths.extend(matched);
+    }
+
+    Ok(paths)
+}
+
+/// The files a config's `sources`, `include` and `exclude` select under
+/// its directory `base_dir`.
+fn config_paths(config: &M3lConfig, base_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let include: Vec<String> = config
+        .sources
+        .iter()
+        .chain(config.include.iter())
+        .flatten()
+        .cloned()
+        .collect();
+    let mut paths = if include.is_empty() {
+        scan_directory(base_dir)?
+    } else {
+        expand_globs(base_dir, &include)?
+    };
 
-        for path in matched {
+    let exclude = config
+        .exclude
+        .iter()
+        .flatten()
+        .map(|p| {
+            glob::Pattern::new(p).map_err(|e| format!("Invalid exclude pattern '{}': {}", p, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if !exclude.is_empty() {
+        paths.retain(|path| {
+            let relative = path.strip_prefix(base_dir).unwrap_or(path);
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            !exclude.iter().any(|p| p.matches(&relative))
+        });
+    }
+    Ok(paths)
+}
+
+fn read_paths(paths: Vec<PathBuf>) -> Result<Vec<M3lFile>, String> {
+    let start = Instant::now();
+    let files = paths
+        .into_iter()
+        .map(|path| {
             let content = fs::read_to_string(&path)
                 .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
-            files.push(M3lFile {
+            Ok(M3lFile {
                 path: path.to_string_lossy().to_string(),
                 content,
-            });
-        }
-    }
-
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    tracing::info!(
+        files = files.len(),
+        bytes = files.iter().map(|f| f.content.len()).sum::<usize>(),
+        elapsed_ms = elapsed_ms(start),
+        "read files"
+    );
     Ok(files)
 }
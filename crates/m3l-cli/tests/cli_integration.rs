@@ -71,6 +71,33 @@ fn cli_parse_directory() {
     assert_eq!(sources.len(), 2); // base.m3l.md + inventory.m3l.md
 }
 
+#[test]
+fn cli_parse_follows_imports() {
+    let output = m3l_bin()
+        .args(["parse", "samples/multi/inventory.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let ast: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    assert_eq!(
+        ast["sources"],
+        serde_json::json!([
+            "samples/multi/inventory.m3l.md",
+            "samples/multi/base.m3l.md"
+        ])
+    );
+    assert!(
+        ast["errors"].as_array().unwrap().is_empty(),
+        "{}",
+        ast["errors"]
+    );
+}
+
 #[test]
 fn cli_parse_nonexistent() {
     let output = m3l_bin()
@@ -158,6 +185,44 @@ fn cli_parse_output_file() {
     std::fs::remove_file(&tmp).ok();
 }
 
+#[test]
+fn cli_parse_yaml_msgpack_and_compact() {
+    let parse = |args: &[&str]| {
+        let output = m3l_bin()
+            .args(["parse", "samples/multi"])
+            .args(args)
+            .output()
+            .expect("failed to run");
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        output.stdout
+    };
+    let pretty: serde_json::Value = serde_json::from_slice(&parse(&[])).expect("invalid JSON");
+
+    let compact = parse(&["--compact"]);
+    assert_eq!(String::from_utf8_lossy(&compact).lines().count(), 1);
+    let from_compact: serde_json::Value = serde_json::from_slice(&compact).expect("invalid JSON");
+    assert_eq!(from_compact, pretty);
+
+    let from_yaml: serde_json::Value =
+        serde_yaml::from_slice(&parse(&["--format", "yaml"])).expect("invalid YAML");
+    assert_eq!(from_yaml, pretty);
+
+    let from_msgpack: serde_json::Value =
+        rmp_serde::from_slice(&parse(&["--format", "msgpack"])).expect("invalid MessagePack");
+    assert_eq!(from_msgpack, pretty);
+
+    let output = m3l_bin()
+        .args(["parse", "samples/multi", "--format", "yaml", "--compact"])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--compact"));
+}
+
 // ── Lint tests ───────────────────────────────────────────────
 
 #[test]
@@ -517,6 +582,139 @@ fn validate_e010_relations_no_ref() {
     );
 }
 
+#[test]
+fn validate_e011_unresolved_reference() {
+    let output = m3l_bin()
+        .args([
+            "validate",
+            "samples/test/validate/e011-unresolved-reference.m3l.md",
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let result: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON");
+    let e011: Vec<&serde_json::Value> = result["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["code"] == "M3L-E011")
+        .collect();
+    // Wrong namespace and missing field are reported; the qualified reference resolves
+    assert_eq!(e011.len(), 2, "diagnostics: {e011:?}");
+    assert!(e011
+        .iter()
+        .any(|d| d["message"].as_str().unwrap().contains("test.billing.User")));
+    assert!(e011
+        .iter()
+        .any(|d| d["message"].as_str().unwrap().contains("uuid")));
+}
+
+#[test]
+fn validate_e013_missing_import() {
+    let output = m3l_bin()
+        .args([
+            "validate",
+            "samples/test/validate/e013-missing-import.m3l.md",
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let result: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON");
+    assert!(
+        result["diagnostics"].as_array().unwrap().iter().any(|d| {
+            d["code"] == "M3L-E013"
+                && d["message"]
+                    .as_str()
+                    .unwrap()
+                    .contains("./shared/audit.m3l.md")
+        }),
+        "Expected M3L-E013 in diagnostics: {stdout}"
+    );
+}
+
+#[test]
+fn validate_syntax_diagnostics() {
+    let output = m3l_bin()
+        .args([
+            "validate",
+            "samples/test/validate/p-syntax.m3l.md",
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let result: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON");
+    let found: Vec<(String, u64, u64)> = result["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["code"].as_str().unwrap().starts_with("M3L-P"))
+        .map(|d| {
+            (
+                d["code"].as_str().unwrap().to_string(),
+                d["line"].as_u64().unwrap(),
+                d["col"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("M3L-P001".to_string(), 6, 15),
+            ("M3L-P001".to_string(), 7, 16),
+            ("M3L-P003".to_string(), 8, 3),
+            ("M3L-P002".to_string(), 10, 11),
+        ],
+        "{stdout}"
+    );
+}
+
+#[test]
+fn validate_e014_e015_selective_import() {
+    let output = m3l_bin()
+        .args([
+            "validate",
+            "samples/test/validate/selective-import/orders.m3l.md",
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let result: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON");
+    let codes: Vec<(&str, &str)> = result["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| (d["code"].as_str().unwrap(), d["message"].as_str().unwrap()))
+        .collect();
+    // BaseModel is listed, so inheriting from it resolves
+    assert!(
+        !codes.iter().any(|(c, _)| *c == "M3L-E007"),
+        "diagnostics: {codes:?}"
+    );
+    assert!(codes
+        .iter()
+        .any(|(c, m)| *c == "M3L-E014" && m.contains("\"Auditable\"")));
+    let e015: Vec<&str> = codes
+        .iter()
+        .filter(|(c, _)| *c == "M3L-E015")
+        .map(|(_, m)| *m)
+        .collect();
+    assert_eq!(e015.len(), 2, "diagnostics: {codes:?}");
+    assert!(e015.iter().any(|m| m.contains("\"Customer\"")));
+    assert!(e015.iter().any(|m| m.contains("\"OrderStatus\"")));
+}
+
 // ══════════════════════════════════════════════════════════════
 // Validate — warning codes (dedicated fixtures)
 // ══════════════════════════════════════════════════════════════
@@ -663,6 +861,107 @@ fn validate_clean_fixture() {
     assert_eq!(result["summary"]["warnings"], 0);
 }
 
+#[test]
+fn validate_human_output_shows_source_snippet() {
+    let fixture = "samples/test/validate/e009-undefined-type.m3l.md";
+    let output = m3l_bin()
+        .args(["validate", fixture])
+        .output()
+        .expect("failed to run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("error[M3L-E009]: Undefined type \"foobar_type\""),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains(&format!(" --> {fixture}:7:1")),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("7 | - data: foobar_type\n  |         ^^^^^^^^^^^\n"),
+        "stdout: {stdout}"
+    );
+    // Piped output has no color unless asked for
+    assert!(!stdout.contains('\x1b'), "stdout: {stdout}");
+
+    let output = m3l_bin()
+        .args(["validate", fixture, "--color", "always"])
+        .output()
+        .expect("failed to run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("\x1b[1;31merror[M3L-E009]\x1b[0m"),
+        "stdout: {stdout}"
+    );
+
+    let output = m3l_bin()
+        .args([
+            "lint",
+            "samples/test/lint/naming-bad.m3l.md",
+            "--color",
+            "never",
+        ])
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("warning[naming-convention]"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains(" | "), "stdout: {stdout}");
+    assert!(!stdout.contains('\x1b'), "stdout: {stdout}");
+}
+
+#[test]
+fn validate_and_lint_max_warnings() {
+    let fixture = "samples/test/validate/w003-deprecated.m3l.md";
+    let output = m3l_bin()
+        .args(["validate", fixture, "--max-warnings", "2"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+
+    let output = m3l_bin()
+        .args(["validate", fixture, "--max-warnings", "1"])
+        .output()
+        .expect("failed to run");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("2 warnings exceed the --max-warnings limit of 1"),
+        "stderr: {stderr}"
+    );
+    // The report is still printed
+    assert!(String::from_utf8_lossy(&output.stdout).contains("warning[M3L-W003]"));
+
+    // Info-level lint findings do not count against the budget
+    let output = m3l_bin()
+        .args([
+            "lint",
+            "samples/test/lint/similar-fields.m3l.md",
+            "--max-warnings",
+            "0",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = m3l_bin()
+        .args([
+            "lint",
+            "samples/test/lint/naming-bad.m3l.md",
+            "--max-warnings",
+            "0",
+        ])
+        .output()
+        .expect("failed to run");
+    assert_eq!(output.status.code(), Some(1));
+}
+
 // ══════════════════════════════════════════════════════════════
 // Lint — rule-specific fixtures
 // ══════════════════════════════════════════════════════════════
@@ -691,6 +990,38 @@ fn lint_naming_convention() {
     );
 }
 
+#[test]
+fn lint_rule_levels_from_config() {
+    let dir = std::env::temp_dir().join("m3l-lint-config-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(
+        workspace_root().join("samples/test/lint/naming-bad.m3l.md"),
+        dir.join("naming-bad.m3l.md"),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("m3l.config.yaml"),
+        "lint:\n  rules:\n    naming-convention: off\n",
+    )
+    .unwrap();
+
+    let output = m3l_bin()
+        .args(["lint", dir.to_str().unwrap(), "--format", "json"])
+        .output()
+        .expect("failed to run");
+    std::fs::remove_dir_all(&dir).ok();
+    assert!(output.status.success());
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    assert!(
+        !result["diagnostics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|d| d["rule"] == "naming-convention"),
+        "naming-convention is off in the config: {result}"
+    );
+}
+
 #[test]
 fn lint_model_size() {
     let output = m3l_bin()
@@ -764,399 +1095,4036 @@ fn lint_relation_complexity() {
 }
 
 #[test]
-fn lint_clean_fixture() {
+fn lint_enum_value_naming() {
     let output = m3l_bin()
-        .args(["lint", "samples/test/lint/clean.m3l.md", "--format", "json"])
+        .args([
+            "lint",
+            "samples/test/lint/enum-values.m3l.md",
+            "--format",
+            "json",
+        ])
         .output()
         .expect("failed to run");
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
     let result: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON");
-    assert_eq!(
-        result["summary"]["count"], 0,
-        "Expected 0 lint issues for clean fixture"
+    let hits: Vec<&serde_json::Value> = result["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["rule"] == "enum-value-naming")
+        .collect();
+    assert!(
+        hits.iter()
+            .any(|d| d["message"].as_str().unwrap().contains("mixes")),
+        "Expected mixed-convention hit"
     );
+    assert_eq!(hits.len(), 3, "Expected 2 value hits plus 1 mixed hit");
 }
 
-// ══════════════════════════════════════════════════════════════
-// Diff — dedicated fixtures
-// ══════════════════════════════════════════════════════════════
-
 #[test]
-fn diff_known_changes() {
+fn lint_description_quality() {
     let output = m3l_bin()
         .args([
-            "diff",
-            "samples/test/diff/v1.m3l.md",
-            "samples/test/diff/v2.m3l.md",
+            "lint",
+            "samples/test/lint/weak-descriptions.m3l.md",
+            "--format",
+            "json",
         ])
         .output()
         .expect("failed to run");
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("+ model NewModel"), "stdout: {stdout}");
-    assert!(stdout.contains("- model OldModel"), "stdout: {stdout}");
-    assert!(stdout.contains("+ Customer.age"), "stdout: {stdout}");
-    assert!(stdout.contains("~ Customer.phone"), "stdout: {stdout}");
+    let result: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON");
+    let hits = result["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["rule"] == "description-quality")
+        .count();
+    assert_eq!(hits, 2, "Expected repeated-name and too-short hits");
 }
 
 #[test]
-fn diff_reverse() {
+fn lint_view_fields() {
     let output = m3l_bin()
         .args([
-            "diff",
-            "samples/test/diff/v2.m3l.md",
-            "samples/test/diff/v1.m3l.md",
+            "lint",
+            "samples/test/lint/view-no-fields.m3l.md",
+            "--format",
+            "json",
         ])
         .output()
         .expect("failed to run");
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
-    // Reversing swaps additions and removals
-    assert!(stdout.contains("+ model OldModel"), "stdout: {stdout}");
-    assert!(stdout.contains("- model NewModel"), "stdout: {stdout}");
-    assert!(stdout.contains("- Customer.age"), "stdout: {stdout}");
+    let result: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON");
+    assert!(
+        result["diagnostics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|d| d["rule"] == "view-fields"),
+        "Expected view-fields rule hit"
+    );
 }
 
 #[test]
-fn diff_summary_counts() {
+fn lint_inheritance_depth() {
     let output = m3l_bin()
         .args([
-            "diff",
-            "samples/test/diff/v1.m3l.md",
-            "samples/test/diff/v2.m3l.md",
+            "lint",
+            "samples/test/lint/deep-inheritance.m3l.md",
+            "--format",
+            "json",
         ])
         .output()
         .expect("failed to run");
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("2 added"), "stdout: {stdout}");
-    assert!(stdout.contains("1 removed"), "stdout: {stdout}");
-    assert!(stdout.contains("1 modified"), "stdout: {stdout}");
+    let result: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON");
+    assert!(
+        result["diagnostics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|d| d["rule"] == "inheritance-depth"),
+        "Expected inheritance-depth rule hit"
+    );
 }
 
-// ══════════════════════════════════════════════════════════════
-// Analyze — dedicated fixtures
-// ══════════════════════════════════════════════════════════════
-
 #[test]
-fn analyze_mermaid_edges() {
+fn lint_clean_fixture() {
     let output = m3l_bin()
-        .args(["analyze", "samples/test/analyze/graph.m3l.md"])
+        .args(["lint", "samples/test/lint/clean.m3l.md", "--format", "json"])
         .output()
         .expect("failed to run");
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("graph LR"), "stdout: {stdout}");
-    assert!(
-        stdout.contains("-->|inherits|"),
-        "Expected inheritance edge, stdout: {stdout}"
-    );
-    assert!(
-        stdout.contains("-->|ref|"),
-        "Expected reference edge, stdout: {stdout}"
+    let result: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON");
+    assert_eq!(
+        result["summary"]["count"], 0,
+        "Expected 0 lint issues for clean fixture"
     );
 }
 
+// ══════════════════════════════════════════════════════════════
+// Check — validate and lint in one pass
+// ══════════════════════════════════════════════════════════════
+
 #[test]
-fn analyze_dot_format() {
+fn check_combines_validate_and_lint() {
     let output = m3l_bin()
-        .args([
-            "analyze",
-            "samples/test/analyze/graph.m3l.md",
-            "--format",
-            "dot",
-        ])
+        .args(["check", "samples/01-ecommerce.m3l.md"])
         .output()
         .expect("failed to run");
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("digraph M3L"), "stdout: {stdout}");
-    assert!(stdout.contains("rankdir=LR"), "stdout: {stdout}");
-    assert!(stdout.contains("->"), "stdout: {stdout}");
-    assert!(
-        stdout.contains("style=dashed"),
-        "Expected inherits edge style, stdout: {stdout}"
-    );
+    assert!(stdout.contains("warning[view-fields]"), "stdout: {stdout}");
+    assert!(stdout.contains("0 errors"), "stdout: {stdout}");
+
+    let output = m3l_bin()
+        .args(["check", "samples/test/validate", "--format", "json"])
+        .output()
+        .expect("failed to run");
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    let diagnostics = report["diagnostics"].as_array().unwrap();
+    assert!(diagnostics.iter().any(|d| d["source"] == "validate"));
+    assert!(diagnostics.iter().any(|d| d["source"] == "lint"));
+    assert!(report["summary"]["errors"].as_u64().unwrap() > 0);
+
+    let output = m3l_bin()
+        .args(["check", "samples/test/validate", "--format", "sarif"])
+        .output()
+        .expect("failed to run");
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid SARIF");
+    let tools: Vec<&str> = sarif["runs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["tool"]["driver"]["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(tools, ["m3l-validate", "m3l-lint"]);
 }
 
+// ══════════════════════════════════════════════════════════════
+// Explain — code and lint rule reference
+// ══════════════════════════════════════════════════════════════
+
 #[test]
-fn analyze_isolated_node() {
+fn explain_code_rule_and_list() {
     let output = m3l_bin()
-        .args(["analyze", "samples/test/analyze/graph.m3l.md"])
+        .args(["explain", "M3L-E005"])
         .output()
         .expect("failed to run");
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
-    // Isolated node appears standalone without any edge
-    assert!(stdout.contains("    Isolated"), "stdout: {stdout}");
     assert!(
-        !stdout.contains("Isolated -->") && !stdout.contains("--> Isolated"),
-        "Isolated node should have no edges, stdout: {stdout}"
+        stdout.starts_with("M3L-E005: Duplicate name (error)"),
+        "stdout: {stdout}"
     );
+    assert!(stdout.contains("Bad:\n    ## Customer"), "stdout: {stdout}");
+    assert!(stdout.contains("Fixed:"), "stdout: {stdout}");
+
+    let output = m3l_bin()
+        .args(["explain", "naming-convention", "--format", "json"])
+        .output()
+        .expect("failed to run");
+    let doc: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    assert_eq!(doc["kind"], "lint");
+
+    let output = m3l_bin().arg("explain").output().expect("failed to run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("M3L-W006"), "stdout: {stdout}");
+    assert!(stdout.contains("enum-value-naming"), "stdout: {stdout}");
+
+    let output = m3l_bin()
+        .args(["explain", "M3L-E999"])
+        .output()
+        .expect("failed to run");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown code \"M3L-E999\""));
 }
 
 // ══════════════════════════════════════════════════════════════
-// Format — dedicated fixtures
+// Diff — dedicated fixtures
 // ══════════════════════════════════════════════════════════════
 
 #[test]
-fn format_full_features() {
+fn diff_known_changes() {
     let output = m3l_bin()
-        .args(["format", "samples/test/format/full.m3l.md"])
+        .args([
+            "diff",
+            "samples/test/diff/v1.m3l.md",
+            "samples/test/diff/v2.m3l.md",
+        ])
         .output()
         .expect("failed to run");
-    assert!(
-        output.status.success(),
-        "stderr: {}",
-        String::from_utf8_lossy(&output.stderr)
-    );
+    assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("# Namespace:"), "stdout: {stdout}");
-    assert!(stdout.contains("::interface"), "stdout: {stdout}");
-    assert!(stdout.contains("::enum"), "stdout: {stdout}");
-    assert!(stdout.contains("::view"), "stdout: {stdout}");
-    assert!(stdout.contains("## Customer"), "stdout: {stdout}");
+    assert!(stdout.contains("+ model NewModel"), "stdout: {stdout}");
+    assert!(stdout.contains("- model OldModel"), "stdout: {stdout}");
+    assert!(stdout.contains("+ Customer.age"), "stdout: {stdout}");
+    assert!(stdout.contains("~ Customer.phone"), "stdout: {stdout}");
 }
 
 #[test]
-fn format_nested_fields() {
+fn diff_reverse() {
     let output = m3l_bin()
-        .args(["format", "samples/test/format/full.m3l.md"])
+        .args([
+            "diff",
+            "samples/test/diff/v2.m3l.md",
+            "samples/test/diff/v1.m3l.md",
+        ])
         .output()
         .expect("failed to run");
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
-    // Nested fields should be indented
-    assert!(
-        stdout.contains("  - bio:") || stdout.contains("  - avatar_url:"),
-        "Expected indented nested fields, stdout: {stdout}"
-    );
+    // Reversing swaps additions and removals
+    assert!(stdout.contains("+ model OldModel"), "stdout: {stdout}");
+    assert!(stdout.contains("- model NewModel"), "stdout: {stdout}");
+    assert!(stdout.contains("- Customer.age"), "stdout: {stdout}");
 }
 
 #[test]
-fn format_roundtrip() {
-    // Format → write to temp file → parse → should produce valid JSON AST
+fn diff_summary_counts() {
     let output = m3l_bin()
-        .args(["format", "samples/test/format/full.m3l.md"])
+        .args([
+            "diff",
+            "samples/test/diff/v1.m3l.md",
+            "samples/test/diff/v2.m3l.md",
+        ])
         .output()
         .expect("failed to run");
     assert!(output.status.success());
-    let formatted = String::from_utf8_lossy(&output.stdout);
-
-    let tmp = std::env::temp_dir().join("m3l-format-roundtrip.m3l.md");
-    std::fs::write(&tmp, formatted.as_ref()).expect("write tmp");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2 added"), "stdout: {stdout}");
+    assert!(stdout.contains("1 removed"), "stdout: {stdout}");
+    assert!(stdout.contains("2 modified"), "stdout: {stdout}");
+}
 
-    let parse_out = m3l_bin()
-        .args(["parse", tmp.to_str().unwrap()])
+#[test]
+fn diff_json_change_records() {
+    let output = m3l_bin()
+        .args([
+            "diff",
+            "samples/test/diff/v1.m3l.md",
+            "samples/test/diff/v2.m3l.md",
+            "--format",
+            "json",
+        ])
         .output()
         .expect("failed to run");
-    assert!(
-        parse_out.status.success(),
-        "Roundtrip parse failed, stderr: {}",
-        String::from_utf8_lossy(&parse_out.stderr)
-    );
-    let stdout = String::from_utf8_lossy(&parse_out.stdout);
-    let ast: serde_json::Value =
-        serde_json::from_str(&stdout).expect("invalid JSON from roundtrip");
-    assert!(
-        ast["models"].is_array(),
-        "Roundtrip should produce models array"
-    );
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(json["summary"]["added"], 2);
+    assert_eq!(json["summary"]["removed"], 1);
+    assert_eq!(json["summary"]["modified"], 2);
 
-    std::fs::remove_file(&tmp).ok();
+    let changes = json["changes"].as_array().unwrap();
+    let find = |path: &str| changes.iter().find(|c| c["path"] == path).unwrap();
+    let removed = find("OldModel");
+    assert_eq!(removed["change"], "removed");
+    assert_eq!(removed["entity"], "model");
+    assert!(removed["location"]["file"]
+        .as_str()
+        .unwrap()
+        .ends_with("v1.m3l.md"));
+
+    let phone = find("Customer.phone");
+    assert_eq!(phone["change"], "modified");
+    assert_eq!(phone["entity"], "field");
+    assert_eq!(
+        phone["properties"],
+        serde_json::json!([{ "property": "type", "before": "phone", "after": "string" }])
+    );
+    assert!(phone["location"]["file"]
+        .as_str()
+        .unwrap()
+        .ends_with("v2.m3l.md"));
+    assert!(phone["location"]["line"].as_u64().unwrap() > 0);
 }
 
 #[test]
-#[ignore] // BUG: formatter inlines inherited fields but keeps `: Parent`, causing duplication on re-format
-fn format_idempotent() {
-    // format(input) should equal format(format(input))
-    // i.e., formatting twice produces the same output
-    let inputs = &[
-        "samples/test/format/full.m3l.md",
-        "spec/conformance/inputs/01-ecommerce.m3l.md",
-        "spec/conformance/inputs/02-blog-cms.m3l.md",
-        "spec/conformance/inputs/03-types-showcase.m3l.md",
-    ];
+fn diff_field_properties() {
+    let output = m3l_bin()
+        .args([
+            "diff",
+            "samples/test/diff/fields-v1.m3l.md",
+            "samples/test/diff/fields-v2.m3l.md",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for line in [
+        "~ Account.email: label: Email → Email Address, description: Login address → Primary login address, added @unique",
+        "~ Account.nickname: removed @unique",
+        "~ Account.owner_id: @reference(User) → @reference(Member)",
+        "~ Account.status: default: active → pending",
+    ] {
+        assert!(stdout.contains(line), "missing {line:?} in: {stdout}");
+    }
 
-    for input_path in inputs {
-        // First format
-        let output1 = m3l_bin()
-            .args(["format", input_path])
+    let output = m3l_bin()
+        .args([
+            "diff",
+            "samples/test/diff/fields-v1.m3l.md",
+            "samples/test/diff/fields-v2.m3l.md",
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("failed to run");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    let owner = json["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["path"] == "Account.owner_id")
+        .unwrap();
+    assert_eq!(
+        owner["properties"],
+        serde_json::json!([{
+            "property": "@reference",
+            "before": "@reference(User)",
+            "after": "@reference(Member)"
+        }])
+    );
+}
+
+#[test]
+fn diff_detects_renames() {
+    let diff = |extra: &[&str]| {
+        let output = m3l_bin()
+            .args([
+                "diff",
+                "samples/test/diff/renames-v1.m3l.md",
+                "samples/test/diff/renames-v2.m3l.md",
+            ])
+            .args(extra)
             .output()
             .expect("failed to run");
         assert!(
-            output1.status.success(),
-            "[{input_path}] First format failed: {}",
-            String::from_utf8_lossy(&output1.stderr)
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
         );
-        let formatted1 = String::from_utf8_lossy(&output1.stdout);
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
 
-        // Write first format to temp file
-        let tmp = std::env::temp_dir().join("m3l-idempotent-test.m3l.md");
-        std::fs::write(&tmp, formatted1.as_ref()).expect("write tmp");
+    // By default only a field with the same type and attributes qualifies
+    let stdout = diff(&[]);
+    assert!(
+        stdout.contains("~ Person.mail → Person.email (possible rename)\n"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("- Person.full_name"), "stdout: {stdout}");
+    assert!(stdout.contains("1 renamed"), "stdout: {stdout}");
 
-        // Second format (format the already-formatted output)
-        let output2 = m3l_bin()
-            .args(["format", tmp.to_str().unwrap()])
-            .output()
-            .expect("failed to run");
-        assert!(
-            output2.status.success(),
-            "[{input_path}] Second format failed: {}",
-            String::from_utf8_lossy(&output2.stderr)
-        );
-        let formatted2 = String::from_utf8_lossy(&output2.stdout);
+    let stdout = diff(&["--rename-threshold", "0.7"]);
+    assert!(
+        stdout.contains("~ Person.full_name → Person.display_name (possible rename): type: string(100) → string(200)"),
+        "stdout: {stdout}"
+    );
 
-        // They should be identical
-        assert_eq!(
-            formatted1.as_ref(),
-            formatted2.as_ref(),
-            "[{input_path}] Format is not idempotent — second format produced different output"
-        );
+    let stdout = diff(&["--no-renames"]);
+    assert!(stdout.contains("3 added, 3 removed"), "stdout: {stdout}");
 
-        // Also verify the formatted output parses correctly
-        let parse_out = m3l_bin()
-            .args(["parse", tmp.to_str().unwrap()])
-            .output()
-            .expect("failed to run");
-        assert!(
-            parse_out.status.success(),
-            "[{input_path}] Formatted output failed to parse: {}",
-            String::from_utf8_lossy(&parse_out.stderr)
-        );
+    let json: serde_json::Value =
+        serde_json::from_str(&diff(&["--format", "json"])).expect("valid JSON");
+    let renamed = json["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["change"] == "renamed")
+        .unwrap();
+    assert_eq!(renamed["path"], "Person.email");
+    assert_eq!(renamed["previousPath"], "Person.mail");
+    assert_eq!(renamed["similarity"], 1.0);
+    assert_eq!(json["summary"]["renamed"], 1);
 
-        std::fs::remove_file(&tmp).ok();
-    }
+    let sql = diff(&["--migration", "sql"]);
+    assert!(
+        sql.contains("\nALTER TABLE \"Person\" RENAME COLUMN \"mail\" TO \"email\";\n"),
+        "sql: {sql}"
+    );
 }
 
 #[test]
-#[ignore] // BUG: same inheritance field duplication issue as format_idempotent
-fn format_preserves_ast() {
-    // parse(input) should produce same models/enums as parse(format(input))
-    // We compare structural content (field names, types, counts) rather than
-    // exact JSON equality, since loc/source fields will differ between files.
-    let inputs = &[
-        "samples/test/format/full.m3l.md",
-        "spec/conformance/inputs/01-ecommerce.m3l.md",
-        "spec/conformance/inputs/03-types-showcase.m3l.md",
-    ];
-
-    for input_path in inputs {
-        // Parse original
-        let parse1 = m3l_bin()
-            .args(["parse", input_path])
+fn diff_against_git_revision() {
+    let dir = std::env::temp_dir().join("m3l-diff-against-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(dir.join("models")).unwrap();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=m3l", "-c", "user.email=m3l@example.com"])
+            .args(args)
+            .current_dir(&dir)
             .output()
-            .expect("failed to run");
-        assert!(
-            parse1.status.success(),
-            "[{input_path}] Original parse failed"
-        );
-        let ast1: serde_json::Value =
-            serde_json::from_str(&String::from_utf8_lossy(&parse1.stdout))
-                .expect("invalid JSON from original parse");
+            .expect("failed to run git")
+            .status;
+        assert!(status.success(), "git {args:?}");
+    };
+    git(&["init", "-q"]);
+    std::fs::write(
+        dir.join("models/user.m3l.md"),
+        "## User\n- id: identifier @pk\n- name: string(50)\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("models/old.m3l.md"),
+        "## Legacy\n- id: identifier @pk\n",
+    )
+    .unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-qm", "v1"]);
+    git(&["tag", "v1"]);
 
-        // Format then parse
-        let format_out = m3l_bin()
-            .args(["format", input_path])
-            .output()
-            .expect("failed to run");
-        assert!(format_out.status.success(), "[{input_path}] Format failed");
-        let tmp = std::env::temp_dir().join("m3l-ast-equiv-test.m3l.md");
-        std::fs::write(&tmp, &format_out.stdout).expect("write tmp");
+    // A modified file, a deleted one and an untracked new one
+    std::fs::write(
+        dir.join("models/user.m3l.md"),
+        "## User\n- id: identifier @pk\n- name: string(100)\n",
+    )
+    .unwrap();
+    std::fs::remove_file(dir.join("models/old.m3l.md")).unwrap();
+    std::fs::write(
+        dir.join("models/order.m3l.md"),
+        "## Order\n- id: identifier @pk\n",
+    )
+    .unwrap();
 
-        let parse2 = m3l_bin()
-            .args(["parse", tmp.to_str().unwrap()])
-            .output()
-            .expect("failed to run");
-        assert!(
-            parse2.status.success(),
-            "[{input_path}] Formatted parse failed"
-        );
-        let ast2: serde_json::Value =
-            serde_json::from_str(&String::from_utf8_lossy(&parse2.stdout))
+    let output = m3l_bin()
+        .args(["diff", "--against", "v1", "models"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("+ model Order"), "stdout: {stdout}");
+    assert!(stdout.contains("- model Legacy"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("~ User.name: type: string(50) → string(100)"),
+        "stdout: {stdout}"
+    );
+
+    // An absolute path selects the same files as a relative one, from any
+    // directory in the repository
+    let absolute = dir.join("models/user.m3l.md");
+    let output = m3l_bin()
+        .args(["diff", "--against", "v1", absolute.to_str().unwrap()])
+        .current_dir(dir.join("models"))
+        .output()
+        .expect("failed to run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("~ User.name: type: string(50) → string(100)"),
+        "stdout: {stdout}"
+    );
+    let output = m3l_bin()
+        .args([
+            "diff",
+            "--against",
+            "v1",
+            dir.join("models").to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("- model Legacy"), "stdout: {stdout}");
+
+    let output = m3l_bin()
+        .args(["diff", "--against", "no-such-rev", "models"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown git revision: no-such-rev"));
+}
+
+#[test]
+fn diff_views_interfaces_and_sections() {
+    let output = m3l_bin()
+        .args([
+            "diff",
+            "samples/test/diff/sections-v1.m3l.md",
+            "samples/test/diff/sections-v2.m3l.md",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for expected in [
+        "+ interface Deletable\n",
+        "+ Trackable.updated_at\n",
+        "~ view CheapProducts: where: price < 10 → price < 5, order_by: none → price asc\n",
+        "removed index idx_price(price)",
+        "index idx_sku(sku) → idx_sku(sku, category_id)",
+        "added index idx_category(category_id)",
+        "relation category: >Category via category_id → category: >Category via category_id (load: eager)",
+    ] {
+        assert!(stdout.contains(expected), "missing {expected:?} in: {stdout}");
+    }
+
+    let output = m3l_bin()
+        .args([
+            "diff",
+            "samples/test/diff/sections-v1.m3l.md",
+            "samples/test/diff/sections-v2.m3l.md",
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("failed to run");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    let product = json["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["path"] == "Product")
+        .unwrap();
+    assert_eq!(product["change"], "modified");
+    assert_eq!(product["entity"], "model");
+    assert!(product["properties"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|p| p["property"] == "index:idx_category"
+            && p["before"].is_null()
+            && p["after"] == "idx_category(category_id)"));
+}
+
+#[test]
+fn diff_markdown_changelog() {
+    let output = m3l_bin()
+        .args([
+            "diff",
+            "samples/test/diff/v1.m3l.md",
+            "samples/test/diff/v2.m3l.md",
+            "--format",
+            "markdown",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("## Schema changes\n\n2 added, 1 removed, 2 modified."),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "### Customer\n\n- Added field `age`\n- Changed field `name`\n  - type: `string(100)` → `string(200)`\n- Changed field `phone`\n"
+        ),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("### OldModel\n\n- Removed model"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn diff_sql_migration() {
+    let migration = |extra: &[&str]| {
+        let output = m3l_bin()
+            .args([
+                "diff",
+                "samples/test/diff/v1.m3l.md",
+                "samples/test/diff/v2.m3l.md",
+                "--migration",
+                "sql",
+                "--dialect",
+                "postgres",
+            ])
+            .args(extra)
+            .output()
+            .expect("failed to run");
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let sql = migration(&[]);
+    assert!(sql.starts_with("-- 3 destructive statements"), "sql: {sql}");
+    assert!(
+        sql.contains("CREATE TABLE \"NewModel\" (\n  \"id\" UUID PRIMARY KEY,"),
+        "sql: {sql}"
+    );
+    assert!(
+        sql.contains(
+            "\nALTER TABLE \"Customer\" ADD COLUMN \"age\" INTEGER NOT NULL CONSTRAINT \"Customer_age_check\" CHECK (age >= 0);"
+        ),
+        "sql: {sql}"
+    );
+    assert!(
+        sql.contains("  \"data\" JSONB NOT NULL,\n  CONSTRAINT \"NewModel_check\" CHECK (jsonb_typeof(data) = 'object')\n);"),
+        "sql: {sql}"
+    );
+    // Destructive statements are only commented out
+    assert!(sql.contains("\n-- DROP TABLE \"OldModel\";"), "sql: {sql}");
+    assert!(
+        sql.contains("\n-- ALTER TABLE \"Customer\" ALTER COLUMN \"phone\" TYPE TEXT"),
+        "sql: {sql}"
+    );
+
+    let sql = migration(&["--allow-destructive"]);
+    assert!(sql.starts_with("BEGIN;\n"), "sql: {sql}");
+    assert!(
+        sql.ends_with("\nDROP TABLE \"OldModel\";\nCOMMIT;\n"),
+        "sql: {sql}"
+    );
+}
+
+// ══════════════════════════════════════════════════════════════
+// Analyze — dedicated fixtures
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn analyze_mermaid_edges() {
+    let output = m3l_bin()
+        .args(["analyze", "samples/test/analyze/graph.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("graph LR"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("-->|inherits|"),
+        "Expected inheritance edge, stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("-->|ref|"),
+        "Expected reference edge, stdout: {stdout}"
+    );
+}
+
+#[test]
+fn analyze_dot_format() {
+    let output = m3l_bin()
+        .args([
+            "analyze",
+            "samples/test/analyze/graph.m3l.md",
+            "--format",
+            "dot",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("digraph M3L"), "stdout: {stdout}");
+    assert!(stdout.contains("rankdir=LR"), "stdout: {stdout}");
+    assert!(stdout.contains("->"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("style=dashed"),
+        "Expected inherits edge style, stdout: {stdout}"
+    );
+}
+
+#[test]
+fn analyze_graphml_and_d2_formats() {
+    let output = m3l_bin()
+        .args([
+            "analyze",
+            "samples/test/analyze/graph.m3l.md",
+            "--format",
+            "graphml",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(r#"<graph id="M3L" edgedefault="directed">"#),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains(r#"<node id="Base"><data key="label">Base</data><data key="kind">interface</data></node>"#),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains(
+            r#"source="Product" target="Category"><data key="relation">reference</data>"#
+        ),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.trim_end().ends_with("</graphml>"),
+        "stdout: {stdout}"
+    );
+
+    let output = m3l_bin()
+        .args([
+            "analyze",
+            "samples/test/analyze/graph.m3l.md",
+            "--format",
+            "d2",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("direction: right\n"), "stdout: {stdout}");
+    assert!(stdout.contains("\nIsolated\n"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("Product -> Base: inherits {style.stroke-dash: 3}"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("Product -> Category: ref\n"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn analyze_include_element_kinds() {
+    let output = m3l_bin()
+        .args(["analyze", "samples/multi"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("    Currency{{Currency}}\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("    Timestampable([Timestampable])\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("    LowStockAlert[/LowStockAlert/]\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("    class Currency,UnitOfMeasure enum"),
+        "stdout: {stdout}"
+    );
+
+    let output = m3l_bin()
+        .args([
+            "analyze",
+            "samples/multi",
+            "--include",
+            "enums",
+            "--format",
+            "dot",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("\"Currency\" [shape=hexagon, fillcolor=wheat];"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("\"Supplier\" -> \"Currency\""),
+        "stdout: {stdout}"
+    );
+    assert!(!stdout.contains("Timestampable"), "stdout: {stdout}");
+    assert!(!stdout.contains("LowStockAlert"), "stdout: {stdout}");
+}
+
+#[test]
+fn analyze_isolated_node() {
+    let output = m3l_bin()
+        .args(["analyze", "samples/test/analyze/graph.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Isolated node appears standalone without any edge
+    assert!(stdout.contains("    Isolated"), "stdout: {stdout}");
+    assert!(
+        !stdout.contains("Isolated -->") && !stdout.contains("--> Isolated"),
+        "Isolated node should have no edges, stdout: {stdout}"
+    );
+}
+
+#[test]
+fn analyze_cross_namespace_edges() {
+    let output = m3l_bin()
+        .args(["analyze", "samples/test/analyze/namespaces.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Cross-namespace edges are dotted; same-namespace edges stay solid
+    assert!(
+        stdout.contains("Invoice -.->|ref| User"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("Session -->|ref| User"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("%% 1 cross-namespace edges"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn analyze_keeps_same_named_models_apart() {
+    let dir = std::env::temp_dir().join("m3l-analyze-same-name-test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("schema.m3l.md"),
+        "# Namespace: auth\n\n## User\n- id: identifier @pk\n\n## Session\n- id: identifier @pk\n- user_id: identifier @reference(User)\n\n---\n\n# Namespace: billing\n\n## User\n- id: identifier @pk\n- account_id: identifier @reference(auth.User)\n\n## Invoice\n- id: identifier @pk\n- user_id: identifier @reference(User)\n",
+    )
+    .unwrap();
+
+    let output = m3l_bin()
+        .args(["analyze", "schema.m3l.md"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Each User is its own node, labelled with its qualified name
+    assert!(
+        stdout.contains("    auth_User[\"auth.User\"]\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("    billing_User[\"billing.User\"]\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("Session -->|ref| auth_User"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("Invoice -->|ref| billing_User"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("billing_User -.->|ref| auth_User"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("%% 4 nodes, 3 edges"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("%% 1 cross-namespace edges"),
+        "stdout: {stdout}"
+    );
+
+    let output = m3l_bin()
+        .args(["analyze", "schema.m3l.md", "--format", "dot"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("\"Invoice\" -> \"billing.User\" [label=\"reference\", color=red];"),
+        "stdout: {stdout}"
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn analyze_er_diagram() {
+    let output = m3l_bin()
+        .args([
+            "analyze",
+            "samples/test/analyze/er.m3l.md",
+            "--format",
+            "er",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("erDiagram\n"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("    Author {\n        identifier id PK\n        string name \"Display name\"\n        email email UK\n    }"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("identifier author_id FK, UK"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("    Archive\n"), "stdout: {stdout}");
+    // Cardinality from the reference field
+    assert!(
+        stdout.contains("Post }o--|| Author : \"author_id\""),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("Post }o--o| Author : \"editor_id\""),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("Profile |o--|| Author : \"author_id\""),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("Tag }o--o| Tag : \"parent_id\""),
+        "stdout: {stdout}"
+    );
+    // Relations entries add what no reference field covers
+    assert!(
+        stdout.contains("Post }o--o{ Tag : \"tags\""),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("%% 5 entities, 5 relationships"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn analyze_check_cycles() {
+    let output = m3l_bin()
+        .args([
+            "analyze",
+            "samples/test/analyze/cycles.m3l.md",
+            "--check-cycles",
+        ])
+        .output()
+        .expect("failed to run");
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Found 2 reference cycles:"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("  Department.manager_id → Employee.department_id → Department\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("  Desk.room_id → Room.owner_id → Employee.desk_id → Desk"),
+        "stdout: {stdout}"
+    );
+    // Employee.mentor_id references its own model and is not reported
+    assert!(!stdout.contains("mentor_id"), "stdout: {stdout}");
+
+    let output = m3l_bin()
+        .args([
+            "analyze",
+            "samples/test/analyze/graph.m3l.md",
+            "--check-cycles",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No reference cycles found"));
+}
+
+#[test]
+fn analyze_focus_depth() {
+    let analyze = |args: &[&str]| {
+        let output = m3l_bin()
+            .args(["analyze", "samples/test/analyze/chain.m3l.md"])
+            .args(args)
+            .output()
+            .expect("failed to run");
+        (
+            output.status.success(),
+            String::from_utf8_lossy(&output.stdout).to_string(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        )
+    };
+
+    let (ok, stdout, _) = analyze(&["--focus", "Store"]);
+    assert!(ok);
+    assert!(stdout.contains("Order -->|ref| Store"), "stdout: {stdout}");
+    assert!(stdout.contains("Store -->|ref| Region"), "stdout: {stdout}");
+    assert!(!stdout.contains("OrderLine"), "stdout: {stdout}");
+    assert!(!stdout.contains("Supplier"), "stdout: {stdout}");
+    assert!(stdout.contains("%% 3 nodes, 2 edges"), "stdout: {stdout}");
+
+    let (ok, stdout, _) = analyze(&["--focus", "Store", "--depth", "2"]);
+    assert!(ok);
+    assert!(
+        stdout.contains("OrderLine -->|ref| Order"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("%% 4 nodes, 3 edges"), "stdout: {stdout}");
+
+    let (ok, stdout, _) = analyze(&["--focus", "Region", "--format", "er"]);
+    assert!(ok);
+    assert!(stdout.contains("Store }o--|| Region"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("%% 2 entities, 1 relationships"),
+        "stdout: {stdout}"
+    );
+
+    let (ok, _, stderr) = analyze(&["--focus", "Nope"]);
+    assert!(!ok);
+    assert!(
+        stderr.contains("Unknown element \"Nope\""),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn analyze_orphans() {
+    let output = m3l_bin()
+        .args(["analyze", "samples/test/analyze/graph.m3l.md", "--orphans"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("2 unreferenced elements:\n"),
+        "stdout: {stdout}"
+    );
+    // Product only references itself
+    assert!(
+        stdout.contains("  model Product (samples/test/analyze/graph.m3l.md:17)"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("  model Isolated ("), "stdout: {stdout}");
+    assert!(!stdout.contains("Category"), "stdout: {stdout}");
+    assert!(!stdout.contains("Base"), "stdout: {stdout}");
+
+    let output = m3l_bin()
+        .args(["analyze", "samples/01-ecommerce.m3l.md", "--orphans"])
+        .output()
+        .expect("failed to run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("  enum ShippingPriority ("),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn analyze_creation_order() {
+    let output = m3l_bin()
+        .args([
+            "analyze",
+            "samples/test/analyze/chain.m3l.md",
+            "--format",
+            "order",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "1. Region\n2. Store\n3. Order\n4. OrderLine\n5. Supplier\n"
+    );
+
+    let output = m3l_bin()
+        .args([
+            "analyze",
+            "samples/test/analyze/cycles.m3l.md",
+            "--format",
+            "order",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1. Room (cycle)\n"), "stdout: {stdout}");
+    assert!(stdout.contains("\n2 reference cycles;"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("  Department.manager_id → Employee.department_id → Department"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn analyze_coupling_metrics() {
+    let output = m3l_bin()
+        .args(["analyze", "samples/test/analyze/cycles.m3l.md", "--metrics"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("Model       Fan-in  Fan-out  Depth  SCC\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("Employee         2        2      0    4\n"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("Average degree: 2.5\n"), "stdout: {stdout}");
+    assert!(
+        stdout.contains(
+            "Strongly connected components: 1 (sizes 4)\n  Department, Desk, Employee, Room"
+        ),
+        "stdout: {stdout}"
+    );
+
+    let output = m3l_bin()
+        .args(["analyze", "samples/multi", "--metrics", "--format", "json"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let base = &report["models"][0];
+    assert_eq!(base["name"], "BaseEntity");
+    assert_eq!(base["fanIn"], 5);
+    assert_eq!(base["inheritanceDepth"], 1);
+    assert_eq!(report["models"][4]["name"], "StockItem");
+    assert_eq!(report["models"][4]["inheritanceDepth"], 2);
+    assert_eq!(report["averageDegree"], 3.67);
+    assert_eq!(report["components"], serde_json::json!([]));
+}
+
+// ══════════════════════════════════════════════════════════════
+// Docs — static HTML site
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn docs_writes_cross_linked_pages() {
+    let out = std::env::temp_dir().join("m3l-docs-test");
+    std::fs::remove_dir_all(&out).ok();
+    let output = m3l_bin()
+        .args([
+            "docs",
+            "samples/02-blog-cms.m3l.md",
+            "--out",
+            out.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(out.join("style.css").exists());
+
+    let index = std::fs::read_to_string(out.join("index.html")).unwrap();
+    assert!(index.contains("<pre class=\"mermaid\">"), "{index}");
+    assert!(index.contains("href=\"models/Post.html\""), "{index}");
+    assert!(index.contains("href=\"enums/PostStatus.html\""), "{index}");
+
+    let post = std::fs::read_to_string(out.join("models/Post.html")).unwrap();
+    // Inheritance chain, inherited fields, type and reference links, backlinks
+    assert!(
+        post.contains("<dt>Inherits</dt><dd><a href=\"../models/BaseModel.html\">BaseModel</a>"),
+        "{post}"
+    );
+    assert!(post.contains("(inherited from <a href=\"../models/Trackable.html\">"));
+    assert!(post.contains("<a href=\"../enums/PostStatus.html\">PostStatus</a>"));
+    assert!(post.contains("<a href=\"../models/User.html\">@reference(User)</a>"));
+    assert!(post.contains("<h2>Referenced by</h2>"));
+    assert!(post.contains("<a href=\"../models/Comment.html\">Comment</a><code>.post_id</code>"));
+
+    let status = std::fs::read_to_string(out.join("enums/PostStatus.html")).unwrap();
+    assert!(status.contains("<h2>Used by</h2>"), "{status}");
+    std::fs::remove_dir_all(&out).ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// Export — data dictionary
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn export_dictionary_csv_and_xlsx() {
+    let output = m3l_bin()
+        .args(["export", "dictionary", "samples/01-ecommerce.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    assert_eq!(
+        lines.next(),
+        Some("Model,Field,Type,Nullable,Default,Description,Attributes")
+    );
+    // Inherited fields are listed under each model; commas are quoted
+    assert!(
+        stdout.contains("Customer,created_at,timestamp,no,now(),,"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Address,label,string(50),no,Home,\"Address label (Home, Office, etc.)\","),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Address,state,string(100),yes,,,"),
+        "{stdout}"
+    );
+
+    let xlsx = std::env::temp_dir().join("m3l-dictionary-test.xlsx");
+    std::fs::remove_file(&xlsx).ok();
+    let output = m3l_bin()
+        .args([
+            "export",
+            "dictionary",
+            "samples/01-ecommerce.m3l.md",
+            "--format",
+            "xlsx",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success(), "xlsx needs an output file");
+    let output = m3l_bin()
+        .args([
+            "export",
+            "dictionary",
+            "samples/01-ecommerce.m3l.md",
+            "--format",
+            "xlsx",
+            "-o",
+            xlsx.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let bytes = std::fs::read(&xlsx).unwrap();
+    assert!(bytes.starts_with(b"PK"), "an XLSX file is a zip archive");
+    std::fs::remove_file(&xlsx).ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// Stats — metrics and history
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn stats_json_reports_counts() {
+    let output = m3l_bin()
+        .args(["stats", "samples/01-ecommerce.m3l.md", "--format", "json"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stats: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    assert_eq!(stats["files"], 1);
+    assert!(stats["models"].as_u64().unwrap() > 0);
+    assert!(stats["fields"].as_u64().unwrap() > 0);
+    let spread = &stats["fields_per_model"];
+    assert!(spread["min"].as_u64() <= spread["max"].as_u64());
+    assert!(spread["avg"].as_f64().unwrap() >= spread["min"].as_f64().unwrap());
+    assert_eq!(stats["lookups"], 7);
+    assert_eq!(stats["rollups"], 4);
+    assert_eq!(stats["attributes"]["reference"], 11);
+}
+
+#[test]
+fn stats_history_and_trend() {
+    let history = std::env::temp_dir().join("m3l-stats-history-test.jsonl");
+    // A record written before per-model and attribute metrics existed
+    std::fs::write(
+        &history,
+        "{\"timestamp\":\"2026-01-01T00:00:00Z\",\"files\":1,\"models\":3,\"fields\":12}\n",
+    )
+    .unwrap();
+
+    for input in ["samples/01-ecommerce.m3l.md", "samples/02-blog-cms.m3l.md"] {
+        let output = m3l_bin()
+            .args([
+                "stats",
+                input,
+                "--append-history",
+                history.to_str().unwrap(),
+            ])
+            .output()
+            .expect("failed to run");
+        assert!(output.status.success());
+    }
+    let content = std::fs::read_to_string(&history).unwrap();
+    assert_eq!(content.lines().count(), 3);
+
+    let output = m3l_bin()
+        .args(["stats", "--trend", history.to_str().unwrap()])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("timestamp"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("over 3 runs"),
+        "expected a change summary, got: {stdout}"
+    );
+
+    std::fs::remove_file(&history).ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// Bench — pipeline timings
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn bench_reports_stage_timings() {
+    let output = m3l_bin()
+        .args(["bench", "samples/multi", "-n", "2", "--format", "json"])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    assert_eq!(report["iterations"], 2);
+    let files = report["files"].as_array().unwrap();
+    assert_eq!(files.len(), 2);
+    let total = &report["total"];
+    let bytes: u64 = files.iter().map(|f| f["bytes"].as_u64().unwrap()).sum();
+    assert_eq!(total["bytes"].as_u64().unwrap(), bytes);
+    assert!(total["models"].as_u64().unwrap() > 0);
+    for key in [
+        "lexMs",
+        "parseMs",
+        "resolveMs",
+        "validateMs",
+        "totalMs",
+        "mbPerSec",
+    ] {
+        assert!(total[key].as_f64().unwrap() >= 0.0, "{key}: {total}");
+    }
+
+    let output = m3l_bin()
+        .args(["bench", "samples/multi", "-n", "1"])
+        .output()
+        .expect("failed to run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("Mean of 1 iteration over 2 files"),
+        "{stdout}"
+    );
+    assert!(stdout.lines().last().unwrap().starts_with("Total"));
+
+    let output = m3l_bin()
+        .args(["bench", "samples/multi", "-n", "0"])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+}
+
+// ══════════════════════════════════════════════════════════════
+// Attributes — usage report
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn attributes_report_status() {
+    let output = m3l_bin()
+        .args([
+            "attributes",
+            "samples/test/validate/w005-type-mismatch.m3l.md",
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let report: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    let attrs = report["attributes"].as_array().unwrap();
+    let status = |name: &str| {
+        attrs
+            .iter()
+            .find(|a| a["name"] == name)
+            .map(|a| a["status"].as_str().unwrap().to_string())
+    };
+    assert_eq!(status("rating").as_deref(), Some("registered"));
+    assert_eq!(status("pk").as_deref(), Some("unknown"));
+    assert_eq!(report["summary"]["registered"], 1);
+}
+
+#[test]
+fn attributes_human_counts_and_files() {
+    let output = m3l_bin()
+        .args(["attributes", "samples/01-ecommerce.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let reference = stdout
+        .lines()
+        .find(|l| l.starts_with("@reference "))
+        .expect("missing @reference row");
+    assert!(reference.contains("standard"), "row: {reference}");
+    assert!(
+        reference.contains("samples/01-ecommerce.m3l.md"),
+        "row: {reference}"
+    );
+    assert!(stdout.contains("attributes,"), "stdout: {stdout}");
+}
+
+// ══════════════════════════════════════════════════════════════
+// Find — fuzzy search
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn find_reports_inherited_fields() {
+    let output = m3l_bin()
+        .args(["find", "created_at", "samples/01-ecommerce.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Customer.created_at (inherited from Timestampable)"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("samples/01-ecommerce.m3l.md:10"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn find_kind_filter_and_json() {
+    let output = m3l_bin()
+        .args([
+            "find",
+            "ordst",
+            "samples/01-ecommerce.m3l.md",
+            "--kind",
+            "enum",
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let result: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    let matches = result["matches"].as_array().unwrap();
+    assert_eq!(matches[0]["name"], "OrderStatus");
+    assert!(matches.iter().all(|m| m["kind"] == "enum"));
+}
+
+#[test]
+fn find_matches_descriptions() {
+    let output = m3l_bin()
+        .args([
+            "find",
+            "status tracking",
+            "samples/01-ecommerce.m3l.md",
+            "--kind",
+            "model",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("model     Order —"), "stdout: {stdout}");
+}
+
+#[test]
+fn find_rejects_unknown_kind() {
+    let output = m3l_bin()
+        .args([
+            "find",
+            "x",
+            "samples/01-ecommerce.m3l.md",
+            "--kind",
+            "table",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+}
+
+// ══════════════════════════════════════════════════════════════
+// Merge — single-document snapshot
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn merge_multi_file_project() {
+    let tmp = std::env::temp_dir().join("m3l-merge-test");
+    std::fs::create_dir_all(&tmp).ok();
+    let combined = tmp.join("combined.m3l.md");
+    let output = m3l_bin()
+        .args(["merge", "samples/multi", "-o", combined.to_str().unwrap()])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Merged 2 files into"), "stdout: {stdout}");
+
+    let merged = std::fs::read_to_string(&combined).unwrap();
+    assert!(!merged.contains("@import"));
+    let position = |needle: &str| merged.find(needle).unwrap_or_else(|| panic!("{needle}"));
+    assert!(
+        position("# Namespace: sample.multi\n") < position("# Namespace: sample.multi.inventory")
+    );
+    assert!(position("## Timestampable ::interface") < position("## BaseEntity : Timestampable"));
+    assert!(position("## BaseEntity") < position("## Warehouse : BaseEntity"));
+
+    // The snapshot describes the same schema as the project
+    let output = m3l_bin()
+        .args(["diff", "samples/multi", combined.to_str().unwrap()])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No differences found"));
+    std::fs::remove_dir_all(&tmp).ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// Split — one file per namespace or model
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn split_by_namespace_and_model() {
+    let tmp = std::env::temp_dir().join("m3l-split-test");
+    std::fs::remove_dir_all(&tmp).ok();
+    std::fs::create_dir_all(&tmp).unwrap();
+    let big = tmp.join("big.m3l.md");
+    std::fs::write(
+        &big,
+        "# Namespace: auth\n\n## User\n- id: identifier @pk\n- role: Role\n\n## Role ::enum\n- admin\n- member\n\n---\n\n# Namespace: blog\n\n## Post\n- id: identifier @pk\n- author_id: identifier @reference(User)\n\n### Indexes\n- by_author\n  - fields: [author_id]\n",
+    )
+    .unwrap();
+
+    let by_namespace = tmp.join("by-namespace");
+    let output = m3l_bin()
+        .args([
+            "split",
+            big.to_str().unwrap(),
+            "--out",
+            by_namespace.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let blog = std::fs::read_to_string(by_namespace.join("blog.m3l.md")).unwrap();
+    assert!(
+        blog.starts_with("# Namespace: blog\n\n@import \"./auth.m3l.md\""),
+        "{blog}"
+    );
+    assert!(
+        blog.contains("### Indexes"),
+        "element text is copied unchanged"
+    );
+    assert!(!std::fs::read_to_string(by_namespace.join("auth.m3l.md"))
+        .unwrap()
+        .contains("@import"));
+
+    let by_model = tmp.join("by-model");
+    let output = m3l_bin()
+        .args([
+            "split",
+            "samples/01-ecommerce.m3l.md",
+            "--by",
+            "model",
+            "--out",
+            by_model.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let customer = std::fs::read_to_string(by_model.join("Customer.m3l.md")).unwrap();
+    assert!(
+        customer.contains("@import \"./Timestampable.m3l.md\""),
+        "{customer}"
+    );
+
+    // The split project resolves to the same schema
+    let output = m3l_bin()
+        .args([
+            "diff",
+            "samples/01-ecommerce.m3l.md",
+            by_model.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No differences found"));
+
+    let output = m3l_bin()
+        .args([
+            "split",
+            "samples/01-ecommerce.m3l.md",
+            "--by",
+            "model",
+            "--out",
+            by_model.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already exists"));
+    std::fs::remove_dir_all(&tmp).ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// Query — JSONPath over the AST
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn query_fields_and_missing_keys() {
+    let output = m3l_bin()
+        .args([
+            "query",
+            "$.models[?(@.name == 'Product')].fields[?(@.type == 'decimal')].name",
+            "samples/01-ecommerce.m3l.md",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let names: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    assert!(names.as_array().unwrap().iter().any(|n| n == "price"));
+
+    let tmp = std::env::temp_dir().join("m3l-query-test");
+    std::fs::create_dir_all(&tmp).ok();
+    let file = tmp.join("schema.m3l.md");
+    std::fs::write(
+        &file,
+        "## User\n- id: identifier @pk\n\n## AuditLog\n- message: text\n\n## Tag\n- id: identifier @primary\n",
+    )
+    .unwrap();
+    let output = m3l_bin()
+        .args([
+            "query",
+            "$.models[?(!@.fields[*].attributes[?(@.name == 'pk' || @.name == 'primary')])].name",
+            file.to_str().unwrap(),
+            "--raw",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "AuditLog");
+
+    let output = m3l_bin()
+        .args(["query", "$.models[", file.to_str().unwrap()])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid JSONPath"), "stderr: {stderr}");
+    std::fs::remove_dir_all(&tmp).ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// Hook — git pre-commit / pre-push checks
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn hook_checks_staged_files_and_installs() {
+    let dir = std::env::temp_dir().join("m3l-hook-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("failed to run git")
+            .status;
+        assert!(status.success(), "git {args:?}");
+    };
+    let hook = |args: &[&str]| {
+        m3l_bin()
+            .arg("hook")
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("failed to run")
+    };
+    git(&["init", "-q"]);
+    std::fs::write(dir.join("user.m3l.md"), "## User\n- id: identifier @pk\n").unwrap();
+    std::fs::write(
+        dir.join("order.m3l.md"),
+        "## Order\n- id: identifier @pk\n- user_id: identifier @reference(User)\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("README.md"), "# Not a schema\n").unwrap();
+    git(&["add", "."]);
+
+    // The reference into the other file resolves; README.md is not checked
+    let output = hook(&["run"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(stdout.contains("0 errors"), "stdout: {stdout}");
+    git(&[
+        "-c",
+        "user.name=m3l",
+        "-c",
+        "user.email=m3l@example.com",
+        "commit",
+        "-qm",
+        "init",
+    ]);
+    assert!(String::from_utf8_lossy(&hook(&["run"]).stdout).contains("No changed M3L files"));
+
+    // Unstaged edits are not what gets committed, so they are ignored
+    let broken = "## Order\n- id: identifier @pk\n- status: Missing\n";
+    std::fs::write(dir.join("order.m3l.md"), broken).unwrap();
+    assert!(hook(&["run"]).status.success());
+    git(&["add", "order.m3l.md"]);
+    let output = hook(&["run", "--pre-commit"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout.contains("M3L-E009"), "stdout: {stdout}");
+    assert!(stdout.contains("in 1 file"), "stdout: {stdout}");
+
+    let output = hook(&["install"]);
+    assert!(output.status.success());
+    let script = std::fs::read_to_string(dir.join(".git/hooks/pre-commit")).unwrap();
+    assert!(script.contains("m3l hook run --pre-commit"), "{script}");
+    // Reinstalling over our own hook is fine; over someone else's is not
+    assert!(hook(&["install"]).status.success());
+    std::fs::write(dir.join(".git/hooks/pre-push"), "#!/bin/sh\nexit 0\n").unwrap();
+    let output = hook(&["install", "--pre-push"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+    assert!(hook(&["install", "--pre-push", "--force"]).status.success());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// Init — project scaffolding
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn init_templates_validate_and_lint_clean() {
+    for template in ["minimal", "ecommerce", "blog"] {
+        let dir = std::env::temp_dir().join(format!("m3l-init-{template}"));
+        std::fs::remove_dir_all(&dir).ok();
+        let dir_arg = dir.to_str().unwrap();
+
+        let output = m3l_bin()
+            .args([
+                "init",
+                dir_arg,
+                "--template",
+                template,
+                "--with-lint",
+                "--with-format",
+            ])
+            .output()
+            .expect("failed to run");
+        assert!(output.status.success(), "{template}: {output:?}");
+        assert!(!dir.join("m3l.config.yaml").exists());
+        let config = std::fs::read_to_string(dir.join("m3l.toml")).unwrap();
+        assert!(config.contains(&format!("name = \"m3l-init-{template}\"")));
+        assert!(config.contains("naming-convention = \"warn\""), "{config}");
+        assert!(config.contains("sort_fields = \"declaration\""), "{config}");
+
+        let output = m3l_bin()
+            .args(["validate", dir_arg, "--strict"])
+            .output()
+            .expect("failed to run");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains("0 errors, 0 warnings"),
+            "{template}: {stdout}"
+        );
+
+        let output = m3l_bin()
+            .args(["lint", dir_arg])
+            .output()
+            .expect("failed to run");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("0 lint issues"), "{template}: {stdout}");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
+
+#[test]
+fn init_refuses_to_overwrite_without_force() {
+    let dir = std::env::temp_dir().join("m3l-init-existing");
+    std::fs::remove_dir_all(&dir).ok();
+    let dir_arg = dir.to_str().unwrap();
+
+    assert!(m3l_bin()
+        .args(["init", dir_arg])
+        .output()
+        .expect("failed to run")
+        .status
+        .success());
+    let output = m3l_bin()
+        .args(["init", dir_arg])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--force"), "stderr: {stderr}");
+
+    let output = m3l_bin()
+        .args(["init", dir_arg, "--force", "--name", "renamed"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let config = std::fs::read_to_string(dir.join("m3l.toml")).unwrap();
+    assert!(config.starts_with("name = \"renamed\""), "{config}");
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// Sample — bundled reference schemas
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn sample_writes_conformance_inputs() {
+    let dir = std::env::temp_dir().join("m3l-sample-test");
+    std::fs::remove_dir_all(&dir).ok();
+    for (name, file) in [
+        ("ecommerce", "01-ecommerce.m3l.md"),
+        ("blog", "02-blog-cms.m3l.md"),
+        ("types", "03-types-showcase.m3l.md"),
+    ] {
+        let output = m3l_bin()
+            .args(["sample", name, "-o", dir.to_str().unwrap()])
+            .output()
+            .expect("failed to run");
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        // The bundled copies must not drift from the conformance inputs
+        let expected =
+            std::fs::read_to_string(workspace_root().join("spec/conformance/inputs").join(file))
+                .unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join(file)).unwrap(), expected);
+
+        // Every sample must pass the `m3l validate` it tells the user to run
+        let output = m3l_bin()
+            .args(["validate", dir.join(file).to_str().unwrap()])
+            .output()
+            .expect("failed to run");
+        assert!(
+            output.status.success(),
+            "{file}: {}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+    }
+
+    let output = m3l_bin()
+        .args(["sample", "blog", "-o", dir.to_str().unwrap()])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// Generate — configured artifacts and freshness check
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn generate_writes_targets_and_checks_freshness() {
+    let dir = std::env::temp_dir().join("m3l-generate-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(dir.join("models")).unwrap();
+    std::fs::write(
+        dir.join("m3l.config.yaml"),
+        "name: shop\nsources:\n  - \"models/*.m3l.md\"\ngenerate:\n  - target: ast\n    output: build/schema.json\n  - target: mermaid\n    output: build/erd.mmd\n",
+    )
+    .unwrap();
+    let model = dir.join("models/shop.m3l.md");
+    std::fs::write(
+        &model,
+        "## Customer\n- id: identifier @pk\n\n## Order\n- id: identifier @pk\n- customer_id: identifier @reference(Customer)\n\n## Tag\n- id: identifier @pk\n\n## Note\n- id: identifier @pk\n\n## Setting\n- id: identifier @pk\n",
+    )
+    .unwrap();
+    let dir_arg = dir.to_str().unwrap();
+    let run = |args: &[&str]| m3l_bin().args(args).output().expect("failed to run");
+
+    let output = run(&["generate", dir_arg, "--check"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("build/schema.json (missing)"),
+        "stderr: {stderr}"
+    );
+
+    let output = run(&["generate", dir_arg]);
+    assert!(output.status.success());
+    let schema = std::fs::read_to_string(dir.join("build/schema.json")).unwrap();
+    assert!(schema.contains("\"models/shop.m3l.md\""), "{schema}");
+    assert!(std::fs::read_to_string(dir.join("build/erd.mmd"))
+        .unwrap()
+        .contains("Customer"));
+
+    // Source paths are project-relative, so the check passes from any cwd
+    let output = Command::new(env!("CARGO_BIN_EXE_m3l"))
+        .args(["generate", ".", "--check"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    std::fs::write(
+        &model,
+        "## Customer\n- id: identifier @pk\n- email: email\n",
+    )
+    .unwrap();
+    let output = run(&["generate", dir_arg, "--check"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("2 generated files are not in sync"),
+        "stderr: {stderr}"
+    );
+    assert!(
+        stderr.contains("build/erd.mmd (out of date)"),
+        "stderr: {stderr}"
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn generate_check_is_stable_for_metadata_keys() {
+    let dir = std::env::temp_dir().join("m3l-generate-metadata-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("m3l.config.yaml"),
+        "name: shop\ngenerate:\n  - target: ast\n    output: build/schema.json\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("shop.m3l.md"),
+        "## Product\n- id: identifier @pk\n\n### Metadata\n- owner: catalog\n- domain: sales\n- tier: gold\n- retention: \"90d\"\n- audited: true\n",
+    )
+    .unwrap();
+    let dir_arg = dir.to_str().unwrap();
+    let run = |args: &[&str]| m3l_bin().args(args).output().expect("failed to run");
+
+    // Map keys are written in a fixed order, so every process renders the
+    // same file
+    let output = run(&["generate", dir_arg]);
+    assert!(output.status.success());
+    let first = std::fs::read_to_string(dir.join("build/schema.json")).unwrap();
+    for _ in 0..4 {
+        let output = run(&["generate", dir_arg, "--check"]);
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let output = run(&["generate", dir_arg]);
+    assert!(output.status.success());
+    let second = std::fs::read_to_string(dir.join("build/schema.json")).unwrap();
+    assert_eq!(first, second);
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// Completions
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn completions_cover_subcommands_and_flags() {
+    for shell in ["bash", "zsh", "fish", "powershell"] {
+        let output = m3l_bin()
+            .args(["completions", shell])
+            .output()
+            .expect("failed to run");
+        assert!(output.status.success(), "{shell}: {output:?}");
+        let script = String::from_utf8_lossy(&output.stdout);
+        for word in ["validate", "find", "sort-fields", "append-history"] {
+            assert!(script.contains(word), "{shell} script lacks {word}");
+        }
+    }
+
+    let output = m3l_bin()
+        .args(["completions", "tcsh"])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+}
+
+// ══════════════════════════════════════════════════════════════
+// Watch mode
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn validate_watch_reruns_on_change() {
+    let dir = std::env::temp_dir().join("m3l-watch-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("schema.m3l.md");
+    std::fs::write(&file, "## User\n- id: identifier @pk\n").unwrap();
+
+    let mut child = m3l_bin()
+        .args(["validate", dir.to_str().unwrap(), "--watch"])
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("failed to run");
+    std::thread::sleep(std::time::Duration::from_millis(1000));
+    std::fs::write(&file, "## User\n- id: identifier @pk\n- name: Missing\n").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(1500));
+    child.kill().ok();
+    let output = child.wait_with_output().expect("failed to wait");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("0 errors"), "stdout: {stdout}");
+    assert!(stdout.contains("Watching"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("M3L-E009"),
+        "expected a re-run, got: {stdout}"
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// Serve — local HTTP API
+// ══════════════════════════════════════════════════════════════
+
+/// POST `body` to `path` on a running `m3l serve`; returns status and body.
+fn http_post(address: &str, path: &str, body: &str) -> (u16, serde_json::Value) {
+    use std::io::{Read, Write};
+    let mut stream = std::net::TcpStream::connect(address).expect("failed to connect");
+    write!(
+        stream,
+        "POST {path} HTTP/1.1\r\nHost: {address}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").expect("no header end");
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (
+        status,
+        serde_json::from_str(body).expect("invalid JSON body"),
+    )
+}
+
+#[test]
+fn serve_answers_parse_validate_lint_and_format() {
+    use std::io::BufRead;
+    let mut child = m3l_bin()
+        .args(["serve", "--port", "0"])
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("failed to run");
+    let mut line = String::new();
+    std::io::BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let address = line
+        .split("http://")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .expect("no address in startup line")
+        .to_string();
+
+    let schema = serde_json::json!({
+        "content": "## User\n- id: identifier @pk\n- role: Missing\n- name: string(50)\n",
+        "filename": "user.m3l.md",
+    });
+    let (status, parsed) = http_post(&address, "/parse", &schema.to_string());
+    assert_eq!(status, 200);
+    assert_eq!(parsed["data"]["models"][0]["name"], "User");
+    assert_eq!(parsed["data"]["sources"][0], "user.m3l.md");
+
+    let (status, validated) = http_post(&address, "/validate", &schema.to_string());
+    assert_eq!(status, 200);
+    assert_eq!(validated["data"]["errors"][0]["code"], "M3L-E009");
+    assert_eq!(validated["data"]["errors"][0]["file"], "user.m3l.md");
+
+    let lint = serde_json::json!({
+        "content": "## user_account\n- id: identifier @pk\n",
+        "options": { "rules": { "naming-convention": "error" } },
+    });
+    let (status, linted) = http_post(&address, "/lint", &lint.to_string());
+    assert_eq!(status, 200);
+    assert_eq!(linted["data"]["diagnostics"][0]["severity"], "error");
+
+    let format = serde_json::json!({
+        "content": "## User\n- id: identifier @pk\n- name: string(50)\n",
+        "options": { "align": true },
+    });
+    let (status, formatted) = http_post(&address, "/format", &format.to_string());
+    assert_eq!(status, 200);
+    assert!(formatted["data"]
+        .as_str()
+        .unwrap()
+        .contains("- id:   identifier @pk"));
+
+    let (status, error) = http_post(&address, "/validate", "{\"content\": 1}");
+    assert_eq!(status, 400);
+    assert_eq!(error["success"], false);
+    let (status, _) = http_post(&address, "/nothing", "{}");
+    assert_eq!(status, 404);
+
+    child.kill().ok();
+    child.wait().ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// MCP — Model Context Protocol over stdio
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn mcp_lists_and_calls_tools() {
+    use std::io::Write;
+    let requests = [
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {
+            "protocolVersion": "2025-03-26", "capabilities": {},
+            "clientInfo": {"name": "test", "version": "0"}}}),
+        serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": {
+            "name": "validate_schema",
+            "arguments": {"path": "samples/test/validate/e009-undefined-type.m3l.md"}}}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": {
+            "name": "parse_schema", "arguments": {"content": "## User\n- id: identifier @pk\n"}}}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 5, "method": "tools/call", "params": {
+            "name": "diff_schemas",
+            "arguments": {"left": "samples/test/diff/v1.m3l.md", "right": "samples/test/diff/v2.m3l.md"}}}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 6, "method": "tools/call", "params": {
+            "name": "explain_code", "arguments": {"code": "E009"}}}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 7, "method": "tools/call", "params": {
+            "name": "parse_schema", "arguments": {}}}),
+        serde_json::json!({"jsonrpc": "2.0", "id": 8, "method": "resources/list"}),
+    ];
+    let mut child = m3l_bin()
+        .arg("mcp")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("failed to run");
+    let mut stdin = child.stdin.take().unwrap();
+    for request in &requests {
+        writeln!(stdin, "{request}").unwrap();
+    }
+    drop(stdin);
+    let output = child.wait_with_output().expect("failed to wait");
+    assert!(output.status.success());
+
+    let replies: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("reply is not JSON"))
+        .collect();
+    // Every request but the notification gets exactly one reply, in order
+    let ids: Vec<u64> = replies.iter().map(|r| r["id"].as_u64().unwrap()).collect();
+    assert_eq!(ids, [1, 2, 3, 4, 5, 6, 7, 8]);
+    let text = |i: usize| replies[i]["result"]["content"][0]["text"].as_str().unwrap();
+
+    assert_eq!(replies[0]["result"]["protocolVersion"], "2025-03-26");
+    assert_eq!(replies[0]["result"]["serverInfo"]["name"], "m3l");
+    let tools: Vec<&str> = replies[1]["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    for tool in [
+        "parse_schema",
+        "validate_schema",
+        "diff_schemas",
+        "explain_code",
+    ] {
+        assert!(tools.contains(&tool), "tools: {tools:?}");
+    }
+    assert!(text(2).contains("M3L-E009"));
+    let ast: serde_json::Value = serde_json::from_str(text(3)).unwrap();
+    assert_eq!(ast["models"][0]["name"], "User");
+    assert!(!text(4).is_empty());
+    assert!(text(5).starts_with("M3L-E009"));
+    assert_eq!(replies[6]["result"]["isError"], true);
+    assert_eq!(replies[7]["error"]["code"], -32601);
+}
+
+// ══════════════════════════════════════════════════════════════
+// Logging — verbosity and log format
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn logging_levels_and_json_format() {
+    let sample = "samples/01-ecommerce.m3l.md";
+    let output = m3l_bin()
+        .args(["validate", sample])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.stderr.is_empty(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = m3l_bin()
+        .args(["validate", "samples/multi", "-vv", "--log-format", "json"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let events: Vec<serde_json::Value> = stderr
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("log line is not JSON"))
+        .collect();
+    let messages: Vec<&str> = events
+        .iter()
+        .map(|e| e["fields"]["message"].as_str().unwrap())
+        .collect();
+    for expected in [
+        "scanned directory",
+        "read files",
+        "parsed",
+        "resolved",
+        "validated",
+    ] {
+        assert!(messages.contains(&expected), "events: {messages:?}");
+    }
+    let parsed = events
+        .iter()
+        .find(|e| e["fields"]["message"] == "parsed")
+        .unwrap();
+    assert_eq!(parsed["level"], "DEBUG");
+    assert!(parsed["fields"]["elapsed_ms"].is_number());
+    // Logging never touches stdout
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("0 errors"));
+
+    let output = m3l_bin()
+        .args(["lint", sample, "--quiet"])
+        .output()
+        .expect("failed to run");
+    assert!(output.stderr.is_empty());
+    assert!(!output.stdout.is_empty());
+}
+
+// ══════════════════════════════════════════════════════════════
+// Config — m3l.toml discovery
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn toml_config_found_from_subdirectory() {
+    let dir = std::env::temp_dir().join("m3l-toml-config-test");
+    let models = dir.join("models");
+    std::fs::create_dir_all(&models).unwrap();
+    std::fs::copy(
+        workspace_root().join("samples/test/validate/w001-long-line.m3l.md"),
+        models.join("long.m3l.md"),
+    )
+    .unwrap();
+    std::fs::copy(
+        workspace_root().join("samples/test/lint/naming-bad.m3l.md"),
+        models.join("naming.m3l.md"),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("m3l.toml"),
+        "name = \"shop\"\nversion = \"2.0.0\"\n\n[validate]\nstrict = true\nmax_warnings = 0\n\n[lint.rules]\nnaming-convention = \"off\"\n",
+    )
+    .unwrap();
+    let run = |args: &[&str]| m3l_bin().args(args).output().expect("failed to run");
+    let long = models.join("long.m3l.md");
+    let long = long.to_str().unwrap();
+
+    // Project info applies to a single file inside the project
+    let output = run(&["parse", long]);
+    assert!(output.status.success());
+    let ast: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    assert_eq!(ast["project"]["name"], "shop");
+    assert_eq!(ast["project"]["version"], "2.0.0");
+
+    // [validate] turns on strict mode and the warning budget
+    let output = run(&["validate", long, "--format", "json"]);
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    assert!(result["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|d| d["code"] == "M3L-W001"));
+    assert_eq!(output.status.code(), Some(1));
+    // A flag still wins over the config
+    let output = run(&["validate", long, "--max-warnings", "10"]);
+    assert!(output.status.success());
+
+    // [lint.rules] applies from the subdirectory
+    let output = run(&["lint", models.to_str().unwrap(), "--format", "json"]);
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    assert!(!result["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|d| d["rule"] == "naming-convention"));
+
+    std::fs::write(dir.join("m3l.toml"), "[validate]\nstrictt = true\n").unwrap();
+    let output = run(&["validate", long]);
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid config"), "stderr: {stderr}");
+    assert!(stderr.contains("strictt"), "stderr: {stderr}");
+}
+
+// ══════════════════════════════════════════════════════════════
+// Format — dedicated fixtures
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn format_full_features() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/full.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("# Namespace:"), "stdout: {stdout}");
+    assert!(stdout.contains("::interface"), "stdout: {stdout}");
+    assert!(stdout.contains("::enum"), "stdout: {stdout}");
+    assert!(stdout.contains("::view"), "stdout: {stdout}");
+    assert!(stdout.contains("## Customer"), "stdout: {stdout}");
+}
+
+#[test]
+fn format_sort_and_align_flags() {
+    let output = m3l_bin()
+        .args([
+            "format",
+            "samples/test/format/full.m3l.md",
+            "--sort-fields",
+            "alphabetical",
+            "--align",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let customer: Vec<&str> = stdout
+        .lines()
+        .skip_while(|l| !l.starts_with("## Customer"))
+        .skip(1)
+        .take_while(|l| l.starts_with("- "))
+        .collect();
+    // Inherited fields come from `: Timestampable` and are not repeated
+    assert_eq!(customer[0], "- email:   email       @unique");
+    assert_eq!(customer[1], "- id:      identifier  @pk @generated");
+}
+
+#[test]
+fn format_options_from_config() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/configured"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Dependency order puts the referenced Customer before Order
+    let customer = stdout.find("## Customer").unwrap();
+    let order = stdout.find("## Order").unwrap();
+    assert!(customer < order, "stdout: {stdout}");
+    // pk-first and aligned columns
+    assert!(
+        stdout.contains("## Order\n- id:          identifier     @pk @generated"),
+        "stdout: {stdout}"
+    );
+
+    // Flags override the config
+    let output = m3l_bin()
+        .args([
+            "format",
+            "samples/test/format/configured",
+            "--sort-models",
+            "declaration",
+            "--no-align",
+        ])
+        .output()
+        .expect("failed to run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.find("## Order").unwrap() < stdout.find("## Customer").unwrap());
+    assert!(
+        stdout.contains("- id: identifier @pk @generated"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn format_nested_fields() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/full.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Nested fields should be indented
+    assert!(
+        stdout.contains("  - bio:") || stdout.contains("  - avatar_url:"),
+        "Expected indented nested fields, stdout: {stdout}"
+    );
+}
+
+#[test]
+fn format_roundtrip() {
+    // Format → write to temp file → parse → should produce valid JSON AST
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/full.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let formatted = String::from_utf8_lossy(&output.stdout);
+
+    let tmp = std::env::temp_dir().join("m3l-format-roundtrip.m3l.md");
+    std::fs::write(&tmp, formatted.as_ref()).expect("write tmp");
+
+    let parse_out = m3l_bin()
+        .args(["parse", tmp.to_str().unwrap()])
+        .output()
+        .expect("failed to run");
+    assert!(
+        parse_out.status.success(),
+        "Roundtrip parse failed, stderr: {}",
+        String::from_utf8_lossy(&parse_out.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&parse_out.stdout);
+    let ast: serde_json::Value =
+        serde_json::from_str(&stdout).expect("invalid JSON from roundtrip");
+    assert!(
+        ast["models"].is_array(),
+        "Roundtrip should produce models array"
+    );
+
+    std::fs::remove_file(&tmp).ok();
+}
+
+#[test]
+fn format_keeps_frontmatter() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/frontmatter.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Written back as is, and no `# Namespace:` header for its namespace
+    assert!(
+        stdout.starts_with("---\nnamespace: shop.billing\n"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("# Set by the release script\nstage: beta\n---\n\n## Invoice"));
+    assert!(!stdout.contains("# Namespace:"));
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/frontmatter.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let ast: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    assert_eq!(ast["project"]["name"], "shop.billing");
+    assert_eq!(
+        ast["fileMetadata"],
+        serde_json::json!([{
+            "source": "samples/test/format/frontmatter.m3l.md",
+            "namespace": "shop.billing",
+            "owner": "billing-team",
+            "version": "1.10",
+            "tags": ["billing", "pii"],
+            "metadata": {"reviewed": true, "stage": "beta"}
+        }])
+    );
+    assert_eq!(ast["models"][0]["namespace"], "shop.billing");
+}
+
+#[test]
+fn format_and_docs_keep_seed_rows() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/seed.m3l.md", "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Columns in field order; strings that read as numbers stay quoted
+    assert!(
+        stdout.contains(
+            "### Seed\n| id | name | builtin | code |\n| --- | ---- | ------- | ---- |\n| 1 | admin | true | \"007\" |\n| 2 | guest |  | null |\n- owner\n  - id: 3\n  - name: \"owner\""
+        ),
+        "stdout: {stdout}"
+    );
+
+    let out = std::env::temp_dir().join("m3l-docs-seed-test");
+    std::fs::remove_dir_all(&out).ok();
+    let output = m3l_bin()
+        .args([
+            "docs",
+            "samples/test/format/seed.m3l.md",
+            "--out",
+            out.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let role = std::fs::read_to_string(out.join("models/Role.html")).unwrap();
+    assert!(
+        role.contains("<h2>Seed data</h2>\n<table>\n<tr><th></th><th>id</th><th>name</th><th>builtin</th><th>code</th></tr>\n<tr><td></td><td>1</td><td>admin</td><td>true</td><td>007</td></tr>"),
+        "{role}"
+    );
+    assert!(role.contains("<tr><td>owner</td><td>3</td><td>owner</td><td></td><td></td></tr>"));
+    std::fs::remove_dir_all(&out).ok();
+}
+
+#[test]
+fn format_and_parse_endpoints() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/endpoints.m3l.md", "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Methods are written in upper case; options keep their nesting
+    assert!(
+        stdout.contains(
+            "### Endpoints\n- GET /users \"List users\"\n- GET /users/{id}\n- PATCH /users/{id} \"Update a user\"\n  - auth: admin\n  - roles: [owner, admin]\n- DELETE /users/{id}"
+        ),
+        "stdout: {stdout}"
+    );
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/endpoints.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let ast: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    let endpoints = &ast["models"][0]["sections"]["endpoints"];
+    assert_eq!(endpoints.as_array().map(Vec::len), Some(4));
+    let mut patch = endpoints[2].clone();
+    assert_eq!(patch["loc"]["line"], 8);
+    patch.as_object_mut().unwrap().remove("loc");
+    assert_eq!(
+        patch,
+        serde_json::json!({
+            "method": "PATCH",
+            "path": "/users/{id}",
+            "params": ["id"],
+            "description": "Update a user",
+            "options": {"auth": "admin", "roles": ["owner", "admin"]}
+        })
+    );
+}
+
+#[test]
+fn format_idempotent() {
+    // format(input) should equal format(format(input))
+    // i.e., formatting twice produces the same output
+    let inputs = &[
+        "samples/test/format/full.m3l.md",
+        "spec/conformance/inputs/01-ecommerce.m3l.md",
+        "spec/conformance/inputs/02-blog-cms.m3l.md",
+        "spec/conformance/inputs/03-types-showcase.m3l.md",
+    ];
+
+    for input_path in inputs {
+        // First format
+        let output1 = m3l_bin()
+            .args(["format", input_path])
+            .output()
+            .expect("failed to run");
+        assert!(
+            output1.status.success(),
+            "[{input_path}] First format failed: {}",
+            String::from_utf8_lossy(&output1.stderr)
+        );
+        let formatted1 = String::from_utf8_lossy(&output1.stdout);
+
+        // Write first format to temp file
+        let tmp = std::env::temp_dir().join("m3l-idempotent-test.m3l.md");
+        std::fs::write(&tmp, formatted1.as_ref()).expect("write tmp");
+
+        // Second format (format the already-formatted output)
+        let output2 = m3l_bin()
+            .args(["format", tmp.to_str().unwrap()])
+            .output()
+            .expect("failed to run");
+        assert!(
+            output2.status.success(),
+            "[{input_path}] Second format failed: {}",
+            String::from_utf8_lossy(&output2.stderr)
+        );
+        let formatted2 = String::from_utf8_lossy(&output2.stdout);
+
+        // They should be identical
+        assert_eq!(
+            formatted1.as_ref(),
+            formatted2.as_ref(),
+            "[{input_path}] Format is not idempotent — second format produced different output"
+        );
+
+        // Also verify the formatted output parses correctly
+        let parse_out = m3l_bin()
+            .args(["parse", tmp.to_str().unwrap()])
+            .output()
+            .expect("failed to run");
+        assert!(
+            parse_out.status.success(),
+            "[{input_path}] Formatted output failed to parse: {}",
+            String::from_utf8_lossy(&parse_out.stderr)
+        );
+
+        std::fs::remove_file(&tmp).ok();
+    }
+}
+
+#[test]
+fn format_preserves_ast() {
+    // parse(input) should produce same models/enums as parse(format(input))
+    // We compare structural content (field names, types, counts) rather than
+    // exact JSON equality, since loc/source fields will differ between files.
+    let inputs = &[
+        "samples/test/format/full.m3l.md",
+        "spec/conformance/inputs/01-ecommerce.m3l.md",
+        "spec/conformance/inputs/03-types-showcase.m3l.md",
+    ];
+
+    for input_path in inputs {
+        // Parse original
+        let parse1 = m3l_bin()
+            .args(["parse", input_path])
+            .output()
+            .expect("failed to run");
+        assert!(
+            parse1.status.success(),
+            "[{input_path}] Original parse failed"
+        );
+        let ast1: serde_json::Value =
+            serde_json::from_str(&String::from_utf8_lossy(&parse1.stdout))
+                .expect("invalid JSON from original parse");
+
+        // Format then parse
+        let format_out = m3l_bin()
+            .args(["format", input_path])
+            .output()
+            .expect("failed to run");
+        assert!(format_out.status.success(), "[{input_path}] Format failed");
+        let tmp = std::env::temp_dir().join("m3l-ast-equiv-test.m3l.md");
+        std::fs::write(&tmp, &format_out.stdout).expect("write tmp");
+
+        let parse2 = m3l_bin()
+            .args(["parse", tmp.to_str().unwrap()])
+            .output()
+            .expect("failed to run");
+        assert!(
+            parse2.status.success(),
+            "[{input_path}] Formatted parse failed"
+        );
+        let ast2: serde_json::Value =
+            serde_json::from_str(&String::from_utf8_lossy(&parse2.stdout))
                 .expect("invalid JSON from formatted parse");
 
-        // Compare model count
-        let models1 = ast1["models"].as_array().expect("models1 array");
-        let models2 = ast2["models"].as_array().expect("models2 array");
-        assert_eq!(
-            models1.len(),
-            models2.len(),
-            "[{input_path}] Model count differs after format"
+        // Compare model count
+        let models1 = ast1["models"].as_array().expect("models1 array");
+        let models2 = ast2["models"].as_array().expect("models2 array");
+        assert_eq!(
+            models1.len(),
+            models2.len(),
+            "[{input_path}] Model count differs after format"
+        );
+
+        // Compare each model's name, type, and field count/names
+        for (m1, m2) in models1.iter().zip(models2.iter()) {
+            assert_eq!(m1["name"], m2["name"], "[{input_path}] Model name differs");
+            assert_eq!(
+                m1["type"], m2["type"],
+                "[{input_path}] Model type differs for {}",
+                m1["name"]
+            );
+
+            let f1: Vec<&str> = m1["fields"]
+                .as_array()
+                .map(|a| a.iter().filter_map(|f| f["name"].as_str()).collect())
+                .unwrap_or_default();
+            let f2: Vec<&str> = m2["fields"]
+                .as_array()
+                .map(|a| a.iter().filter_map(|f| f["name"].as_str()).collect())
+                .unwrap_or_default();
+            assert_eq!(
+                f1, f2,
+                "[{input_path}] Field names differ for model {}",
+                m1["name"]
+            );
+        }
+
+        // Compare enum count and names
+        let enums1 = ast1["enums"].as_array();
+        let enums2 = ast2["enums"].as_array();
+        if let (Some(e1), Some(e2)) = (enums1, enums2) {
+            assert_eq!(
+                e1.len(),
+                e2.len(),
+                "[{input_path}] Enum count differs after format"
+            );
+            for (en1, en2) in e1.iter().zip(e2.iter()) {
+                assert_eq!(en1["name"], en2["name"], "[{input_path}] Enum name differs");
+            }
+        }
+
+        std::fs::remove_file(&tmp).ok();
+    }
+}
+
+#[test]
+fn format_verify_reports_first_divergence() {
+    let output = m3l_bin()
+        .args([
+            "format",
+            "spec/conformance/inputs/inheritance.m3l.md",
+            "--verify",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/full.m3l.md", "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout)
+        .contains("### Source\n- from: Customer\n- where: \"is_active = true\""));
+
+    // The formatter does not write custom sections yet
+    let output = m3l_bin()
+        .args([
+            "format",
+            "samples/test/format/custom-section.m3l.md",
+            "--verify",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("models[Customer].sections.Notes: dropped"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn format_write_rewrites_verified_files() {
+    let dir = std::env::temp_dir().join("m3l-format-write");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    let model = dir.join("model.m3l.md");
+    let view = dir.join("view.m3l.md");
+    let view_source =
+        std::fs::read_to_string(workspace_root().join("samples/test/format/custom-section.m3l.md"))
+            .unwrap();
+    std::fs::write(
+        &model,
+        "## Base ::interface\n- id:   identifier @pk\n\n## User : Base\n- email:    email   @unique\n",
+    )
+    .unwrap();
+    std::fs::write(&view, &view_source).unwrap();
+
+    // One file would lose its custom section: nothing is written
+    let output = m3l_bin()
+        .args(["format", dir.to_str().unwrap(), "--write"])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no files were written"), "stderr: {stderr}");
+    assert!(std::fs::read_to_string(&model)
+        .unwrap()
+        .contains("id:   identifier"));
+    assert_eq!(std::fs::read_to_string(&view).unwrap(), view_source);
+
+    std::fs::remove_file(&view).unwrap();
+    let output = m3l_bin()
+        .args(["format", dir.to_str().unwrap(), "--write"])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1 file formatted, 0 unchanged"));
+    let formatted = std::fs::read_to_string(&model).unwrap();
+    assert!(
+        formatted.contains("## User : Base\n- email: email @unique\n"),
+        "{formatted}"
+    );
+
+    let output = m3l_bin()
+        .args(["format", dir.to_str().unwrap(), "-w"])
+        .output()
+        .expect("failed to run");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("0 files formatted, 1 unchanged"));
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+// ══════════════════════════════════════════════════════════════
+// Parse — edge cases
+// ══════════════════════════════════════════════════════════════
+
+#[test]
+fn parse_file_with_errors() {
+    // Even files with errors should produce an AST (with diagnostics)
+    let output = m3l_bin()
+        .args(["parse", "samples/test/validate/e009-undefined-type.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let ast: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON");
+    assert!(ast["models"].is_array());
+    assert!(!ast["models"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn parse_config_include_exclude() {
+    let output = m3l_bin()
+        .args(["parse", "samples/test/discovery"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let ast: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    let models: Vec<&str> = ast["models"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(models, ["Invoice", "User"]);
+}
+
+#[test]
+fn parse_glob_path() {
+    let output = m3l_bin()
+        .args(["parse", "samples/test/discovery/models/**/*.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let ast: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    assert_eq!(ast["sources"].as_array().unwrap().len(), 3);
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/discovery/**/*.m3l"])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("No files match pattern"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn parse_empty_dir() {
+    let tmp = std::env::temp_dir().join("m3l-empty-dir-test");
+    std::fs::create_dir_all(&tmp).ok();
+    let output = m3l_bin()
+        .args(["parse", tmp.to_str().unwrap()])
+        .output()
+        .expect("failed to run");
+    assert!(
+        !output.status.success(),
+        "Expected failure for empty directory"
+    );
+    std::fs::remove_dir_all(&tmp).ok();
+}
+
+#[test]
+fn parse_html_explorer() {
+    let tmp = std::env::temp_dir().join("m3l-html-explorer-test");
+    std::fs::create_dir_all(&tmp).ok();
+    let page = tmp.join("ast.html");
+    let output = m3l_bin()
+        .args([
+            "parse",
+            "samples/01-ecommerce.m3l.md",
+            "--format",
+            "html-explorer",
+            "-o",
+            page.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let html = std::fs::read_to_string(&page).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    // Only the data block and the script close; embedded strings can't
+    assert_eq!(html.matches("</script>").count(), 2);
+    let start = html.find(r#"id="m3l-data">"#).expect("data block") + 14;
+    let end = start + html[start..].find("</script>").unwrap();
+    let data: serde_json::Value = serde_json::from_str(&html[start..end]).expect("invalid JSON");
+    assert!(!data["ast"]["models"].as_array().unwrap().is_empty());
+    assert!(data["diagnostics"].is_array());
+
+    let output = m3l_bin()
+        .args(["parse", "samples/01-ecommerce.m3l.md", "--format", "xml"])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Unknown format"), "stderr: {stderr}");
+    std::fs::remove_dir_all(&tmp).ok();
+}
+
+#[test]
+fn format_and_parse_localized_labels() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/labels.m3l.md", "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // The default label stays first; the other locales follow in order
+    assert!(
+        stdout.contains("## User(ko: \"사용자\", en: \"User\")\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("- name(en: \"Name\", ja: \"名前\", ko: \"이름\"): string(100)\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("- nickname(Display name): string?\n"),
+        "stdout: {stdout}"
+    );
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/labels.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let ast: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    let user = &ast["models"][0];
+    assert_eq!(user["label"], "사용자");
+    assert_eq!(
+        user["labels"],
+        serde_json::json!({"en": "User", "ko": "사용자"})
+    );
+    assert_eq!(user["fields"][1]["labels"]["ja"], "名前");
+    assert_eq!(user["fields"][2]["label"], "Display name");
+    assert!(user["fields"][2].get("labels").is_none());
+    assert_eq!(ast["enums"][0]["labels"]["ko"], "상태");
+}
+
+#[test]
+fn format_and_docs_keep_doc_tags() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/doc-tags.m3l.md", "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Tags follow the description; a continued tag is joined onto one line
+    assert!(
+        stdout.contains(
+            "- phone: string?\n  > Legacy contact number.\n  > @deprecated Use email instead, it is verified.\n  > @example +1 555 0100\n  > +82 10 0000 0000\n"
+        ),
+        "stdout: {stdout}"
+    );
+
+    let out = std::env::temp_dir().join("m3l-docs-doc-tags-test");
+    std::fs::remove_dir_all(&out).ok();
+    let output = m3l_bin()
+        .args([
+            "docs",
+            "samples/test/format/doc-tags.m3l.md",
+            "--out",
+            out.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let customer = std::fs::read_to_string(out.join("models/Customer.html")).unwrap();
+    assert!(
+        customer.contains("<dt>Since</dt><dd>1.2</dd>"),
+        "{customer}"
+    );
+    assert!(
+        customer.contains("<dt>See also</dt><dd><a href=\"../models/Order.html\">Order</a>, <a href=\"https://example.com/customers\">https://example.com/customers</a></dd>"),
+        "{customer}"
+    );
+    assert!(customer.contains(
+        "Legacy contact number. <span class=\"deprecated\">Deprecated: Use email instead, it is verified.</span><pre>+1 555 0100\n+82 10 0000 0000</pre>"
+    ));
+    let state = std::fs::read_to_string(out.join("enums/OrderState.html")).unwrap();
+    assert!(state.contains(
+        "<p class=\"deprecated\"><strong>Deprecated.</strong> Use the order's status history</p>"
+    ));
+    std::fs::remove_dir_all(&out).ok();
+}
+
+#[test]
+fn format_and_parse_generic_models() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/generics.m3l.md", "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("## Paged<T>(Page of results)\n"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("- next: Cursor<T>?\n"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("- counts: map<string, integer>\n"),
+        "stdout: {stdout}"
+    );
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/generics.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["errors"].as_array().unwrap().is_empty());
+    let instances = json["genericInstances"].as_array().unwrap();
+    let names: Vec<&str> = instances
+        .iter()
+        .map(|m| m["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Paged<Product>", "Cursor<Product>"]);
+    assert_eq!(instances[0]["instance_of"], "Paged");
+    assert_eq!(instances[0]["fields"][0]["type"], "Product");
+}
+
+#[test]
+fn format_and_parse_union_types() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/unions.m3l.md", "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("- value: string(200) | integer | boolean\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("- price: decimal(10, 2) | Money?\n"),
+        "stdout: {stdout}"
+    );
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/unions.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let price = &json["models"][1]["fields"][2];
+    assert_eq!(price["type"], "union");
+    assert_eq!(price["nullable"], true);
+    assert_eq!(price["union"][0]["type"], "decimal");
+    assert_eq!(price["union"][1]["type"], "Money");
+}
+
+#[test]
+fn format_parse_and_migrate_tuple_types() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/tuples.m3l.md", "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("- range: (date, date?)?\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("- path: (float, float)[]\n"),
+        "stdout: {stdout}"
+    );
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/tuples.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let range = &json["models"][0]["fields"][2];
+    assert_eq!(range["type"], "tuple");
+    assert_eq!(range["nullable"], true);
+    assert_eq!(range["tuple"][1]["type"], "date");
+    assert_eq!(range["tuple"][1]["nullable"], true);
+
+    // Tuples of one column type become arrays, others JSON
+    let output = m3l_bin()
+        .args([
+            "diff",
+            "samples/test/format/unions.m3l.md",
+            "samples/test/format/tuples.m3l.md",
+            "--migration",
+            "sql",
+        ])
+        .output()
+        .expect("failed to run");
+    let sql = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        sql.contains("  \"point\" DOUBLE PRECISION[] NOT NULL,\n"),
+        "sql: {sql}"
+    );
+    assert!(sql.contains("  \"label\" JSONB NOT NULL,\n"), "sql: {sql}");
+}
+
+#[test]
+fn format_and_parse_inline_enums() {
+    let output = m3l_bin()
+        .args([
+            "format",
+            "samples/test/format/literal-enums.m3l.md",
+            "--verify",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let expected =
+        std::fs::read_to_string(workspace_root().join("samples/test/format/literal-enums.m3l.md"))
+            .unwrap();
+    assert_eq!(stdout, expected.replace("\r\n", "\n"));
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/literal-enums.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let status = &json["models"][0]["fields"][1];
+    assert_eq!(status["type"], "enum");
+    let values: Vec<&str> = status["enum_values"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(values, ["draft", "published", "archived"]);
+    assert_eq!(json["models"][0]["fields"][2]["nullable"], true);
+}
+
+#[test]
+fn format_parse_and_migrate_json_defaults() {
+    let output = m3l_bin()
+        .args([
+            "format",
+            "samples/test/format/json-defaults.m3l.md",
+            "--verify",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let expected =
+        std::fs::read_to_string(workspace_root().join("samples/test/format/json-defaults.m3l.md"))
+            .unwrap();
+    assert_eq!(stdout, expected.replace("\r\n", "\n"));
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/json-defaults.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let tags = &json["models"][0]["fields"][1];
+    assert_eq!(tags["default_value_type"], "json");
+    assert_eq!(tags["default_json"], serde_json::json!(["a", "b"]));
+    assert_eq!(
+        json["models"][0]["fields"][3]["default_json"]["monthly"],
+        300
+    );
+
+    let output = m3l_bin()
+        .args([
+            "diff",
+            "samples/test/format/tuples.m3l.md",
+            "samples/test/format/json-defaults.m3l.md",
+            "--migration",
+            "sql",
+        ])
+        .output()
+        .expect("failed to run");
+    let sql = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        sql.contains("\"tags\" TEXT[] NOT NULL DEFAULT ARRAY['a', 'b'],"),
+        "sql: {sql}"
+    );
+    assert!(
+        sql.contains("\"sizes\" INTEGER[] NOT NULL DEFAULT '{}',"),
+        "sql: {sql}"
+    );
+    assert!(
+        sql.contains(
+            "\"config\" JSONB NOT NULL DEFAULT '{\"retries\": 3, \"mode\": \"fast\"}'::jsonb"
+        ),
+        "sql: {sql}"
+    );
+}
+
+#[test]
+fn validate_and_docs_structured_deprecations() {
+    let fixture = "samples/test/validate/w007-deprecated-use.m3l.md";
+    let output = m3l_bin()
+        .args(["validate", fixture, "--format", "json"])
+        .output()
+        .expect("failed to run");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let messages: Vec<&str> = json["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|w| w["code"] == "M3L-W007")
+        .map(|w| w["message"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        messages,
+        ["Field \"customer_email\" uses deprecated \"Customer.email\" (since 2.0): use Contact.email"]
+    );
+
+    let out = std::env::temp_dir().join("m3l-docs-deprecations-test");
+    std::fs::remove_dir_all(&out).ok();
+    let output = m3l_bin()
+        .args(["docs", fixture, "--out", out.to_str().unwrap()])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let customer = std::fs::read_to_string(out.join("models/Customer.html")).unwrap();
+    assert!(
+        customer.contains("<p class=\"deprecated\"><strong>Deprecated since 3.0.</strong> Use <a href=\"../models/Contact.html\">Contact</a>.</p>"),
+        "{customer}"
+    );
+    assert!(
+        customer.contains("<span class=\"deprecated\">Deprecated since 2.0: use <a href=\"../models/Contact.html\">Contact.email</a></span>"),
+        "{customer}"
+    );
+    std::fs::remove_dir_all(&out).ok();
+}
+
+#[test]
+fn format_and_parse_extend_blocks() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/extend.m3l.md", "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let expected =
+        std::fs::read_to_string(workspace_root().join("samples/test/format/extend.m3l.md"))
+            .unwrap();
+    assert_eq!(stdout, expected.replace("\r\n", "\n"));
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/extend.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let user = &json["models"][0];
+    let fields: Vec<&str> = user["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["id", "name", "email", "team_id"]);
+    assert_eq!(user["sections"]["indexes"].as_array().unwrap().len(), 2);
+    assert_eq!(json["extendBlocks"][0]["name"], "User");
+}
+
+#[test]
+fn format_and_parse_view_join_kinds() {
+    let output = m3l_bin()
+        .args([
+            "format",
+            "samples/test/format/view-joins.m3l.md",
+            "--verify",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let expected =
+        std::fs::read_to_string(workspace_root().join("samples/test/format/view-joins.m3l.md"))
+            .unwrap();
+    assert_eq!(stdout, expected.replace("\r\n", "\n"));
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/view-joins.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let joins = &json["views"][0]["source_def"]["joins"];
+    assert_eq!(joins[0]["kind"], "left");
+    assert_eq!(joins[0]["alias"], "o");
+    assert_eq!(joins[0]["on"], "o.customer_id = Customer.id");
+    assert!(joins[1].get("kind").is_none());
+}
+
+#[test]
+fn format_and_parse_typed_relations() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/relations.m3l.md", "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let expected =
+        std::fs::read_to_string(workspace_root().join("samples/test/format/relations.m3l.md"))
+            .unwrap();
+    assert_eq!(stdout, expected.replace("\r\n", "\n"));
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/relations.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let relations = &json["models"][0]["sections"]["relations"];
+    assert_eq!(relations[0]["name"], "parent");
+    assert_eq!(relations[0]["direction"], "to");
+    assert_eq!(relations[0]["from"], "parent_id");
+    assert_eq!(relations[1]["direction"], "from");
+    assert_eq!(relations[1]["cardinality"], "one-to-many");
+    let category = &json["models"][1]["sections"]["relations"][0];
+    assert_eq!(category["target"], "Category");
+    assert_eq!(category["on_delete"], "restrict");
+    assert_eq!(category["options"]["load"], "eager");
+}
+
+#[test]
+fn format_and_validate_behaviors() {
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/behaviors.m3l.md", "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let expected =
+        std::fs::read_to_string(workspace_root().join("samples/test/format/behaviors.m3l.md"))
+            .unwrap();
+    assert_eq!(stdout, expected.replace("\r\n", "\n"));
+
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/behaviors.m3l.md"])
+        .output()
+        .expect("failed to run");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let behaviors = &json["models"][0]["sections"]["behaviors"];
+    assert_eq!(behaviors[1]["timing"], "before");
+    assert_eq!(behaviors[1]["event"], "update");
+    assert_eq!(behaviors[1]["action"], "set updated_at = now()");
+    assert_eq!(behaviors[2]["condition"], "archived = false");
+
+    let output = m3l_bin()
+        .args([
+            "validate",
+            "samples/test/format/behaviors.m3l.md",
+            "--strict",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("M3L-W010"));
+}
+
+#[test]
+fn format_and_parse_nested_metadata() {
+    let output = m3l_bin()
+        .args(["parse", "samples/test/format/metadata.m3l.md"])
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let ast: serde_json::Value = serde_json::from_slice(&output.stdout).expect("invalid JSON");
+    let metadata = &ast["models"][0]["sections"]["metadata"];
+    assert_eq!(metadata["version"], "1.0");
+    assert_eq!(
+        metadata["owners"],
+        serde_json::json!(["data-team", "billing"])
+    );
+    assert_eq!(
+        metadata["storage"],
+        serde_json::json!({"engine": "postgres", "replicas": 2, "tiers": ["hot", "cold"]})
+    );
+
+    let output = m3l_bin()
+        .args(["format", "samples/test/format/metadata.m3l.md", "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(
+            "- storage:\n  - engine: postgres\n  - replicas: 2\n  - tiers:\n    - hot\n    - cold"
+        ),
+        "{stdout}"
+    );
+    assert!(stdout.contains("- version: \"1.0\""), "{stdout}");
+}
+
+#[test]
+fn format_and_validate_version_pragmas() {
+    let output = m3l_bin()
+        .args([
+            "format",
+            "samples/test/format/version-pragma.m3l.md",
+            "--verify",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout)
+        .starts_with("<!-- m3l: 1.1 -->\n\n# Namespace: test.format\n"));
+
+    // The same file declared as M3L 1.0 loses its extend block
+    let dir = std::env::temp_dir().join("m3l-version-pragma");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("old.m3l.md");
+    let source =
+        std::fs::read_to_string(workspace_root().join("samples/test/format/version-pragma.m3l.md"))
+            .unwrap();
+    std::fs::write(&file, source.replace("m3l: 1.1", "m3l: 1.0")).unwrap();
+
+    let output = m3l_bin()
+        .args(["validate", file.to_str().unwrap()])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("M3L-P005"), "stdout: {stdout}");
+
+    let output = m3l_bin()
+        .args(["format", file.to_str().unwrap(), "--verify"])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("line 9: `## extend` blocks need M3L 1.1"),
+        "stderr: {stderr}"
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn parse_groups_namespaces() {
+    let output = m3l_bin()
+        .args(["parse", "samples/multi", "--group-namespaces"])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json.get("models").is_none());
+    let names: Vec<&str> = json["namespaces"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|g| g["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["sample.multi", "sample.multi.inventory"]);
+    assert!(json["namespaces"][1]["models"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|m| m["namespace"] == "sample.multi.inventory"));
+
+    let output = m3l_bin()
+        .args([
+            "parse",
+            "samples/multi",
+            "--group-namespaces",
+            "--format",
+            "html-explorer",
+        ])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn parse_config_order() {
+    let dir = std::env::temp_dir().join("m3l-order-config-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("shop.m3l.md"),
+        "## Order\n- id: identifier @pk\n- product_id: identifier @fk(Product.id)\n\n\
+         ## Product\n- id: identifier @pk\n- category_id: identifier @fk(Category.id)\n\n\
+         ## Category\n- id: identifier @pk\n",
+    )
+    .unwrap();
+    let model_names = |order: &str| -> Vec<String> {
+        std::fs::write(dir.join("m3l.toml"), format!("order = \"{order}\"\n")).unwrap();
+        let output = m3l_bin()
+            .args(["parse", dir.to_str().unwrap()])
+            .output()
+            .expect("failed to run");
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
         );
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["models"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(model_names("source"), ["Order", "Product", "Category"]);
+    assert_eq!(
+        model_names("alphabetical"),
+        ["Category", "Order", "Product"]
+    );
+    assert_eq!(model_names("dependency"), ["Category", "Product", "Order"]);
+    std::fs::remove_dir_all(&dir).ok();
+}
 
-        // Compare each model's name, type, and field count/names
-        for (m1, m2) in models1.iter().zip(models2.iter()) {
-            assert_eq!(m1["name"], m2["name"], "[{input_path}] Model name differs");
-            assert_eq!(
-                m1["type"], m2["type"],
-                "[{input_path}] Model type differs for {}",
-                m1["name"]
-            );
+#[test]
+fn parse_config_types_and_defaults() {
+    let dir = std::env::temp_dir().join("m3l-types-config-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("app.m3l.md"),
+        "## Timestampable ::interface\n- created_at: timestamp\n\n\
+         ## User\n- id: identifier @pk\n- contact: email?\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("m3l.toml"),
+        "[types]\nemail = \"string(320)\"\n\n[defaults]\ninherits = [\"Timestampable\"]\n",
+    )
+    .unwrap();
+    let output = m3l_bin()
+        .args(["parse", dir.to_str().unwrap()])
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let user = &json["models"][0];
+    assert_eq!(
+        user["default_inherits"],
+        serde_json::json!(["Timestampable"])
+    );
+    assert_eq!(user["fields"][0]["name"], "created_at");
+    let contact = &user["fields"][2];
+    assert_eq!(contact["type"], "string");
+    assert_eq!(contact["type_alias"], "email");
+    assert_eq!(contact["nullable"], true);
 
-            let f1: Vec<&str> = m1["fields"]
-                .as_array()
-                .map(|a| a.iter().filter_map(|f| f["name"].as_str()).collect())
-                .unwrap_or_default();
-            let f2: Vec<&str> = m2["fields"]
-                .as_array()
-                .map(|a| a.iter().filter_map(|f| f["name"].as_str()).collect())
-                .unwrap_or_default();
-            assert_eq!(
-                f1, f2,
-                "[{input_path}] Field names differ for model {}",
-                m1["name"]
-            );
-        }
+    std::fs::write(
+        dir.join("m3l.toml"),
+        "[types]\nemail = \"string @unique\"\n",
+    )
+    .unwrap();
+    let output = m3l_bin()
+        .args(["parse", dir.to_str().unwrap()])
+        .output()
+        .expect("failed to run");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid type alias \"email\""));
+    std::fs::remove_dir_all(&dir).ok();
+}
 
-        // Compare enum count and names
-        let enums1 = ast1["enums"].as_array();
-        let enums2 = ast2["enums"].as_array();
-        if let (Some(e1), Some(e2)) = (enums1, enums2) {
-            assert_eq!(
-                e1.len(),
-                e2.len(),
-                "[{input_path}] Enum count differs after format"
-            );
-            for (en1, en2) in e1.iter().zip(e2.iter()) {
-                assert_eq!(en1["name"], en2["name"], "[{input_path}] Enum name differs");
-            }
-        }
+#[test]
+fn diff_sql_migration_constraints() {
+    let dir = std::env::temp_dir().join("m3l-migration-constraints-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("v1.m3l.md"),
+        "## Customer\n- id: identifier @pk\n- email: email @unique\n- code: string\n\n## Order\n- id: identifier @pk\n- customer_id: identifier @reference(Customer)\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("v2.m3l.md"),
+        "## Customer\n- id: identifier @pk\n- email: email\n- code: string @unique\n\n## Order\n- id: identifier @pk\n- customer_id: identifier @reference(Customer)!!\n- total: decimal(10, 2)\n- coupon: string = \"none\"\n\n## Invoice\n- id: identifier @pk\n- order_id: identifier? @reference(Order)\n",
+    )
+    .unwrap();
+    let output = m3l_bin()
+        .args(["diff", "v1.m3l.md", "v2.m3l.md", "--migration", "sql"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let sql = String::from_utf8_lossy(&output.stdout);
+    let position = |statement: &str| {
+        sql.find(statement)
+            .unwrap_or_else(|| panic!("{statement} missing, sql: {sql}"))
+    };
 
-        std::fs::remove_file(&tmp).ok();
+    // New tables get their foreign keys once every table exists
+    let invoice = position("CREATE TABLE \"Invoice\"");
+    let invoice_fk = position(
+        "\nALTER TABLE \"Invoice\" ADD CONSTRAINT \"Invoice_order_id_fkey\" FOREIGN KEY (\"order_id\") REFERENCES \"Order\" (\"id\") ON DELETE SET NULL;\n",
+    );
+    assert!(invoice < invoice_fk, "sql: {sql}");
+
+    // A changed reference replaces its foreign key
+    let drop_fk = position("\nALTER TABLE \"Order\" DROP CONSTRAINT \"Order_customer_id_fkey\";\n");
+    let add_fk = position(
+        "\nALTER TABLE \"Order\" ADD CONSTRAINT \"Order_customer_id_fkey\" FOREIGN KEY (\"customer_id\") REFERENCES \"Customer\" (\"id\") ON DELETE RESTRICT;\n",
+    );
+    assert!(drop_fk < add_fk, "sql: {sql}");
+
+    // @unique adds and drops UNIQUE constraints
+    position("\nALTER TABLE \"Customer\" DROP CONSTRAINT \"Customer_email_key\";\n");
+    position(
+        "\nALTER TABLE \"Customer\" ADD CONSTRAINT \"Customer_code_key\" UNIQUE (\"code\");\n",
+    );
+
+    // A NOT NULL column without a default is flagged
+    position(
+        "\n-- \"total\" is NOT NULL with no default; this fails on a table with rows.\nALTER TABLE \"Order\" ADD COLUMN \"total\" NUMERIC(10, 2) NOT NULL;\n",
+    );
+    assert!(!sql.contains("-- \"coupon\""), "sql: {sql}");
+    position("\nALTER TABLE \"Order\" ADD COLUMN \"coupon\" TEXT NOT NULL DEFAULT 'none';\n");
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn diff_sql_migration_referential_actions() {
+    let dir = std::env::temp_dir().join("m3l-migration-actions-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("v1.m3l.md"), "## User\n- id: identifier @pk\n").unwrap();
+    std::fs::write(
+        dir.join("v2.m3l.md"),
+        "## User\n- id: identifier @pk\n\n## Post\n- id: identifier @pk\n- author_id: identifier @reference(User)\n- editor_id: identifier? @reference(User)\n- owner_id: identifier @reference(User)!\n- creator_id: identifier @reference(User)!! @on_update(cascade)\n",
+    )
+    .unwrap();
+    let output = m3l_bin()
+        .args(["diff", "v1.m3l.md", "v2.m3l.md", "--migration", "sql"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let sql = String::from_utf8_lossy(&output.stdout);
+    for (column, actions) in [
+        ("author_id", "ON DELETE CASCADE;"),
+        ("editor_id", "ON DELETE SET NULL;"),
+        ("owner_id", "ON DELETE NO ACTION;"),
+        ("creator_id", "ON DELETE RESTRICT ON UPDATE CASCADE;"),
+    ] {
+        assert!(
+            sql.contains(&format!(
+                "FOREIGN KEY (\"{column}\") REFERENCES \"User\" (\"id\") {actions}\n"
+            )),
+            "sql: {sql}"
+        );
     }
+    std::fs::remove_dir_all(&dir).ok();
 }
 
-// ══════════════════════════════════════════════════════════════
-// Parse — edge cases
-// ══════════════════════════════════════════════════════════════
+#[test]
+fn diff_sql_migration_natural_keys() {
+    let dir = std::env::temp_dir().join("m3l-migration-keys-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("v1.m3l.md"), "## Tenant\n- id: identifier @pk\n").unwrap();
+    std::fs::write(
+        dir.join("v2.m3l.md"),
+        "## Tenant\n- id: identifier @pk\n\n## Member\n- id: identifier @pk\n- tenant_id: identifier\n- email: email\n- @check(`email <> ''`)\n\n### Keys\n- tenant_email\n  - fields: [tenant_id, email]\n",
+    )
+    .unwrap();
+    let output = m3l_bin()
+        .args(["diff", "v1.m3l.md", "v2.m3l.md", "--migration", "sql"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    assert!(output.status.success());
+    let sql = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        sql.contains(
+            "  \"email\" VARCHAR(320) NOT NULL,\n  CONSTRAINT \"Member_tenant_id_email_key\" UNIQUE (\"tenant_id\", \"email\"),\n  CONSTRAINT \"Member_check\" CHECK (email <> '')\n);"
+        ),
+        "sql: {sql}"
+    );
+
+    // Keys of an existing table change as constraints, and both forms
+    // survive formatting
+    std::fs::write(
+        dir.join("v3.m3l.md"),
+        "## Tenant\n- id: identifier @pk\n\n## Member\n- id: identifier @pk\n- tenant_id: identifier\n- email: email\n- handle: string(30)\n- @natural_key(handle) \"Handles are global\"\n- @check(`email <> ''`)\n\n### Keys\n- tenant_handle(Handle per tenant)\n  - fields: [tenant_id, handle]\n",
+    )
+    .unwrap();
+    let output = m3l_bin()
+        .args(["format", "v3.m3l.md", "--verify"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("- @natural_key(handle) \"Handles are global\"\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "### Keys\n- tenant_handle \"Handle per tenant\"\n  - fields: [tenant_id, handle]"
+        ),
+        "stdout: {stdout}"
+    );
+
+    let output = m3l_bin()
+        .args(["diff", "v2.m3l.md", "v3.m3l.md"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("removed key tenant_email(tenant_id, email)"),
+        "stdout: {stdout}"
+    );
+    let output = m3l_bin()
+        .args(["diff", "v2.m3l.md", "v3.m3l.md", "--migration", "sql"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    let sql = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        sql.contains("ALTER TABLE \"Member\" DROP CONSTRAINT \"Member_tenant_id_email_key\";"),
+        "sql: {sql}"
+    );
+    assert!(
+        sql.contains("ALTER TABLE \"Member\" ADD CONSTRAINT \"Member_handle_key\" UNIQUE (\"handle\");\nALTER TABLE \"Member\" ADD CONSTRAINT \"Member_tenant_id_handle_key\" UNIQUE (\"tenant_id\", \"handle\");"),
+        "sql: {sql}"
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
 
 #[test]
-fn parse_file_with_errors() {
-    // Even files with errors should produce an AST (with diagnostics)
+fn diff_sql_migration_drops_tables_in_dependency_order() {
+    let dir = std::env::temp_dir().join("m3l-migration-drop-order-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("v1.m3l.md"),
+        "## Customer\n- id: identifier @pk\n\n## Order\n- id: identifier @pk\n- customer_id: identifier @reference(Customer)\n\n## Person\n- id: identifier @pk\n- team_id: identifier? @reference(Team)\n\n## Team\n- id: identifier @pk\n- lead_id: identifier? @reference(Person)\n\n## Setting\n- id: identifier @pk\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("v2.m3l.md"), "## Setting\n- id: identifier @pk\n").unwrap();
     let output = m3l_bin()
-        .args(["parse", "samples/test/validate/e009-undefined-type.m3l.md"])
+        .args([
+            "diff",
+            "v1.m3l.md",
+            "v2.m3l.md",
+            "--migration",
+            "sql",
+            "--allow-destructive",
+        ])
+        .current_dir(&dir)
         .output()
         .expect("failed to run");
     assert!(output.status.success());
+    let sql = String::from_utf8_lossy(&output.stdout);
+    // Order references Customer, so it goes first; the Person/Team cycle
+    // is broken by dropping one of its foreign keys
+    assert!(
+        sql.contains(
+            "BEGIN;\nALTER TABLE \"Team\" DROP CONSTRAINT \"Team_lead_id_fkey\";\nDROP TABLE \"Person\";\nDROP TABLE \"Team\";\nDROP TABLE \"Order\";\nDROP TABLE \"Customer\";\nCOMMIT;"
+        ),
+        "sql: {sql}"
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn format_and_migrate_checks() {
+    let dir = std::env::temp_dir().join("m3l-checks-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("v1.m3l.md"),
+        "## Product\n- id: identifier @pk\n- price: decimal\n- discount: decimal\n- @check(`price > 0`)\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("v2.m3l.md"),
+        "## Product\n- id: identifier @pk\n- price: decimal @check(`price >= 0`)\n- discount: decimal\n- @check(`price > discount`) \"Discount below price\"\n- @check(`discount >= 0`)\n",
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        m3l_bin()
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("failed to run")
+    };
+
+    let output = run(&["format", "v2.m3l.md", "--verify"]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let ast: serde_json::Value = serde_json::from_str(&stdout).expect("invalid JSON");
-    assert!(ast["models"].is_array());
-    assert!(!ast["models"].as_array().unwrap().is_empty());
+    assert!(
+        stdout.contains(
+            "- discount: decimal\n- @check(`price > discount`) \"Discount below price\"\n- @check(`discount >= 0`)"
+        ),
+        "stdout: {stdout}"
+    );
+
+    let output = run(&["diff", "v1.m3l.md", "v2.m3l.md"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("~ model Product: removed check price > 0"),
+        "stdout: {stdout}"
+    );
+
+    // Both the column's and the table's checks change on an existing table
+    let output = run(&["diff", "v1.m3l.md", "v2.m3l.md", "--migration", "sql"]);
+    assert!(output.status.success());
+    let sql = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        sql.contains(
+            "BEGIN;\nALTER TABLE \"Product\" DROP CONSTRAINT \"Product_check\";\nALTER TABLE \"Product\" ADD CONSTRAINT \"Product_check\" CHECK (price > discount);\nALTER TABLE \"Product\" ADD CONSTRAINT \"Product_check1\" CHECK (discount >= 0);\nALTER TABLE \"Product\" ADD CONSTRAINT \"Product_price_check\" CHECK (price >= 0);\nCOMMIT;"
+        ),
+        "sql: {sql}"
+    );
+    std::fs::remove_dir_all(&dir).ok();
 }
 
 #[test]
-fn parse_empty_dir() {
-    let tmp = std::env::temp_dir().join("m3l-empty-dir-test");
-    std::fs::create_dir_all(&tmp).ok();
+fn format_ignores_config_types_and_defaults() {
+    let dir = std::env::temp_dir().join("m3l-format-config-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(dir.join("models")).unwrap();
+    std::fs::write(
+        dir.join("m3l.toml"),
+        "[types]\nhandle = \"string(30)\"\n\n[defaults]\ninherits = [\"Timestampable\"]\n",
+    )
+    .unwrap();
+    let source = "## User\n- id: identifier @pk\n- name: handle\n\n## Timestampable ::interface\n- created_at: timestamp\n";
+    std::fs::write(dir.join("models/user.m3l.md"), source).unwrap();
+    let run = |args: &[&str]| {
+        m3l_bin()
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("failed to run")
+    };
+
+    // stdout is the source as written, the same as what --write keeps
+    let output = run(&["format", "models/user.m3l.md", "--verify"]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, source);
+
+    let output = run(&["format", "models", "--write"]);
+    assert!(output.status.success());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("models/user.m3l.md")).unwrap(),
+        source
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn format_writes_element_namespaces() {
     let output = m3l_bin()
-        .args(["parse", tmp.to_str().unwrap()])
+        .args([
+            "format",
+            "spec/conformance/inputs/multi-namespace.m3l.md",
+            "--verify",
+        ])
+        .current_dir(workspace_root())
         .output()
         .expect("failed to run");
     assert!(
-        !output.status.success(),
-        "Expected failure for empty directory"
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
     );
-    std::fs::remove_dir_all(&tmp).ok();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("# Namespace: Auth\n\n## User\n"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("\n# Namespace: Billing\n\n## Invoice\n"),
+        "stdout: {stdout}"
+    );
+
+    // The project name is not a namespace
+    let dir = std::env::temp_dir().join("m3l-format-project-name-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("m3l.toml"), "name = \"shop\"\n").unwrap();
+    std::fs::write(
+        dir.join("shop.m3l.md"),
+        "## Product\n- id: identifier @pk\n",
+    )
+    .unwrap();
+    let output = m3l_bin()
+        .args(["format", "shop.m3l.md", "--verify"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "## Product\n- id: identifier @pk\n"
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn subdirectory_input_uses_project_config_above() {
+    let dir = std::env::temp_dir().join("m3l-config-above-test");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(dir.join("models/drafts")).unwrap();
+    std::fs::write(
+        dir.join("m3l.toml"),
+        "exclude = [\"models/drafts/**\"]\n\n[types]\nhandle = \"string(30)\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("models/user.m3l.md"),
+        "## User\n- id: identifier @pk\n- name: handle\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("models/drafts/draft.m3l.md"),
+        "## Draft\n- id: identifier @pk\n",
+    )
+    .unwrap();
+
+    // The config's exclude applies to a directory below it
+    let output = m3l_bin()
+        .args(["parse", "models"])
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let models: Vec<&str> = json["models"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(models, ["User"]);
+    assert_eq!(json["sources"], serde_json::json!(["models/user.m3l.md"]));
+
+    // Watching the directory re-runs when the config above it changes
+    std::fs::write(dir.join("m3l.toml"), "").unwrap();
+    let mut child = m3l_bin()
+        .args(["validate", "models", "--watch"])
+        .current_dir(&dir)
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("failed to run");
+    std::thread::sleep(std::time::Duration::from_millis(1000));
+    std::fs::write(dir.join("m3l.toml"), "[types]\nhandle = \"string(30)\"\n").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(1500));
+    child.kill().ok();
+    let output = child.wait_with_output().expect("failed to wait");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("M3L-E009"), "stdout: {stdout}");
+    let rerun = stdout
+        .split_once("Changed:")
+        .unwrap_or_else(|| panic!("expected a re-run, got: {stdout}"))
+        .1;
+    assert!(rerun.contains("0 errors"), "stdout: {stdout}");
+    std::fs::remove_dir_all(&dir).ok();
 }
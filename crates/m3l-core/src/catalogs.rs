@@ -29,6 +29,8 @@ pub static TYPE_CATALOG: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
     s.insert("json");
     s.insert("enum");
     s.insert("map");
+    s.insert("union");
+    s.insert("tuple");
     // Deprecated (§10.4.5) — still accepted
     s.insert("datetime");
     s
@@ -63,6 +65,7 @@ pub static STANDARD_ATTRIBUTES: LazyLock<HashSet<&'static str>> = LazyLock::new(
     s.insert("pattern");
     s.insert("validate");
     s.insert("not_null");
+    s.insert("check");
     // Derived fields
     s.insert("computed");
     s.insert("computed_raw");
@@ -75,6 +78,9 @@ pub static STANDARD_ATTRIBUTES: LazyLock<HashSet<&'static str>> = LazyLock::new(
     s.insert("private");
     s.insert("materialized");
     s.insert("meta");
+    s.insert("natural_key");
+    s.insert("alias");
+    s.insert("deprecated");
     s.insert("behavior");
     s.insert("override");
     s.insert("default_attribute");
@@ -94,3 +100,7 @@ pub static KIND_SECTIONS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
 /// Parser and AST version constants.
 pub const PARSER_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const AST_VERSION: &str = "1.0";
+
+/// Newest M3L language version the parser reads. A file may declare an
+/// older one with a version pragma, which turns off syntax added since.
+pub const SPEC_VERSION: &str = "1.1";
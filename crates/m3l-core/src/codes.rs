@@ -0,0 +1,529 @@
+//! Reference text for every diagnostic code and lint rule, shared by
+//! `m3l explain` and the bindings so each one shows the same explanation.
+//!
+//! Lint rules live in `m3l-lint`, but their text is kept here with the
+//! validator codes: one registry, reachable from every binding through
+//! [`crate::explain_to_json`].
+
+use serde::Serialize;
+
+/// Which pass reports a code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeKind {
+    /// Validation or syntax error (`M3L-E…`, `M3L-P…`).
+    Error,
+    /// Validation or syntax warning (`M3L-W…`, `M3L-P…`).
+    Warning,
+    /// Lint rule (`naming-convention`, …); level set per project.
+    Lint,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CodeDoc {
+    /// `M3L-E005` or a lint rule id.
+    pub code: &'static str,
+    pub kind: CodeKind,
+    /// One line, as in the specification's error catalog.
+    pub title: &'static str,
+    /// What is checked, why it matters and how to fix it.
+    pub description: &'static str,
+    /// M3L that is reported.
+    pub bad: &'static str,
+    /// The same M3L, fixed.
+    pub good: &'static str,
+    /// Only reported by `validate --strict`.
+    pub strict: bool,
+}
+
+/// Every validation code, then every lint rule.
+pub const CODES: &[CodeDoc] = &[
+    CodeDoc {
+        code: "M3L-E001",
+        kind: CodeKind::Error,
+        title: "Rollup FK missing @reference",
+        description: "A rollup aggregates rows of another model through a foreign key: `@rollup(OrderItem.order_id, count)` counts the OrderItem rows whose `order_id` points at this model. That field must declare the relationship with `@reference`, otherwise there is nothing tying the two models together. Add `@reference(<this model>)` to the foreign key field.",
+        bad: "## Order\n- id: identifier @pk\n\n### Rollup\n- item_count: integer @rollup(OrderItem.order_id, count)\n\n## OrderItem\n- id: identifier @pk\n- order_id: identifier",
+        good: "## Order\n- id: identifier @pk\n\n### Rollup\n- item_count: integer @rollup(OrderItem.order_id, count)\n\n## OrderItem\n- id: identifier @pk\n- order_id: identifier @reference(Order)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E002",
+        kind: CodeKind::Error,
+        title: "Lookup FK missing @reference",
+        description: "A lookup follows a foreign key to read a field of the related model: `@lookup(customer_id.name)` reads `name` from the model `customer_id` references. The first hop must be a field with `@reference`, or the lookup has no target. Add `@reference(<Model>)` to the foreign key.",
+        bad: "## Customer\n- id: identifier @pk\n- name: string\n\n## Order\n- id: identifier @pk\n- customer_id: identifier\n\n### Lookup\n- customer_name: string @lookup(customer_id.name)",
+        good: "## Customer\n- id: identifier @pk\n- name: string\n\n## Order\n- id: identifier @pk\n- customer_id: identifier @reference(Customer)\n\n### Lookup\n- customer_name: string @lookup(customer_id.name)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E003",
+        kind: CodeKind::Error,
+        title: "Circular import",
+        description: "Files import each other, directly or through other files, so there is no order in which to load them. The message lists the chain. Move what both files need into a third file that each of them imports.",
+        bad: "<!-- orders.m3l.md -->\n@import \"./customers.m3l.md\"\n\n<!-- customers.m3l.md -->\n@import \"./orders.m3l.md\"",
+        good: "<!-- orders.m3l.md -->\n@import \"./common.m3l.md\"\n\n<!-- customers.m3l.md -->\n@import \"./common.m3l.md\"",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E004",
+        kind: CodeKind::Error,
+        title: "View references a model that does not exist",
+        description: "A view's `from:` or `joins` names a model that is not defined anywhere in the project, usually a typo or a plural. Point the source at a defined model.",
+        bad: "## Product\n- id: identifier @pk\n- active: boolean\n\n## ActiveProducts ::view\n\n### Source\n- from: Products\n- where: \"active = true\"",
+        good: "## Product\n- id: identifier @pk\n- active: boolean\n\n## ActiveProducts ::view\n\n### Source\n- from: Product\n- where: \"active = true\"",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E005",
+        kind: CodeKind::Error,
+        title: "Duplicate name",
+        description: "Two models, enums, interfaces or views share a name (or a model declares the same field twice), so references to it are ambiguous. The message points at the first definition. Rename or remove one of them; to add fields to an existing model, use inheritance instead of redefining it.",
+        bad: "## Customer\n- id: identifier @pk\n- name: string\n\n## Customer\n- id: identifier @pk\n- bio: text",
+        good: "## Customer\n- id: identifier @pk\n- name: string\n\n## CustomerProfile\n- id: identifier @pk\n- bio: text",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E006",
+        kind: CodeKind::Error,
+        title: "Duplicate field",
+        description: "A field name appears twice in one model, including fields the model inherits. Column names must be unique; rename or remove one of them.",
+        bad: "## Customer\n- id: identifier @pk\n- email: email\n- email: email?",
+        good: "## Customer\n- id: identifier @pk\n- email: email\n- backup_email: email?",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E007",
+        kind: CodeKind::Error,
+        title: "Unresolved parent in inheritance",
+        description: "`## Child : Parent` names a parent that is not defined, so its fields cannot be inherited. Define the parent (usually an `::interface`) or fix the name.",
+        bad: "## Customer : Timestamped\n- id: identifier @pk",
+        good: "## Timestamped ::interface\n- created_at: timestamp = now()\n\n## Customer : Timestamped\n- id: identifier @pk",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E008",
+        kind: CodeKind::Error,
+        title: "Ambiguous model reference",
+        description: "A short name used as a parent, field type or reference target is defined in more than one namespace, none of them the referring element's own, so it could mean either. Qualify it with its namespace (`auth.User`).",
+        bad: "# Namespace: auth\n\n## User\n- id: identifier @pk\n\n# Namespace: billing\n\n## User\n- id: identifier @pk\n\n# Namespace: shop\n\n## Order\n- id: identifier @pk\n- buyer: User",
+        good: "# Namespace: auth\n\n## User\n- id: identifier @pk\n\n# Namespace: billing\n\n## User\n- id: identifier @pk\n\n# Namespace: shop\n\n## Order\n- id: identifier @pk\n- buyer: auth.User",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E009",
+        kind: CodeKind::Error,
+        title: "Undefined type",
+        description: "A field's type is neither a built-in type (`string`, `integer`, `timestamp`, …) nor a model, enum or interface defined in the project. Fix the spelling, or define the enum or model it refers to.",
+        bad: "## Order\n- id: identifier @pk\n- status: OrderStatus",
+        good: "## OrderStatus ::enum\n- pending\n- shipped\n\n## Order\n- id: identifier @pk\n- status: OrderStatus",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E010",
+        kind: CodeKind::Error,
+        title: "Relations entry without a matching @reference",
+        description: "A `### Relations` entry describes a relationship through a foreign key that does not declare it with `@reference`. Add `@reference(<Model>)` to the field named after `via`.",
+        bad: "## Category\n- id: identifier @pk\n\n## Product\n- id: identifier @pk\n- category_id: identifier\n\n### Relations\n- category: >Category via category_id",
+        good: "## Category\n- id: identifier @pk\n\n## Product\n- id: identifier @pk\n- category_id: identifier @reference(Category)\n\n### Relations\n- category: >Category via category_id",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E011",
+        kind: CodeKind::Error,
+        title: "Unresolved @reference target",
+        description: "`@reference`/`@fk` or a Relations `target` names a model (or `Model.field`) that does not exist. A namespace-qualified target (`auth.User`) must also match the namespace the model is declared in. Fix the name or define the model.",
+        bad: "## User\n- id: identifier @pk\n\n## Post\n- id: identifier @pk\n- author_id: identifier @reference(Author)",
+        good: "## User\n- id: identifier @pk\n\n## Post\n- id: identifier @pk\n- author_id: identifier @reference(User)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E012",
+        kind: CodeKind::Error,
+        title: "Key references an undefined field",
+        description: "A `@natural_key(...)` or `### Keys` entry lists no fields, or names a field the model does not define. List the model's own or inherited fields.",
+        bad: "## User\n- tenant_id: identifier\n- email: email\n- @natural_key(tenant_id, mail)",
+        good: "## User\n- tenant_id: identifier\n- email: email\n- @natural_key(tenant_id, email)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E013",
+        kind: CodeKind::Error,
+        title: "Imported file not found",
+        description: "An `@import` names a file that is not part of the schema. Relative paths (`./`, `../`) start at the importing file's directory; package paths start at the project root. Fix the path, or add the file to the project.",
+        bad: "<!-- orders.m3l.md -->\n@import \"./comon.m3l.md\"",
+        good: "<!-- orders.m3l.md -->\n@import \"./common.m3l.md\"",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E014",
+        kind: CodeKind::Error,
+        title: "Selectively imported name not found",
+        description: "A selective import lists a name that the imported file does not define as a model, interface, view or enum. Fix the spelling, or import it from the file that defines it.",
+        bad: "<!-- base.m3l.md defines BaseModel -->\n@import \"./base.m3l.md\" (BaseModel, Timestampable)",
+        good: "<!-- base.m3l.md defines BaseModel -->\n@import \"./base.m3l.md\" (BaseModel)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E015",
+        kind: CodeKind::Error,
+        title: "Name not imported",
+        description: "A file imports another with a list of names, then uses one of that file's other elements as a parent, field type, reference target or view source. Only the listed names are in scope; add the name to the list, or import the whole file.",
+        bad: "@import \"./base.m3l.md\" (BaseModel)\n\n## Order : BaseModel\n- customer_id: identifier @reference(Customer)",
+        good: "@import \"./base.m3l.md\" (BaseModel, Customer)\n\n## Order : BaseModel\n- customer_id: identifier @reference(Customer)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E016",
+        kind: CodeKind::Error,
+        title: "Malformed check expression",
+        description: "`@check(...)` takes a boolean expression that generators copy into a CHECK constraint. The expression is empty, or a parenthesis, bracket or string literal in it is not closed, so the generated SQL would not parse. Balance the expression; wrap it in backticks when it contains commas.",
+        bad: "## Product\n- id: identifier @pk\n- price: decimal @check(`(price > 0`)",
+        good: "## Product\n- id: identifier @pk\n- price: decimal @check(`price > 0`)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E017",
+        kind: CodeKind::Error,
+        title: "Seed row does not match the model",
+        description: "A row in a `### Seed` section sets a field the model does not have, sets a computed, lookup or rollup field (those are derived, not stored), sets `null` on a field that is not nullable, or gives a numeric, boolean or enum field a value of the wrong kind. Fix the column name or the value so the fixture loads.",
+        bad: "## User\n- id: integer @pk\n- role: string\n\n### Seed\n| id | role | email |\n|----|------|-------|\n| 1 | admin | admin@example.com |",
+        good: "## User\n- id: integer @pk\n- role: string\n- email: email\n\n### Seed\n| id | role | email |\n|----|------|-------|\n| 1 | admin | admin@example.com |",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E018",
+        kind: CodeKind::Error,
+        title: "Endpoint path parameter is not a field",
+        description: "A `{name}` segment in the path of an `### Endpoints` item must name a field of the model (inherited fields count), so the parameter can be typed and bound. Usually a typo or a name from another model; rename the parameter after the field it selects.",
+        bad: "## User\n- id: identifier @pk\n\n### Endpoints\n- GET /users/{user_id}",
+        good: "## User\n- id: identifier @pk\n\n### Endpoints\n- GET /users/{id}",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E019",
+        kind: CodeKind::Error,
+        title: "Generic model used with the wrong number of type arguments",
+        description: "A field typed with a generic model (`## Paged<T>`) must give one type argument per parameter, as in `Paged<Product>`, so the resolver can instantiate it. Giving type arguments to a model that declares no parameters is reported too. Add or remove arguments to match the definition.",
+        bad: "## Product\n- id: identifier @pk\n\n## Paged<T>\n- items: T[]\n- total: integer\n\n## Catalog\n- id: identifier @pk\n- products: Paged",
+        good: "## Product\n- id: identifier @pk\n\n## Paged<T>\n- items: T[]\n- total: integer\n\n## Catalog\n- id: identifier @pk\n- products: Paged<Product>",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E020",
+        kind: CodeKind::Error,
+        title: "JSON default value does not fit the field",
+        description: "An array or object default (`= [\"a\", \"b\"]`, `= {\"x\": 1}`) must be valid JSON and match the field: arrays only for array fields, with items of the item type; objects only for `object`, `json` and `map` fields, with keys naming the nested fields of an `object` and values of the `map` value type. Fix the literal or the field type.",
+        bad: "## Settings\n- id: identifier @pk\n- retries: integer[] = [1, \"two\"]",
+        good: "## Settings\n- id: identifier @pk\n- retries: integer[] = [1, 2]",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E021",
+        kind: CodeKind::Error,
+        title: "Extend block targets an undefined model",
+        description: "An `## extend Name` block adds fields, indexes and relations to a model defined elsewhere, and no model (or model alias) of that name is loaded. Fix the name, or load the file that defines the model.",
+        bad: "## Customer\n- id: identifier @pk\n\n## extend Cutsomer\n- nickname: string",
+        good: "## Customer\n- id: identifier @pk\n\n## extend Customer\n- nickname: string",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E022",
+        kind: CodeKind::Error,
+        title: "Invalid view refresh strategy or interval",
+        description: "A view's `### Refresh` section names its strategy, one of `full`, `incremental`, `scheduled` or `manual`, and optionally an `interval`: a duration such as `5m` or `6 hours`, `daily 02:00`, or a five-field cron expression such as `0 3 * * *`. Generators schedule the refresh from the parsed value, so anything else is rejected. Fix the spelling, or rewrite the interval in one of these forms.",
+        bad: "## Sales ::view @materialized\n### Source\n- from: Order\n\n### Refresh\n- strategy: nightly\n- interval: \"every so often\"\n\n- total: decimal(12, 2) @from(Order.total)\n\n## Order\n- id: identifier @pk\n- total: decimal(12, 2)",
+        good: "## Sales ::view @materialized\n### Source\n- from: Order\n\n### Refresh\n- strategy: scheduled\n- interval: \"0 3 * * *\"\n\n- total: decimal(12, 2) @from(Order.total)\n\n## Order\n- id: identifier @pk\n- total: decimal(12, 2)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E023",
+        kind: CodeKind::Error,
+        title: "Field does not fit its interface",
+        description: "An interface is a contract: code written against it relies on the types of its fields. A model may declare an interface field again with the same type, or narrow it under `@override`: a non-null field for a nullable one, a shorter length, a subtype such as `email` for `string`, or one member of a union. Another type, a nullable field for a non-null one, a longer length or a list for a single value breaks the contract, and so does narrowing without `@override`.",
+        bad: "## Contact ::interface\n- email: string\n- phone: string?\n\n## Customer : Contact\n- id: identifier @pk\n- email: integer\n- phone: string",
+        good: "## Contact ::interface\n- email: string\n- phone: string?\n\n## Customer : Contact\n- id: identifier @pk\n- email: email @override\n- phone: string @override",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-E024",
+        kind: CodeKind::Error,
+        title: "Unknown referential action",
+        description: "`@on_delete` and `@on_update` on a reference field, and the `on_delete:`/`on_update:` items of the extended format, take one of `cascade`, `set_null`, `no_action` or `restrict`. Anything else is ignored: the field keeps its default delete action and gets no update action.",
+        bad: "## User\n- id: identifier @pk\n\n## Order\n- id: identifier @pk\n- user_id: identifier @reference(User) @on_delete(remove)",
+        good: "## User\n- id: identifier @pk\n\n## Order\n- id: identifier @pk\n- user_id: identifier @reference(User) @on_delete(restrict)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-W001",
+        kind: CodeKind::Warning,
+        title: "Field line longer than 80 characters",
+        description: "Strict mode keeps field definitions short enough to read in a diff or a narrow editor. The length counts the name, type, default, attributes and description together. Shorten the description to the essentials, and keep the longer story in the model's own description.",
+        bad: "## Customer\n- id: identifier @pk\n- email: email @unique \"Primary address used for sign-in, receipts and every account notification\"",
+        good: "## Customer\n- id: identifier @pk\n- email: email @unique \"Sign-in and notification address\"",
+        strict: true,
+    },
+    CodeDoc {
+        code: "M3L-W002",
+        kind: CodeKind::Warning,
+        title: "Object nesting deeper than 3 levels",
+        description: "Strict mode: deeply nested `object` fields are hard to read in Markdown and usually hide an entity of their own. Extract the inner structure into a separate model.",
+        bad: "## Customer\n- id: identifier @pk\n- profile: object\n  - address: object\n    - geo: object\n      - lat: float",
+        good: "## Location\n- lat: float\n\n## Customer\n- id: identifier @pk\n- profile: object\n  - address: object\n    - geo: Location",
+        strict: true,
+    },
+    CodeDoc {
+        code: "M3L-W003",
+        kind: CodeKind::Warning,
+        title: "Deprecated syntax",
+        description: "The `datetime` type is read as `timestamp`, and the cascade attributes (`@cascade`, `@no_action`, `@set_null`, `@restrict`) are superseded by `@reference(Model)!`/`?`/`!!` markers. Use the current form.",
+        bad: "## Event\n- id: identifier @pk\n- starts_at: datetime",
+        good: "## Event\n- id: identifier @pk\n- starts_at: timestamp",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-W004",
+        kind: CodeKind::Warning,
+        title: "Lookup chain longer than 3 hops",
+        description: "Strict mode: a `@lookup` path that crosses more than three relations is fragile and expensive to query. Look up an intermediate value instead, or add a lookup on the nearer model and read that.",
+        bad: "## Customer\n- id: identifier @pk\n- region_name: string\n\n## Order\n- id: identifier @pk\n- customer_id: identifier @reference(Customer)\n\n### Lookup\n- region_name: string @lookup(customer_id.address_id.city_id.region_id.name)",
+        good: "## Customer\n- id: identifier @pk\n- region_name: string\n\n## Order\n- id: identifier @pk\n- customer_id: identifier @reference(Customer)\n\n### Lookup\n- region_name: string @lookup(customer_id.region_name)",
+        strict: true,
+    },
+    CodeDoc {
+        code: "M3L-W005",
+        kind: CodeKind::Warning,
+        title: "Attribute argument has the wrong type",
+        description: "An attribute registered with `::attribute` declares a `type`, and a use passes an argument of another type. Pass a value of the registered type.",
+        bad: "## rating ::attribute\n- target: [field]\n- type: number\n\n## Product\n- id: identifier @pk\n- score: integer @rating(high)",
+        good: "## rating ::attribute\n- target: [field]\n- type: number\n\n## Product\n- id: identifier @pk\n- score: integer @rating(5)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-W006",
+        kind: CodeKind::Warning,
+        title: "Attribute argument outside its range",
+        description: "An attribute registered with `::attribute` declares a `range`, and a use passes a number outside it. Pass a value within the registered range, or widen the range.",
+        bad: "## priority ::attribute\n- target: [field]\n- type: number\n- range: [1, 10]\n\n## Task\n- id: identifier @pk\n- level: integer @priority(99)",
+        good: "## priority ::attribute\n- target: [field]\n- type: number\n- range: [1, 10]\n\n## Task\n- id: identifier @pk\n- level: integer @priority(9)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-W007",
+        kind: CodeKind::Warning,
+        title: "Use of a deprecated element",
+        description: "A model, interface or enum deprecated by an `@deprecated` description line or attribute is used as a field type, an `@reference`/`@fk` target or a parent, or a deprecated field is used as a reference target. The warning repeats the deprecation message and version, which usually name the replacement; move to it. Deprecated elements may use each other without warnings.",
+        bad: "## OrderState ::enum\n> @deprecated Use Status\n- open \"Open\"\n\n## Order\n- id: identifier @pk\n- state: OrderState",
+        good: "## Status ::enum\n- open \"Open\"\n\n## Order\n- id: identifier @pk\n- state: Status",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-W008",
+        kind: CodeKind::Warning,
+        title: "Attribute argument not an allowed value",
+        description: "An attribute registered with `::attribute` as `type: enum` lists its allowed arguments in `values`, and a use passes something else. Pass one of the listed values, or add it to `values`.",
+        bad: "## tier ::attribute\n- target: [field]\n- type: enum\n- values: [gold, silver, bronze]\n\n## Customer\n- id: identifier @pk\n- level: string @tier(platinum)",
+        good: "## tier ::attribute\n- target: [field]\n- type: enum\n- values: [gold, silver, bronze]\n\n## Customer\n- id: identifier @pk\n- level: string @tier(gold)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-W009",
+        kind: CodeKind::Warning,
+        title: "Attribute argument does not match its pattern",
+        description: "An attribute registered with `::attribute` declares a `pattern` regex, and a use passes a string that does not match it. Pass a matching value, or loosen the pattern. A pattern that is not a valid regex is not checked.",
+        bad: "## currency ::attribute\n- target: [field]\n- type: string\n- pattern: \"^[A-Z]{3}$\"\n\n## Price\n- id: identifier @pk\n- amount: decimal @currency(usd)",
+        good: "## currency ::attribute\n- target: [field]\n- type: string\n- pattern: \"^[A-Z]{3}$\"\n\n## Price\n- id: identifier @pk\n- amount: decimal @currency(USD)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-W010",
+        kind: CodeKind::Warning,
+        title: "Behavior on an unknown event",
+        description: "Strict mode: a behavior is named by a timing (`before_`, `after_` or `on_`) and a record lifecycle event (`create`, `update`, `delete` or `save`), which generators turn into database triggers or ORM hooks. A behavior without a timing, or on a custom event such as `after_publish`, is left to the application. Rename it to a lifecycle event, or drop strict mode for models with custom events.",
+        bad: "## Post\n- id: identifier @pk\n- updated_at: timestamp\n\n### Behaviors\n- before_edit: set updated_at = now()",
+        good: "## Post\n- id: identifier @pk\n- updated_at: timestamp\n\n### Behaviors\n- before_update: set updated_at = now()",
+        strict: true,
+    },
+    CodeDoc {
+        code: "M3L-P001",
+        kind: CodeKind::Error,
+        title: "Unclosed quote, backtick or bracket",
+        description: "A field or header opens a quote, backtick, parenthesis or bracket and never closes it. The parser would swallow the rest of the line into the open span, so the type, attributes or description after it are lost. Close it on the same line.",
+        bad: "## Customer\n- id: identifier @pk\n- name: string(100 @required",
+        good: "## Customer\n- id: identifier @pk\n- name: string(100) @required",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-P002",
+        kind: CodeKind::Warning,
+        title: "Unknown type indicator",
+        description: "A `::` type indicator is not a word, or differs from a built-in one (`enum`, `interface`, `view`, `flow`, `attribute`) only by case or a plural `s`. Any other word declares a custom extension element, so a misspelt `::Enum` silently becomes an extension instead of an enum. Use the built-in indicator as written in the specification.",
+        bad: "## Status ::Enum\n- active \"Active\"",
+        good: "## Status ::enum\n- active \"Active\"",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-P003",
+        kind: CodeKind::Error,
+        title: "Unparsable list item",
+        description: "A list item of a model, interface or view does not read as `- name: type`, an enum item as `- NAME \"description\"`, or an `### Endpoints` item as `- METHOD /path`. The parser would keep the whole line as a field name, or drop the endpoint. Use a single word for the name and put the type after a colon; start endpoints with an HTTP method and a path beginning with `/`.",
+        bad: "## Customer\n- id: identifier @pk\n- phone number: phone",
+        good: "## Customer\n- id: identifier @pk\n- phone_number: phone",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-P004",
+        kind: CodeKind::Error,
+        title: "Unsupported M3L version",
+        description: "A version pragma (`<!-- m3l: 1.1 -->` or `# M3L-Version: 1.1`) is not a `MAJOR.MINOR` version, or names a version newer than the parser reads. The file is then read with the newest syntax the parser knows, which may not be what it was written for. Upgrade the tools, or declare a version they read.",
+        bad: "<!-- m3l: 9.0 -->\n## Customer\n- id: identifier @pk",
+        good: "<!-- m3l: 1.1 -->\n## Customer\n- id: identifier @pk",
+        strict: false,
+    },
+    CodeDoc {
+        code: "M3L-P005",
+        kind: CodeKind::Error,
+        title: "Syntax newer than the declared M3L version",
+        description: "A file that declares an M3L version with a pragma uses syntax added in a later version, such as `## extend` blocks (M3L 1.1). The parser reads the file as that version would and skips the block, so what it declares is missing from the schema, and `m3l format` leaves the file alone. Raise the declared version, or rewrite the block with the older syntax.",
+        bad: "<!-- m3l: 1.0 -->\n## User\n- id: identifier @pk\n\n## extend User\n- nickname: string",
+        good: "<!-- m3l: 1.1 -->\n## User\n- id: identifier @pk\n\n## extend User\n- nickname: string",
+        strict: false,
+    },
+    CodeDoc {
+        code: "naming-convention",
+        kind: CodeKind::Lint,
+        title: "Model names PascalCase, field names snake_case",
+        description: "Consistent casing keeps generated code predictable: models become classes and tables, fields become properties and columns. The fix renames the element and every reference to it.",
+        bad: "## order_item\n- id: identifier @pk\n- UnitPrice: decimal(10,2)",
+        good: "## OrderItem\n- id: identifier @pk\n- unit_price: decimal(10,2)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "similar-fields",
+        kind: CodeKind::Lint,
+        title: "Fields with very similar names",
+        description: "Two fields of one model whose names differ only by underscores or a common suffix (`user_name` and `username`, `created_at` and `created`) usually store the same thing twice. Remove one, or give them names that say how they differ.",
+        bad: "## Customer\n- id: identifier @pk\n- user_name: string\n- username: string",
+        good: "## Customer\n- id: identifier @pk\n- username: string",
+        strict: false,
+    },
+    CodeDoc {
+        code: "model-size",
+        kind: CodeKind::Lint,
+        title: "Model with too many fields",
+        description: "A model with more than 20 fields usually mixes several concerns. Split groups of related fields into their own models, or share them through interfaces.",
+        bad: "## Customer\n- id: identifier @pk\n- f02: string\n- f03: string\n- f04: string\n- f05: string\n- f06: string\n- f07: string\n- f08: string\n- f09: string\n- f10: string\n- f11: string\n- f12: string\n- f13: string\n- f14: string\n- f15: string\n- f16: string\n- f17: string\n- f18: string\n- f19: string\n- f20: string\n- f21: string",
+        good: "## Customer\n- id: identifier @pk\n- name: string\n- email: email\n\n## CustomerPreferences\n- customer_id: identifier @pk @reference(Customer)\n- locale: string",
+        strict: false,
+    },
+    CodeDoc {
+        code: "relation-complexity",
+        kind: CodeKind::Lint,
+        title: "Model with too many outgoing references",
+        description: "A model referencing more than 5 others is coupled to much of the schema and hard to change. Check whether some references belong on a related model.",
+        bad: "## Order\n- id: identifier @pk\n- customer_id: identifier @reference(Customer)\n- store_id: identifier @reference(Store)\n- clerk_id: identifier @reference(Employee)\n- coupon_id: identifier @reference(Coupon)\n- carrier_id: identifier @reference(Carrier)\n- warehouse_id: identifier @reference(Warehouse)",
+        good: "## Order\n- id: identifier @pk\n- customer_id: identifier @reference(Customer)\n- store_id: identifier @reference(Store)\n- coupon_id: identifier @reference(Coupon)\n\n## Shipment\n- id: identifier @pk\n- order_id: identifier @reference(Order)\n- carrier_id: identifier @reference(Carrier)\n- warehouse_id: identifier @reference(Warehouse)",
+        strict: false,
+    },
+    CodeDoc {
+        code: "inheritance-depth",
+        kind: CodeKind::Lint,
+        title: "Inheritance chain deeper than 3 levels",
+        description: "Each level of inheritance is another place to look for a field. Flatten the chain, or combine small interfaces by listing several parents on one model.",
+        bad: "## Base ::interface\n- id: identifier @pk\n\n## Timestamped : Base\n- created_at: timestamp\n\n## Audited : Timestamped\n- created_by: string\n\n## Versioned : Audited\n- version: integer\n\n## Document : Versioned\n- title: string",
+        good: "## Base ::interface\n- id: identifier @pk\n\n## Timestamped ::interface\n- created_at: timestamp\n\n## Audited ::interface\n- created_by: string\n\n## Document : Base, Timestamped, Audited\n- version: integer\n- title: string",
+        strict: false,
+    },
+    CodeDoc {
+        code: "view-fields",
+        kind: CodeKind::Lint,
+        title: "View with a source but no fields",
+        description: "A view without fields leaves its output shape to whatever the source returns, so consumers cannot rely on it. List the fields the view exposes.",
+        bad: "## Product\n- id: identifier @pk\n- name: string\n- active: boolean\n\n## ActiveProducts ::view\n\n### Source\n- from: Product\n- where: \"active = true\"",
+        good: "## Product\n- id: identifier @pk\n- name: string\n- active: boolean\n\n## ActiveProducts ::view\n- id: identifier @from(Product.id)\n- name: string @from(Product.name)\n\n### Source\n- from: Product\n- where: \"active = true\"",
+        strict: false,
+    },
+    CodeDoc {
+        code: "description-quality",
+        kind: CodeKind::Lint,
+        title: "Description that adds nothing",
+        description: "A description that only repeats the name, is shorter than 10 characters, or is pasted onto more than 3 fields tells the reader nothing new. Say what the value means, where it comes from or how it is used.",
+        bad: "## Customer\n- id: identifier @pk\n- email: email \"Email\"",
+        good: "## Customer\n- id: identifier @pk\n- email: email \"Sign-in address; receipts are sent here\"",
+        strict: false,
+    },
+    CodeDoc {
+        code: "enum-value-naming",
+        kind: CodeKind::Lint,
+        title: "Enum values in mixed or unexpected casing",
+        description: "Enum values become constants in generated code, so one casing convention (snake_case by default) keeps them predictable. The fix renames each value to the expected casing.",
+        bad: "## OrderStatus ::enum\n- pending\n- OnHold\n- SHIPPED",
+        good: "## OrderStatus ::enum\n- pending\n- on_hold\n- shipped",
+        strict: false,
+    },
+];
+
+/// Look up a validation code or lint rule id, ignoring case. The `M3L-`
+/// prefix is optional: `e005` finds `M3L-E005`.
+pub fn explain(code: &str) -> Option<&'static CodeDoc> {
+    let code = code.trim();
+    CODES.iter().find(|doc| {
+        doc.code.eq_ignore_ascii_case(code)
+            || doc
+                .code
+                .strip_prefix("M3L-")
+                .is_some_and(|short| short.eq_ignore_ascii_case(code))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_string, resolve, validate, ValidateOptions};
+
+    fn codes_reported(source: &str, strict: bool) -> Vec<String> {
+        let ast = resolve(&[parse_string(source, "example.m3l.md")], None);
+        let result = validate(&ast, &ValidateOptions { strict });
+        result
+            .errors
+            .iter()
+            .chain(result.warnings.iter())
+            .map(|d| d.code.clone())
+            .collect()
+    }
+
+    #[test]
+    fn lookup() {
+        assert_eq!(explain("M3L-E005").unwrap().code, "M3L-E005");
+        assert_eq!(explain("e005").unwrap().code, "M3L-E005");
+        assert_eq!(explain("Naming-Convention").unwrap().kind, CodeKind::Lint);
+        assert!(explain("M3L-E999").is_none());
+    }
+
+    #[test]
+    fn validation_examples_match_their_code() {
+        // The import codes (E003, E013-E015) need several files, and the
+        // parser keeps only two levels of nested fields, so W002 cannot be
+        // reproduced from one snippet yet
+        for doc in CODES.iter().filter(|d| {
+            d.kind != CodeKind::Lint
+                && !matches!(
+                    d.code,
+                    "M3L-E003" | "M3L-E013" | "M3L-E014" | "M3L-E015" | "M3L-W002"
+                )
+        }) {
+            let bad = codes_reported(doc.bad, doc.strict);
+            assert!(
+                bad.iter().any(|c| c == doc.code),
+                "{}: bad example reports {bad:?}",
+                doc.code
+            );
+            let good = codes_reported(doc.good, doc.strict);
+            assert!(
+                good.is_empty(),
+                "{}: good example reports {good:?}",
+                doc.code
+            );
+        }
+    }
+}
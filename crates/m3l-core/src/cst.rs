@@ -0,0 +1,393 @@
+//! Lossless concrete syntax tree. The AST keeps what a schema means and
+//! drops how it was written; the CST keeps every line of the file, in order,
+//! classified like the lexer sees it, so tools that rewrite a file (the
+//! formatter, fixers) can leave comments, blank lines and layout alone.
+//! `Cst::to_source` reproduces the input byte for byte.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::lexer::lex;
+use crate::types::{Token, TokenType};
+
+/// A file as a sequence of top-level lines and elements.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cst {
+    pub file: String,
+    pub nodes: Vec<CstNode>,
+}
+
+/// A line outside any element (title, namespace, import, comment, blank
+/// line), or an element with its lines.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum CstNode {
+    Line(CstLine),
+    Element(CstElement),
+}
+
+/// A model, enum, interface, view, flow, extension or attribute definition:
+/// its header and every line up to its last content line, including the
+/// comments and blank lines between them. Trivia after the last content
+/// line belongs to the enclosing file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CstElement {
+    pub name: String,
+    pub lines: Vec<CstLine>,
+}
+
+/// One source line.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CstLine {
+    pub kind: CstLineKind,
+    pub line: usize,
+    /// The line as written, without its line ending.
+    pub text: String,
+    /// Whether the line ends with `\r\n` rather than `\n`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub crlf: bool,
+    /// Inline `# ...` comment of a field or nested item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CstLineKind {
+    /// `# Heading` that is not a namespace.
+    Title,
+    Namespace,
+    /// `# M3L-Version: 1.1` pragma; the `<!-- m3l: 1.1 -->` form is a
+    /// comment line.
+    Version,
+    Import,
+    /// `## Name` header of an element.
+    Header,
+    /// `### Section` header.
+    Section,
+    Field,
+    NestedItem,
+    Blockquote,
+    /// A code fence or a line inside one.
+    Code,
+    /// A line of an HTML comment (`<!-- ... -->`).
+    Comment,
+    Blank,
+    HorizontalRule,
+    Text,
+    /// A line of the leading `---` YAML block, fences included.
+    Frontmatter,
+}
+
+impl CstLineKind {
+    /// Lines that never extend an element on their own.
+    fn is_trivia(self) -> bool {
+        matches!(
+            self,
+            CstLineKind::Blank | CstLineKind::Comment | CstLineKind::HorizontalRule
+        )
+    }
+}
+
+impl Cst {
+    /// The source text the tree was parsed from.
+    pub fn to_source(&self) -> String {
+        let lines: Vec<&CstLine> = self.lines().collect();
+        let mut out = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            out.push_str(&line.text);
+            if i + 1 < lines.len() {
+                out.push_str(if line.crlf { "\r\n" } else { "\n" });
+            }
+        }
+        out
+    }
+
+    /// Every line in source order.
+    pub fn lines(&self) -> impl Iterator<Item = &CstLine> {
+        self.nodes.iter().flat_map(|node| match node {
+            CstNode::Line(line) => std::slice::from_ref(line),
+            CstNode::Element(element) => element.lines.as_slice(),
+        })
+    }
+
+    pub fn elements(&self) -> impl Iterator<Item = &CstElement> {
+        self.nodes.iter().filter_map(|node| match node {
+            CstNode::Element(element) => Some(element),
+            CstNode::Line(_) => None,
+        })
+    }
+
+    /// The first element named `name`.
+    pub fn element(&self, name: &str) -> Option<&CstElement> {
+        self.elements().find(|e| e.name == name)
+    }
+}
+
+/// Parse `content` into a lossless CST.
+pub fn parse_cst(content: &str, file: &str) -> Cst {
+    let tokens = lex(content, file);
+    let by_line: HashMap<usize, &Token> = tokens.iter().map(|t| (t.line, t)).collect();
+
+    let mut nodes = Vec::new();
+    let mut element: Option<CstElement> = None;
+    // Trivia seen in an element since its last content line
+    let mut pending: Vec<CstLine> = Vec::new();
+    let mut in_fence = false;
+    let mut in_comment = false;
+    let mut frontmatter_end = 0;
+
+    for (i, raw_line) in content.split('\n').enumerate() {
+        let (text, crlf) = match raw_line.strip_suffix('\r') {
+            Some(text) => (text, true),
+            None => (raw_line, false),
+        };
+        let token = by_line.get(&(i + 1)).copied();
+        if let Some(t) = token.filter(|t| t.token_type == TokenType::Frontmatter) {
+            frontmatter_end = t.data.folded_end.map_or(t.line, |(line, _)| line);
+        }
+        let kind = if i < frontmatter_end {
+            CstLineKind::Frontmatter
+        } else {
+            classify(text, token, &mut in_fence, &mut in_comment)
+        };
+        let line = CstLine {
+            kind,
+            line: i + 1,
+            text: text.to_string(),
+            crlf,
+            comment: token.and_then(|t| t.data.comment.clone()),
+        };
+
+        let starts_element = token.is_some_and(|t| {
+            matches!(
+                t.token_type,
+                TokenType::Model
+                    | TokenType::Enum
+                    | TokenType::Interface
+                    | TokenType::View
+                    | TokenType::Flow
+                    | TokenType::Extension(_)
+                    | TokenType::AttributeDef
+            )
+        });
+        let closes_element = matches!(kind, CstLineKind::Namespace | CstLineKind::Title);
+        if starts_element || closes_element {
+            close(&mut element, &mut pending, &mut nodes);
+        }
+        if starts_element {
+            let name = token.and_then(|t| t.data.name.clone()).unwrap_or_default();
+            element = Some(CstElement {
+                name,
+                lines: vec![line],
+            });
+            continue;
+        }
+        match element.as_mut() {
+            Some(_) if kind.is_trivia() || kind == CstLineKind::Import => pending.push(line),
+            Some(element) => {
+                element.lines.append(&mut pending);
+                element.lines.push(line);
+            }
+            None => nodes.push(CstNode::Line(line)),
+        }
+    }
+    close(&mut element, &mut pending, &mut nodes);
+
+    Cst {
+        file: file.to_string(),
+        nodes,
+    }
+}
+
+fn close(element: &mut Option<CstElement>, pending: &mut Vec<CstLine>, nodes: &mut Vec<CstNode>) {
+    if let Some(element) = element.take() {
+        nodes.push(CstNode::Element(element));
+    }
+    nodes.extend(pending.drain(..).map(CstNode::Line));
+}
+
+/// Kind of a line, from the lexer's token for it where there is one. Code
+/// blocks, indented blockquotes and titles are folded or skipped by the
+/// lexer, and HTML comments are plain text to it.
+fn classify(
+    text: &str,
+    token: Option<&Token>,
+    in_fence: &mut bool,
+    in_comment: &mut bool,
+) -> CstLineKind {
+    let trimmed = text.trim_start();
+    if trimmed.starts_with("```") {
+        *in_fence = !*in_fence;
+        return CstLineKind::Code;
+    }
+    if *in_fence {
+        return CstLineKind::Code;
+    }
+    if *in_comment || trimmed.starts_with("<!--") {
+        *in_comment = !text.contains("-->");
+        return CstLineKind::Comment;
+    }
+
+    let Some(token) = token else {
+        return if trimmed.starts_with('>') {
+            CstLineKind::Blockquote
+        } else if trimmed.starts_with('#') {
+            CstLineKind::Title
+        } else if trimmed.is_empty() {
+            CstLineKind::Blank
+        } else {
+            CstLineKind::Text
+        };
+    };
+    match token.token_type {
+        TokenType::Namespace => CstLineKind::Namespace,
+        TokenType::Model
+        | TokenType::Enum
+        | TokenType::Interface
+        | TokenType::View
+        | TokenType::Flow
+        | TokenType::Extension(_)
+        | TokenType::AttributeDef => CstLineKind::Header,
+        TokenType::Section => CstLineKind::Section,
+        TokenType::Field => CstLineKind::Field,
+        TokenType::NestedItem => CstLineKind::NestedItem,
+        TokenType::Blockquote => CstLineKind::Blockquote,
+        TokenType::HorizontalRule => CstLineKind::HorizontalRule,
+        TokenType::Blank => CstLineKind::Blank,
+        TokenType::Text if token.data.is_import => CstLineKind::Import,
+        TokenType::Text => CstLineKind::Text,
+        TokenType::Frontmatter => CstLineKind::Frontmatter,
+        TokenType::Version => CstLineKind::Version,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "# Shop schema\n# Namespace: shop\n\n@import \"./base.m3l.md\"\n\n<!-- Orders\n     and their lines -->\n## Order : BaseModel\n> An order.\n\n- total: decimal(10,2)  # cents are rounded\n<!-- TODO: currency -->\n- status: string\n  > Workflow state\n\n### Indexes\n- by_status\n  - fields: [status]\n\n---\n\n## Report ::view\n### Source\n```sql\nSELECT 1\n```\n";
+
+    fn kinds(element: &CstElement) -> Vec<CstLineKind> {
+        element.lines.iter().map(|l| l.kind).collect()
+    }
+
+    #[test]
+    fn round_trips_exactly() {
+        for input in [
+            INPUT,
+            "",
+            "\n\n",
+            "## A\r\n- id: identifier\r\n",
+            "## A\n- id: identifier",
+            "---\r\nowner: me\r\n---\r\n## A\r\n",
+        ] {
+            assert_eq!(parse_cst(input, "test.m3l.md").to_source(), input);
+        }
+    }
+
+    #[test]
+    fn round_trips_samples() {
+        fn visit(dir: &std::path::Path, count: &mut usize) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    visit(&path, count);
+                } else if path.to_string_lossy().ends_with(".m3l.md") {
+                    let content = std::fs::read_to_string(&path).unwrap();
+                    let cst = parse_cst(&content, &path.to_string_lossy());
+                    assert_eq!(cst.to_source(), content, "{}", path.display());
+                    *count += 1;
+                }
+            }
+        }
+        let mut count = 0;
+        visit(
+            &std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../samples"),
+            &mut count,
+        );
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn keeps_comments_and_layout() {
+        let cst = parse_cst(INPUT, "test.m3l.md");
+        let top: Vec<CstLineKind> = cst
+            .nodes
+            .iter()
+            .filter_map(|n| match n {
+                CstNode::Line(l) => Some(l.kind),
+                CstNode::Element(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            top,
+            vec![
+                CstLineKind::Title,
+                CstLineKind::Namespace,
+                CstLineKind::Blank,
+                CstLineKind::Import,
+                CstLineKind::Blank,
+                CstLineKind::Comment,
+                CstLineKind::Comment,
+                // After Order's last content line
+                CstLineKind::Blank,
+                CstLineKind::HorizontalRule,
+                CstLineKind::Blank,
+                // The empty line after the final newline
+                CstLineKind::Blank,
+            ]
+        );
+
+        let order = cst.element("Order").unwrap();
+        assert_eq!(
+            kinds(order),
+            vec![
+                CstLineKind::Header,
+                CstLineKind::Blockquote,
+                CstLineKind::Blank,
+                CstLineKind::Field,
+                CstLineKind::Comment,
+                CstLineKind::Field,
+                CstLineKind::Blockquote,
+                CstLineKind::Blank,
+                CstLineKind::Section,
+                CstLineKind::Field,
+                CstLineKind::NestedItem,
+            ]
+        );
+        assert_eq!(order.lines[3].comment.as_deref(), Some("cents are rounded"));
+
+        let frontmatter = parse_cst("---\nowner: me\n---\n\n## A\n", "test.m3l.md");
+        let top: Vec<CstLineKind> = frontmatter
+            .nodes
+            .iter()
+            .filter_map(|n| match n {
+                CstNode::Line(l) => Some(l.kind),
+                CstNode::Element(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            top[..4],
+            [
+                CstLineKind::Frontmatter,
+                CstLineKind::Frontmatter,
+                CstLineKind::Frontmatter,
+                CstLineKind::Blank,
+            ]
+        );
+
+        let report = cst.element("Report").unwrap();
+        assert_eq!(
+            kinds(report),
+            vec![
+                CstLineKind::Header,
+                CstLineKind::Section,
+                CstLineKind::Code,
+                CstLineKind::Code,
+                CstLineKind::Code,
+            ]
+        );
+    }
+}
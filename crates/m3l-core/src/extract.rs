@@ -0,0 +1,425 @@
+//! Pull part of a resolved AST out by path, so hosts can fetch one model or
+//! field list without serializing and re-parsing the whole tree.
+//!
+//! Two path syntaxes are accepted:
+//! - JSON Pointer (RFC 6901): `/models/0/fields`. Yields a single value.
+//! - A JSONPath subset: `$`, `.name`, `['name']`, `[n]` (negative counts from
+//!   the end), `.*`/`[*]`, and `[?(...)]` filters, e.g.
+//!   `$.models[?(@.name == 'User')].fields[*].name`. Yields an array of every
+//!   match.
+//!
+//! A filter tests each child against a condition built from relative paths
+//! (`@`, `@.loc.line`, `@.attributes[*].name`, with nested filters):
+//! - `@.key` holds when the path finds a value other than `null` or `false`;
+//!   `!@.key` when it finds none.
+//! - `@.key == literal` (also `!=`, `<`, `<=`, `>`, `>=`) holds when any value
+//!   found compares true; `!=` holds when none is equal. Literals are JSON or
+//!   single-quoted strings.
+//! - Conditions combine with `&&`, `||` and parentheses.
+//!
+//! Models without a primary key field, for instance:
+//! `$.models[?(!@.fields[*].attributes[?(@.name == 'pk' || @.name == 'primary')])].name`
+
+use serde_json::Value;
+
+use crate::types::M3lAst;
+
+/// Extract the value at `path` (a JSON Pointer or JSONPath) from the AST.
+pub fn extract(ast: &M3lAst, path: &str) -> Result<Value, String> {
+    let root = serde_json::to_value(ast).map_err(|e| format!("JSON serialization error: {e}"))?;
+    extract_value(&root, path)
+}
+
+/// [`extract`] over an already serialized AST (or any JSON value).
+pub fn extract_value(root: &Value, path: &str) -> Result<Value, String> {
+    let path = path.trim();
+    if path.is_empty() || path.starts_with('/') {
+        return root
+            .pointer(path)
+            .cloned()
+            .ok_or_else(|| format!("No value at JSON Pointer \"{path}\""));
+    }
+    if path.starts_with('$') {
+        let segments = parse_path(path)?;
+        let found = select(&segments, root);
+        return Ok(Value::Array(found.into_iter().cloned().collect()));
+    }
+    Err(format!(
+        "Invalid path \"{path}\": expected a JSON Pointer (/models/0) or JSONPath ($.models[0])"
+    ))
+}
+
+enum Segment {
+    Child(String),
+    Index(i64),
+    Wildcard,
+    Filter(Condition),
+}
+
+enum Condition {
+    Any(Vec<Condition>),
+    All(Vec<Condition>),
+    Not(Box<Condition>),
+    Exists(Vec<Segment>),
+    Compare {
+        path: Vec<Segment>,
+        op: Op,
+        value: Value,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Operators in match order, so `<=` is not read as `<`.
+const OPERATORS: [(&str, Op); 6] = [
+    ("==", Op::Eq),
+    ("!=", Op::Ne),
+    ("<=", Op::Le),
+    (">=", Op::Ge),
+    ("<", Op::Lt),
+    (">", Op::Gt),
+];
+
+/// Every value reached from `root` by following `segments`.
+fn select<'a>(segments: &[Segment], root: &'a Value) -> Vec<&'a Value> {
+    let mut current = vec![root];
+    for segment in segments {
+        current = current.into_iter().flat_map(|v| segment.apply(v)).collect();
+    }
+    current
+}
+
+impl Segment {
+    fn apply<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        match self {
+            Segment::Child(name) => value.get(name.as_str()).into_iter().collect(),
+            Segment::Index(index) => {
+                let Some(items) = value.as_array() else {
+                    return Vec::new();
+                };
+                let index = if *index < 0 {
+                    items.len() as i64 + index
+                } else {
+                    *index
+                };
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|i| items.get(i))
+                    .into_iter()
+                    .collect()
+            }
+            Segment::Wildcard => children(value),
+            Segment::Filter(condition) => children(value)
+                .into_iter()
+                .filter(|child| condition.matches(child))
+                .collect(),
+        }
+    }
+}
+
+impl Condition {
+    fn matches(&self, item: &Value) -> bool {
+        match self {
+            Condition::Any(conditions) => conditions.iter().any(|c| c.matches(item)),
+            Condition::All(conditions) => conditions.iter().all(|c| c.matches(item)),
+            Condition::Not(condition) => !condition.matches(item),
+            Condition::Exists(path) => select(path, item)
+                .into_iter()
+                .any(|v| !matches!(v, Value::Null | Value::Bool(false))),
+            Condition::Compare { path, op, value } => {
+                let found = select(path, item);
+                match op {
+                    Op::Eq => found.into_iter().any(|v| json_eq(v, value)),
+                    Op::Ne => !found.into_iter().any(|v| json_eq(v, value)),
+                    _ => found.into_iter().any(|v| compare(v, *op, value)),
+                }
+            }
+        }
+    }
+}
+
+/// Ordering between two numbers or two strings; anything else is false.
+fn compare(a: &Value, op: Op, b: &Value) -> bool {
+    let ordering = match (a, b) {
+        (Value::String(x), Value::String(y)) => x.partial_cmp(y),
+        _ => match (a.as_f64(), b.as_f64()) {
+            (Some(x), Some(y)) => x.partial_cmp(&y),
+            _ => None,
+        },
+    };
+    let Some(ordering) = ordering else {
+        return false;
+    };
+    match op {
+        Op::Lt => ordering.is_lt(),
+        Op::Le => ordering.is_le(),
+        Op::Gt => ordering.is_gt(),
+        Op::Ge => ordering.is_ge(),
+        Op::Eq | Op::Ne => unreachable!("equality is handled by json_eq"),
+    }
+}
+
+fn children(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Equality that treats `1` and `1.0` as the same number.
+fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+fn parse_path(path: &str) -> Result<Vec<Segment>, String> {
+    parse_segments(&path[1..]).map_err(|reason| format!("Invalid JSONPath \"{path}\": {reason}"))
+}
+
+/// Segments following a `$` or `@`.
+fn parse_segments(mut rest: &str) -> Result<Vec<Segment>, &'static str> {
+    let mut segments = Vec::new();
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            if after.starts_with('.') {
+                return Err("recursive descent (..) is not supported");
+            }
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            let name = &after[..end];
+            segments.push(match name {
+                "" => return Err("empty member name"),
+                "*" => Segment::Wildcard,
+                _ => Segment::Child(name.to_string()),
+            });
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = closing_bracket(after).ok_or("unclosed '['")?;
+            segments.push(
+                parse_bracket(after[..end].trim())
+                    .ok_or("expected [n], ['name'], [*] or [?(@.key == value)]")?,
+            );
+            rest = &after[end + 1..];
+        } else {
+            return Err("expected '.' or '['");
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Byte offset of the `]` closing a bracket, skipping quoted strings and
+/// nested brackets.
+fn closing_bracket(s: &str) -> Option<usize> {
+    top_level(s).find(|&(_, c)| c == ']').map(|(i, _)| i)
+}
+
+/// Characters of `s` that are outside quoted strings and outside any
+/// bracket or parenthesis opened within `s`, with their byte offsets.
+fn top_level(s: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let mut quote: Option<char> = None;
+    let mut depth = 0usize;
+    s.char_indices().filter(move |&(_, c)| {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == '[' || c == '(' => depth += 1,
+            None if (c == ']' || c == ')') && depth > 0 => depth -= 1,
+            None => return depth == 0,
+        }
+        false
+    })
+}
+
+/// Split `s` on every top-level occurrence of `separator`.
+fn split_top<'a>(s: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (i, _) in top_level(s) {
+        if i >= start && s[i..].starts_with(separator) {
+            parts.push(&s[start..i]);
+            start = i + separator.len();
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn unquote(s: &str) -> Option<&str> {
+    s.strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .or_else(|| s.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+}
+
+fn parse_bracket(inner: &str) -> Option<Segment> {
+    if inner == "*" {
+        return Some(Segment::Wildcard);
+    }
+    if let Some(name) = unquote(inner) {
+        return Some(Segment::Child(name.to_string()));
+    }
+    if let Some(condition) = inner.strip_prefix("?(").and_then(|c| c.strip_suffix(')')) {
+        return parse_condition(condition).map(Segment::Filter);
+    }
+    inner.parse().ok().map(Segment::Index)
+}
+
+fn parse_condition(condition: &str) -> Option<Condition> {
+    let any = split_top(condition, "||");
+    if any.len() > 1 {
+        return any
+            .into_iter()
+            .map(parse_condition)
+            .collect::<Option<_>>()
+            .map(Condition::Any);
+    }
+    let all = split_top(condition, "&&");
+    if all.len() > 1 {
+        return all
+            .into_iter()
+            .map(parse_condition)
+            .collect::<Option<_>>()
+            .map(Condition::All);
+    }
+
+    let condition = condition.trim();
+    if let Some(inner) = condition
+        .strip_prefix('(')
+        .and_then(|c| c.strip_suffix(')'))
+    {
+        // Only when the parentheses wrap the whole condition
+        if top_level(condition).count() == 0 {
+            return parse_condition(inner);
+        }
+    }
+    if let Some(negated) = condition.strip_prefix('!') {
+        if !negated.starts_with('=') {
+            return parse_condition(negated).map(|c| Condition::Not(Box::new(c)));
+        }
+    }
+
+    let operator = top_level(condition).find_map(|(i, _)| {
+        OPERATORS
+            .iter()
+            .find(|(token, _)| condition[i..].starts_with(token))
+            .map(|&(token, op)| (i, token.len(), op))
+    });
+    let (lhs, rhs) = match operator {
+        Some((i, len, op)) => (&condition[..i], Some((op, &condition[i + len..]))),
+        None => (condition, None),
+    };
+    let path = parse_segments(lhs.trim().strip_prefix('@')?).ok()?;
+    match rhs {
+        None => Some(Condition::Exists(path)),
+        Some((op, literal)) => {
+            let literal = literal.trim();
+            let value = match unquote(literal) {
+                Some(s) => Value::String(s.to_string()),
+                None => serde_json::from_str(literal).ok()?,
+            };
+            Some(Condition::Compare { path, op, value })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_string, resolve};
+
+    fn ast() -> M3lAst {
+        let input = "## User\n- id: identifier @primary\n- email: email\n\n## Post\n- id: identifier @primary\n- title: string(200)\n- author_id: identifier @reference(User)";
+        resolve(&[parse_string(input, "blog.m3l.md")], None)
+    }
+
+    #[test]
+    fn json_pointer() {
+        let ast = ast();
+        assert_eq!(extract(&ast, "/models/1/name").unwrap(), "Post");
+        assert_eq!(
+            extract(&ast, "/models/0/fields")
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(extract(&ast, "/models/9").is_err());
+    }
+
+    #[test]
+    fn jsonpath_filter_and_wildcard() {
+        let ast = ast();
+        let names = extract(&ast, "$.models[?(@.name == 'Post')].fields[*].name").unwrap();
+        assert_eq!(names, serde_json::json!(["id", "title", "author_id"]));
+
+        let lines = extract(&ast, "$['models'][-1].fields[?(@.loc.line == 8)].name").unwrap();
+        assert_eq!(lines, serde_json::json!(["author_id"]));
+
+        let located = extract(&ast, "$.models[?(@.loc.file)].name").unwrap();
+        assert_eq!(located, serde_json::json!(["User", "Post"]));
+
+        assert_eq!(extract(&ast, "$.models[5]").unwrap(), serde_json::json!([]));
+    }
+
+    #[test]
+    fn filter_conditions() {
+        let ast = ast();
+        let missing_pk =
+            "$.models[?(!@.fields[*].attributes[?(@.name == 'pk' || @.name == 'primary')])].name";
+        let input =
+            "## User\n- id: identifier @pk\n\n## Log\n- message: text\n- level: integer = 3";
+        let other = resolve(&[parse_string(input, "log.m3l.md")], None);
+        assert_eq!(
+            extract(&other, missing_pk).unwrap(),
+            serde_json::json!(["Log"])
+        );
+        assert_eq!(extract(&ast, missing_pk).unwrap(), serde_json::json!([]));
+
+        let not_id = extract(&ast, "$.models[0].fields[?(@.name != 'id')].name").unwrap();
+        assert_eq!(not_id, serde_json::json!(["email"]));
+
+        let late = extract(
+            &ast,
+            "$.models[*].fields[?(@.loc.line >= 7 && @.type == 'string')].name",
+        );
+        assert_eq!(late.unwrap(), serde_json::json!(["title"]));
+
+        let either = "$.models[*].fields[?((@.type == 'email') || @.name == 'title')].name";
+        assert_eq!(
+            extract(&ast, either).unwrap(),
+            serde_json::json!(["email", "title"])
+        );
+
+        let params = extract(&ast, "$.models[*].fields[?(@.params[0] > 100)].name").unwrap();
+        assert_eq!(params, serde_json::json!(["title"]));
+    }
+
+    #[test]
+    fn invalid_paths() {
+        let ast = ast();
+        for path in [
+            "models",
+            "$..name",
+            "$.models[",
+            "$.models[?(name)]",
+            "$.",
+            "$.models[?(@.name == )]",
+            "$.models[?(@.name == 'A' ||)]",
+        ] {
+            assert!(extract(&ast, path).is_err(), "{path}");
+        }
+    }
+}
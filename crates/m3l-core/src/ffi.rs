@@ -2,22 +2,30 @@
 //!
 //! All functions take string inputs and return JSON strings,
 //! minimizing the FFI surface area.
+//!
+//! `catch_unwind` here is a last resort only: wasm32 cannot unwind, so the
+//! parser itself must never panic (see `tests/panic_free.rs` and `fuzz/`).
 
 use crate::types::*;
-use crate::{parse_string, resolve, validate};
+use crate::{extract, parse_string, resolve, validate};
 use serde::{Deserialize, Serialize};
 
 // ---------------------------------------------------------------------------
 // Options types (deserialized from JSON input)
+//
+// Unknown keys are rejected: serde's error names the offending key and the
+// accepted ones, so a host's typo surfaces instead of being ignored.
 // ---------------------------------------------------------------------------
 
 #[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ParseOptions {
     #[serde(default)]
     pub filename: String,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ValidateJsonOptions {
     #[serde(default)]
     pub strict: bool,
@@ -189,3 +197,59 @@ pub fn validate_to_json(content: &str, options_json: &str) -> String {
         .unwrap(),
     }
 }
+
+/// Parse M3L content and return only the part of the AST at `path`.
+///
+/// Input: M3L markdown text, filename, and a JSON Pointer (`/models/0`) or
+/// JSONPath (`$.models[?(@.name == 'User')]`)
+/// Output: JSON string whose `data` is the value at a pointer, or an array
+/// of every JSONPath match
+pub fn extract_to_json(content: &str, filename: &str, path: &str) -> String {
+    let result = std::panic::catch_unwind(|| {
+        let parsed = parse_string(content, filename);
+        extract(&resolve(&[parsed], None), path)
+    });
+
+    match result {
+        Ok(Ok(value)) => serde_json::to_string(&FfiResult {
+            success: true,
+            data: Some(value),
+            error: None,
+        })
+        .unwrap(),
+        Ok(Err(e)) => serde_json::to_string(&FfiResult::<()> {
+            success: false,
+            data: None,
+            error: Some(e),
+        })
+        .unwrap(),
+        Err(_) => serde_json::to_string(&FfiResult::<()> {
+            success: false,
+            data: None,
+            error: Some("Internal parser panic".to_string()),
+        })
+        .unwrap(),
+    }
+}
+
+/// Describe a validation code (`M3L-E005`, `E005`) or lint rule
+/// (`naming-convention`).
+///
+/// Output: JSON string whose `data` is the code's entry (code, kind, title,
+/// description, bad and good examples, strict)
+pub fn explain_to_json(code: &str) -> String {
+    match crate::explain(code) {
+        Some(doc) => serde_json::to_string(&FfiResult {
+            success: true,
+            data: Some(doc),
+            error: None,
+        })
+        .unwrap(),
+        None => serde_json::to_string(&FfiResult::<()> {
+            success: false,
+            data: None,
+            error: Some(format!("Unknown code \"{code}\"")),
+        })
+        .unwrap(),
+    }
+}
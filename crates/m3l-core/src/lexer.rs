@@ -13,30 +13,49 @@ static RE_HR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^-{3,}$").unwrap()
 static RE_BLOCKQUOTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\s*)> (.+)$").unwrap());
 static RE_LIST_ITEM: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\s*)- (.+)$").unwrap());
 static RE_BLANK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*$").unwrap());
+static RE_YAML_LINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:[\w-]+\s*:(?:\s|$)|\s|-(?:\s|$)|#(?:[^#]|$))").unwrap());
 
 // H2 sub-patterns
-static RE_TYPE_INDICATOR: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^(@?[\w][\w.]*(?:\([^)]*\))?)\s*::(\w+)(.*)$").unwrap());
+static RE_TYPE_INDICATOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(@?[\w][\w.]*(?:<[^>]*>)?(?:\([^)]*\))?)\s*::(\w+)(.*)$").unwrap()
+});
+static RE_EXTEND: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^extend\s+([\w][\w.]*)$").unwrap());
 static RE_MODEL_DEF: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^([\w][\w.]*(?:\([^)]*\))?)\s*(?::\s*(.+?))?(\s+@.+)?$").unwrap()
+    Regex::new(r"^([\w][\w.]*(?:<[^>]*>)?(?:\([^)]*\))?)\s*(?::\s*(.+?))?(\s+@.+)?$").unwrap()
 });
 
 // Field line patterns
 static RE_FIELD_NAME: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^([\w]+)(?:\(([^)]*)\))?\s*(?::\s*(.+))?$").unwrap());
+    LazyLock::new(|| Regex::new(r"^([\w]+)(?:\(([^)]*)\))?(?:\s*:\s*(.+)|\s+(@.+))?\s*$").unwrap());
 static RE_TYPE_PART: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"^([\w][\w.]*)(?:<([^>]+)>)?(?:\(([^)]*)\))?(\?)?(\[\])?(\?)?").unwrap()
 });
+static RE_TYPE_SUFFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\?)?(\[\])?(\?)?").unwrap());
 static RE_FRAMEWORK_ATTR: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"`\[([^\]]+)\]`").unwrap());
 static RE_INLINE_COMMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+#\s+(.+)$").unwrap());
 
 static RE_NAME_LABEL: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^([\w][\w.]*)\(([^)]*)\)$").unwrap());
+    LazyLock::new(|| Regex::new(r"^([\w][\w.]*)(?:<([^>]*)>)?(?:\(([^)]*)\))?$").unwrap());
+// One `locale: "text"` entry of localized labels
+const LOCALE_LABEL: &str = r#"([A-Za-z]{2,3}(?:[-_][A-Za-z0-9]+)*)\s*:\s*"([^"]*)""#;
+static RE_LOCALE_LABEL: LazyLock<Regex> = LazyLock::new(|| Regex::new(LOCALE_LABEL).unwrap());
+static RE_LOCALE_LABELS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"^\s*{LOCALE_LABEL}(?:\s*,\s*{LOCALE_LABEL})*\s*$"
+    ))
+    .unwrap()
+});
+static RE_VERSION_PRAGMA: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:<!--\s*m3l:\s*(\S+?)\s*-->|#\s+M3L-Version:\s*(\S+))\s*$").unwrap()
+});
 static RE_NAMESPACE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^Namespace:\s*(.+)$").unwrap());
 static RE_IMPORT: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r#"^@import\s+["'](.+?)["']\s*$"#).unwrap());
+    LazyLock::new(|| Regex::new(r#"^@import\s+["'](.+?)["'](?:\s*\(([^)]*)\))?\s*$"#).unwrap());
 static RE_ENUM_VALUE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r#"^([\w]+)(?:\(([^)]*)\))?\s+"((?:[^"\\]|\\.)*)"$"#).unwrap());
 static RE_NESTED_KV: LazyLock<Regex> =
@@ -47,6 +66,27 @@ static RE_H2_DESC: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#""([^"]+)""#)
 static RE_MODEL_ATTR: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"@([\w]+)(?:\(([^)]*)\))?").unwrap());
 
+/// Index of the line closing a YAML frontmatter block that opens the file
+/// (`---` on the first line, `---` or `...` to close). A block whose lines
+/// do not read as YAML keys, list items or comments is a horizontal rule;
+/// `## ...` lines are element headers, not comments.
+fn frontmatter_end(lines: &[&str]) -> Option<usize> {
+    let line = |i: usize| lines[i].strip_suffix('\r').unwrap_or(lines[i]);
+    if lines.is_empty() || line(0).trim_end() != "---" {
+        return None;
+    }
+    for i in 1..lines.len() {
+        let text = line(i);
+        if matches!(text.trim_end(), "---" | "...") {
+            return Some(i);
+        }
+        if !RE_BLANK.is_match(text) && !RE_YAML_LINE.is_match(text) {
+            return None;
+        }
+    }
+    None
+}
+
 /// Tokenize M3L markdown content into a sequence of tokens.
 pub fn lex(content: &str, _file: &str) -> Vec<Token> {
     let lines: Vec<&str> = content.split('\n').collect();
@@ -54,6 +94,29 @@ pub fn lex(content: &str, _file: &str) -> Vec<Token> {
     let total = lines.len();
     let mut i = 0;
 
+    // YAML frontmatter, folded into one token
+    if let Some(end) = frontmatter_end(&lines) {
+        let body: Vec<&str> = lines[1..end]
+            .iter()
+            .map(|l| l.strip_suffix('\r').unwrap_or(l))
+            .collect();
+        tokens.push(Token {
+            token_type: TokenType::Frontmatter,
+            raw: lines[0].strip_suffix('\r').unwrap_or(lines[0]).to_string(),
+            line: 1,
+            indent: 0,
+            data: TokenData {
+                code_block: Some(CodeBlock {
+                    language: Some("yaml".to_string()),
+                    content: body.join("\n"),
+                }),
+                folded_end: Some((end + 1, line_end_col(lines[end]))),
+                ..Default::default()
+            },
+        });
+        i = end + 1;
+    }
+
     while i < total {
         let raw_line = lines[i];
         // Strip trailing \r for CRLF
@@ -87,8 +150,10 @@ pub fn lex(content: &str, _file: &str) -> Vec<Token> {
             let code_content = code_lines
                 .iter()
                 .map(|l| {
-                    if l.len() > fence_indent {
-                        &l[fence_indent..]
+                    // Strip at most the fence's own indentation, and only whitespace
+                    let indent = (l.len() - l.trim_start().len()).min(fence_indent);
+                    if l.is_char_boundary(indent) {
+                        &l[indent..]
                     } else {
                         l.trim_start()
                     }
@@ -105,6 +170,9 @@ pub fn lex(content: &str, _file: &str) -> Vec<Token> {
                         language: lang_hint.clone(),
                         content: code_content.clone(),
                     });
+                    // The closing fence, or the last line when it is missing
+                    let last = i.min(total - 1);
+                    tokens[j].data.folded_end = Some((last + 1, line_end_col(lines[last])));
                     break;
                 }
                 if *tt != TokenType::Blank {
@@ -169,6 +237,23 @@ pub fn lex(content: &str, _file: &str) -> Vec<Token> {
             continue;
         }
 
+        // Version pragma: `<!-- m3l: 1.1 -->` or `# M3L-Version: 1.1`
+        if let Some(caps) = RE_VERSION_PRAGMA.captures(raw.trim()) {
+            let version = caps.get(1).or_else(|| caps.get(2)).map(|m| m.as_str());
+            tokens.push(Token {
+                token_type: TokenType::Version,
+                raw: raw.to_string(),
+                line: line_num,
+                indent: 0,
+                data: TokenData {
+                    name: version.map(str::to_string),
+                    ..TokenData::default()
+                },
+            });
+            i += 1;
+            continue;
+        }
+
         // H1 — Namespace (only if `# Namespace: ...` pattern)
         // Non-namespace H1 lines (e.g. `# My Data Model`) are treated as
         // document titles and silently ignored — M3L preserves Markdown
@@ -203,6 +288,7 @@ pub fn lex(content: &str, _file: &str) -> Vec<Token> {
                             Some(prev) => format!("{}\n{}", prev, bq_text),
                             None => bq_text.clone(),
                         });
+                        tokens[j].data.folded_end = Some((line_num, line_end_col(raw)));
                         break;
                     }
                     if *tt != TokenType::Blank && *tt != TokenType::Blockquote {
@@ -261,6 +347,14 @@ pub fn lex(content: &str, _file: &str) -> Vec<Token> {
             let data = TokenData {
                 is_import: true,
                 import_path: Some(caps[1].to_string()),
+                import_symbols: caps.get(2).map(|list| {
+                    list.as_str()
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                }),
                 name: Some(trimmed.to_string()),
                 ..Default::default()
             };
@@ -301,10 +395,8 @@ fn tokenize_h2(content: &str, raw: &str, line: usize) -> Token {
         let type_indicator = &caps[2];
         let rest = caps.get(3).map(|m| m.as_str().trim()).unwrap_or("");
 
-        let (name, label) = parse_name_label(namepart);
         let mut data = TokenData::default();
-        data.name = Some(name);
-        data.label = label;
+        data.name = Some(parse_name_label(namepart, &mut data));
 
         // Parse inheritance
         if let Some(inherit_caps) = RE_H2_INHERIT.captures(rest) {
@@ -342,13 +434,30 @@ fn tokenize_h2(content: &str, raw: &str, line: usize) -> Token {
         };
     }
 
+    // Additions to a model defined elsewhere: ## extend Name
+    if let Some(caps) = RE_EXTEND.captures(content) {
+        let data = TokenData {
+            name: Some(caps[1].to_string()),
+            is_extend: true,
+            ..Default::default()
+        };
+        return Token {
+            token_type: TokenType::Model,
+            raw: raw.to_string(),
+            line,
+            indent: 0,
+            data,
+        };
+    }
+
     // Regular model: ## Name : Parent1, Parent2
     if let Some(caps) = RE_MODEL_DEF.captures(content) {
         let namepart = &caps[1];
         let inherits_str = caps.get(2).map(|m| m.as_str().trim());
         let attrs_str = caps.get(3).map(|m| m.as_str().trim());
 
-        let (name, label) = parse_name_label(namepart);
+        let mut data = TokenData::default();
+        data.name = Some(parse_name_label(namepart, &mut data));
         let inherits = match inherits_str {
             Some(s) if !s.is_empty() => s
                 .split(',')
@@ -358,9 +467,6 @@ fn tokenize_h2(content: &str, raw: &str, line: usize) -> Token {
             _ => Vec::new(),
         };
 
-        let mut data = TokenData::default();
-        data.name = Some(name);
-        data.label = label;
         data.inherits = inherits;
 
         // Parse model-level attributes
@@ -405,11 +511,156 @@ fn tokenize_h2(content: &str, raw: &str, line: usize) -> Token {
     }
 }
 
-fn parse_name_label(s: &str) -> (String, Option<String>) {
+/// The type name, `<generics>` and `(params)` matched by `RE_TYPE_PART`.
+fn type_member(caps: &regex::Captures) -> TypeMember {
+    let generic_params = caps.get(2).map(|m| {
+        m.as_str()
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .collect()
+    });
+    let params = caps.get(3).map(|m| {
+        m.as_str()
+            .split(',')
+            .map(|s| {
+                let s = s.trim();
+                if let Ok(n) = s.parse::<f64>() {
+                    ParamValue::Number(n)
+                } else {
+                    ParamValue::String(s.to_string())
+                }
+            })
+            .collect()
+    });
+    TypeMember {
+        member_type: caps[1].to_string(),
+        params,
+        generic_params,
+        nullable: false,
+    }
+}
+
+/// The components of a tuple type at the start of `rest`, `(float, float?)`,
+/// and the offset after its closing parenthesis. A tuple has at least two
+/// components, each a type with an optional `?`.
+fn tuple_components(rest: &str) -> Option<(Vec<TypeMember>, usize)> {
+    if !rest.starts_with('(') {
+        return None;
+    }
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 1;
+    let mut end = None;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' | '<' => depth += 1,
+            ')' | '>' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    parts.push(&rest[start..i]);
+                    end = Some(i + 1);
+                    break;
+                }
+            }
+            ',' if depth == 1 => {
+                parts.push(&rest[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let end = end?;
+    if parts.len() < 2 {
+        return None;
+    }
+    let components = parts
+        .iter()
+        .map(|part| {
+            let part = part.trim();
+            let caps = RE_TYPE_PART.captures(part)?;
+            if caps[0].len() != part.len() || caps.get(5).is_some() {
+                return None;
+            }
+            let mut member = type_member(&caps);
+            member.nullable = caps.get(4).is_some() || caps.get(6).is_some();
+            Some(member)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some((components, end))
+}
+
+/// The values of an inline literal enum at the start of `rest`,
+/// `"draft" | "published"`, and the offset after the last one. At least two
+/// quoted values are needed; a single one is a description.
+fn enum_literals(rest: &str) -> Option<(Vec<String>, usize)> {
+    let mut values = Vec::new();
+    let mut pos = 0;
+    let mut end = 0;
+    loop {
+        if !rest[pos..].starts_with('"') {
+            break;
+        }
+        let close = find_closing_quote(rest, pos)?;
+        values.push(rest[pos + 1..close].to_string());
+        end = close + 1;
+        let after = rest[end..].trim_start_matches(' ');
+        match after.strip_prefix('|') {
+            Some(next) => pos = rest.len() - next.trim_start_matches(' ').len(),
+            None => break,
+        }
+    }
+    (values.len() >= 2).then_some((values, end))
+}
+
+/// Nullable and array flags from a `?[]?` suffix: groups `first` to
+/// `first + 2` of `caps`.
+fn set_type_suffix(caps: &regex::Captures, first: usize, data: &mut TokenData) {
+    data.array = caps.get(first + 1).is_some();
+    let q4 = caps.get(first).is_some();
+    let q6 = caps.get(first + 2).is_some();
+
+    if data.array {
+        data.nullable = q6;
+        data.array_item_nullable = q4;
+    } else {
+        data.nullable = q4 || q6;
+        data.array_item_nullable = false;
+    }
+}
+
+fn parse_name_label(s: &str, data: &mut TokenData) -> String {
     if let Some(caps) = RE_NAME_LABEL.captures(s) {
-        (caps[1].to_string(), Some(caps[2].to_string()))
+        if let Some(params) = caps.get(2) {
+            data.type_generic_params = params
+                .as_str()
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+        }
+        if let Some(label) = caps.get(3) {
+            set_label(label.as_str(), data);
+        }
+        caps[1].to_string()
     } else {
-        (s.to_string(), None)
+        s.to_string()
+    }
+}
+
+/// Record the text between a name's parentheses: one label, or localized
+/// labels (`en: "Name", ko: "이름"`) with the first as the label.
+fn set_label(raw: &str, data: &mut TokenData) {
+    if !RE_LOCALE_LABELS.is_match(raw) {
+        data.label = Some(raw.to_string());
+        return;
+    }
+    for caps in RE_LOCALE_LABEL.captures_iter(raw) {
+        if data.label.is_none() {
+            data.label = Some(caps[2].to_string());
+        }
+        data.labels
+            .entry(caps[1].to_string())
+            .or_insert_with(|| caps[2].to_string());
     }
 }
 
@@ -474,10 +725,11 @@ fn parse_field_line(content: &str) -> TokenData {
         Some(caps) => {
             data.name = Some(caps[1].to_string());
             if let Some(m) = caps.get(2) {
-                data.label = Some(m.as_str().to_string());
+                set_label(m.as_str(), &mut data);
             }
 
-            match caps.get(3) {
+            // `- name @from(User.name)`: attributes without a type
+            match caps.get(3).or_else(|| caps.get(4)) {
                 None => return data,
                 Some(m) => {
                     let rest = m.as_str().trim();
@@ -507,56 +759,61 @@ pub fn parse_type_and_attrs(rest: &str, data: &mut TokenData) {
 
     // Check if entire rest is a quoted string
     if !rest.is_empty() && bytes[0] == b'"' {
-        let close_idx = find_closing_quote(rest, 0);
-        if close_idx >= 0 && close_idx as usize == len - 1 {
-            data.description = Some(rest[1..close_idx as usize].to_string());
+        if let Some(close_idx) = find_closing_quote(rest, 0).filter(|&c| c == len - 1) {
+            data.description = Some(rest[1..close_idx].to_string());
             return;
         }
     }
 
     // Parse type: word<generics>?(params)?[]??
     if let Some(caps) = RE_TYPE_PART.captures(rest) {
-        data.type_name = Some(caps[1].to_string());
+        let member = type_member(&caps);
+        data.type_name = Some(member.member_type.clone());
+        data.type_generic_params = member.generic_params.clone().unwrap_or_default();
+        data.type_params = member.params.clone().unwrap_or_default();
+        set_type_suffix(&caps, 4, data);
+        pos = caps[0].len();
+        skip_ws(&mut pos);
 
-        // Generic params <K,V>
-        if let Some(m) = caps.get(2) {
-            data.type_generic_params = m
-                .as_str()
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .collect();
+        // Union: string | integer. Suffixes after the last member apply to
+        // the whole type
+        let mut members = vec![member];
+        while pos < len && bytes[pos] == b'|' {
+            pos += 1;
+            skip_ws(&mut pos);
+            let Some(caps) = RE_TYPE_PART.captures(&rest[pos..]) else {
+                break;
+            };
+            members.push(type_member(&caps));
+            set_type_suffix(&caps, 4, data);
+            pos += caps[0].len();
+            skip_ws(&mut pos);
         }
-
-        // Type params (n,m)
-        if let Some(m) = caps.get(3) {
-            data.type_params = m
-                .as_str()
-                .split(',')
-                .map(|s| {
-                    let s = s.trim();
-                    if let Ok(n) = s.parse::<f64>() {
-                        ParamValue::Number(n)
-                    } else {
-                        ParamValue::String(s.to_string())
-                    }
-                })
-                .collect();
+        if members.len() > 1 {
+            data.type_name = Some("union".to_string());
+            data.type_generic_params = Vec::new();
+            data.type_params = Vec::new();
+            data.union_types = members;
         }
-
-        // Array
-        data.array = caps.get(5).is_some_and(|m| m.as_str() == "[]");
-        let q4 = caps.get(4).is_some_and(|m| m.as_str() == "?");
-        let q6 = caps.get(6).is_some_and(|m| m.as_str() == "?");
-
-        if data.array {
-            data.nullable = q6;
-            data.array_item_nullable = q4;
-        } else {
-            data.nullable = q4 || q6;
-            data.array_item_nullable = false;
+    } else if let Some((values, end)) = enum_literals(rest) {
+        // Inline literal enum: "draft" | "published"
+        data.type_name = Some("enum".to_string());
+        data.enum_literals = values;
+        pos = end;
+        if let Some(caps) = RE_TYPE_SUFFIX.captures(&rest[pos..]) {
+            set_type_suffix(&caps, 1, data);
+            pos += caps[0].len();
+        }
+        skip_ws(&mut pos);
+    } else if let Some((components, end)) = tuple_components(rest) {
+        // Tuple: (float, float), suffixes after the parenthesis
+        data.type_name = Some("tuple".to_string());
+        data.tuple_types = components;
+        pos = end;
+        if let Some(caps) = RE_TYPE_SUFFIX.captures(&rest[pos..]) {
+            set_type_suffix(&caps, 1, data);
+            pos += caps[0].len();
         }
-
-        pos = caps[0].len();
         skip_ws(&mut pos);
     }
 
@@ -565,19 +822,23 @@ pub fn parse_type_and_attrs(rest: &str, data: &mut TokenData) {
         pos += 1;
         skip_ws(&mut pos);
         if pos < len && bytes[pos] == b'"' {
-            let close_idx = find_closing_quote(rest, pos);
-            if close_idx >= 0 {
-                data.default_value = Some(rest[pos..=close_idx as usize].to_string());
-                pos = close_idx as usize + 1;
+            if let Some(close_idx) = find_closing_quote(rest, pos) {
+                data.default_value = Some(rest[pos..=close_idx].to_string());
+                pos = close_idx + 1;
                 skip_ws(&mut pos);
             }
         } else if pos < len && bytes[pos] == b'`' {
-            let close_idx = find_closing_backtick(rest, pos);
-            if close_idx >= 0 {
-                data.default_value = Some(rest[pos..=close_idx as usize].to_string());
-                pos = close_idx as usize + 1;
+            if let Some(close_idx) = find_closing_backtick(rest, pos) {
+                data.default_value = Some(rest[pos..=close_idx].to_string());
+                pos = close_idx + 1;
                 skip_ws(&mut pos);
             }
+        } else if pos < len && (bytes[pos] == b'[' || bytes[pos] == b'{') {
+            // JSON array or object literal
+            let close_idx = find_json_end(rest, pos).unwrap_or(len - 1);
+            data.default_value = Some(rest[pos..=close_idx].to_string());
+            pos = close_idx + 1;
+            skip_ws(&mut pos);
         } else {
             let start = pos;
             while pos < len
@@ -587,12 +848,7 @@ pub fn parse_type_and_attrs(rest: &str, data: &mut TokenData) {
                 && bytes[pos] != b'`'
             {
                 if bytes[pos] == b'(' {
-                    let close_p = find_balanced_paren(rest, pos);
-                    pos = if close_p >= 0 {
-                        close_p as usize + 1
-                    } else {
-                        pos + 1
-                    };
+                    pos = find_balanced_paren(rest, pos).map_or(pos + 1, |close_p| close_p + 1);
                 } else {
                     pos += 1;
                 }
@@ -622,17 +878,14 @@ pub fn parse_type_and_attrs(rest: &str, data: &mut TokenData) {
 
         pos += 1; // skip @
         let name_start = pos;
-        while pos < len && is_word_char(bytes[pos]) {
-            pos += 1;
-        }
+        pos += word_len(&rest[pos..]);
         let attr_name = rest[name_start..pos].to_string();
         let mut args = Vec::new();
         if pos < len && bytes[pos] == b'(' {
-            let close_p = find_balanced_paren(rest, pos);
-            if close_p >= 0 {
-                let args_str = &rest[pos + 1..close_p as usize];
+            if let Some(close_p) = find_balanced_paren(rest, pos) {
+                let args_str = &rest[pos + 1..close_p];
                 args = parse_attr_args_string(args_str);
-                pos = close_p as usize + 1;
+                pos = close_p + 1;
             }
         }
         attrs.push(RawAttribute {
@@ -649,30 +902,153 @@ pub fn parse_type_and_attrs(rest: &str, data: &mut TokenData) {
     // Trailing description
     skip_ws(&mut pos);
     if pos < len && bytes[pos] == b'"' {
-        let close_idx = find_closing_quote(rest, pos);
-        if close_idx >= 0 {
-            data.description = Some(rest[pos + 1..close_idx as usize].to_string());
+        if let Some(close_idx) = find_closing_quote(rest, pos) {
+            data.description = Some(rest[pos + 1..close_idx].to_string());
         }
     }
 }
 
-fn find_balanced_paren(s: &str, open_pos: usize) -> i32 {
+/// Column just past the last non-blank character of a line.
+pub(crate) fn line_end_col(raw: &str) -> usize {
+    raw.trim_end().chars().count() + 1
+}
+
+/// Byte range of the attribute `@name` in `line`, searching from byte
+/// `from`: the `@` through the paren closing its arguments and, with
+/// `cascade`, the `!`/`!!`/`?` symbol after it.
+pub(crate) fn attribute_range(
+    line: &str,
+    from: usize,
+    name: &str,
+    cascade: bool,
+) -> Option<(usize, usize)> {
+    let bytes = line.as_bytes();
+    let needle = format!("@{name}");
+    let mut pos = from;
+    loop {
+        let start = pos + line.get(pos..)?.find(&needle)?;
+        let mut end = start + needle.len();
+        // `@index` is not `@in`
+        if line[end..].starts_with(is_word_char) {
+            pos = end;
+            continue;
+        }
+        if bytes.get(end) == Some(&b'(') {
+            if let Some(close) = find_balanced_paren(line, end) {
+                end = close + 1;
+            }
+        }
+        if cascade {
+            let symbol = end + line[end..].len() - line[end..].trim_start().len();
+            let width = match &line[symbol..] {
+                s if s.starts_with("!!") => 2,
+                s if s.starts_with('!') || s.starts_with('?') => 1,
+                _ => 0,
+            };
+            if width > 0 {
+                end = symbol + width;
+            }
+        }
+        return Some((start, end));
+    }
+}
+
+/// The first quote, backtick or bracket in `s` that is never closed, as its
+/// byte index and character. Scanning stops at an inline `# comment`. A
+/// `'` only opens a string where a value starts (after `(`, `,`, `=`, `[`
+/// or `:`), so apostrophes in words are not counted.
+pub(crate) fn unclosed_delimiter(s: &str) -> Option<(usize, char)> {
+    let bytes = s.as_bytes();
+    let mut open: Vec<(usize, u8)> = Vec::new();
+    let mut prev = b'(';
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let closing = match b {
+            b'"' => Some(find_closing_quote(s, i)),
+            b'`' => Some(find_closing_backtick(s, i)),
+            b'\'' if matches!(prev, b'(' | b',' | b'=' | b'[' | b':') => {
+                Some(s[i + 1..].find('\'').map(|close| i + 1 + close))
+            }
+            _ => None,
+        };
+        match (b, closing) {
+            (_, Some(Some(close))) => i = close,
+            (_, Some(None)) => return Some((i, b as char)),
+            (b'(' | b'[' | b'{', _) => open.push((i, b)),
+            (b')' | b']' | b'}', _) => {
+                let expected = match b {
+                    b')' => b'(',
+                    b']' => b'[',
+                    _ => b'{',
+                };
+                match open.last() {
+                    Some(&(_, top)) if top == expected => {
+                        open.pop();
+                    }
+                    Some(&(at, top)) => return Some((at, top as char)),
+                    // A stray closer is left to the parser
+                    None => {}
+                }
+            }
+            (b'#', _)
+                if i > 0
+                    && bytes[i - 1].is_ascii_whitespace()
+                    && bytes.get(i + 1).is_none_or(|c| c.is_ascii_whitespace()) =>
+            {
+                break
+            }
+            _ => {}
+        }
+        if !b.is_ascii_whitespace() {
+            prev = bytes[i];
+        }
+        i += 1;
+    }
+    open.first().map(|&(at, b)| (at, b as char))
+}
+
+/// Byte index of the `)` closing the paren at `open_pos`, skipping quoted
+/// and backtick spans.
+/// Index of the bracket closing the JSON array or object opened at
+/// `open_pos`, skipping string contents.
+fn find_json_end(s: &str, open_pos: usize) -> Option<usize> {
     let bytes = s.as_bytes();
-    let mut depth = 0i32;
+    let mut depth = 0usize;
+    let mut i = open_pos;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'[' | b'{' => depth += 1,
+            b']' | b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            b'"' => i = find_closing_quote(s, i)?,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn find_balanced_paren(s: &str, open_pos: usize) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut depth = 0usize;
     let mut i = open_pos;
     while i < bytes.len() {
         match bytes[i] {
             b'(' => depth += 1,
             b')' => {
-                depth -= 1;
+                depth = depth.saturating_sub(1);
                 if depth == 0 {
-                    return i as i32;
+                    return Some(i);
                 }
             }
             b'"' => {
-                let close_q = find_closing_quote(s, i);
-                if close_q >= 0 {
-                    i = close_q as usize;
+                if let Some(close_q) = find_closing_quote(s, i) {
+                    i = close_q;
                 }
             }
             b'\'' => {
@@ -681,19 +1057,18 @@ fn find_balanced_paren(s: &str, open_pos: usize) -> i32 {
                 }
             }
             b'`' => {
-                let close_q = find_closing_backtick(s, i);
-                if close_q >= 0 {
-                    i = close_q as usize;
+                if let Some(close_q) = find_closing_backtick(s, i) {
+                    i = close_q;
                 }
             }
             _ => {}
         }
         i += 1;
     }
-    -1
+    None
 }
 
-fn find_closing_quote(s: &str, open_pos: usize) -> i32 {
+fn find_closing_quote(s: &str, open_pos: usize) -> Option<usize> {
     let bytes = s.as_bytes();
     let mut i = open_pos + 1;
     while i < bytes.len() {
@@ -702,14 +1077,14 @@ fn find_closing_quote(s: &str, open_pos: usize) -> i32 {
             continue;
         }
         if bytes[i] == b'"' {
-            return i as i32;
+            return Some(i);
         }
         i += 1;
     }
-    -1
+    None
 }
 
-fn find_closing_backtick(s: &str, open_pos: usize) -> i32 {
+fn find_closing_backtick(s: &str, open_pos: usize) -> Option<usize> {
     let bytes = s.as_bytes();
     let mut i = open_pos + 1;
     while i < bytes.len() {
@@ -718,11 +1093,11 @@ fn find_closing_backtick(s: &str, open_pos: usize) -> i32 {
             continue;
         }
         if bytes[i] == b'`' {
-            return i as i32;
+            return Some(i);
         }
         i += 1;
     }
-    -1
+    None
 }
 
 fn parse_attributes_balanced(content: &str) -> Vec<RawAttribute> {
@@ -736,20 +1111,17 @@ fn parse_attributes_balanced(content: &str) -> Vec<RawAttribute> {
             Some(offset) => pos += offset + 1,
         }
         let name_start = pos;
-        while pos < len && is_word_char(bytes[pos]) {
-            pos += 1;
-        }
+        pos += word_len(&content[pos..]);
         let name = content[name_start..pos].to_string();
         if name.is_empty() {
             continue;
         }
         let mut args = Vec::new();
         if pos < len && bytes[pos] == b'(' {
-            let close_p = find_balanced_paren(content, pos);
-            if close_p >= 0 {
-                let args_str = &content[pos + 1..close_p as usize];
+            if let Some(close_p) = find_balanced_paren(content, pos) {
+                let args_str = &content[pos + 1..close_p];
                 args = parse_attr_args_string(args_str);
-                pos = close_p as usize + 1;
+                pos = close_p + 1;
             }
         }
         attrs.push(RawAttribute {
@@ -779,6 +1151,9 @@ fn parse_nested_item(content: &str) -> TokenData {
         data.type_name = field_data.type_name;
         data.type_params = field_data.type_params;
         data.type_generic_params = field_data.type_generic_params;
+        data.union_types = field_data.union_types;
+        data.tuple_types = field_data.tuple_types;
+        data.enum_literals = field_data.enum_literals;
         data.nullable = field_data.nullable;
         data.array = field_data.array;
         data.array_item_nullable = field_data.array_item_nullable;
@@ -787,6 +1162,7 @@ fn parse_nested_item(content: &str) -> TokenData {
         data.description = field_data.description;
         data.framework_attrs = field_data.framework_attrs;
         data.label = field_data.label;
+        data.labels = field_data.labels;
         data.comment = field_data.comment;
     }
 
@@ -811,19 +1187,17 @@ pub fn parse_attr_args_string(s: &str) -> Vec<AttrArgValue> {
         }
 
         if bytes[pos] == b'"' {
-            let close = find_closing_quote(s, pos);
-            if close >= 0 {
-                args.push(AttrArgValue::String(s[pos + 1..close as usize].to_string()));
-                pos = close as usize + 1;
+            if let Some(close) = find_closing_quote(s, pos) {
+                args.push(AttrArgValue::String(s[pos + 1..close].to_string()));
+                pos = close + 1;
             } else {
                 pos += 1;
             }
         } else if bytes[pos] == b'`' {
-            let close = find_closing_backtick(s, pos);
-            if close >= 0 {
+            if let Some(close) = find_closing_backtick(s, pos) {
                 // Include backticks as part of the value
-                args.push(AttrArgValue::String(s[pos..=close as usize].to_string()));
-                pos = close as usize + 1;
+                args.push(AttrArgValue::String(s[pos..=close].to_string()));
+                pos = close + 1;
             } else {
                 pos += 1;
             }
@@ -842,12 +1216,7 @@ pub fn parse_attr_args_string(s: &str) -> Vec<AttrArgValue> {
             let start = pos;
             while pos < len && bytes[pos] != b',' {
                 if bytes[pos] == b'(' {
-                    let close_p = find_balanced_paren(s, pos);
-                    pos = if close_p >= 0 {
-                        close_p as usize + 1
-                    } else {
-                        pos + 1
-                    };
+                    pos = find_balanced_paren(s, pos).map_or(pos + 1, |close_p| close_p + 1);
                 } else {
                     pos += 1;
                 }
@@ -874,9 +1243,15 @@ pub fn parse_attr_args_string(s: &str) -> Vec<AttrArgValue> {
     args
 }
 
+/// Identifier character: a letter or digit in any script, or `_`.
 #[inline]
-fn is_word_char(b: u8) -> bool {
-    b.is_ascii_alphanumeric() || b == b'_'
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Byte length of the identifier at the start of `s`.
+fn word_len(s: &str) -> usize {
+    s.find(|c: char| !is_word_char(c)).unwrap_or(s.len())
 }
 
 #[cfg(test)]
@@ -977,6 +1352,34 @@ mod tests {
         assert_eq!(tokens[0].token_type, TokenType::HorizontalRule);
     }
 
+    #[test]
+    fn lex_frontmatter() {
+        let tokens = lex(
+            "---\r\nnamespace: shop\r\ntags:\r\n  - a\r\n---\r\n## User",
+            "test.m3l.md",
+        );
+        assert_eq!(tokens[0].token_type, TokenType::Frontmatter);
+        let block = tokens[0].data.code_block.as_ref().unwrap();
+        assert_eq!(block.content, "namespace: shop\ntags:\n  - a");
+        assert_eq!(tokens[0].data.folded_end, Some((5, 4)));
+        assert_eq!(tokens[1].token_type, TokenType::Model);
+
+        // Only at the top of the file, closed, and reading as YAML
+        for input in [
+            "\n---\nname: x\n---",
+            "---\nname: x",
+            "---\n## User\n- id: identifier\n---",
+        ] {
+            let tokens = lex(input, "test.m3l.md");
+            assert!(
+                tokens
+                    .iter()
+                    .all(|t| t.token_type != TokenType::Frontmatter),
+                "{input:?}"
+            );
+        }
+    }
+
     #[test]
     fn lex_blockquote() {
         let tokens = lex("> Model description", "test.m3l.md");
@@ -1019,6 +1422,168 @@ mod tests {
         assert_eq!(tokens[0].data.label.as_deref(), Some("Users"));
     }
 
+    #[test]
+    fn lex_localized_labels() {
+        let tokens = lex(
+            "## User(ko: \"사용자\", en: \"User\") : Base\n- name(en: \"Name\", pt-BR: \"Nome, completo\"): string\n- note(Note: see docs): text",
+            "test.m3l.md",
+        );
+        let d = &tokens[0].data;
+        assert_eq!(d.name.as_deref(), Some("User"));
+        assert_eq!(d.label.as_deref(), Some("사용자"));
+        assert_eq!(d.labels["en"], "User");
+        assert_eq!(d.labels["ko"], "사용자");
+        assert_eq!(d.inherits, vec!["Base"]);
+        let d = &tokens[1].data;
+        assert_eq!(d.label.as_deref(), Some("Name"));
+        assert_eq!(d.labels["pt-BR"], "Nome, completo");
+        assert_eq!(d.type_name.as_deref(), Some("string"));
+        // Not every colon makes a locale
+        let d = &tokens[2].data;
+        assert_eq!(d.label.as_deref(), Some("Note: see docs"));
+        assert!(d.labels.is_empty());
+    }
+
+    #[test]
+    fn lex_generic_model() {
+        let tokens = lex(
+            "## Pair<K, V>(Key and value) : Base\n## Box<T> ::interface\n- page: Paged<Product>",
+            "test.m3l.md",
+        );
+        let d = &tokens[0].data;
+        assert_eq!(d.name.as_deref(), Some("Pair"));
+        assert_eq!(d.type_generic_params, vec!["K", "V"]);
+        assert_eq!(d.label.as_deref(), Some("Key and value"));
+        assert_eq!(d.inherits, vec!["Base"]);
+        assert_eq!(tokens[1].token_type, TokenType::Interface);
+        assert_eq!(tokens[1].data.name.as_deref(), Some("Box"));
+        assert_eq!(tokens[1].data.type_generic_params, vec!["T"]);
+        let d = &tokens[2].data;
+        assert_eq!(d.type_name.as_deref(), Some("Paged"));
+        assert_eq!(d.type_generic_params, vec!["Product"]);
+    }
+
+    #[test]
+    fn lex_union_type() {
+        let tokens = lex(
+            "## Setting\n- value: string(50) | integer | Money? = \"0\" @index\n- ids: identifier|long[]\n- left: string |",
+            "test.m3l.md",
+        );
+        let d = &tokens[1].data;
+        assert_eq!(d.type_name.as_deref(), Some("union"));
+        let members: Vec<&str> = d
+            .union_types
+            .iter()
+            .map(|m| m.member_type.as_str())
+            .collect();
+        assert_eq!(members, ["string", "integer", "Money"]);
+        assert_eq!(
+            d.union_types[0].params,
+            Some(vec![ParamValue::Number(50.0)])
+        );
+        assert!(d.type_params.is_empty());
+        assert!(d.nullable);
+        assert_eq!(d.default_value.as_deref(), Some("\"0\""));
+        assert_eq!(d.attributes[0].name, "index");
+        let d = &tokens[2].data;
+        assert_eq!(d.union_types.len(), 2);
+        assert!(d.array);
+        // A trailing bar names no member
+        let d = &tokens[3].data;
+        assert_eq!(d.type_name.as_deref(), Some("string"));
+        assert!(d.union_types.is_empty());
+    }
+
+    #[test]
+    fn lex_untyped_field_attributes() {
+        let tokens = lex(
+            "## V ::view\n- name @from(User.name)\n- email",
+            "test.m3l.md",
+        );
+        let d = &tokens[1].data;
+        assert_eq!(d.name.as_deref(), Some("name"));
+        assert!(d.type_name.is_none());
+        assert_eq!(d.attributes[0].name, "from");
+        assert_eq!(tokens[2].data.name.as_deref(), Some("email"));
+        assert!(tokens[2].data.attributes.is_empty());
+    }
+
+    #[test]
+    fn lex_tuple_type() {
+        let tokens = lex(
+            "## Place\n- point: (float, float?)[] = `[]` @index\n- pair: (map<string, integer>, decimal(10, 2))\n- one: (float)\n- bad: (float, )",
+            "test.m3l.md",
+        );
+        let d = &tokens[1].data;
+        assert_eq!(d.type_name.as_deref(), Some("tuple"));
+        let components: Vec<(&str, bool)> = d
+            .tuple_types
+            .iter()
+            .map(|m| (m.member_type.as_str(), m.nullable))
+            .collect();
+        assert_eq!(components, [("float", false), ("float", true)]);
+        assert!(d.array);
+        assert_eq!(d.default_value.as_deref(), Some("`[]`"));
+        assert_eq!(d.attributes[0].name, "index");
+        let d = &tokens[2].data;
+        assert_eq!(
+            d.tuple_types[0].generic_params,
+            Some(vec!["string".to_string(), "integer".to_string()])
+        );
+        assert_eq!(
+            d.tuple_types[1].params,
+            Some(vec![ParamValue::Number(10.0), ParamValue::Number(2.0)])
+        );
+        // One component, or an empty one, is not a tuple
+        assert!(tokens[3].data.tuple_types.is_empty());
+        assert!(tokens[4].data.tuple_types.is_empty());
+    }
+
+    #[test]
+    fn lex_literal_enum() {
+        let tokens = lex(
+            "## Post\n- status: \"draft\" | \"in review\"|\"published\"? = \"draft\" \"Workflow state\"\n- note: \"Only a description\"",
+            "test.m3l.md",
+        );
+        let d = &tokens[1].data;
+        assert_eq!(d.type_name.as_deref(), Some("enum"));
+        assert_eq!(d.enum_literals, vec!["draft", "in review", "published"]);
+        assert!(d.nullable);
+        assert_eq!(d.default_value.as_deref(), Some("\"draft\""));
+        assert_eq!(d.description.as_deref(), Some("Workflow state"));
+        let d = &tokens[2].data;
+        assert_eq!(d.type_name, None);
+        assert!(d.enum_literals.is_empty());
+        assert_eq!(d.description.as_deref(), Some("Only a description"));
+    }
+
+    #[test]
+    fn lex_extend_header() {
+        let tokens = lex("## extend Auth.User\n## extender\n## extend", "test.m3l.md");
+        assert_eq!(tokens[0].token_type, TokenType::Model);
+        assert!(tokens[0].data.is_extend);
+        assert_eq!(tokens[0].data.name.as_deref(), Some("Auth.User"));
+        // A model may still be called `extend` or `extender`
+        assert!(!tokens[1].data.is_extend);
+        assert!(!tokens[2].data.is_extend);
+        assert_eq!(tokens[2].data.name.as_deref(), Some("extend"));
+    }
+
+    #[test]
+    fn lex_version_pragma() {
+        let tokens = lex(
+            "<!-- m3l: 1.1 -->\n# M3L-Version: 1.0\n<!-- m3l notes -->\n# Version history",
+            "test.m3l.md",
+        );
+        assert_eq!(tokens[0].token_type, TokenType::Version);
+        assert_eq!(tokens[0].data.name.as_deref(), Some("1.1"));
+        assert_eq!(tokens[1].token_type, TokenType::Version);
+        assert_eq!(tokens[1].data.name.as_deref(), Some("1.0"));
+        assert!(tokens[2..]
+            .iter()
+            .all(|t| t.token_type != TokenType::Version));
+    }
+
     #[test]
     fn lex_view_indicator() {
         let tokens = lex("## SalesSummary ::view @materialized", "test.m3l.md");
@@ -1039,6 +1604,21 @@ mod tests {
         assert_eq!(tokens[0].token_type, TokenType::Text);
         assert!(tokens[0].data.is_import);
         assert_eq!(tokens[0].data.import_path.as_deref(), Some("base.m3l.md"));
+        assert_eq!(tokens[0].data.import_symbols, None);
+    }
+
+    #[test]
+    fn lex_selective_import() {
+        let tokens = lex(
+            "@import \"./base.m3l.md\" (BaseModel, Timestampable)",
+            "test.m3l.md",
+        );
+        assert!(tokens[0].data.is_import);
+        assert_eq!(tokens[0].data.import_path.as_deref(), Some("./base.m3l.md"));
+        assert_eq!(
+            tokens[0].data.import_symbols,
+            Some(vec!["BaseModel".to_string(), "Timestampable".to_string()])
+        );
     }
 
     #[test]
@@ -1095,4 +1675,23 @@ mod tests {
         assert_eq!(args[1], AttrArgValue::Number(42.0));
         assert_eq!(args[2], AttrArgValue::Bool(true));
     }
+
+    #[test]
+    fn unclosed_delimiters() {
+        assert_eq!(
+            unclosed_delimiter("name: string(100) \"Name (legal)\""),
+            None
+        );
+        assert_eq!(unclosed_delimiter("status: string = 'draft' @index"), None);
+        assert_eq!(unclosed_delimiter("owner: string \"Owner's name\""), None);
+        assert_eq!(unclosed_delimiter("total: decimal @computed(`a)`)"), None);
+        assert_eq!(unclosed_delimiter("tags: string[]  # don't (sort"), None);
+        assert_eq!(unclosed_delimiter("name: string(100"), Some((12, '(')));
+        assert_eq!(unclosed_delimiter("name: string \"Name"), Some((13, '"')));
+        assert_eq!(
+            unclosed_delimiter("x: decimal @computed(`a + b)"),
+            Some((21, '`'))
+        );
+        assert_eq!(unclosed_delimiter("x: integer @in([1, 2)"), Some((15, '[')));
+    }
 }
@@ -1,15 +1,35 @@
 pub mod catalogs;
+pub mod codes;
+pub mod cst;
+pub mod extract;
 pub mod ffi;
 pub mod lexer;
+pub mod namespaces;
 pub mod parser;
+pub mod project;
 pub mod resolver;
+pub mod search;
+mod sql;
+pub mod symbols;
 pub mod types;
 pub mod validator;
 
-pub use catalogs::{AST_VERSION, PARSER_VERSION};
-pub use ffi::{parse_multi_to_json, parse_to_json, validate_to_json};
+pub use catalogs::{AST_VERSION, PARSER_VERSION, SPEC_VERSION};
+pub use codes::{explain, CodeDoc, CodeKind, CODES};
+pub use cst::{parse_cst, Cst, CstElement, CstLine, CstLineKind, CstNode};
+pub use extract::extract;
+pub use ffi::{
+    explain_to_json, extract_to_json, parse_multi_to_json, parse_to_json, validate_to_json,
+};
 pub use lexer::lex;
-pub use parser::parse_string;
-pub use resolver::{detect_circular_imports, resolve};
+pub use namespaces::DEFAULT_NAMESPACE;
+pub use parser::{parse_relation, parse_string};
+pub use project::{is_m3l_path, Project, ProjectSnapshot};
+pub use resolver::{
+    detect_circular_imports, find_reference_target, find_reference_target_from, import_target,
+    resolve, resolve_with_options,
+};
+pub use search::fuzzy_score;
+pub use symbols::{Symbol, SymbolKind, SymbolTable};
 pub use types::*;
 pub use validator::validate;
@@ -0,0 +1,119 @@
+//! Namespace-grouped output: the AST with its models, enums, interfaces,
+//! views and flows nested under `namespaces: [{name, models, ...}]`
+//! instead of the flat lists.
+
+use std::collections::BTreeMap;
+
+use crate::types::*;
+
+/// The namespace of elements declared outside any `# Namespace:`.
+pub const DEFAULT_NAMESPACE: &str = "_default";
+
+/// The top-level keys `namespaces` replaces.
+const GROUPED_KEYS: [&str; 5] = ["models", "enums", "interfaces", "views", "flows"];
+
+impl M3lAst {
+    /// The elements grouped by namespace: `_default` first, then the
+    /// others by name. Elements keep their AST order within a group.
+    pub fn namespaces(&self) -> Vec<NamespaceGroup> {
+        // Keyed so that `_default` sorts first
+        let mut groups: BTreeMap<(bool, String), NamespaceGroup> = BTreeMap::new();
+        for model in &self.models {
+            group(&mut groups, &model.namespace)
+                .models
+                .push(model.clone());
+        }
+        for node in &self.enums {
+            group(&mut groups, &node.namespace).enums.push(node.clone());
+        }
+        for interface in &self.interfaces {
+            group(&mut groups, &interface.namespace)
+                .interfaces
+                .push(interface.clone());
+        }
+        for view in &self.views {
+            group(&mut groups, &view.namespace).views.push(view.clone());
+        }
+        for flow in &self.flows {
+            group(&mut groups, &flow.namespace).flows.push(flow.clone());
+        }
+        groups.into_values().collect()
+    }
+
+    /// The AST as a JSON value with `namespaces` in place of the flat
+    /// element lists; every other key is serialized as usual.
+    pub fn to_namespaced_value(&self) -> Result<serde_json::Value, String> {
+        let mut value =
+            serde_json::to_value(self).map_err(|e| format!("JSON serialization error: {e}"))?;
+        let namespaces = serde_json::to_value(self.namespaces())
+            .map_err(|e| format!("JSON serialization error: {e}"))?;
+        let object = value
+            .as_object_mut()
+            .ok_or("JSON serialization error: AST is not an object")?;
+        for key in GROUPED_KEYS {
+            object.remove(key);
+        }
+        object.insert("namespaces".to_string(), namespaces);
+        Ok(value)
+    }
+}
+
+fn group<'a>(
+    groups: &'a mut BTreeMap<(bool, String), NamespaceGroup>,
+    namespace: &Option<String>,
+) -> &'a mut NamespaceGroup {
+    let name = namespace.as_deref().unwrap_or(DEFAULT_NAMESPACE);
+    groups
+        .entry((name != DEFAULT_NAMESPACE, name.to_string()))
+        .or_insert_with(|| NamespaceGroup {
+            name: name.to_string(),
+            models: Vec::new(),
+            enums: Vec::new(),
+            interfaces: Vec::new(),
+            views: Vec::new(),
+            flows: Vec::new(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_string, resolve};
+
+    #[test]
+    fn group_by_namespace() {
+        let input = "\
+## Setting
+- key: string
+
+# Namespace: shop
+
+## Product
+- id: identifier @pk
+
+## Status ::enum
+- active
+
+# Namespace: auth
+
+## User
+- id: identifier @pk
+";
+        let ast = resolve(&[parse_string(input, "app.m3l.md")], None);
+        let namespaces = ast.namespaces();
+        let groups: Vec<(&str, usize, usize)> = namespaces
+            .iter()
+            .map(|g| (g.name.as_str(), g.models.len(), g.enums.len()))
+            .collect();
+        assert_eq!(groups, [("_default", 1, 0), ("auth", 1, 0), ("shop", 1, 1)]);
+
+        let value = ast.to_namespaced_value().unwrap();
+        let object = value.as_object().unwrap();
+        assert!(!object.contains_key("models"));
+        assert!(!object.contains_key("enums"));
+        assert!(object.contains_key("parserVersion"));
+        assert_eq!(value["namespaces"][2]["models"][0]["name"], "Product");
+        assert_eq!(value["namespaces"][2]["enums"][0]["name"], "Status");
+        // Empty lists are left out of a group
+        assert!(value["namespaces"][0].get("enums").is_none());
+    }
+}
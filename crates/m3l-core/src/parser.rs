@@ -3,18 +3,24 @@
 #![allow(clippy::field_reassign_with_default)]
 
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::LazyLock;
 
-use crate::catalogs::STANDARD_ATTRIBUTES;
-use crate::lexer::{lex, parse_type_and_attrs};
+use crate::catalogs::{SPEC_VERSION, STANDARD_ATTRIBUTES};
+use crate::lexer::{attribute_range, lex, line_end_col, parse_type_and_attrs, unclosed_delimiter};
+use crate::sql::source_tables;
 use crate::types::*;
 
 static RE_QUOTE_STR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"^"(.*)"$"#).unwrap());
 static RE_CUSTOM_ATTR: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^([A-Za-z_][\w.]*)(?:\((.+)\))?$").unwrap());
+    LazyLock::new(|| Regex::new(r"^([\p{L}_][\w.]*)(?:\((.+)\))?$").unwrap());
 static RE_AGG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\w+)(?:\((\w+)\))?$").unwrap());
 static RE_WHERE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"^where:\s*"(.*)"$"#).unwrap());
+static RE_ENDPOINT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^(?i:(GET|POST|PUT|PATCH|DELETE|HEAD|OPTIONS))\s+(/\S*)(?:\s+"([^"]*)")?$"#)
+        .unwrap()
+});
+static RE_PATH_PARAM: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{(\w+)\}").unwrap());
 static RE_PLATFORM: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r#"platform\s*:\s*["']?([^"'\s]+)["']?"#).unwrap());
 
@@ -35,6 +41,7 @@ struct AttrDef {
 struct ParserState {
     file: String,
     namespace: Option<String>,
+    current_namespace: Option<String>, // namespace of elements declared from here on
     current_element: CurrentElement,
     current_section: Option<String>,
     current_kind: FieldKind,
@@ -44,11 +51,33 @@ struct ParserState {
     interfaces: Vec<ModelNode>,
     views: Vec<ModelNode>,
     flows: Vec<ModelNode>,
-    extensions: HashMap<String, Vec<ModelNode>>,
+    extensions: BTreeMap<String, Vec<ModelNode>>,
     attribute_registry: Vec<AttributeRegistryEntry>,
+    extend_blocks: Vec<ModelNode>,
+    // The current model is an `## extend` block
+    extending: bool,
     current_attr_def: Option<AttrDef>,
     source_directives_done: bool,
-    imports: Vec<String>,
+    imports: Vec<ImportDirective>,
+    /// Lines of the current element, for the ranges of it and its fields.
+    element_lines: Vec<LineExtent>,
+    diagnostics: Vec<Diagnostic>,
+    metadata: Option<FileMetadata>,
+    // Header row of the `### Seed` table being read
+    seed_columns: Option<Vec<String>>,
+    // Open `### Metadata` entries: the indent and key of each, outermost first
+    metadata_path: Vec<(usize, String)>,
+    m3l_version: Option<M3lVersion>,
+    // The declared version as (major, minor); `None` reads the newest syntax
+    syntax_version: Option<(u32, u32)>,
+}
+
+/// A token's place in the current element: its start line and indent and
+/// where its text ends.
+struct LineExtent {
+    line: usize,
+    indent: usize,
+    end: (usize, usize),
 }
 
 /// Parse M3L content string into a ParsedFile AST.
@@ -62,6 +91,7 @@ pub fn parse_tokens(tokens: &[Token], file: &str) -> ParsedFile {
     let mut state = ParserState {
         file: file.to_string(),
         namespace: None,
+        current_namespace: None,
         current_element: CurrentElement::None,
         current_section: None,
         current_kind: FieldKind::Stored,
@@ -71,11 +101,20 @@ pub fn parse_tokens(tokens: &[Token], file: &str) -> ParsedFile {
         interfaces: Vec::new(),
         views: Vec::new(),
         flows: Vec::new(),
-        extensions: HashMap::new(),
+        extensions: BTreeMap::new(),
         attribute_registry: Vec::new(),
+        extend_blocks: Vec::new(),
+        extending: false,
         current_attr_def: None,
         source_directives_done: false,
         imports: Vec::new(),
+        element_lines: Vec::new(),
+        diagnostics: Vec::new(),
+        metadata: None,
+        seed_columns: None,
+        metadata_path: Vec::new(),
+        m3l_version: None,
+        syntax_version: None,
     };
 
     for token in tokens {
@@ -94,11 +133,16 @@ pub fn parse_tokens(tokens: &[Token], file: &str) -> ParsedFile {
         flows: state.flows,
         extensions: state.extensions,
         attribute_registry: state.attribute_registry,
+        extend_blocks: state.extend_blocks,
         imports: state.imports,
+        diagnostics: state.diagnostics,
+        metadata: state.metadata,
+        m3l_version: state.m3l_version,
     }
 }
 
 fn process_token(token: &Token, state: &mut ParserState) {
+    check_syntax(token, state);
     match &token.token_type {
         TokenType::Namespace => handle_namespace(token, state),
         TokenType::Model | TokenType::Interface => handle_model_start(token, state),
@@ -112,48 +156,447 @@ fn process_token(token: &Token, state: &mut ParserState) {
         TokenType::NestedItem => handle_nested_item(token, state),
         TokenType::Blockquote => handle_blockquote(token, state),
         TokenType::Text => handle_text(token, state),
+        TokenType::Frontmatter => handle_frontmatter(token, state),
+        TokenType::Version => handle_version(token, state),
         TokenType::HorizontalRule | TokenType::Blank => {}
     }
+
+    let in_element = !matches!(state.current_element, CurrentElement::None);
+    let content = !matches!(
+        token.token_type,
+        TokenType::Namespace
+            | TokenType::HorizontalRule
+            | TokenType::Blank
+            | TokenType::Frontmatter
+            | TokenType::Version
+    );
+    if in_element && content && !token.data.is_import {
+        state.element_lines.push(LineExtent {
+            line: token.line,
+            indent: token.indent,
+            end: token_end(token),
+        });
+    }
+}
+
+/// Type indicators the parser knows; any other word is an extension kind.
+const TYPE_INDICATORS: &[&str] = &["enum", "interface", "view", "flow", "attribute"];
+
+/// Report lines the lexer could only read by guessing: unclosed quotes,
+/// backticks and brackets (M3L-P001), type indicators that are not a word
+/// or look like a misspelt built-in one (M3L-P002), and list items that do
+/// not parse as a field or enum value (M3L-P003).
+fn check_syntax(token: &Token, state: &mut ParserState) {
+    let raw = token.raw.as_str();
+    let content_start = match token.token_type {
+        TokenType::Field | TokenType::NestedItem => raw.find("- ").map_or(0, |i| i + 2),
+        TokenType::Model
+        | TokenType::Enum
+        | TokenType::Interface
+        | TokenType::View
+        | TokenType::Flow
+        | TokenType::Extension(_)
+        | TokenType::AttributeDef => 3,
+        _ => return,
+    };
+    let content = raw.get(content_start..).unwrap_or_default();
+    let mut report = |code: &str, severity: DiagnosticSeverity, at: usize, message: String| {
+        state.diagnostics.push(Diagnostic {
+            code: code.into(),
+            severity,
+            file: state.file.clone(),
+            line: token.line,
+            col: raw[..content_start + at].chars().count() + 1,
+            message,
+        });
+    };
+
+    if let Some((at, delimiter)) = unclosed_delimiter(content) {
+        let what = match delimiter {
+            '"' => "quote",
+            '\'' => "single quote",
+            '`' => "backtick",
+            '(' => "parenthesis",
+            '[' => "bracket",
+            _ => "brace",
+        };
+        report(
+            "M3L-P001",
+            DiagnosticSeverity::Error,
+            at,
+            format!("Unclosed {what}"),
+        );
+        return;
+    }
+
+    if token.token_type == TokenType::NestedItem {
+        return;
+    }
+    if token.token_type == TokenType::Field {
+        let name = token.data.name.as_deref().unwrap_or_default();
+        let expected = match &state.current_element {
+            _ if token.data.is_directive || state.current_attr_def.is_some() => None,
+            CurrentElement::Enum(_) if name.contains(char::is_whitespace) => {
+                Some("an enum value (`- NAME \"description\"`)")
+            }
+            CurrentElement::Model(_)
+                if matches!(state.current_section.as_deref(), Some("Endpoints" | "API"))
+                    && parse_endpoint(content, token_loc(&state.file, token)).is_none() =>
+            {
+                Some("an endpoint (`- METHOD /path`)")
+            }
+            CurrentElement::Model(model)
+                if matches!(
+                    model.model_type,
+                    ModelType::Model | ModelType::Interface | ModelType::View
+                ) && (state.current_section.is_none()
+                    || state.current_section.as_deref() == Some("Source"))
+                    && !name.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+            {
+                Some("a field (`- name: type`)")
+            }
+            _ => None,
+        };
+        if let Some(expected) = expected {
+            report(
+                "M3L-P003",
+                DiagnosticSeverity::Error,
+                0,
+                format!("Cannot parse \"{}\" as {expected}", content.trim()),
+            );
+        }
+        return;
+    }
+
+    // Type indicator, up to the description
+    let header = content.split('"').next().unwrap_or_default();
+    if let Some(at) = header.find("::") {
+        let indicator = header[at + 2..]
+            .split(char::is_whitespace)
+            .next()
+            .unwrap_or_default();
+        if TYPE_INDICATORS.contains(&indicator) {
+            return;
+        }
+        let lower = indicator.to_lowercase();
+        let builtin = TYPE_INDICATORS
+            .iter()
+            .find(|t| **t == lower || lower.strip_suffix('s') == Some(**t));
+        let message = match builtin {
+            Some(builtin) => {
+                format!("Unknown type indicator \"::{indicator}\"; did you mean \"::{builtin}\"?")
+            }
+            None if indicator.is_empty() => "Missing type indicator after \"::\"".to_string(),
+            None if !indicator.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+                format!("Unknown type indicator \"::{indicator}\"")
+            }
+            // A custom kind, kept under `extensions`
+            None => return,
+        };
+        report("M3L-P002", DiagnosticSeverity::Warning, at, message);
+    }
+}
+
+/// Where a token's text ends, including lines folded into it.
+fn token_end(token: &Token) -> (usize, usize) {
+    token
+        .data
+        .folded_end
+        .unwrap_or((token.line, line_end_col(&token.raw)))
+}
+
+/// Location of a token's text. Elements and fields are widened to their
+/// nested lines when the element is finalized.
+fn token_loc(file: &str, token: &Token) -> SourceLocation {
+    let (end_line, end_col) = token_end(token);
+    SourceLocation {
+        file: file.to_string(),
+        line: token.line,
+        col: 1,
+        end_line,
+        end_col,
+    }
+}
+
+/// Widen `loc` to the lines after its own that are indented deeper, as
+/// nested items and folded lines of a field are.
+fn widen_to_nested(loc: &mut SourceLocation, lines: &[LineExtent]) {
+    let Some(start) = lines.iter().position(|l| l.line == loc.line) else {
+        return;
+    };
+    let indent = lines[start].indent;
+    let mut end = lines[start].end;
+    for line in lines[start + 1..].iter().take_while(|l| l.indent > indent) {
+        end = end.max(line.end);
+    }
+    (loc.end_line, loc.end_col) = end;
+}
+
+fn widen_fields(fields: &mut [FieldNode], lines: &[LineExtent]) {
+    for field in fields {
+        widen_to_nested(&mut field.loc, lines);
+        if let Some(sub_fields) = field.fields.as_mut() {
+            widen_fields(sub_fields, lines);
+        }
+    }
 }
 
 fn handle_namespace(token: &Token, state: &mut ParserState) {
-    if matches!(state.current_element, CurrentElement::None) {
+    // A namespace header closes the current element; the first one is the file namespace
+    finalize_element(state);
+    if state.namespace.is_none() {
         state.namespace = token.data.name.clone();
     }
+    state.current_namespace = token.data.name.clone();
+}
+
+/// Read the frontmatter into `FileMetadata`. Its `namespace` is the file
+/// namespace; keys without a field of their own go to `metadata`.
+fn handle_frontmatter(token: &Token, state: &mut ParserState) {
+    let raw = token
+        .data
+        .code_block
+        .as_ref()
+        .map(|cb| cb.content.clone())
+        .unwrap_or_default();
+    let mut metadata = FileMetadata {
+        source: state.file.clone(),
+        ..Default::default()
+    };
+    for (key, value) in parse_frontmatter(&raw) {
+        let text = match &value {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        };
+        match key.as_str() {
+            "namespace" if text.is_some() => metadata.namespace = text,
+            "owner" if text.is_some() => metadata.owner = text,
+            "version" if text.is_some() => metadata.version = text,
+            "tags" => {
+                metadata.tags = match value {
+                    serde_json::Value::Array(items) => items
+                        .iter()
+                        .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string))
+                        .collect(),
+                    _ => text.into_iter().collect(),
+                }
+            }
+            _ => {
+                metadata.metadata.insert(key, value);
+            }
+        }
+    }
+    metadata.raw = raw;
+    if metadata.namespace.is_some() {
+        state.namespace = metadata.namespace.clone();
+        state.current_namespace = metadata.namespace.clone();
+    }
+    state.metadata = Some(metadata);
+}
+
+/// Top-level `key: value` pairs of a frontmatter block. Values are scalars,
+/// `[a, b]` flow lists, or `- item` lists under an empty key; nested maps
+/// are skipped.
+fn parse_frontmatter(yaml: &str) -> Vec<(String, serde_json::Value)> {
+    let mut pairs: Vec<(String, serde_json::Value)> = Vec::new();
+    // Key whose value is the `- item` lines that follow
+    let mut list_key: Option<usize> = None;
+    for line in yaml.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix('-'))
+        {
+            if let Some(idx) = list_key {
+                if let serde_json::Value::Array(items) = &mut pairs[idx].1 {
+                    items.push(yaml_scalar(item.trim()));
+                }
+            }
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            list_key = None;
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = strip_yaml_comment(value.trim());
+        if value.is_empty() {
+            pairs.push((key.trim().to_string(), serde_json::Value::Array(Vec::new())));
+            list_key = Some(pairs.len() - 1);
+        } else {
+            pairs.push((key.trim().to_string(), yaml_value(value)));
+            list_key = None;
+        }
+    }
+    pairs
+}
+
+fn strip_yaml_comment(value: &str) -> &str {
+    if value.starts_with(['"', '\'']) {
+        return value;
+    }
+    value.split(" #").next().unwrap_or(value).trim_end()
+}
+
+fn yaml_value(value: &str) -> serde_json::Value {
+    match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        Some(items) => serde_json::Value::Array(
+            items
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(yaml_scalar)
+                .collect(),
+        ),
+        None => yaml_scalar(value),
+    }
+}
+
+fn yaml_scalar(value: &str) -> serde_json::Value {
+    let quoted = (value.len() >= 2)
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')));
+    if quoted {
+        return serde_json::Value::String(value[1..value.len() - 1].to_string());
+    }
+    match value {
+        "true" => serde_json::Value::Bool(true),
+        "false" => serde_json::Value::Bool(false),
+        "null" | "~" => serde_json::Value::Null,
+        // Only integers become numbers, so versions like `1.10` keep their
+        // spelling
+        _ => match value.parse::<i64>() {
+            Ok(n) => serde_json::Value::from(n),
+            Err(_) => serde_json::Value::String(value.to_string()),
+        },
+    }
+}
+
+/// A version pragma. The first one counts: an unreadable version, or one
+/// newer than the parser reads, is reported as M3L-P004, and the file is
+/// then read with the newest syntax.
+fn handle_version(token: &Token, state: &mut ParserState) {
+    if state.m3l_version.is_some() {
+        return;
+    }
+    let version = token.data.name.clone().unwrap_or_default();
+    let message = match parse_version(&version) {
+        None => Some(format!(
+            "M3L version \"{version}\" is not a MAJOR.MINOR version"
+        )),
+        Some(declared) if Some(declared) > parse_version(SPEC_VERSION) => Some(format!(
+            "File requires M3L {version}, but this parser reads M3L {SPEC_VERSION} and older"
+        )),
+        Some(declared) => {
+            state.syntax_version = Some(declared);
+            None
+        }
+    };
+    if let Some(message) = message {
+        state.diagnostics.push(Diagnostic {
+            code: "M3L-P004".into(),
+            severity: DiagnosticSeverity::Error,
+            file: state.file.clone(),
+            line: token.line,
+            col: 1,
+            message,
+        });
+    }
+    state.m3l_version = Some(M3lVersion {
+        source: state.file.clone(),
+        version,
+        raw: token.raw.clone(),
+    });
+}
+
+/// `1.1` as (1, 1); a patch number is ignored and a missing minor is 0.
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |m| m.parse().ok())?;
+    match parts.next() {
+        Some(patch) if patch.parse::<u32>().is_err() || parts.next().is_some() => None,
+        _ => Some((major, minor)),
+    }
+}
+
+/// Whether syntax added in M3L `since` can be used here. Files that declare
+/// an older version do not get it, and the line is reported as M3L-P005.
+fn syntax_available(state: &mut ParserState, token: &Token, what: &str, since: (u32, u32)) -> bool {
+    let Some(declared) = state.syntax_version.filter(|&v| v < since) else {
+        return true;
+    };
+    state.diagnostics.push(Diagnostic {
+        code: "M3L-P005".into(),
+        severity: DiagnosticSeverity::Error,
+        file: state.file.clone(),
+        line: token.line,
+        col: 1,
+        message: format!(
+            "{what} need M3L {}.{}, but this file declares M3L {}.{}; the block is skipped",
+            since.0, since.1, declared.0, declared.1
+        ),
+    });
+    false
 }
 
 fn handle_model_start(token: &Token, state: &mut ParserState) {
     finalize_element(state);
 
-    let model_attrs = parse_raw_attributes(&token.data.attributes);
+    let model_attrs = parse_raw_attributes(&token.data.attributes, &state.file, token);
     let model_type = if token.token_type == TokenType::Interface {
         ModelType::Interface
     } else {
         ModelType::Model
     };
 
+    let aliases = model_attrs
+        .iter()
+        .filter(|a| a.name == "alias")
+        .flat_map(|a| a.args.iter().flatten())
+        .filter_map(|arg| match arg {
+            AttrArgValue::String(s) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        })
+        .collect();
+
     let model = ModelNode {
         name: token.data.name.clone().unwrap_or_default(),
         label: token.data.label.clone(),
+        labels: token.data.labels.clone(),
         model_type,
         source: state.file.clone(),
+        namespace: state.current_namespace.clone(),
         line: token.line,
         inherits: token.data.inherits.clone(),
+        aliases,
         description: None,
+        doc: None,
+        deprecated: None,
         attributes: model_attrs,
         fields: Vec::new(),
         sections: Sections::default(),
         materialized: None,
         source_def: None,
         refresh: None,
-        loc: SourceLocation {
-            file: state.file.clone(),
-            line: token.line,
-            col: 1,
-        },
+        generic_params: token.data.type_generic_params.clone(),
+        instance_of: None,
+        generic_args: Vec::new(),
+        default_inherits: Vec::new(),
+        loc: token_loc(&state.file, token),
     };
 
+    if token.data.is_extend && !syntax_available(state, token, "`## extend` blocks", (1, 1)) {
+        return;
+    }
     state.current_element = CurrentElement::Model(Box::new(model));
+    state.extending = token.data.is_extend;
     state.current_section = None;
     state.current_kind = FieldKind::Stored;
     state.last_field_idx = None;
@@ -166,17 +609,17 @@ fn handle_enum_start(token: &Token, state: &mut ParserState) {
     let enum_node = EnumNode {
         name: token.data.name.clone().unwrap_or_default(),
         label: token.data.label.clone(),
+        labels: token.data.labels.clone(),
         enum_type: ModelType::Enum,
         source: state.file.clone(),
+        namespace: state.current_namespace.clone(),
         line: token.line,
         inherits: token.data.inherits.clone(),
         description: token.data.description.clone(),
+        doc: None,
+        deprecated: None,
         values: Vec::new(),
-        loc: SourceLocation {
-            file: state.file.clone(),
-            line: token.line,
-            col: 1,
-        },
+        loc: token_loc(&state.file, token),
     };
 
     state.current_element = CurrentElement::Enum(Box::new(enum_node));
@@ -192,22 +635,27 @@ fn handle_view_start(token: &Token, state: &mut ParserState) {
     let view = ModelNode {
         name: token.data.name.clone().unwrap_or_default(),
         label: token.data.label.clone(),
+        labels: token.data.labels.clone(),
         model_type: ModelType::View,
         source: state.file.clone(),
+        namespace: state.current_namespace.clone(),
         line: token.line,
         inherits: Vec::new(),
+        aliases: Vec::new(),
         description: None,
+        doc: None,
+        deprecated: None,
         attributes: Vec::new(),
         materialized: Some(materialized),
         fields: Vec::new(),
         sections: Sections::default(),
         source_def: None,
         refresh: None,
-        loc: SourceLocation {
-            file: state.file.clone(),
-            line: token.line,
-            col: 1,
-        },
+        generic_params: token.data.type_generic_params.clone(),
+        instance_of: None,
+        generic_args: Vec::new(),
+        default_inherits: Vec::new(),
+        loc: token_loc(&state.file, token),
     };
 
     state.current_element = CurrentElement::Model(Box::new(view));
@@ -223,22 +671,27 @@ fn handle_flow_start(token: &Token, state: &mut ParserState) {
     let flow = ModelNode {
         name: token.data.name.clone().unwrap_or_default(),
         label: token.data.label.clone(),
+        labels: token.data.labels.clone(),
         model_type: ModelType::Flow,
         source: state.file.clone(),
+        namespace: state.current_namespace.clone(),
         line: token.line,
         inherits: Vec::new(),
+        aliases: Vec::new(),
         description: None,
-        attributes: parse_raw_attributes(&token.data.attributes),
+        doc: None,
+        deprecated: None,
+        attributes: parse_raw_attributes(&token.data.attributes, &state.file, token),
         fields: Vec::new(),
         sections: Sections::default(),
         materialized: None,
         source_def: None,
         refresh: None,
-        loc: SourceLocation {
-            file: state.file.clone(),
-            line: token.line,
-            col: 1,
-        },
+        generic_params: token.data.type_generic_params.clone(),
+        instance_of: None,
+        generic_args: Vec::new(),
+        default_inherits: Vec::new(),
+        loc: token_loc(&state.file, token),
     };
 
     state.current_element = CurrentElement::Model(Box::new(flow));
@@ -254,22 +707,27 @@ fn handle_extension_start(token: &Token, ext_type: &str, state: &mut ParserState
     let node = ModelNode {
         name: token.data.name.clone().unwrap_or_default(),
         label: token.data.label.clone(),
+        labels: token.data.labels.clone(),
         model_type: ModelType::Extension(ext_type.to_string()),
         source: state.file.clone(),
+        namespace: state.current_namespace.clone(),
         line: token.line,
         inherits: Vec::new(),
+        aliases: Vec::new(),
         description: None,
-        attributes: parse_raw_attributes(&token.data.attributes),
+        doc: None,
+        deprecated: None,
+        attributes: parse_raw_attributes(&token.data.attributes, &state.file, token),
         fields: Vec::new(),
         sections: Sections::default(),
         materialized: None,
         source_def: None,
         refresh: None,
-        loc: SourceLocation {
-            file: state.file.clone(),
-            line: token.line,
-            col: 1,
-        },
+        generic_params: token.data.type_generic_params.clone(),
+        instance_of: None,
+        generic_args: Vec::new(),
+        default_inherits: Vec::new(),
+        loc: token_loc(&state.file, token),
     };
 
     state.current_element = CurrentElement::Model(Box::new(node));
@@ -301,6 +759,8 @@ fn handle_section(token: &Token, state: &mut ParserState) {
     }
 
     state.current_section = Some(section_name.clone());
+    state.seed_columns = None;
+    state.metadata_path.clear();
     state.last_field_idx = None;
 
     // View Source SQL block
@@ -320,6 +780,17 @@ fn handle_section(token: &Token, state: &mut ParserState) {
                     });
                     sd.raw_sql = Some(cb.content.clone());
                     sd.language_hint = cb.language.clone();
+                    if cb
+                        .language
+                        .as_deref()
+                        .is_some_and(|l| l.eq_ignore_ascii_case("sql"))
+                    {
+                        let (from, joins) = source_tables(&cb.content);
+                        sd.from = sd.from.take().or(from);
+                        if sd.joins.is_none() && !joins.is_empty() {
+                            sd.joins = Some(joins);
+                        }
+                    }
                 }
             }
         }
@@ -346,6 +817,7 @@ fn handle_field(token: &Token, state: &mut ParserState) {
                 description: token.data.description.clone(),
                 value_type: None,
                 value: None,
+                loc: Some(token_loc(&state.file, token)),
             };
 
             if let Some(ref type_name) = token.data.type_name {
@@ -380,6 +852,17 @@ fn handle_field(token: &Token, state: &mut ParserState) {
                 return;
             }
 
+            // Metadata entries, which later nested items can extend
+            if state.current_section.as_deref() == Some("Metadata") {
+                let content = token.raw.trim().trim_start_matches("- ");
+                let (name, value) = metadata_item(content);
+                let name = name.unwrap_or(content).to_string();
+                let value = value.unwrap_or_else(|| serde_json::Value::String(String::new()));
+                model.sections.metadata.insert(name.clone(), value);
+                state.metadata_path = vec![(0, name)];
+                return;
+            }
+
             // Section-specific items
             if let Some(ref section) = state.current_section {
                 let section = section.clone();
@@ -398,6 +881,7 @@ fn handle_field(token: &Token, state: &mut ParserState) {
 
             // Regular field
             let field = build_field_node(&token.data, token, &state.file, &state.current_kind);
+            model.sections.checks.extend(field_checks(&field));
             model.fields.push(field);
             state.last_field_idx = Some(model.fields.len() - 1);
         }
@@ -410,11 +894,6 @@ fn handle_directive(data: &TokenData, model: &mut ModelNode, token: &Token, file
     }
 
     let attr = &data.attributes[0];
-    let loc = serde_json::json!({
-        "file": file,
-        "line": token.line,
-        "col": 1
-    });
 
     let raw_content = token.raw.trim().to_string();
     let args_val = if !attr.args.is_empty() {
@@ -423,57 +902,91 @@ fn handle_directive(data: &TokenData, model: &mut ModelNode, token: &Token, file
         None
     };
 
-    if attr.name == "index" || attr.name == "unique" {
-        let mut entry = serde_json::Map::new();
-        entry.insert("type".into(), serde_json::json!("directive"));
-        entry.insert("raw".into(), serde_json::json!(raw_content));
-        if let Some(ref a) = args_val {
-            entry.insert("args".into(), a.clone());
+    if attr.name == "natural_key" {
+        let fields = attr
+            .args
+            .iter()
+            .filter_map(|a| match a {
+                AttrArgValue::String(s) => Some(s.trim().to_string()),
+                _ => None,
+            })
+            .filter(|s| !s.is_empty())
+            .collect();
+        model.sections.keys.push(KeyDef {
+            name: None,
+            fields,
+            description: trailing_description(&raw_content),
+            loc: token_loc(file, token),
+        });
+    } else if attr.name == "check" {
+        model.sections.checks.push(CheckDef {
+            expression: check_expression(&attr.args),
+            field: None,
+            description: trailing_description(&raw_content),
+            loc: token_loc(file, token),
+        });
+    } else if attr.name == "index" || attr.name == "unique" {
+        let mut index = IndexDef {
+            name: None,
+            label: None,
+            fields: Vec::new(),
+            unique: attr.name == "unique",
+            where_clause: None,
+            method: None,
+            options: serde_json::Map::new(),
+            description: trailing_description(&raw_content),
+            raw: Some(raw_content.clone()),
+            loc: token_loc(file, token),
+        };
+        for arg in &attr.args {
+            let AttrArgValue::String(arg) = arg else {
+                continue;
+            };
+            match arg.split_once(':') {
+                Some((key, value)) => {
+                    set_index_property(&mut index, key.trim(), parse_nested_value(value))
+                }
+                None => index.fields.push(arg.trim().to_string()),
+            }
         }
-        entry.insert("unique".into(), serde_json::json!(attr.name == "unique"));
-        entry.insert("loc".into(), loc);
-        model
-            .sections
-            .indexes
-            .push(serde_json::Value::Object(entry));
+        model.sections.indexes.push(index);
     } else if attr.name == "relation" {
-        let mut entry = serde_json::Map::new();
-        entry.insert("type".into(), serde_json::json!("directive"));
-        entry.insert("raw".into(), serde_json::json!(raw_content));
-        if let Some(ref a) = args_val {
-            entry.insert("args".into(), a.clone());
-        }
-        entry.insert("loc".into(), loc);
+        let raw = raw_content.trim_start_matches("- ");
         model
             .sections
             .relations
-            .push(serde_json::Value::Object(entry));
-    } else {
-        let mut section_name = attr.name.clone();
-        if section_name == "behavior" {
-            section_name = "behaviors".to_string();
+            .push(parse_relation(raw, token_loc(file, token)));
+    } else if attr.name == "behavior" {
+        let raw = raw_content.trim_start_matches("- ");
+        let mut args = attr.args.iter().filter_map(|a| match a {
+            AttrArgValue::String(s) => Some(s.trim()),
+            _ => None,
+        });
+        let mut behavior = new_behavior(args.next().unwrap_or(""), raw, token_loc(file, token));
+        behavior.description = trailing_description(&raw_content);
+        for arg in args {
+            match arg.split_once(':') {
+                Some((key, value)) => {
+                    set_behavior_property(&mut behavior, key.trim(), parse_nested_value(value))
+                }
+                None => behavior.action = Some(arg.to_string()),
+            }
         }
-
+        model.sections.behaviors.push(behavior);
+    } else {
         let mut entry = serde_json::Map::new();
         entry.insert("raw".into(), serde_json::json!(raw_content));
         if let Some(ref a) = args_val {
             entry.insert("args".into(), a.clone());
         }
 
-        if section_name == "behaviors" {
-            model
-                .sections
-                .behaviors
-                .push(serde_json::Value::Object(entry));
-        } else {
-            let section = model
-                .sections
-                .custom
-                .entry(section_name)
-                .or_insert_with(|| serde_json::json!([]));
-            if let serde_json::Value::Array(ref mut arr) = section {
-                arr.push(serde_json::Value::Object(entry));
-            }
+        let section = model
+            .sections
+            .custom
+            .entry(attr.name.clone())
+            .or_insert_with(|| serde_json::json!([]));
+        if let serde_json::Value::Array(ref mut arr) = section {
+            arr.push(serde_json::Value::Object(entry));
         }
     }
 }
@@ -488,11 +1001,7 @@ fn handle_section_item(
     source_directives_done: &mut bool,
     last_field_idx: &mut Option<usize>,
 ) {
-    let loc = serde_json::json!({
-        "file": file,
-        "line": token.line,
-        "col": 1
-    });
+    let loc = serde_json::json!(token_loc(file, token));
 
     // View Source section
     if section == "Source" && model.model_type == ModelType::View {
@@ -508,7 +1017,15 @@ fn handle_section_item(
                 raw_sql: None,
                 language_hint: None,
             });
-            set_source_directive(sd, data);
+            if name == "join" {
+                // The lexer stops an unquoted value at the first space; a
+                // join reads the whole line
+                let mut data = data.clone();
+                data.description = raw_item_value(token);
+                set_source_directive(sd, &data);
+            } else {
+                set_source_directive(sd, data);
+            }
             return;
         }
 
@@ -524,79 +1041,92 @@ fn handle_section_item(
         let refresh = model.refresh.get_or_insert(RefreshDef {
             strategy: String::new(),
             interval: None,
+            schedule: None,
         });
         let name = data.name.clone().unwrap_or_default();
-        let type_name = data.type_name.clone();
-        let desc = data.description.clone();
         if name == "strategy" {
-            refresh.strategy = type_name.unwrap_or_default();
+            refresh.strategy = data.type_name.clone().unwrap_or_default();
         } else if name == "interval" {
-            refresh.interval = Some(desc.or(type_name).unwrap_or_default());
+            // Unquoted cron expressions and "6 hours" contain spaces
+            let interval = raw_item_value(token).unwrap_or_default();
+            refresh.schedule = parse_refresh_schedule(&interval).ok();
+            refresh.interval = Some(interval);
         }
         return;
     }
 
     // Indexes section
     if section == "Indexes" {
-        let mut entry = serde_json::Map::new();
-        entry.insert(
-            "name".into(),
-            serde_json::json!(data.name.clone().unwrap_or_default()),
-        );
-        if let Some(ref label) = data.label {
-            entry.insert("label".into(), serde_json::json!(label));
-        }
-        entry.insert("loc".into(), loc);
-        model
-            .sections
-            .indexes
-            .push(serde_json::Value::Object(entry));
+        model.sections.indexes.push(IndexDef {
+            name: Some(data.name.clone().unwrap_or_default()),
+            label: data.label.clone(),
+            fields: Vec::new(),
+            unique: false,
+            where_clause: None,
+            method: None,
+            options: serde_json::Map::new(),
+            description: data.description.clone(),
+            raw: None,
+            loc: token_loc(file, token),
+        });
         *last_field_idx = Some(usize::MAX); // sentinel for index
         return;
     }
 
+    // Keys section
+    if section == "Keys" {
+        model.sections.keys.push(KeyDef {
+            name: data.name.clone(),
+            fields: Vec::new(),
+            description: data.description.clone().or_else(|| data.label.clone()),
+            loc: token_loc(file, token),
+        });
+        *last_field_idx = Some(usize::MAX); // sentinel for key
+        return;
+    }
+
     // Relations section
     if section == "Relations" {
-        let raw = token.raw.trim().trim_start_matches("- ").to_string();
-        let mut entry = serde_json::Map::new();
-        entry.insert("raw".into(), serde_json::json!(raw));
-        entry.insert("loc".into(), loc);
+        let raw = token.raw.trim().trim_start_matches("- ");
         model
             .sections
             .relations
-            .push(serde_json::Value::Object(entry));
+            .push(parse_relation(raw, token_loc(file, token)));
         *last_field_idx = Some(usize::MAX); // sentinel
         return;
     }
 
-    // Metadata section
-    if section == "Metadata" {
-        let name = data.name.clone().unwrap_or_default();
-        let value = data
-            .type_name
-            .clone()
-            .or_else(|| data.description.clone())
-            .unwrap_or_default();
-        model
-            .sections
-            .metadata
-            .insert(name, parse_metadata_value(&value));
+    // Behaviors section
+    if section == "Behaviors" {
+        let raw = token.raw.trim().trim_start_matches("- ");
+        let name = raw.split_once(':').map_or(raw, |(name, _)| name);
+        let mut behavior = new_behavior(name, raw, token_loc(file, token));
+        // The lexer stops an unquoted value at the first space; an action
+        // reads the whole line
+        behavior.action = raw_item_value(token).filter(|a| !a.is_empty());
+        model.sections.behaviors.push(behavior);
+        *last_field_idx = Some(usize::MAX); // sentinel for behavior options
         return;
     }
 
-    // Behaviors section
-    if section == "Behaviors" {
-        let mut entry = serde_json::Map::new();
-        entry.insert(
-            "name".into(),
-            serde_json::json!(data.name.clone().unwrap_or_default()),
-        );
-        entry.insert("raw".into(), serde_json::json!(token.raw.trim()));
-        entry.insert("loc".into(), loc);
-        model
-            .sections
-            .behaviors
-            .push(serde_json::Value::Object(entry));
+    // Endpoints section; unreadable lines are reported as M3L-P003
+    if section == "Endpoints" || section == "API" {
+        let content = token.raw.trim().trim_start_matches('-').trim_start();
+        if let Some(endpoint) = parse_endpoint(content, token_loc(file, token)) {
+            model.sections.endpoints.push(endpoint);
+        }
+        *last_field_idx = Some(usize::MAX); // sentinel for endpoint options
+        return;
+    }
+
+    // Seed section: `- name` starts a row
+    if section == "Seed" {
+        model.sections.seeds.push(SeedRow {
+            name: data.name.clone(),
+            values: serde_json::Map::new(),
+            loc: token_loc(file, token),
+        });
+        *last_field_idx = Some(usize::MAX); // sentinel for seed row
         return;
     }
 
@@ -638,6 +1168,7 @@ fn handle_nested_item(token: &Token, state: &mut ParserState) {
                     description: None,
                     value_type: None,
                     value: None,
+                    loc: Some(token_loc(&state.file, token)),
                 };
                 if let Some(v) = value {
                     if let Some(caps) = RE_QUOTE_STR.captures(v) {
@@ -653,26 +1184,69 @@ fn handle_nested_item(token: &Token, state: &mut ParserState) {
             // Nested items under index
             if state.current_section.as_deref() == Some("Indexes") && state.last_field_idx.is_some()
             {
-                if let Some(k) = key {
-                    if let Some(last) = model.sections.indexes.last_mut() {
-                        if let serde_json::Value::Object(ref mut obj) = last {
-                            obj.insert(k.to_string(), parse_nested_value(value.unwrap_or("")));
+                if let (Some(k), Some(index)) = (key, model.sections.indexes.last_mut()) {
+                    set_index_property(index, k, parse_nested_value(value.unwrap_or("")));
+                }
+                return;
+            }
+
+            // Nested `fields` list under a key
+            if state.current_section.as_deref() == Some("Keys") && state.last_field_idx.is_some() {
+                if key == Some("fields") {
+                    if let Some(last) = model.sections.keys.last_mut() {
+                        if let serde_json::Value::Array(items) =
+                            parse_nested_value(value.unwrap_or(""))
+                        {
+                            last.fields = items
+                                .iter()
+                                .filter_map(|v| v.as_str())
+                                .map(str::to_string)
+                                .collect();
                         }
                     }
                 }
                 return;
             }
 
+            // Options under an endpoint
+            if matches!(state.current_section.as_deref(), Some("Endpoints" | "API")) {
+                if let (Some(k), Some(endpoint)) = (key, model.sections.endpoints.last_mut()) {
+                    endpoint
+                        .options
+                        .insert(k.to_string(), parse_nested_value(value.unwrap_or("")));
+                }
+                return;
+            }
+
+            // `- field: value` under a seed row
+            if state.current_section.as_deref() == Some("Seed") {
+                if let (Some(k), Some(row)) = (key, model.sections.seeds.last_mut()) {
+                    row.values
+                        .insert(k.to_string(), seed_value(value.unwrap_or("")));
+                }
+                return;
+            }
+
+            // Nested `### Metadata` objects and lists
+            if state.current_section.as_deref() == Some("Metadata") {
+                add_metadata_item(token, &mut state.metadata_path, model);
+                return;
+            }
+
+            // `- action:`, `- condition:` under a behavior
+            if state.current_section.as_deref() == Some("Behaviors") {
+                if let (Some(k), Some(behavior)) = (key, model.sections.behaviors.last_mut()) {
+                    set_behavior_property(behavior, k, parse_nested_value(value.unwrap_or("")));
+                }
+                return;
+            }
+
             // Nested items under relation
             if state.current_section.as_deref() == Some("Relations")
                 && state.last_field_idx.is_some()
             {
-                if let Some(k) = key {
-                    if let Some(last) = model.sections.relations.last_mut() {
-                        if let serde_json::Value::Object(ref mut obj) = last {
-                            obj.insert(k.to_string(), parse_nested_value(value.unwrap_or("")));
-                        }
-                    }
+                if let (Some(k), Some(relation)) = (key, model.sections.relations.last_mut()) {
+                    set_relation_property(relation, k, parse_nested_value(value.unwrap_or("")));
                 }
                 return;
             }
@@ -681,6 +1255,7 @@ fn handle_nested_item(token: &Token, state: &mut ParserState) {
             if state.last_field_idx == Some(usize::MAX) {
                 let section_name = state.current_section.as_deref().unwrap_or("");
                 if section_name != "Indexes"
+                    && section_name != "Keys"
                     && section_name != "Relations"
                     && !section_name.is_empty()
                 {
@@ -721,6 +1296,7 @@ fn handle_nested_item(token: &Token, state: &mut ParserState) {
                                 description: None,
                                 value_type: None,
                                 value: None,
+                                loc: Some(token_loc(&state.file, token)),
                             };
                             if let Some(v) = value {
                                 if let Some(caps) = RE_QUOTE_STR.captures(v) {
@@ -750,6 +1326,7 @@ fn handle_nested_item(token: &Token, state: &mut ParserState) {
                                     description: None,
                                     value_type: None,
                                     value: None,
+                                    loc: Some(token_loc(&state.file, token)),
                                 };
                                 if let Some(v) = value {
                                     if let Some(caps) = RE_QUOTE_STR.captures(v) {
@@ -803,10 +1380,16 @@ fn handle_nested_item(token: &Token, state: &mut ParserState) {
 
                     // Extended format field attributes
                     if let Some(k) = key {
+                        // The attribute is the whole nested line
+                        let loc = SourceLocation {
+                            col: token.indent + 1,
+                            ..token_loc(&state.file, token)
+                        };
                         apply_extended_attribute(
                             &mut model.fields[field_idx],
                             k,
                             value.unwrap_or(""),
+                            loc,
                         );
                     }
                     return;
@@ -877,12 +1460,21 @@ fn handle_text(token: &Token, state: &mut ParserState) {
     // Collect import directives
     if token.data.is_import {
         if let Some(ref path) = token.data.import_path {
-            state.imports.push(path.clone());
+            state.imports.push(ImportDirective {
+                path: path.clone(),
+                symbols: token.data.import_symbols.clone(),
+                line: token.line,
+            });
         }
         return;
     }
 
     if let CurrentElement::Model(ref mut model) = state.current_element {
+        if state.current_section.as_deref() == Some("Seed") && token.raw.trim().starts_with('|') {
+            let row = token_loc(&state.file, token);
+            seed_table_row(token.raw.trim(), row, &mut state.seed_columns, model);
+            return;
+        }
         if model.fields.is_empty() {
             let text = token.data.name.clone().unwrap_or_default();
             if !text.is_empty() && model.description.is_none() {
@@ -895,9 +1487,37 @@ fn handle_text(token: &Token, state: &mut ParserState) {
 fn finalize_element(state: &mut ParserState) {
     finalize_attr_def(state);
 
-    let element = std::mem::replace(&mut state.current_element, CurrentElement::None);
+    let mut element = std::mem::replace(&mut state.current_element, CurrentElement::None);
+    let lines = std::mem::take(&mut state.element_lines);
+    let last = lines.iter().map(|l| l.end).max();
+    match &mut element {
+        CurrentElement::Model(model) => {
+            if let Some(end) = last {
+                (model.loc.end_line, model.loc.end_col) = end;
+            }
+            widen_fields(&mut model.fields, &lines);
+            model.doc = take_doc_tags(&mut model.description);
+            model.deprecated = deprecation(&model.attributes, model.doc.as_ref());
+            take_field_doc_tags(&mut model.fields);
+        }
+        CurrentElement::Enum(en) => {
+            if let Some(end) = last {
+                (en.loc.end_line, en.loc.end_col) = end;
+            }
+            en.doc = take_doc_tags(&mut en.description);
+            en.deprecated = deprecation(&[], en.doc.as_ref());
+            for value in &mut en.values {
+                if let Some(loc) = value.loc.as_mut() {
+                    widen_to_nested(loc, &lines);
+                }
+            }
+        }
+        CurrentElement::None => {}
+    }
+    let extending = std::mem::take(&mut state.extending);
     match element {
         CurrentElement::Enum(en) => state.enums.push(*en),
+        CurrentElement::Model(model) if extending => state.extend_blocks.push(*model),
         CurrentElement::Model(model) => match &model.model_type {
             ModelType::Interface => state.interfaces.push(*model),
             ModelType::View => state.views.push(*model),
@@ -919,10 +1539,112 @@ fn finalize_element(state: &mut ParserState) {
     state.last_field_idx = None;
 }
 
-fn handle_attribute_def_start(token: &Token, state: &mut ParserState) {
-    finalize_element(state);
+fn take_field_doc_tags(fields: &mut [FieldNode]) {
+    for field in fields {
+        field.doc = take_doc_tags(&mut field.description);
+        field.deprecated = deprecation(&field.attributes, field.doc.as_ref());
+        if let Some(ref mut sub_fields) = field.fields {
+            take_field_doc_tags(sub_fields);
+        }
+    }
+}
 
-    let name = token
+/// Move `@since`, `@deprecated`, `@example` and `@see` lines out of a
+/// description. A tag runs until the next one, so an example can span
+/// several lines; the text before the first tag stays the description.
+fn take_doc_tags(description: &mut Option<String>) -> Option<DocTags> {
+    let text = description.as_deref()?;
+    if !text.lines().any(|line| doc_tag(line).is_some()) {
+        return None;
+    }
+    let mut doc = DocTags::default();
+    let mut kept: Vec<&str> = Vec::new();
+    let mut current: Option<(&str, String)> = None;
+    for line in text.lines() {
+        if let Some((tag, rest)) = doc_tag(line) {
+            if let Some((tag, value)) = current.take() {
+                add_doc_tag(&mut doc, tag, value);
+            }
+            current = Some((tag, rest.to_string()));
+        } else if let Some((tag, value)) = current.as_mut() {
+            if !value.is_empty() {
+                value.push(if *tag == "example" { '\n' } else { ' ' });
+            }
+            value.push_str(line.trim());
+        } else {
+            kept.push(line);
+        }
+    }
+    if let Some((tag, value)) = current {
+        add_doc_tag(&mut doc, tag, value);
+    }
+    *description = (!kept.is_empty()).then(|| kept.join("\n"));
+    Some(doc)
+}
+
+/// The doc tag a description line starts with, and the text after it.
+fn doc_tag(line: &str) -> Option<(&'static str, &str)> {
+    let rest = line.trim_start().strip_prefix('@')?;
+    ["since", "deprecated", "example", "see"]
+        .into_iter()
+        .find_map(|tag| {
+            let after = rest.strip_prefix(tag)?;
+            (after.is_empty() || after.starts_with(char::is_whitespace))
+                .then(|| (tag, after.trim()))
+        })
+}
+
+fn add_doc_tag(doc: &mut DocTags, tag: &str, value: String) {
+    let value = (!value.is_empty()).then_some(value);
+    match tag {
+        "since" => doc.since = value,
+        "deprecated" => {
+            doc.deprecated = true;
+            doc.deprecation = value;
+        }
+        "example" => doc.examples.extend(value),
+        _ => doc.see.extend(value),
+    }
+}
+
+/// The deprecation an `@deprecated("...", since: "2.0")` attribute
+/// declares, or else the one an `@deprecated` doc tag does.
+fn deprecation(attrs: &[FieldAttribute], doc: Option<&DocTags>) -> Option<Deprecation> {
+    let mut deprecation = match attrs.iter().find(|a| a.name == "deprecated") {
+        Some(attr) => {
+            let mut deprecation = Deprecation::default();
+            for arg in attr.args.iter().flatten() {
+                let AttrArgValue::String(arg) = arg else {
+                    continue;
+                };
+                match arg.split_once(':').map(|(k, v)| (k.trim(), v.trim())) {
+                    Some(("since", v)) => deprecation.since = Some(v.to_string()),
+                    Some(("replacement", v)) => deprecation.replacement = Some(v.to_string()),
+                    _ => deprecation.message = Some(arg.clone()),
+                }
+            }
+            deprecation
+        }
+        None => Deprecation {
+            message: doc.filter(|d| d.deprecated)?.deprecation.clone(),
+            ..Deprecation::default()
+        },
+    };
+    if deprecation.replacement.is_none() {
+        // "use Customer.email", "Use Status."
+        deprecation.replacement = deprecation.message.as_deref().and_then(|m| {
+            let rest = m.strip_prefix("use ").or_else(|| m.strip_prefix("Use "))?;
+            let name = rest.trim().trim_end_matches('.');
+            (!name.is_empty() && !name.contains(char::is_whitespace)).then(|| name.to_string())
+        });
+    }
+    Some(deprecation)
+}
+
+fn handle_attribute_def_start(token: &Token, state: &mut ParserState) {
+    finalize_element(state);
+
+    let name = token
         .data
         .name
         .clone()
@@ -968,6 +1690,24 @@ fn finalize_attr_def(state: &mut ParserState) {
         }
     });
 
+    let values = attr_def.fields.get("values").map(|v| {
+        v.trim_start_matches('[')
+            .trim_end_matches(']')
+            .split(',')
+            .map(|s| s.trim().trim_matches('"').to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    });
+
+    let pattern = attr_def.fields.get("pattern").map(|p| {
+        let p = p.trim();
+        p.strip_prefix('"')
+            .and_then(|p| p.strip_suffix('"'))
+            .or_else(|| p.strip_prefix('`').and_then(|p| p.strip_suffix('`')))
+            .unwrap_or(p)
+            .to_string()
+    });
+
     let required = attr_def
         .fields
         .get("required")
@@ -1000,6 +1740,8 @@ fn finalize_attr_def(state: &mut ParserState) {
             .cloned()
             .unwrap_or_else(|| "boolean".to_string()),
         range,
+        values,
+        pattern,
         required,
         default_value,
     };
@@ -1015,7 +1757,7 @@ fn build_field_node(
     file: &str,
     current_kind: &FieldKind,
 ) -> FieldNode {
-    let attrs = parse_raw_attributes(&data.attributes);
+    let attrs = parse_raw_attributes(&data.attributes, file, token);
     let mut kind = current_kind.clone();
 
     // Detect kind from attributes
@@ -1053,6 +1795,7 @@ fn build_field_node(
     let mut field = FieldNode {
         name: data.name.clone().unwrap_or_default(),
         label: data.label.clone(),
+        labels: data.labels.clone(),
         field_type: data.type_name.clone(),
         params,
         generic_params,
@@ -1060,21 +1803,41 @@ fn build_field_node(
         array: data.array,
         array_item_nullable: data.array_item_nullable,
         kind,
+        default_json: match default_value_type {
+            Some(DefaultValueType::Json) => default_value
+                .as_deref()
+                .and_then(|v| serde_json::from_str(v).ok()),
+            _ => None,
+        },
         default_value,
         default_value_type,
         description: data.description.clone(),
+        doc: None,
+        deprecated: None,
         attributes: attrs.clone(),
         framework_attrs,
+        reference: None,
+        from: None,
         lookup: None,
         rollup: None,
         computed: None,
-        enum_values: None,
+        enum_values: (!data.enum_literals.is_empty()).then(|| {
+            data.enum_literals
+                .iter()
+                .map(|name| EnumValue {
+                    name: name.clone(),
+                    description: None,
+                    value_type: None,
+                    value: None,
+                    loc: Some(token_loc(file, token)),
+                })
+                .collect()
+        }),
+        union: (!data.union_types.is_empty()).then(|| data.union_types.clone()),
+        tuple: (!data.tuple_types.is_empty()).then(|| data.tuple_types.clone()),
+        type_alias: None,
         fields: None,
-        loc: SourceLocation {
-            file: file.to_string(),
-            line: token.line,
-            col: 1,
-        },
+        loc: token_loc(file, token),
     };
 
     // Parse lookup
@@ -1194,6 +1957,8 @@ fn process_default_value(raw: Option<&str>) -> (Option<String>, Option<DefaultVa
                     Some(v[1..v.len() - 1].to_string()),
                     Some(DefaultValueType::Expression),
                 )
+            } else if v.starts_with('[') || v.starts_with('{') {
+                (Some(v.to_string()), Some(DefaultValueType::Json))
             } else if v.starts_with('"') && v.ends_with('"') && v.len() >= 2 {
                 (
                     Some(v[1..v.len() - 1].to_string()),
@@ -1211,10 +1976,29 @@ fn process_default_value(raw: Option<&str>) -> (Option<String>, Option<DefaultVa
     }
 }
 
-fn parse_raw_attributes(raw_attrs: &[RawAttribute]) -> Vec<FieldAttribute> {
+fn parse_raw_attributes(
+    raw_attrs: &[RawAttribute],
+    file: &str,
+    token: &Token,
+) -> Vec<FieldAttribute> {
+    // Attributes are found in the line in order, each after the previous one
+    let mut cursor = 0;
     raw_attrs
         .iter()
         .map(|a| {
+            let loc = attribute_range(&token.raw, cursor, &a.name, a.cascade.is_some()).map(
+                |(start, end)| {
+                    cursor = end;
+                    let col = |byte: usize| token.raw[..byte].chars().count() + 1;
+                    SourceLocation {
+                        file: file.to_string(),
+                        line: token.line,
+                        col: col(start),
+                        end_line: token.line,
+                        end_col: col(end),
+                    }
+                },
+            );
             let args = if a.args.is_empty() {
                 None
             } else {
@@ -1231,6 +2015,7 @@ fn parse_raw_attributes(raw_attrs: &[RawAttribute]) -> Vec<FieldAttribute> {
                 cascade: a.cascade.clone(),
                 is_standard,
                 is_registered: None,
+                loc,
             }
         })
         .collect()
@@ -1344,6 +2129,10 @@ fn split_computed_raw_args(raw: &str) -> (String, Option<String>) {
             }
             i += 1;
         }
+        if i >= bytes.len() {
+            // Unterminated quote: keep the whole string as the expression
+            return (raw.to_string(), None);
+        }
         let expression = raw[..=i].to_string();
         let remainder = raw[i + 1..].trim();
         let platform = RE_PLATFORM.captures(remainder).map(|c| c[1].to_string());
@@ -1378,17 +2167,381 @@ fn set_source_directive(def: &mut ViewSourceDef, data: &TokenData) {
     }
 }
 
+/// `[inner|left|right|full|cross [outer] join] Model [[as] alias] [on condition]`.
 fn parse_join_value(value: &str) -> JoinDef {
-    let parts: Vec<&str> = value.splitn(2, " on ").collect();
+    let (head, on) = match value.to_ascii_lowercase().find(" on ") {
+        Some(i) => (&value[..i], value[i + 4..].trim()),
+        None => (value, ""),
+    };
+    let mut words: Vec<&str> = head.split_whitespace().collect();
+    let mut kind = None;
+    let join_at = words.iter().position(|w| w.eq_ignore_ascii_case("join"));
+    if let Some(at) = join_at {
+        let prefix: Vec<String> = words[..at].iter().map(|w| w.to_ascii_lowercase()).collect();
+        let prefix: Vec<&str> = prefix.iter().map(String::as_str).collect();
+        kind = match prefix.as_slice() {
+            [] => None,
+            ["inner"] => Some(JoinKind::Inner),
+            ["left"] | ["left", "outer"] => Some(JoinKind::Left),
+            ["right"] | ["right", "outer"] => Some(JoinKind::Right),
+            ["full"] | ["full", "outer"] => Some(JoinKind::Full),
+            ["cross"] => Some(JoinKind::Cross),
+            // Not a join keyword sequence; read it as written
+            _ => return plain_join(head, on),
+        };
+        words.drain(..=at);
+    }
+    if words.get(1).is_some_and(|w| w.eq_ignore_ascii_case("as")) {
+        words.remove(1);
+    }
+    match words.as_slice() {
+        [model] => JoinDef {
+            model: model.to_string(),
+            on: on.to_string(),
+            kind,
+            alias: None,
+        },
+        [model, alias] => JoinDef {
+            model: model.to_string(),
+            on: on.to_string(),
+            kind,
+            alias: Some(alias.to_string()),
+        },
+        _ => plain_join(head, on),
+    }
+}
+
+fn plain_join(model: &str, on: &str) -> JoinDef {
     JoinDef {
-        model: parts
-            .first()
-            .map(|s| s.trim().to_string())
-            .unwrap_or_default(),
-        on: parts
-            .get(1)
-            .map(|s| s.trim().to_string())
-            .unwrap_or_default(),
+        model: model.trim().to_string(),
+        on: on.to_string(),
+        kind: None,
+        alias: None,
+    }
+}
+
+/// The value of a `- name: value` item as written, without surrounding
+/// quotes. The lexer stops an unquoted value at the first space.
+fn raw_item_value(token: &Token) -> Option<String> {
+    token.raw.split_once(':').map(|(_, v)| {
+        let v = v.trim();
+        v.strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(v)
+            .to_string()
+    })
+}
+
+/// Parse a view refresh interval: a duration (`30s`, `5m`, `6 hours`,
+/// `daily`), `daily HH:MM`, or a five-field cron expression.
+pub(crate) fn parse_refresh_schedule(text: &str) -> Result<RefreshSchedule, String> {
+    let lower = text.trim().to_ascii_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+    if words.len() == 5 {
+        return parse_cron(&words).map(|()| RefreshSchedule::Cron {
+            expression: words.join(" "),
+        });
+    }
+    if let ["daily", time] = words.as_slice() {
+        let (hour, minute) = time
+            .split_once(':')
+            .and_then(|(h, m)| Some((h.parse::<u32>().ok()?, m.parse::<u32>().ok()?)))
+            .filter(|&(h, m)| h < 24 && m < 60)
+            .ok_or_else(|| format!("\"{time}\" is not a time of day (HH:MM)"))?;
+        return Ok(RefreshSchedule::Cron {
+            expression: format!("{minute} {hour} * * *"),
+        });
+    }
+    let seconds = match words.as_slice() {
+        ["hourly"] => 3600,
+        ["daily"] => 86_400,
+        ["weekly"] => 604_800,
+        _ => {
+            let compact = words.concat();
+            let split = compact
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(compact.len());
+            let (count, unit) = compact.split_at(split);
+            let unit_seconds = match unit {
+                "s" | "sec" | "secs" | "second" | "seconds" => 1,
+                "m" | "min" | "mins" | "minute" | "minutes" => 60,
+                "h" | "hr" | "hrs" | "hour" | "hours" => 3600,
+                "d" | "day" | "days" => 86_400,
+                "w" | "week" | "weeks" => 604_800,
+                _ => {
+                    return Err(format!(
+                        "\"{text}\" is neither a duration (5m, 6 hours) nor a five-field cron expression"
+                    ))
+                }
+            };
+            let count: u64 = count
+                .parse()
+                .map_err(|_| format!("\"{text}\" has no number before \"{unit}\""))?;
+            count * unit_seconds
+        }
+    };
+    if seconds == 0 {
+        return Err(format!("\"{text}\" is not a positive duration"));
+    }
+    Ok(RefreshSchedule::Interval { seconds })
+}
+
+/// Check each field of `minute hour day-of-month month day-of-week`:
+/// `*`, numbers, ranges `a-b`, lists `a,b` and steps `*/n`, `a-b/n`.
+fn parse_cron(fields: &[&str]) -> Result<(), String> {
+    const FIELDS: [(&str, u32, u32); 5] = [
+        ("minute", 0, 59),
+        ("hour", 0, 23),
+        ("day of month", 1, 31),
+        ("month", 1, 12),
+        ("day of week", 0, 7),
+    ];
+    for (field, (label, min, max)) in fields.iter().zip(FIELDS) {
+        for item in field.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (item, None),
+            };
+            if step.is_some_and(|s| s.parse::<u32>().map_or(true, |n| n == 0)) {
+                return Err(format!("cron {label} \"{item}\" has an invalid step"));
+            }
+            if range == "*" {
+                continue;
+            }
+            let (lo, hi) = range.split_once('-').unwrap_or((range, range));
+            for bound in [lo, hi] {
+                match bound.parse::<u32>() {
+                    Ok(n) if (min..=max).contains(&n) => {}
+                    _ => return Err(format!("cron {label} \"{item}\" is outside {min}-{max}")),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply a nested `- key: value` item, or a named directive argument, to an
+/// index. Keys other than the ones `IndexDef` has go to `options`.
+fn set_index_property(index: &mut IndexDef, key: &str, value: serde_json::Value) {
+    let text = || match &value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    match key {
+        "fields" | "columns" => {
+            index.fields = match &value {
+                serde_json::Value::Array(items) => items
+                    .iter()
+                    .map(|i| i.as_str().map_or_else(|| i.to_string(), str::to_string))
+                    .collect(),
+                _ => vec![text()],
+            }
+        }
+        "unique" => index.unique = value == serde_json::Value::Bool(true),
+        "where" => index.where_clause = Some(text()),
+        "method" => index.method = Some(text()),
+        "name" => index.name = Some(text()),
+        _ => {
+            index.options.insert(key.to_string(), value);
+        }
+    }
+}
+
+/// Parse a relation without its list marker: `>author`, `<>tags:
+/// many-to-many`, `category: >Category via category_id (optional)`,
+/// `orders > Order via customer_id, one-to-many` or an `@relation(name,
+/// -> Target, from: field)` line. Nested items are applied afterwards with
+/// [`set_relation_property`].
+pub fn parse_relation(raw: &str, loc: SourceLocation) -> RelationDef {
+    let mut relation = RelationDef {
+        name: String::new(),
+        direction: None,
+        target: None,
+        from_field: None,
+        cardinality: None,
+        optional: false,
+        on_delete: None,
+        options: serde_json::Map::new(),
+        description: None,
+        raw: raw.to_string(),
+        loc,
+    };
+    let mut body = raw.trim();
+    if let Some(args) = body.strip_prefix("@relation(") {
+        relation.description = trailing_description(body);
+        let args = args.rsplit_once(')').map_or(args, |(args, _)| args);
+        for (i, arg) in split_balanced(args).iter().map(|a| a.trim()).enumerate() {
+            if let Some((direction, target)) = split_direction(arg) {
+                relation.direction = Some(direction);
+                set_relation_target(&mut relation, target);
+            } else if let Some((key, value)) = arg.split_once(':') {
+                set_relation_property(&mut relation, key.trim(), parse_nested_value(value));
+            } else if i == 0 {
+                relation.name = arg.to_string();
+            }
+        }
+    } else {
+        // `(optional)` and `(load: eager)` after the entry
+        while body.ends_with(')') {
+            let Some(open) = body.rfind('(') else {
+                break;
+            };
+            let group = &body[open + 1..body.len() - 1];
+            body = body[..open].trim_end();
+            for item in split_balanced(group) {
+                match item.split_once(':') {
+                    Some((key, value)) => {
+                        set_relation_property(&mut relation, key.trim(), parse_nested_value(value))
+                    }
+                    None if item.trim() == "optional" => relation.optional = true,
+                    None => {}
+                }
+            }
+        }
+        if let Some((head, tail)) = body.rsplit_once(',') {
+            if let Some(cardinality) = parse_cardinality(tail) {
+                relation.cardinality = Some(cardinality);
+                body = head.trim_end();
+            }
+        }
+        if let Some((direction, rest)) = split_direction(body) {
+            // `>author`, `<>tags: many-to-many`, `>team: Team via team_id`
+            relation.direction = Some(direction);
+            let (name, after) = rest.split_once(':').unwrap_or((rest, ""));
+            relation.name = name.trim().to_string();
+            match parse_cardinality(after) {
+                Some(cardinality) => relation.cardinality = Some(cardinality),
+                None if !after.trim().is_empty() => set_relation_target(&mut relation, after),
+                None => {}
+            }
+        } else if let Some((name, rest)) = body.split_once(':') {
+            // `category: >Category via category_id`, `tags: many-to-many`
+            relation.name = name.trim().to_string();
+            if let Some((direction, target)) = split_direction(rest.trim()) {
+                relation.direction = Some(direction);
+                set_relation_target(&mut relation, target);
+            } else if let Some(cardinality) = parse_cardinality(rest) {
+                relation.cardinality = Some(cardinality);
+            }
+        } else {
+            // `orders > Order via customer_id`, `tags`
+            let (name, rest) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+            relation.name = name.to_string();
+            if let Some((direction, target)) = split_direction(rest.trim()) {
+                relation.direction = Some(direction);
+                set_relation_target(&mut relation, target);
+            }
+        }
+    }
+    if relation.cardinality.is_none() {
+        relation.cardinality = relation.direction.map(|d| match d {
+            RelationDirection::To => Cardinality::ManyToOne,
+            RelationDirection::From => Cardinality::OneToMany,
+            RelationDirection::Both => Cardinality::ManyToMany,
+        });
+    }
+    relation
+}
+
+/// Apply a nested `- key: value` item, a named `@relation` argument or an
+/// `(key: value)` group to a relation. Keys other than the ones
+/// `RelationDef` has go to `options`.
+fn set_relation_property(relation: &mut RelationDef, key: &str, value: serde_json::Value) {
+    let text = match &value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    match key {
+        "target" => relation.target = Some(text),
+        "from" => relation.from_field = Some(text),
+        "on_delete" => relation.on_delete = Some(text),
+        "optional" => relation.optional = value == serde_json::Value::Bool(true),
+        "cardinality" if parse_cardinality(&text).is_some() => {
+            relation.cardinality = parse_cardinality(&text)
+        }
+        _ => {
+            relation.options.insert(key.to_string(), value);
+        }
+    }
+}
+
+/// `<>`, `<-`/`<`, `->`/`>` at the start of `text`, and what follows.
+fn split_direction(text: &str) -> Option<(RelationDirection, &str)> {
+    [
+        ("<>", RelationDirection::Both),
+        ("<-", RelationDirection::From),
+        ("->", RelationDirection::To),
+        ("<", RelationDirection::From),
+        (">", RelationDirection::To),
+    ]
+    .into_iter()
+    .find_map(|(marker, direction)| {
+        text.strip_prefix(marker)
+            .map(|rest| (direction, rest.trim_start()))
+    })
+}
+
+/// `Target` or `Target via field`.
+fn set_relation_target(relation: &mut RelationDef, text: &str) {
+    let mut words = text.split_whitespace();
+    relation.target = words.next().map(str::to_string);
+    if words.next() == Some("via") {
+        relation.from_field = words.next().map(str::to_string);
+    }
+}
+
+fn parse_cardinality(text: &str) -> Option<Cardinality> {
+    match text.trim().to_ascii_lowercase().as_str() {
+        "one-to-one" => Some(Cardinality::OneToOne),
+        "one-to-many" => Some(Cardinality::OneToMany),
+        "many-to-one" => Some(Cardinality::ManyToOne),
+        "many-to-many" => Some(Cardinality::ManyToMany),
+        _ => None,
+    }
+}
+
+/// A behavior named `before_create`, `after update` or `on create`, with
+/// its timing split off.
+fn new_behavior(name: &str, raw: &str, loc: SourceLocation) -> BehaviorDef {
+    let name = name.trim();
+    let (timing, event) = [
+        ("before", BehaviorTiming::Before),
+        ("after", BehaviorTiming::After),
+        ("on", BehaviorTiming::On),
+    ]
+    .into_iter()
+    .find_map(|(prefix, timing)| {
+        let rest = name.strip_prefix(prefix)?;
+        let event = rest.strip_prefix(['_', ' '])?.trim();
+        (!event.is_empty()).then_some((Some(timing), event))
+    })
+    .unwrap_or((None, name));
+    BehaviorDef {
+        name: name.to_string(),
+        timing,
+        event: event.to_string(),
+        action: None,
+        condition: None,
+        options: serde_json::Map::new(),
+        description: None,
+        raw: raw.to_string(),
+        loc,
+    }
+}
+
+/// Apply a nested `- key: value` item or a named `@behavior` argument to a
+/// behavior. Keys other than `action` and `condition` go to `options`.
+fn set_behavior_property(behavior: &mut BehaviorDef, key: &str, value: serde_json::Value) {
+    let text = match &value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    match key {
+        "action" => behavior.action = Some(text),
+        "condition" => behavior.condition = Some(text),
+        _ => {
+            behavior.options.insert(key.to_string(), value);
+        }
     }
 }
 
@@ -1401,6 +2554,154 @@ fn parse_array_value(value: &str) -> Vec<String> {
         .collect()
 }
 
+/// `METHOD /path "description"`, ignoring an inline `# comment`.
+fn parse_endpoint(content: &str, loc: SourceLocation) -> Option<EndpointDef> {
+    let content = content.split(" # ").next().unwrap_or_default().trim();
+    let caps = RE_ENDPOINT.captures(content)?;
+    let path = caps[2].to_string();
+    Some(EndpointDef {
+        method: caps[1].to_ascii_uppercase(),
+        params: RE_PATH_PARAM
+            .captures_iter(&path)
+            .map(|c| c[1].to_string())
+            .collect(),
+        path,
+        description: caps.get(3).map(|d| d.as_str().to_string()),
+        options: serde_json::Map::new(),
+        loc,
+    })
+}
+
+/// One line of a `### Seed` table: the header row names the columns, the
+/// `|---|` rule is skipped, and each later row is a seed row. Empty cells
+/// leave the field unset.
+fn seed_table_row(
+    line: &str,
+    loc: SourceLocation,
+    columns: &mut Option<Vec<String>>,
+    model: &mut ModelNode,
+) {
+    let inner = line.trim_start_matches('|').trim_end_matches('|');
+    let cells: Vec<&str> = inner.split('|').map(str::trim).collect();
+    if cells
+        .iter()
+        .all(|c| !c.is_empty() && c.trim_matches(':').chars().all(|ch| ch == '-'))
+    {
+        return;
+    }
+    let Some(columns) = columns else {
+        *columns = Some(cells.iter().map(|c| c.to_string()).collect());
+        return;
+    };
+    let values = columns
+        .iter()
+        .zip(&cells)
+        .filter(|(_, cell)| !cell.is_empty())
+        .map(|(column, cell)| (column.clone(), seed_value(cell)))
+        .collect();
+    model.sections.seeds.push(SeedRow {
+        name: None,
+        values,
+        loc,
+    });
+}
+
+/// A seed value: quoted strings, numbers, booleans and `null`; anything
+/// else is a string as written.
+fn seed_value(value: &str) -> serde_json::Value {
+    match value.trim() {
+        "null" => serde_json::Value::Null,
+        value => parse_metadata_value(value),
+    }
+}
+
+/// Split a `### Metadata` item into its key and value: `key: value`,
+/// `key:` (no value yet), or a bare list value. Quoted values stay
+/// strings, `[a, b]` is a list, and an inline `# comment` is ignored.
+fn metadata_item(content: &str) -> (Option<&str>, Option<serde_json::Value>) {
+    let content = content.split(" # ").next().unwrap_or_default().trim();
+    let value = |text: &str| {
+        let text = text.trim();
+        match text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            Some(items) => serde_json::Value::Array(
+                items
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|i| !i.is_empty())
+                    .map(parse_metadata_value)
+                    .collect(),
+            ),
+            None => parse_metadata_value(text),
+        }
+    };
+    let is_key = |key: &str| {
+        !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    };
+    if let Some(key) = content.strip_suffix(':').filter(|k| is_key(k)) {
+        return (Some(key), None);
+    }
+    match content.split_once(": ") {
+        Some((key, text)) if is_key(key.trim_end()) => (Some(key.trim_end()), Some(value(text))),
+        _ => (None, Some(value(content))),
+    }
+}
+
+/// A nested item under a `### Metadata` entry. `key: value` items make
+/// their parent an object and bare items make it a list; a `key:` item
+/// opens a level of its own for the items indented under it.
+fn add_metadata_item(token: &Token, path: &mut Vec<(usize, String)>, model: &mut ModelNode) {
+    while path
+        .last()
+        .is_some_and(|(indent, _)| *indent >= token.indent)
+    {
+        path.pop();
+    }
+    let Some(((_, root), keys)) = path.split_first() else {
+        return;
+    };
+    let mut parent = model.sections.metadata.get_mut(root);
+    for (_, key) in keys {
+        parent = match parent {
+            Some(serde_json::Value::Object(map)) => map.get_mut(key),
+            Some(serde_json::Value::Array(items)) => items
+                .last_mut()
+                .and_then(|item| item.as_object_mut())
+                .and_then(|map| map.get_mut(key)),
+            _ => None,
+        };
+    }
+    let Some(parent) = parent else {
+        return;
+    };
+
+    let (key, value) = metadata_item(token.raw.trim().trim_start_matches("- "));
+    if parent.as_str() == Some("") {
+        *parent = match key {
+            Some(_) => serde_json::Value::Object(serde_json::Map::new()),
+            None => serde_json::Value::Array(Vec::new()),
+        };
+    }
+    let value = value.unwrap_or_else(|| serde_json::Value::String(String::new()));
+    match (parent, key) {
+        (serde_json::Value::Object(map), Some(key)) => {
+            map.insert(key.to_string(), value);
+        }
+        (serde_json::Value::Array(items), Some(key)) => {
+            let mut item = serde_json::Map::new();
+            item.insert(key.to_string(), value);
+            items.push(serde_json::Value::Object(item));
+        }
+        (serde_json::Value::Array(items), None) => items.push(value),
+        _ => return,
+    }
+    if let Some(key) = key {
+        path.push((token.indent, key.to_string()));
+    }
+}
+
 fn parse_metadata_value(value: &str) -> serde_json::Value {
     let was_quoted = (value.starts_with('"') && value.ends_with('"'))
         || (value.starts_with('\'') && value.ends_with('\''));
@@ -1451,13 +2752,14 @@ fn parse_nested_value(value: &str) -> serde_json::Value {
         }
         return serde_json::json!(n);
     }
-    let unquoted = s
-        .trim_start_matches(['"', '\''])
-        .trim_end_matches(['"', '\'']);
+    let unquoted = ['"', '\'']
+        .iter()
+        .find_map(|&q| s.strip_prefix(q)?.strip_suffix(q))
+        .unwrap_or(s);
     serde_json::Value::String(unquoted.to_string())
 }
 
-fn apply_extended_attribute(field: &mut FieldNode, key: &str, value: &str) {
+fn apply_extended_attribute(field: &mut FieldNode, key: &str, value: &str, loc: SourceLocation) {
     match key {
         "type" => {
             let mut t = value.to_string();
@@ -1484,6 +2786,7 @@ fn apply_extended_attribute(field: &mut FieldNode, key: &str, value: &str) {
                 cascade: None,
                 is_standard: Some(true),
                 is_registered: None,
+                loc: Some(loc),
             });
         }
         "on_delete" => {
@@ -1493,6 +2796,7 @@ fn apply_extended_attribute(field: &mut FieldNode, key: &str, value: &str) {
                 cascade: None,
                 is_standard: Some(true),
                 is_registered: None,
+                loc: Some(loc),
             });
         }
         _ => {
@@ -1507,6 +2811,7 @@ fn apply_extended_attribute(field: &mut FieldNode, key: &str, value: &str) {
                     None
                 },
                 is_registered: None,
+                loc: Some(loc),
             });
         }
     }
@@ -1580,15 +2885,61 @@ fn parse_arg_value(s: &str) -> AttrArgValue {
             return AttrArgValue::Number(n);
         }
     }
-    let unquoted =
-        if (s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')) {
-            &s[1..s.len() - 1]
-        } else {
-            s
-        };
+    let unquoted = ['"', '\'']
+        .iter()
+        .find_map(|&q| s.strip_prefix(q)?.strip_suffix(q))
+        .unwrap_or(s);
     AttrArgValue::String(unquoted.to_string())
 }
 
+/// The checks declared with `@check` on `field`.
+pub(crate) fn field_checks(field: &FieldNode) -> Vec<CheckDef> {
+    field
+        .attributes
+        .iter()
+        .filter(|a| a.name == "check")
+        .map(|a| CheckDef {
+            expression: check_expression(a.args.as_deref().unwrap_or_default()),
+            field: Some(field.name.clone()),
+            description: None,
+            loc: a.loc.clone().unwrap_or_else(|| field.loc.clone()),
+        })
+        .collect()
+}
+
+/// The expression of `@check(...)`. The lexer splits unquoted arguments
+/// at commas, so they are joined back, and quotes or backticks around the
+/// whole expression are dropped.
+fn check_expression(args: &[AttrArgValue]) -> String {
+    let joined = args
+        .iter()
+        .map(|a| match a {
+            AttrArgValue::String(s) => s.clone(),
+            AttrArgValue::Number(n) => n.to_string(),
+            AttrArgValue::Bool(b) => b.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let trimmed = joined.trim();
+    ['`', '"', '\'']
+        .iter()
+        .find_map(|&q| trimmed.strip_prefix(q)?.strip_suffix(q))
+        .unwrap_or(trimmed)
+        .trim()
+        .to_string()
+}
+
+/// The quoted description after a directive's closing paren, as in
+/// `@natural_key(a, b) "Unique per tenant"`.
+fn trailing_description(raw: &str) -> Option<String> {
+    let body = raw.trim_end().strip_suffix('"')?;
+    let open = body.rfind('"')?;
+    body[..open]
+        .trim_end()
+        .ends_with(')')
+        .then(|| body[open + 1..].to_string())
+}
+
 fn attr_args_to_json(args: &[AttrArgValue]) -> serde_json::Value {
     if args.len() == 1 {
         match &args[0] {
@@ -1655,6 +3006,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_unicode_names() {
+        let input = "## 注文 : 基本情報\n- 顧客: 사용자 @reference(사용자) @색인\n- 金額: decimal(10,2) `[表示(一覧)]`";
+        let result = parse_string(input, "test.m3l.md");
+        let model = &result.models[0];
+        assert_eq!(model.name, "注文");
+        assert_eq!(model.inherits, vec!["基本情報"]);
+        assert_eq!(model.fields[0].name, "顧客");
+        assert_eq!(model.fields[0].field_type.as_deref(), Some("사용자"));
+        let attrs: Vec<&str> = model.fields[0]
+            .attributes
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(attrs, vec!["reference", "색인"]);
+        let custom = model.fields[1].framework_attrs.as_ref().unwrap();
+        assert_eq!(custom[0].parsed.as_ref().unwrap().name, "表示");
+    }
+
+    #[test]
+    fn parse_doc_tags() {
+        let input = "## User\n> A person who signs in.\n> @since 2.0\n> @see Account\n- id: identifier @pk\n- nick: string # @deprecated\n- phone: string\n  > @deprecated Use email,\n  > which is verified.\n  > @example +1 555 0100\n  > +82 10 0000 0000\n  > @sincere thanks";
+        let result = parse_string(input, "test.m3l.md");
+        let model = &result.models[0];
+        assert_eq!(model.description.as_deref(), Some("A person who signs in."));
+        let doc = model.doc.as_ref().unwrap();
+        assert_eq!(doc.since.as_deref(), Some("2.0"));
+        assert_eq!(doc.see, vec!["Account"]);
+        assert!(!doc.deprecated);
+        assert!(model.fields[0].doc.is_none());
+
+        let nick = &model.fields[1];
+        assert_eq!(nick.description, None);
+        assert!(nick.doc.as_ref().unwrap().deprecated);
+        assert_eq!(nick.doc.as_ref().unwrap().deprecation, None);
+
+        let phone = &model.fields[2];
+        assert_eq!(phone.description, None);
+        let doc = phone.doc.as_ref().unwrap();
+        assert_eq!(
+            doc.deprecation.as_deref(),
+            Some("Use email, which is verified.")
+        );
+        // A tag runs until the next one; `@sincere` is not a tag
+        assert_eq!(
+            doc.examples,
+            vec!["+1 555 0100\n+82 10 0000 0000\n@sincere thanks"]
+        );
+    }
+
+    #[test]
+    fn parse_deprecated_attribute() {
+        let input = "## User @deprecated(since: \"3.0\", replacement: Customer)\n- id: identifier @pk\n- mail: string @deprecated(\"use Customer.email\", since: \"2.0\")\n- phone: string @deprecated\n- fax: string\n  > @deprecated Use phone.";
+        let result = parse_string(input, "test.m3l.md");
+        let model = &result.models[0];
+        let deprecation =
+            |message: Option<&str>, since: Option<&str>, replacement: Option<&str>| {
+                Some(Deprecation {
+                    message: message.map(String::from),
+                    since: since.map(String::from),
+                    replacement: replacement.map(String::from),
+                })
+            };
+        assert_eq!(
+            model.deprecated,
+            deprecation(None, Some("3.0"), Some("Customer"))
+        );
+        assert_eq!(model.fields[0].deprecated, None);
+        assert_eq!(
+            model.fields[1].deprecated,
+            deprecation(
+                Some("use Customer.email"),
+                Some("2.0"),
+                Some("Customer.email")
+            )
+        );
+        assert_eq!(model.fields[2].deprecated, deprecation(None, None, None));
+        assert_eq!(
+            model.fields[3].deprecated,
+            deprecation(Some("Use phone."), None, Some("phone"))
+        );
+    }
+
+    #[test]
+    fn parse_view_join_kinds_and_aliases() {
+        let input = "## V ::view\n### Source\n- from: Customer\n- join: left outer join Order o on o.customer_id = Customer.id\n- join: \"CROSS JOIN Region\"\n- join: Address as a ON a.id = Customer.address_id\n- join: Product on Product.id = o.product_id\n\n- id: identifier";
+        let result = parse_string(input, "test.m3l.md");
+        let joins = result.views[0]
+            .source_def
+            .as_ref()
+            .unwrap()
+            .joins
+            .as_ref()
+            .unwrap();
+        let joins: Vec<(&str, Option<JoinKind>, Option<&str>, &str)> = joins
+            .iter()
+            .map(|j| (j.model.as_str(), j.kind, j.alias.as_deref(), j.on.as_str()))
+            .collect();
+        assert_eq!(
+            joins,
+            [
+                (
+                    "Order",
+                    Some(JoinKind::Left),
+                    Some("o"),
+                    "o.customer_id = Customer.id"
+                ),
+                ("Region", Some(JoinKind::Cross), None, ""),
+                ("Address", None, Some("a"), "a.id = Customer.address_id"),
+                ("Product", None, None, "Product.id = o.product_id"),
+            ]
+        );
+    }
+
     #[test]
     fn parse_view() {
         let input = "## SalesSummary ::view @materialized\n- total: decimal";
@@ -1664,6 +3129,38 @@ mod tests {
         assert_eq!(result.views[0].materialized, Some(true));
     }
 
+    #[test]
+    fn parse_refresh_schedules() {
+        let input = "## V ::view @materialized\n### Source\n- from: Order\n\n### Refresh\n- strategy: scheduled\n- interval: 0 3 * * 1-5\n\n- total: decimal";
+        let result = parse_string(input, "test.m3l.md");
+        let refresh = result.views[0].refresh.as_ref().unwrap();
+        assert_eq!(refresh.interval.as_deref(), Some("0 3 * * 1-5"));
+        assert_eq!(
+            refresh.schedule,
+            Some(RefreshSchedule::Cron {
+                expression: "0 3 * * 1-5".into()
+            })
+        );
+
+        let every = |seconds| Ok(RefreshSchedule::Interval { seconds });
+        assert_eq!(parse_refresh_schedule("5m"), every(300));
+        assert_eq!(parse_refresh_schedule("6 hours"), every(21_600));
+        assert_eq!(parse_refresh_schedule("30 Minutes"), every(1800));
+        assert_eq!(parse_refresh_schedule("hourly"), every(3600));
+        assert_eq!(
+            parse_refresh_schedule("daily 02:30"),
+            Ok(RefreshSchedule::Cron {
+                expression: "30 2 * * *".into()
+            })
+        );
+        assert!(parse_refresh_schedule("*/15 * * * *").is_ok());
+        assert!(parse_refresh_schedule("0m").is_err());
+        assert!(parse_refresh_schedule("soon").is_err());
+        assert!(parse_refresh_schedule("daily 25:00").is_err());
+        assert!(parse_refresh_schedule("75 * * * *").is_err());
+        assert!(parse_refresh_schedule("0 3 * * */0").is_err());
+    }
+
     #[test]
     fn parse_interface() {
         let input = "## Timestampable ::interface\n- created_at: timestamp";
@@ -1697,6 +3194,110 @@ mod tests {
         assert_eq!(result.namespace.as_deref(), Some("sample.ecommerce"));
     }
 
+    #[test]
+    fn parse_frontmatter() {
+        let input = "---\nnamespace: shop # the file namespace\nowner: \"team: billing\"\nversion: 1.10\ntags:\n  - billing\n  - pii\nreviewed: true\nretention: 30\nlabels: [a, 'b c']\nnested:\n  key: value\n---\n## Invoice\n- id: identifier\n\n# Namespace: audit\n## Entry\n- id: identifier";
+        let result = parse_string(input, "test.m3l.md");
+        let meta = result.metadata.as_ref().unwrap();
+        assert_eq!(meta.source, "test.m3l.md");
+        assert_eq!(meta.namespace.as_deref(), Some("shop"));
+        assert_eq!(meta.owner.as_deref(), Some("team: billing"));
+        assert_eq!(meta.version.as_deref(), Some("1.10"));
+        assert_eq!(meta.tags, ["billing", "pii"]);
+        assert_eq!(meta.metadata["reviewed"], serde_json::json!(true));
+        assert_eq!(meta.metadata["retention"], serde_json::json!(30));
+        assert_eq!(meta.metadata["labels"], serde_json::json!(["a", "b c"]));
+        // Nested maps are not read
+        assert_eq!(meta.metadata["nested"], serde_json::json!([]));
+        assert!(meta.raw.starts_with("namespace: shop"));
+
+        // The frontmatter namespace is the file's; a header still overrides
+        // it for the elements after it
+        assert_eq!(result.namespace.as_deref(), Some("shop"));
+        assert_eq!(result.models[0].namespace.as_deref(), Some("shop"));
+        assert_eq!(result.models[1].namespace.as_deref(), Some("audit"));
+        assert_eq!(result.models[0].line, 14);
+        assert!(result.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn parse_seed_rows() {
+        let input = "## Role\n- id: integer @pk\n- name: string\n- builtin: boolean = false\n- weight: decimal?\n\n### Seed\n| id | name | builtin | weight |\n|---:|:-----|---------|--------|\n| 1 | admin | true | 0.5 |\n| 2 | \"guest user\" |  | null |\n- owner\n  - id: 3\n  - name: \"owner\"\n\n### Indexes\n| not | seed |";
+        let result = parse_string(input, "test.m3l.md");
+        let seeds = &result.models[0].sections.seeds;
+        assert_eq!(seeds.len(), 3);
+        assert_eq!(
+            serde_json::Value::Object(seeds[0].values.clone()),
+            serde_json::json!({"id": 1, "name": "admin", "builtin": true, "weight": 0.5})
+        );
+        assert_eq!(seeds[0].name, None);
+        assert_eq!(seeds[0].loc.line, 10);
+        // Empty cells leave the field unset
+        assert_eq!(
+            serde_json::Value::Object(seeds[1].values.clone()),
+            serde_json::json!({"id": 2, "name": "guest user", "weight": null})
+        );
+        assert_eq!(seeds[2].name.as_deref(), Some("owner"));
+        assert_eq!(
+            serde_json::Value::Object(seeds[2].values.clone()),
+            serde_json::json!({"id": 3, "name": "owner"})
+        );
+        // Seed rows are not fields, and a table outside `### Seed` is not
+        // seed data
+        assert_eq!(result.models[0].fields.len(), 4);
+        assert!(result.models[0].sections.custom.is_empty());
+        assert_eq!(result.models[0].loc.end_line, 17);
+    }
+
+    #[test]
+    fn parse_endpoints() {
+        let input = "## User\n- id: identifier @pk\n\n### Endpoints\n- GET /users \"List users\"\n- patch /users/{id}  # partial update\n  - auth: admin\n  - roles: [owner, admin]\n- FETCH /users\n- GET users/{id}";
+        let result = parse_string(input, "test.m3l.md");
+        let endpoints = &result.models[0].sections.endpoints;
+        assert_eq!(endpoints.len(), 2);
+        assert_eq!(endpoints[0].method, "GET");
+        assert_eq!(endpoints[0].path, "/users");
+        assert!(endpoints[0].params.is_empty());
+        assert_eq!(endpoints[0].description.as_deref(), Some("List users"));
+        assert_eq!(endpoints[1].method, "PATCH");
+        assert_eq!(endpoints[1].params, ["id"]);
+        assert_eq!(endpoints[1].loc.line, 6);
+        assert_eq!(
+            serde_json::Value::Object(endpoints[1].options.clone()),
+            serde_json::json!({"auth": "admin", "roles": ["owner", "admin"]})
+        );
+        assert_eq!(result.models[0].fields.len(), 1);
+
+        let p003: Vec<(usize, &str)> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.line, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            p003,
+            [
+                (
+                    9,
+                    "Cannot parse \"FETCH /users\" as an endpoint (`- METHOD /path`)"
+                ),
+                (
+                    10,
+                    "Cannot parse \"GET users/{id}\" as an endpoint (`- METHOD /path`)"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_multiple_namespaces_per_element() {
+        let input = "# Namespace: auth\n## User\n- id: identifier\n\n# Namespace: billing\n## Invoice\n- id: identifier";
+        let result = parse_string(input, "test.m3l.md");
+        assert_eq!(result.namespace.as_deref(), Some("auth"));
+        assert_eq!(result.models[0].namespace.as_deref(), Some("auth"));
+        assert_eq!(result.models[1].namespace.as_deref(), Some("billing"));
+        assert_eq!(result.models[0].fields.len(), 1);
+    }
+
     #[test]
     fn parse_non_namespace_h1_ignored() {
         // A non-namespace H1 should not change the namespace
@@ -1726,6 +3327,195 @@ mod tests {
         assert!(!result.models[0].sections.indexes.is_empty());
     }
 
+    #[test]
+    fn parse_typed_indexes() {
+        let input = "## Post\n- id: identifier\n- @index(author_id, created_at, name: \"by_author\", fulltext: true) \"Author feed\"\n- @unique(slug)\n### Indexes\n- idx_published(Published posts)\n  - fields: [published_at, author_id]\n  - unique: true\n  - where: \"status = 'published'\"\n  - method: btree\n  - fillfactor: 90";
+        let result = parse_string(input, "test.m3l.md");
+        let indexes = &result.models[0].sections.indexes;
+        assert_eq!(indexes.len(), 3);
+
+        assert_eq!(indexes[0].name.as_deref(), Some("by_author"));
+        assert_eq!(indexes[0].fields, ["author_id", "created_at"]);
+        assert!(!indexes[0].unique);
+        assert_eq!(indexes[0].options["fulltext"], true);
+        assert_eq!(indexes[0].description.as_deref(), Some("Author feed"));
+        assert!(indexes[0].raw.as_deref().unwrap().starts_with("- @index("));
+
+        assert_eq!(indexes[1].fields, ["slug"]);
+        assert!(indexes[1].unique);
+
+        let published = &indexes[2];
+        assert_eq!(published.name.as_deref(), Some("idx_published"));
+        assert_eq!(published.label.as_deref(), Some("Published posts"));
+        assert_eq!(published.fields, ["published_at", "author_id"]);
+        assert!(published.unique);
+        assert_eq!(
+            published.where_clause.as_deref(),
+            Some("status = 'published'")
+        );
+        assert_eq!(published.method.as_deref(), Some("btree"));
+        assert_eq!(published.options["fillfactor"], 90);
+        assert_eq!(published.raw, None);
+    }
+
+    #[test]
+    fn parse_typed_relations() {
+        let input = "## Post\n- id: identifier\n- @relation(parent, -> Post, from: parent_id) \"Parent post\"\n### Relations\n- orders > Order via customer_id, one-to-many\n- category: >Category via category_id (optional)\n- <>tags: many-to-many\n- >author\n  - target: Person\n  - from: author_id\n  - on_delete: restrict\n  - load: eager\n- reviews\n  - target: Review.post_id\n  - cardinality: one-to-many";
+        let result = parse_string(input, "test.m3l.md");
+        let relations = &result.models[0].sections.relations;
+        assert_eq!(relations.len(), 6);
+
+        let parent = &relations[0];
+        assert_eq!(parent.name, "parent");
+        assert_eq!(parent.direction, Some(RelationDirection::To));
+        assert_eq!(parent.target.as_deref(), Some("Post"));
+        assert_eq!(parent.from_field.as_deref(), Some("parent_id"));
+        assert_eq!(parent.cardinality, Some(Cardinality::ManyToOne));
+        assert_eq!(parent.description.as_deref(), Some("Parent post"));
+        assert!(parent.raw.starts_with("@relation("));
+
+        let orders = &relations[1];
+        assert_eq!(orders.name, "orders");
+        assert_eq!(orders.direction, Some(RelationDirection::To));
+        assert_eq!(orders.target.as_deref(), Some("Order"));
+        assert_eq!(orders.from_field.as_deref(), Some("customer_id"));
+        assert_eq!(orders.cardinality, Some(Cardinality::OneToMany));
+
+        let category = &relations[2];
+        assert_eq!(category.name, "category");
+        assert_eq!(category.target.as_deref(), Some("Category"));
+        assert!(category.optional);
+
+        let tags = &relations[3];
+        assert_eq!(tags.name, "tags");
+        assert_eq!(tags.direction, Some(RelationDirection::Both));
+        assert_eq!(tags.target, None);
+        assert_eq!(tags.cardinality, Some(Cardinality::ManyToMany));
+
+        let author = &relations[4];
+        assert_eq!(author.name, "author");
+        assert_eq!(author.target.as_deref(), Some("Person"));
+        assert_eq!(author.from_field.as_deref(), Some("author_id"));
+        assert_eq!(author.on_delete.as_deref(), Some("restrict"));
+        assert_eq!(author.options["load"], "eager");
+        assert_eq!(author.loc.line, 8);
+
+        let reviews = &relations[5];
+        assert_eq!(reviews.direction, None);
+        assert_eq!(reviews.target.as_deref(), Some("Review.post_id"));
+        assert_eq!(reviews.cardinality, Some(Cardinality::OneToMany));
+    }
+
+    #[test]
+    fn parse_typed_behaviors() {
+        let input = "## Post\n- id: identifier\n- title: string\n- @behavior(after_create, notify_author, condition: \"status = 'published'\") \"Tell the author\"\n### Behaviors\n- before_update: set updated_at = now()\n- on delete\n  - action: archive\n  - condition: \"archived = false\"\n  - async: true\n- reindex: search_sync";
+        let result = parse_string(input, "test.m3l.md");
+        let model = &result.models[0];
+        assert_eq!(model.fields.len(), 2);
+        let behaviors = &model.sections.behaviors;
+        assert_eq!(behaviors.len(), 4);
+
+        assert_eq!(behaviors[0].timing, Some(BehaviorTiming::After));
+        assert_eq!(behaviors[0].event, "create");
+        assert_eq!(behaviors[0].action.as_deref(), Some("notify_author"));
+        assert_eq!(
+            behaviors[0].condition.as_deref(),
+            Some("status = 'published'")
+        );
+        assert_eq!(behaviors[0].description.as_deref(), Some("Tell the author"));
+
+        assert_eq!(behaviors[1].name, "before_update");
+        assert_eq!(behaviors[1].timing, Some(BehaviorTiming::Before));
+        assert_eq!(behaviors[1].event, "update");
+        assert_eq!(
+            behaviors[1].action.as_deref(),
+            Some("set updated_at = now()")
+        );
+
+        assert_eq!(behaviors[2].timing, Some(BehaviorTiming::On));
+        assert_eq!(behaviors[2].event, "delete");
+        assert_eq!(behaviors[2].action.as_deref(), Some("archive"));
+        assert_eq!(behaviors[2].condition.as_deref(), Some("archived = false"));
+        assert_eq!(behaviors[2].options["async"], true);
+
+        assert_eq!(behaviors[3].timing, None);
+        assert_eq!(behaviors[3].event, "reindex");
+    }
+
+    #[test]
+    fn parse_nested_metadata() {
+        let input = "## Customer\n- id: identifier\n### Metadata\n- version: \"1.0\"\n- note: kept for audits # internal\n- tags: [billing, 2]\n- owners:\n  - alice\n  - bob\n- storage:\n  - engine: postgres\n  - tiers:\n    - hot\n    - cold\n  - replicas: 2\n- contacts:\n  - primary:\n    - email: ops@example.com\n- empty:\n- id_hint: uuid";
+        let result = parse_string(input, "test.m3l.md");
+        let model = &result.models[0];
+        assert_eq!(model.fields.len(), 1);
+        let metadata = &model.sections.metadata;
+        assert_eq!(metadata["version"], "1.0");
+        assert_eq!(metadata["note"], "kept for audits");
+        assert_eq!(metadata["tags"], serde_json::json!(["billing", 2]));
+        assert_eq!(metadata["owners"], serde_json::json!(["alice", "bob"]));
+        assert_eq!(
+            metadata["storage"],
+            serde_json::json!({"engine": "postgres", "tiers": ["hot", "cold"], "replicas": 2})
+        );
+        assert_eq!(
+            metadata["contacts"],
+            serde_json::json!({"primary": {"email": "ops@example.com"}})
+        );
+        assert_eq!(metadata["empty"], "");
+        assert_eq!(metadata["id_hint"], "uuid");
+    }
+
+    #[test]
+    fn parse_natural_keys() {
+        let input = "## User\n- tenant_id: identifier\n- email: email\n- @natural_key(tenant_id, email) \"Email per tenant (case-folded)\"\n### Keys\n- tenant_handle(Handle per tenant)\n  - fields: [tenant_id, handle]\n### Indexes\n- idx_email";
+        let result = parse_string(input, "test.m3l.md");
+        let sections = &result.models[0].sections;
+        assert_eq!(sections.keys.len(), 2);
+        assert_eq!(sections.keys[0].name, None);
+        assert_eq!(sections.keys[0].fields, ["tenant_id", "email"]);
+        assert_eq!(
+            sections.keys[0].description.as_deref(),
+            Some("Email per tenant (case-folded)")
+        );
+        assert_eq!(sections.keys[1].name.as_deref(), Some("tenant_handle"));
+        assert_eq!(sections.keys[1].fields, ["tenant_id", "handle"]);
+        assert_eq!(sections.keys[1].loc.line, 6);
+        assert_eq!(sections.indexes.len(), 1);
+        assert!(sections.custom.is_empty());
+    }
+
+    #[test]
+    fn parse_checks() {
+        let input = "## Product\n- price: decimal @check(`price > 0`) @min(0)\n- discount: decimal @check(discount >= 0, discount < 1)\n- @check(`price > discount`) \"Discount below price\"";
+        let result = parse_string(input, "test.m3l.md");
+        let model = &result.models[0];
+        let checks: Vec<(&str, Option<&str>)> = model
+            .sections
+            .checks
+            .iter()
+            .map(|c| (c.expression.as_str(), c.field.as_deref()))
+            .collect();
+        assert_eq!(
+            checks,
+            [
+                ("price > 0", Some("price")),
+                ("discount >= 0, discount < 1", Some("discount")),
+                ("price > discount", None),
+            ]
+        );
+        // The field keeps its attribute; the check points at it
+        assert_eq!(model.fields[0].attributes[0].name, "check");
+        assert_eq!(
+            model.sections.checks[0].loc,
+            *model.fields[0].attributes[0].loc.as_ref().unwrap()
+        );
+        assert_eq!(
+            model.sections.checks[2].description.as_deref(),
+            Some("Discount below price")
+        );
+        assert!(model.sections.custom.is_empty());
+    }
+
     #[test]
     fn parse_attribute_def() {
         let input = "## custom_flag ::attribute\n> A custom flag\n- target: [field, model]\n- type: boolean\n- required: false\n- default: true";
@@ -1738,6 +3528,31 @@ mod tests {
         assert_eq!(entry.attr_type, "boolean");
         assert!(!entry.required);
         assert_eq!(entry.default_value, Some(AttrArgValue::Bool(true)));
+        assert_eq!(entry.values, None);
+        assert_eq!(entry.pattern, None);
+    }
+
+    #[test]
+    fn parse_pattern_attribute_def() {
+        let input =
+            "## currency ::attribute\n- target: [field]\n- type: string\n- pattern: \"^[A-Z]{3}$\"";
+        let result = parse_string(input, "test.m3l.md");
+        assert_eq!(
+            result.attribute_registry[0].pattern.as_deref(),
+            Some("^[A-Z]{3}$")
+        );
+    }
+
+    #[test]
+    fn parse_enum_attribute_def() {
+        let input = "## tier ::attribute\n- target: [field]\n- type: enum\n- values: [gold, \"silver\", bronze]";
+        let result = parse_string(input, "test.m3l.md");
+        let entry = &result.attribute_registry[0];
+        assert_eq!(entry.attr_type, "enum");
+        assert_eq!(
+            entry.values.as_deref(),
+            Some(&["gold".to_string(), "silver".into(), "bronze".into()][..])
+        );
     }
 
     #[test]
@@ -1838,4 +3653,109 @@ mod tests {
             Some("Auth.User")
         );
     }
+
+    fn range(loc: &SourceLocation) -> (usize, usize, usize, usize) {
+        (loc.line, loc.col, loc.end_line, loc.end_col)
+    }
+
+    #[test]
+    fn parse_ranges() {
+        let input = "## User @table(\"users\")\n- id: identifier @pk\n- email: string(320) @unique @reference(Account.email)!\n  > Login address\n- address: object\n  - city: string\n  - zip: string(10)\n\n## Status ::enum\n- active \"Active\"\n\n## Next\n- id: identifier";
+        let result = parse_string(input, "test.m3l.md");
+        let user = &result.models[0];
+        assert_eq!(range(&user.loc), (1, 1, 7, 20));
+        assert_eq!(
+            range(user.attributes[0].loc.as_ref().unwrap()),
+            (1, 9, 1, 24)
+        );
+
+        let id = &user.fields[0];
+        assert_eq!(range(&id.loc), (2, 1, 2, 21));
+        assert_eq!(
+            range(id.attributes[0].loc.as_ref().unwrap()),
+            (2, 18, 2, 21)
+        );
+
+        // The blockquote folded into the field widens it
+        let email = &user.fields[1];
+        assert_eq!(range(&email.loc), (3, 1, 4, 18));
+        assert_eq!(
+            range(email.attributes[0].loc.as_ref().unwrap()),
+            (3, 22, 3, 29)
+        );
+        assert_eq!(
+            range(email.attributes[1].loc.as_ref().unwrap()),
+            (3, 30, 3, 56)
+        );
+
+        let address = &user.fields[2];
+        assert_eq!(range(&address.loc), (5, 1, 7, 20));
+
+        assert_eq!(range(&result.enums[0].loc), (9, 1, 10, 18));
+        assert_eq!(range(&result.models[1].loc), (12, 1, 13, 17));
+    }
+
+    #[test]
+    fn parse_view_range_covers_code_block() {
+        let input = "## Report ::view\n### Source\n```sql\nSELECT 1\n```\n";
+        let result = parse_string(input, "test.m3l.md");
+        assert_eq!(range(&result.views[0].loc), (1, 1, 5, 4));
+    }
+
+    fn syntax_codes(input: &str) -> Vec<(String, usize, usize)> {
+        parse_string(input, "test.m3l.md")
+            .diagnostics
+            .into_iter()
+            .map(|d| (d.code, d.line, d.col))
+            .collect()
+    }
+
+    #[test]
+    fn parse_syntax_diagnostics() {
+        let input = "## User(Account\n- id: identifier @pk\n- name: string \"Full name\n- first name: string\n\n## Role ::Enum\n## Level ::enum\n- LOW Low priority\n\n## Graph ::ontology\n- a b\n\n## Bad ::my-kind\n";
+        assert_eq!(
+            syntax_codes(input),
+            vec![
+                ("M3L-P001".to_string(), 1, 8),
+                ("M3L-P001".to_string(), 3, 16),
+                ("M3L-P003".to_string(), 4, 3),
+                ("M3L-P002".to_string(), 6, 9),
+                ("M3L-P003".to_string(), 8, 3),
+                ("M3L-P002".to_string(), 13, 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_version_pragma() {
+        let input = "<!-- m3l: 1.0 -->\n## User\n- id: identifier @pk\n\n## extend User\n- nickname: string\n\n## Role\n- id: identifier @pk\n";
+        let result = parse_string(input, "test.m3l.md");
+        assert_eq!(result.m3l_version.as_ref().unwrap().version, "1.0");
+        assert_eq!(syntax_codes(input), vec![("M3L-P005".to_string(), 5, 1)]);
+        // The skipped block's fields go nowhere; the next element is read
+        assert!(result.extend_blocks.is_empty());
+        assert_eq!(result.models.len(), 2);
+        assert_eq!(result.models[0].fields.len(), 1);
+
+        let newest = input.replace("m3l: 1.0", "m3l: 1.1");
+        assert!(syntax_codes(&newest).is_empty());
+        assert_eq!(parse_string(&newest, "test.m3l.md").extend_blocks.len(), 1);
+
+        for (pragma, line) in [("# M3L-Version: 1.9", 1), ("<!-- m3l: v1 -->", 1)] {
+            let input = format!("{pragma}\n<!-- m3l: 1.0 -->\n## extend User\n- nickname: string");
+            assert_eq!(
+                syntax_codes(&input),
+                vec![("M3L-P004".to_string(), line, 1)]
+            );
+        }
+        assert_eq!(parse_version("1.1.2"), Some((1, 1)));
+        assert_eq!(parse_version("2"), Some((2, 0)));
+        assert_eq!(parse_version("1.1.x"), None);
+    }
+
+    #[test]
+    fn parse_syntax_diagnostics_skip_free_form_items() {
+        let input = "## Post\n- id: identifier @pk\n@index(id)\n\n### Relations\n- <>tags: many-to-many\n  - target: Tag\n\n### Behaviors\n- on create: notify owner\n";
+        assert!(syntax_codes(input).is_empty());
+    }
 }
@@ -0,0 +1,395 @@
+//! Long-lived project model for hosts that keep schemas in memory
+//! (language server, `m3l serve`, napi servers).
+//!
+//! A [`Project`] owns the content of every file and caches its parsed form,
+//! so an edit only re-parses the file that changed. Resolution and validation
+//! are re-run lazily and published as an immutable [`ProjectSnapshot`] that
+//! readers can keep while further edits are applied.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::types::*;
+use crate::{parse_string, resolve, validate};
+
+struct ProjectFile {
+    content: Arc<str>,
+    parsed: ParsedFile,
+}
+
+/// In-memory set of M3L files with cached parse results.
+pub struct Project {
+    files: BTreeMap<String, ProjectFile>,
+    info: Option<ProjectInfo>,
+    options: ValidateOptions,
+    generation: u64,
+    snapshot: Option<Arc<ProjectSnapshot>>,
+}
+
+/// Consistent view of a project at a given generation.
+#[derive(Debug)]
+pub struct ProjectSnapshot {
+    /// Incremented every time the project content changes.
+    pub generation: u64,
+    /// Resolved AST of all files.
+    pub ast: M3lAst,
+    /// Validation result for `ast` (includes resolver diagnostics).
+    pub validation: ValidateResult,
+    files: BTreeMap<String, Arc<str>>,
+}
+
+impl ProjectSnapshot {
+    /// Content of a file as it was when the snapshot was taken.
+    pub fn file(&self, path: &str) -> Option<&str> {
+        self.files.get(path).map(|c| c.as_ref())
+    }
+
+    /// Paths of all files in the snapshot, in resolution order.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl Project {
+    pub fn new(info: Option<ProjectInfo>) -> Self {
+        Self {
+            files: BTreeMap::new(),
+            info,
+            options: ValidateOptions::default(),
+            generation: 0,
+            snapshot: None,
+        }
+    }
+
+    /// Set the validation options used for snapshots.
+    pub fn set_validate_options(&mut self, options: ValidateOptions) {
+        if self.options != options {
+            self.options = options;
+            self.invalidate();
+        }
+    }
+
+    /// Set the project name/version reported in the AST.
+    pub fn set_info(&mut self, info: Option<ProjectInfo>) {
+        if self.info != info {
+            self.info = info;
+            self.invalidate();
+        }
+    }
+
+    /// Add or replace a file. Returns `false` if the content is unchanged,
+    /// in which case nothing is re-parsed.
+    pub fn set_file(&mut self, path: &str, content: &str) -> bool {
+        if let Some(existing) = self.files.get(path) {
+            if existing.content.as_ref() == content {
+                #[cfg(feature = "tracing")]
+                tracing::trace!(path, "content unchanged; keeping parse");
+                return false;
+            }
+        }
+        let parsed = parse_string(content, path);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(path, "parsed");
+        self.files.insert(
+            path.to_string(),
+            ProjectFile {
+                content: Arc::from(content),
+                parsed,
+            },
+        );
+        self.invalidate();
+        true
+    }
+
+    /// Remove a file. Returns `false` if it was not part of the project.
+    pub fn remove_file(&mut self, path: &str) -> bool {
+        if self.files.remove(path).is_some() {
+            self.invalidate();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Read a file from disk and add it to the project.
+    pub fn load_file(&mut self, path: &std::path::Path) -> std::io::Result<bool> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(self.set_file(&path.to_string_lossy(), &content))
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.files.contains_key(path)
+    }
+
+    /// Current content of a file.
+    pub fn file(&self, path: &str) -> Option<&str> {
+        self.files.get(path).map(|f| f.content.as_ref())
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Generation counter, incremented on every effective change.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Return the current snapshot, re-resolving only if something changed
+    /// since the last call.
+    pub fn snapshot(&mut self) -> Arc<ProjectSnapshot> {
+        if let Some(ref snap) = self.snapshot {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(generation = self.generation, "snapshot cache hit");
+            return Arc::clone(snap);
+        }
+
+        #[cfg(feature = "tracing")]
+        let start = std::time::Instant::now();
+        let parsed: Vec<ParsedFile> = self.files.values().map(|f| f.parsed.clone()).collect();
+        let ast = resolve(&parsed, self.info.clone());
+        let validation = validate(&ast, &self.options);
+        let snap = Arc::new(ProjectSnapshot {
+            generation: self.generation,
+            ast,
+            validation,
+            files: self
+                .files
+                .iter()
+                .map(|(k, f)| (k.clone(), Arc::clone(&f.content)))
+                .collect(),
+        });
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            generation = self.generation,
+            files = self.files.len(),
+            elapsed_ms = start.elapsed().as_secs_f64() * 1000.0,
+            "snapshot rebuilt"
+        );
+        self.snapshot = Some(Arc::clone(&snap));
+        snap
+    }
+
+    fn invalidate(&mut self) {
+        self.generation += 1;
+        self.snapshot = None;
+    }
+}
+
+/// Whether a path has one of the M3L file extensions (.m3l.md, .m3l, .md).
+pub fn is_m3l_path(path: &std::path::Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("md") | Some("m3l")
+    )
+}
+
+#[cfg(feature = "watch")]
+pub use watch::ProjectWatcher;
+
+#[cfg(feature = "watch")]
+mod watch {
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+    use std::time::Duration;
+
+    use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+    use super::{is_m3l_path, Project};
+
+    /// Watches a directory and applies file changes to a [`Project`].
+    ///
+    /// Event paths are reported relative to the root as it was given, so
+    /// files loaded with `Project::load_file(root.join(..))` keep their keys.
+    pub struct ProjectWatcher {
+        _watcher: RecommendedWatcher,
+        rx: Receiver<notify::Result<Event>>,
+        root: PathBuf,
+        canonical_root: PathBuf,
+    }
+
+    impl ProjectWatcher {
+        pub fn new(root: &Path) -> Result<Self, String> {
+            let (tx, rx) = channel();
+            let mut watcher = notify::recommended_watcher(tx)
+                .map_err(|e| format!("Failed to create watcher: {e}"))?;
+            watcher
+                .watch(root, RecursiveMode::Recursive)
+                .map_err(|e| format!("Failed to watch {}: {e}", root.display()))?;
+            let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+            Ok(Self {
+                _watcher: watcher,
+                rx,
+                root: root.to_path_buf(),
+                canonical_root,
+            })
+        }
+
+        /// Apply all pending events without blocking. Returns the number of
+        /// files that changed.
+        pub fn apply(&self, project: &mut Project) -> usize {
+            let mut changed = 0;
+            while let Ok(event) = self.rx.try_recv() {
+                changed += self.apply_event(project, event);
+            }
+            changed
+        }
+
+        /// Block until at least one event arrives (or `timeout` elapses),
+        /// then apply it and everything else pending.
+        pub fn wait(&self, project: &mut Project, timeout: Duration) -> usize {
+            match self.rx.recv_timeout(timeout) {
+                Ok(event) => self.apply_event(project, event) + self.apply(project),
+                Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => 0,
+            }
+        }
+
+        fn apply_event(&self, project: &mut Project, event: notify::Result<Event>) -> usize {
+            let Ok(event) = event else { return 0 };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return 0;
+            }
+            let mut changed = 0;
+            for path in event.paths {
+                if !is_m3l_path(&path) {
+                    continue;
+                }
+                let path = match path.strip_prefix(&self.canonical_root) {
+                    Ok(rel) => self.root.join(rel),
+                    Err(_) => path,
+                };
+                let key = path.to_string_lossy().to_string();
+                let did_change = if path.is_file() {
+                    project.load_file(&path).unwrap_or(false)
+                } else {
+                    project.remove_file(&key)
+                };
+                if did_change {
+                    changed += 1;
+                }
+            }
+            changed
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_is_cached_until_change() {
+        let mut project = Project::default();
+        project.set_file("a.m3l.md", "## User\n- id: identifier @pk");
+        let first = project.snapshot();
+        let second = project.snapshot();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(first.ast.models.len(), 1);
+    }
+
+    #[test]
+    fn unchanged_content_does_not_invalidate() {
+        let mut project = Project::default();
+        assert!(project.set_file("a.m3l.md", "## User\n- id: identifier"));
+        let generation = project.generation();
+        assert!(!project.set_file("a.m3l.md", "## User\n- id: identifier"));
+        assert_eq!(project.generation(), generation);
+    }
+
+    #[test]
+    fn edits_are_reflected_in_new_snapshot() {
+        let mut project = Project::default();
+        project.set_file("a.m3l.md", "## User\n- id: identifier");
+        project.set_file("b.m3l.md", "## Post\n- author: User");
+        let before = project.snapshot();
+
+        project.set_file("b.m3l.md", "## Post\n- author: User\n- title: string");
+        let after = project.snapshot();
+
+        assert_eq!(before.ast.models[1].fields.len(), 1);
+        assert_eq!(after.ast.models[1].fields.len(), 2);
+        assert!(after.generation > before.generation);
+        assert_eq!(before.file("b.m3l.md"), Some("## Post\n- author: User"));
+    }
+
+    #[test]
+    fn remove_file_drops_its_elements() {
+        let mut project = Project::default();
+        project.set_file("a.m3l.md", "## User\n- id: identifier");
+        project.set_file("b.m3l.md", "## Post\n- title: string");
+        assert!(project.remove_file("a.m3l.md"));
+        assert!(!project.remove_file("a.m3l.md"));
+        let snap = project.snapshot();
+        assert_eq!(snap.ast.models.len(), 1);
+        assert_eq!(snap.ast.models[0].name, "Post");
+        assert_eq!(snap.paths().collect::<Vec<_>>(), vec!["b.m3l.md"]);
+    }
+
+    #[test]
+    fn changed_validate_options_revalidate() {
+        let mut project = Project::default();
+        project.set_file("a.m3l.md", "## User\n- id: identifier");
+        let generation = project.generation();
+        project.set_validate_options(ValidateOptions::default());
+        assert_eq!(project.generation(), generation);
+        project.set_validate_options(ValidateOptions { strict: true });
+        assert!(project.generation() > generation);
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn watcher_applies_edits_on_disk() {
+        use std::time::{Duration, Instant};
+
+        let root = std::env::temp_dir().join("m3l-project-watcher-test");
+        std::fs::remove_dir_all(&root).ok();
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join("a.m3l.md");
+        std::fs::write(&path, "## User\n- id: identifier").unwrap();
+
+        let mut project = Project::default();
+        project.load_file(&path).unwrap();
+        let watcher = ProjectWatcher::new(&root).unwrap();
+        std::fs::write(&path, "## User\n- id: identifier\n- email: email").unwrap();
+
+        // Events may arrive in several bursts
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while project.snapshot().ast.models[0].fields.len() < 2 && Instant::now() < deadline {
+            watcher.wait(&mut project, Duration::from_millis(200));
+        }
+        assert_eq!(project.snapshot().ast.models[0].fields.len(), 2);
+
+        std::fs::remove_file(&path).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !project.is_empty() && Instant::now() < deadline {
+            watcher.wait(&mut project, Duration::from_millis(200));
+        }
+        assert!(project.is_empty());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn snapshot_includes_validation() {
+        let mut project = Project::default();
+        project.set_file(
+            "a.m3l.md",
+            "## User\n- id: identifier\n\n## User\n- id: identifier",
+        );
+        let snap = project.snapshot();
+        assert!(snap.validation.errors.iter().any(|e| e.code == "M3L-E005"));
+    }
+}
@@ -1,13 +1,25 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::catalogs::{AST_VERSION, PARSER_VERSION};
+use crate::parser::field_checks;
+use crate::symbols::SymbolTable;
 use crate::types::*;
 
 /// Resolve and merge multiple parsed file ASTs into a single M3lAst.
 /// Handles: inheritance resolution, duplicate detection, attribute registry tagging.
 pub fn resolve(files: &[ParsedFile], project: Option<ProjectInfo>) -> M3lAst {
+    resolve_with_options(files, project, &ResolveOptions::default())
+}
+
+/// [`resolve`] with the project's type aliases and default parents
+/// applied, and the merged elements in `options.order`.
+pub fn resolve_with_options(
+    files: &[ParsedFile],
+    project: Option<ProjectInfo>,
+    options: &ResolveOptions,
+) -> M3lAst {
     let mut errors: Vec<Diagnostic> = Vec::new();
-    let warnings: Vec<Diagnostic> = Vec::new();
+    let mut warnings: Vec<Diagnostic> = Vec::new();
 
     // Collect all elements from all files
     let mut all_models: Vec<ModelNode> = Vec::new();
@@ -15,8 +27,9 @@ pub fn resolve(files: &[ParsedFile], project: Option<ProjectInfo>) -> M3lAst {
     let mut all_interfaces: Vec<ModelNode> = Vec::new();
     let mut all_views: Vec<ModelNode> = Vec::new();
     let mut all_flows: Vec<ModelNode> = Vec::new();
-    let mut all_extensions: HashMap<String, Vec<ModelNode>> = HashMap::new();
+    let mut all_extensions: BTreeMap<String, Vec<ModelNode>> = BTreeMap::new();
     let mut all_attr_registry: Vec<AttributeRegistryEntry> = Vec::new();
+    let mut all_extend_blocks: Vec<ModelNode> = Vec::new();
     let mut sources: Vec<String> = Vec::new();
 
     for file in files {
@@ -33,197 +46,221 @@ pub fn resolve(files: &[ParsedFile], project: Option<ProjectInfo>) -> M3lAst {
                 .extend(nodes.iter().cloned());
         }
         all_attr_registry.extend(file.attribute_registry.iter().cloned());
+        all_extend_blocks.extend(file.extend_blocks.iter().cloned());
+        for diagnostic in &file.diagnostics {
+            match diagnostic.severity {
+                DiagnosticSeverity::Error => errors.push(diagnostic.clone()),
+                DiagnosticSeverity::Warning => warnings.push(diagnostic.clone()),
+            }
+        }
+    }
+
+    // Project type aliases, expanded before inheritance so children and
+    // generic instances see the expanded types
+    if !options.type_aliases.is_empty() {
+        for element in all_models
+            .iter_mut()
+            .chain(&mut all_interfaces)
+            .chain(&mut all_views)
+            .chain(&mut all_flows)
+            .chain(&mut all_extend_blocks)
+            .chain(all_extensions.values_mut().flatten())
+        {
+            expand_type_aliases(&mut element.fields, &options.type_aliases);
+        }
     }
 
-    // Build source → namespace map for E008 ambiguity detection
+    // Elements carry their namespace; the file namespace covers those
+    // that do not
     let source_ns: HashMap<&str, Option<&str>> = files
         .iter()
         .map(|f| (f.source.as_str(), f.namespace.as_deref()))
         .collect();
+    let namespace_of = |namespace: &Option<String>, source: &str| {
+        namespace
+            .clone()
+            .or_else(|| source_ns.get(source).copied().flatten().map(String::from))
+    };
 
-    // name → Vec<(namespace, file, line)> for E008 cross-namespace ambiguity
-    let mut name_ns_map: HashMap<String, Vec<(Option<String>, String, usize)>> = HashMap::new();
-
-    // Build name maps and check duplicates
-    let mut model_map: HashMap<String, usize> = HashMap::new(); // name → index in all_models
-    let mut interface_map: HashMap<String, usize> = HashMap::new();
-    let mut all_named: HashMap<String, (String, String, usize)> = HashMap::new(); // name → (type, file, line)
+    // Names clash within a namespace (E005); the same name in two
+    // namespaces is fine until a short reference cannot tell them apart (E008)
+    let mut parents: Scope<Parent> = Scope::default();
+    let mut declared: HashMap<String, (String, usize)> = HashMap::new(); // qualified name → (file, line)
 
     for (i, model) in all_models.iter().enumerate() {
+        let ns = namespace_of(&model.namespace, &model.source);
         check_duplicate(
             &model.name,
+            ns.as_deref(),
             "model",
             &model.source,
             model.line,
-            &all_named,
+            &mut declared,
             &mut errors,
         );
-        model_map.insert(model.name.clone(), i);
-        all_named.insert(
-            model.name.clone(),
-            ("model".into(), model.source.clone(), model.line),
-        );
-        let ns = source_ns
-            .get(model.source.as_str())
-            .copied()
-            .flatten()
-            .map(String::from);
-        name_ns_map.entry(model.name.clone()).or_default().push((
-            ns,
-            model.source.clone(),
-            model.line,
-        ));
+        parents.insert(&model.name, ns.as_deref(), Parent::Model(i));
     }
 
     for en in all_enums.iter() {
+        let ns = namespace_of(&en.namespace, &en.source);
         check_duplicate(
             &en.name,
+            ns.as_deref(),
             "enum",
             &en.source,
             en.line,
-            &all_named,
+            &mut declared,
             &mut errors,
         );
-        all_named.insert(en.name.clone(), ("enum".into(), en.source.clone(), en.line));
-        let ns = source_ns
-            .get(en.source.as_str())
-            .copied()
-            .flatten()
-            .map(String::from);
-        name_ns_map
-            .entry(en.name.clone())
-            .or_default()
-            .push((ns, en.source.clone(), en.line));
+        parents.insert(&en.name, ns.as_deref(), Parent::Other);
     }
 
     for (i, iface) in all_interfaces.iter().enumerate() {
+        let ns = namespace_of(&iface.namespace, &iface.source);
         check_duplicate(
             &iface.name,
+            ns.as_deref(),
             "interface",
             &iface.source,
             iface.line,
-            &all_named,
+            &mut declared,
             &mut errors,
         );
-        interface_map.insert(iface.name.clone(), i);
-        all_named.insert(
-            iface.name.clone(),
-            ("interface".into(), iface.source.clone(), iface.line),
-        );
-        let ns = source_ns
-            .get(iface.source.as_str())
-            .copied()
-            .flatten()
-            .map(String::from);
-        name_ns_map.entry(iface.name.clone()).or_default().push((
-            ns,
-            iface.source.clone(),
-            iface.line,
-        ));
-    }
-
-    for view in &all_views {
-        all_named.insert(
-            view.name.clone(),
-            ("view".into(), view.source.clone(), view.line),
-        );
-        let ns = source_ns
-            .get(view.source.as_str())
-            .copied()
-            .flatten()
-            .map(String::from);
-        name_ns_map.entry(view.name.clone()).or_default().push((
-            ns,
-            view.source.clone(),
-            view.line,
-        ));
+        parents.insert(&iface.name, ns.as_deref(), Parent::Interface(i));
     }
 
-    for flow in &all_flows {
-        all_named.insert(
-            flow.name.clone(),
-            ("flow".into(), flow.source.clone(), flow.line),
-        );
-        let ns = source_ns
-            .get(flow.source.as_str())
-            .copied()
-            .flatten()
-            .map(String::from);
-        name_ns_map.entry(flow.name.clone()).or_default().push((
-            ns,
-            flow.source.clone(),
-            flow.line,
-        ));
+    for node in all_views.iter().chain(&all_flows) {
+        let ns = namespace_of(&node.namespace, &node.source);
+        declared
+            .entry(qualified_name(ns.as_deref(), &node.name))
+            .or_insert((node.source.clone(), node.line));
+        parents.insert(&node.name, ns.as_deref(), Parent::Other);
     }
 
     for ext_nodes in all_extensions.values() {
         for ext in ext_nodes {
+            let ns = namespace_of(&ext.namespace, &ext.source);
             check_duplicate(
                 &ext.name,
+                ns.as_deref(),
                 "extension",
                 &ext.source,
                 ext.line,
-                &all_named,
+                &mut declared,
                 &mut errors,
             );
-            all_named.insert(
-                ext.name.clone(),
-                ("extension".into(), ext.source.clone(), ext.line),
+            parents.insert(&ext.name, ns.as_deref(), Parent::Other);
+        }
+    }
+
+    // Aliases (`@alias(Account)`) are further names for the same model, so
+    // they share the symbol table and clash like names do
+    for (i, model) in all_models.iter().enumerate() {
+        let ns = namespace_of(&model.namespace, &model.source);
+        for alias in &model.aliases {
+            check_duplicate(
+                alias,
+                ns.as_deref(),
+                "alias",
+                &model.source,
+                model.line,
+                &mut declared,
+                &mut errors,
             );
-            let ns = source_ns
-                .get(ext.source.as_str())
-                .copied()
-                .flatten()
-                .map(String::from);
-            name_ns_map.entry(ext.name.clone()).or_default().push((
-                ns,
-                ext.source.clone(),
-                ext.line,
-            ));
+            parents.insert(alias, ns.as_deref(), Parent::Model(i));
         }
     }
 
-    // E008: Ambiguous model reference — same name in multiple namespaces
-    for (name, entries) in &name_ns_map {
-        if entries.len() < 2 {
-            continue;
+    // Project default parents, for every model that is not itself a
+    // default parent or one of their ancestors
+    if !options.default_inherits.is_empty() {
+        let mut exempt: HashSet<usize> = HashSet::new();
+        let mut pending: Vec<(String, Option<String>)> = options
+            .default_inherits
+            .iter()
+            .map(|name| (name.clone(), None))
+            .collect();
+        let mut seen: HashSet<Parent> = HashSet::new();
+        while let Some((name, from)) = pending.pop() {
+            let NameLookup::Found(parent) = parents.lookup(&name, from.as_deref()) else {
+                continue;
+            };
+            let element = match parent {
+                Parent::Model(i) => {
+                    exempt.insert(i);
+                    &all_models[i]
+                }
+                Parent::Interface(i) => &all_interfaces[i],
+                Parent::Other => continue,
+            };
+            if seen.insert(parent) {
+                pending.extend(
+                    element
+                        .inherits
+                        .iter()
+                        .map(|p| (p.clone(), element.namespace.clone())),
+                );
+            }
         }
-        // Collect distinct namespaces (only if at least 2 different namespaces)
-        let distinct_ns: HashSet<Option<&str>> =
-            entries.iter().map(|(ns, _, _)| ns.as_deref()).collect();
-        if distinct_ns.len() >= 2 {
-            let ns_list: Vec<String> = distinct_ns
-                .iter()
-                .map(|ns| ns.unwrap_or("(none)").to_string())
-                .collect();
-            let ns_display = ns_list.join(", ");
-            // Report on the second occurrence
-            let (_, file, line) = &entries[1];
+        for (i, model) in all_models.iter_mut().enumerate() {
+            if exempt.contains(&i) || !model.generic_params.is_empty() {
+                continue;
+            }
+            for name in &options.default_inherits {
+                if !model.inherits.contains(name) {
+                    model.inherits.push(name.clone());
+                    model.default_inherits.push(name.clone());
+                }
+            }
+        }
+    }
+
+    // `## extend User` blocks add to their model before inheritance, so
+    // children of the model get the additions too (E021)
+    for block in &all_extend_blocks {
+        let ns = namespace_of(&block.namespace, &block.source);
+        let target = match parents.lookup(&block.name, ns.as_deref()) {
+            NameLookup::Found(Parent::Model(i)) => Some(i),
+            NameLookup::Ambiguous(namespaces) => {
+                errors.push(ambiguous_reference(
+                    &block.name,
+                    &namespaces,
+                    &block.source,
+                    block.line,
+                ));
+                continue;
+            }
+            _ => None,
+        };
+        let Some(i) = target else {
             errors.push(Diagnostic {
-                code: "M3L-E008".to_string(),
+                code: "M3L-E021".to_string(),
                 severity: DiagnosticSeverity::Error,
-                file: file.clone(),
-                line: *line,
+                file: block.source.clone(),
+                line: block.line,
                 col: 1,
                 message: format!(
-                    "Ambiguous model reference \"{}\" in namespaces {}",
-                    name, ns_display
+                    "Extend block targets \"{}\", which is not a defined model",
+                    block.name
                 ),
             });
-        }
+            continue;
+        };
+        let model = &mut all_models[i];
+        model.fields.extend(block.fields.iter().cloned());
+        model
+            .sections
+            .indexes
+            .extend(block.sections.indexes.iter().cloned());
+        model
+            .sections
+            .relations
+            .extend(block.sections.relations.iter().cloned());
     }
 
     // Resolve inheritance
     for i in 0..all_models.len() {
-        resolve_inheritance(
-            i,
-            &mut all_models,
-            &model_map,
-            &all_interfaces,
-            &interface_map,
-            &all_named,
-            &mut errors,
-        );
+        resolve_inheritance(i, &mut all_models, &all_interfaces, &parents, &mut errors);
     }
 
     // Check duplicate field names
@@ -240,6 +277,52 @@ pub fn resolve(files: &[ParsedFile], project: Option<ProjectInfo>) -> M3lAst {
         }
     }
 
+    // View fields point at the source field they select, and take its
+    // type when they declare none
+    let view_sources: Vec<ModelNode> = all_models.iter().chain(&all_views).cloned().collect();
+    let view_sources: Vec<&ModelNode> = view_sources.iter().collect();
+    for view in all_views.iter_mut() {
+        expand_view_fields(view, &view_sources);
+    }
+
+    // Concrete models for the uses of generic models
+    let mut generic_instances = {
+        let elements: Vec<&ModelNode> = all_models
+            .iter()
+            .chain(&all_interfaces)
+            .chain(&all_views)
+            .chain(&all_flows)
+            .chain(all_extensions.values().flatten())
+            .collect();
+        instantiate_generics(&elements)
+    };
+
+    // Where @reference/@fk fields point; targets that do not resolve are
+    // left unset and reported by the validator (M3L-E011)
+    let targets: Vec<ModelNode> = all_models
+        .iter()
+        .chain(&all_views)
+        .chain(&all_flows)
+        .chain(&all_interfaces)
+        .cloned()
+        .collect();
+    let targets: Vec<&ModelNode> = targets.iter().collect();
+    for element in all_models
+        .iter_mut()
+        .chain(all_views.iter_mut())
+        .chain(all_flows.iter_mut())
+        .chain(all_interfaces.iter_mut())
+        .chain(generic_instances.iter_mut())
+    {
+        resolve_references(
+            &mut element.fields,
+            &element.generic_params,
+            element.namespace.as_deref(),
+            &targets,
+            &mut errors,
+        );
+    }
+
     // Tag isRegistered on attributes matching the registry
     if !all_attr_registry.is_empty() {
         let registered_names: HashSet<String> =
@@ -281,10 +364,51 @@ pub fn resolve(files: &[ParsedFile], project: Option<ProjectInfo>) -> M3lAst {
         }
     }
 
+    // Imports of files that were not loaded (E013)
+    let loaded: Vec<String> = files.iter().map(|f| normalize_path(&f.source)).collect();
+    let mut source_files: Vec<SourceFile> = Vec::new();
+    for file in files {
+        let mut edges: Vec<ImportEdge> = Vec::new();
+        for import in &file.imports {
+            let target = imported_source(&file.source, &import.path, &loaded)
+                .and_then(|source| loaded.iter().position(|l| l == source))
+                .map(|i| files[i].source.clone());
+            edges.push(ImportEdge {
+                path: import.path.clone(),
+                target: target.clone(),
+                symbols: import.symbols.clone(),
+                line: import.line,
+            });
+            if target.is_none() {
+                errors.push(Diagnostic {
+                    code: "M3L-E013".to_string(),
+                    severity: DiagnosticSeverity::Error,
+                    file: file.source.clone(),
+                    line: import.line,
+                    col: 1,
+                    message: format!("Imported file not found: {}", import.path),
+                });
+            }
+        }
+        source_files.push(SourceFile {
+            path: file.source.clone(),
+            namespace: file.namespace.clone(),
+            imports: edges,
+        });
+    }
+
+    // Selective imports: listed names exist (E014), others stay hidden (E015)
+    check_selective_imports(files, &loaded, &mut errors);
+
     // Detect circular imports (E003)
+    let import_paths: Vec<Vec<String>> = files
+        .iter()
+        .map(|f| f.imports.iter().map(|i| i.path.clone()).collect())
+        .collect();
     let file_imports: Vec<(&str, &[String])> = files
         .iter()
-        .map(|f| (f.source.as_str(), f.imports.as_slice()))
+        .zip(&import_paths)
+        .map(|(f, paths)| (f.source.as_str(), paths.as_slice()))
         .collect();
     let circular_errors = detect_circular_imports_internal(&file_imports);
     errors.extend(circular_errors);
@@ -298,11 +422,25 @@ pub fn resolve(files: &[ParsedFile], project: Option<ProjectInfo>) -> M3lAst {
         project_info.name = files.iter().find_map(|f| f.namespace.clone());
     }
 
-    M3lAst {
+    for elements in [
+        &mut all_models,
+        &mut all_interfaces,
+        &mut all_views,
+        &mut all_flows,
+        &mut generic_instances,
+    ] {
+        order_elements(elements, options.order);
+    }
+    if options.order != MergeOrder::SourceOrder {
+        all_enums.sort_by(|a, b| (&a.name, &a.namespace).cmp(&(&b.name, &b.namespace)));
+    }
+
+    let mut ast = M3lAst {
         parser_version: PARSER_VERSION.to_string(),
         ast_version: AST_VERSION.to_string(),
         project: project_info,
         sources,
+        files: source_files,
         models: all_models,
         enums: all_enums,
         interfaces: all_interfaces,
@@ -310,20 +448,267 @@ pub fn resolve(files: &[ParsedFile], project: Option<ProjectInfo>) -> M3lAst {
         flows: all_flows,
         extensions: all_extensions,
         attribute_registry: all_attr_registry,
+        file_metadata: files.iter().filter_map(|f| f.metadata.clone()).collect(),
+        m3l_versions: files.iter().filter_map(|f| f.m3l_version.clone()).collect(),
+        generic_instances,
+        extend_blocks: all_extend_blocks,
         errors,
         warnings,
+        symbols: SymbolTable::default(),
+    };
+    ast.index();
+    ast
+}
+
+/// Set `from` on the fields of `view` that select a source field: with
+/// `@from(Model.field)` (the model may be a join alias), or, for a field
+/// without a type, the field of the same name in the `from` model. Untyped
+/// fields copy the source field's type, parameters and nullability.
+fn expand_view_fields(view: &mut ModelNode, sources: &[&ModelNode]) {
+    let source_def = view.source_def.as_ref();
+    let primary = source_def
+        .and_then(|d| d.from.as_deref())
+        .and_then(|from| from.split_whitespace().next())
+        .map(String::from);
+    let join_aliases: HashMap<String, String> = source_def
+        .and_then(|d| d.joins.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|join| Some((join.alias.clone()?, join.model.clone())))
+        .collect();
+    let namespace = view.namespace.clone();
+
+    for field in &mut view.fields {
+        let path = field
+            .attributes
+            .iter()
+            .find(|a| a.name == "from")
+            .and_then(|a| a.args.as_ref()?.first())
+            .and_then(|arg| match arg {
+                AttrArgValue::String(path) => Some(path.as_str()),
+                _ => None,
+            });
+        let (model_name, field_name) = match (path, &primary) {
+            (Some(path), _) => match path.rsplit_once('.') {
+                Some((model, name)) => (model, name.to_string()),
+                None => continue,
+            },
+            (None, Some(primary))
+                if field.field_type.is_none() && field.kind == FieldKind::Stored =>
+            {
+                (primary.as_str(), field.name.clone())
+            }
+            _ => continue,
+        };
+        let model_name = join_aliases
+            .get(model_name)
+            .map(String::as_str)
+            .unwrap_or(model_name);
+        let Some((model, None)) =
+            find_reference_target_from(model_name, sources, namespace.as_deref())
+        else {
+            continue;
+        };
+        let Some(source) = model.fields.iter().find(|f| f.name == field_name) else {
+            continue;
+        };
+
+        if field.field_type.is_none() {
+            field.field_type = source.field_type.clone();
+            field.params = source.params.clone();
+            field.generic_params = source.generic_params.clone();
+            field.nullable = source.nullable;
+            field.array = source.array;
+            field.array_item_nullable = source.array_item_nullable;
+            field.enum_values = source.enum_values.clone();
+            field.union = source.union.clone();
+            field.tuple = source.tuple.clone();
+            field.type_alias = source.type_alias.clone();
+            if field.fields.is_none() {
+                field.fields = source.fields.clone();
+            }
+        }
+        field.from = Some(FromDef {
+            model: model.name.clone(),
+            field: field_name,
+        });
+    }
+}
+
+impl TypeAlias {
+    /// The alias `name` for the type written as `expansion`, such as
+    /// `string(320)` or `decimal(10,2)?`.
+    pub fn new(name: &str, expansion: &str) -> Result<Self, String> {
+        let invalid = |reason: &str| {
+            Err(format!(
+                "Invalid type alias \"{name}\" = \"{expansion}\": {reason}"
+            ))
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return invalid("the alias name must be a single word");
+        }
+        let mut data = TokenData::default();
+        crate::lexer::parse_type_and_attrs(expansion.trim(), &mut data);
+        let Some(field_type) = data.type_name else {
+            return invalid("not a type");
+        };
+        if !data.attributes.is_empty()
+            || data.default_value.is_some()
+            || data.description.is_some()
+            || !data.framework_attrs.is_empty()
+            || !data.union_types.is_empty()
+            || !data.tuple_types.is_empty()
+            || !data.enum_literals.is_empty()
+        {
+            return invalid("only a single type with parameters is allowed");
+        }
+        if field_type == name {
+            return invalid("an alias cannot expand to itself");
+        }
+        Ok(TypeAlias {
+            name: name.to_string(),
+            field_type,
+            params: (!data.type_params.is_empty()).then_some(data.type_params),
+            generic_params: (!data.type_generic_params.is_empty())
+                .then_some(data.type_generic_params),
+            nullable: data.nullable,
+            array: data.array,
+            array_item_nullable: data.array_item_nullable,
+        })
+    }
+}
+
+/// Replace field types named after a type alias with its expansion.
+/// Parameters written on the field take precedence over the alias's, and
+/// `?`/`[]` on either side apply.
+fn expand_type_aliases(fields: &mut [FieldNode], aliases: &[TypeAlias]) {
+    for field in fields {
+        let alias = field
+            .field_type
+            .as_deref()
+            .and_then(|t| aliases.iter().find(|a| a.name == t));
+        if let Some(alias) = alias {
+            field.field_type = Some(alias.field_type.clone());
+            if field.params.is_none() {
+                field.params = alias.params.clone();
+            }
+            if field.generic_params.is_none() {
+                field.generic_params = alias.generic_params.clone();
+            }
+            if field.array {
+                // `email[]`: a nullable alias makes the items nullable
+                field.array_item_nullable |= alias.nullable && !alias.array;
+            } else {
+                field.array = alias.array;
+                field.array_item_nullable = alias.array_item_nullable;
+                field.nullable |= alias.nullable;
+            }
+            field.type_alias = Some(alias.name.clone());
+        }
+        if let Some(ref mut sub_fields) = field.fields {
+            expand_type_aliases(sub_fields, aliases);
+        }
+    }
+}
+
+/// Put one list of merged elements in `order`.
+fn order_elements(elements: &mut Vec<ModelNode>, order: MergeOrder) {
+    if order == MergeOrder::SourceOrder {
+        return;
+    }
+    elements.sort_by(|a, b| (&a.name, &a.namespace).cmp(&(&b.name, &b.namespace)));
+    if order == MergeOrder::Alphabetical {
+        return;
+    }
+
+    // Depth-first from each element by name, its dependencies first
+    let deps: Vec<Vec<usize>> = {
+        let refs: Vec<&ModelNode> = elements.iter().collect();
+        let position = |name: &str| {
+            let (model, _) = find_reference_target(name, &refs)?;
+            refs.iter().position(|m| std::ptr::eq(*m, model))
+        };
+        elements
+            .iter()
+            .enumerate()
+            .map(|(i, element)| {
+                let mut names: Vec<&str> = element.inherits.iter().map(String::as_str).collect();
+                collect_reference_models(&element.fields, &mut names);
+                names.extend(
+                    element
+                        .sections
+                        .relations
+                        .iter()
+                        .filter_map(|r| r.target.as_deref()),
+                );
+                let mut found: Vec<usize> = names
+                    .into_iter()
+                    .filter_map(position)
+                    .filter(|&j| j != i)
+                    .collect();
+                found.sort_unstable();
+                found.dedup();
+                found
+            })
+            .collect()
+    };
+
+    fn visit(i: usize, deps: &[Vec<usize>], state: &mut [u8], result: &mut Vec<usize>) {
+        // 0: not yet, 1: on the current path (a cycle), 2: placed
+        if state[i] != 0 {
+            return;
+        }
+        state[i] = 1;
+        for &j in &deps[i] {
+            visit(j, deps, state, result);
+        }
+        state[i] = 2;
+        result.push(i);
+    }
+
+    let mut state = vec![0u8; elements.len()];
+    let mut result = Vec::with_capacity(elements.len());
+    for i in 0..elements.len() {
+        visit(i, &deps, &mut state, &mut result);
+    }
+    let mut slots: Vec<Option<ModelNode>> =
+        std::mem::take(elements).into_iter().map(Some).collect();
+    *elements = result.into_iter().filter_map(|i| slots[i].take()).collect();
+}
+
+fn collect_reference_models<'a>(fields: &'a [FieldNode], names: &mut Vec<&'a str>) {
+    for field in fields {
+        if let Some(reference) = &field.reference {
+            names.push(&reference.model);
+        }
+        if let Some(sub_fields) = &field.fields {
+            collect_reference_models(sub_fields, names);
+        }
     }
 }
 
+/// What a name written as a parent refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Parent {
+    Model(usize),
+    Interface(usize),
+    /// An enum, view, flow or extension: not a parent, but not an
+    /// unresolved name either
+    Other,
+}
+
+/// Names declared so far, by namespace-qualified name (E005).
 fn check_duplicate(
     name: &str,
+    namespace: Option<&str>,
     kind: &str,
     source: &str,
     line: usize,
-    all_named: &HashMap<String, (String, String, usize)>,
+    declared: &mut HashMap<String, (String, usize)>,
     errors: &mut Vec<Diagnostic>,
 ) {
-    if let Some((_, existing_file, existing_line)) = all_named.get(name) {
+    let qualified = qualified_name(namespace, name);
+    if let Some((existing_file, existing_line)) = declared.get(&qualified) {
         errors.push(Diagnostic {
             code: "M3L-E005".to_string(),
             severity: DiagnosticSeverity::Error,
@@ -335,17 +720,43 @@ fn check_duplicate(
                 kind, name, existing_file, existing_line
             ),
         });
+    } else {
+        declared.insert(qualified, (source.to_string(), line));
+    }
+}
+
+fn qualified_name(namespace: Option<&str>, name: &str) -> String {
+    match namespace {
+        Some(namespace) => format!("{namespace}.{name}"),
+        None => name.to_string(),
+    }
+}
+
+pub(crate) fn ambiguous_reference(
+    name: &str,
+    namespaces: &[String],
+    file: &str,
+    line: usize,
+) -> Diagnostic {
+    Diagnostic {
+        code: "M3L-E008".to_string(),
+        severity: DiagnosticSeverity::Error,
+        file: file.to_string(),
+        line,
+        col: 1,
+        message: format!(
+            "Ambiguous model reference \"{}\" in namespaces {}",
+            name,
+            namespaces.join(", ")
+        ),
     }
 }
 
-#[allow(clippy::too_many_arguments)]
 fn resolve_inheritance(
     model_idx: usize,
     all_models: &mut [ModelNode],
-    model_map: &HashMap<String, usize>,
     all_interfaces: &[ModelNode],
-    interface_map: &HashMap<String, usize>,
-    all_named: &HashMap<String, (String, String, usize)>,
+    parents: &Scope<Parent>,
     errors: &mut Vec<Diagnostic>,
 ) {
     let inherits = all_models[model_idx].inherits.clone();
@@ -354,102 +765,96 @@ fn resolve_inheritance(
     }
 
     let mut inherited_fields: Vec<FieldNode> = Vec::new();
-    let mut resolved: HashSet<String> = HashSet::new();
-    let mut visiting: HashSet<String> = HashSet::new();
+    let mut resolved: HashSet<Parent> = HashSet::new();
+    let mut visiting: HashSet<Parent> = HashSet::new();
 
-    let model_source = all_models[model_idx].source.clone();
-    let model_line = all_models[model_idx].line;
-    let model_name = all_models[model_idx].name.clone();
+    let model = &all_models[model_idx];
+    let namespace = model.namespace.clone();
+    let context = (model.source.clone(), model.line, model.name.clone());
 
+    // `from` is the namespace of the element that names the parent
+    #[allow(clippy::too_many_arguments)]
     fn collect_fields(
         name: &str,
-        model_source: &str,
-        model_line: usize,
-        model_name: &str,
+        from: Option<&str>,
+        context: &(String, usize, String),
         all_models: &[ModelNode],
-        model_map: &HashMap<String, usize>,
         all_interfaces: &[ModelNode],
-        interface_map: &HashMap<String, usize>,
-        all_named: &HashMap<String, (String, String, usize)>,
+        parents: &Scope<Parent>,
         inherited_fields: &mut Vec<FieldNode>,
-        resolved: &mut HashSet<String>,
-        visiting: &mut HashSet<String>,
+        resolved: &mut HashSet<Parent>,
+        visiting: &mut HashSet<Parent>,
         errors: &mut Vec<Diagnostic>,
     ) {
-        if resolved.contains(name) || visiting.contains(name) {
+        let (model_source, model_line, model_name) = context;
+        let parent = match parents.lookup(name, from) {
+            NameLookup::Found(Parent::Model(i)) => (Parent::Model(i), &all_models[i]),
+            NameLookup::Found(Parent::Interface(i)) => (Parent::Interface(i), &all_interfaces[i]),
+            NameLookup::Found(Parent::Other) => return,
+            NameLookup::Ambiguous(namespaces) => {
+                errors.push(ambiguous_reference(
+                    name,
+                    &namespaces,
+                    model_source,
+                    *model_line,
+                ));
+                return;
+            }
+            NameLookup::Missing => {
+                errors.push(Diagnostic {
+                    code: "M3L-E007".to_string(),
+                    severity: DiagnosticSeverity::Error,
+                    file: model_source.to_string(),
+                    line: *model_line,
+                    col: 1,
+                    message: format!(
+                        "Unresolved inheritance reference \"{}\" in model \"{}\"",
+                        name, model_name
+                    ),
+                });
+                return;
+            }
+        };
+        let (key, parent_model) = parent;
+        if resolved.contains(&key) || !visiting.insert(key) {
             return;
         }
-        visiting.insert(name.to_string());
 
-        let parent = model_map
-            .get(name)
-            .map(|&idx| &all_models[idx])
-            .or_else(|| interface_map.get(name).map(|&idx| &all_interfaces[idx]));
+        // Resolve grandparents first, as named in the parent's namespace
+        for grandparent in &parent_model.inherits {
+            collect_fields(
+                grandparent,
+                parent_model.namespace.as_deref(),
+                context,
+                all_models,
+                all_interfaces,
+                parents,
+                inherited_fields,
+                resolved,
+                visiting,
+                errors,
+            );
+        }
 
-        match parent {
-            None => {
-                if !all_named.contains_key(name) {
-                    errors.push(Diagnostic {
-                        code: "M3L-E007".to_string(),
-                        severity: DiagnosticSeverity::Error,
-                        file: model_source.to_string(),
-                        line: model_line,
-                        col: 1,
-                        message: format!(
-                            "Unresolved inheritance reference \"{}\" in model \"{}\"",
-                            name, model_name
-                        ),
-                    });
-                }
-                visiting.remove(name);
-                return;
-            }
-            Some(parent_model) => {
-                // Resolve grandparents first
-                let parent_inherits = parent_model.inherits.clone();
-                for grandparent in &parent_inherits {
-                    collect_fields(
-                        grandparent,
-                        model_source,
-                        model_line,
-                        model_name,
-                        all_models,
-                        model_map,
-                        all_interfaces,
-                        interface_map,
-                        all_named,
-                        inherited_fields,
-                        resolved,
-                        visiting,
-                        errors,
-                    );
-                }
-
-                // Add parent's fields
-                let parent_fields = parent_model.fields.clone();
-                for field in &parent_fields {
-                    if !inherited_fields.iter().any(|f| f.name == field.name) {
-                        inherited_fields.push(field.clone());
-                    }
-                }
+        // Add parent's fields
+        for field in &parent_model.fields {
+            if !inherited_fields.iter().any(|f| f.name == field.name) {
+                inherited_fields.push(field.clone());
             }
         }
 
-        visiting.remove(name);
-        resolved.insert(name.to_string());
+        visiting.remove(&key);
+        resolved.insert(key);
     }
 
     for parent_name in &inherits {
         collect_fields(
             parent_name,
-            &model_source,
-            model_line,
-            &model_name,
+            namespace.as_deref(),
+            &context,
             all_models,
-            model_map,
             all_interfaces,
-            interface_map,
-            all_named,
+            parents,
             &mut inherited_fields,
             &mut resolved,
             &mut visiting,
@@ -457,27 +862,141 @@ fn resolve_inheritance(
         );
     }
 
-    // Handle @override
-    let override_names: HashSet<String> = all_models[model_idx]
-        .fields
-        .iter()
-        .filter(|f| f.attributes.iter().any(|a| a.name == "override"))
-        .map(|f| f.name.clone())
-        .collect();
-
+    // Own fields replace inherited ones marked @override, and fields of
+    // interfaces, which the model may redeclare; the validator checks
+    // that those keep the interface's contract (M3L-E023)
+    let own_fields = &all_models[model_idx].fields;
+    let from_interface = |inherited: &FieldNode| {
+        all_interfaces
+            .iter()
+            .any(|i| i.fields.iter().any(|f| f.loc == inherited.loc))
+    };
     let filtered_inherited: Vec<FieldNode> = inherited_fields
         .into_iter()
-        .filter(|f| !override_names.contains(&f.name))
+        .filter(|inherited| {
+            !own_fields.iter().any(|f| {
+                f.name == inherited.name
+                    && (f.attributes.iter().any(|a| a.name == "override")
+                        || from_interface(inherited))
+            })
+        })
         .collect();
 
-    // Prepend inherited fields
+    // Prepend inherited fields, with the checks declared on them
     if !filtered_inherited.is_empty() {
+        let checks = &mut all_models[model_idx].sections.checks;
+        let own_checks = std::mem::take(checks);
+        checks.extend(filtered_inherited.iter().flat_map(field_checks));
+        checks.extend(own_checks);
         let own_fields = std::mem::take(&mut all_models[model_idx].fields);
         all_models[model_idx].fields = filtered_inherited;
         all_models[model_idx].fields.extend(own_fields);
     }
 }
 
+/// One model per distinct `Generic<Args>` field type, with the generic's
+/// type parameters replaced by the arguments. Uses inside a generic model
+/// that mention its own parameters are instantiated through the instances
+/// instead; uses with the wrong number of arguments are left to the
+/// validator.
+fn instantiate_generics(elements: &[&ModelNode]) -> Vec<ModelNode> {
+    let generics: HashMap<&str, &ModelNode> = elements
+        .iter()
+        .filter(|m| !m.generic_params.is_empty())
+        .map(|m| (m.name.as_str(), *m))
+        .collect();
+    let mut uses: Vec<(String, Vec<String>)> = Vec::new();
+    for element in elements {
+        collect_generic_uses(
+            &element.fields,
+            &element.generic_params,
+            &generics,
+            &mut uses,
+        );
+    }
+
+    let mut instances: Vec<ModelNode> = Vec::new();
+    let mut next = 0;
+    while next < uses.len() {
+        let (name, args) = uses[next].clone();
+        next += 1;
+        let generic = generics[name.as_str()];
+        let bindings: HashMap<&str, &str> = generic
+            .generic_params
+            .iter()
+            .map(String::as_str)
+            .zip(args.iter().map(String::as_str))
+            .collect();
+
+        let mut instance = generic.clone();
+        instance.name = format!("{}<{}>", name, args.join(", "));
+        instance.generic_params = Vec::new();
+        substitute_type_params(&mut instance.fields, &bindings);
+        instance.instance_of = Some(name);
+        instance.generic_args = args;
+        collect_generic_uses(&instance.fields, &[], &generics, &mut uses);
+        instances.push(instance);
+    }
+    instances
+}
+
+fn collect_generic_uses(
+    fields: &[FieldNode],
+    own_params: &[String],
+    generics: &HashMap<&str, &ModelNode>,
+    uses: &mut Vec<(String, Vec<String>)>,
+) {
+    for field in fields {
+        if let (Some(type_name), Some(args)) = (&field.field_type, &field.generic_params) {
+            let fits = generics
+                .get(type_name.as_str())
+                .is_some_and(|g| g.generic_params.len() == args.len());
+            let open = args.iter().any(|a| own_params.contains(a));
+            let key = (type_name.clone(), args.clone());
+            if fits && !open && !uses.contains(&key) {
+                uses.push(key);
+            }
+        }
+        if let Some(ref sub_fields) = field.fields {
+            collect_generic_uses(sub_fields, own_params, generics, uses);
+        }
+    }
+}
+
+/// Replace type parameters in field types, type arguments and string
+/// attribute arguments (`@reference(T)`).
+fn substitute_type_params(fields: &mut [FieldNode], bindings: &HashMap<&str, &str>) {
+    let bind = |s: &mut String| {
+        if let Some(arg) = bindings.get(s.as_str()) {
+            *s = arg.to_string();
+        }
+    };
+    for field in fields {
+        if let Some(ref mut type_name) = field.field_type {
+            bind(type_name);
+        }
+        for arg in field.generic_params.iter_mut().flatten() {
+            bind(arg);
+        }
+        for member in field.union.iter_mut().chain(&mut field.tuple).flatten() {
+            bind(&mut member.member_type);
+            for arg in member.generic_params.iter_mut().flatten() {
+                bind(arg);
+            }
+        }
+        for attr in &mut field.attributes {
+            for arg in attr.args.iter_mut().flatten() {
+                if let AttrArgValue::String(s) = arg {
+                    bind(s);
+                }
+            }
+        }
+        if let Some(ref mut sub_fields) = field.fields {
+            substitute_type_params(sub_fields, bindings);
+        }
+    }
+}
+
 fn check_duplicate_fields(model: &ModelNode, errors: &mut Vec<Diagnostic>) {
     let mut seen: HashMap<String, usize> = HashMap::new(); // name → line
     for field in &model.fields {
@@ -507,6 +1026,370 @@ fn check_duplicate_fields(model: &ModelNode, errors: &mut Vec<Diagnostic>) {
     }
 }
 
+/// Fill in `reference` on each `@reference`/`@fk` field whose target
+/// resolves, and check its referential actions (E024). A generic model's type parameters resolve per instance.
+fn resolve_references(
+    fields: &mut [FieldNode],
+    type_params: &[String],
+    from: Option<&str>,
+    targets: &[&ModelNode],
+    errors: &mut Vec<Diagnostic>,
+) {
+    for field in fields {
+        let reference = field
+            .attributes
+            .iter()
+            .find(|a| a.name == "reference" || a.name == "fk");
+        if let Some(attr) = reference {
+            check_referential_actions(field, errors);
+            if let Some(AttrArgValue::String(target)) = attr.args.as_ref().and_then(|a| a.first()) {
+                if !type_params.contains(target) {
+                    field.reference = reference_def(target, attr, field, from, targets);
+                }
+            }
+        }
+        if let Some(ref mut sub_fields) = field.fields {
+            resolve_references(sub_fields, type_params, from, targets, errors);
+        }
+    }
+}
+
+fn reference_def(
+    target: &str,
+    attr: &FieldAttribute,
+    field: &FieldNode,
+    from: Option<&str>,
+    targets: &[&ModelNode],
+) -> Option<ReferenceDef> {
+    let (model, target_field) = find_reference_target_from(target, targets, from)?;
+    let target_field = match target_field {
+        Some(name) => Some(model.fields.iter().find(|f| f.name == name)?.name.clone()),
+        None => {
+            let mut keys = model.fields.iter().filter(|f| {
+                f.attributes
+                    .iter()
+                    .any(|a| a.name == "primary" || a.name == "pk")
+            });
+            // A composite key has no single field to point at
+            match (keys.next(), keys.next()) {
+                (Some(key), None) => Some(key.name.clone()),
+                _ => None,
+            }
+        }
+    };
+    let on_delete = attr
+        .cascade
+        .as_deref()
+        .and_then(ReferentialAction::from_symbol)
+        .or_else(|| action_attribute(field, "on_delete"))
+        .unwrap_or(if field.nullable {
+            ReferentialAction::SetNull
+        } else {
+            ReferentialAction::Cascade
+        });
+    Some(ReferenceDef {
+        model: model.name.clone(),
+        namespace: model.namespace.clone(),
+        field: target_field,
+        on_delete,
+        on_update: action_attribute(field, "on_update"),
+    })
+}
+
+/// The action an `@on_delete`/`@on_update` attribute (or extended-format
+/// item) names, if it names one.
+fn action_attribute(field: &FieldNode, name: &str) -> Option<ReferentialAction> {
+    match field
+        .attributes
+        .iter()
+        .find(|a| a.name == name)?
+        .args
+        .as_ref()?
+        .first()?
+    {
+        AttrArgValue::String(text) => ReferentialAction::parse(text),
+        _ => None,
+    }
+}
+
+/// `@on_delete`/`@on_update` on a reference field naming no action (E024).
+fn check_referential_actions(field: &FieldNode, errors: &mut Vec<Diagnostic>) {
+    for attr in &field.attributes {
+        if attr.name != "on_delete" && attr.name != "on_update" {
+            continue;
+        }
+        let text = match attr.args.as_ref().and_then(|a| a.first()) {
+            Some(AttrArgValue::String(text)) => text.clone(),
+            Some(AttrArgValue::Number(n)) => n.to_string(),
+            Some(AttrArgValue::Bool(b)) => b.to_string(),
+            _ => String::new(),
+        };
+        if ReferentialAction::parse(&text).is_some() {
+            continue;
+        }
+        let loc = attr.loc.as_ref().unwrap_or(&field.loc);
+        let diagnostic = Diagnostic {
+            code: "M3L-E024".to_string(),
+            severity: DiagnosticSeverity::Error,
+            file: loc.file.clone(),
+            line: loc.line,
+            col: loc.col,
+            message: format!(
+                "Unknown referential action \"{}\" in @{} of field \"{}\" (expected cascade, set_null, no_action or restrict)",
+                text, attr.name, field.name
+            ),
+        };
+        if !errors.contains(&diagnostic) {
+            errors.push(diagnostic);
+        }
+    }
+}
+
+/// Elements by name within their namespaces, for names written short
+/// (`BaseModel`) or qualified (`sales.BaseModel`).
+#[derive(Debug)]
+pub(crate) struct Scope<T> {
+    names: HashMap<String, Vec<(Option<String>, T)>>,
+}
+
+impl<T> Default for Scope<T> {
+    fn default() -> Self {
+        Scope {
+            names: HashMap::new(),
+        }
+    }
+}
+
+/// What a name resolves to in a [`Scope`].
+#[derive(Debug, PartialEq)]
+pub(crate) enum NameLookup<T> {
+    Found(T),
+    /// A short name defined in several namespaces, none of them the
+    /// referring element's: those namespaces (M3L-E008)
+    Ambiguous(Vec<String>),
+    Missing,
+}
+
+impl<T: Copy> Scope<T> {
+    pub(crate) fn insert(&mut self, name: &str, namespace: Option<&str>, value: T) {
+        self.names
+            .entry(name.to_string())
+            .or_default()
+            .push((namespace.map(String::from), value));
+    }
+
+    /// Resolve `name` as written in an element of namespace `from`. A
+    /// qualified name only matches that namespace; a short one prefers
+    /// `from`, and is ambiguous when defined in several other namespaces.
+    pub(crate) fn lookup(&self, name: &str, from: Option<&str>) -> NameLookup<T> {
+        if let Some(entries) = self.names.get(name) {
+            if let Some((_, value)) = entries.iter().find(|(ns, _)| ns.as_deref() == from) {
+                return NameLookup::Found(*value);
+            }
+            let mut namespaces: Vec<String> = entries
+                .iter()
+                .map(|(ns, _)| ns.as_deref().unwrap_or("(none)").to_string())
+                .collect();
+            namespaces.sort();
+            namespaces.dedup();
+            return match namespaces.len() {
+                1 => NameLookup::Found(entries[0].1),
+                _ => NameLookup::Ambiguous(namespaces),
+            };
+        }
+        if let Some((namespace, short)) = name.rsplit_once('.') {
+            let found = self.names.get(short).and_then(|entries| {
+                entries
+                    .iter()
+                    .find(|(ns, _)| ns.as_deref() == Some(namespace))
+            });
+            if let Some((_, value)) = found {
+                return NameLookup::Found(*value);
+            }
+        }
+        NameLookup::Missing
+    }
+}
+
+/// Resolve a reference target such as `User`, `User.id`, `Auth.User` or
+/// `Auth.User.id` against a set of models.
+///
+/// Qualified targets only match a model declared in that namespace. Returns
+/// the model and the trailing field name, if any; the longest model path wins.
+/// A model's aliases match like its name.
+pub fn find_reference_target<'a, 't>(
+    target: &'t str,
+    models: &[&'a ModelNode],
+) -> Option<(&'a ModelNode, Option<&'t str>)> {
+    find_reference_target_from(target, models, None)
+}
+
+/// Like [`find_reference_target`], for a target written in namespace
+/// `from`: a short name defined in several namespaces prefers the model
+/// in `from` (`None`: the one outside any namespace).
+pub fn find_reference_target_from<'a, 't>(
+    target: &'t str,
+    models: &[&'a ModelNode],
+    from: Option<&str>,
+) -> Option<(&'a ModelNode, Option<&'t str>)> {
+    let segments: Vec<&str> = target.split('.').collect();
+    for i in (1..=segments.len()).rev() {
+        let rest = &segments[i..];
+        if rest.len() > 1 {
+            continue;
+        }
+        let name = segments[i - 1];
+        let namespace = segments[..i - 1].join(".");
+        let matches = |m: &&&ModelNode| {
+            (m.name == name || m.aliases.iter().any(|a| a == name))
+                && (namespace.is_empty() || m.namespace.as_deref() == Some(&namespace))
+        };
+        let found = models
+            .iter()
+            .filter(matches)
+            .find(|m| namespace.is_empty() && m.namespace.as_deref() == from)
+            .or_else(|| models.iter().find(matches));
+        if let Some(model) = found {
+            return Some((model, rest.first().copied()));
+        }
+    }
+    None
+}
+
+/// Check the selective imports (`@import "base.m3l.md" (BaseModel)`) of
+/// each file: every listed name must be defined in the imported file
+/// (E014), and the imported file's other elements are out of scope in the
+/// importing file, so using one as a parent, type, reference target or view
+/// source is an error (E015). Importing the same file without a list, or
+/// defining the name locally, brings it back into scope.
+fn check_selective_imports(files: &[ParsedFile], loaded: &[String], errors: &mut Vec<Diagnostic>) {
+    let all_models: Vec<&ModelNode> = files.iter().flat_map(|f| f.models.iter()).collect();
+    for file in files {
+        let imported = |path: &str| {
+            imported_source(&file.source, path, loaded)
+                .and_then(|source| loaded.iter().position(|l| l == source))
+        };
+        // Hidden name → the import that hides it
+        let mut hidden: HashMap<&str, &str> = HashMap::new();
+        for import in &file.imports {
+            let Some(symbols) = &import.symbols else {
+                continue;
+            };
+            // A missing file is reported as E013
+            let Some(index) = imported(&import.path) else {
+                continue;
+            };
+            let defined = element_names(&files[index]);
+            for symbol in symbols {
+                if !defined.contains(&symbol.as_str()) {
+                    errors.push(Diagnostic {
+                        code: "M3L-E014".to_string(),
+                        severity: DiagnosticSeverity::Error,
+                        file: file.source.clone(),
+                        line: import.line,
+                        col: 1,
+                        message: format!("\"{symbol}\" is not defined in {}", import.path),
+                    });
+                }
+            }
+            let whole = file
+                .imports
+                .iter()
+                .any(|i| i.symbols.is_none() && imported(&i.path) == Some(index));
+            if !whole {
+                for name in defined {
+                    hidden.entry(name).or_insert(&import.path);
+                }
+            }
+        }
+        for symbol in file.imports.iter().flat_map(|i| i.symbols.iter().flatten()) {
+            hidden.remove(symbol.as_str());
+        }
+        for name in element_names(file) {
+            hidden.remove(name);
+        }
+        if hidden.is_empty() {
+            continue;
+        }
+
+        let elements = file
+            .models
+            .iter()
+            .chain(&file.interfaces)
+            .chain(&file.views);
+        for element in elements {
+            for (name, line) in scope_uses(element, &all_models) {
+                if let Some(path) = hidden.get(name) {
+                    errors.push(Diagnostic {
+                        code: "M3L-E015".to_string(),
+                        severity: DiagnosticSeverity::Error,
+                        file: file.source.clone(),
+                        line,
+                        col: 1,
+                        message: format!(
+                            "\"{name}\" is used by \"{}\" but not imported from {path}; add it to the import list",
+                            element.name
+                        ),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Names of the models, enums, interfaces and views a file defines.
+fn element_names(file: &ParsedFile) -> Vec<&str> {
+    file.models
+        .iter()
+        .chain(&file.interfaces)
+        .chain(&file.views)
+        .map(|m| m.name.as_str())
+        .chain(file.enums.iter().map(|e| e.name.as_str()))
+        .collect()
+}
+
+/// Names an element uses, with the line of each use: parents, field types,
+/// `@reference`/`@fk` targets and a view's `from:` model.
+fn scope_uses<'a>(element: &'a ModelNode, models: &[&'a ModelNode]) -> Vec<(&'a str, usize)> {
+    fn field_uses<'a>(
+        fields: &'a [FieldNode],
+        models: &[&'a ModelNode],
+        uses: &mut Vec<(&'a str, usize)>,
+    ) {
+        for field in fields {
+            if let Some(type_name) = &field.field_type {
+                uses.push((type_name, field.loc.line));
+            }
+            for attr in &field.attributes {
+                if attr.name != "reference" && attr.name != "fk" {
+                    continue;
+                }
+                let Some(AttrArgValue::String(target)) = attr.args.as_ref().and_then(|a| a.first())
+                else {
+                    continue;
+                };
+                if let Some((model, _)) = find_reference_target(target, models) {
+                    uses.push((&model.name, field.loc.line));
+                }
+            }
+            if let Some(sub_fields) = &field.fields {
+                field_uses(sub_fields, models, uses);
+            }
+        }
+    }
+
+    let mut uses: Vec<(&str, usize)> = element
+        .inherits
+        .iter()
+        .map(|parent| (parent.as_str(), element.line))
+        .collect();
+    if let Some(from) = element.source_def.as_ref().and_then(|s| s.from.as_deref()) {
+        uses.push((from, element.line));
+    }
+    field_uses(&element.fields, models, &mut uses);
+    uses
+}
+
 /// Detect circular imports in a set of parsed files.
 ///
 /// Takes a list of (source_path, import_paths) pairs and returns diagnostics
@@ -522,27 +1405,52 @@ pub fn detect_circular_imports(file_imports: &[(String, Vec<String>)]) -> Vec<Di
 fn detect_circular_imports_internal(file_imports: &[(&str, &[String])]) -> Vec<Diagnostic> {
     let mut errors = Vec::new();
 
-    // Build adjacency map: source → imports
-    let adj: HashMap<&str, &[String]> = file_imports
+    // Build adjacency map over normalized paths: source → imported sources
+    let sources: Vec<String> = file_imports
         .iter()
-        .map(|&(src, imports)| (src, imports))
+        .map(|&(src, _)| normalize_path(src))
+        .collect();
+    let edges: Vec<Vec<&str>> = file_imports
+        .iter()
+        .map(|&(src, imports)| {
+            imports
+                .iter()
+                .filter_map(|import| imported_source(src, import, &sources))
+                .collect()
+        })
+        .collect();
+    let adj: HashMap<&str, &[&str]> = sources
+        .iter()
+        .map(String::as_str)
+        .zip(edges.iter().map(Vec::as_slice))
+        .collect();
+    // Diagnostics name the file as it was given
+    let originals: HashMap<&str, &str> = sources
+        .iter()
+        .map(String::as_str)
+        .zip(file_imports.iter().map(|&(src, _)| src))
         .collect();
 
     let mut visited: HashSet<&str> = HashSet::new();
     let mut rec_stack: Vec<&str> = Vec::new();
 
-    for &(src, _) in file_imports {
-        if !visited.contains(src) {
+    for src in &sources {
+        if !visited.contains(src.as_str()) {
             dfs_detect_cycle(src, &adj, &mut visited, &mut rec_stack, &mut errors);
         }
     }
 
+    for error in &mut errors {
+        if let Some(original) = originals.get(error.file.as_str()) {
+            error.file = original.to_string();
+        }
+    }
     errors
 }
 
 fn dfs_detect_cycle<'a>(
     node: &'a str,
-    adj: &HashMap<&'a str, &'a [String]>,
+    adj: &HashMap<&'a str, &'a [&'a str]>,
     visited: &mut HashSet<&'a str>,
     rec_stack: &mut Vec<&'a str>,
     errors: &mut Vec<Diagnostic>,
@@ -551,8 +1459,7 @@ fn dfs_detect_cycle<'a>(
     rec_stack.push(node);
 
     if let Some(imports) = adj.get(node) {
-        for import in *imports {
-            let import_str = import.as_str();
+        for &import_str in *imports {
             if !visited.contains(import_str) {
                 if adj.contains_key(import_str) {
                     dfs_detect_cycle(import_str, adj, visited, rec_stack, errors);
@@ -583,6 +1490,61 @@ fn dfs_detect_cycle<'a>(
     rec_stack.pop();
 }
 
+/// Path of the file that `import`, written in `importer`, names relative to
+/// the importer's directory, with `.` and `..` folded and `/` separators.
+pub fn import_target(importer: &str, import: &str) -> String {
+    let import = import.replace('\\', "/");
+    if import.starts_with('/') {
+        return normalize_path(&import);
+    }
+    let importer = importer.replace('\\', "/");
+    match importer.rsplit_once('/') {
+        Some((dir, _)) => normalize_path(&format!("{dir}/{import}")),
+        None => normalize_path(&import),
+    }
+}
+
+/// The loaded source (from `loaded`, normalized) that `import` refers to.
+/// Relative imports (`./`, `../`) only match the file next to the importer.
+/// Package paths are relative to the project root, which the resolver does
+/// not know, so any source ending in the path matches, preferring the one
+/// next to the importer.
+fn imported_source<'a>(importer: &str, import: &str, loaded: &'a [String]) -> Option<&'a str> {
+    let target = import_target(importer, import);
+    if let Some(found) = loaded.iter().find(|s| **s == target) {
+        return Some(found);
+    }
+    if import.starts_with("./") || import.starts_with("../") || import.starts_with('/') {
+        return None;
+    }
+    let path = normalize_path(import);
+    let suffix = format!("/{path}");
+    loaded
+        .iter()
+        .find(|s| **s == path || s.ends_with(&suffix))
+        .map(String::as_str)
+}
+
+fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." if parts.last().is_some_and(|p| *p != "..") => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    let joined = parts.join("/");
+    if path.starts_with('/') {
+        format!("/{joined}")
+    } else {
+        joined
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -600,6 +1562,42 @@ mod tests {
         assert!(ast.errors.is_empty());
     }
 
+    #[test]
+    fn resolve_generic_instances() {
+        let parsed = parse_string(
+            "## Product\n- id: identifier @pk\n\n## Paged<T>\n- items: T[]\n- first_id: identifier @reference(T)\n- meta: object\n  - sample: T?\n- next: Cursor<T>\n\n## Cursor<T>\n- after: T?\n\n## Catalog\n- id: identifier @pk\n- products: Paged<Product>\n- again: Paged<Product>\n- wrong: Paged<Product, Product>",
+            "test.m3l.md",
+        );
+        let ast = resolve(&[parsed], None);
+        assert!(ast.errors.is_empty());
+        // Generic definitions stay as written
+        assert_eq!(ast.models[1].generic_params, vec!["T"]);
+        let names: Vec<&str> = ast
+            .generic_instances
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(names, ["Paged<Product>", "Cursor<Product>"]);
+
+        let paged = &ast.generic_instances[0];
+        assert_eq!(paged.instance_of.as_deref(), Some("Paged"));
+        assert_eq!(paged.generic_args, vec!["Product"]);
+        assert!(paged.generic_params.is_empty());
+        let field = |name: &str| paged.fields.iter().find(|f| f.name == name).unwrap();
+        assert_eq!(field("items").field_type.as_deref(), Some("Product"));
+        assert!(field("items").array);
+        assert_eq!(
+            field("first_id").attributes[0].args,
+            Some(vec![AttrArgValue::String("Product".into())])
+        );
+        let sample = &field("meta").fields.as_ref().unwrap()[0];
+        assert_eq!(sample.field_type.as_deref(), Some("Product"));
+        assert_eq!(
+            field("next").generic_params,
+            Some(vec!["Product".to_string()])
+        );
+    }
+
     #[test]
     fn resolve_multiple_files() {
         let f1 = parse_string("## User\n- id: identifier", "a.m3l.md");
@@ -624,6 +1622,74 @@ mod tests {
         assert_eq!(ast.models[0].fields[2].name, "id");
     }
 
+    #[test]
+    fn resolve_inherited_checks() {
+        let input = "## Priced ::interface\n- price: decimal @check(`price >= 0`)\n\n## Product : Priced\n- stock: integer @check(`stock >= 0`)\n- @check(`price > 0 or stock = 0`)";
+        let ast = resolve(&[parse_string(input, "test.m3l.md")], None);
+        let checks: Vec<(&str, Option<&str>)> = ast.models[0]
+            .sections
+            .checks
+            .iter()
+            .map(|c| (c.expression.as_str(), c.field.as_deref()))
+            .collect();
+        assert_eq!(
+            checks,
+            [
+                ("price >= 0", Some("price")),
+                ("stock >= 0", Some("stock")),
+                ("price > 0 or stock = 0", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_model_aliases() {
+        let input = "## Auditable ::interface\n- created_at: timestamp\n\n## User : Auditable @alias(Account, Member)\n- id: identifier @pk\n\n## Admin : Account\n- level: integer";
+        let ast = resolve(&[parse_string(input, "test.m3l.md")], None);
+        assert!(ast.errors.is_empty(), "errors: {:?}", ast.errors);
+        assert_eq!(ast.models[0].aliases, ["Account", "Member"]);
+        let admin: Vec<&str> = ast.models[1]
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(admin, ["created_at", "id", "level"]);
+
+        let input = "## User @alias(Account)\n- id: identifier\n\n## Account\n- id: identifier";
+        let ast = resolve(&[parse_string(input, "test.m3l.md")], None);
+        assert!(ast.errors.iter().any(
+            |e| e.code == "M3L-E005" && e.message.contains("Duplicate alias name \"Account\"")
+        ));
+    }
+
+    #[test]
+    fn resolve_extend_blocks() {
+        let base = parse_string(
+            "## User\n- id: identifier @pk\n\n## Admin : User\n- level: integer",
+            "base.m3l.md",
+        );
+        let ext = parse_string(
+            "## extend User\n- email: email\n\n### Indexes\n- by_email\n  - fields: [email]\n\n## extend Missing\n- x: string",
+            "ext.m3l.md",
+        );
+        assert_eq!(ext.extend_blocks.len(), 2);
+        assert!(ext.models.is_empty());
+        let ast = resolve(&[base, ext], None);
+        let names =
+            |m: &ModelNode| -> Vec<String> { m.fields.iter().map(|f| f.name.clone()).collect() };
+        assert_eq!(names(&ast.models[0]), ["id", "email"]);
+        assert_eq!(ast.models[0].sections.indexes.len(), 1);
+        // Children see the additions
+        assert_eq!(names(&ast.models[1]), ["id", "email", "level"]);
+        assert_eq!(ast.extend_blocks.len(), 2);
+        let errors: Vec<(&str, &str, usize)> = ast
+            .errors
+            .iter()
+            .map(|e| (e.code.as_str(), e.file.as_str(), e.line))
+            .collect();
+        assert_eq!(errors, [("M3L-E021", "ext.m3l.md", 8)]);
+    }
+
     #[test]
     fn resolve_duplicate_model() {
         let f1 = parse_string("## User\n- id: identifier", "a.m3l.md");
@@ -672,11 +1738,43 @@ mod tests {
             "# Namespace: inventory\n## Product\n- id: identifier",
             "inventory.m3l.md",
         );
-        let ast = resolve(&[f1, f2], None);
-        assert!(
-            ast.errors.iter().any(|e| e.code == "M3L-E008"),
-            "Expected E008 for same name 'Product' in different namespaces"
+        // The same name in two namespaces is fine on its own
+        let ast = resolve(&[f1.clone(), f2.clone()], None);
+        assert!(ast.errors.is_empty(), "{:?}", ast.errors);
+
+        let f3 = parse_string(
+            "# Namespace: shop\n## Item : Product\n- sku: string\n\n## Listing : inventory.Product\n- price: decimal",
+            "shop.m3l.md",
+        );
+        let ast = resolve(&[f1, f2, f3], None);
+        let e008: Vec<&Diagnostic> = ast.errors.iter().filter(|e| e.code == "M3L-E008").collect();
+        assert_eq!(e008.len(), 1, "{:?}", ast.errors);
+        assert_eq!(e008[0].line, 2);
+        assert!(e008[0].message.contains("inventory, sales"));
+        let listing = ast.models.iter().find(|m| m.name == "Listing").unwrap();
+        assert_eq!(listing.fields.len(), 2);
+    }
+
+    #[test]
+    fn resolve_qualified_parents_by_namespace() {
+        let base = parse_string(
+            "# Namespace: sales\n## BaseModel\n- id: identifier\n\n# Namespace: auth\n## BaseModel\n- user_id: identifier",
+            "base.m3l.md",
         );
+        let orders = parse_string(
+            "# Namespace: sales\n## Order : BaseModel\n- total: decimal\n\n## Session : auth.BaseModel\n- token: string\n\n## Refund : billing.BaseModel\n- amount: decimal",
+            "orders.m3l.md",
+        );
+        let ast = resolve(&[base, orders], None);
+        let fields = |name: &str| -> Vec<String> {
+            let model = ast.models.iter().find(|m| m.name == name).unwrap();
+            model.fields.iter().map(|f| f.name.clone()).collect()
+        };
+        // A short parent means the one in the model's own namespace
+        assert_eq!(fields("Order"), ["id", "total"]);
+        assert_eq!(fields("Session"), ["user_id", "token"]);
+        let codes: Vec<&str> = ast.errors.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(codes, ["M3L-E007"], "{:?}", ast.errors);
     }
 
     #[test]
@@ -766,12 +1864,165 @@ mod tests {
         );
     }
 
+    #[test]
+    fn detect_circular_import_through_relative_paths() {
+        let file_imports = vec![
+            (
+                "schema/a.m3l.md".to_string(),
+                vec!["./sub/b.m3l.md".to_string()],
+            ),
+            (
+                "schema/sub/b.m3l.md".to_string(),
+                vec!["../a.m3l.md".to_string()],
+            ),
+        ];
+        let errors = detect_circular_imports(&file_imports);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].file, "schema/sub/b.m3l.md");
+        assert!(errors[0]
+            .message
+            .contains("schema/a.m3l.md → schema/sub/b.m3l.md → schema/a.m3l.md"));
+    }
+
+    #[test]
+    fn import_target_is_relative_to_importer() {
+        assert_eq!(
+            import_target("schema/orders.m3l.md", "./base.m3l.md"),
+            "schema/base.m3l.md"
+        );
+        assert_eq!(
+            import_target("./schema/sales/orders.m3l.md", "../common/base.m3l.md"),
+            "schema/common/base.m3l.md"
+        );
+        assert_eq!(
+            import_target("schema\\orders.m3l.md", "base.m3l.md"),
+            "schema/base.m3l.md"
+        );
+        assert_eq!(
+            import_target("orders.m3l.md", "./base.m3l.md"),
+            "base.m3l.md"
+        );
+        assert_eq!(
+            import_target("/srv/orders.m3l.md", "../base.m3l.md"),
+            "/base.m3l.md"
+        );
+    }
+
+    #[test]
+    fn resolve_records_file_graph() {
+        let base = parse_string(
+            "# Namespace: shop.base\n\n## Base\n- id: identifier",
+            "schema/base.m3l.md",
+        );
+        let orders = parse_string(
+            "@import \"./base.m3l.md\" (Base)\n@import \"./audit.m3l.md\"\n\n## Order : Base\n- total: decimal",
+            "schema/orders.m3l.md",
+        );
+        let ast = resolve(&[base, orders], None);
+        assert_eq!(ast.files.len(), 2);
+        assert_eq!(ast.files[0].path, "schema/base.m3l.md");
+        assert_eq!(ast.files[0].namespace.as_deref(), Some("shop.base"));
+        assert!(ast.files[0].imports.is_empty());
+
+        let imports = &ast.files[1].imports;
+        assert_eq!(ast.files[1].namespace, None);
+        assert_eq!(imports.len(), 2);
+        assert_eq!(imports[0].path, "./base.m3l.md");
+        assert_eq!(imports[0].target.as_deref(), Some("schema/base.m3l.md"));
+        assert_eq!(imports[0].symbols, Some(vec!["Base".to_string()]));
+        assert_eq!(imports[0].line, 1);
+        // Not loaded (E013)
+        assert_eq!(imports[1].target, None);
+    }
+
+    #[test]
+    fn resolve_reports_missing_import_e013() {
+        let base = parse_string("## Base\n- id: identifier", "schema/base.m3l.md");
+        let orders = parse_string(
+            "@import \"./base.m3l.md\"\n@import \"./audit.m3l.md\"\n\n## Order : Base\n- total: decimal",
+            "schema/orders.m3l.md",
+        );
+        let ast = resolve(&[base, orders], None);
+        let missing: Vec<_> = ast.errors.iter().filter(|e| e.code == "M3L-E013").collect();
+        assert_eq!(missing.len(), 1, "{:?}", ast.errors);
+        assert_eq!(missing[0].file, "schema/orders.m3l.md");
+        assert!(missing[0].message.contains("./audit.m3l.md"));
+    }
+
+    #[test]
+    fn selective_import_e014_unknown_symbol() {
+        let base = parse_string(
+            "## BaseModel\n- id: identifier\n\n## Timestampable ::interface\n- created_at: timestamp",
+            "base.m3l.md",
+        );
+        let orders = parse_string(
+            "@import \"./base.m3l.md\" (BaseModel, Auditable)\n\n## Order : BaseModel\n- total: decimal",
+            "orders.m3l.md",
+        );
+        let ast = resolve(&[base, orders], None);
+        let e014: Vec<_> = ast.errors.iter().filter(|e| e.code == "M3L-E014").collect();
+        assert_eq!(e014.len(), 1, "{:?}", ast.errors);
+        assert!(e014[0].message.contains("\"Auditable\""));
+        assert_eq!(e014[0].line, 1);
+    }
+
+    #[test]
+    fn selective_import_e015_unlisted_symbol() {
+        let base = parse_string(
+            "## BaseModel\n- id: identifier @pk\n\n## Customer\n- id: identifier @pk\n\n## Status ::enum\n- active \"Active\"",
+            "base.m3l.md",
+        );
+        let orders = parse_string(
+            "@import \"./base.m3l.md\" (BaseModel)\n\n## Order : BaseModel\n- customer_id: identifier @reference(Customer)\n- status: Status",
+            "orders.m3l.md",
+        );
+        let ast = resolve(&[base.clone(), orders], None);
+        let e015: Vec<_> = ast.errors.iter().filter(|e| e.code == "M3L-E015").collect();
+        assert_eq!(e015.len(), 2, "{:?}", ast.errors);
+        assert!(e015.iter().any(|e| e.message.contains("\"Customer\"")));
+        assert!(e015.iter().any(|e| e.message.contains("\"Status\"")));
+
+        // Listing the names, or importing the whole file as well, brings them into scope
+        for header in [
+            "@import \"./base.m3l.md\" (BaseModel, Customer, Status)",
+            "@import \"./base.m3l.md\" (BaseModel)\n@import \"./base.m3l.md\"",
+        ] {
+            let orders = parse_string(
+                &format!("{header}\n\n## Order : BaseModel\n- customer_id: identifier @reference(Customer)\n- status: Status"),
+                "orders.m3l.md",
+            );
+            let ast = resolve(&[base.clone(), orders], None);
+            assert!(
+                !ast.errors.iter().any(|e| e.code == "M3L-E015"),
+                "{header}: {:?}",
+                ast.errors
+            );
+        }
+    }
+
+    #[test]
+    fn package_import_matches_any_directory() {
+        let base = parse_string("## Base\n- id: identifier", "schema/common/base.m3l.md");
+        let orders = parse_string(
+            "@import \"common/base.m3l.md\"\n\n## Order : Base\n- total: decimal",
+            "schema/sales/orders.m3l.md",
+        );
+        let ast = resolve(&[base, orders], None);
+        assert!(
+            !ast.errors.iter().any(|e| e.code == "M3L-E013"),
+            "{:?}",
+            ast.errors
+        );
+    }
+
     #[test]
     fn parse_import_directive() {
         let input = "@import \"base.m3l.md\"\n\n## User\n- id: identifier";
         let parsed = parse_string(input, "test.m3l.md");
         assert_eq!(parsed.imports.len(), 1);
-        assert_eq!(parsed.imports[0], "base.m3l.md");
+        assert_eq!(parsed.imports[0].path, "base.m3l.md");
+        assert_eq!(parsed.imports[0].symbols, None);
+        assert_eq!(parsed.imports[0].line, 1);
     }
 
     #[test]
@@ -783,4 +2034,249 @@ mod tests {
         assert_eq!(ast.models[0].fields.len(), 1);
         assert_eq!(ast.models[0].fields[0].field_type.as_deref(), Some("text"));
     }
+
+    #[test]
+    fn resolve_reference_targets() {
+        use ReferentialAction::*;
+        let input = "# Namespace: shop\n## User @alias(Account)\n- id: identifier @pk\n- email: email @unique\n\n## Tenant\n- region: string @pk\n- code: string @pk\n\n## Order\n- user_id: identifier @reference(User)\n- reviewer_id: identifier? @reference(Account)\n- blocker_id: identifier @reference(shop.User)!\n- email: email @fk(User.email) @on_delete(restrict) @on_update(cascade)\n- tenant_code: string @reference(Tenant)\n- missing_id: identifier @reference(User.uuid)\n- blocked_id: identifier\n  - reference: User\n  - on_delete: no action\n  - on_update: restrict";
+        let ast = resolve(&[parse_string(input, "test.m3l.md")], None);
+        let references: Vec<(&str, Option<ReferenceDef>)> = ast.models[2]
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.reference.clone()))
+            .collect();
+        let to = |model: &str, field: Option<&str>, on_delete, on_update| {
+            Some(ReferenceDef {
+                model: model.into(),
+                namespace: Some("shop".into()),
+                field: field.map(str::to_string),
+                on_delete,
+                on_update,
+            })
+        };
+        assert_eq!(
+            references,
+            [
+                ("user_id", to("User", Some("id"), Cascade, None)),
+                ("reviewer_id", to("User", Some("id"), SetNull, None)),
+                ("blocker_id", to("User", Some("id"), NoAction, None)),
+                ("email", to("User", Some("email"), Restrict, Some(Cascade))),
+                ("tenant_code", to("Tenant", None, Cascade, None)),
+                ("missing_id", None),
+                (
+                    "blocked_id",
+                    to("User", Some("id"), NoAction, Some(Restrict))
+                ),
+            ]
+        );
+        let json = serde_json::to_value(&ast.models[2].fields[3].reference).unwrap();
+        assert_eq!(json["on_delete"], "restrict");
+        assert_eq!(json["on_update"], "cascade");
+        assert!(!ast.errors.iter().any(|e| e.code == "M3L-E024"));
+    }
+
+    #[test]
+    fn resolve_unknown_referential_action_e024() {
+        let input = "## User\n- id: identifier @pk\n- updated_at: timestamp @on_update(now())\n\n## Order\n- user_id: identifier @reference(User) @on_delete(explode)\n- owner_id: identifier\n  - reference: User\n  - on_update: sometimes";
+        let ast = resolve(&[parse_string(input, "test.m3l.md")], None);
+        let e024: Vec<usize> = ast
+            .errors
+            .iter()
+            .filter(|e| e.code == "M3L-E024")
+            .map(|e| e.line)
+            .collect();
+        // `@on_update(now())` on a field that references nothing is not an action
+        assert_eq!(e024, [6, 9], "{:?}", ast.errors);
+        // The default still applies
+        let reference = ast.models[1].fields[0].reference.as_ref().unwrap();
+        assert_eq!(reference.on_delete, ReferentialAction::Cascade);
+    }
+
+    #[test]
+    fn resolve_merge_order() {
+        let shop = "## Order : Base\n- customer_id: identifier @reference(Customer)\n\n## Status ::enum\n- open";
+        let base = "## Base\n- id: identifier @pk\n\n## Customer : Base\n- name: string\n\n## Kind ::enum\n- retail";
+        let names = |order: MergeOrder, files: &[(&str, &str)]| {
+            let parsed: Vec<ParsedFile> = files
+                .iter()
+                .map(|(content, source)| parse_string(content, source))
+                .collect();
+            let ast = resolve_with_options(
+                &parsed,
+                None,
+                &ResolveOptions {
+                    order,
+                    ..Default::default()
+                },
+            );
+            let models: Vec<String> = ast.models.iter().map(|m| m.name.clone()).collect();
+            let enums: Vec<String> = ast.enums.iter().map(|e| e.name.clone()).collect();
+            (models, enums)
+        };
+        let forward = [(shop, "shop.m3l.md"), (base, "base.m3l.md")];
+        let backward = [(base, "base.m3l.md"), (shop, "shop.m3l.md")];
+
+        assert_eq!(
+            names(MergeOrder::SourceOrder, &forward).0,
+            ["Order", "Base", "Customer"]
+        );
+        for order in [MergeOrder::Alphabetical, MergeOrder::DependencyOrder] {
+            assert_eq!(names(order, &forward), names(order, &backward));
+        }
+        assert_eq!(
+            names(MergeOrder::Alphabetical, &forward),
+            (
+                vec!["Base".to_string(), "Customer".into(), "Order".into()],
+                vec!["Kind".to_string(), "Status".into()]
+            )
+        );
+        // Order needs Base and Customer; Customer needs Base
+        assert_eq!(
+            names(MergeOrder::DependencyOrder, &backward).0,
+            ["Base", "Customer", "Order"]
+        );
+        let chain = "## C : B\n- c: string\n\n## B : A\n- b: string\n\n## A\n- a: string\n\n## Z\n- z: string";
+        assert_eq!(
+            names(MergeOrder::DependencyOrder, &[(chain, "chain.m3l.md")]).0,
+            ["A", "B", "C", "Z"]
+        );
+    }
+
+    #[test]
+    fn resolve_view_fields_from_sources() {
+        let input = "\
+## Customer
+- id: identifier @pk
+- name: string(100)
+- email: email?
+
+## Order
+- id: identifier @pk
+- customer_id: identifier @fk(Customer.id)
+- total: decimal(12,2)
+
+## OrderSummary ::view
+### Source
+- from: Order
+- join: left join Customer c on c.id = Order.customer_id
+
+- id
+- total
+- customer_name @from(c.name)
+- email: string @from(Customer.email)
+- note
+- missing @from(Customer.missing)
+";
+        let ast = resolve(&[parse_string(input, "shop.m3l.md")], None);
+        assert!(ast.errors.is_empty(), "{:?}", ast.errors);
+        let view = &ast.views[0];
+        let field = |name: &str| view.fields.iter().find(|f| f.name == name).unwrap();
+        let source = |name: &str| {
+            field(name)
+                .from
+                .as_ref()
+                .map(|f| (f.model.as_str(), f.field.as_str()))
+        };
+
+        assert_eq!(field("total").field_type.as_deref(), Some("decimal"));
+        assert_eq!(field("total").params.as_ref().map(Vec::len), Some(2));
+        assert_eq!(source("total"), Some(("Order", "total")));
+        assert_eq!(source("id"), Some(("Order", "id")));
+        // Through the join alias, keeping the source's nullability
+        let customer_name = field("customer_name");
+        assert_eq!(customer_name.field_type.as_deref(), Some("string"));
+        assert_eq!(source("customer_name"), Some(("Customer", "name")));
+        // A declared type is kept; the mapping is still recorded
+        let email = field("email");
+        assert_eq!(email.field_type.as_deref(), Some("string"));
+        assert!(!email.nullable);
+        assert_eq!(source("email"), Some(("Customer", "email")));
+        // No source field of that name
+        assert!(field("note").field_type.is_none() && field("note").from.is_none());
+        assert!(field("missing").from.is_none());
+    }
+
+    #[test]
+    fn resolve_project_defaults() {
+        let input = "\
+## Timestampable ::interface
+- created_at: timestamp
+
+## Base
+- id: identifier @pk
+
+## Audited : Base
+- audited_by: string?
+
+## User : Base
+- email: email @unique
+- backup_email: email?
+- tags: email[]
+- code: email(10)
+
+## Paged<T>
+- items: T[]
+";
+        let options = ResolveOptions {
+            type_aliases: vec![
+                TypeAlias::new("email", "string(320)").unwrap(),
+                TypeAlias::new("money", "decimal(10,2)").unwrap(),
+            ],
+            default_inherits: vec!["Audited".to_string(), "Timestampable".to_string()],
+            ..Default::default()
+        };
+        let ast = resolve_with_options(&[parse_string(input, "app.m3l.md")], None, &options);
+        assert!(ast.errors.is_empty(), "{:?}", ast.errors);
+
+        let user = ast.find_model("User").unwrap();
+        assert_eq!(user.inherits, ["Base", "Audited", "Timestampable"]);
+        assert_eq!(user.default_inherits, ["Audited", "Timestampable"]);
+        let names: Vec<&str> = user.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "id",
+                "audited_by",
+                "created_at",
+                "email",
+                "backup_email",
+                "tags",
+                "code"
+            ]
+        );
+        // Audited is a default parent and Base its ancestor; generic
+        // models are left alone
+        for name in ["Audited", "Base", "Paged"] {
+            assert!(ast.find_model(name).unwrap().default_inherits.is_empty());
+        }
+
+        let field = |name: &str| user.fields.iter().find(|f| f.name == name).unwrap();
+        let email = field("email");
+        assert_eq!(email.field_type.as_deref(), Some("string"));
+        assert_eq!(email.params, Some(vec![ParamValue::Number(320.0)]));
+        assert_eq!(email.type_alias.as_deref(), Some("email"));
+        assert!(field("backup_email").nullable);
+        assert!(field("tags").array);
+        assert_eq!(field("code").params, Some(vec![ParamValue::Number(10.0)]));
+
+        assert!(TypeAlias::new("email", "string @unique").is_err());
+        assert!(TypeAlias::new("email", "email").is_err());
+        assert!(TypeAlias::new("two words", "string").is_err());
+        assert!(TypeAlias::new("status", "string | integer").is_err());
+    }
+
+    #[test]
+    fn resolve_redeclared_interface_fields() {
+        let input = "## Named ::interface\n- name: string\n\n## Base\n- id: identifier\n\n## Child : Base, Named\n- name: string @required\n- id: identifier";
+        let ast = resolve(&[parse_string(input, "test.m3l.md")], None);
+        let child = &ast.models[1];
+        let fields: Vec<(&str, usize)> = child
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.loc.line))
+            .collect();
+        // The interface field is replaced; the model field stays a duplicate
+        assert_eq!(fields, [("id", 5), ("name", 8), ("id", 9)]);
+        assert!(ast.errors.iter().any(|e| e.code == "M3L-E005"));
+    }
 }
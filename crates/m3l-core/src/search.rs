@@ -0,0 +1,45 @@
+//! Fuzzy name matching shared by the language server's symbol search and
+//! `m3l find`.
+
+/// Score how well `candidate` matches `query` as a case-insensitive
+/// subsequence. Returns `None` when it does not match; higher is better.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let cand: Vec<char> = candidate.chars().collect();
+    let mut score = 0i64;
+    let mut pos = 0;
+    let mut prev: Option<usize> = None;
+    for q in query.chars() {
+        let q = q.to_ascii_lowercase();
+        let found = (pos..cand.len()).find(|&i| cand[i].to_ascii_lowercase() == q)?;
+        // Consecutive matches and matches at word starts score higher
+        if prev.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        let at_word_start = found == 0
+            || matches!(cand[found - 1], '_' | '.')
+            || (cand[found].is_ascii_uppercase() && cand[found - 1].is_ascii_lowercase());
+        if at_word_start {
+            score += 10;
+        }
+        score -= (found - pos) as i64;
+        prev = Some(found);
+        pos = found + 1;
+    }
+    // Prefer shorter names among equal matches
+    Some(score - (cand.len() as i64 - query.len() as i64) / 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_matching() {
+        assert!(fuzzy_score("ordst", "OrderStatus").is_some());
+        assert!(fuzzy_score("xyz", "OrderStatus").is_none());
+        assert!(fuzzy_score("os", "OrderStatus") > fuzzy_score("os", "Customers"));
+    }
+}
@@ -0,0 +1,374 @@
+//! Just enough SQL to find what a view's ```sql source reads from. This is
+//! a tokenizer with a walk over the outermost query's FROM and JOIN
+//! clauses, not a parser: subqueries are skipped, CTE names are not
+//! reported, and anything it does not understand is left alone.
+
+use crate::types::{JoinDef, JoinKind};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok<'a> {
+    /// Identifier or keyword; quoted identifiers are unquoted.
+    Word(&'a str),
+    /// `'...'` literal or any other single character.
+    Other,
+    Dot,
+    Comma,
+    Open,
+    Close,
+}
+
+/// Tokens with the byte offsets they span in `sql`.
+fn tokenize(sql: &str) -> Vec<(Tok<'_>, usize, usize)> {
+    let bytes = sql.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let c = bytes[i];
+        let close = match c {
+            b'"' => Some(b'"'),
+            b'`' => Some(b'`'),
+            b'[' => Some(b']'),
+            b'\'' => Some(b'\''),
+            _ => None,
+        };
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if sql[i..].starts_with("--") {
+            i = sql[i..].find('\n').map_or(bytes.len(), |n| i + n);
+        } else if sql[i..].starts_with("/*") {
+            i = sql[i + 2..].find("*/").map_or(bytes.len(), |n| i + n + 4);
+        } else if let Some(close) = close {
+            let end = sql[i + 1..]
+                .find(close as char)
+                .map_or(bytes.len(), |n| i + 1 + n);
+            i = (end + 1).min(bytes.len());
+            let tok = if c == b'\'' {
+                Tok::Other
+            } else {
+                Tok::Word(&sql[start + 1..end])
+            };
+            tokens.push((tok, start, i));
+        } else if c.is_ascii_alphanumeric() || c == b'_' || c >= 0x80 {
+            while i < bytes.len()
+                && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] >= 0x80)
+            {
+                i += 1;
+            }
+            tokens.push((Tok::Word(&sql[start..i]), start, i));
+        } else {
+            i += sql[i..].chars().next().map_or(1, char::len_utf8);
+            let tok = match c {
+                b'.' => Tok::Dot,
+                b',' => Tok::Comma,
+                b'(' => Tok::Open,
+                b')' => Tok::Close,
+                _ => Tok::Other,
+            };
+            tokens.push((tok, start, i));
+        }
+    }
+    tokens
+}
+
+/// Keywords that end a FROM item or a join condition.
+const CLAUSE_KEYWORDS: &[&str] = &[
+    "join",
+    "inner",
+    "left",
+    "right",
+    "full",
+    "cross",
+    "natural",
+    "outer",
+    "where",
+    "group",
+    "order",
+    "having",
+    "limit",
+    "offset",
+    "union",
+    "intersect",
+    "except",
+    "window",
+    "on",
+    "using",
+    "select",
+    "from",
+    "fetch",
+    "for",
+    "qualify",
+    "lateral",
+];
+
+fn is_keyword(word: &str, keyword: &str) -> bool {
+    word.eq_ignore_ascii_case(keyword)
+}
+
+fn is_clause_keyword(word: &str) -> bool {
+    CLAUSE_KEYWORDS.iter().any(|k| is_keyword(word, k))
+}
+
+/// The tables the outermost query of `sql` reads: the first FROM table,
+/// then every other FROM item and joined table in order. Joins keep their
+/// `ON` condition as written; comma-separated FROM items, `USING` joins and
+/// the FROM of a later `UNION` branch get an empty one.
+pub(crate) fn source_tables(sql: &str) -> (Option<String>, Vec<JoinDef>) {
+    let tokens = tokenize(sql);
+    let ctes = cte_names(&tokens);
+    let mut from: Option<String> = None;
+    let mut joins: Vec<JoinDef> = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match tokens[i].0 {
+            Tok::Open => depth += 1,
+            Tok::Close => depth = depth.saturating_sub(1),
+            Tok::Word(word) if depth == 0 && is_keyword(word, "from") => {
+                // FROM a, b, ...
+                i += 1;
+                while let Some((table, next)) = table_name(&tokens, i) {
+                    i = skip_alias(&tokens, next);
+                    if !ctes.iter().any(|c| c.eq_ignore_ascii_case(&table)) {
+                        if from.is_none() {
+                            from = Some(table);
+                        } else {
+                            joins.push(JoinDef {
+                                model: table,
+                                on: String::new(),
+                                kind: None,
+                                alias: None,
+                            });
+                        }
+                    }
+                    if !matches!(tokens.get(i), Some((Tok::Comma, _, _))) {
+                        break;
+                    }
+                    i += 1;
+                }
+                continue;
+            }
+            Tok::Word(word) if depth == 0 && is_keyword(word, "join") => {
+                let kind = join_kind(&tokens, i);
+                let Some((table, next)) = table_name(&tokens, i + 1) else {
+                    i += 1;
+                    continue;
+                };
+                i = skip_alias(&tokens, next);
+                let alias = (i > next).then(|| match tokens[i - 1].0 {
+                    Tok::Word(alias) => alias.to_string(),
+                    _ => String::new(),
+                });
+                let mut on = String::new();
+                if matches!(tokens.get(i), Some((Tok::Word(w), _, _)) if is_keyword(w, "on")) {
+                    let (end, next) = condition_end(&tokens, i + 1, sql.len());
+                    let start = tokens.get(i + 1).map_or(end, |t| t.1);
+                    on = sql[start..end]
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    i = next;
+                }
+                if !ctes.iter().any(|c| c.eq_ignore_ascii_case(&table)) {
+                    joins.push(JoinDef {
+                        model: table,
+                        on,
+                        kind,
+                        alias,
+                    });
+                }
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    (from, joins)
+}
+
+/// The table named at `i` (last segment of a dotted name) and the index
+/// after it. Subqueries and table functions are not tables.
+fn table_name(tokens: &[(Tok<'_>, usize, usize)], mut i: usize) -> Option<(String, usize)> {
+    if matches!(tokens.get(i), Some((Tok::Word(w), _, _)) if is_keyword(w, "lateral") || is_keyword(w, "only"))
+    {
+        i += 1;
+    }
+    // Whatever word follows is the table, even one spelled like a keyword
+    // (`JOIN Order o`)
+    let Some((Tok::Word(mut name), _, _)) = tokens.get(i) else {
+        return None;
+    };
+    i += 1;
+    while let (Some((Tok::Dot, _, _)), Some((Tok::Word(part), _, _))) =
+        (tokens.get(i), tokens.get(i + 1))
+    {
+        name = part;
+        i += 2;
+    }
+    if matches!(tokens.get(i), Some((Tok::Open, _, _))) {
+        return None;
+    }
+    Some((name.to_string(), i))
+}
+
+/// The kind of the join whose `JOIN` keyword is at `i`, from the words
+/// before it: `LEFT [OUTER] JOIN`.
+fn join_kind(tokens: &[(Tok<'_>, usize, usize)], mut i: usize) -> Option<JoinKind> {
+    let word = |i: usize| match tokens.get(i) {
+        Some((Tok::Word(w), _, _)) => Some(*w),
+        _ => None,
+    };
+    if word(i.checked_sub(1)?).is_some_and(|w| is_keyword(w, "outer")) {
+        i -= 1;
+    }
+    let before = word(i.checked_sub(1)?)?;
+    [
+        ("inner", JoinKind::Inner),
+        ("left", JoinKind::Left),
+        ("right", JoinKind::Right),
+        ("full", JoinKind::Full),
+        ("cross", JoinKind::Cross),
+    ]
+    .into_iter()
+    .find_map(|(keyword, kind)| is_keyword(before, keyword).then_some(kind))
+}
+
+/// Skip `[AS] alias` at `i`.
+fn skip_alias(tokens: &[(Tok<'_>, usize, usize)], mut i: usize) -> usize {
+    if matches!(tokens.get(i), Some((Tok::Word(w), _, _)) if is_keyword(w, "as")) {
+        i += 1;
+    }
+    match tokens.get(i) {
+        Some((Tok::Word(w), _, _)) if !is_clause_keyword(w) => i + 1,
+        _ => i,
+    }
+}
+
+/// Byte offset where a join condition starting at token `i` ends, and the
+/// index of the token that ends it.
+fn condition_end(tokens: &[(Tok<'_>, usize, usize)], mut i: usize, len: usize) -> (usize, usize) {
+    let mut depth = 0usize;
+    let mut end = tokens.get(i).map_or(len, |t| t.1);
+    while let Some((tok, _, tok_end)) = tokens.get(i) {
+        match tok {
+            Tok::Open => depth += 1,
+            Tok::Close if depth == 0 => break,
+            Tok::Close => depth -= 1,
+            Tok::Word(w) if depth == 0 && is_clause_keyword(w) && !is_keyword(w, "on") => break,
+            _ => {}
+        }
+        end = *tok_end;
+        i += 1;
+    }
+    (end, i)
+}
+
+/// Names defined by a leading `WITH [RECURSIVE] name [(cols)] AS (...)`.
+fn cte_names<'a>(tokens: &[(Tok<'a>, usize, usize)]) -> Vec<&'a str> {
+    let mut names = Vec::new();
+    let mut i = match tokens.first() {
+        Some((Tok::Word(w), _, _)) if is_keyword(w, "with") => 1,
+        _ => return names,
+    };
+    if matches!(tokens.get(i), Some((Tok::Word(w), _, _)) if is_keyword(w, "recursive")) {
+        i += 1;
+    }
+    while let Some((Tok::Word(name), _, _)) = tokens.get(i) {
+        names.push(*name);
+        i += 1;
+        // Column list, AS, then the body; skip to the token after its ")"
+        let mut depth = 0usize;
+        let mut seen_body = false;
+        while let Some((tok, _, _)) = tokens.get(i) {
+            i += 1;
+            match tok {
+                Tok::Open => depth += 1,
+                Tok::Close => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 && seen_body {
+                        break;
+                    }
+                }
+                Tok::Word(w) if depth == 0 && is_keyword(w, "as") => seen_body = true,
+                _ => {}
+            }
+        }
+        if !matches!(tokens.get(i), Some((Tok::Comma, _, _))) {
+            break;
+        }
+        i += 1;
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables(sql: &str) -> (Option<String>, Vec<(String, String)>) {
+        let (from, joins) = source_tables(sql);
+        (from, joins.into_iter().map(|j| (j.model, j.on)).collect())
+    }
+
+    fn join(model: &str, on: &str) -> (String, String) {
+        (model.to_string(), on.to_string())
+    }
+
+    #[test]
+    fn from_and_joins() {
+        let (from, joins) = tables(
+            "SELECT c.id, COUNT(o.id)\nFROM Customer c\nJOIN \"Order\" AS o ON c.id = o.customer_id\n  AND o.status <> 'cancelled'\nLEFT OUTER JOIN public.Address a USING (customer_id)\nWHERE c.active -- JOIN Ignored\nGROUP BY c.id",
+        );
+        assert_eq!(from.as_deref(), Some("Customer"));
+        assert_eq!(
+            joins,
+            vec![
+                join("Order", "c.id = o.customer_id AND o.status <> 'cancelled'"),
+                join("Address", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn join_kinds_and_aliases() {
+        let (_, joins) = source_tables(
+            "SELECT * FROM Customer c\nLEFT OUTER JOIN \"Order\" AS o ON o.customer_id = c.id\nCROSS JOIN Region\nJOIN Address a USING (id)",
+        );
+        let joins: Vec<(&str, Option<JoinKind>, Option<&str>)> = joins
+            .iter()
+            .map(|j| (j.model.as_str(), j.kind, j.alias.as_deref()))
+            .collect();
+        assert_eq!(
+            joins,
+            [
+                ("Order", Some(JoinKind::Left), Some("o")),
+                ("Region", Some(JoinKind::Cross), None),
+                ("Address", None, Some("a")),
+            ]
+        );
+    }
+
+    #[test]
+    fn comma_joins_and_unions() {
+        let (from, joins) = tables(
+            "SELECT * FROM Product p, Category\nUNION ALL\nSELECT * FROM Archive /* FROM Nope */",
+        );
+        assert_eq!(from.as_deref(), Some("Product"));
+        assert_eq!(joins, vec![join("Category", ""), join("Archive", "")]);
+    }
+
+    #[test]
+    fn skips_subqueries_ctes_and_functions() {
+        let (from, joins) = tables(
+            "WITH recent AS (SELECT * FROM Order WHERE placed_at > now())\nSELECT * FROM recent r\nJOIN Customer c ON c.id = r.customer_id\nWHERE EXISTS (SELECT 1 FROM Review)",
+        );
+        assert_eq!(from, None);
+        assert_eq!(joins, vec![join("Customer", "c.id = r.customer_id")]);
+
+        let (from, joins) = tables("SELECT * FROM (SELECT * FROM Order) o, generate_series(1, 3)");
+        assert_eq!(from, None);
+        assert!(joins.is_empty());
+        assert_eq!(tables("SELECT 1"), (None, vec![]));
+    }
+}
@@ -0,0 +1,403 @@
+//! Name lookups over a resolved AST: `ast.find_model("User")`,
+//! `ast.find_field("User.email")`, `ast.references_to("Customer")` and
+//! `ast.inheritance_chain("Admin")`.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+use crate::types::*;
+
+/// Which list of the AST a symbol lives in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Model,
+    Enum,
+    Interface,
+    View,
+    Flow,
+    GenericInstance,
+}
+
+/// An element of the AST: its list and its position in that list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol {
+    pub kind: SymbolKind,
+    pub index: usize,
+}
+
+/// Index of the AST's elements by name, built by `resolve`.
+///
+/// Elements are found by name, by alias and by namespace-qualified name
+/// (`Auth.User`). The table is derived from the AST and not serialized; a
+/// deserialized or edited AST can rebuild it with [`M3lAst::index`], and
+/// queries on an AST without one build a temporary table. Tables compare
+/// by content, so an AST only equals its deserialized copy once the copy
+/// has been indexed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SymbolTable {
+    names: HashMap<String, Vec<Symbol>>,
+    /// Resolved `@reference` target model → the fields that point at it
+    references: HashMap<String, Vec<(Symbol, usize)>>,
+}
+
+impl SymbolTable {
+    pub fn build(ast: &M3lAst) -> Self {
+        let mut table = SymbolTable::default();
+        for (kind, elements) in element_lists(ast) {
+            for (index, element) in elements.iter().enumerate() {
+                let symbol = Symbol { kind, index };
+                table.add_names(
+                    &element.name,
+                    &element.aliases,
+                    element.namespace.as_deref(),
+                    symbol,
+                );
+                for (i, field) in element.fields.iter().enumerate() {
+                    if let Some(reference) = &field.reference {
+                        table
+                            .references
+                            .entry(reference.model.clone())
+                            .or_default()
+                            .push((symbol, i));
+                    }
+                }
+            }
+        }
+        for (index, node) in ast.enums.iter().enumerate() {
+            let symbol = Symbol {
+                kind: SymbolKind::Enum,
+                index,
+            };
+            table.add_names(&node.name, &[], node.namespace.as_deref(), symbol);
+        }
+        table
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Every element named, aliased or qualified as `name`.
+    pub fn lookup(&self, name: &str) -> &[Symbol] {
+        self.names.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    fn add_names(
+        &mut self,
+        name: &str,
+        aliases: &[String],
+        namespace: Option<&str>,
+        symbol: Symbol,
+    ) {
+        let mut keys: Vec<String> = vec![name.to_string()];
+        keys.extend(aliases.iter().cloned());
+        if let Some(namespace) = namespace {
+            keys.push(format!("{}.{}", namespace, name));
+        }
+        for key in keys {
+            let entry = self.names.entry(key).or_default();
+            if !entry.contains(&symbol) {
+                entry.push(symbol);
+            }
+        }
+    }
+}
+
+fn element_lists(ast: &M3lAst) -> [(SymbolKind, &[ModelNode]); 5] {
+    [
+        (SymbolKind::Model, &ast.models),
+        (SymbolKind::Interface, &ast.interfaces),
+        (SymbolKind::View, &ast.views),
+        (SymbolKind::Flow, &ast.flows),
+        (SymbolKind::GenericInstance, &ast.generic_instances),
+    ]
+}
+
+impl M3lAst {
+    /// Rebuild the symbol table after deserializing or editing the AST.
+    pub fn index(&mut self) {
+        self.symbols = SymbolTable::build(self);
+    }
+
+    fn table(&self) -> Cow<'_, SymbolTable> {
+        if self.symbols.is_empty() {
+            Cow::Owned(SymbolTable::build(self))
+        } else {
+            Cow::Borrowed(&self.symbols)
+        }
+    }
+
+    /// The model, interface, view, flow or generic instance a symbol
+    /// points at; `None` for enums.
+    pub fn element(&self, symbol: Symbol) -> Option<&ModelNode> {
+        let list = match symbol.kind {
+            SymbolKind::Model => &self.models,
+            SymbolKind::Interface => &self.interfaces,
+            SymbolKind::View => &self.views,
+            SymbolKind::Flow => &self.flows,
+            SymbolKind::GenericInstance => &self.generic_instances,
+            SymbolKind::Enum => return None,
+        };
+        list.get(symbol.index)
+    }
+
+    /// The element with fields named, aliased or qualified as `name`,
+    /// looking at models first, then interfaces, views, flows and generic
+    /// instances.
+    pub fn find_element(&self, name: &str) -> Option<&ModelNode> {
+        self.table()
+            .lookup(name)
+            .iter()
+            .filter_map(|&symbol| self.element(symbol))
+            .find(|element| names(element, name))
+    }
+
+    /// Like [`find_element`](Self::find_element), for a name written in
+    /// namespace `from`: a short name defined in several namespaces
+    /// prefers the element in `from` (`None`: the one outside any
+    /// namespace).
+    pub fn find_element_from(&self, name: &str, from: Option<&str>) -> Option<&ModelNode> {
+        let table = self.table();
+        let mut found = table
+            .lookup(name)
+            .iter()
+            .filter_map(|&symbol| self.element(symbol))
+            .filter(|element| names(element, name));
+        let first = found.next()?;
+        if name.contains('.') || first.namespace.as_deref() == from {
+            return Some(first);
+        }
+        Some(
+            found
+                .find(|element| element.namespace.as_deref() == from)
+                .unwrap_or(first),
+        )
+    }
+
+    /// The element a `@reference`/`@fk` field resolved to.
+    pub fn reference_target(&self, field: &FieldNode) -> Option<&ModelNode> {
+        let reference = field.reference.as_ref()?;
+        self.table()
+            .lookup(&reference.model)
+            .iter()
+            .filter(|s| s.kind != SymbolKind::GenericInstance)
+            .filter_map(|&symbol| self.element(symbol))
+            .find(|element| {
+                element.name == reference.model && element.namespace == reference.namespace
+            })
+    }
+
+    /// The model named, aliased or qualified (`Auth.User`) as `name`,
+    /// including generic instances such as `Paged<Product>`.
+    pub fn find_model(&self, name: &str) -> Option<&ModelNode> {
+        self.table()
+            .lookup(name)
+            .iter()
+            .filter(|s| matches!(s.kind, SymbolKind::Model | SymbolKind::GenericInstance))
+            .filter_map(|&symbol| self.element(symbol))
+            .find(|model| names(model, name))
+    }
+
+    pub fn find_enum(&self, name: &str) -> Option<&EnumNode> {
+        self.table()
+            .lookup(name)
+            .iter()
+            .filter(|s| s.kind == SymbolKind::Enum)
+            .filter_map(|s| self.enums.get(s.index))
+            .find(|node| {
+                node.name == name
+                    || node
+                        .namespace
+                        .as_ref()
+                        .is_some_and(|ns| format!("{}.{}", ns, node.name) == name)
+            })
+    }
+
+    /// A field by `Element.field` path; the element part may be qualified
+    /// (`Auth.User.email`). Fields include inherited ones.
+    pub fn find_field(&self, path: &str) -> Option<&FieldNode> {
+        let (element, field) = path.rsplit_once('.')?;
+        self.find_element(element)?
+            .fields
+            .iter()
+            .find(|f| f.name == field)
+    }
+
+    /// Fields whose `@reference`/`@fk` resolved to the model `name`, with
+    /// the element that declares each, in AST order.
+    pub fn references_to(&self, name: &str) -> Vec<(&ModelNode, &FieldNode)> {
+        let Some(target) = self.find_model(name) else {
+            return Vec::new();
+        };
+        let table = self.table();
+        let Some(fields) = table.references.get(&target.name) else {
+            return Vec::new();
+        };
+        fields
+            .iter()
+            .filter_map(|&(symbol, i)| {
+                let element = self.element(symbol)?;
+                let field = element.fields.get(i)?;
+                let resolved = field.reference.as_ref()?;
+                (resolved.model == target.name && resolved.namespace == target.namespace)
+                    .then_some((element, field))
+            })
+            .collect()
+    }
+
+    /// The element `name` followed by its parents and their ancestors,
+    /// depth-first in `inherits` order, each listed once. Parents that do
+    /// not resolve are skipped; empty when `name` itself does not.
+    pub fn inheritance_chain(&self, name: &str) -> Vec<&ModelNode> {
+        fn walk<'a>(
+            ast: &'a M3lAst,
+            element: &'a ModelNode,
+            seen: &mut HashSet<*const ModelNode>,
+            chain: &mut Vec<&'a ModelNode>,
+        ) {
+            if !seen.insert(element as *const ModelNode) {
+                return;
+            }
+            chain.push(element);
+            for parent in &element.inherits {
+                if let Some(parent) = ast.find_element(parent) {
+                    walk(ast, parent, seen, chain);
+                }
+            }
+        }
+
+        let mut chain = Vec::new();
+        if let Some(element) = self.find_element(name) {
+            walk(self, element, &mut HashSet::new(), &mut chain);
+        }
+        chain
+    }
+}
+
+/// Whether `name` still names `element`, in case the table is stale.
+fn names(element: &ModelNode, name: &str) -> bool {
+    element.name == name
+        || element.aliases.iter().any(|a| a == name)
+        || element
+            .namespace
+            .as_ref()
+            .is_some_and(|ns| format!("{}.{}", ns, element.name) == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_string, resolve, M3lAst};
+
+    fn ast() -> M3lAst {
+        let input = "\
+## Timestamped ::interface
+- created_at: timestamp
+
+## Customer
+- id: identifier @pk
+- email: email
+
+## Admin : Customer, Timestamped
+- level: integer
+
+## Order
+- id: identifier @pk
+- customer_id: identifier @fk(Customer.id)
+- reviewer_id: identifier? @reference(Admin)
+
+## Status ::enum
+- active
+";
+        resolve(&[parse_string(input, "shop.m3l.md")], None)
+    }
+
+    #[test]
+    fn query_symbols() {
+        let mut ast = ast();
+        assert!(ast.errors.is_empty(), "{:?}", ast.errors);
+        assert_eq!(ast.find_model("Customer").unwrap().name, "Customer");
+        assert!(ast.find_model("Timestamped").is_none());
+        assert_eq!(ast.find_element("Timestamped").unwrap().name, "Timestamped");
+        assert_eq!(ast.find_enum("Status").unwrap().name, "Status");
+        assert!(ast.find_model("Status").is_none());
+
+        assert_eq!(ast.find_field("Customer.email").unwrap().name, "email");
+        assert_eq!(
+            ast.find_field("Admin.created_at").unwrap().name,
+            "created_at"
+        );
+        assert!(ast.find_field("Customer.missing").is_none());
+        assert!(ast.find_field("Customer").is_none());
+
+        let references: Vec<(&str, &str)> = ast
+            .references_to("Customer")
+            .iter()
+            .map(|(model, field)| (model.name.as_str(), field.name.as_str()))
+            .collect();
+        assert_eq!(references, [("Order", "customer_id")]);
+
+        let chain: Vec<&str> = ast
+            .inheritance_chain("Admin")
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(chain, ["Admin", "Customer", "Timestamped"]);
+        assert!(ast.inheritance_chain("Missing").is_empty());
+
+        // A deserialized AST has no table until it is rebuilt; queries
+        // still answer
+        let json = serde_json::to_string(&ast).unwrap();
+        let mut copy: M3lAst = serde_json::from_str(&json).unwrap();
+        assert!(copy.symbols.is_empty());
+        assert_ne!(copy, ast);
+        assert_eq!(
+            copy.find_field("Order.reviewer_id").unwrap().name,
+            "reviewer_id"
+        );
+        copy.index();
+        assert!(!copy.symbols.is_empty());
+        assert_eq!(copy, ast);
+
+        // Edits after resolve are picked up by rebuilding the table
+        ast.models.remove(0);
+        ast.index();
+        assert!(ast.find_model("Customer").is_none());
+        assert_eq!(ast.find_model("Order").unwrap().name, "Order");
+    }
+
+    #[test]
+    fn namespaced_symbols() {
+        let auth = "# Namespace: auth\n## User\n- id: identifier @pk\n";
+        let shop = "# Namespace: shop\n## User\n- id: identifier @pk\n\n## Order\n- buyer: identifier @reference(auth.User)\n- seller: identifier @reference(User)\n";
+        let ast = resolve(
+            &[
+                parse_string(auth, "auth.m3l.md"),
+                parse_string(shop, "shop.m3l.md"),
+            ],
+            None,
+        );
+        let namespace = |element: Option<&crate::ModelNode>| element?.namespace.clone();
+        assert_eq!(
+            namespace(ast.find_element_from("User", Some("shop"))).as_deref(),
+            Some("shop")
+        );
+        assert_eq!(
+            namespace(ast.find_element_from("User", Some("auth"))).as_deref(),
+            Some("auth")
+        );
+        assert_eq!(
+            namespace(ast.find_element_from("auth.User", Some("shop"))).as_deref(),
+            Some("auth")
+        );
+
+        let order = ast.find_model("Order").unwrap();
+        let targets: Vec<Option<&str>> = order
+            .fields
+            .iter()
+            .map(|f| ast.reference_target(f)?.namespace.as_deref())
+            .collect();
+        assert_eq!(targets, [Some("auth"), Some("shop")]);
+        assert_eq!(ast.references_to("auth.User").len(), 1);
+        assert_eq!(ast.references_to("shop.User")[0].1.name, "seller");
+    }
+}
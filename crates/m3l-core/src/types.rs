@@ -1,15 +1,25 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+
+use crate::symbols::SymbolTable;
 
 // ---------------------------------------------------------------------------
 // Source location
 // ---------------------------------------------------------------------------
 
+/// Where a node is written: from `line`/`col` to `end_line`/`end_col`,
+/// 1-based, with `end_col` just past the last character. A model's range
+/// runs to its last line and a field's covers its nested lines. The end is
+/// zero in ASTs serialized before ranges were recorded.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SourceLocation {
     pub file: String,
     pub line: usize,
     pub col: usize,
+    #[serde(rename = "endLine", default)]
+    pub end_line: usize,
+    #[serde(rename = "endCol", default)]
+    pub end_col: usize,
 }
 
 // ---------------------------------------------------------------------------
@@ -33,6 +43,11 @@ pub enum TokenType {
     HorizontalRule,
     Blank,
     Text,
+    /// Leading `---` YAML block; its body is in `data.code_block`.
+    Frontmatter,
+    /// `<!-- m3l: 1.1 -->` or `# M3L-Version: 1.1`; the version is in
+    /// `data.name`.
+    Version,
 }
 
 #[derive(Debug, Clone)]
@@ -50,6 +65,7 @@ pub struct TokenData {
     // Common
     pub name: Option<String>,
     pub label: Option<String>,
+    pub labels: BTreeMap<String, String>,
     pub description: Option<String>,
     pub comment: Option<String>,
 
@@ -62,13 +78,26 @@ pub struct TokenData {
     pub type_name: Option<String>,
     pub type_params: Vec<ParamValue>,
     pub type_generic_params: Vec<String>,
+    /// Members of a union type (`string | integer`); `type_name` is then
+    /// `union`.
+    pub union_types: Vec<TypeMember>,
+    /// Components of a tuple type (`(float, float)`); `type_name` is then
+    /// `tuple`.
+    pub tuple_types: Vec<TypeMember>,
+    /// Values of an inline literal enum (`"draft" | "published"`);
+    /// `type_name` is then `enum`.
+    pub enum_literals: Vec<String>,
     pub nullable: bool,
     pub array: bool,
     pub array_item_nullable: bool,
     pub default_value: Option<String>,
     pub is_directive: bool,
+    /// `## extend User`: the header names a model to add to.
+    pub is_extend: bool,
     pub is_import: bool,
     pub import_path: Option<String>,
+    /// Names listed after the import path, for a selective import.
+    pub import_symbols: Option<Vec<String>>,
     pub framework_attrs: Vec<String>,
     pub blockquote_desc: Option<String>,
     pub enum_value_description: Option<String>,
@@ -82,6 +111,10 @@ pub struct TokenData {
     // Nested item key/value
     pub key: Option<String>,
     pub value: Option<String>,
+
+    /// End (line, column) of later lines folded into this token: indented
+    /// blockquotes and code blocks.
+    pub folded_end: Option<(usize, usize)>,
 }
 
 #[derive(Debug, Clone)]
@@ -141,6 +174,9 @@ pub struct FieldAttribute {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "isRegistered")]
     pub is_registered: Option<bool>,
+    /// Range of the attribute text, `@name` through its arguments.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loc: Option<SourceLocation>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -167,6 +203,105 @@ pub struct EnumValue {
     pub value_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loc: Option<SourceLocation>,
+}
+
+/// One member type of a union or component of a tuple: `string(50)` in
+/// `string(50) | integer`, `float?` in `(float, float?)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeMember {
+    #[serde(rename = "type")]
+    pub member_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<Vec<ParamValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generic_params: Option<Vec<String>>,
+    /// Only tuple components are nullable on their own.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub nullable: bool,
+}
+
+/// What happens to a referencing row when the row it points at is
+/// deleted or its key updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferentialAction {
+    Cascade,
+    SetNull,
+    NoAction,
+    Restrict,
+}
+
+impl ReferentialAction {
+    /// The action as written in `@on_delete(...)` or an `on_delete:` item:
+    /// `set_null`, `set null` and `SET NULL` all read the same.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_ascii_lowercase().replace([' ', '-'], "_");
+        match text.as_str() {
+            "cascade" => Some(ReferentialAction::Cascade),
+            "set_null" => Some(ReferentialAction::SetNull),
+            "no_action" => Some(ReferentialAction::NoAction),
+            "restrict" => Some(ReferentialAction::Restrict),
+            _ => None,
+        }
+    }
+
+    /// The action for a cascade symbol after the attribute: `!`, `!!`, `?`.
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "!" => Some(ReferentialAction::NoAction),
+            "!!" => Some(ReferentialAction::Restrict),
+            "?" => Some(ReferentialAction::SetNull),
+            _ => None,
+        }
+    }
+
+    /// The action as written: `cascade`, `set_null`, ...
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReferentialAction::Cascade => "cascade",
+            ReferentialAction::SetNull => "set_null",
+            ReferentialAction::NoAction => "no_action",
+            ReferentialAction::Restrict => "restrict",
+        }
+    }
+
+    /// The SQL clause: `CASCADE`, `SET NULL`, ...
+    pub fn sql(self) -> &'static str {
+        match self {
+            ReferentialAction::Cascade => "CASCADE",
+            ReferentialAction::SetNull => "SET NULL",
+            ReferentialAction::NoAction => "NO ACTION",
+            ReferentialAction::Restrict => "RESTRICT",
+        }
+    }
+}
+
+/// Where an `@reference`/`@fk` field points, filled in by the resolver:
+/// the target model by name and namespace (whichever alias or qualified
+/// name was written), the target field (as written, or else the model's primary key), and
+/// the referential actions. `on_delete` comes from the `!`/`!!`/`?`
+/// symbol, `@on_delete`, or the field's nullability; `on_update` only
+/// from `@on_update` or an `on_update:` item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReferenceDef {
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub on_delete: ReferentialAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_update: Option<ReferentialAction>,
+}
+
+/// The source field a view field selects: `@from(User.name)`, or the
+/// field of the same name in the view's `from` model for an untyped field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FromDef {
+    pub model: String,
+    pub field: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -198,6 +333,40 @@ pub struct ComputedDef {
 pub enum DefaultValueType {
     Literal,
     Expression,
+    /// A JSON array or object literal, parsed into `default_json`.
+    Json,
+}
+
+/// Structured tags from a description: `> @since 2.1`,
+/// `> @deprecated Use email instead`, `> @example ...`, `> @see User.email`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocTags {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// Text after `@deprecated`: why, or what to use instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecation: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub see: Vec<String>,
+}
+
+/// Why and since when an element is deprecated, and what replaces it. Taken
+/// from `@deprecated("Use email", since: "2.0")`, or else from an
+/// `@deprecated` description line.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Deprecation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// The element to use instead: a `replacement:` argument, or `X` in a
+    /// message of the form `use X`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -205,6 +374,10 @@ pub struct FieldNode {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
+    /// Localized labels by locale, from `name(en: "Name", ko: "이름")`;
+    /// `label` holds the first one written.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "type")]
     pub field_type: Option<String>,
@@ -221,11 +394,29 @@ pub struct FieldNode {
     pub default_value: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_value_type: Option<DefaultValueType>,
+    /// The parsed value of a `json` default; `None` when the literal is not
+    /// valid JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_json: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// `@since`, `@deprecated`, `@example` and `@see` lines taken out of
+    /// the description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<DocTags>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Deprecation>,
     pub attributes: Vec<FieldAttribute>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub framework_attrs: Option<Vec<CustomAttribute>>,
+    /// The resolved `@reference`/`@fk` target; `None` until resolved, or
+    /// when the target is not defined (M3L-E011).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<ReferenceDef>,
+    /// For a view field, the resolved source field it selects; a field
+    /// declared without a type takes the source field's type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<FromDef>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lookup: Option<LookupDef>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -234,6 +425,16 @@ pub struct FieldNode {
     pub computed: Option<ComputedDef>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub enum_values: Option<Vec<EnumValue>>,
+    /// Member types when `type` is `union`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub union: Option<Vec<TypeMember>>,
+    /// Component types, in order, when `type` is `tuple`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tuple: Option<Vec<TypeMember>>,
+    /// The project type alias the field was written with; `type`, `params`
+    /// and the suffixes hold its expansion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_alias: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fields: Option<Vec<FieldNode>>,
     pub loc: SourceLocation,
@@ -276,10 +477,39 @@ impl<'de> Deserialize<'de> for ModelType {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JoinKind {
+    Inner,
+    Left,
+    Right,
+    Full,
+    Cross,
+}
+
+impl JoinKind {
+    /// The kind as written before `join`: `left`, `full`, ...
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JoinKind::Inner => "inner",
+            JoinKind::Left => "left",
+            JoinKind::Right => "right",
+            JoinKind::Full => "full",
+            JoinKind::Cross => "cross",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct JoinDef {
     pub model: String,
     pub on: String,
+    /// `left` in `left join Order o on ...`; unset for a plain join.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<JoinKind>,
+    /// `o` in `left join Order o on ...`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -304,20 +534,231 @@ pub struct ViewSourceDef {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RefreshDef {
     pub strategy: String,
+    /// The interval as written: `"5m"`, `"6 hours"`, `"0 3 * * *"`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub interval: Option<String>,
+    /// `interval`, parsed. `None` when it is absent or malformed (E022).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<RefreshSchedule>,
+}
+
+/// When a materialized view is refreshed: every `seconds`, or on a
+/// five-field cron schedule. `daily 02:00` is read as the cron `0 2 * * *`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum RefreshSchedule {
+    Interval { seconds: u64 },
+    Cron { expression: String },
+}
+
+/// A natural key: fields that identify a record and must be unique together.
+/// Declared with `@natural_key(...)` or in a `### Keys` section; unlike
+/// indexes, generators emit keys as UNIQUE constraints.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyDef {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub fields: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub loc: SourceLocation,
+}
+
+/// A database index: a `- name` entry in an `### Indexes` section with
+/// nested `- fields:`, `- unique:`, `- where:` and `- method:` items, or an
+/// `@index(...)`/`@unique(...)` directive line, which keeps the line as
+/// `raw`. Other nested items and named directive arguments go to `options`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexDef {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub fields: Vec<String>,
+    #[serde(default)]
+    pub unique: bool,
+    /// Partial index condition: `status = 'published'`.
+    #[serde(rename = "where", skip_serializing_if = "Option::is_none")]
+    pub where_clause: Option<String>,
+    /// Index method: `btree`, `hash`, `gin`, ...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub options: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+    pub loc: SourceLocation,
+}
+
+/// Which side of a relation holds the foreign key: `>`/`->` (this model
+/// references the target), `<`/`<-` (the target references this model)
+/// or `<>` (many-to-many).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelationDirection {
+    To,
+    From,
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Cardinality {
+    OneToOne,
+    OneToMany,
+    ManyToOne,
+    ManyToMany,
+}
+
+impl Cardinality {
+    /// The cardinality as written: `one-to-many`, ...
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Cardinality::OneToOne => "one-to-one",
+            Cardinality::OneToMany => "one-to-many",
+            Cardinality::ManyToOne => "many-to-one",
+            Cardinality::ManyToMany => "many-to-many",
+        }
+    }
+}
+
+/// A `### Relations` entry or an `@relation(...)` line, as written in
+/// `raw`: `- orders: <Order via customer_id`, `- >author` with nested
+/// `- target:`/`- from:` items, or `- @relation(parent, -> Category,
+/// from: parent_id)`. A cardinality that is not written follows the
+/// direction: many-to-one for `>`, one-to-many for `<`, many-to-many for
+/// `<>`. Other nested items, and `(load: eager)` after the entry, go to
+/// `options`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelationDef {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<RelationDirection>,
+    /// `Person`, or `Comment.post_id` for the field referencing this model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// The foreign key field: `customer_id` in `via customer_id`.
+    #[serde(rename = "from", skip_serializing_if = "Option::is_none")]
+    pub from_field: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cardinality: Option<Cardinality>,
+    /// Written `(optional)`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_delete: Option<String>,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub options: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub raw: String,
+    pub loc: SourceLocation,
+}
+
+/// When a behavior runs relative to its event: `before` in `before_create`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BehaviorTiming {
+    Before,
+    After,
+    On,
+}
+
+impl BehaviorTiming {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BehaviorTiming::Before => "before",
+            BehaviorTiming::After => "after",
+            BehaviorTiming::On => "on",
+        }
+    }
+}
+
+/// A `### Behaviors` entry or an `@behavior(...)` line, as written in
+/// `raw`: `- before_update: set updated_at = now()`, or `- after_create`
+/// with nested `- action:`/`- condition:` items. `name` splits into a
+/// timing and an event (`create`, `update`, `delete`, `save` or a custom
+/// one); a name without a timing is all event. Other nested items go to
+/// `options`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BehaviorDef {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<BehaviorTiming>,
+    pub event: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub options: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub raw: String,
+    pub loc: SourceLocation,
+}
+
+/// A row of fixture data from a `### Seed` section, as a field-to-value
+/// map. Rows come from a Markdown table or from `- name` list items whose
+/// nested `- field: value` lines hold the values; list rows keep the name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeedRow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub values: serde_json::Map<String, serde_json::Value>,
+    pub loc: SourceLocation,
+}
+
+/// A REST operation from an `### Endpoints` (or `### API`) section:
+/// `- GET /users/{id} "Fetch a user"`, with nested `- key: value` items as
+/// options. `params` are the `{name}` segments of the path, in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EndpointDef {
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub options: serde_json::Map<String, serde_json::Value>,
+    pub loc: SourceLocation,
+}
+
+/// A check constraint: a boolean expression every record must satisfy.
+/// Declared with `@check(...)` on a field, which then names the field, or
+/// on its own line in a model; generators emit it as a CHECK constraint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckDef {
+    pub expression: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub loc: SourceLocation,
 }
 
 /// Sections block — always has indexes, relations, behaviors, metadata,
-/// plus arbitrary custom sections.
+/// plus arbitrary custom sections. `keys`, `checks`, `seeds` and
+/// `endpoints` are omitted when empty.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Sections {
-    pub indexes: Vec<serde_json::Value>,
-    pub relations: Vec<serde_json::Value>,
-    pub behaviors: Vec<serde_json::Value>,
-    pub metadata: HashMap<String, serde_json::Value>,
+    pub indexes: Vec<IndexDef>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<KeyDef>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<CheckDef>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seeds: Vec<SeedRow>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub endpoints: Vec<EndpointDef>,
+    pub relations: Vec<RelationDef>,
+    pub behaviors: Vec<BehaviorDef>,
+    pub metadata: BTreeMap<String, serde_json::Value>,
     #[serde(flatten)]
-    pub custom: HashMap<String, serde_json::Value>,
+    pub custom: BTreeMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -325,13 +766,28 @@ pub struct ModelNode {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
+    /// Localized labels by locale, from `name(en: "Name", ko: "이름")`;
+    /// `label` holds the first one written.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
     #[serde(rename = "type")]
     pub model_type: ModelType,
     pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
     pub line: usize,
     pub inherits: Vec<String>,
+    /// Other names the model can be referenced by, from `@alias(Account)`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// `@since`, `@deprecated`, `@example` and `@see` lines taken out of
+    /// the description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<DocTags>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Deprecation>,
     pub attributes: Vec<FieldAttribute>,
     pub fields: Vec<FieldNode>,
     pub sections: Sections,
@@ -341,6 +797,19 @@ pub struct ModelNode {
     pub source_def: Option<ViewSourceDef>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub refresh: Option<RefreshDef>,
+    /// Type parameters of a generic model: `T` in `## Paged<T>`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generic_params: Vec<String>,
+    /// For a model the resolver instantiated from a generic one, the
+    /// generic model's name and the type arguments it was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_of: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generic_args: Vec<String>,
+    /// Parents the project adds to every model (`[defaults] inherits`);
+    /// they are also listed in `inherits`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_inherits: Vec<String>,
     pub loc: SourceLocation,
 }
 
@@ -349,13 +818,25 @@ pub struct EnumNode {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
+    /// Localized labels by locale, from `name(en: "Name", ko: "이름")`;
+    /// `label` holds the first one written.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
     #[serde(rename = "type")]
     pub enum_type: ModelType, // always ModelType::Enum
     pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
     pub line: usize,
     pub inherits: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// `@since`, `@deprecated`, `@example` and `@see` lines taken out of
+    /// the description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<DocTags>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Deprecation>,
     pub values: Vec<EnumValue>,
     pub loc: SourceLocation,
 }
@@ -395,6 +876,12 @@ pub struct AttributeRegistryEntry {
     pub attr_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub range: Option<(f64, f64)>,
+    /// Allowed arguments of a `type: enum` attribute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<String>>,
+    /// Regex that string arguments must match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
     pub required: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "defaultValue")]
@@ -411,10 +898,92 @@ pub struct ParsedFile {
     pub interfaces: Vec<ModelNode>,
     pub views: Vec<ModelNode>,
     pub flows: Vec<ModelNode>,
-    pub extensions: HashMap<String, Vec<ModelNode>>,
+    pub extensions: BTreeMap<String, Vec<ModelNode>>,
     pub attribute_registry: Vec<AttributeRegistryEntry>,
-    /// Import paths found in this file (for circular import detection).
-    pub imports: Vec<String>,
+    /// `## extend User` blocks, named after the model they add to.
+    pub extend_blocks: Vec<ModelNode>,
+    /// `@import` directives found in this file.
+    pub imports: Vec<ImportDirective>,
+    /// Syntax diagnostics (`M3L-P…`) for lines the parser could not read
+    /// as written; `resolve` reports them with the other diagnostics.
+    pub diagnostics: Vec<Diagnostic>,
+    /// The file's YAML frontmatter, if it has one.
+    pub metadata: Option<FileMetadata>,
+    /// The M3L version the file declares, if it has a version pragma.
+    pub m3l_version: Option<M3lVersion>,
+}
+
+/// Metadata from a file's leading `---` YAML frontmatter block.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileMetadata {
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Any other keys, as JSON values.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, serde_json::Value>,
+    /// The block as written between its `---` lines, for tools that write
+    /// the file back.
+    #[serde(skip)]
+    pub raw: String,
+}
+
+/// A file's M3L version pragma: `<!-- m3l: 1.1 -->` or
+/// `# M3L-Version: 1.1`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct M3lVersion {
+    pub source: String,
+    /// The version as written.
+    pub version: String,
+    /// The pragma line as written, for tools that write the file back.
+    #[serde(skip)]
+    pub raw: String,
+}
+
+/// An `@import` directive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportDirective {
+    /// The path as written.
+    pub path: String,
+    /// Names listed after the path (`@import "base.m3l.md" (BaseModel)`);
+    /// `None` imports everything the file defines.
+    pub symbols: Option<Vec<String>>,
+    pub line: usize,
+}
+
+/// A loaded file with its namespace and the files it imports, so tools
+/// can rebuild the project graph from the AST alone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceFile {
+    /// The source as listed in `sources`.
+    pub path: String,
+    /// The file namespace: the frontmatter `namespace` or the first
+    /// `# Namespace:` header.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<ImportEdge>,
+}
+
+/// An `@import` of a file, resolved against the loaded sources.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportEdge {
+    /// The path as written.
+    pub path: String,
+    /// The loaded source it refers to; `None` when it was not loaded
+    /// (`M3L-E013`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Names listed after the path; `None` imports everything.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbols: Option<Vec<String>>,
+    pub line: usize,
 }
 
 /// Final AST — top-level JSON output.
@@ -426,20 +995,113 @@ pub struct M3lAst {
     pub ast_version: String,
     pub project: ProjectInfo,
     pub sources: Vec<String>,
+    /// Each source's namespace and import edges, in source order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<SourceFile>,
     pub models: Vec<ModelNode>,
     pub enums: Vec<EnumNode>,
     pub interfaces: Vec<ModelNode>,
     pub views: Vec<ModelNode>,
     pub flows: Vec<ModelNode>,
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub extensions: HashMap<String, Vec<ModelNode>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extensions: BTreeMap<String, Vec<ModelNode>>,
     #[serde(rename = "attributeRegistry")]
     pub attribute_registry: Vec<AttributeRegistryEntry>,
+    /// Frontmatter of the files that have one, in source order.
+    #[serde(
+        rename = "fileMetadata",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub file_metadata: Vec<FileMetadata>,
+    /// Version pragmas of the files that have one, in source order.
+    #[serde(rename = "m3lVersions", default, skip_serializing_if = "Vec::is_empty")]
+    pub m3l_versions: Vec<M3lVersion>,
+    /// Concrete models for each use of a generic model, named as used:
+    /// `Paged<Product>`.
+    #[serde(
+        rename = "genericInstances",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub generic_instances: Vec<ModelNode>,
+    /// `## extend User` blocks as written; the resolver has already added
+    /// their fields, indexes and relations to the model they name.
+    #[serde(
+        rename = "extendBlocks",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub extend_blocks: Vec<ModelNode>,
     pub errors: Vec<Diagnostic>,
     pub warnings: Vec<Diagnostic>,
+    /// Name index behind `find_model`, `find_field` and the other queries.
+    #[serde(skip)]
+    pub symbols: SymbolTable,
+}
+
+/// The elements of one namespace, for output grouped by namespace instead
+/// of the flat lists (`m3l parse --group-namespaces`). Elements outside
+/// any namespace are grouped under `_default`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamespaceGroup {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<ModelNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enums: Vec<EnumNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interfaces: Vec<ModelNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub views: Vec<ModelNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flows: Vec<ModelNode>,
+}
+
+/// How `resolve` orders the elements of the merged AST.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MergeOrder {
+    /// As the files were given, then as declared in each file.
+    #[default]
+    #[serde(rename = "source")]
+    SourceOrder,
+    /// By name, then namespace.
+    #[serde(rename = "alphabetical")]
+    Alphabetical,
+    /// Parents, reference and Relations targets before the elements that
+    /// use them; otherwise by name.
+    #[serde(rename = "dependency")]
+    DependencyOrder,
 }
 
 #[derive(Debug, Clone, Default)]
+pub struct ResolveOptions {
+    /// Order of models, enums, interfaces, views, flows and generic
+    /// instances. Alphabetical and dependency order do not depend on the
+    /// order the files were read in.
+    pub order: MergeOrder,
+    /// Project type aliases, expanded wherever a field is written with one.
+    pub type_aliases: Vec<TypeAlias>,
+    /// Parents every model inherits without naming them, such as a
+    /// `Timestampable` interface. Models a default parent itself inherits
+    /// from are left out.
+    pub default_inherits: Vec<String>,
+}
+
+/// A project type alias: `email = "string(320)"` makes a field typed
+/// `email` a `string(320)`. Built with [`TypeAlias::new`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeAlias {
+    pub name: String,
+    pub field_type: String,
+    pub params: Option<Vec<ParamValue>>,
+    pub generic_params: Option<Vec<String>>,
+    pub nullable: bool,
+    pub array: bool,
+    pub array_item_nullable: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct ValidateOptions {
     pub strict: bool,
 }
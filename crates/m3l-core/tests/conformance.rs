@@ -281,6 +281,8 @@ fn conformance_view_sql_block() {
     assert!(src.raw_sql.is_some());
     assert!(src.raw_sql.as_ref().unwrap().contains("FROM User u"));
     assert_eq!(src.language_hint.as_deref(), Some("sql"));
+    assert_eq!(src.from.as_deref(), Some("User"));
+    assert!(src.joins.is_none());
 }
 
 #[test]
@@ -784,6 +786,11 @@ fn sample_01_ecommerce_views() {
     let src2 = cos.source_def.as_ref().unwrap();
     assert!(src2.raw_sql.is_some());
     assert!(src2.raw_sql.as_ref().unwrap().contains("FROM Customer"));
+    assert_eq!(src2.from.as_deref(), Some("Customer"));
+    let joins = src2.joins.as_ref().unwrap();
+    assert_eq!(joins.len(), 1);
+    assert_eq!(joins[0].model, "Order");
+    assert_eq!(joins[0].on, "c.id = o.customer_id");
 
     // CustomerOrderSummary has fields
     assert!(cos.fields.len() >= 4);
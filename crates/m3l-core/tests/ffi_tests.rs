@@ -1,4 +1,6 @@
-use m3l_core::{parse_multi_to_json, parse_to_json, validate_to_json};
+use m3l_core::{
+    explain_to_json, extract_to_json, parse_multi_to_json, parse_to_json, validate_to_json,
+};
 use serde_json::Value;
 
 fn assert_success(json: &str) -> Value {
@@ -116,6 +118,54 @@ fn ffi_parse_multi_empty_array() {
     assert_eq!(v["data"]["models"].as_array().unwrap().len(), 0);
 }
 
+// ---------------------------------------------------------------------------
+// extract_to_json
+// ---------------------------------------------------------------------------
+
+#[test]
+fn ffi_extract_pointer_and_jsonpath() {
+    let content = "## User\n- id: identifier\n- email: email\n\n## Order\n- id: identifier\n- total: decimal\n";
+
+    let result = extract_to_json(content, "shop.m3l.md", "/models/1/name");
+    let v = assert_success(&result);
+    assert_eq!(v["data"], "Order");
+
+    let result = extract_to_json(
+        content,
+        "shop.m3l.md",
+        "$.models[?(@.name == 'User')].fields[*].name",
+    );
+    let v = assert_success(&result);
+    assert_eq!(v["data"], serde_json::json!(["id", "email"]));
+}
+
+#[test]
+fn ffi_extract_missing_or_invalid_path() {
+    let content = "## User\n- id: identifier\n";
+    let v = assert_failure(&extract_to_json(content, "a.m3l.md", "/models/3"));
+    assert!(v["error"].as_str().unwrap().contains("/models/3"));
+    let v = assert_failure(&extract_to_json(content, "a.m3l.md", "models"));
+    assert!(v["error"].as_str().unwrap().contains("Invalid path"));
+}
+
+// ---------------------------------------------------------------------------
+// explain_to_json
+// ---------------------------------------------------------------------------
+
+#[test]
+fn ffi_explain_code_and_rule() {
+    let v = assert_success(&explain_to_json("e005"));
+    assert_eq!(v["data"]["code"], "M3L-E005");
+    assert_eq!(v["data"]["kind"], "error");
+    assert!(v["data"]["bad"].as_str().unwrap().contains("## Customer"));
+
+    let v = assert_success(&explain_to_json("naming-convention"));
+    assert_eq!(v["data"]["kind"], "lint");
+
+    let v = assert_failure(&explain_to_json("M3L-E999"));
+    assert!(v["error"].as_str().unwrap().contains("M3L-E999"));
+}
+
 // ---------------------------------------------------------------------------
 // validate_to_json
 // ---------------------------------------------------------------------------
@@ -155,6 +205,20 @@ fn ffi_validate_invalid_options() {
         .contains("Invalid options JSON"));
 }
 
+#[test]
+fn ffi_validate_rejects_unknown_option_keys() {
+    let content = "## Product\n- name : string\n";
+    let result = validate_to_json(content, r#"{"strcit": true}"#);
+    let v = assert_failure(&result);
+    let error = v["error"].as_str().unwrap();
+    assert!(error.contains("Invalid options JSON"), "{error}");
+    assert!(error.contains("unknown field `strcit`"), "{error}");
+    assert!(
+        error.contains("`strict`") && error.contains("`filename`"),
+        "{error}"
+    );
+}
+
 #[test]
 fn ffi_validate_default_options() {
     let content = "## Product\n- name : string\n";
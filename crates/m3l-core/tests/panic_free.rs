@@ -0,0 +1,155 @@
+//! The parser must turn any input into an AST plus diagnostics. Hosts call it
+//! through the C ABI and wasm; wasm32 cannot unwind, so a panic aborts the
+//! host and the `catch_unwind` in `ffi` is no safety net there.
+//!
+//! These tests replay inputs that used to panic and run a deterministic
+//! mutation pass over the samples; `fuzz/` explores further with libFuzzer.
+
+use std::path::Path;
+
+use m3l_core::{parse_string, resolve, validate, ValidateOptions};
+
+fn pipeline(input: &str) {
+    let parsed = parse_string(input, "fuzz.m3l.md");
+    let ast = resolve(&[parsed], None);
+    validate(&ast, &ValidateOptions { strict: true });
+}
+
+// ---------------------------------------------------------------------------
+// Regressions
+// ---------------------------------------------------------------------------
+
+#[test]
+fn code_block_with_multibyte_indent() {
+    pipeline("## Report ::view\n### Source\n  ```sql\n🎉 SELECT 1\n한\n  ```\n");
+}
+
+#[test]
+fn code_block_line_shorter_than_fence_indent_keeps_text() {
+    let input = "## A\n- total: decimal @computed_raw\n    ```sql\n  a + b\n    ```\n";
+    let ast = resolve(&[parse_string(input, "a.m3l.md")], None);
+    let field = &ast.models[0].fields[0];
+    let code = field.computed.as_ref().map(|c| c.expression.as_str());
+    assert!(code.is_none_or(|c| c.contains("a + b")), "code: {code:?}");
+}
+
+#[test]
+fn unterminated_computed_raw_quote() {
+    pipeline("## A\n- x: json @computed_raw('`metadata->>')\n");
+}
+
+#[test]
+fn lone_quote_in_extended_attribute() {
+    pipeline("## A\n- status: string\n  - default: \"\n  - min: '\n");
+}
+
+// ---------------------------------------------------------------------------
+// Mutation pass
+// ---------------------------------------------------------------------------
+
+/// Fragments spliced into the samples: M3L syntax, quotes and brackets that
+/// the lexer scans byte-wise, and multibyte characters.
+const FRAGMENTS: &[&str] = &[
+    "é",
+    "한",
+    "🎉",
+    "\u{200b}",
+    "\"",
+    "'",
+    "`",
+    "(",
+    ")",
+    "[",
+    "]",
+    "@",
+    "#",
+    ":",
+    "=",
+    "?",
+    "!",
+    "<",
+    ">",
+    "\\",
+    "- ",
+    "## ",
+    "### ",
+    "> ",
+    "::",
+    "```",
+    "@reference(",
+    "@lookup(",
+    "\n",
+    "\t",
+    "\r\n",
+    ",",
+    ".",
+];
+
+/// xorshift64; deterministic so failures reproduce.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+fn seeds() -> Vec<String> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    let mut seeds = Vec::new();
+    for dir in ["samples", "samples/multi", "samples/test/validate"] {
+        let Ok(entries) = std::fs::read_dir(root.join(dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|e| e == "md") {
+                seeds.push(std::fs::read_to_string(&path).unwrap());
+            }
+        }
+    }
+    seeds.sort();
+    seeds
+}
+
+fn mutate(rng: &mut Rng, input: &str) -> String {
+    let mut s = input.to_string();
+    for _ in 0..1 + rng.below(6) {
+        let boundaries: Vec<usize> = s
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(s.len()))
+            .collect();
+        let at = boundaries[rng.below(boundaries.len())];
+        match rng.below(4) {
+            0 | 1 => s.insert_str(at, FRAGMENTS[rng.below(FRAGMENTS.len())]),
+            2 => {
+                let end = boundaries
+                    .iter()
+                    .copied()
+                    .filter(|&b| b >= at)
+                    .nth(rng.below(6))
+                    .unwrap_or(s.len());
+                s.replace_range(at..end, "");
+            }
+            _ => s.truncate(at),
+        }
+    }
+    s
+}
+
+#[test]
+fn mutated_samples_do_not_panic() {
+    let seeds = seeds();
+    assert!(!seeds.is_empty(), "no sample files found");
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    for _ in 0..3000 {
+        let seed = &seeds[rng.below(seeds.len())];
+        let input = mutate(&mut rng, seed);
+        let result = std::panic::catch_unwind(|| pipeline(&input));
+        assert!(result.is_ok(), "parser panicked on input:\n{input}");
+    }
+}
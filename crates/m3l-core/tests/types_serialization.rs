@@ -7,6 +7,7 @@ fn field_node_json_keys() {
     let field = FieldNode {
         name: "email".into(),
         label: None,
+        labels: Default::default(),
         field_type: Some("string".into()),
         params: None,
         generic_params: None,
@@ -16,18 +17,28 @@ fn field_node_json_keys() {
         kind: FieldKind::Stored,
         default_value: None,
         default_value_type: None,
+        default_json: None,
         description: None,
+        doc: None,
+        deprecated: None,
         attributes: vec![],
         framework_attrs: None,
+        reference: None,
+        from: None,
         lookup: None,
         rollup: None,
         computed: None,
         enum_values: None,
+        union: None,
+        tuple: None,
+        type_alias: None,
         fields: None,
         loc: SourceLocation {
             file: "test.m3l.md".into(),
             line: 1,
             col: 1,
+            end_line: 1,
+            end_col: 20,
         },
     };
 
@@ -46,16 +57,21 @@ fn field_node_json_keys() {
 
     // Optional fields should be absent when None
     assert!(!obj.contains_key("label"));
+    assert!(!obj.contains_key("labels"));
+    assert!(!obj.contains_key("doc"));
     assert!(!obj.contains_key("params"));
     assert!(!obj.contains_key("generic_params"));
     assert!(!obj.contains_key("default_value"));
     assert!(!obj.contains_key("default_value_type"));
+    assert!(!obj.contains_key("default_json"));
     assert!(!obj.contains_key("description"));
     assert!(!obj.contains_key("framework_attrs"));
     assert!(!obj.contains_key("lookup"));
     assert!(!obj.contains_key("rollup"));
     assert!(!obj.contains_key("computed"));
     assert!(!obj.contains_key("enum_values"));
+    assert!(!obj.contains_key("union"));
+    assert!(!obj.contains_key("tuple"));
     assert!(!obj.contains_key("fields"));
 
     // Always-present fields have correct values
@@ -66,6 +82,25 @@ fn field_node_json_keys() {
     assert_eq!(json["attributes"], serde_json::json!([]));
 }
 
+#[test]
+fn source_location_json_keys() {
+    let loc = SourceLocation {
+        file: "test.m3l.md".into(),
+        line: 2,
+        col: 1,
+        end_line: 4,
+        end_col: 18,
+    };
+    let json = serde_json::to_value(&loc).unwrap();
+    assert_eq!(json["endLine"], 4); // camelCase
+    assert_eq!(json["endCol"], 18);
+
+    // ASTs written before ranges were recorded still load
+    let old: SourceLocation =
+        serde_json::from_str(r#"{"file":"test.m3l.md","line":2,"col":1}"#).unwrap();
+    assert_eq!((old.end_line, old.end_col), (0, 0));
+}
+
 #[test]
 fn field_attribute_optional_fields() {
     let attr = FieldAttribute {
@@ -74,6 +109,7 @@ fn field_attribute_optional_fields() {
         cascade: None,
         is_standard: None,
         is_registered: None,
+        loc: None,
     };
     let json = serde_json::to_value(&attr).unwrap();
     let obj = json.as_object().unwrap();
@@ -90,6 +126,7 @@ fn field_attribute_with_standard() {
         cascade: None,
         is_standard: Some(true),
         is_registered: None,
+        loc: None,
     };
     let json = serde_json::to_value(&attr).unwrap();
 
@@ -103,21 +140,32 @@ fn model_node_json_keys() {
     let model = ModelNode {
         name: "User".into(),
         label: None,
+        labels: Default::default(),
         model_type: ModelType::Model,
         source: "test.m3l.md".into(),
+        namespace: None,
         line: 1,
         inherits: vec![],
+        aliases: vec![],
         description: None,
+        doc: None,
+        deprecated: None,
         attributes: vec![],
         fields: vec![],
         sections: Sections::default(),
         materialized: None,
         source_def: None,
         refresh: None,
+        generic_params: vec![],
+        instance_of: None,
+        generic_args: vec![],
+        default_inherits: vec![],
         loc: SourceLocation {
             file: "test.m3l.md".into(),
             line: 1,
             col: 1,
+            end_line: 1,
+            end_col: 20,
         },
     };
 
@@ -132,6 +180,8 @@ fn model_node_json_keys() {
     assert!(!obj.contains_key("materialized"));
     assert!(!obj.contains_key("source_def"));
     assert!(!obj.contains_key("refresh"));
+    assert!(!obj.contains_key("generic_params"));
+    assert!(!obj.contains_key("instance_of"));
 }
 
 #[test]
@@ -144,15 +194,21 @@ fn m3l_ast_json_keys() {
             version: None,
         },
         sources: vec![],
+        files: vec![],
         models: vec![],
         enums: vec![],
         interfaces: vec![],
         views: vec![],
         flows: vec![],
-        extensions: std::collections::HashMap::new(),
+        extensions: std::collections::BTreeMap::new(),
         attribute_registry: vec![],
+        file_metadata: vec![],
+        m3l_versions: vec![],
+        generic_instances: vec![],
+        extend_blocks: vec![],
         errors: vec![],
         warnings: vec![],
+        symbols: Default::default(),
     };
 
     let json = serde_json::to_value(&ast).unwrap();
@@ -162,6 +218,10 @@ fn m3l_ast_json_keys() {
     assert!(obj.contains_key("parserVersion"));
     assert!(obj.contains_key("astVersion"));
     assert!(obj.contains_key("attributeRegistry"));
+    // Omitted when no file has frontmatter
+    assert!(!obj.contains_key("fileMetadata"));
+    assert!(!obj.contains_key("genericInstances"));
+    assert!(!obj.contains_key("extendBlocks"));
 
     // Not snake_case
     assert!(!obj.contains_key("parser_version"));
@@ -174,21 +234,28 @@ fn enum_node_json() {
     let en = EnumNode {
         name: "Status".into(),
         label: None,
+        labels: Default::default(),
         enum_type: ModelType::Enum,
         source: "test.m3l.md".into(),
+        namespace: None,
         line: 5,
         inherits: vec![],
         description: None,
+        doc: None,
+        deprecated: None,
         values: vec![EnumValue {
             name: "Active".into(),
             description: Some("Active status".into()),
             value_type: None,
             value: None,
+            loc: None,
         }],
         loc: SourceLocation {
             file: "test.m3l.md".into(),
             line: 5,
             col: 1,
+            end_line: 5,
+            end_col: 20,
         },
     };
 
@@ -206,6 +273,8 @@ fn attribute_registry_entry_json() {
         target: vec!["field".into(), "model".into()],
         attr_type: "boolean".into(),
         range: None,
+        values: None,
+        pattern: None,
         required: false,
         default_value: Some(AttrArgValue::Bool(false)),
     };
@@ -269,7 +338,7 @@ fn catalogs_content() {
     assert!(TYPE_CATALOG.contains("string"));
     assert!(TYPE_CATALOG.contains("datetime")); // deprecated but still accepted
     assert!(!TYPE_CATALOG.contains("unknown_type"));
-    assert_eq!(TYPE_CATALOG.len(), 22);
+    assert_eq!(TYPE_CATALOG.len(), 24);
 
     // Standard attributes
     assert!(STANDARD_ATTRIBUTES.contains("primary"));
@@ -278,7 +347,8 @@ fn catalogs_content() {
     assert!(STANDARD_ATTRIBUTES.contains("min_length"));
     assert!(STANDARD_ATTRIBUTES.contains("max_length"));
     assert!(!STANDARD_ATTRIBUTES.contains("custom_attr"));
-    assert_eq!(STANDARD_ATTRIBUTES.len(), 34);
+    assert!(STANDARD_ATTRIBUTES.contains("check"));
+    assert_eq!(STANDARD_ATTRIBUTES.len(), 38);
 
     // Kind sections
     assert!(KIND_SECTIONS.contains("Lookup"));
@@ -0,0 +1,191 @@
+//! Lint diagnostics and textDocument/codeAction quick-fixes.
+//!
+//! Fixes come from the linter's [`LintFix`]; renames are widened to every
+//! reference through the rename machinery, other edits apply as written.
+
+use std::collections::HashMap;
+
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, DiagnosticSeverity,
+    NumberOrString, Position, Range, TextEdit, Url, WorkspaceEdit,
+};
+use m3l_core::ProjectSnapshot;
+use m3l_lint::{LintDiagnostic, LintFix, LintSeverity, Linter};
+
+use crate::document::{line_at, utf16_col};
+
+/// Run the default lint rules over the snapshot.
+pub fn lint(snapshot: &ProjectSnapshot) -> Vec<LintDiagnostic> {
+    Linter::default().lint(&snapshot.ast)
+}
+
+/// Range of the first fix edit in `d`'s file, or of its whole line.
+fn diagnostic_range(snapshot: &ProjectSnapshot, d: &LintDiagnostic) -> Range {
+    let line0 = d.line.saturating_sub(1);
+    let anchored = d.fix.as_ref().and_then(|fix| {
+        let edit = fix.edits.iter().find(|e| e.file == d.file)?;
+        let text = line_at(snapshot.file(&edit.file)?, edit.line.checked_sub(1)?)?;
+        let (start, end) = edit.columns(text)?;
+        let line = edit.line as u32 - 1;
+        Some(Range::new(
+            Position::new(line, utf16_col(text, start) as u32),
+            Position::new(line, utf16_col(text, end) as u32),
+        ))
+    });
+    anchored.unwrap_or_else(|| {
+        let col = d.col.saturating_sub(1);
+        let col = snapshot
+            .file(&d.file)
+            .and_then(|text| line_at(text, line0))
+            .map_or(col, |text| utf16_col(text, col)) as u32;
+        Range::new(
+            Position::new(line0 as u32, col),
+            Position::new(line0 as u32, u32::MAX),
+        )
+    })
+}
+
+/// Convert a lint diagnostic for publishing.
+pub fn to_lsp_diagnostic(snapshot: &ProjectSnapshot, d: &LintDiagnostic) -> Diagnostic {
+    Diagnostic {
+        range: diagnostic_range(snapshot, d),
+        severity: Some(match d.severity {
+            LintSeverity::Error => DiagnosticSeverity::ERROR,
+            LintSeverity::Warning => DiagnosticSeverity::WARNING,
+            LintSeverity::Info => DiagnosticSeverity::INFORMATION,
+        }),
+        code: Some(NumberOrString::String(d.rule.clone())),
+        source: Some("m3l-lint".into()),
+        message: d.message.clone(),
+        ..Default::default()
+    }
+}
+
+/// Workspace edit for a fix. Renames that land on a known model or field
+/// update every reference; anything else applies the edits verbatim.
+pub fn fix_to_edit(snapshot: &ProjectSnapshot, fix: &LintFix) -> Option<WorkspaceEdit> {
+    let mut positions = Vec::new();
+    for edit in &fix.edits {
+        let text = line_at(snapshot.file(&edit.file)?, edit.line.checked_sub(1)?)?;
+        let (start, end) = edit.columns(text)?;
+        let (start, end) = (utf16_col(text, start), utf16_col(text, end));
+        positions.push((edit, edit.line as u32 - 1, start as u32, end as u32));
+    }
+
+    if fix.is_rename {
+        if let [(edit, line, start, _)] = positions[..] {
+            let pos = Position::new(line, start);
+            if let Ok(Some(ws)) = crate::rename::rename(snapshot, &edit.file, pos, &edit.new_text) {
+                if ws.changes.as_ref().is_some_and(|c| !c.is_empty()) {
+                    return Some(ws);
+                }
+            }
+        }
+    }
+
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+    for (edit, line, start, end) in positions {
+        changes
+            .entry(Url::parse(&edit.file).ok()?)
+            .or_default()
+            .push(TextEdit {
+                range: Range::new(Position::new(line, start), Position::new(line, end)),
+                new_text: edit.new_text.clone(),
+            });
+    }
+    Some(WorkspaceEdit {
+        changes: Some(changes),
+        ..Default::default()
+    })
+}
+
+/// Quick-fixes for lint diagnostics in `file` whose lines overlap `range`.
+pub fn code_actions(
+    snapshot: &ProjectSnapshot,
+    file: &str,
+    range: Range,
+) -> Vec<CodeActionOrCommand> {
+    lint(snapshot)
+        .iter()
+        .filter(|d| d.file == file)
+        .filter_map(|d| {
+            let fix = d.fix.as_ref()?;
+            let diagnostic = to_lsp_diagnostic(snapshot, d);
+            if diagnostic.range.start.line > range.end.line
+                || diagnostic.range.end.line < range.start.line
+            {
+                return None;
+            }
+            Some(CodeActionOrCommand::CodeAction(CodeAction {
+                title: fix.title.clone(),
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![diagnostic]),
+                edit: Some(fix_to_edit(snapshot, fix)?),
+                is_preferred: Some(true),
+                ..Default::default()
+            }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m3l_core::Project;
+
+    fn actions(files: &[(&str, &str)], file: &str, line: u32) -> Vec<CodeAction> {
+        let mut project = Project::default();
+        for (path, content) in files {
+            project.set_file(path, content);
+        }
+        let snap = project.snapshot();
+        let range = Range::new(Position::new(line, 0), Position::new(line, 0));
+        code_actions(&snap, file, range)
+            .into_iter()
+            .filter_map(|a| match a {
+                CodeActionOrCommand::CodeAction(a) => Some(a),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn field_rename_fix_updates_references() {
+        let base = "## User\n- id: identifier @pk\n- FullName: string\n";
+        let post = "## Post\n- author_id: identifier @reference(User.id)\n- author: string @lookup(author_id.FullName)\n";
+        let found = actions(
+            &[("file:///base.m3l.md", base), ("file:///post.m3l.md", post)],
+            "file:///base.m3l.md",
+            2,
+        );
+        let action = found
+            .iter()
+            .find(|a| a.title == "Rename to \"full_name\"")
+            .unwrap();
+        assert_eq!(action.kind, Some(CodeActionKind::QUICKFIX));
+        let changes = action.edit.as_ref().unwrap().changes.as_ref().unwrap();
+        let post_edits = &changes[&Url::parse("file:///post.m3l.md").unwrap()];
+        assert_eq!(post_edits[0].new_text, "full_name");
+        assert_eq!(post_edits[0].range.start, Position::new(2, 35));
+    }
+
+    #[test]
+    fn enum_value_fix_edits_declaration() {
+        let text = "## Status ::enum\n- active: \"Active\"\n- OnHold: \"On hold\"\n";
+        let found = actions(&[("file:///a.m3l.md", text)], "file:///a.m3l.md", 2);
+        let action = found.iter().find(|a| a.title.contains("on_hold")).unwrap();
+        let changes = action.edit.as_ref().unwrap().changes.as_ref().unwrap();
+        let edits = &changes[&Url::parse("file:///a.m3l.md").unwrap()];
+        assert_eq!(edits.len(), 1);
+        assert_eq!(
+            edits[0].range,
+            Range::new(Position::new(2, 2), Position::new(2, 8))
+        );
+    }
+
+    #[test]
+    fn no_actions_outside_range() {
+        let text = "## User\n- id: identifier\n- FullName: string\n";
+        assert!(actions(&[("file:///a.m3l.md", text)], "file:///a.m3l.md", 1).is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+//! textDocument/definition
+
+use lsp_types::{Location, Position, Range, Url};
+use m3l_core::ProjectSnapshot;
+
+use crate::document::{line_at, name_column};
+use crate::navigation::{symbol_at, Symbol};
+
+/// Location of `name` on the given 1-based line of `file`.
+///
+/// The range covers the name itself when it can be found on the line, and
+/// falls back to the start of the line otherwise.
+pub fn name_location(
+    snapshot: &ProjectSnapshot,
+    file: &str,
+    line: usize,
+    name: &str,
+) -> Option<Location> {
+    let uri = Url::parse(file).ok()?;
+    let line0 = line.saturating_sub(1) as u32;
+    let col = snapshot
+        .file(file)
+        .and_then(|text| line_at(text, line0 as usize))
+        .and_then(|l| name_column(l, name))
+        .unwrap_or(0) as u32;
+    let len = if col == 0 && name.is_empty() {
+        0
+    } else {
+        name.encode_utf16().count() as u32
+    };
+    Some(Location {
+        uri,
+        range: Range::new(Position::new(line0, col), Position::new(line0, col + len)),
+    })
+}
+
+/// Find the definition of the symbol at `position` in `file`.
+pub fn definition(snapshot: &ProjectSnapshot, file: &str, position: Position) -> Option<Location> {
+    match symbol_at(
+        snapshot,
+        file,
+        position.line as usize,
+        position.character as usize,
+    )? {
+        Symbol::Element(el) => {
+            // Namespaced enums are declared as `Namespace.Name`; link the last part.
+            let short = el.name().rsplit('.').next().unwrap_or(el.name());
+            name_location(snapshot, el.file(), el.line(), short)
+        }
+        Symbol::Field { field, .. } => {
+            name_location(snapshot, &field.loc.file, field.loc.line, &field.name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m3l_core::Project;
+
+    #[test]
+    fn jumps_to_field_in_other_file() {
+        let mut project = Project::default();
+        project.set_file(
+            "file:///base.m3l.md",
+            "## Customer\n- id: identifier @pk\n- name: string\n",
+        );
+        project.set_file(
+            "file:///order.m3l.md",
+            "## Order\n- customer_id: identifier @reference(Customer.id)\n",
+        );
+        let snap = project.snapshot();
+
+        let loc = definition(&snap, "file:///order.m3l.md", Position::new(1, 46)).unwrap();
+        assert_eq!(loc.uri.as_str(), "file:///base.m3l.md");
+        assert_eq!(loc.range.start, Position::new(1, 2));
+        assert_eq!(loc.range.end, Position::new(1, 4));
+
+        let loc = definition(&snap, "file:///order.m3l.md", Position::new(1, 40)).unwrap();
+        assert_eq!(loc.range.start, Position::new(0, 3));
+    }
+
+    #[test]
+    fn jumps_to_namespaced_model() {
+        let mut project = Project::default();
+        project.set_file(
+            "file:///auth.m3l.md",
+            "# Namespace: auth\n## User\n- id: identifier @pk\n",
+        );
+        project.set_file(
+            "file:///shop.m3l.md",
+            "# Namespace: shop\n## User\n- id: identifier @pk\n\n## Order\n- buyer: auth.User\n- seller_id: identifier @reference(User.id)\n",
+        );
+        let snap = project.snapshot();
+
+        let loc = definition(&snap, "file:///shop.m3l.md", Position::new(5, 14)).unwrap();
+        assert_eq!(loc.uri.as_str(), "file:///auth.m3l.md");
+        assert_eq!(loc.range.start, Position::new(1, 3));
+        // The namespace part is not the model
+        assert!(definition(&snap, "file:///shop.m3l.md", Position::new(5, 9)).is_none());
+
+        // A short name prefers the model in the same namespace
+        let loc = definition(&snap, "file:///shop.m3l.md", Position::new(6, 36)).unwrap();
+        assert_eq!(loc.uri.as_str(), "file:///shop.m3l.md");
+        assert_eq!(loc.range.start, Position::new(1, 3));
+    }
+
+    #[test]
+    fn jumps_through_alias() {
+        let mut project = Project::default();
+        project.set_file(
+            "file:///shop.m3l.md",
+            "## User @alias(Account)\n- id: identifier @pk\n\n## Order\n- owner_id: identifier @reference(Account.id)\n",
+        );
+        let snap = project.snapshot();
+
+        let loc = definition(&snap, "file:///shop.m3l.md", Position::new(4, 36)).unwrap();
+        assert_eq!(loc.range.start, Position::new(0, 3));
+        let loc = definition(&snap, "file:///shop.m3l.md", Position::new(4, 44)).unwrap();
+        assert_eq!(loc.range.start, Position::new(1, 2));
+    }
+}
@@ -0,0 +1,183 @@
+//! Text helpers for mapping LSP positions onto M3L source lines.
+//!
+//! Columns are UTF-16 code units, as LSP positions count them. M3L
+//! identifiers may contain any alphanumeric character, so a column is
+//! converted to a character index before the line is inspected.
+
+/// Identifier under the cursor, including dotted segments (`Model.field`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordAt {
+    /// Full dotted text, e.g. `customer_id.name`.
+    pub text: String,
+    /// Start column (inclusive) of the full text.
+    pub start: usize,
+    /// End column (exclusive) of the full text.
+    pub end: usize,
+    /// Index of the dotted segment the cursor is on.
+    pub segment: usize,
+    /// Whether the word is prefixed by `@` (an attribute name).
+    pub is_attribute: bool,
+}
+
+impl WordAt {
+    pub fn segments(&self) -> Vec<&str> {
+        self.text.split('.').collect()
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// UTF-16 column of the character at `index` in `line`.
+pub fn utf16_col(line: &str, index: usize) -> usize {
+    line.chars().take(index).map(char::len_utf16).sum()
+}
+
+/// Index of the character at UTF-16 column `col` of `line`, or the line
+/// length past its end. A column inside a surrogate pair maps to the
+/// character it splits.
+pub fn char_index(line: &str, col: usize) -> usize {
+    let mut units = 0;
+    for (i, c) in line.chars().enumerate() {
+        units += c.len_utf16();
+        if units > col {
+            return i;
+        }
+    }
+    line.chars().count()
+}
+
+/// Return the given 0-based line of `text`.
+pub fn line_at(text: &str, line: usize) -> Option<&str> {
+    text.lines().nth(line)
+}
+
+/// Find the (possibly dotted) identifier at column `col` of `line`.
+pub fn word_at(line: &str, col: usize) -> Option<WordAt> {
+    let chars: Vec<char> = line.chars().collect();
+    let is_word = |c: char| is_ident_char(c) || c == '.';
+
+    // Allow the cursor to sit right after the last character of a word.
+    let mut pos = char_index(line, col);
+    if pos == chars.len() || !is_word(chars[pos]) {
+        if pos > 0 && is_word(chars[pos - 1]) {
+            pos -= 1;
+        } else {
+            return None;
+        }
+    }
+
+    let mut start = pos;
+    while start > 0 && is_word(chars[start - 1]) {
+        start -= 1;
+    }
+    let mut end = pos;
+    while end < chars.len() && is_word(chars[end]) {
+        end += 1;
+    }
+
+    // Trim leading/trailing dots (e.g. sentence punctuation)
+    while start < end && chars[start] == '.' {
+        start += 1;
+    }
+    while end > start && chars[end - 1] == '.' {
+        end -= 1;
+    }
+    if start >= end || pos < start || pos >= end {
+        return None;
+    }
+
+    let text: String = chars[start..end].iter().collect();
+    let segment = chars[start..pos].iter().filter(|&&c| c == '.').count();
+    let is_attribute = start > 0 && chars[start - 1] == '@';
+
+    Some(WordAt {
+        text,
+        start: utf16_col(line, start),
+        end: utf16_col(line, end),
+        segment,
+        is_attribute,
+    })
+}
+
+/// Column of the first whole-word occurrence of `name` in `line`.
+pub fn name_column(line: &str, name: &str) -> Option<usize> {
+    let chars: Vec<char> = line.chars().collect();
+    let needle: Vec<char> = name.chars().collect();
+    if needle.is_empty() || needle.len() > chars.len() {
+        return None;
+    }
+    (0..=chars.len() - needle.len())
+        .find(|&i| {
+            chars[i..i + needle.len()] == needle[..]
+                && (i == 0 || !is_ident_char(chars[i - 1]))
+                && chars
+                    .get(i + needle.len())
+                    .is_none_or(|&c| !is_ident_char(c))
+        })
+        .map(|i| utf16_col(line, i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_at_simple_identifier() {
+        let w = word_at("## Child : Base", 12).unwrap();
+        assert_eq!(w.text, "Base");
+        assert_eq!(w.segment, 0);
+        assert!(!w.is_attribute);
+    }
+
+    #[test]
+    fn word_at_dotted_segment() {
+        let line = "- customer_name: string @lookup(customer_id.name)";
+        let col = line.find("name)").unwrap() + 1;
+        let w = word_at(line, col).unwrap();
+        assert_eq!(w.text, "customer_id.name");
+        assert_eq!(w.segment, 1);
+    }
+
+    #[test]
+    fn word_at_attribute_name() {
+        let line = "- author_id: identifier @reference(User)";
+        let col = line.find("reference").unwrap() + 2;
+        let w = word_at(line, col).unwrap();
+        assert!(w.is_attribute);
+    }
+
+    #[test]
+    fn word_at_end_of_word() {
+        let w = word_at("- from: Order", 13).unwrap();
+        assert_eq!(w.text, "Order");
+    }
+
+    #[test]
+    fn word_at_whitespace_is_none() {
+        assert!(word_at("- a:  b", 4).is_none());
+    }
+
+    #[test]
+    fn columns_are_utf16_units() {
+        // `é` is one unit and `𝑥` two, so units and characters diverge
+        let line = "- 𝑥_café: string @reference(Café)";
+        let col = line.encode_utf16().count() - 3;
+        let w = word_at(line, col).unwrap();
+        assert_eq!(w.text, "Café");
+        assert_eq!((w.start, w.end), (29, 33));
+        let w = word_at(line, 5).unwrap();
+        assert_eq!(w.text, "𝑥_café");
+        assert_eq!((w.start, w.end), (2, 9));
+        assert_eq!(name_column(line, "Café"), Some(29));
+        assert_eq!(utf16_col(line, 3), 4);
+        assert_eq!(char_index(line, 4), 3);
+    }
+
+    #[test]
+    fn name_column_respects_word_boundaries() {
+        assert_eq!(name_column("- user_id: User", "User"), Some(11));
+        assert_eq!(name_column("## Users", "User"), None);
+    }
+}
@@ -0,0 +1,240 @@
+//! textDocument/hover
+
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position};
+use m3l_core::{
+    AttrArgValue, EnumNode, FieldAttribute, FieldNode, M3lAst, ModelNode, ModelType, ParamValue,
+    ProjectSnapshot,
+};
+
+use crate::navigation::{enclosing_element, symbol_at, Element, Symbol};
+
+/// Hover content for the symbol at `position` in `file`.
+pub fn hover(snapshot: &ProjectSnapshot, file: &str, position: Position) -> Option<Hover> {
+    let markdown = match symbol_at(
+        snapshot,
+        file,
+        position.line as usize,
+        position.character as usize,
+    )? {
+        Symbol::Element(Element::Model(model)) => model_markdown(model),
+        Symbol::Element(Element::Enum(en)) => enum_markdown(en),
+        Symbol::Field { owner, field } => field_markdown(&snapshot.ast, owner, field),
+    };
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: markdown,
+        }),
+        range: None,
+    })
+}
+
+fn kind_label(model_type: &ModelType) -> &str {
+    match model_type {
+        ModelType::Model => "model",
+        ModelType::Enum => "enum",
+        ModelType::Interface => "interface",
+        ModelType::View => "view",
+        ModelType::Flow => "flow",
+        ModelType::Extension(s) => s,
+    }
+}
+
+/// Field type as written in source, e.g. `decimal(10, 2)?` or `string[]`.
+pub fn type_signature(field: &FieldNode) -> String {
+    let mut sig = field.field_type.clone().unwrap_or_default();
+    if let Some(ref generics) = field.generic_params {
+        sig.push_str(&format!("<{}>", generics.join(", ")));
+    }
+    if let Some(ref params) = field.params {
+        let params: Vec<String> = params
+            .iter()
+            .map(|p| match p {
+                ParamValue::String(s) => s.clone(),
+                ParamValue::Number(n) => n.to_string(),
+            })
+            .collect();
+        sig.push_str(&format!("({})", params.join(", ")));
+    }
+    if field.array {
+        if field.array_item_nullable {
+            sig.push('?');
+        }
+        sig.push_str("[]");
+    }
+    if field.nullable {
+        sig.push('?');
+    }
+    sig
+}
+
+fn attribute_text(attr: &FieldAttribute) -> String {
+    let mut text = format!("@{}", attr.name);
+    if let Some(ref args) = attr.args {
+        let args: Vec<String> = args
+            .iter()
+            .map(|a| match a {
+                AttrArgValue::String(s) => s.clone(),
+                AttrArgValue::Number(n) => n.to_string(),
+                AttrArgValue::Bool(b) => b.to_string(),
+            })
+            .collect();
+        text.push_str(&format!("({})", args.join(", ")));
+    }
+    text
+}
+
+/// Name of the model that declares `field`, when it is not `owner` itself.
+pub fn inherited_from<'a>(
+    ast: &'a M3lAst,
+    owner: &ModelNode,
+    field: &FieldNode,
+) -> Option<&'a str> {
+    match enclosing_element(ast, &field.loc.file, field.loc.line)? {
+        Element::Model(declaring) if declaring.name != owner.name => Some(&declaring.name),
+        _ => None,
+    }
+}
+
+fn model_markdown(model: &ModelNode) -> String {
+    let mut out = format!("```m3l\n## {}", model.name);
+    if let Some(ref label) = model.label {
+        out.push_str(&format!("({label})"));
+    }
+    if model.model_type != ModelType::Model {
+        out.push_str(&format!(" ::{}", kind_label(&model.model_type)));
+    }
+    if !model.inherits.is_empty() {
+        out.push_str(&format!(" : {}", model.inherits.join(", ")));
+    }
+    out.push_str("\n```");
+
+    if let Some(ref desc) = model.description {
+        out.push_str(&format!("\n\n{desc}"));
+    }
+
+    if !model.fields.is_empty() {
+        out.push_str("\n\n| Field | Type |\n|---|---|");
+        for field in &model.fields {
+            out.push_str(&format!(
+                "\n| `{}` | `{}` |",
+                field.name,
+                type_signature(field)
+            ));
+        }
+    }
+    out
+}
+
+fn enum_markdown(en: &EnumNode) -> String {
+    let mut out = format!("```m3l\n## {} ::enum", en.name);
+    if !en.inherits.is_empty() {
+        out.push_str(&format!(" : {}", en.inherits.join(", ")));
+    }
+    out.push_str("\n```");
+    if let Some(ref desc) = en.description {
+        out.push_str(&format!("\n\n{desc}"));
+    }
+    if !en.values.is_empty() {
+        out.push('\n');
+        for value in &en.values {
+            out.push_str(&format!("\n- `{}`", value.name));
+            if let Some(ref desc) = value.description {
+                out.push_str(&format!(" — {desc}"));
+            }
+        }
+    }
+    out
+}
+
+fn field_markdown(ast: &M3lAst, owner: &ModelNode, field: &FieldNode) -> String {
+    let mut out = format!(
+        "```m3l\n{}.{}: {}",
+        owner.name,
+        field.name,
+        type_signature(field)
+    );
+    if let Some(ref dv) = field.default_value {
+        out.push_str(&format!(" = {dv}"));
+    }
+    for attr in &field.attributes {
+        out.push(' ');
+        out.push_str(&attribute_text(attr));
+    }
+    out.push_str("\n```");
+
+    if let Some(parent) = inherited_from(ast, owner, field) {
+        out.push_str(&format!("\n\n*from {parent}*"));
+    }
+    if let Some(ref desc) = field.description {
+        out.push_str(&format!("\n\n{desc}"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m3l_core::Project;
+
+    fn hover_text(files: &[(&str, &str)], file: &str, line: u32, col: u32) -> String {
+        let mut project = Project::default();
+        for (path, content) in files {
+            project.set_file(path, content);
+        }
+        let snap = project.snapshot();
+        match hover(&snap, file, Position::new(line, col))
+            .unwrap()
+            .contents
+        {
+            HoverContents::Markup(m) => m.value,
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    const SCHEMA: &str = "## Timestampable ::interface\n- created_at: timestamp = now() \"Creation time\"\n\n## User : Timestampable\n> Registered account\n- id: identifier @pk\n- email: string(320)? @unique\n";
+
+    #[test]
+    fn model_hover_lists_parents_and_fields() {
+        let text = hover_text(&[("a.m3l.md", SCHEMA)], "a.m3l.md", 3, 4);
+        assert!(text.contains("## User : Timestampable"));
+        assert!(text.contains("Registered account"));
+        assert!(text.contains("| `email` | `string(320)?` |"));
+        assert!(text.contains("| `created_at` | `timestamp` |"));
+    }
+
+    #[test]
+    fn field_hover_shows_type_and_attributes() {
+        let text = hover_text(&[("a.m3l.md", SCHEMA)], "a.m3l.md", 6, 3);
+        assert!(text.contains("User.email: string(320)? @unique"));
+        assert!(!text.contains("*from"));
+    }
+
+    #[test]
+    fn inherited_field_hover_shows_origin() {
+        let order = "## Order : Timestampable\n- id: identifier @pk\n- placed: boolean @lookup(created_at)\n";
+        let text = hover_text(
+            &[("a.m3l.md", SCHEMA), ("b.m3l.md", order)],
+            "b.m3l.md",
+            2,
+            30,
+        );
+        assert!(text.contains("Order.created_at: timestamp = now()"));
+        assert!(text.contains("*from Timestampable*"));
+        assert!(text.contains("Creation time"));
+    }
+
+    #[test]
+    fn namespaced_type_hover_shows_model() {
+        let auth = "# Namespace: auth\n## User\n- id: identifier @pk\n- name: string\n";
+        let shop = "# Namespace: shop\n## Order\n- buyer: auth.User\n";
+        let text = hover_text(
+            &[("auth.m3l.md", auth), ("shop.m3l.md", shop)],
+            "shop.m3l.md",
+            2,
+            14,
+        );
+        assert!(text.contains("## User"));
+        assert!(text.contains("| `name` | `string` |"));
+    }
+}
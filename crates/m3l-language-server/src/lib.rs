@@ -0,0 +1,46 @@
+//! M3L language server.
+//!
+//! Speaks LSP over stdio on top of [`m3l_core::Project`], which keeps every
+//! workspace file parsed in memory and re-resolves on edit.
+
+pub mod code_actions;
+pub mod definition;
+pub mod document;
+pub mod hover;
+pub mod navigation;
+pub mod rename;
+pub mod semantic_tokens;
+pub mod server;
+pub mod symbols;
+
+use lsp_server::Connection;
+use lsp_types::InitializeParams;
+
+pub use server::{capabilities, Server, ServerResult};
+
+/// Serve LSP over stdin/stdout until the client exits.
+pub fn run() -> ServerResult<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let caps = serde_json::to_value(capabilities())?;
+    let init = connection.initialize(caps)?;
+    let params: InitializeParams = serde_json::from_value(init)?;
+
+    let mut server = Server::new();
+    #[allow(deprecated)]
+    let root = params
+        .workspace_folders
+        .into_iter()
+        .flatten()
+        .map(|f| f.uri)
+        .chain(params.root_uri)
+        .filter_map(|uri| uri.to_file_path().ok());
+    for dir in root {
+        server.load_workspace(&dir);
+    }
+
+    server.main_loop(&connection)?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
@@ -0,0 +1,8 @@
+use std::process;
+
+fn main() {
+    if let Err(e) = m3l_language_server::run() {
+        eprintln!("Error: {e}");
+        process::exit(1);
+    }
+}
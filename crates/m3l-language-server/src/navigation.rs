@@ -0,0 +1,247 @@
+//! Symbol lookup over a resolved project snapshot.
+//!
+//! Shared by go-to-definition and the other position-based requests: given a
+//! cursor position it works out which model, enum, or field the identifier
+//! under the cursor refers to.
+
+use m3l_core::{EnumNode, FieldNode, M3lAst, ModelNode, ProjectSnapshot};
+
+use crate::document::{line_at, word_at};
+
+/// A top-level named element (model, interface, view, flow, or enum).
+#[derive(Debug, Clone, Copy)]
+pub enum Element<'a> {
+    Model(&'a ModelNode),
+    Enum(&'a EnumNode),
+}
+
+impl<'a> Element<'a> {
+    pub fn name(&self) -> &'a str {
+        match self {
+            Element::Model(m) => &m.name,
+            Element::Enum(e) => &e.name,
+        }
+    }
+
+    pub fn file(&self) -> &'a str {
+        match self {
+            Element::Model(m) => &m.source,
+            Element::Enum(e) => &e.source,
+        }
+    }
+
+    pub fn namespace(&self) -> Option<&'a str> {
+        match self {
+            Element::Model(m) => m.namespace.as_deref(),
+            Element::Enum(e) => e.namespace.as_deref(),
+        }
+    }
+
+    /// 1-based line of the element header.
+    pub fn line(&self) -> usize {
+        match self {
+            Element::Model(m) => m.line,
+            Element::Enum(e) => e.line,
+        }
+    }
+}
+
+/// What the identifier under the cursor refers to.
+#[derive(Debug, Clone, Copy)]
+pub enum Symbol<'a> {
+    Element(Element<'a>),
+    Field {
+        owner: &'a ModelNode,
+        field: &'a FieldNode,
+    },
+}
+
+/// All model-like nodes: models, interfaces, views, and flows.
+pub fn all_models(ast: &M3lAst) -> impl Iterator<Item = &ModelNode> {
+    ast.models
+        .iter()
+        .chain(ast.interfaces.iter())
+        .chain(ast.views.iter())
+        .chain(ast.flows.iter())
+}
+
+/// The element `name` refers to when written in namespace `from`.
+pub fn find_element<'a>(ast: &'a M3lAst, name: &str, from: Option<&str>) -> Option<Element<'a>> {
+    ast.find_element_from(name, from)
+        .map(Element::Model)
+        .or_else(|| ast.find_enum(name).map(Element::Enum))
+}
+
+/// The element whose definition encloses the given 1-based line of `file`.
+pub fn enclosing_element<'a>(ast: &'a M3lAst, file: &str, line: usize) -> Option<Element<'a>> {
+    all_models(ast)
+        .filter(|m| m.source == file && m.line <= line)
+        .map(Element::Model)
+        .chain(
+            ast.enums
+                .iter()
+                .filter(|e| e.source == file && e.line <= line)
+                .map(Element::Enum),
+        )
+        .max_by_key(|e| e.line())
+}
+
+/// Model a field of `owner` points at: its `@reference`/`@fk` target, or
+/// its type when the type names a model.
+pub fn reference_target<'a>(
+    ast: &'a M3lAst,
+    owner: &ModelNode,
+    field: &FieldNode,
+) -> Option<&'a ModelNode> {
+    ast.reference_target(field).or_else(|| {
+        field
+            .field_type
+            .as_deref()
+            .and_then(|t| ast.find_element_from(t, owner.namespace.as_deref()))
+    })
+}
+
+/// Resolve the symbol at a 0-based line/column of `file`.
+///
+/// Dotted identifiers are walked segment by segment: a leading element name
+/// (`@reference(User.id)`) starts at that element, otherwise the path starts
+/// at the enclosing model and follows references (`@lookup(customer_id.name)`).
+pub fn symbol_at<'a>(
+    snapshot: &'a ProjectSnapshot,
+    file: &str,
+    line: usize,
+    col: usize,
+) -> Option<Symbol<'a>> {
+    let ast = &snapshot.ast;
+    let text = snapshot.file(file)?;
+    let word = word_at(line_at(text, line)?, col)?;
+    if word.is_attribute {
+        return None;
+    }
+
+    let segments = word.segments();
+    let upto = segments[..=word.segment].join(".");
+    let enclosing = enclosing_element(ast, file, line + 1);
+    let from = enclosing.and_then(|el| el.namespace());
+
+    // Whole (possibly namespaced) element name, e.g. `Order.Status` or
+    // `auth.User`; the namespace part itself names nothing
+    if let Some(el) = find_element(ast, &word.text, from).or_else(|| find_element(ast, &upto, from))
+    {
+        let in_namespace = el.namespace().is_some_and(|ns| {
+            word.text.starts_with(&format!("{ns}.")) && word.segment < ns.split('.').count()
+        });
+        return (!in_namespace).then_some(Symbol::Element(el));
+    }
+
+    // The longest leading element name starts the path (`auth.User.id`)
+    let start = (1..=word.segment)
+        .rev()
+        .find_map(|i| Some((ast.find_element_from(&segments[..i].join("."), from)?, i)));
+    let (mut current, rest) = match start {
+        Some((m, i)) => (Some(m), &segments[i..=word.segment]),
+        None => match enclosing? {
+            Element::Model(m) => (Some(m), &segments[..=word.segment]),
+            Element::Enum(_) => return None,
+        },
+    };
+
+    let mut result = None;
+    for (i, seg) in rest.iter().enumerate() {
+        let owner = current?;
+        let field = owner.fields.iter().find(|f| f.name == *seg)?;
+        if i == rest.len() - 1 {
+            result = Some(Symbol::Field { owner, field });
+        } else {
+            current = reference_target(ast, owner, field);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m3l_core::Project;
+
+    fn snapshot(files: &[(&str, &str)]) -> std::sync::Arc<ProjectSnapshot> {
+        let mut project = Project::default();
+        for (path, content) in files {
+            project.set_file(path, content);
+        }
+        project.snapshot()
+    }
+
+    /// Line/column of the `nth` occurrence of `needle` in `text`.
+    fn position(text: &str, needle: &str, nth: usize) -> (usize, usize) {
+        text.lines()
+            .enumerate()
+            .flat_map(|(i, line)| line.match_indices(needle).map(move |(c, _)| (i, c)))
+            .nth(nth)
+            .unwrap_or_else(|| panic!("{needle} not found"))
+    }
+
+    const BASE: &str = "## Timestampable ::interface\n- created_at: timestamp\n\n## Customer : Timestampable\n- id: identifier @pk\n- name: string\n";
+    const ORDER: &str = "## Order\n- id: identifier @pk\n- customer_id: identifier @reference(Customer)\n- customer_name: string @lookup(customer_id.name)\n\n## ActiveOrders ::view\n### Source\n- from: Order\n";
+
+    #[test]
+    fn resolves_inheritance_parent() {
+        let snap = snapshot(&[("base.m3l.md", BASE)]);
+        let (line, col) = position(BASE, "Timestampable", 1);
+        match symbol_at(&snap, "base.m3l.md", line, col) {
+            Some(Symbol::Element(el)) => assert_eq!(el.name(), "Timestampable"),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn resolves_reference_across_files() {
+        let snap = snapshot(&[("base.m3l.md", BASE), ("order.m3l.md", ORDER)]);
+        let (line, col) = position(ORDER, "Customer)", 0);
+        match symbol_at(&snap, "order.m3l.md", line, col) {
+            Some(Symbol::Element(el)) => {
+                assert_eq!(el.name(), "Customer");
+                assert_eq!(el.file(), "base.m3l.md");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn resolves_lookup_path_segment() {
+        let snap = snapshot(&[("base.m3l.md", BASE), ("order.m3l.md", ORDER)]);
+        let (line, col) = position(ORDER, "name)", 0);
+        match symbol_at(&snap, "order.m3l.md", line, col) {
+            Some(Symbol::Field { owner, field }) => {
+                assert_eq!(owner.name, "Customer");
+                assert_eq!(field.name, "name");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        let (line, col) = position(ORDER, "customer_id.name", 0);
+        match symbol_at(&snap, "order.m3l.md", line, col) {
+            Some(Symbol::Field { owner, field }) => {
+                assert_eq!(owner.name, "Order");
+                assert_eq!(field.name, "customer_id");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn resolves_view_from_target() {
+        let snap = snapshot(&[("order.m3l.md", ORDER)]);
+        let (line, col) = position(ORDER, "Order", 2);
+        match symbol_at(&snap, "order.m3l.md", line, col) {
+            Some(Symbol::Element(el)) => assert_eq!(el.name(), "Order"),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn attribute_names_do_not_resolve() {
+        let snap = snapshot(&[("order.m3l.md", ORDER)]);
+        let (line, col) = position(ORDER, "reference", 0);
+        assert!(symbol_at(&snap, "order.m3l.md", line, col + 1).is_none());
+    }
+}
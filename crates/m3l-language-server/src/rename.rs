@@ -0,0 +1,301 @@
+//! textDocument/rename
+//!
+//! Every identifier in the workspace is resolved with the same lookup used by
+//! go-to-definition; occurrences that resolve to the renamed symbol are
+//! rewritten. Descriptions, blockquotes, and inline comments are left alone.
+
+use std::collections::{HashMap, HashSet};
+
+use lsp_types::{Position, Range, TextEdit, Url, WorkspaceEdit};
+use m3l_core::ProjectSnapshot;
+
+use crate::document::{utf16_col, word_at};
+use crate::navigation::{symbol_at, Symbol};
+
+/// Identity of a renameable symbol, independent of where it is referenced.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SymbolKey {
+    /// Declaring file, line, and name of an element, so same-named
+    /// elements in other namespaces stay apart
+    Element(String, usize, String),
+    /// Declaring file, line, and name of a field
+    Field(String, usize, String),
+}
+
+fn key_of(symbol: &Symbol) -> SymbolKey {
+    match symbol {
+        Symbol::Element(el) => {
+            SymbolKey::Element(el.file().to_string(), el.line(), el.name().to_string())
+        }
+        Symbol::Field { field, .. } => {
+            SymbolKey::Field(field.loc.file.clone(), field.loc.line, field.name.clone())
+        }
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_numeric())
+        && name
+            .split('.')
+            .all(|s| !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_'))
+}
+
+/// Columns of `line` that hold code rather than prose: quoted strings,
+/// blockquotes, and trailing `# comments` are masked out.
+fn code_mask(line: &str) -> Vec<bool> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut mask = vec![true; chars.len()];
+    if line.trim_start().starts_with('>') {
+        return vec![false; chars.len()];
+    }
+    let is_item = line.trim_start().starts_with("- ");
+    let mut in_quote = false;
+    for i in 0..chars.len() {
+        if chars[i] == '"' {
+            in_quote = !in_quote;
+            mask[i] = false;
+            continue;
+        }
+        if in_quote {
+            mask[i] = false;
+        } else if is_item && chars[i] == '#' && i > 0 && chars[i - 1] == ' ' {
+            mask[i..].iter_mut().for_each(|m| *m = false);
+            break;
+        }
+    }
+    mask
+}
+
+/// Compute the edits for renaming the symbol at `position` to `new_name`.
+pub fn rename(
+    snapshot: &ProjectSnapshot,
+    file: &str,
+    position: Position,
+    new_name: &str,
+) -> Result<Option<WorkspaceEdit>, String> {
+    let Some(symbol) = symbol_at(
+        snapshot,
+        file,
+        position.line as usize,
+        position.character as usize,
+    ) else {
+        return Ok(None);
+    };
+    if !is_valid_name(new_name) {
+        return Err(format!("\"{new_name}\" is not a valid M3L identifier"));
+    }
+    let target = key_of(&symbol);
+    if matches!(target, SymbolKey::Field(..)) && new_name.contains('.') {
+        return Err("Field names cannot contain '.'".into());
+    }
+
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+    for path in snapshot.paths() {
+        let Some(text) = snapshot.file(path) else {
+            continue;
+        };
+        let mut edits = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let mask = code_mask(line);
+            let chars: Vec<char> = line.chars().collect();
+            let mut seen: HashSet<(usize, usize)> = HashSet::new();
+            let mut col = 0;
+            while col < chars.len() {
+                let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+                if !is_ident(chars[col]) || !mask[col] {
+                    col += 1;
+                    continue;
+                }
+                let start = col;
+                while col < chars.len() && is_ident(chars[col]) {
+                    col += 1;
+                }
+                let start16 = utf16_col(line, start);
+                let Some(found) = symbol_at(snapshot, path, line_no, start16) else {
+                    continue;
+                };
+                if key_of(&found) != target {
+                    continue;
+                }
+                // A whole dotted word naming the element (e.g. `Order.Status`)
+                // is replaced at once; otherwise just this segment. Aliases
+                // (`@alias(Account)`) keep their own name.
+                let ident: String = chars[start..col].iter().collect();
+                let range = match (&found, word_at(line, start16)) {
+                    (Symbol::Element(el), Some(w)) if w.text == el.name() => (w.start, w.end),
+                    (Symbol::Element(el), _) if ident != el.name() => continue,
+                    _ => (start16, utf16_col(line, col)),
+                };
+                if seen.insert(range) {
+                    edits.push(TextEdit {
+                        range: Range::new(
+                            Position::new(line_no as u32, range.0 as u32),
+                            Position::new(line_no as u32, range.1 as u32),
+                        ),
+                        new_text: new_name.to_string(),
+                    });
+                }
+            }
+        }
+        if !edits.is_empty() {
+            if let Ok(uri) = Url::parse(path) {
+                changes.insert(uri, edits);
+            }
+        }
+    }
+
+    Ok(Some(WorkspaceEdit {
+        changes: Some(changes),
+        ..Default::default()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m3l_core::Project;
+
+    const BASE: &str =
+        "## User\n> User accounts\n- id: identifier @pk\n- name: string \"User name\"\n";
+    const BLOG: &str = "## Author : User\n- bio: text\n\n## Post\n- author_id: identifier @reference(User.id)\n- author_name: string @lookup(author_id.name)\n\n### Relations\n- >author\n  - target: User\n\n## UserPosts ::view\n### Source\n- from: User\n";
+
+    fn apply(text: &str, edits: &[TextEdit]) -> String {
+        let mut lines: Vec<String> = text.lines().map(String::from).collect();
+        let mut edits = edits.to_vec();
+        edits.sort_by_key(|e| std::cmp::Reverse((e.range.start.line, e.range.start.character)));
+        for e in edits {
+            let line = &mut lines[e.range.start.line as usize];
+            let units: Vec<u16> = line.encode_utf16().collect();
+            let before = String::from_utf16(&units[..e.range.start.character as usize]).unwrap();
+            let after = String::from_utf16(&units[e.range.end.character as usize..]).unwrap();
+            *line = format!("{before}{}{after}", e.new_text);
+        }
+        lines.join("\n") + "\n"
+    }
+
+    fn rename_in(line: u32, col: u32, file: &str, new_name: &str) -> (String, String) {
+        let mut project = Project::default();
+        project.set_file("file:///base.m3l.md", BASE);
+        project.set_file("file:///blog.m3l.md", BLOG);
+        let snap = project.snapshot();
+        let edit = rename(&snap, file, Position::new(line, col), new_name)
+            .unwrap()
+            .unwrap();
+        let changes = edit.changes.unwrap();
+        let get = |uri: &str, text: &str| {
+            changes
+                .get(&Url::parse(uri).unwrap())
+                .map(|e| apply(text, e))
+                .unwrap_or_else(|| text.to_string())
+        };
+        (
+            get("file:///base.m3l.md", BASE),
+            get("file:///blog.m3l.md", BLOG),
+        )
+    }
+
+    #[test]
+    fn rename_model_updates_all_references() {
+        let (base, blog) = rename_in(0, 4, "file:///base.m3l.md", "Account");
+        assert!(base.starts_with("## Account\n"));
+        // Prose is untouched
+        assert!(base.contains("> User accounts"));
+        assert!(base.contains("\"User name\""));
+        assert!(blog.contains("## Author : Account"));
+        assert!(blog.contains("@reference(Account.id)"));
+        assert!(blog.contains("  - target: Account"));
+        assert!(blog.contains("- from: Account"));
+        // Different symbol sharing a prefix is untouched
+        assert!(blog.contains("## UserPosts ::view"));
+    }
+
+    #[test]
+    fn rename_field_updates_reference_and_lookup_paths() {
+        let (base, blog) = rename_in(3, 3, "file:///base.m3l.md", "full_name");
+        assert!(base.contains("- full_name: string"));
+        assert!(blog.contains("@lookup(author_id.full_name)"));
+
+        let (_, blog) = rename_in(4, 40, "file:///blog.m3l.md", "key");
+        assert!(blog.contains("@reference(User.key)"));
+    }
+
+    #[test]
+    fn rename_rejects_invalid_names() {
+        let mut project = Project::default();
+        project.set_file("file:///base.m3l.md", BASE);
+        let snap = project.snapshot();
+        assert!(rename(
+            &snap,
+            "file:///base.m3l.md",
+            Position::new(0, 4),
+            "Bad Name"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rename_with_non_ascii_identifiers() {
+        let text = "## Café\n- id: identifier @pk\n\n## 𝑥Order\n- 𝑥_café_id: identifier @reference(Café.id)\n";
+        let mut project = Project::default();
+        project.set_file("file:///shop.m3l.md", text);
+        let snap = project.snapshot();
+        // UTF-16 column of `Café` after the two-unit `𝑥`
+        let line = text.lines().nth(4).unwrap();
+        let col = line[..line.find("Café.").unwrap()].encode_utf16().count() as u32;
+        let edit = rename(
+            &snap,
+            "file:///shop.m3l.md",
+            Position::new(4, col + 1),
+            "Bistro",
+        )
+        .unwrap()
+        .unwrap();
+        let changes = edit.changes.unwrap();
+        let renamed = apply(text, &changes[&Url::parse("file:///shop.m3l.md").unwrap()]);
+        assert!(renamed.starts_with("## Bistro\n"));
+        assert!(renamed.contains("- 𝑥_café_id: identifier @reference(Bistro.id)"));
+    }
+
+    #[test]
+    fn rename_namespaced_model() {
+        let auth = "# Namespace: auth\n## User\n- id: identifier @pk\n";
+        let shop = "# Namespace: shop\n## User\n- id: identifier @pk\n\n## Order\n- buyer: auth.User\n- seller_id: identifier @reference(User.id)\n";
+        let mut project = Project::default();
+        project.set_file("file:///auth.m3l.md", auth);
+        project.set_file("file:///shop.m3l.md", shop);
+        let snap = project.snapshot();
+        let edit = rename(
+            &snap,
+            "file:///shop.m3l.md",
+            Position::new(5, 14),
+            "Account",
+        )
+        .unwrap()
+        .unwrap();
+        let changes = edit.changes.unwrap();
+        let renamed = |uri: &str, text: &str| apply(text, &changes[&Url::parse(uri).unwrap()]);
+        assert!(renamed("file:///auth.m3l.md", auth).contains("## Account\n"));
+        let shop = renamed("file:///shop.m3l.md", shop);
+        assert!(shop.contains("- buyer: auth.Account\n"));
+        // The shop namespace's own User is a different model
+        assert!(shop.contains("## User\n"));
+        assert!(shop.contains("@reference(User.id)"));
+    }
+
+    #[test]
+    fn rename_keeps_aliases() {
+        let text = "## User @alias(Account)\n- id: identifier @pk\n\n## Order\n- owner_id: identifier @reference(Account.id)\n- buyer_id: identifier @reference(User.id)\n";
+        let mut project = Project::default();
+        project.set_file("file:///shop.m3l.md", text);
+        let snap = project.snapshot();
+        let edit = rename(&snap, "file:///shop.m3l.md", Position::new(0, 4), "Person")
+            .unwrap()
+            .unwrap();
+        let changes = edit.changes.unwrap();
+        let renamed = apply(text, &changes[&Url::parse("file:///shop.m3l.md").unwrap()]);
+        assert!(renamed.starts_with("## Person @alias(Account)\n"));
+        assert!(renamed.contains("@reference(Account.id)"));
+        assert!(renamed.contains("@reference(Person.id)"));
+    }
+}
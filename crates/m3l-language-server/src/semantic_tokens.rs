@@ -0,0 +1,529 @@
+//! textDocument/semanticTokens/full
+//!
+//! Lines are classified by the core lexer; each line is then scanned for the
+//! spans worth colouring (names, types, attributes, defaults, descriptions).
+//! Columns are reported in UTF-16 code units, so Korean or other non-ASCII
+//! descriptions do not shift the tokens that follow them.
+
+use lsp_types::{
+    SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens, SemanticTokensLegend,
+};
+use m3l_core::{lex, Token, TokenType};
+
+/// Token types in legend order; the index is the encoded type.
+const TOKEN_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::CLASS,
+    SemanticTokenType::ENUM,
+    SemanticTokenType::INTERFACE,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::DECORATOR,
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::STRING,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::COMMENT,
+];
+
+const TOKEN_MODIFIERS: &[SemanticTokenModifier] = &[SemanticTokenModifier::DECLARATION];
+
+const DECLARATION: u32 = 1;
+
+/// Legend advertised in the server capabilities.
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: TOKEN_MODIFIERS.to_vec(),
+    }
+}
+
+fn type_index(ty: &SemanticTokenType) -> u32 {
+    TOKEN_TYPES.iter().position(|t| t == ty).unwrap_or(0) as u32
+}
+
+/// An unencoded token: 0-based line, char column and length.
+#[derive(Debug, Clone, PartialEq)]
+struct Span {
+    line: usize,
+    start: usize,
+    len: usize,
+    ty: SemanticTokenType,
+    modifiers: u32,
+}
+
+/// What the list items of the current block describe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Block {
+    None,
+    Fields,
+    EnumValues,
+    /// Key/value items of a section such as Indexes or Relations
+    Section,
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+struct LineScanner<'a> {
+    chars: &'a [char],
+    line: usize,
+    pos: usize,
+    spans: Vec<Span>,
+}
+
+impl<'a> LineScanner<'a> {
+    fn new(chars: &'a [char], line: usize, pos: usize) -> Self {
+        Self {
+            chars,
+            line,
+            pos,
+            spans: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn push(&mut self, start: usize, end: usize, ty: SemanticTokenType, modifiers: u32) {
+        if end > start {
+            self.spans.push(Span {
+                line: self.line,
+                start,
+                len: end - start,
+                ty,
+                modifiers,
+            });
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consume an identifier, optionally dotted. Returns its start and end.
+    fn ident(&mut self, dotted: bool) -> Option<(usize, usize)> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| is_ident(c) || (dotted && c == '.'))
+        {
+            self.pos += 1;
+        }
+        (self.pos > start).then_some((start, self.pos))
+    }
+
+    /// Skip a balanced `(...)` group, emitting strings and numbers inside it.
+    fn group(&mut self) {
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            match c {
+                '(' => {
+                    depth += 1;
+                    self.pos += 1;
+                }
+                ')' => {
+                    self.pos += 1;
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                '"' | '\'' => self.string(c),
+                c if c.is_ascii_digit() => self.number(),
+                c if is_ident(c) => {
+                    self.ident(true);
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn string(&mut self, quote: char) {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            if c == '\\' {
+                self.pos += 1;
+            } else if c == quote {
+                break;
+            }
+        }
+        self.pos = self.pos.min(self.chars.len());
+        self.push(start, self.pos, SemanticTokenType::STRING, 0);
+    }
+
+    fn number(&mut self) {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || c == '.' || c == '-')
+        {
+            self.pos += 1;
+        }
+        self.push(start, self.pos, SemanticTokenType::NUMBER, 0);
+    }
+
+    fn attribute(&mut self) {
+        let start = self.pos;
+        self.pos += 1;
+        self.ident(false);
+        self.push(start, self.pos, SemanticTokenType::DECORATOR, 0);
+        if self.peek() == Some('(') {
+            self.group();
+        }
+    }
+
+    /// Whether a value region (type list or default) ends at the cursor.
+    fn at_boundary(&self) -> bool {
+        let prev_ws = self.pos > 0 && self.chars[self.pos - 1].is_whitespace();
+        match self.peek() {
+            None | Some('@') | Some('"') | Some('`') | Some('=') => true,
+            Some('#') => prev_ws,
+            _ => false,
+        }
+    }
+
+    /// Default expression after `=`, up to the next attribute, description,
+    /// or comment.
+    fn default_value(&mut self) {
+        self.skip_ws();
+        match self.peek() {
+            Some(q @ ('"' | '\'')) => return self.string(q),
+            Some(c) if c.is_ascii_digit() || c == '-' => return self.number(),
+            _ => {}
+        }
+        let start = self.pos;
+        while !self.at_boundary() {
+            if self.peek() == Some('(') {
+                self.group();
+            } else {
+                self.pos += 1;
+            }
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        let text = text.trim_end();
+        let end = start + text.chars().count();
+        let ty = if matches!(text, "true" | "false" | "null") {
+            SemanticTokenType::KEYWORD
+        } else if text.contains('(') {
+            SemanticTokenType::FUNCTION
+        } else {
+            SemanticTokenType::VARIABLE
+        };
+        self.push(start, end, ty, 0);
+    }
+
+    /// Everything after a name: labels, `::kind`, types, defaults,
+    /// attributes, descriptions, and comments. Identifiers after `:` are
+    /// typed when `types` is set (field types, header parents).
+    fn rest(&mut self, types: bool) {
+        let mut in_type = false;
+        while let Some(c) = self.peek() {
+            match c {
+                ':' if self.chars.get(self.pos + 1) == Some(&':') => {
+                    let start = self.pos;
+                    self.pos += 2;
+                    self.ident(false);
+                    self.push(start, self.pos, SemanticTokenType::KEYWORD, 0);
+                }
+                ':' => {
+                    self.pos += 1;
+                    in_type = types;
+                }
+                '=' => {
+                    self.pos += 1;
+                    in_type = false;
+                    self.default_value();
+                }
+                '@' => {
+                    in_type = false;
+                    self.attribute();
+                }
+                '"' => {
+                    in_type = false;
+                    self.string('"');
+                }
+                '`' => {
+                    in_type = false;
+                    let start = self.pos;
+                    self.pos += 1;
+                    while self.peek().is_some_and(|c| c != '`') {
+                        self.pos += 1;
+                    }
+                    self.pos = (self.pos + 1).min(self.chars.len());
+                    self.push(start, self.pos, SemanticTokenType::DECORATOR, 0);
+                }
+                '#' if self.pos > 0 && self.chars[self.pos - 1].is_whitespace() => {
+                    let start = self.pos;
+                    self.pos = self.chars.len();
+                    self.push(start, self.pos, SemanticTokenType::COMMENT, 0);
+                }
+                '(' if !in_type => self.group(),
+                c if c.is_ascii_digit() => self.number(),
+                c if is_ident(c) => {
+                    let (start, end) = self.ident(true).unwrap_or_default();
+                    if in_type {
+                        self.push(start, end, SemanticTokenType::TYPE, 0);
+                    }
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+}
+
+fn header_spans(token: &Token, chars: &[char], line: usize) -> Vec<Span> {
+    let hashes = chars.iter().take_while(|&&c| c == '#').count();
+    let mut s = LineScanner::new(chars, line, hashes);
+    s.skip_ws();
+    match token.token_type {
+        TokenType::Namespace => {
+            // `# Namespace: name`
+            s.ident(false);
+            s.pos += 1;
+            s.skip_ws();
+            let start = s.pos;
+            s.pos = chars.len();
+            s.push(start, s.pos, SemanticTokenType::NAMESPACE, DECLARATION);
+        }
+        TokenType::Section => {
+            let start = s.pos;
+            s.pos = chars.len();
+            s.push(start, s.pos, SemanticTokenType::KEYWORD, 0);
+        }
+        _ => {
+            let ty = match token.token_type {
+                TokenType::Enum => SemanticTokenType::ENUM,
+                TokenType::Interface => SemanticTokenType::INTERFACE,
+                TokenType::AttributeDef => SemanticTokenType::DECORATOR,
+                _ => SemanticTokenType::CLASS,
+            };
+            let start = s.pos;
+            if s.peek() == Some('@') {
+                s.pos += 1;
+            }
+            if s.ident(true).is_some() {
+                s.push(start, s.pos, ty, DECLARATION);
+            }
+            s.rest(true);
+        }
+    }
+    s.spans
+}
+
+fn item_spans(chars: &[char], line: usize, block: Block) -> Vec<Span> {
+    let dash = chars.iter().position(|&c| c == '-').unwrap_or(0);
+    let mut s = LineScanner::new(chars, line, dash + 1);
+    s.skip_ws();
+    // Relation items are written `- >name` / `- <name`
+    while matches!(s.peek(), Some('>' | '<')) {
+        s.pos += 1;
+    }
+    let (ty, modifiers) = match block {
+        Block::EnumValues => (SemanticTokenType::ENUM_MEMBER, DECLARATION),
+        Block::Section => (SemanticTokenType::PROPERTY, 0),
+        _ => (SemanticTokenType::PROPERTY, DECLARATION),
+    };
+    if let Some((start, end)) = s.ident(false) {
+        s.push(start, end, ty, modifiers);
+    }
+    s.rest(block != Block::Section);
+    s.spans
+}
+
+fn encode(spans: &[Span], lines: &[Vec<char>]) -> Vec<SemanticToken> {
+    let utf16 = |line: usize, col: usize| -> u32 {
+        lines
+            .get(line)
+            .map(|l| l.iter().take(col).map(|c| c.len_utf16() as u32).sum())
+            .unwrap_or(col as u32)
+    };
+    let mut out = Vec::with_capacity(spans.len());
+    let (mut prev_line, mut prev_start) = (0usize, 0u32);
+    for span in spans {
+        let start = utf16(span.line, span.start);
+        let end = utf16(span.line, span.start + span.len);
+        let delta_line = (span.line - prev_line) as u32;
+        let delta_start = if delta_line == 0 {
+            start - prev_start
+        } else {
+            start
+        };
+        out.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: end - start,
+            token_type: type_index(&span.ty),
+            token_modifiers_bitset: span.modifiers,
+        });
+        prev_line = span.line;
+        prev_start = start;
+    }
+    out
+}
+
+/// Semantic tokens for the whole of `text`.
+pub fn semantic_tokens(text: &str, file: &str) -> SemanticTokens {
+    let lines: Vec<Vec<char>> = text
+        .split('\n')
+        .map(|l| l.strip_suffix('\r').unwrap_or(l).chars().collect())
+        .collect();
+    let mut spans = Vec::new();
+    let mut block = Block::None;
+
+    for token in lex(text, file) {
+        let line = token.line - 1;
+        let Some(chars) = lines.get(line) else {
+            continue;
+        };
+        match token.token_type {
+            TokenType::Namespace => spans.extend(header_spans(&token, chars, line)),
+            TokenType::Enum => {
+                block = Block::EnumValues;
+                spans.extend(header_spans(&token, chars, line));
+            }
+            TokenType::Model
+            | TokenType::Interface
+            | TokenType::View
+            | TokenType::Flow
+            | TokenType::Extension(_)
+            | TokenType::AttributeDef => {
+                block = Block::Fields;
+                spans.extend(header_spans(&token, chars, line));
+            }
+            TokenType::Section => {
+                if block != Block::None {
+                    block = if token.data.kind_section {
+                        Block::Fields
+                    } else {
+                        Block::Section
+                    };
+                }
+                spans.extend(header_spans(&token, chars, line));
+            }
+            TokenType::Field | TokenType::NestedItem if block != Block::None => {
+                spans.extend(item_spans(chars, line, block));
+            }
+            _ => {}
+        }
+    }
+
+    SemanticTokens {
+        result_id: None,
+        data: encode(&spans, &lines),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode tokens back into `(line, col, text, type)` tuples.
+    fn decode(text: &str) -> Vec<(u32, u32, String, SemanticTokenType)> {
+        let lines: Vec<Vec<u16>> = text.lines().map(|l| l.encode_utf16().collect()).collect();
+        let mut out = Vec::new();
+        let (mut line, mut col) = (0u32, 0u32);
+        for t in semantic_tokens(text, "test.m3l.md").data {
+            if t.delta_line > 0 {
+                line += t.delta_line;
+                col = t.delta_start;
+            } else {
+                col += t.delta_start;
+            }
+            let units = &lines[line as usize][col as usize..(col + t.length) as usize];
+            out.push((
+                line,
+                col,
+                String::from_utf16_lossy(units),
+                TOKEN_TYPES[t.token_type as usize].clone(),
+            ));
+        }
+        out
+    }
+
+    fn texts_of(
+        tokens: &[(u32, u32, String, SemanticTokenType)],
+        ty: SemanticTokenType,
+    ) -> Vec<&str> {
+        tokens
+            .iter()
+            .filter(|t| t.3 == ty)
+            .map(|t| t.2.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn header_tokens() {
+        let tokens = decode("# Namespace: shop\n\n## Order : Base, Auditable @table(\"orders\")\n");
+        assert_eq!(texts_of(&tokens, SemanticTokenType::NAMESPACE), ["shop"]);
+        assert_eq!(texts_of(&tokens, SemanticTokenType::CLASS), ["Order"]);
+        assert_eq!(
+            texts_of(&tokens, SemanticTokenType::TYPE),
+            ["Base", "Auditable"]
+        );
+        assert_eq!(texts_of(&tokens, SemanticTokenType::DECORATOR), ["@table"]);
+        assert_eq!(texts_of(&tokens, SemanticTokenType::STRING), ["\"orders\""]);
+    }
+
+    #[test]
+    fn field_tokens() {
+        let tokens = decode(
+            "## Order\n- total: decimal(10, 2)? = 0 @min(0) \"Order total\" # cents\n- placed_at: timestamp = now()\n",
+        );
+        assert_eq!(
+            texts_of(&tokens, SemanticTokenType::PROPERTY),
+            ["total", "placed_at"]
+        );
+        assert_eq!(
+            texts_of(&tokens, SemanticTokenType::TYPE),
+            ["decimal", "timestamp"]
+        );
+        assert_eq!(
+            texts_of(&tokens, SemanticTokenType::NUMBER),
+            ["10", "2", "0", "0"]
+        );
+        assert_eq!(texts_of(&tokens, SemanticTokenType::DECORATOR), ["@min"]);
+        assert_eq!(texts_of(&tokens, SemanticTokenType::COMMENT), ["# cents"]);
+        assert_eq!(texts_of(&tokens, SemanticTokenType::FUNCTION), ["now()"]);
+    }
+
+    #[test]
+    fn enum_values_and_sections() {
+        let tokens = decode(
+            "## Status ::enum\n- active: \"Active\"\n\n## User\n- id: identifier\n\n### Indexes\n- by_email\n  - fields: [email]\n",
+        );
+        assert_eq!(texts_of(&tokens, SemanticTokenType::ENUM), ["Status"]);
+        assert_eq!(
+            texts_of(&tokens, SemanticTokenType::KEYWORD),
+            ["::enum", "Indexes"]
+        );
+        assert_eq!(
+            texts_of(&tokens, SemanticTokenType::ENUM_MEMBER),
+            ["active"]
+        );
+        // Section items are keys, not fields with types
+        assert_eq!(texts_of(&tokens, SemanticTokenType::TYPE), ["identifier"]);
+        assert_eq!(
+            texts_of(&tokens, SemanticTokenType::PROPERTY),
+            ["id", "by_email", "fields"]
+        );
+    }
+
+    #[test]
+    fn columns_are_utf16() {
+        let tokens = decode("## User\n- name: string \"이름 😀\" @unique\n");
+        let attr = tokens
+            .iter()
+            .find(|t| t.3 == SemanticTokenType::DECORATOR)
+            .unwrap();
+        assert_eq!(attr.2, "@unique");
+    }
+}
@@ -0,0 +1,407 @@
+//! Server state and the LSP message loop.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{
+    CodeActionRequest, DocumentSymbolRequest, GotoDefinition, HoverRequest, Rename, Request as _,
+    SemanticTokensFullRequest, WorkspaceSymbolRequest,
+};
+use lsp_types::{
+    CodeActionKind, CodeActionOptions, CodeActionParams, CodeActionProviderCapability,
+    CodeActionResponse, Diagnostic, DiagnosticSeverity, DocumentSymbolParams,
+    DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverParams,
+    HoverProviderCapability, OneOf, Position, PublishDiagnosticsParams, Range, RenameParams,
+    SemanticTokensFullOptions, SemanticTokensOptions, SemanticTokensParams, SemanticTokensResult,
+    SemanticTokensServerCapabilities, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, Url, WorkspaceEdit, WorkspaceSymbolParams, WorkspaceSymbolResponse,
+};
+use m3l_core::{is_m3l_path, Project};
+
+use crate::document::{line_at, utf16_col};
+
+pub type ServerResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Directories never scanned for workspace files.
+const SKIP_DIRS: &[&str] = &[".git", "node_modules", "target", "bin", "obj"];
+
+/// Capabilities advertised in the initialize response.
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        rename_provider: Some(OneOf::Left(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+            code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+            ..Default::default()
+        })),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        workspace_symbol_provider: Some(OneOf::Left(true)),
+        semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(
+            SemanticTokensOptions {
+                legend: crate::semantic_tokens::legend(),
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..Default::default()
+            },
+        )),
+        ..Default::default()
+    }
+}
+
+/// Language server state. Files are keyed by their URI string, which is also
+/// the `source` recorded in the AST, so locations map straight back to URIs.
+pub struct Server {
+    project: Project,
+    /// Diagnostic count last published per file, to clear stale ones.
+    published: HashMap<String, usize>,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self {
+            project: Project::default(),
+            published: HashMap::new(),
+        }
+    }
+
+    pub fn project(&mut self) -> &mut Project {
+        &mut self.project
+    }
+
+    /// Load every M3L file below `root` into the project.
+    pub fn load_workspace(&mut self, root: &Path) {
+        let mut stack = vec![root.to_path_buf()];
+        while let Some(dir) = stack.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    let name = entry.file_name();
+                    if !SKIP_DIRS.contains(&name.to_string_lossy().as_ref()) {
+                        stack.push(path);
+                    }
+                } else if is_m3l_path(&path) {
+                    if let (Ok(uri), Ok(content)) =
+                        (Url::from_file_path(&path), std::fs::read_to_string(&path))
+                    {
+                        self.project.set_file(uri.as_str(), &content);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Run the message loop until the client asks to shut down.
+    pub fn main_loop(&mut self, connection: &Connection) -> ServerResult<()> {
+        self.publish_diagnostics(connection)?;
+        for msg in &connection.receiver {
+            match msg {
+                Message::Request(req) => {
+                    if connection.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+                    let response = self.handle_request(req);
+                    connection.sender.send(Message::Response(response))?;
+                }
+                Message::Notification(not) => {
+                    if self.handle_notification(not) {
+                        self.publish_diagnostics(connection)?;
+                    }
+                }
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_request(&mut self, req: Request) -> Response {
+        match req.method.as_str() {
+            GotoDefinition::METHOD => handle::<GotoDefinition>(req, |p| Ok(self.definition(p))),
+            HoverRequest::METHOD => handle::<HoverRequest>(req, |p| Ok(self.hover(p))),
+            Rename::METHOD => handle::<Rename>(req, |p| self.rename(p)),
+            CodeActionRequest::METHOD => {
+                handle::<CodeActionRequest>(req, |p| Ok(self.code_actions(p)))
+            }
+            DocumentSymbolRequest::METHOD => {
+                handle::<DocumentSymbolRequest>(req, |p| Ok(self.document_symbols(p)))
+            }
+            SemanticTokensFullRequest::METHOD => {
+                handle::<SemanticTokensFullRequest>(req, |p| Ok(self.semantic_tokens(p)))
+            }
+            WorkspaceSymbolRequest::METHOD => {
+                handle::<WorkspaceSymbolRequest>(req, |p| Ok(self.workspace_symbols(p)))
+            }
+            _ => Response::new_err(
+                req.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unhandled method: {}", req.method),
+            ),
+        }
+    }
+
+    /// Apply a notification. Returns whether the project content changed.
+    pub fn handle_notification(&mut self, not: Notification) -> bool {
+        match not.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Ok(p) =
+                    serde_json::from_value::<lsp_types::DidOpenTextDocumentParams>(not.params)
+                else {
+                    return false;
+                };
+                self.project
+                    .set_file(p.text_document.uri.as_str(), &p.text_document.text)
+            }
+            DidChangeTextDocument::METHOD => {
+                let Ok(p) =
+                    serde_json::from_value::<lsp_types::DidChangeTextDocumentParams>(not.params)
+                else {
+                    return false;
+                };
+                // Full sync: the last change carries the whole document
+                match p.content_changes.into_iter().last() {
+                    Some(change) => self
+                        .project
+                        .set_file(p.text_document.uri.as_str(), &change.text),
+                    None => false,
+                }
+            }
+            DidSaveTextDocument::METHOD => false,
+            DidCloseTextDocument::METHOD => {
+                let Ok(p) =
+                    serde_json::from_value::<lsp_types::DidCloseTextDocumentParams>(not.params)
+                else {
+                    return false;
+                };
+                // Fall back to the on-disk content, or drop unsaved buffers
+                let uri = p.text_document.uri;
+                match uri
+                    .to_file_path()
+                    .ok()
+                    .and_then(|path| std::fs::read_to_string(path).ok())
+                {
+                    Some(content) => self.project.set_file(uri.as_str(), &content),
+                    None => self.project.remove_file(uri.as_str()),
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn definition(&mut self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let snapshot = self.project.snapshot();
+        let pos = params.text_document_position_params;
+        crate::definition::definition(&snapshot, pos.text_document.uri.as_str(), pos.position)
+            .map(GotoDefinitionResponse::Scalar)
+    }
+
+    fn hover(&mut self, params: HoverParams) -> Option<Hover> {
+        let snapshot = self.project.snapshot();
+        let pos = params.text_document_position_params;
+        crate::hover::hover(&snapshot, pos.text_document.uri.as_str(), pos.position)
+    }
+
+    fn rename(&mut self, params: RenameParams) -> Result<Option<WorkspaceEdit>, String> {
+        let snapshot = self.project.snapshot();
+        let pos = params.text_document_position;
+        crate::rename::rename(
+            &snapshot,
+            pos.text_document.uri.as_str(),
+            pos.position,
+            &params.new_name,
+        )
+    }
+
+    fn code_actions(&mut self, params: CodeActionParams) -> Option<CodeActionResponse> {
+        let snapshot = self.project.snapshot();
+        Some(crate::code_actions::code_actions(
+            &snapshot,
+            params.text_document.uri.as_str(),
+            params.range,
+        ))
+    }
+
+    fn document_symbols(&mut self, params: DocumentSymbolParams) -> Option<DocumentSymbolResponse> {
+        let snapshot = self.project.snapshot();
+        let symbols =
+            crate::symbols::document_symbols(&snapshot, params.text_document.uri.as_str());
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
+
+    fn workspace_symbols(
+        &mut self,
+        params: WorkspaceSymbolParams,
+    ) -> Option<WorkspaceSymbolResponse> {
+        let snapshot = self.project.snapshot();
+        let symbols = crate::symbols::workspace_symbols(&snapshot, &params.query);
+        Some(WorkspaceSymbolResponse::Flat(symbols))
+    }
+
+    fn semantic_tokens(&mut self, params: SemanticTokensParams) -> Option<SemanticTokensResult> {
+        let uri = params.text_document.uri;
+        let text = self.project.file(uri.as_str())?;
+        Some(SemanticTokensResult::Tokens(
+            crate::semantic_tokens::semantic_tokens(text, uri.as_str()),
+        ))
+    }
+
+    fn publish_diagnostics(&mut self, connection: &Connection) -> ServerResult<()> {
+        let snapshot = self.project.snapshot();
+        let mut by_file: HashMap<String, Vec<Diagnostic>> = HashMap::new();
+        for path in snapshot.paths() {
+            by_file.entry(path.to_string()).or_default();
+        }
+        for d in snapshot
+            .validation
+            .errors
+            .iter()
+            .chain(snapshot.validation.warnings.iter())
+        {
+            let line0 = d.line.saturating_sub(1);
+            // Core columns count characters
+            let col = snapshot
+                .file(&d.file)
+                .and_then(|text| line_at(text, line0))
+                .map_or(d.col.saturating_sub(1), |text| {
+                    utf16_col(text, d.col.saturating_sub(1))
+                }) as u32;
+            let line = line0 as u32;
+            by_file.entry(d.file.clone()).or_default().push(Diagnostic {
+                range: Range::new(Position::new(line, col), Position::new(line, u32::MAX)),
+                severity: Some(match d.severity {
+                    m3l_core::DiagnosticSeverity::Error => DiagnosticSeverity::ERROR,
+                    m3l_core::DiagnosticSeverity::Warning => DiagnosticSeverity::WARNING,
+                }),
+                code: Some(lsp_types::NumberOrString::String(d.code.clone())),
+                source: Some("m3l".into()),
+                message: d.message.clone(),
+                ..Default::default()
+            });
+        }
+
+        for d in crate::code_actions::lint(&snapshot) {
+            let diagnostic = crate::code_actions::to_lsp_diagnostic(&snapshot, &d);
+            by_file.entry(d.file).or_default().push(diagnostic);
+        }
+
+        // Files that had diagnostics before but are gone now get an empty list
+        for (file, count) in &self.published {
+            if *count > 0 {
+                by_file.entry(file.clone()).or_default();
+            }
+        }
+
+        let mut published = HashMap::new();
+        for (file, diagnostics) in by_file {
+            let Ok(uri) = Url::parse(&file) else {
+                continue;
+            };
+            if diagnostics.is_empty() && self.published.get(&file).copied().unwrap_or(0) == 0 {
+                published.insert(file, 0);
+                continue;
+            }
+            published.insert(file, diagnostics.len());
+            let params = PublishDiagnosticsParams {
+                uri,
+                diagnostics,
+                version: None,
+            };
+            connection
+                .sender
+                .send(Message::Notification(Notification::new(
+                    PublishDiagnostics::METHOD.to_string(),
+                    params,
+                )))?;
+        }
+        self.published = published;
+        Ok(())
+    }
+}
+
+/// Decode request params, run `f`, and wrap its result in a response.
+fn handle<R>(req: Request, f: impl FnOnce(R::Params) -> Result<R::Result, String>) -> Response
+where
+    R: lsp_types::request::Request,
+{
+    let id: RequestId = req.id.clone();
+    match req.extract::<R::Params>(R::METHOD) {
+        Ok((id, params)) => match f(params) {
+            Ok(result) => Response::new_ok(id, result),
+            Err(e) => Response::new_err(id, ErrorCode::RequestFailed as i32, e),
+        },
+        Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn definition_request_round_trip() {
+        let mut server = Server::new();
+        server.project().set_file(
+            "file:///a.m3l.md",
+            "## User\n- id: identifier @pk\n\n## Post\n- author_id: identifier @reference(User)\n",
+        );
+        let req = Request::new(
+            RequestId::from(1),
+            GotoDefinition::METHOD.to_string(),
+            serde_json::json!({
+                "textDocument": { "uri": "file:///a.m3l.md" },
+                "position": { "line": 4, "character": 36 }
+            }),
+        );
+        let resp = server.handle_request(req);
+        assert!(resp.error.is_none());
+        let loc: lsp_types::Location = serde_json::from_value(resp.result.unwrap()).unwrap();
+        assert_eq!(loc.range.start, Position::new(0, 3));
+    }
+
+    #[test]
+    fn unknown_method_is_rejected() {
+        let mut server = Server::new();
+        let req = Request::new(
+            RequestId::from(1),
+            "m3l/unknown".into(),
+            serde_json::json!({}),
+        );
+        let resp = server.handle_request(req);
+        assert_eq!(resp.error.unwrap().code, ErrorCode::MethodNotFound as i32);
+    }
+
+    #[test]
+    fn did_change_updates_project() {
+        let mut server = Server::new();
+        let open = Notification::new(
+            DidOpenTextDocument::METHOD.into(),
+            serde_json::json!({
+                "textDocument": { "uri": "file:///a.m3l.md", "languageId": "markdown", "version": 1, "text": "## A\n- id: identifier" }
+            }),
+        );
+        assert!(server.handle_notification(open));
+        let change = Notification::new(
+            DidChangeTextDocument::METHOD.into(),
+            serde_json::json!({
+                "textDocument": { "uri": "file:///a.m3l.md", "version": 2 },
+                "contentChanges": [{ "text": "## B\n- id: identifier" }]
+            }),
+        );
+        assert!(server.handle_notification(change));
+        assert_eq!(server.project().snapshot().ast.models[0].name, "B");
+    }
+}
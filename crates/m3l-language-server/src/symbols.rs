@@ -0,0 +1,315 @@
+//! textDocument/documentSymbol and workspace/symbol
+//!
+//! The outline comes from the resolved AST; section headers and enum values
+//! carry no line numbers there, so they are located in the element's source
+//! text instead.
+
+use lsp_types::{DocumentSymbol, Location, Position, Range, SymbolInformation, SymbolKind, Url};
+use m3l_core::{fuzzy_score, FieldNode, ModelType, ProjectSnapshot};
+
+use crate::document::name_column;
+use crate::navigation::{all_models, Element};
+
+/// Maximum number of results returned for a workspace symbol query.
+const MAX_WORKSPACE_SYMBOLS: usize = 256;
+
+fn element_kind(el: &Element) -> SymbolKind {
+    match el {
+        Element::Enum(_) => SymbolKind::ENUM,
+        Element::Model(m) => match m.model_type {
+            ModelType::Interface => SymbolKind::INTERFACE,
+            ModelType::View => SymbolKind::CLASS,
+            ModelType::Flow => SymbolKind::EVENT,
+            _ => SymbolKind::STRUCT,
+        },
+    }
+}
+
+/// Range covering `name` on the 0-based `line`, or the line start if absent.
+fn name_range(lines: &[&str], line: usize, name: &str) -> Range {
+    let col = lines
+        .get(line)
+        .and_then(|l| name_column(l, name))
+        .unwrap_or(0);
+    let len = name.chars().count();
+    Range::new(
+        Position::new(line as u32, col as u32),
+        Position::new(line as u32, (col + len) as u32),
+    )
+}
+
+fn line_range(lines: &[&str], start: usize, end: usize) -> Range {
+    let end_col = lines.get(end).map(|l| l.chars().count()).unwrap_or(0);
+    Range::new(
+        Position::new(start as u32, 0),
+        Position::new(end as u32, end_col as u32),
+    )
+}
+
+#[allow(deprecated)]
+fn symbol(
+    name: String,
+    detail: Option<String>,
+    kind: SymbolKind,
+    range: Range,
+    selection_range: Range,
+    children: Vec<DocumentSymbol>,
+) -> DocumentSymbol {
+    DocumentSymbol {
+        name,
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range,
+        children: if children.is_empty() {
+            None
+        } else {
+            Some(children)
+        },
+    }
+}
+
+/// Fields declared in `file` within lines `start..=end` (0-based), nested
+/// fields included as children. Inherited fields are left out.
+fn field_symbols(
+    fields: &[FieldNode],
+    file: &str,
+    lines: &[&str],
+    start: usize,
+    end: usize,
+) -> Vec<DocumentSymbol> {
+    fields
+        .iter()
+        .filter(|f| f.loc.file == file && (start + 1..=end + 1).contains(&f.loc.line))
+        .map(|f| {
+            let line = f.loc.line - 1;
+            let children = f
+                .fields
+                .as_deref()
+                .map(|sub| field_symbols(sub, file, lines, line, end))
+                .unwrap_or_default();
+            let last = children
+                .last()
+                .map(|c| c.range.end.line as usize)
+                .unwrap_or(line);
+            symbol(
+                f.name.clone(),
+                Some(crate::hover::type_signature(f)).filter(|s| !s.is_empty()),
+                SymbolKind::FIELD,
+                line_range(lines, line, last),
+                name_range(lines, line, &f.name),
+                children,
+            )
+        })
+        .collect()
+}
+
+/// `### Section` headers within lines `start..=end` (0-based).
+fn section_symbols(lines: &[&str], start: usize, end: usize) -> Vec<DocumentSymbol> {
+    let headers: Vec<(usize, &str)> = (start..=end)
+        .filter_map(|i| {
+            let name = lines.get(i)?.trim().strip_prefix("### ")?.trim();
+            Some((i, name))
+        })
+        .collect();
+    headers
+        .iter()
+        .enumerate()
+        .map(|(idx, &(line, name))| {
+            let last = headers.get(idx + 1).map(|&(l, _)| l - 1).unwrap_or(end);
+            symbol(
+                name.to_string(),
+                None,
+                SymbolKind::NAMESPACE,
+                line_range(lines, line, last),
+                name_range(lines, line, name),
+                vec![],
+            )
+        })
+        .collect()
+}
+
+/// Outline of `file`: every element with its fields, enum values, and sections.
+pub fn document_symbols(snapshot: &ProjectSnapshot, file: &str) -> Vec<DocumentSymbol> {
+    let Some(text) = snapshot.file(file) else {
+        return vec![];
+    };
+    let lines: Vec<&str> = text.lines().collect();
+    let ast = &snapshot.ast;
+
+    let mut elements: Vec<Element> = all_models(ast)
+        .map(Element::Model)
+        .chain(ast.enums.iter().map(Element::Enum))
+        .filter(|el| el.file() == file)
+        .collect();
+    elements.sort_by_key(|el| el.line());
+
+    let mut out = Vec::new();
+    for (idx, el) in elements.iter().enumerate() {
+        let start = el.line().saturating_sub(1);
+        let mut end = elements
+            .get(idx + 1)
+            .map(|next| next.line().saturating_sub(2))
+            .unwrap_or(lines.len().saturating_sub(1))
+            .max(start);
+        while end > start && lines.get(end).is_some_and(|l| l.trim().is_empty()) {
+            end -= 1;
+        }
+
+        let (detail, children) = match el {
+            Element::Model(m) => {
+                let mut children = field_symbols(&m.fields, file, &lines, start, end);
+                children.extend(section_symbols(&lines, start, end));
+                children.sort_by_key(|c| c.range.start.line);
+                (m.label.clone(), children)
+            }
+            Element::Enum(e) => {
+                let children = e
+                    .values
+                    .iter()
+                    .filter_map(|v| {
+                        let line = (start + 1..=end).find(|&i| {
+                            lines[i]
+                                .trim_start()
+                                .strip_prefix("- ")
+                                .is_some_and(|rest| name_column(rest, &v.name) == Some(0))
+                        })?;
+                        let range = name_range(&lines, line, &v.name);
+                        Some(symbol(
+                            v.name.clone(),
+                            v.description.clone(),
+                            SymbolKind::ENUM_MEMBER,
+                            line_range(&lines, line, line),
+                            range,
+                            vec![],
+                        ))
+                    })
+                    .collect();
+                (e.label.clone(), children)
+            }
+        };
+
+        let short = el.name().rsplit('.').next().unwrap_or(el.name());
+        out.push(symbol(
+            el.name().to_string(),
+            detail,
+            element_kind(el),
+            line_range(&lines, start, end),
+            name_range(&lines, start, short),
+            children,
+        ));
+    }
+    out
+}
+
+/// Models, interfaces, views, flows, and enums across the workspace whose
+/// name fuzzily matches `query`, best matches first.
+#[allow(deprecated)]
+pub fn workspace_symbols(snapshot: &ProjectSnapshot, query: &str) -> Vec<SymbolInformation> {
+    let ast = &snapshot.ast;
+    let mut scored: Vec<(i64, Element)> = all_models(ast)
+        .map(Element::Model)
+        .chain(ast.enums.iter().map(Element::Enum))
+        .filter_map(|el| fuzzy_score(query, el.name()).map(|s| (s, el)))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name().cmp(b.1.name())));
+
+    scored
+        .into_iter()
+        .filter_map(|(_, el)| {
+            let uri = Url::parse(el.file()).ok()?;
+            let short = el.name().rsplit('.').next().unwrap_or(el.name());
+            let lines: Vec<&str> = snapshot
+                .file(el.file())
+                .map(|t| t.lines().collect())
+                .unwrap_or_default();
+            let container_name = match el {
+                Element::Model(m) => m.namespace.clone(),
+                Element::Enum(e) => e.namespace.clone(),
+            };
+            Some(SymbolInformation {
+                name: el.name().to_string(),
+                kind: element_kind(&el),
+                tags: None,
+                deprecated: None,
+                location: Location {
+                    uri,
+                    range: name_range(&lines, el.line().saturating_sub(1), short),
+                },
+                container_name,
+            })
+        })
+        .take(MAX_WORKSPACE_SYMBOLS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m3l_core::Project;
+
+    const SCHEMA: &str = "# Namespace: shop\n\n## Customer(Buyer)\n- id: identifier @pk\n- address: object\n  - city: string\n\n### Indexes\n- by_city\n  - fields: [address.city]\n\n## OrderStatus ::enum\n- pending: \"Waiting\"\n- shipped: \"Shipped\"\n";
+
+    fn snapshot(files: &[(&str, &str)]) -> std::sync::Arc<ProjectSnapshot> {
+        let mut project = Project::default();
+        for (path, content) in files {
+            project.set_file(path, content);
+        }
+        project.snapshot()
+    }
+
+    #[test]
+    fn outline_lists_elements_fields_and_sections() {
+        let snap = snapshot(&[("file:///a.m3l.md", SCHEMA)]);
+        let symbols = document_symbols(&snap, "file:///a.m3l.md");
+        assert_eq!(symbols.len(), 2);
+
+        let customer = &symbols[0];
+        assert_eq!(customer.name, "Customer");
+        assert_eq!(customer.kind, SymbolKind::STRUCT);
+        assert_eq!(customer.detail.as_deref(), Some("Buyer"));
+        assert_eq!(customer.selection_range.start, Position::new(2, 3));
+        assert_eq!(customer.range.end.line, 9);
+
+        let children = customer.children.as_ref().unwrap();
+        let names: Vec<&str> = children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "address", "Indexes"]);
+        let city = &children[1].children.as_ref().unwrap()[0];
+        assert_eq!(city.name, "city");
+        assert_eq!(city.selection_range.start, Position::new(5, 4));
+
+        let status = &symbols[1];
+        assert_eq!(status.kind, SymbolKind::ENUM);
+        let values = status.children.as_ref().unwrap();
+        assert_eq!(values[1].name, "shipped");
+        assert_eq!(values[1].range.start.line, 13);
+    }
+
+    #[test]
+    fn outline_skips_inherited_fields() {
+        let snap = snapshot(&[
+            ("file:///base.m3l.md", "## Base\n- id: identifier\n"),
+            ("file:///a.m3l.md", "## Child : Base\n- name: string\n"),
+        ]);
+        let symbols = document_symbols(&snap, "file:///a.m3l.md");
+        let children = symbols[0].children.as_ref().unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].name, "name");
+    }
+
+    #[test]
+    fn workspace_symbols_rank_best_match_first() {
+        let snap = snapshot(&[
+            ("file:///a.m3l.md", SCHEMA),
+            ("file:///b.m3l.md", "## Order\n- id: identifier\n"),
+        ]);
+        let results = workspace_symbols(&snap, "ord");
+        let names: Vec<&str> = results.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Order", "OrderStatus"]);
+        assert_eq!(results[1].container_name.as_deref(), Some("shop"));
+        assert_eq!(results[0].location.uri.as_str(), "file:///b.m3l.md");
+        assert_eq!(workspace_symbols(&snap, "").len(), 3);
+    }
+}
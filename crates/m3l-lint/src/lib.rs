@@ -34,6 +34,90 @@ pub struct LintDiagnostic {
     pub line: usize,
     pub col: usize,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<LintFix>,
+}
+
+// ---------------------------------------------------------------------------
+// Fixes
+// ---------------------------------------------------------------------------
+
+/// A replacement anchored to one source line.
+///
+/// `old_text` is matched as the first whole-word occurrence on the line, so
+/// the edit stays applicable without column information in the AST. An empty
+/// `old_text` appends `new_text` to the end of the line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixEdit {
+    pub file: String,
+    /// 1-based line number.
+    pub line: usize,
+    pub old_text: String,
+    pub new_text: String,
+}
+
+impl FixEdit {
+    /// Character columns `[start, end)` of the text this edit replaces on
+    /// `line_text`, or `None` if the anchor text is no longer there.
+    pub fn columns(&self, line_text: &str) -> Option<(usize, usize)> {
+        let chars: Vec<char> = line_text.chars().collect();
+        if self.old_text.is_empty() {
+            return Some((chars.len(), chars.len()));
+        }
+        let needle: Vec<char> = self.old_text.chars().collect();
+        let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+        (0..=chars.len().checked_sub(needle.len())?)
+            .find(|&i| {
+                chars[i..i + needle.len()] == needle[..]
+                    && (i == 0 || !is_word(&chars[i - 1]))
+                    && chars.get(i + needle.len()).is_none_or(|c| !is_word(c))
+            })
+            .map(|i| (i, i + needle.len()))
+    }
+}
+
+/// A machine-applicable fix for a lint diagnostic.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintFix {
+    /// Short imperative title, e.g. `Rename to "user_name"`.
+    pub title: String,
+    pub edits: Vec<FixEdit>,
+    /// The edits rename a declaration. Tools that track references (such as
+    /// the language server) should rename its uses as well.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_rename: bool,
+}
+
+impl LintFix {
+    /// Fix that renames the declaration `old` on `line` of `file` to `new`.
+    pub fn rename(file: &str, line: usize, old: &str, new: &str) -> Self {
+        Self {
+            title: format!("Rename to \"{new}\""),
+            edits: vec![FixEdit {
+                file: file.into(),
+                line,
+                old_text: old.into(),
+                new_text: new.into(),
+            }],
+            is_rename: true,
+        }
+    }
+
+    /// Apply the edits that target `file` to its `source` text.
+    ///
+    /// Returns `None` if any edit no longer matches the source.
+    pub fn apply(&self, file: &str, source: &str) -> Option<String> {
+        let mut lines: Vec<String> = source.split('\n').map(String::from).collect();
+        for edit in self.edits.iter().filter(|e| e.file == file) {
+            let line = lines.get_mut(edit.line.checked_sub(1)?)?;
+            let (start, end) = edit.columns(line)?;
+            let chars: Vec<char> = line.chars().collect();
+            let before: String = chars[..start].iter().collect();
+            let after: String = chars[end..].iter().collect();
+            *line = format!("{before}{}{after}", edit.new_text);
+        }
+        Some(lines.join("\n"))
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -68,7 +152,10 @@ pub enum RuleLevel {
     Error,
 }
 
+/// Unknown keys are rejected so a misspelled option fails loudly instead of
+/// being ignored.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct LintConfig {
     #[serde(default)]
     pub rules: HashMap<String, RuleLevel>,
@@ -146,6 +233,10 @@ fn builtin_rules() -> Vec<Box<dyn LintRule>> {
         Box::new(ModelSizeRule::default()),
         Box::new(SimilarFieldsRule),
         Box::new(RelationComplexityRule::default()),
+        Box::new(EnumValueNamingRule::default()),
+        Box::new(DescriptionQualityRule::default()),
+        Box::new(ViewFieldsRule::default()),
+        Box::new(InheritanceDepthRule::default()),
     ]
 }
 
@@ -177,6 +268,28 @@ pub fn lint_to_json(content: &str, config_json: &str) -> String {
         }
     };
 
+    let rules = builtin_rules();
+    let known: Vec<&str> = rules.iter().map(|r| r.id()).collect();
+    let mut unknown: Vec<&str> = config
+        .rules
+        .keys()
+        .map(String::as_str)
+        .filter(|id| !known.contains(id))
+        .collect();
+    if !unknown.is_empty() {
+        unknown.sort();
+        return serde_json::to_string(&FfiLintResult {
+            success: false,
+            data: None,
+            error: Some(format!(
+                "Invalid config JSON: unknown rule `{}`, expected one of: {}",
+                unknown.join("`, `"),
+                known.join(", ")
+            )),
+        })
+        .unwrap();
+    }
+
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         let parsed = m3l_core::parse_string(content, "input.m3l.md");
         let ast = m3l_core::resolve(&[parsed], None);
@@ -247,15 +360,21 @@ mod tests {
                 version: None,
             },
             sources: vec![],
+            files: vec![],
             models: vec![],
             enums: vec![],
             interfaces: vec![],
             views: vec![],
             flows: vec![],
-            extensions: std::collections::HashMap::new(),
+            extensions: std::collections::BTreeMap::new(),
             attribute_registry: vec![],
+            file_metadata: vec![],
+            m3l_versions: vec![],
+            generic_instances: vec![],
+            extend_blocks: vec![],
             errors: vec![],
             warnings: vec![],
+            symbols: Default::default(),
         };
         let result = linter.lint(&ast);
         assert!(result.is_empty());
@@ -292,6 +411,29 @@ mod tests {
         assert_eq!(config.severity_for(&TestRule), LintSeverity::Error);
     }
 
+    #[test]
+    fn fix_edit_matches_whole_words() {
+        let edit = FixEdit {
+            file: "a.m3l.md".into(),
+            line: 1,
+            old_text: "id".into(),
+            new_text: "key".into(),
+        };
+        assert_eq!(edit.columns("- user_id: identifier, id"), Some((23, 25)));
+        assert_eq!(edit.columns("- name: string"), None);
+    }
+
+    #[test]
+    fn fix_apply_renames_declaration() {
+        let fix = LintFix::rename("a.m3l.md", 2, "UserName", "user_name");
+        let source = "## User\n- UserName: string\n";
+        assert_eq!(
+            fix.apply("a.m3l.md", source).as_deref(),
+            Some("## User\n- user_name: string\n")
+        );
+        assert!(fix.apply("a.m3l.md", "## User\n").is_none());
+    }
+
     #[test]
     fn lint_to_json_empty_content() {
         let result = lint_to_json("", "{}");
@@ -321,6 +463,21 @@ mod tests {
             .contains("Invalid config JSON"));
     }
 
+    #[test]
+    fn lint_to_json_rejects_unknown_keys_and_rules() {
+        for (config, expected) in [
+            (r#"{"rule":{}}"#, "unknown field `rule`, expected `rules`"),
+            (r#"{"rules":{"naming":"off"}}"#, "unknown rule `naming`"),
+        ] {
+            let result = lint_to_json("## User\n- id: identifier\n", config);
+            let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+            assert_eq!(parsed["success"], false);
+            let error = parsed["error"].as_str().unwrap();
+            assert!(error.contains(expected), "{error}");
+            assert!(error.starts_with("Invalid config JSON"), "{error}");
+        }
+    }
+
     #[test]
     fn lint_to_json_with_custom_config() {
         let content = "# test_model\n\n- Name: string\n";
@@ -334,4 +491,32 @@ mod tests {
             assert_ne!(d["rule"].as_str().unwrap(), "naming-convention");
         }
     }
+
+    #[test]
+    fn every_rule_is_explained() {
+        use m3l_core::{explain, parse_string, resolve};
+        let rule_ids = |source: &str| -> Vec<String> {
+            let ast = resolve(&[parse_string(source, "example.m3l.md")], None);
+            Linter::default()
+                .lint(&ast)
+                .into_iter()
+                .map(|d| d.rule)
+                .collect()
+        };
+        for rule in builtin_rules() {
+            let doc = explain(rule.id()).unwrap_or_else(|| panic!("{} has no entry", rule.id()));
+            let bad = rule_ids(doc.bad);
+            assert!(
+                bad.iter().any(|r| r == rule.id()),
+                "{}: bad example reports {bad:?}",
+                rule.id()
+            );
+            let good = rule_ids(doc.good);
+            assert!(
+                !good.iter().any(|r| r == rule.id()),
+                "{}: good example reports {good:?}",
+                rule.id()
+            );
+        }
+    }
 }
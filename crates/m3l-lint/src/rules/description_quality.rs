@@ -0,0 +1,243 @@
+//! Rule: description-quality
+//!
+//! Flags descriptions that add nothing beyond the name they describe
+//! ("user_id: the user id"), are too short to be useful, or are pasted
+//! verbatim across many fields.
+
+use std::collections::{HashMap, HashSet};
+
+use m3l_core::types::M3lAst;
+
+use crate::{LintDiagnostic, LintRule, LintSeverity};
+
+const DEFAULT_MIN_LENGTH: usize = 10;
+const DEFAULT_MAX_REPEATS: usize = 3;
+
+/// Filler words ignored when comparing a description to a name.
+const FILLER_WORDS: &[&str] = &["the", "a", "an", "of", "for", "this", "its", "is"];
+
+pub struct DescriptionQualityRule {
+    /// Minimum description length in characters.
+    pub min_length: usize,
+    /// Number of fields that may share the same description before it is
+    /// reported as copy-pasted.
+    pub max_repeats: usize,
+}
+
+impl Default for DescriptionQualityRule {
+    fn default() -> Self {
+        Self {
+            min_length: DEFAULT_MIN_LENGTH,
+            max_repeats: DEFAULT_MAX_REPEATS,
+        }
+    }
+}
+
+impl LintRule for DescriptionQualityRule {
+    fn id(&self) -> &str {
+        "description-quality"
+    }
+
+    fn description(&self) -> &str {
+        "Descriptions should add information beyond the name and not be copy-pasted"
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Info
+    }
+
+    fn check(&self, ast: &M3lAst) -> Vec<LintDiagnostic> {
+        let mut diagnostics = Vec::new();
+        // Normalized description -> (file, line, Model.field) of each use
+        let mut uses: HashMap<String, Vec<(String, usize, String)>> = HashMap::new();
+        // Inherited fields appear in every child; count each declaration once
+        let mut seen: HashSet<(String, usize)> = HashSet::new();
+
+        for model in ast
+            .models
+            .iter()
+            .chain(ast.interfaces.iter())
+            .chain(ast.views.iter())
+        {
+            if let Some(ref desc) = model.description {
+                if let Some(problem) = self.problem(&model.name, desc) {
+                    diagnostics.push(self.diagnostic(
+                        &model.source,
+                        model.line,
+                        format!("Description of \"{}\" {problem}", model.name),
+                    ));
+                }
+            }
+
+            for field in &model.fields {
+                let Some(ref desc) = field.description else {
+                    continue;
+                };
+                if !seen.insert((field.loc.file.clone(), field.loc.line)) {
+                    continue;
+                }
+                if let Some(problem) = self.problem(&field.name, desc) {
+                    diagnostics.push(self.diagnostic(
+                        &field.loc.file,
+                        field.loc.line,
+                        format!("Description of \"{}.{}\" {problem}", model.name, field.name),
+                    ));
+                }
+                uses.entry(normalize(desc)).or_default().push((
+                    field.loc.file.clone(),
+                    field.loc.line,
+                    format!("{}.{}", model.name, field.name),
+                ));
+            }
+        }
+
+        let mut repeated: Vec<_> = uses
+            .into_iter()
+            .filter(|(desc, u)| !desc.is_empty() && u.len() > self.max_repeats)
+            .collect();
+        repeated.sort_by(|a, b| a.1[0].cmp(&b.1[0]));
+        for (_, u) in repeated {
+            let names: Vec<&str> = u.iter().map(|(_, _, n)| n.as_str()).collect();
+            let (file, line, _) = &u[0];
+            diagnostics.push(self.diagnostic(
+                file,
+                *line,
+                format!(
+                    "Same description is used on {} fields ({}); describe each field specifically",
+                    u.len(),
+                    names.join(", ")
+                ),
+            ));
+        }
+
+        diagnostics
+    }
+}
+
+impl DescriptionQualityRule {
+    fn diagnostic(&self, file: &str, line: usize, message: String) -> LintDiagnostic {
+        LintDiagnostic {
+            rule: self.id().into(),
+            severity: self.default_severity(),
+            file: file.into(),
+            line,
+            col: 1,
+            message,
+            fix: None,
+        }
+    }
+
+    /// What is wrong with `desc` as a description of `name`, if anything.
+    fn problem(&self, name: &str, desc: &str) -> Option<String> {
+        if repeats_name(name, desc) {
+            return Some("only repeats its name".into());
+        }
+        let len = desc.trim().chars().count();
+        if len < self.min_length {
+            return Some(format!(
+                "is too short ({len} characters, min {})",
+                self.min_length
+            ));
+        }
+        None
+    }
+}
+
+/// Lowercase words of an identifier or sentence.
+fn words(text: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                out.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        // Split camelCase / PascalCase
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            out.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        out.push(current);
+    }
+    out
+}
+
+/// Whether every meaningful word of `desc` already appears in `name`.
+fn repeats_name(name: &str, desc: &str) -> bool {
+    let name_words: HashSet<String> = words(name).into_iter().collect();
+    let desc_words: Vec<String> = words(desc)
+        .into_iter()
+        .filter(|w| !FILLER_WORDS.contains(&w.as_str()))
+        .collect();
+    !desc_words.is_empty() && desc_words.iter().all(|w| name_words.contains(w))
+}
+
+fn normalize(desc: &str) -> String {
+    words(desc).join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(input: &str, rule: &DescriptionQualityRule) -> Vec<LintDiagnostic> {
+        let parsed = m3l_core::parse_string(input, "test.m3l.md");
+        let ast = m3l_core::resolve(&[parsed], None);
+        rule.check(&ast)
+    }
+
+    #[test]
+    fn repeats_name_detection() {
+        assert!(repeats_name("user_id", "The user id"));
+        assert!(repeats_name("createdAt", "created at"));
+        assert!(!repeats_name("user_id", "Account that placed the order"));
+    }
+
+    #[test]
+    fn detects_name_repetition() {
+        let input = "## Order\n- user_id: identifier \"The user ID\"";
+        let results = lint(input, &DescriptionQualityRule::default());
+        assert_eq!(results.len(), 1);
+        assert!(results[0].message.contains("repeats its name"));
+        assert_eq!(results[0].line, 2);
+    }
+
+    #[test]
+    fn detects_short_description() {
+        let input = "## Order\n- total: decimal \"Sum\"";
+        let results = lint(input, &DescriptionQualityRule::default());
+        assert_eq!(results.len(), 1);
+        assert!(results[0].message.contains("too short"));
+    }
+
+    #[test]
+    fn detects_copy_pasted_descriptions() {
+        let input = "## A\n- x: string \"Value set by the importer\"\n- y: string \"Value set by the importer\"\n\n## B\n- z: string \"value set by the importer.\"";
+        let rule = DescriptionQualityRule {
+            max_repeats: 2,
+            ..Default::default()
+        };
+        let results = lint(input, &rule);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].message.contains("3 fields (A.x, A.y, B.z)"));
+    }
+
+    #[test]
+    fn inherited_fields_count_once() {
+        let input = "## Base ::interface\n- note: string \"Free-form remarks from staff\"\n\n## A : Base\n## B : Base\n## C : Base\n## D : Base";
+        assert!(lint(input, &DescriptionQualityRule::default()).is_empty());
+    }
+
+    #[test]
+    fn good_descriptions_pass() {
+        let input = "## Order\n> Purchase placed by a customer\n- user_id: identifier \"Account that placed the order\"";
+        assert!(lint(input, &DescriptionQualityRule::default()).is_empty());
+    }
+}
@@ -0,0 +1,318 @@
+//! Rule: enum-value-naming
+//!
+//! Checks that enum values follow one casing convention (default: snake_case)
+//! and that a single enum does not mix conventions.
+
+use m3l_core::types::{EnumNode, M3lAst};
+use serde::{Deserialize, Serialize};
+
+use crate::{LintDiagnostic, LintFix, LintRule, LintSeverity};
+
+/// Casing convention for enum values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnumValueCase {
+    /// `credit_card`
+    #[default]
+    SnakeCase,
+    /// `CREDIT_CARD`
+    ScreamingSnakeCase,
+    /// `CreditCard`
+    PascalCase,
+}
+
+impl EnumValueCase {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::SnakeCase => "snake_case",
+            Self::ScreamingSnakeCase => "SCREAMING_SNAKE_CASE",
+            Self::PascalCase => "PascalCase",
+        }
+    }
+
+    /// Check whether `name` follows this convention. Letters from scripts
+    /// without case (Hangul, kana, Han, ...) fit any convention.
+    pub fn matches(self, name: &str) -> bool {
+        let Some(first) = name.chars().next() else {
+            return false;
+        };
+        match self {
+            Self::SnakeCase => {
+                (first.is_lowercase() || is_caseless(first))
+                    && name
+                        .chars()
+                        .all(|c| c.is_lowercase() || c.is_numeric() || c == '_' || is_caseless(c))
+            }
+            Self::ScreamingSnakeCase => {
+                (first.is_uppercase() || is_caseless(first))
+                    && name
+                        .chars()
+                        .all(|c| c.is_uppercase() || c.is_numeric() || c == '_' || is_caseless(c))
+            }
+            Self::PascalCase => {
+                (first.is_uppercase() || is_caseless(first))
+                    && name.chars().all(|c| c.is_alphanumeric())
+                    && name.chars().any(|c| c.is_lowercase() || is_caseless(c))
+            }
+        }
+    }
+
+    /// Rewrite `name` in this convention.
+    pub fn convert(self, name: &str) -> String {
+        let words = split_words(name);
+        match self {
+            Self::SnakeCase => words.join("_"),
+            Self::ScreamingSnakeCase => words.join("_").to_uppercase(),
+            Self::PascalCase => words
+                .iter()
+                .map(|w| {
+                    let mut chars = w.chars();
+                    match chars.next() {
+                        Some(c) => c.to_uppercase().collect::<String>() + chars.as_str(),
+                        None => String::new(),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// The convention `name` is written in, if any. Names without a cased
+    /// letter are written in none.
+    fn detect(name: &str) -> Option<Self> {
+        if !name.chars().any(|c| c.is_uppercase() || c.is_lowercase()) {
+            return None;
+        }
+        [Self::SnakeCase, Self::ScreamingSnakeCase, Self::PascalCase]
+            .into_iter()
+            .find(|c| c.matches(name))
+    }
+}
+
+/// Split an identifier into lowercase words at underscores and case changes.
+fn split_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' || c == ' ' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            // `camelCase` -> camel|case, `HTTPServer` -> http|server
+            if prev.is_lowercase() || prev.is_numeric() || next_lower {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// A letter from a script without upper and lower case, such as Hangul or
+/// Han, which no casing convention can ask to change.
+pub(crate) fn is_caseless(c: char) -> bool {
+    c.is_alphabetic() && !c.is_uppercase() && !c.is_lowercase()
+}
+
+pub struct EnumValueNamingRule {
+    pub case: EnumValueCase,
+}
+
+impl Default for EnumValueNamingRule {
+    fn default() -> Self {
+        Self {
+            case: EnumValueCase::SnakeCase,
+        }
+    }
+}
+
+impl LintRule for EnumValueNamingRule {
+    fn id(&self) -> &str {
+        "enum-value-naming"
+    }
+
+    fn description(&self) -> &str {
+        "Enum values should follow a single casing convention"
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Warning
+    }
+
+    fn check(&self, ast: &M3lAst) -> Vec<LintDiagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for e in &ast.enums {
+            if let Some(d) = self.check_mixed(e) {
+                diagnostics.push(d);
+            }
+            for value in &e.values {
+                if self.case.matches(&value.name) {
+                    continue;
+                }
+                let suggested = self.case.convert(&value.name);
+                let (file, line) = value
+                    .loc
+                    .as_ref()
+                    .map(|l| (l.file.as_str(), l.line))
+                    .unwrap_or((e.source.as_str(), e.line));
+                diagnostics.push(LintDiagnostic {
+                    rule: self.id().into(),
+                    severity: self.default_severity(),
+                    file: file.into(),
+                    line,
+                    col: 1,
+                    message: format!(
+                        "Enum value \"{}.{}\" should be {} (\"{}\")",
+                        e.name,
+                        value.name,
+                        self.case.label(),
+                        suggested
+                    ),
+                    fix: value
+                        .loc
+                        .is_some()
+                        .then(|| LintFix::rename(file, line, &value.name, &suggested)),
+                });
+            }
+        }
+
+        diagnostics
+    }
+}
+
+impl EnumValueNamingRule {
+    /// Report an enum whose values are written in more than one convention.
+    fn check_mixed(&self, e: &EnumNode) -> Option<LintDiagnostic> {
+        let mut seen: Vec<EnumValueCase> = Vec::new();
+        for value in &e.values {
+            if let Some(case) = EnumValueCase::detect(&value.name) {
+                if !seen.contains(&case) {
+                    seen.push(case);
+                }
+            }
+        }
+        if seen.len() < 2 {
+            return None;
+        }
+        let labels: Vec<&str> = seen.iter().map(|c| c.label()).collect();
+        Some(LintDiagnostic {
+            rule: self.id().into(),
+            severity: self.default_severity(),
+            file: e.source.clone(),
+            line: e.line,
+            col: 1,
+            message: format!(
+                "Enum \"{}\" mixes naming conventions ({})",
+                e.name,
+                labels.join(", ")
+            ),
+            fix: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(input: &str, rule: &EnumValueNamingRule) -> Vec<LintDiagnostic> {
+        let parsed = m3l_core::parse_string(input, "test.m3l.md");
+        let ast = m3l_core::resolve(&[parsed], None);
+        rule.check(&ast)
+    }
+
+    #[test]
+    fn case_matching() {
+        assert!(EnumValueCase::SnakeCase.matches("credit_card"));
+        assert!(!EnumValueCase::SnakeCase.matches("CreditCard"));
+        assert!(EnumValueCase::ScreamingSnakeCase.matches("CREDIT_CARD"));
+        assert!(!EnumValueCase::ScreamingSnakeCase.matches("credit_card"));
+        assert!(EnumValueCase::PascalCase.matches("CreditCard"));
+        assert!(!EnumValueCase::PascalCase.matches("CREDIT"));
+    }
+
+    #[test]
+    fn case_conversion() {
+        assert_eq!(
+            EnumValueCase::SnakeCase.convert("CreditCard"),
+            "credit_card"
+        );
+        assert_eq!(
+            EnumValueCase::ScreamingSnakeCase.convert("creditCard"),
+            "CREDIT_CARD"
+        );
+        assert_eq!(
+            EnumValueCase::PascalCase.convert("CREDIT_CARD"),
+            "CreditCard"
+        );
+        assert_eq!(
+            EnumValueCase::SnakeCase.convert("HTTPServer"),
+            "http_server"
+        );
+    }
+
+    #[test]
+    fn snake_case_enum_is_clean() {
+        let input = "## Status ::enum\n- active: \"Active\"\n- on_hold: \"On hold\"";
+        assert!(lint(input, &EnumValueNamingRule::default()).is_empty());
+    }
+
+    #[test]
+    fn detects_values_in_wrong_case() {
+        let input = "## Status ::enum\n- ACTIVE: \"Active\"\n- ON_HOLD: \"On hold\"";
+        let results = lint(input, &EnumValueNamingRule::default());
+        assert_eq!(results.len(), 2);
+        assert!(results[1].message.contains("\"on_hold\""));
+        assert_eq!(results[1].line, 3);
+        let fix = results[1].fix.as_ref().unwrap();
+        assert_eq!(
+            fix.apply("test.m3l.md", input).unwrap().lines().nth(2),
+            Some("- on_hold: \"On hold\"")
+        );
+    }
+
+    #[test]
+    fn detects_mixed_conventions() {
+        let input = "## Status ::enum\n- active: \"Active\"\n- OnHold: \"On hold\"";
+        let rule = EnumValueNamingRule {
+            case: EnumValueCase::PascalCase,
+        };
+        let results = lint(input, &rule);
+        assert!(results.iter().any(|d| d.message.contains("mixes")));
+        assert!(results
+            .iter()
+            .any(|d| d.message.contains("Status.active") && d.message.contains("\"Active\"")));
+    }
+
+    #[test]
+    fn caseless_values_fit_any_convention() {
+        let input =
+            "## 상태 ::enum\n- 활성: \"Active\"\n- 保留: \"On hold\"\n- archived: \"Archived\"";
+        assert!(lint(input, &EnumValueNamingRule::default()).is_empty());
+        let rule = EnumValueNamingRule {
+            case: EnumValueCase::PascalCase,
+        };
+        let results = lint(input, &rule);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].message.contains("\"Archived\""));
+        assert_eq!(EnumValueCase::SnakeCase.convert("ÉtatFinal"), "état_final");
+    }
+
+    #[test]
+    fn configured_case_is_respected() {
+        let input = "## Status ::enum\n- ACTIVE: \"Active\"\n- ON_HOLD: \"On hold\"";
+        let rule = EnumValueNamingRule {
+            case: EnumValueCase::ScreamingSnakeCase,
+        };
+        assert!(lint(input, &rule).is_empty());
+    }
+}
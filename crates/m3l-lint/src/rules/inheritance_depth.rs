@@ -0,0 +1,140 @@
+//! Rule: inheritance-depth
+//!
+//! Warns when a model's inheritance chain is deeper than a threshold
+//! (default: 3), since deep chains make the flattened field order hard to
+//! reason about.
+
+use std::collections::HashMap;
+
+use m3l_core::types::{M3lAst, ModelNode};
+
+use crate::{LintDiagnostic, LintRule, LintSeverity};
+
+const DEFAULT_MAX_DEPTH: usize = 3;
+
+pub struct InheritanceDepthRule {
+    pub max_depth: usize,
+}
+
+impl Default for InheritanceDepthRule {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+impl LintRule for InheritanceDepthRule {
+    fn id(&self) -> &str {
+        "inheritance-depth"
+    }
+
+    fn description(&self) -> &str {
+        "Inheritance chains should not be too deep"
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Warning
+    }
+
+    fn check(&self, ast: &M3lAst) -> Vec<LintDiagnostic> {
+        let by_name: HashMap<&str, &ModelNode> = ast
+            .models
+            .iter()
+            .chain(ast.interfaces.iter())
+            .chain(ast.views.iter())
+            .map(|m| (m.name.as_str(), m))
+            .collect();
+        let mut diagnostics = Vec::new();
+
+        for model in ast.models.iter().chain(ast.views.iter()) {
+            let chain = longest_chain(&model.name, &by_name, &mut Vec::new());
+            let depth = chain.len() - 1;
+            if depth > self.max_depth {
+                diagnostics.push(LintDiagnostic {
+                    rule: self.id().into(),
+                    severity: self.default_severity(),
+                    file: model.source.clone(),
+                    line: model.line,
+                    col: 1,
+                    message: format!(
+                        "Model \"{}\" has an inheritance depth of {} (max {}): {}",
+                        model.name,
+                        depth,
+                        self.max_depth,
+                        chain.join(" → ")
+                    ),
+                    fix: None,
+                });
+            }
+        }
+
+        diagnostics
+    }
+}
+
+/// Longest ancestor path starting at `name` (inclusive). Names already on
+/// `visiting` are skipped, so cycles (reported by the resolver) terminate.
+fn longest_chain<'a>(
+    name: &'a str,
+    by_name: &HashMap<&str, &'a ModelNode>,
+    visiting: &mut Vec<&'a str>,
+) -> Vec<&'a str> {
+    let mut best: Vec<&str> = Vec::new();
+    if let Some(model) = by_name.get(name) {
+        visiting.push(name);
+        for parent in &model.inherits {
+            if visiting.contains(&parent.as_str()) {
+                continue;
+            }
+            let chain = longest_chain(parent, by_name, visiting);
+            if chain.len() > best.len() {
+                best = chain;
+            }
+        }
+        visiting.pop();
+    }
+    let mut chain = vec![name];
+    chain.extend(best);
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(input: &str, rule: &InheritanceDepthRule) -> Vec<LintDiagnostic> {
+        let parsed = m3l_core::parse_string(input, "test.m3l.md");
+        let ast = m3l_core::resolve(&[parsed], None);
+        rule.check(&ast)
+    }
+
+    const CHAIN: &str = "## A ::interface\n- a: string\n\n## B : A\n- b: string\n\n## C : B\n- c: string\n\n## D : C\n- d: string\n\n## E : D\n- e: string\n";
+
+    #[test]
+    fn detects_deep_chain() {
+        let results = lint(CHAIN, &InheritanceDepthRule::default());
+        assert_eq!(results.len(), 1);
+        assert!(results[0].message.contains("\"E\""));
+        assert!(results[0].message.contains("E → D → C → B → A"));
+    }
+
+    #[test]
+    fn custom_limit() {
+        let rule = InheritanceDepthRule { max_depth: 2 };
+        let results = lint(CHAIN, &rule);
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn shallow_models_pass() {
+        let input = "## Base ::interface\n- id: identifier\n\n## User : Base\n- name: string\n";
+        assert!(lint(input, &InheritanceDepthRule::default()).is_empty());
+    }
+
+    #[test]
+    fn cycles_terminate() {
+        let input = "## A : B\n- a: string\n\n## B : A\n- b: string\n";
+        assert!(lint(input, &InheritanceDepthRule::default()).is_empty());
+    }
+}
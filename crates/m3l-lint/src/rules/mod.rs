@@ -1,11 +1,19 @@
 //! Built-in lint rules.
 
+pub mod description_quality;
+pub mod enum_value_naming;
+pub mod inheritance_depth;
 pub mod model_size;
 pub mod naming_convention;
 pub mod relation_complexity;
 pub mod similar_fields;
+pub mod view_fields;
 
+pub use description_quality::DescriptionQualityRule;
+pub use enum_value_naming::{EnumValueCase, EnumValueNamingRule};
+pub use inheritance_depth::InheritanceDepthRule;
 pub use model_size::ModelSizeRule;
 pub use naming_convention::NamingConventionRule;
 pub use relation_complexity::RelationComplexityRule;
 pub use similar_fields::SimilarFieldsRule;
+pub use view_fields::ViewFieldsRule;
@@ -50,6 +50,7 @@ impl LintRule for ModelSizeRule {
                         model.fields.len(),
                         self.max_fields
                     ),
+                    fix: None,
                 });
             }
         }
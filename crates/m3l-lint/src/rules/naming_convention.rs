@@ -4,7 +4,8 @@
 
 use m3l_core::types::M3lAst;
 
-use crate::{LintDiagnostic, LintRule, LintSeverity};
+use super::enum_value_naming::is_caseless;
+use crate::{EnumValueCase, LintDiagnostic, LintFix, LintRule, LintSeverity};
 
 pub struct NamingConventionRule;
 
@@ -34,6 +35,12 @@ impl LintRule for NamingConventionRule {
                     line: model.line,
                     col: 1,
                     message: format!("Model name \"{}\" should be PascalCase", model.name),
+                    fix: rename_fix(
+                        &model.source,
+                        model.line,
+                        &model.name,
+                        EnumValueCase::PascalCase,
+                    ),
                 });
             }
 
@@ -51,6 +58,12 @@ impl LintRule for NamingConventionRule {
                     line: iface.line,
                     col: 1,
                     message: format!("Interface name \"{}\" should be PascalCase", iface.name),
+                    fix: rename_fix(
+                        &iface.source,
+                        iface.line,
+                        &iface.name,
+                        EnumValueCase::PascalCase,
+                    ),
                 });
             }
             check_field_names(&iface.fields, &iface.source, self, &mut diagnostics);
@@ -66,6 +79,7 @@ impl LintRule for NamingConventionRule {
                     line: e.line,
                     col: 1,
                     message: format!("Enum name \"{}\" should be PascalCase", e.name),
+                    fix: rename_fix(&e.source, e.line, &e.name, EnumValueCase::PascalCase),
                 });
             }
         }
@@ -89,6 +103,12 @@ fn check_field_names(
                 line: field.loc.line,
                 col: 1,
                 message: format!("Field name \"{}\" should be snake_case", field.name),
+                fix: rename_fix(
+                    &field.loc.file,
+                    field.loc.line,
+                    &field.name,
+                    EnumValueCase::SnakeCase,
+                ),
             });
         }
 
@@ -99,26 +119,35 @@ fn check_field_names(
     }
 }
 
-/// Check if a name is PascalCase: starts with uppercase, no underscores.
+/// Fix renaming `name` into `case`. Namespace-qualified names are left alone.
+fn rename_fix(file: &str, line: usize, name: &str, case: EnumValueCase) -> Option<LintFix> {
+    let new = case.convert(name);
+    (!name.contains('.') && !new.is_empty() && new != name)
+        .then(|| LintFix::rename(file, line, name, &new))
+}
+
+/// Check if a name is PascalCase: starts with uppercase (or a letter from a
+/// caseless script), no underscores.
 fn is_pascal_case(name: &str) -> bool {
     if name.is_empty() {
         return false;
     }
     let first = name.chars().next().unwrap();
-    if !first.is_ascii_uppercase() {
+    if !first.is_uppercase() && !is_caseless(first) {
         return false;
     }
     // Allow dots for namespace-qualified names (e.g., Auth.User)
     !name.contains('_')
 }
 
-/// Check if a name is snake_case: all lowercase/digits/underscores, doesn't start with uppercase.
+/// Check if a name is snake_case: all lowercase/digits/underscores (or letters
+/// from a caseless script), doesn't start with uppercase.
 fn is_snake_case(name: &str) -> bool {
     if name.is_empty() {
         return false;
     }
     name.chars()
-        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        .all(|c| c.is_lowercase() || c.is_numeric() || c == '_' || is_caseless(c))
 }
 
 #[cfg(test)]
@@ -153,6 +182,16 @@ mod tests {
         assert!(!is_snake_case("ID"));
     }
 
+    #[test]
+    fn caseless_scripts_are_valid() {
+        assert!(is_pascal_case("사용자"));
+        assert!(is_pascal_case("注文"));
+        assert!(is_snake_case("생성일"));
+        assert!(is_snake_case("作成_日時"));
+        assert!(is_pascal_case("Élève"));
+        assert!(!is_snake_case("Élève"));
+    }
+
     #[test]
     fn rule_detects_bad_model_name() {
         let ast = m3l_core::parse_string("## user_model\n- id: identifier", "test.m3l.md");
@@ -175,6 +214,21 @@ mod tests {
             results.iter().any(|d| d.message.contains("snake_case")),
             "Should detect non-snake_case field name"
         );
+        let fix = results[0].fix.as_ref().unwrap();
+        assert!(fix.is_rename);
+        assert_eq!(fix.edits[0].line, 2);
+        assert_eq!(fix.edits[0].old_text, "UserName");
+        assert_eq!(fix.edits[0].new_text, "user_name");
+    }
+
+    #[test]
+    fn rule_no_warnings_for_korean_names() {
+        let ast = m3l_core::parse_string(
+            "## 사용자 : 기본정보\n- 이름: string(50)\n- 생성일: timestamp",
+            "test.m3l.md",
+        );
+        let resolved = m3l_core::resolve(&[ast], None);
+        assert!(NamingConventionRule.check(&resolved).is_empty());
     }
 
     #[test]
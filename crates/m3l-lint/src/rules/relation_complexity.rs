@@ -58,6 +58,7 @@ impl LintRule for RelationComplexityRule {
                         "Model \"{}\" has {} reference fields (max {}). Consider decomposing",
                         model.name, ref_count, self.max_relations
                     ),
+                    fix: None,
                 });
             }
         }
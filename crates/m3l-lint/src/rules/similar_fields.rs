@@ -41,6 +41,7 @@ impl LintRule for SimilarFieldsRule {
                                 "Field \"{}\" is very similar to \"{}\" in model \"{}\"",
                                 names[j], names[i], model.name
                             ),
+                            fix: None,
                         });
                     }
                 }
@@ -0,0 +1,100 @@
+//! Rule: view-fields
+//!
+//! Warns when a view declares a source but no fields, leaving its output
+//! shape undefined for consumers. Raw-SQL views with a language hint can be
+//! allowed through `allow_raw_sql`.
+
+use m3l_core::types::M3lAst;
+
+use crate::{LintDiagnostic, LintRule, LintSeverity};
+
+#[derive(Default)]
+pub struct ViewFieldsRule {
+    /// Accept field-less views whose source is a fenced SQL block with a
+    /// language hint (e.g. ```` ```sql ````).
+    pub allow_raw_sql: bool,
+}
+
+impl LintRule for ViewFieldsRule {
+    fn id(&self) -> &str {
+        "view-fields"
+    }
+
+    fn description(&self) -> &str {
+        "Views with a source should declare their output fields"
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Warning
+    }
+
+    fn check(&self, ast: &M3lAst) -> Vec<LintDiagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for view in &ast.views {
+            let Some(ref source) = view.source_def else {
+                continue;
+            };
+            if !view.fields.is_empty() {
+                continue;
+            }
+            if self.allow_raw_sql && source.raw_sql.is_some() && source.language_hint.is_some() {
+                continue;
+            }
+            diagnostics.push(LintDiagnostic {
+                rule: self.id().into(),
+                severity: self.default_severity(),
+                file: view.source.clone(),
+                line: view.line,
+                col: 1,
+                message: format!(
+                    "View \"{}\" declares a source but no fields; its output shape is undefined",
+                    view.name
+                ),
+                fix: None,
+            });
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(input: &str, rule: &ViewFieldsRule) -> Vec<LintDiagnostic> {
+        let parsed = m3l_core::parse_string(input, "test.m3l.md");
+        let ast = m3l_core::resolve(&[parsed], None);
+        rule.check(&ast)
+    }
+
+    const BASE: &str = "## Product\n- id: identifier @pk\n- name: string\n\n";
+
+    #[test]
+    fn detects_view_without_fields() {
+        let input = format!("{BASE}## Catalog ::view\n### Source\n- from: Product\n");
+        let results = lint(&input, &ViewFieldsRule::default());
+        assert_eq!(results.len(), 1);
+        assert!(results[0].message.contains("Catalog"));
+    }
+
+    #[test]
+    fn view_with_fields_passes() {
+        let input =
+            format!("{BASE}## Catalog ::view\n- name: string\n### Source\n- from: Product\n");
+        assert!(lint(&input, &ViewFieldsRule::default()).is_empty());
+    }
+
+    #[test]
+    fn raw_sql_view_allowed_when_configured() {
+        let input = format!(
+            "{BASE}## Catalog ::view\n### Source\n```sql\nSELECT id, name FROM Product\n```\n"
+        );
+        assert_eq!(lint(&input, &ViewFieldsRule::default()).len(), 1);
+        let rule = ViewFieldsRule {
+            allow_raw_sql: true,
+        };
+        assert!(lint(&input, &rule).is_empty());
+    }
+}
@@ -6,7 +6,9 @@
 #[macro_use]
 extern crate napi_derive;
 
-use m3l_core::{parse_multi_to_json, parse_to_json, validate_to_json};
+use m3l_core::{
+    explain_to_json, extract_to_json, parse_multi_to_json, parse_to_json, validate_to_json,
+};
 use m3l_lint::lint_to_json;
 
 /// Parse a single M3L file and return the AST as JSON.
@@ -28,6 +30,17 @@ pub fn parse_multi(files_json: String) -> String {
     parse_multi_to_json(&files_json)
 }
 
+/// Parse a single M3L file and return only the part of the AST at `path`.
+///
+/// @param content - M3L markdown text
+/// @param filename - Source filename for error reporting
+/// @param path - JSON Pointer (`/models/0`) or JSONPath (`$.models[?(@.name == 'User')]`)
+/// @returns JSON string with `{ success: boolean, data?: unknown, error?: string }`
+#[napi]
+pub fn extract(content: String, filename: String, path: String) -> String {
+    extract_to_json(&content, &filename, &path)
+}
+
 /// Validate M3L content and return diagnostics as JSON.
 ///
 /// @param content - M3L markdown text
@@ -47,3 +60,12 @@ pub fn validate(content: String, options_json: String) -> String {
 pub fn lint(content: String, config_json: String) -> String {
     lint_to_json(&content, &config_json)
 }
+
+/// Describe a validation code or lint rule.
+///
+/// @param code - `M3L-E005`, `E005` or a lint rule id such as `naming-convention`
+/// @returns JSON string with `{ success: boolean, data?: CodeDoc, error?: string }`
+#[napi]
+pub fn explain(code: String) -> String {
+    explain_to_json(&code)
+}
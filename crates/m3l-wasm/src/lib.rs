@@ -3,7 +3,9 @@
 //! Provides JavaScript-callable functions via wasm-bindgen.
 //! All functions take string inputs and return JSON strings.
 
-use m3l_core::{parse_multi_to_json, parse_to_json, validate_to_json};
+use m3l_core::{
+    explain_to_json, extract_to_json, parse_multi_to_json, parse_to_json, validate_to_json,
+};
 use m3l_lint::lint_to_json;
 use wasm_bindgen::prelude::*;
 
@@ -26,6 +28,17 @@ pub fn wasm_parse_multi(files_json: &str) -> String {
     parse_multi_to_json(files_json)
 }
 
+/// Parse a single M3L file and return only the part of the AST at `path`.
+///
+/// @param content - M3L markdown text
+/// @param filename - Source filename for error reporting
+/// @param path - JSON Pointer (`/models/0`) or JSONPath (`$.models[?(@.name == 'User')]`)
+/// @returns JSON string with `{ success: boolean, data?: unknown, error?: string }`
+#[wasm_bindgen(js_name = "extract")]
+pub fn wasm_extract(content: &str, filename: &str, path: &str) -> String {
+    extract_to_json(content, filename, path)
+}
+
 /// Validate M3L content and return diagnostics as JSON.
 ///
 /// @param content - M3L markdown text
@@ -45,3 +58,12 @@ pub fn wasm_validate(content: &str, options_json: &str) -> String {
 pub fn wasm_lint(content: &str, config_json: &str) -> String {
     lint_to_json(content, config_json)
 }
+
+/// Describe a validation code or lint rule.
+///
+/// @param code - `M3L-E005`, `E005` or a lint rule id such as `naming-convention`
+/// @returns JSON string with `{ success: boolean, data?: CodeDoc, error?: string }`
+#[wasm_bindgen(js_name = "explain")]
+pub fn wasm_explain(code: &str) -> String {
+    explain_to_json(code)
+}
@@ -0,0 +1,19 @@
+//! Full pipeline on arbitrary input: lex, parse, resolve and validate must
+//! return diagnostics, never panic.
+//!
+//! Run with `cargo +nightly fuzz run parse -- -dict=fuzz/m3l.dict`, seeding
+//! the corpus from `samples/` for faster coverage.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use m3l_core::{parse_string, resolve, validate, ValidateOptions};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let parsed = parse_string(text, "fuzz.m3l.md");
+    let ast = resolve(&[parsed], None);
+    let _ = validate(&ast, &ValidateOptions { strict: true });
+});